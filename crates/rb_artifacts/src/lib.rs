@@ -0,0 +1,19 @@
+//! Tagged generation outputs that outlive a session.
+//!
+//! Each artifact is a directory under [`ARTIFACTS_DIR`] named by its tag,
+//! holding a [`manifest`](ArtifactManifest) and content-addressed blobs: the
+//! macro map's layer rasters, the world definition with its civilization,
+//! and its territory. The editor and the `rb_artifacts` command line tool
+//! both read and write them through [`save_layers`], [`load_layers`] and
+//! [`list_artifacts`].
+
+pub mod manifest;
+pub mod raster;
+pub mod store;
+
+pub use manifest::{ArtifactManifest, LayerEntry, ARTIFACT_VERSION};
+pub use raster::{map_rasters, territory_from_rasters, territory_rasters, LayerRaster};
+pub use store::{
+    artifact_dir, artifact_tag, list_artifacts, load_layers, save_layers, Artifact, ArtifactError,
+    ARTIFACTS_DIR,
+};
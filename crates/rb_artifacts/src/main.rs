@@ -0,0 +1,113 @@
+//! Generate and inspect world artifacts without the editor.
+//!
+//! ```text
+//! rb_artifacts generate <tag> <seed> [width height]
+//! rb_artifacts list
+//! rb_artifacts show <tag>
+//! ```
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use rb_artifacts::{list_artifacts, load_layers, save_layers, ArtifactError, ARTIFACTS_DIR};
+use rb_noise::{BiomeMap, GenerationConfig};
+use rb_world::{CivilizationConfig, CivilizationGenerator, WorldDefinition};
+
+const USAGE: &str = "usage:
+  rb_artifacts generate <tag> <seed> [width height]
+  rb_artifacts list
+  rb_artifacts show <tag>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let root = Path::new(ARTIFACTS_DIR);
+
+    let result = match args.as_slice() {
+        ["generate", tag, seed, size @ ..] => {
+            let size = match size {
+                [] => Some((1024, 512)),
+                [width, height] => width.parse().ok().zip(height.parse().ok()),
+                _ => None,
+            };
+            match (seed.parse(), size) {
+                (Ok(seed), Some((width, height))) => generate(root, tag, seed, width, height),
+                _ => return usage(),
+            }
+        }
+        ["list"] => list(root),
+        ["show", tag] => show(root, tag),
+        _ => return usage(),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{}", USAGE);
+    ExitCode::from(2)
+}
+
+/// Generate a world and its civilization with default parameters and save it.
+fn generate(root: &Path, tag: &str, seed: u32, width: usize, height: usize) -> Result<(), ArtifactError> {
+    let mut world = WorldDefinition {
+        name: tag.to_string(),
+        seed,
+        width,
+        height,
+        ..Default::default()
+    };
+    println!("Generating {}×{} world with seed {}...", width, height, seed);
+    let map = BiomeMap::generate_with_config(seed, width, height, &GenerationConfig::default());
+    let civilization = CivilizationGenerator::new(seed, CivilizationConfig::default()).generate(&map, &mut world);
+
+    let manifest = save_layers(root, tag, &map, &world, Some(&civilization))?;
+    println!(
+        "Saved {} layers, {} settlements and {} factions to {}",
+        manifest.layers.len(),
+        civilization.settlements_placed,
+        civilization.factions_created,
+        root.join(tag).display()
+    );
+    Ok(())
+}
+
+fn list(root: &Path) -> Result<(), ArtifactError> {
+    let manifests = list_artifacts(root)?;
+    if manifests.is_empty() {
+        println!("No artifacts in {}", root.display());
+    }
+    for manifest in manifests {
+        println!(
+            "{}  seed {}  {}×{}  v{}",
+            manifest.tag, manifest.seed, manifest.width, manifest.height, manifest.version
+        );
+    }
+    Ok(())
+}
+
+fn show(root: &Path, tag: &str) -> Result<(), ArtifactError> {
+    let artifact = load_layers(root, tag)?;
+    let manifest = &artifact.manifest;
+    println!("{} (seed {}, {}×{}, v{})", manifest.tag, manifest.seed, manifest.width, manifest.height, manifest.version);
+    if let Some(civ) = &manifest.civilization {
+        println!(
+            "  {} settlements, {} factions, {} roads, {} trade routes",
+            civ.settlements_placed, civ.factions_created, civ.roads_built, civ.trade_routes_created
+        );
+    }
+    for layer in &artifact.layers {
+        let (min, max) = layer
+            .values
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        println!("  {:<20} {:>10.3} .. {:.3}", layer.name, min, max);
+    }
+    Ok(())
+}
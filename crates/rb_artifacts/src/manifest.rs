@@ -0,0 +1,46 @@
+use rb_world::{CivilizationResult, NoiseParams};
+use serde::{Deserialize, Serialize};
+
+/// Version of this crate, recorded in every manifest it writes.
+pub const ARTIFACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Where one raster of an artifact lives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerEntry {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    /// Content hash naming the raster's blob.
+    pub blob: String,
+}
+
+/// Everything an artifact holds, and what it was generated from.
+///
+/// Saved as `manifest.ron` in the artifact's directory. Layers and the world
+/// definition are blobs named by the hash of their contents, so saving the
+/// same world twice rewrites only what changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    pub tag: String,
+    /// Version of `rb_artifacts` that wrote the artifact.
+    pub version: String,
+    /// Seconds since the Unix epoch when the artifact was saved.
+    pub created: u64,
+    pub seed: u32,
+    pub width: usize,
+    pub height: usize,
+    pub params: NoiseParams,
+    /// Blob holding the world definition as RON.
+    pub world: String,
+    pub layers: Vec<LayerEntry>,
+    /// Summary of civilization generation, if it ran before saving. Its
+    /// cities, factions and roads are in the world definition.
+    #[serde(default)]
+    pub civilization: Option<CivilizationResult>,
+}
+
+impl ArtifactManifest {
+    pub fn layer(&self, name: &str) -> Option<&LayerEntry> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+}
@@ -0,0 +1,108 @@
+use rb_noise::{BiomeMap, LayerFloat, LayerValue};
+use rb_world::TerritoryMap;
+
+use crate::store::ArtifactError;
+
+/// One layer of a generated map as a row-major grid of samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerRaster {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub values: Vec<f32>,
+}
+
+impl LayerRaster {
+    pub fn new(name: &str, width: usize, height: usize, values: Vec<f32>) -> Self {
+        assert_eq!(values.len(), width * height, "one value per cell of {}", name);
+        Self {
+            name: name.to_string(),
+            width,
+            height,
+            values,
+        }
+    }
+
+    fn from_layer(name: &str, width: usize, height: usize, layer: &[LayerFloat]) -> Self {
+        Self::new(name, width, height, layer.iter().map(|v| v.to_f64() as f32).collect())
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<f32> {
+        (x < self.width && y < self.height).then(|| self.values[y * self.width + x])
+    }
+
+    /// Samples as little-endian `f32`s, the layout of a raster blob.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        self.values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    pub(crate) fn from_bytes(
+        name: &str,
+        width: usize,
+        height: usize,
+        bytes: &[u8],
+    ) -> Result<Self, ArtifactError> {
+        if bytes.len() != width * height * 4 {
+            return Err(ArtifactError::Corrupt(format!(
+                "layer {} has {} bytes, expected {}×{} samples",
+                name,
+                bytes.len(),
+                width,
+                height
+            )));
+        }
+        let values = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        Ok(Self::new(name, width, height, values))
+    }
+}
+
+/// Every per-cell layer of a macro map worth keeping: the noise layers,
+/// terrain elevation, rivers and biomes (as [`TileType`](rb_core::TileType)
+/// indices).
+pub fn map_rasters(map: &BiomeMap) -> Vec<LayerRaster> {
+    let (w, h) = (map.width, map.height);
+    let elevation = (0..w * h)
+        .map(|idx| map.elevation(idx % w, idx / w).unwrap_or_default() as f32)
+        .collect();
+    vec![
+        LayerRaster::new("biomes", w, h, map.biomes.iter().map(|b| b.index() as f32).collect()),
+        LayerRaster::new("elevation", w, h, elevation),
+        LayerRaster::from_layer("continentalness", w, h, &map.continentalness),
+        LayerRaster::from_layer("temperature", w, h, &map.temperature),
+        LayerRaster::from_layer("tectonic", w, h, &map.tectonic),
+        LayerRaster::from_layer("uplift", w, h, &map.uplift),
+        LayerRaster::from_layer("erosion", w, h, &map.erosion),
+        LayerRaster::from_layer("peaks_valleys", w, h, &map.peaks_valleys),
+        LayerRaster::from_layer("humidity", w, h, &map.humidity),
+        LayerRaster::from_layer("precipitation", w, h, &map.precipitation),
+        LayerRaster::from_layer("caves", w, h, &map.caves),
+        LayerRaster::from_layer("rivers", w, h, &map.rivers),
+    ]
+}
+
+/// Faction ownership and influence strength, the territory civilization
+/// generation leaves behind.
+pub fn territory_rasters(territory: &TerritoryMap) -> [LayerRaster; 2] {
+    let (w, h) = (territory.width, territory.height);
+    [
+        LayerRaster::new("territory_owner", w, h, territory.ownership.iter().map(|&id| id as f32).collect()),
+        LayerRaster::new(
+            "territory_influence",
+            w,
+            h,
+            territory.influence.iter().map(|&v| v as f32).collect(),
+        ),
+    ]
+}
+
+/// Territory back from its two rasters. Faction IDs survive exactly up to
+/// 2^24, far past any world's faction count.
+pub fn territory_from_rasters(owner: &LayerRaster, influence: &LayerRaster) -> TerritoryMap {
+    let mut territory = TerritoryMap::new(owner.width, owner.height);
+    territory.ownership = owner.values.iter().map(|&id| id as u32).collect();
+    territory.influence = influence.values.iter().map(|&v| v as f64).collect();
+    territory
+}
@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rb_noise::BiomeMap;
+use rb_world::{CivilizationResult, WorldDefinition};
+
+use crate::manifest::{ArtifactManifest, LayerEntry, ARTIFACT_VERSION};
+use crate::raster::{map_rasters, territory_from_rasters, territory_rasters, LayerRaster};
+
+/// Default directory artifacts are stored under, one subdirectory per tag.
+pub const ARTIFACTS_DIR: &str = "worlds";
+
+const MANIFEST_FILE: &str = "manifest.ron";
+const BLOBS_DIR: &str = "blobs";
+
+/// Error type for artifact storage.
+#[derive(Debug)]
+pub enum ArtifactError {
+    Io(std::io::Error),
+    Ron(ron::Error),
+    RonSpanned(ron::error::SpannedError),
+    /// A tag that can't name a directory.
+    InvalidTag(String),
+    /// A blob is missing, truncated or doesn't match its hash.
+    Corrupt(String),
+}
+
+impl From<std::io::Error> for ArtifactError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::Error> for ArtifactError {
+    fn from(err: ron::Error) -> Self {
+        Self::Ron(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ArtifactError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::RonSpanned(err)
+    }
+}
+
+impl std::fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "IO error: {}", e),
+            Self::Ron(e) => write!(f, "RON serialization error: {}", e),
+            Self::RonSpanned(e) => write!(f, "RON parse error: {}", e),
+            Self::InvalidTag(tag) => write!(f, "invalid artifact tag {:?}", tag),
+            Self::Corrupt(what) => write!(f, "corrupt artifact: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for ArtifactError {}
+
+/// A saved world read back from the store.
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    pub manifest: ArtifactManifest,
+    /// The world definition, with its territory restored if it had one.
+    pub world: WorldDefinition,
+    pub layers: Vec<LayerRaster>,
+}
+
+impl Artifact {
+    pub fn layer(&self, name: &str) -> Option<&LayerRaster> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+}
+
+/// Default tag for a world: its name made filename-safe, then its seed.
+pub fn artifact_tag(world_name: &str, seed: u32) -> String {
+    let name: String = world_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}-{}", name.to_lowercase(), seed)
+}
+
+/// Directory of the artifact tagged `tag` under `root`.
+pub fn artifact_dir(root: &Path, tag: &str) -> Result<PathBuf, ArtifactError> {
+    let valid = !tag.is_empty()
+        && tag.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(ArtifactError::InvalidTag(tag.to_string()));
+    }
+    Ok(root.join(tag))
+}
+
+/// Save a generated world under `root/tag`: every raster of `map`, the
+/// world definition with its civilization, its territory if generated, and
+/// a manifest of what it was made from.
+///
+/// Saving over an existing tag replaces it; blobs nothing refers to any more
+/// are removed.
+pub fn save_layers(
+    root: &Path,
+    tag: &str,
+    map: &BiomeMap,
+    world: &WorldDefinition,
+    civilization: Option<&CivilizationResult>,
+) -> Result<ArtifactManifest, ArtifactError> {
+    let dir = artifact_dir(root, tag)?;
+    let blobs = dir.join(BLOBS_DIR);
+    fs::create_dir_all(&blobs)?;
+
+    let mut rasters = map_rasters(map);
+    if let Some(territory) = &world.territory_cache {
+        rasters.extend(territory_rasters(territory));
+    }
+
+    let mut layers = Vec::with_capacity(rasters.len());
+    for raster in &rasters {
+        layers.push(LayerEntry {
+            name: raster.name.clone(),
+            width: raster.width,
+            height: raster.height,
+            blob: write_blob(&blobs, &raster.to_bytes())?,
+        });
+    }
+    let world_blob = write_blob(&blobs, ron::to_string(world)?.as_bytes())?;
+
+    let manifest = ArtifactManifest {
+        tag: tag.to_string(),
+        version: ARTIFACT_VERSION.to_string(),
+        created: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        seed: world.seed,
+        width: map.width,
+        height: map.height,
+        params: world.noise_params.clone(),
+        world: world_blob,
+        layers,
+        civilization: civilization.cloned(),
+    };
+    let pretty = ron::ser::PrettyConfig::new().depth_limit(3);
+    fs::write(dir.join(MANIFEST_FILE), ron::ser::to_string_pretty(&manifest, pretty)?)?;
+
+    // Drop blobs an earlier save left behind
+    let live: HashSet<&str> = manifest
+        .layers
+        .iter()
+        .map(|layer| layer.blob.as_str())
+        .chain([manifest.world.as_str()])
+        .collect();
+    for entry in fs::read_dir(&blobs)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if !live.contains(name) {
+            fs::remove_file(&path)?;
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Read the artifact tagged `tag` under `root` back, checking every blob
+/// against its hash.
+pub fn load_layers(root: &Path, tag: &str) -> Result<Artifact, ArtifactError> {
+    let dir = artifact_dir(root, tag)?;
+    let manifest = read_manifest(&dir)?;
+    let blobs = dir.join(BLOBS_DIR);
+
+    let world_ron = String::from_utf8(read_blob(&blobs, &manifest.world)?)
+        .map_err(|_| ArtifactError::Corrupt("world definition is not UTF-8".to_string()))?;
+    let mut world: WorldDefinition = ron::from_str(&world_ron)?;
+
+    let layers = manifest
+        .layers
+        .iter()
+        .map(|entry| {
+            let bytes = read_blob(&blobs, &entry.blob)?;
+            LayerRaster::from_bytes(&entry.name, entry.width, entry.height, &bytes)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let raster = |name| layers.iter().find(|layer: &&LayerRaster| layer.name == name);
+    if let (Some(owner), Some(influence)) = (raster("territory_owner"), raster("territory_influence")) {
+        world.territory_cache = Some(territory_from_rasters(owner, influence));
+    }
+
+    Ok(Artifact {
+        manifest,
+        world,
+        layers,
+    })
+}
+
+/// Manifests of every artifact under `root`, sorted by tag. Directories
+/// without a readable manifest are skipped.
+pub fn list_artifacts(root: &Path) -> Result<Vec<ArtifactManifest>, ArtifactError> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut manifests = Vec::new();
+    for entry in fs::read_dir(root)? {
+        let path = entry?.path();
+        if !path.join(MANIFEST_FILE).exists() {
+            continue;
+        }
+        match read_manifest(&path) {
+            Ok(manifest) => manifests.push(manifest),
+            Err(e) => eprintln!("Warning: skipping artifact {}: {}", path.display(), e),
+        }
+    }
+    manifests.sort_by(|a, b| a.tag.cmp(&b.tag));
+    Ok(manifests)
+}
+
+fn read_manifest(dir: &Path) -> Result<ArtifactManifest, ArtifactError> {
+    Ok(ron::from_str(&fs::read_to_string(dir.join(MANIFEST_FILE))?)?)
+}
+
+/// 64-bit FNV-1a of `bytes` as hex. Stable across platforms and Rust
+/// versions, unlike `std`'s hashers.
+fn content_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Store `bytes` under their hash, unless an identical blob is already there.
+fn write_blob(blobs: &Path, bytes: &[u8]) -> Result<String, ArtifactError> {
+    let hash = content_hash(bytes);
+    let path = blobs.join(&hash);
+    if !path.exists() {
+        fs::write(path, bytes)?;
+    }
+    Ok(hash)
+}
+
+fn read_blob(blobs: &Path, hash: &str) -> Result<Vec<u8>, ArtifactError> {
+    let bytes = fs::read(blobs.join(hash))
+        .map_err(|e| ArtifactError::Corrupt(format!("blob {}: {}", hash, e)))?;
+    if content_hash(&bytes) != hash {
+        return Err(ArtifactError::Corrupt(format!("blob {} does not match its hash", hash)));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_noise::LayerValue;
+    use rb_world::{CivilizationConfig, CivilizationGenerator};
+    use tempfile::tempdir;
+
+    #[test]
+    fn artifacts_round_trip_and_share_blobs() {
+        let root = tempdir().unwrap();
+        let map = BiomeMap::generate(42, 64, 32);
+        let mut world = WorldDefinition {
+            seed: 42,
+            width: 64,
+            height: 32,
+            ..Default::default()
+        };
+        let civilization = CivilizationGenerator::new(42, CivilizationConfig::default())
+            .generate(&map, &mut world);
+
+        let tag = artifact_tag("Test World", 42);
+        assert_eq!(tag, "test_world-42");
+        let manifest = save_layers(root.path(), &tag, &map, &world, Some(&civilization)).unwrap();
+        assert_eq!(manifest.version, ARTIFACT_VERSION);
+        assert!(manifest.layer("territory_owner").is_some());
+
+        let artifact = load_layers(root.path(), &tag).unwrap();
+        assert_eq!(artifact.manifest, manifest);
+        assert_eq!(artifact.manifest.civilization, Some(civilization));
+        assert_eq!(artifact.world.cities, world.cities);
+        let continentalness = artifact.layer("continentalness").unwrap();
+        assert_eq!(continentalness.get(10, 5), Some(map.continentalness[5 * 64 + 10].to_f64() as f32));
+        let territory = artifact.world.territory_cache.as_ref().unwrap();
+        assert_eq!(territory.ownership, world.territory_cache.as_ref().unwrap().ownership);
+
+        // Re-saving the same world writes no new blobs
+        let blobs = root.path().join(&tag).join(BLOBS_DIR);
+        let count = || fs::read_dir(&blobs).unwrap().count();
+        let before = count();
+        save_layers(root.path(), &tag, &map, &world, None).unwrap();
+        assert_eq!(count(), before);
+
+        let listed = list_artifacts(root.path()).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].seed, 42);
+
+        // Damage is caught rather than loaded
+        fs::write(blobs.join(&manifest.layers[0].blob), b"bad").unwrap();
+        assert!(matches!(load_layers(root.path(), &tag), Err(ArtifactError::Corrupt(_))));
+        assert!(matches!(
+            save_layers(root.path(), "../escape", &map, &world, None),
+            Err(ArtifactError::InvalidTag(_))
+        ));
+    }
+}
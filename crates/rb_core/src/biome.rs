@@ -1,12 +1,20 @@
+/// Depth below sea level under which open water is shallow.
+pub const SHALLOW_SEA_DEPTH: f64 = 0.04;
+/// Depth below sea level beyond which open water is deep ocean.
+pub const DEEP_SEA_DEPTH: f64 = 0.12;
+
 /// Biome/tile types for world map generation.
 /// Uses multi-axis climate classification for realistic biome placement.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 pub enum TileType {
     // Water types
+    ShallowSea,  // Coastal shelf waters
     #[default]
     Sea,
+    DeepSea,     // Open ocean far below sea level
     OceanTrench, // Deep navy - plate boundary depths
     River,       // Flowing water
+    Lake,        // Standing water in an inland basin
 
     // Coastal
     Beach,
@@ -35,41 +43,99 @@ pub enum TileType {
     Volcanic, // Dark red-brown - active volcanic
 }
 
+/// A biome's display name and map color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaletteEntry {
+    pub tile: TileType,
+    pub name: &'static str,
+    pub rgb: [u8; 3],
+}
+
+const fn entry(tile: TileType, name: &'static str, rgb: [u8; 3]) -> PaletteEntry {
+    PaletteEntry { tile, name, rgb }
+}
+
+/// Every biome with its color, in canonical order: `PALETTE[i].tile` has
+/// index `i` (see [`TileType::index`]).
+///
+/// Anything that draws or serializes biomes by color or position reads this
+/// table, so a color change here applies everywhere.
+pub const PALETTE: [PaletteEntry; 24] = [
+    // Water types
+    entry(TileType::ShallowSea, "Shallow Sea", [72, 209, 255]), // Light cyan - coastal shelf
+    entry(TileType::Sea, "Sea", [0, 191, 255]),                  // Cyan blue
+    entry(TileType::DeepSea, "Deep Sea", [0, 105, 178]),         // Dark blue - open ocean
+    entry(TileType::OceanTrench, "Ocean Trench", [0, 51, 102]),  // Deep navy - plate boundary depths
+    entry(TileType::River, "River", [64, 164, 223]),             // Light blue
+    entry(TileType::Lake, "Lake", [46, 139, 187]),               // Steel blue - inland basin
+    // Coastal
+    entry(TileType::Beach, "Beach", [222, 184, 135]), // Tan/burlywood
+    // Frozen biomes
+    entry(TileType::White, "Frozen Ocean", [255, 255, 255]), // Pure white
+    entry(TileType::Glacier, "Glacier", [200, 220, 255]),    // Ice blue - glacial ice
+    entry(TileType::Snow, "Snow", [211, 211, 211]),          // Light gray
+    entry(TileType::Tundra, "Tundra", [176, 196, 176]),      // Grayish green - permafrost
+    entry(TileType::Taiga, "Taiga", [34, 85, 68]),           // Dark teal - cold coniferous forest
+    // Temperate biomes
+    entry(TileType::Plains, "Plains", [50, 205, 50]),       // Lime green
+    entry(TileType::Forest, "Forest", [0, 100, 0]),         // Dark green
+    entry(TileType::Marsh, "Marsh", [85, 107, 47]),         // Olive drab - wetland
+    entry(TileType::Steppe, "Steppe", [160, 170, 110]),     // Pale yellow-green - dry grassland
+    entry(TileType::Mountain, "Mountain", [105, 105, 105]), // Dark gray
+    entry(TileType::Plateau, "Plateau", [139, 69, 19]),     // Saddle brown
+    // Hot biomes
+    entry(TileType::Savanna, "Savanna", [189, 183, 107]),   // Khaki - hot grassland
+    entry(TileType::Jungle, "Jungle", [0, 80, 32]),         // Very dark green - hot humid forest
+    entry(TileType::Desert, "Desert", [255, 215, 0]),       // Gold
+    entry(TileType::Sahara, "Sahara", [255, 165, 0]),       // Orange
+    entry(TileType::Badlands, "Badlands", [178, 102, 68]),  // Rust brown - eroded arid terrain
+    entry(TileType::Volcanic, "Volcanic", [64, 32, 32]),    // Dark red-brown - active volcanic
+];
+
+const ALL_TILES: [TileType; PALETTE.len()] = {
+    let mut tiles = [TileType::Sea; PALETTE.len()];
+    let mut i = 0;
+    while i < PALETTE.len() {
+        tiles[i] = PALETTE[i].tile;
+        i += 1;
+    }
+    tiles
+};
+
 impl TileType {
+    /// Returns all tile types in [`PALETTE`] order (water first).
+    pub fn all() -> &'static [TileType] {
+        &ALL_TILES
+    }
+
+    /// Position of this tile type in [`PALETTE`].
+    pub const fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Tile type at a [`PALETTE`] index.
+    pub fn from_index(index: usize) -> Option<Self> {
+        PALETTE.get(index).map(|entry| entry.tile)
+    }
+
+    /// Tile type drawn in exactly this color, if any.
+    pub fn from_rgb(rgb: [u8; 3]) -> Option<Self> {
+        PALETTE.iter().find(|entry| entry.rgb == rgb).map(|entry| entry.tile)
+    }
+
+    /// Tile type with this display name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        PALETTE.iter().find(|entry| entry.name.eq_ignore_ascii_case(name)).map(|entry| entry.tile)
+    }
+
+    /// Display name, e.g. for legends.
+    pub fn name(&self) -> &'static str {
+        PALETTE[self.index()].name
+    }
+
     /// Returns the RGB color for this tile type.
     pub fn rgb(&self) -> [u8; 3] {
-        match self {
-            // Water types
-            Self::Sea => [0, 191, 255],         // Cyan blue
-            Self::OceanTrench => [0, 51, 102],  // Deep navy - plate boundary depths
-            Self::River => [64, 164, 223],      // Light blue
-
-            // Coastal
-            Self::Beach => [222, 184, 135],     // Tan/burlywood
-
-            // Frozen biomes
-            Self::White => [255, 255, 255],     // Pure white (frozen ocean)
-            Self::Glacier => [200, 220, 255],   // Ice blue - glacial ice
-            Self::Snow => [211, 211, 211],      // Light gray
-            Self::Tundra => [176, 196, 176],    // Grayish green - permafrost
-            Self::Taiga => [34, 85, 68],        // Dark teal - cold coniferous forest
-
-            // Temperate biomes
-            Self::Plains => [50, 205, 50],      // Lime green
-            Self::Forest => [0, 100, 0],        // Dark green
-            Self::Marsh => [85, 107, 47],       // Olive drab - wetland
-            Self::Steppe => [160, 170, 110],    // Pale yellow-green - dry grassland
-            Self::Mountain => [105, 105, 105],  // Dark gray
-            Self::Plateau => [139, 69, 19],     // Saddle brown
-
-            // Hot biomes
-            Self::Savanna => [189, 183, 107],   // Khaki - hot grassland
-            Self::Jungle => [0, 80, 32],        // Very dark green - hot humid forest
-            Self::Desert => [255, 215, 0],      // Gold
-            Self::Sahara => [255, 165, 0],      // Orange
-            Self::Badlands => [178, 102, 68],   // Rust brown - eroded arid terrain
-            Self::Volcanic => [64, 32, 32],     // Dark red-brown - active volcanic
-        }
+        PALETTE[self.index()].rgb
     }
 
     /// Returns the RGBA color for this tile type.
@@ -78,6 +144,25 @@ impl TileType {
         [r, g, b, 255]
     }
 
+    /// Returns true for open water tiles (every sea tier and ocean trenches).
+    pub fn is_ocean(&self) -> bool {
+        matches!(
+            self,
+            Self::ShallowSea | Self::Sea | Self::DeepSea | Self::OceanTrench
+        )
+    }
+
+    /// Select the sea tier for a given depth below sea level.
+    pub fn sea_from_depth(depth: f64) -> Self {
+        if depth < SHALLOW_SEA_DEPTH {
+            Self::ShallowSea
+        } else if depth < DEEP_SEA_DEPTH {
+            Self::Sea
+        } else {
+            Self::DeepSea
+        }
+    }
+
     /// Determine tile type from continentalness and temperature.
     /// Uses the fungal-jungle tiling strategy thresholds.
     ///
@@ -93,7 +178,7 @@ impl TileType {
             } else if temperature > 50.0 {
                 Self::Desert // Hot ocean (rare)
             } else {
-                Self::Sea
+                Self::sea_from_depth(sea_level - continentalness)
             }
         } else if continentalness < sea_level + 0.02 {
             // Coastal zone
@@ -145,12 +230,32 @@ pub type BiomeType = TileType;
 mod tests {
     use super::*;
 
+    #[test]
+    fn colors_come_from_palette() {
+        for (i, entry) in PALETTE.iter().enumerate() {
+            assert_eq!(entry.tile.index(), i, "{} is out of order", entry.name);
+            let [r, g, b] = entry.rgb;
+            assert_eq!(entry.tile.color(), [r, g, b, 255]);
+            assert_eq!(TileType::from_rgb(entry.rgb), Some(entry.tile));
+            assert_eq!(TileType::from_name(&entry.name.to_uppercase()), Some(entry.tile));
+        }
+        assert_eq!(TileType::all().len(), PALETTE.len());
+        assert_eq!(TileType::from_index(PALETTE.len()), None);
+    }
+
     #[test]
     fn ocean_below_sea_level() {
-        let tile = TileType::from_climate(-0.5, 20.0, -0.025);
+        let tile = TileType::from_climate(-0.1, 20.0, -0.025);
         assert_eq!(tile, TileType::Sea);
     }
 
+    #[test]
+    fn ocean_tiers_by_depth() {
+        assert_eq!(TileType::from_climate(-0.03, 20.0, -0.025), TileType::ShallowSea);
+        assert_eq!(TileType::from_climate(-0.5, 20.0, -0.025), TileType::DeepSea);
+        assert!(TileType::ShallowSea.is_ocean() && TileType::DeepSea.is_ocean());
+    }
+
     #[test]
     fn frozen_ocean() {
         let tile = TileType::from_climate(-0.5, -30.0, -0.025);
@@ -0,0 +1,268 @@
+use bevy::prelude::Resource;
+use ron::extensions::Extensions;
+use serde::{Deserialize, Serialize};
+
+use crate::{ResourceType, TileType, PALETTE};
+
+/// Gameplay properties of one biome.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BiomeDef {
+    pub name: String,
+    pub color: [u8; 3],
+    /// Cost of crossing a cell when routing roads and trade. Infinite cells
+    /// are impassable by land.
+    pub movement_cost: f64,
+    /// Share of a faction's influence carried into a cell. 0 is a hard
+    /// border, 1 lets influence spread unchecked.
+    pub influence_decay: f64,
+    /// Names of the trade goods settled cells produce, e.g. `"Timber"`.
+    pub trade_goods: Vec<String>,
+    /// Multipliers on how readily each resource forms here. Resources not
+    /// listed keep their usual odds.
+    pub resource_biases: Vec<(ResourceType, f64)>,
+}
+
+/// A biome entry as written in a registry file: only the name is required,
+/// and anything left out keeps the built-in value.
+#[derive(Deserialize)]
+struct BiomePatch {
+    name: String,
+    color: Option<[u8; 3]>,
+    movement_cost: Option<f64>,
+    influence_decay: Option<f64>,
+    trade_goods: Option<Vec<String>>,
+    resource_biases: Option<Vec<(ResourceType, f64)>>,
+}
+
+/// Error loading a [`BiomeRegistry`].
+#[derive(Debug)]
+pub enum BiomeRegistryError {
+    Parse(ron::error::SpannedError),
+    /// A new biome (not overriding a built-in one) was given no color.
+    MissingColor(String),
+}
+
+impl From<ron::error::SpannedError> for BiomeRegistryError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl std::fmt::Display for BiomeRegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "RON parse error: {}", e),
+            Self::MissingColor(name) => write!(f, "new biome {:?} needs a color", name),
+        }
+    }
+}
+
+impl std::error::Error for BiomeRegistryError {}
+
+/// Movement cost, influence decay and trade goods of a built-in biome.
+fn builtin_properties(tile: TileType) -> (f64, f64, &'static [&'static str]) {
+    use TileType::*;
+    match tile {
+        // Water: impassable by land and a hard border, apart from inland water
+        ShallowSea => (f64::INFINITY, 0.0, &["Fish", "Salt"]),
+        Sea => (f64::INFINITY, 0.0, &["Fish"]),
+        DeepSea | OceanTrench => (f64::INFINITY, 0.0, &[]),
+        River => (2.0, 0.5, &["Fish", "Food"]), // Forded or bridged
+        Lake => (3.5, 0.4, &["Fish"]),          // Ferried across, unlike the open sea
+        Beach => (1.5, 0.9, &["Fish", "Salt"]),
+
+        // Frozen
+        White => (f64::INFINITY, 0.0, &["Fish", "Furs"]),
+        Glacier => (f64::INFINITY, 0.0, &["Furs"]),
+        Snow | Tundra => (6.0, 0.6, &["Furs"]),
+        Taiga => (3.5, 0.7, &["Timber", "Furs"]),
+
+        // Temperate
+        Plains => (1.0, 0.95, &["Food", "Textiles"]),
+        Forest => (3.0, 0.8, &["Timber", "Furs"]),
+        Marsh => (4.5, 0.55, &["Fish", "Food"]),
+        Steppe => (1.5, 0.85, &["Food", "Furs"]),
+        Mountain => (8.0, 0.3, &["Ore", "Weapons"]),
+        Plateau => (5.0, 0.5, &["Ore", "Food"]),
+
+        // Hot
+        Savanna => (1.5, 0.85, &["Food", "Furs"]),
+        Jungle => (4.5, 0.55, &["Timber", "Luxury"]),
+        Desert => (4.0, 0.7, &["Salt", "Luxury"]),
+        Sahara => (4.0, 0.7, &["Luxury"]),
+        Badlands => (5.5, 0.5, &["Ore"]),
+        Volcanic => (10.0, 0.2, &["Ore", "Luxury"]), // Dangerous ground
+    }
+}
+
+/// Every biome's gameplay properties, as data.
+///
+/// The first [`PALETTE`]`.len()` entries are the built-in biomes in
+/// [`TileType::index`] order, so a tile's entry is a direct lookup. A
+/// registry file can override any of their properties by name, and append
+/// new biomes after them.
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BiomeRegistry {
+    biomes: Vec<BiomeDef>,
+}
+
+impl Default for BiomeRegistry {
+    fn default() -> Self {
+        let biomes = PALETTE
+            .iter()
+            .map(|entry| {
+                let (movement_cost, influence_decay, goods) = builtin_properties(entry.tile);
+                BiomeDef {
+                    name: entry.name.to_string(),
+                    color: entry.rgb,
+                    movement_cost,
+                    influence_decay,
+                    trade_goods: goods.iter().map(|good| good.to_string()).collect(),
+                    resource_biases: Vec::new(),
+                }
+            })
+            .collect();
+        Self { biomes }
+    }
+}
+
+impl BiomeRegistry {
+    /// The built-in biomes with a RON list of entries applied on top.
+    ///
+    /// An entry named after a built-in biome overrides only the fields it
+    /// gives; any other name adds a new biome, which must have a color:
+    ///
+    /// ```ron
+    /// [
+    ///     (name: "Marsh", movement_cost: 6.0),
+    ///     (name: "Salt Flats", color: (240, 235, 220), trade_goods: ["Salt"]),
+    /// ]
+    /// ```
+    pub fn from_ron(source: &str) -> Result<Self, BiomeRegistryError> {
+        // Fields are optional, but a file shouldn't have to wrap them in `Some`
+        let options = ron::Options::default().with_default_extension(Extensions::IMPLICIT_SOME);
+        let patches: Vec<BiomePatch> = options.from_str(source)?;
+        let mut registry = Self::default();
+        for patch in patches {
+            let index = match registry.index_of(&patch.name) {
+                Some(index) => index,
+                None => {
+                    let Some(color) = patch.color else {
+                        return Err(BiomeRegistryError::MissingColor(patch.name));
+                    };
+                    registry.biomes.push(BiomeDef {
+                        name: patch.name.clone(),
+                        color,
+                        movement_cost: 1.0,
+                        influence_decay: 0.9,
+                        trade_goods: Vec::new(),
+                        resource_biases: Vec::new(),
+                    });
+                    registry.biomes.len() - 1
+                }
+            };
+
+            let biome = &mut registry.biomes[index];
+            if let Some(color) = patch.color {
+                biome.color = color;
+            }
+            if let Some(cost) = patch.movement_cost {
+                biome.movement_cost = cost;
+            }
+            if let Some(decay) = patch.influence_decay {
+                biome.influence_decay = decay;
+            }
+            if let Some(goods) = patch.trade_goods {
+                biome.trade_goods = goods;
+            }
+            if let Some(biases) = patch.resource_biases {
+                biome.resource_biases = biases;
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Properties of a built-in biome.
+    pub fn get(&self, tile: TileType) -> &BiomeDef {
+        &self.biomes[tile.index()]
+    }
+
+    /// Position of the biome with this name, if registered.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.biomes.iter().position(|biome| biome.name == name)
+    }
+
+    /// Biome with this name, built-in or added.
+    pub fn by_name(&self, name: &str) -> Option<&BiomeDef> {
+        self.index_of(name).map(|index| &self.biomes[index])
+    }
+
+    /// Every registered biome, built-ins first.
+    pub fn iter(&self) -> impl Iterator<Item = &BiomeDef> {
+        self.biomes.iter()
+    }
+
+    /// Biomes added on top of the built-in ones.
+    pub fn custom(&self) -> &[BiomeDef] {
+        &self.biomes[PALETTE.len()..]
+    }
+
+    pub fn movement_cost(&self, tile: TileType) -> f64 {
+        self.get(tile).movement_cost
+    }
+
+    pub fn influence_decay(&self, tile: TileType) -> f64 {
+        self.get(tile).influence_decay
+    }
+
+    /// Multiplier on `resource`'s odds in `tile`; 1 unless the registry
+    /// says otherwise.
+    pub fn resource_bias(&self, tile: TileType, resource: ResourceType) -> f64 {
+        self.get(tile)
+            .resource_biases
+            .iter()
+            .find(|(biased, _)| *biased == resource)
+            .map_or(1.0, |&(_, bias)| bias)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtins_follow_the_palette() {
+        let registry = BiomeRegistry::default();
+        for &tile in TileType::all() {
+            assert_eq!(registry.get(tile).name, tile.name());
+            assert_eq!(registry.get(tile).color, tile.rgb());
+        }
+        assert!(registry.custom().is_empty());
+        assert!(registry.movement_cost(TileType::Sea).is_infinite());
+        assert_eq!(registry.influence_decay(TileType::Sea), 0.0);
+        assert_eq!(registry.resource_bias(TileType::Plains, ResourceType::Iron), 1.0);
+    }
+
+    #[test]
+    fn ron_overrides_and_adds_biomes() {
+        let registry = BiomeRegistry::from_ron(
+            r#"[
+                (name: "Marsh", movement_cost: 6.0, resource_biases: [(Fish, 2.0)]),
+                (name: "Salt Flats", color: (240, 235, 220), trade_goods: ["Salt"]),
+            ]"#,
+        )
+        .unwrap();
+
+        let marsh = registry.get(TileType::Marsh);
+        assert_eq!(marsh.movement_cost, 6.0);
+        assert_eq!(marsh.influence_decay, 0.55); // Untouched
+        assert_eq!(registry.resource_bias(TileType::Marsh, ResourceType::Fish), 2.0);
+
+        assert_eq!(registry.custom().len(), 1);
+        let flats = registry.by_name("Salt Flats").unwrap();
+        assert_eq!(flats.trade_goods, vec!["Salt".to_string()]);
+
+        let missing = BiomeRegistry::from_ron(r#"[(name: "Ash Fields")]"#);
+        assert!(matches!(missing, Err(BiomeRegistryError::MissingColor(_))));
+    }
+}
@@ -39,6 +39,19 @@ impl WorldPos {
     }
 }
 
+/// Convert a world-space position to map space for a map of `map_size` units.
+///
+/// The map is drawn centered on the origin with +Y up, while map space starts
+/// at the top-left corner with +Y down.
+pub fn world_to_map(world: Vec2, map_size: Vec2) -> Vec2 {
+    Vec2::new(world.x + map_size.x / 2.0, map_size.y / 2.0 - world.y)
+}
+
+/// Inverse of [`world_to_map`].
+pub fn map_to_world(map: Vec2, map_size: Vec2) -> Vec2 {
+    Vec2::new(map.x - map_size.x / 2.0, map_size.y / 2.0 - map.y)
+}
+
 /// Detail level for the fractal noise hierarchy.
 /// Each level provides progressively finer detail.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
@@ -66,4 +79,72 @@ impl DetailLevel {
     pub const fn as_u32(&self) -> u32 {
         *self as u32
     }
+
+    /// Returns the level for a detail index, clamped to `Micro`.
+    pub const fn from_index(index: u32) -> Self {
+        match index {
+            0 => DetailLevel::Macro,
+            1 => DetailLevel::Meso,
+            _ => DetailLevel::Micro,
+        }
+    }
+
+    /// Split a continuous detail value into the two adjacent levels and the
+    /// blend factor between them.
+    ///
+    /// `1.25` gives `(Meso, Micro, 0.25)`. Values outside `0..=2` are clamped.
+    pub fn blend(detail: f64) -> (DetailLevel, DetailLevel, f64) {
+        let detail = detail.clamp(0.0, DetailLevel::Micro.as_u32() as f64);
+        let lower = detail.floor() as u32;
+        (
+            Self::from_index(lower),
+            Self::from_index(lower + 1),
+            detail - lower as f64,
+        )
+    }
+
+    /// Effective (fractional) octave count for a strategy with `base_octaves`
+    /// sampled at a continuous detail value. Each level adds one octave, so the
+    /// count ramps linearly instead of stepping.
+    pub fn octaves_at(base_octaves: u32, detail: f64) -> f64 {
+        let (lower, _, t) = Self::blend(detail);
+        (base_octaves + lower.as_u32()) as f64 + t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_and_map_space_round_trip() {
+        let size = Vec2::new(512.0, 256.0);
+        assert_eq!(world_to_map(Vec2::ZERO, size), Vec2::new(256.0, 128.0));
+        assert_eq!(world_to_map(Vec2::new(-256.0, 128.0), size), Vec2::ZERO);
+
+        let world = Vec2::new(37.5, -90.25);
+        assert_eq!(map_to_world(world_to_map(world, size), size), world);
+    }
+
+    #[test]
+    fn blend_splits_between_levels() {
+        assert_eq!(DetailLevel::blend(0.0), (DetailLevel::Macro, DetailLevel::Meso, 0.0));
+        assert_eq!(DetailLevel::blend(1.25), (DetailLevel::Meso, DetailLevel::Micro, 0.25));
+        assert_eq!(DetailLevel::blend(5.0), (DetailLevel::Micro, DetailLevel::Micro, 0.0));
+    }
+
+    #[test]
+    fn octave_count_ramps_monotonically() {
+        let mut previous = DetailLevel::octaves_at(6, 0.0);
+        assert_eq!(previous, 6.0);
+
+        for step in 1..=40 {
+            let detail = step as f64 * 0.05;
+            let octaves = DetailLevel::octaves_at(6, detail);
+            assert!(octaves > previous, "Octaves dropped at detail {}", detail);
+            assert!(octaves - previous <= 0.05 + 1e-9, "Octaves jumped at detail {}", detail);
+            previous = octaves;
+        }
+        assert_eq!(previous, 8.0);
+    }
 }
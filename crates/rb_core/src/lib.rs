@@ -1,16 +1,20 @@
 use bevy::prelude::*;
 
 pub mod biome;
+pub mod biome_registry;
 pub mod coords;
 pub mod mode;
 pub mod noise;
 pub mod resource_type;
+pub mod rng;
 
-pub use biome::{BiomeType, TileType};
-pub use coords::{ChunkCoord, DetailLevel, TileCoord, WorldPos};
+pub use biome::{BiomeType, PaletteEntry, TileType, DEEP_SEA_DEPTH, PALETTE, SHALLOW_SEA_DEPTH};
+pub use biome_registry::{BiomeDef, BiomeRegistry, BiomeRegistryError};
+pub use coords::{map_to_world, world_to_map, ChunkCoord, DetailLevel, TileCoord, WorldPos};
 pub use mode::{AppMode, ModeTransitionEvent, handle_mode_shortcuts};
-pub use noise::NoiseStrategy;
+pub use noise::{ElevationSource, NoiseStrategy};
 pub use resource_type::{ResourceType, TerrainBias};
+pub use rng::{rng_for, stream_seed};
 
 /// Core plugin providing foundational types for Randlebrot.
 pub struct RbCorePlugin;
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 /// Application mode state for the Randlebrot editor.
 ///
-/// The editor operates in one of four modes, each providing
+/// The editor operates in one of five modes, each providing
 /// different tools and views for world creation and testing.
 #[derive(States, Default, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum AppMode {
@@ -26,6 +26,11 @@ pub enum AppMode {
     /// Primary view: Playable chunk with player
     /// Tools: Play/stop, teleport, debug overlays
     LevelLauncher,
+
+    /// Compare candidate seeds before committing to one.
+    /// Primary view: Grid of low-res biome thumbnails
+    /// Tools: Seed range, page through seeds, click to adopt
+    SeedExplorer,
 }
 
 impl AppMode {
@@ -36,6 +41,7 @@ impl AppMode {
             Self::WorldMapEditor => "Map Editor",
             Self::ChunkEditor => "Chunk Editor",
             Self::LevelLauncher => "Launcher",
+            Self::SeedExplorer => "Seed Explorer",
         }
     }
 
@@ -46,6 +52,7 @@ impl AppMode {
             Self::WorldMapEditor => KeyCode::F2,
             Self::ChunkEditor => KeyCode::F3,
             Self::LevelLauncher => KeyCode::F4,
+            Self::SeedExplorer => KeyCode::F5,
         }
     }
 
@@ -56,6 +63,7 @@ impl AppMode {
             Self::WorldMapEditor,
             Self::ChunkEditor,
             Self::LevelLauncher,
+            Self::SeedExplorer,
         ]
     }
 }
@@ -67,7 +75,7 @@ pub struct ModeTransitionEvent {
     pub to: AppMode,
 }
 
-/// System that handles F1-F4 key presses to switch modes.
+/// System that handles F1-F5 key presses to switch modes.
 pub fn handle_mode_shortcuts(
     keyboard: Res<ButtonInput<KeyCode>>,
     current_mode: Res<State<AppMode>>,
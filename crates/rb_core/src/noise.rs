@@ -1,3 +1,5 @@
+use crate::DetailLevel;
+
 /// Trait for noise generation strategies.
 ///
 /// Each strategy generates a specific type of noise (continentalness, temperature, etc.)
@@ -12,13 +14,107 @@ pub trait NoiseStrategy: Send + Sync {
     /// * `y` - World Y coordinate (f64 for precision)
     /// * `detail_level` - The detail level (0=Macro, 1=Meso, 2=Micro)
     ///
+    /// Each detail level adds a finer octave on top of the base ones without
+    /// renormalizing them, so a coordinate's value only moves by that octave's
+    /// small amplitude between levels and biomes don't shift on zoom.
+    ///
     /// # Returns
     /// A noise value, typically in the range [-1.0, 1.0] or [0.0, 1.0]
     /// depending on the specific strategy.
     fn generate(&self, x: f64, y: f64, detail_level: u32) -> f64;
 
+    /// Generate at a continuous detail value, crossfading between the two
+    /// adjacent detail levels so the finer octave fades in instead of popping.
+    ///
+    /// See [`DetailLevel::blend`] for how the value is split.
+    fn generate_blended(&self, x: f64, y: f64, detail: f64) -> f64 {
+        let (lower, upper, t) = DetailLevel::blend(detail);
+        let coarse = self.generate(x, y, lower.as_u32());
+        if t <= 0.0 {
+            return coarse;
+        }
+        coarse + (self.generate(x, y, upper.as_u32()) - coarse) * t
+    }
+
+    /// Generate the low-frequency shape and the finer texture at
+    /// `detail_level` separately, as `(coarse, detail)`.
+    ///
+    /// `coarse` is the value at detail level 0. The extra octaves are tiny
+    /// next to it by design, which leaves chunk-scale terrain flat, so fBm
+    /// strategies override this to return them normalized on their own, in
+    /// [-1.0, 1.0], for callers to scale as they see fit. The default gives
+    /// the plain difference between the two levels.
+    fn generate_split(&self, x: f64, y: f64, detail_level: u32) -> (f64, f64) {
+        let coarse = self.generate(x, y, 0);
+        (coarse, self.generate(x, y, detail_level) - coarse)
+    }
+
+    /// Generate at many points at once, writing the value for `points[i]`
+    /// to `out[i]`.
+    ///
+    /// Gives the same values as [`generate`](Self::generate). The default
+    /// calls it per point; fBm strategies override it to run each octave
+    /// across the whole batch, which keeps the per-point loop free of
+    /// dispatch and branching.
+    ///
+    /// # Panics
+    /// If `points` and `out` differ in length.
+    fn generate_batch(&self, points: &[(f64, f64)], detail_level: u32, out: &mut [f64]) {
+        assert_eq!(points.len(), out.len(), "one output per point");
+        for (value, &(x, y)) in out.iter_mut().zip(points) {
+            *value = self.generate(x, y, detail_level);
+        }
+    }
+
     /// Returns the name of this noise strategy for debugging.
     fn name(&self) -> &'static str {
         "NoiseStrategy"
     }
+
+    /// Bounds `(min, max)` that [`generate`](Self::generate) stays within,
+    /// for scaling previews and legends.
+    fn output_range(&self) -> (f64, f64) {
+        (-1.0, 1.0)
+    }
+
+    /// One-line summary of what the values mean, for tooltips and tooling.
+    fn description(&self) -> &'static str {
+        ""
+    }
+}
+
+/// Point query for terrain elevation.
+///
+/// Implemented by both precomputed maps and streamed samplers so consumers
+/// (pathfinding, exporters, the launcher) don't depend on where the data lives.
+pub trait ElevationSource: Send + Sync {
+    /// Terrain elevation at a world position, interpolated between samples.
+    fn height(&self, x: f64, y: f64) -> f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the detail level it was asked for.
+    struct LevelEcho;
+
+    impl NoiseStrategy for LevelEcho {
+        fn generate(&self, _x: f64, _y: f64, detail_level: u32) -> f64 {
+            detail_level as f64
+        }
+    }
+
+    #[test]
+    fn blended_generation_interpolates_levels() {
+        assert_eq!(LevelEcho.generate_blended(0.0, 0.0, 1.0), 1.0);
+        assert!((LevelEcho.generate_blended(0.0, 0.0, 0.3) - 0.3).abs() < 1e-12);
+        assert!((LevelEcho.generate_blended(0.0, 0.0, 1.75) - 1.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn split_separates_base_from_detail() {
+        assert_eq!(LevelEcho.generate_split(0.0, 0.0, 2), (0.0, 2.0));
+        assert_eq!(LevelEcho.generate_split(0.0, 0.0, 0), (0.0, 0.0));
+    }
 }
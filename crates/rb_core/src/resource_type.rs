@@ -1,8 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 use crate::TileType;
 
 /// Types of natural resources that can be found in the world.
 /// Each resource has terrain biases that affect where it appears.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ResourceType {
     // Metals (biased toward mountains/tectonic boundaries)
     Iron,
@@ -69,6 +71,27 @@ impl ResourceType {
         }
     }
 
+    /// Fraction of a generated map's cells that should hold this resource.
+    ///
+    /// Generation picks each resource's cutoff to hit this share, so the
+    /// abundance of a resource doesn't drift with map size or seed.
+    pub fn target_density(&self) -> f64 {
+        match self {
+            Self::Iron => 0.01,
+            Self::Gold => 0.02,
+            Self::Copper => 0.01,
+            Self::Silver => 0.03,
+            Self::Gems => 0.02,
+            Self::Coal => 0.01,
+            Self::Stone => 0.01,
+            Self::Salt => 0.1,
+            Self::Timber => 0.002,
+            Self::Fish => 0.1,
+            Self::FertileSoil => 0.007,
+            Self::WildGame => 0.008,
+        }
+    }
+
     /// Returns the display name for this resource.
     pub fn name(&self) -> &'static str {
         match self {
@@ -154,8 +177,16 @@ impl TerrainBias {
     ) -> f64 {
         match self {
             TerrainBias::Mountain { weight } => {
-                // Continentalness > 0.1 is elevated terrain
-                let mountain_factor = ((continentalness - 0.1) / 0.4).clamp(0.0, 1.0);
+                // Continentalness > 0.1 is elevated terrain; highland biomes
+                // count as fully elevated so overrides carry their ore
+                let mountain_factor = if matches!(
+                    biome,
+                    TileType::Mountain | TileType::Plateau | TileType::Volcanic
+                ) {
+                    1.0
+                } else {
+                    ((continentalness - 0.1) / 0.4).clamp(0.0, 1.0)
+                };
                 1.0 - weight + weight * mountain_factor
             }
             TerrainBias::TectonicBoundary { weight } => {
@@ -223,6 +254,6 @@ mod tests {
         let forest = bias.calculate(0.0, 0.5, 0.5, TileType::Forest);
         let plains = bias.calculate(0.0, 0.5, 0.5, TileType::Plains);
         assert_eq!(forest, 1.0);
-        assert_eq!(plains, 0.1);
+        assert!((plains - 0.1).abs() < 1e-9);
     }
 }
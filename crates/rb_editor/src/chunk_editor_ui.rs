@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::{egui, EguiContexts};
-use rb_core::AppMode;
+use rb_core::{world_to_map, AppMode};
 use rb_world::{SelectedChunk, WorldDefinition};
 
 // WorldDefinition is used in chunk_selection_system
@@ -143,6 +143,7 @@ pub fn chunk_editor_ui_system(
 }
 
 /// System to handle chunk selection clicks (in Map Editor or Chunk Editor mode).
+#[allow(clippy::too_many_arguments)]
 pub fn chunk_selection_system(
     mouse: Res<ButtonInput<MouseButton>>,
     keyboard: Res<ButtonInput<KeyCode>>,
@@ -186,8 +187,8 @@ pub fn chunk_selection_system(
     let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) else { return };
 
     // Convert to map coordinates
-    let map_x = world_pos.x + (world_def.width as f32 / 2.0);
-    let map_y = (world_def.height as f32 / 2.0) - world_pos.y;
+    let map_size = Vec2::new(world_def.width as f32, world_def.height as f32);
+    let Vec2 { x: map_x, y: map_y } = world_to_map(world_pos, map_size);
 
     // Check bounds
     if map_x < 0.0 || map_x >= world_def.width as f32 || map_y < 0.0 || map_y >= world_def.height as f32 {
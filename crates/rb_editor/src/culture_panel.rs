@@ -0,0 +1,224 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use rb_core::AppMode;
+use rb_persistence::{culture_pack_path, list_culture_packs, load_culture_pack, save_culture_pack, CulturePack};
+use rb_world::{Culture, CultureType, WorldDefinition};
+
+use crate::undo::{EditCultures, UndoStack};
+
+/// State of the Cultures window.
+#[derive(Resource)]
+pub struct CulturePanelState {
+    pub open: bool,
+    /// Index of the culture whose properties are shown.
+    pub selected: Option<usize>,
+    /// Name the cultures are saved under as a pack.
+    pub pack_name: String,
+    /// Outcome of the last save or load.
+    pub status: Option<String>,
+}
+
+impl Default for CulturePanelState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            selected: None,
+            pack_name: "My Cultures".to_string(),
+            status: None,
+        }
+    }
+}
+
+/// System to show the Cultures window: the world's cultures, the selected
+/// one's preferences and traits, and saving and loading them as a pack.
+pub fn culture_panel_system(
+    mut contexts: EguiContexts,
+    mut state: ResMut<CulturePanelState>,
+    mut world_def: ResMut<WorldDefinition>,
+    mut undo: ResMut<UndoStack>,
+    current_mode: Res<State<AppMode>>,
+) {
+    if *current_mode.get() != AppMode::WorldMapEditor || !state.open {
+        return;
+    }
+
+    let mut open = state.open;
+    egui::Window::new("Cultures")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(320.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label("Used when the civilization is next generated");
+            if world_def.cultures.is_empty() {
+                ui.label("None authored; the built-in cultures are used");
+            }
+            for (i, culture) in world_def.cultures.iter().enumerate() {
+                let label = format!("{} ({})", culture.name, culture.culture_type.name());
+                if ui.selectable_label(state.selected == Some(i), label).clicked() {
+                    state.selected = Some(i);
+                }
+            }
+
+            let selected = state.selected.filter(|&i| i < world_def.cultures.len());
+            let mut after = None;
+            ui.horizontal(|ui| {
+                if ui.button("New").clicked() {
+                    let mut culture = Culture::from_type(CultureType::TwilightDweller);
+                    culture.name = "New Culture".to_string();
+                    after = Some(("Add culture", with_culture(&world_def.cultures, culture)));
+                }
+                if let Some(i) = selected {
+                    if ui.button("Duplicate").clicked() {
+                        let mut copy = world_def.cultures[i].clone();
+                        copy.name += " (copy)";
+                        after = Some(("Duplicate culture", with_culture(&world_def.cultures, copy)));
+                    }
+                    if ui.button("Delete").clicked() {
+                        let mut cultures = world_def.cultures.clone();
+                        cultures.remove(i);
+                        after = Some(("Delete culture", cultures));
+                    }
+                }
+                if world_def.cultures.is_empty() && ui.button("Start from built-ins").clicked() {
+                    after = Some(("Add built-in cultures", Culture::all_defaults()));
+                }
+            });
+            if let Some((description, cultures)) = after {
+                state.selected = (cultures.len() > world_def.cultures.len()).then(|| cultures.len() - 1);
+                let before = world_def.cultures.clone();
+                undo.execute(&mut world_def, EditCultures::new(description, before, cultures, false));
+            }
+
+            if let Some(i) = selected {
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    if let Some((edited, open)) = culture_properties(ui, &world_def.cultures[i]) {
+                        let description = format!("Edit culture {}", world_def.cultures[i].name);
+                        let before = world_def.cultures.clone();
+                        let mut cultures = before.clone();
+                        cultures[i] = edited;
+                        undo.execute(&mut world_def, EditCultures::new(description, before, cultures, open));
+                    }
+                });
+            }
+
+            ui.separator();
+            pack_controls(ui, &mut state, &mut world_def, &mut undo);
+        });
+    state.open = open;
+}
+
+/// `cultures` with `culture` added at the end.
+fn with_culture(cultures: &[Culture], culture: Culture) -> Vec<Culture> {
+    cultures.iter().cloned().chain([culture]).collect()
+}
+
+/// Widgets for one culture. Returns the edited culture if anything changed,
+/// and whether the edit is still in progress (a name being typed or a
+/// slider held).
+fn culture_properties(ui: &mut egui::Ui, culture: &Culture) -> Option<(Culture, bool)> {
+    let mut edited = culture.clone();
+    let mut open = false;
+
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        open |= ui.text_edit_singleline(&mut edited.name).has_focus();
+    });
+    egui::ComboBox::from_label("Based on")
+        .selected_text(edited.culture_type.name())
+        .show_ui(ui, |ui| {
+            for &culture_type in CultureType::all() {
+                ui.selectable_value(&mut edited.culture_type, culture_type, culture_type.name());
+            }
+        });
+
+    // Ranges as min and max, each kept on its own side of the other
+    let mut range = |ui: &mut egui::Ui, label: &str, (min, max): &mut (f64, f64), limits: (f64, f64), speed: f64| {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            let low = ui.add(egui::DragValue::new(min).range(limits.0..=*max).speed(speed));
+            let high = ui.add(egui::DragValue::new(max).range(*min..=limits.1).speed(speed));
+            open |= low.dragged() || high.dragged() || low.has_focus() || high.has_focus();
+        });
+    };
+    range(ui, "Temperature:", &mut edited.temperature_range, (-100.0, 150.0), 0.5);
+    range(ui, "Continentalness:", &mut edited.continentalness_range, (-1.0, 1.0), 0.005);
+
+    ui.label("Traits:");
+    let traits = &mut edited.traits;
+    open |= ui.add(egui::Slider::new(&mut traits.settlement_tendency, 0.0..=1.0).text("Settlement tendency")).dragged();
+    open |= ui.add(egui::Slider::new(&mut traits.settlement_spacing, 20.0..=200.0).text("Settlement spacing")).dragged();
+    open |= ui.add(egui::Slider::new(&mut traits.expansion_drive, 0.0..=1.0).text("Expansion drive")).dragged();
+    open |= ui.add(egui::Slider::new(&mut traits.trade_focus, 0.0..=1.0).text("Trade focus")).dragged();
+    open |= ui.add(egui::Slider::new(&mut traits.defensive_preference, 0.0..=1.0).text("Defensive preference")).dragged();
+
+    ui.collapsing("Biome preferences", |ui| {
+        for (biome, value) in edited.biome_preferences.entries_mut() {
+            open |= ui.add(egui::Slider::new(value, -1.0..=1.0).text(biome)).dragged();
+        }
+    });
+
+    (edited != *culture).then_some((edited, open))
+}
+
+/// Saving the world's cultures as a pack, and loading one in their place.
+fn pack_controls(ui: &mut egui::Ui, state: &mut CulturePanelState, world_def: &mut WorldDefinition, undo: &mut UndoStack) {
+    ui.horizontal(|ui| {
+        ui.label("Pack:");
+        ui.text_edit_singleline(&mut state.pack_name);
+    });
+    ui.horizontal(|ui| {
+        let saveable = !world_def.cultures.is_empty() && !state.pack_name.trim().is_empty();
+        if ui.add_enabled(saveable, egui::Button::new("Save pack")).clicked() {
+            let path = culture_pack_path(&state.pack_name);
+            let pack = CulturePack { name: state.pack_name.clone(), cultures: world_def.cultures.clone() };
+            state.status = Some(match save_culture_pack(&path, &pack) {
+                Ok(()) => format!("Saved {}", path.display()),
+                Err(e) => format!("Could not save {}: {}", path.display(), e),
+            });
+        }
+
+        let mut load = None;
+        egui::ComboBox::from_id_salt("culture_pack_load")
+            .selected_text("Load pack…")
+            .show_ui(ui, |ui| match list_culture_packs() {
+                Ok(packs) if !packs.is_empty() => {
+                    for path in packs {
+                        let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+                        if ui.selectable_label(false, name).clicked() {
+                            load = Some(path);
+                        }
+                    }
+                }
+                Ok(_) => {
+                    ui.label("No packs saved yet");
+                }
+                Err(e) => {
+                    ui.label(format!("Could not list packs: {}", e));
+                }
+            });
+        if let Some(path) = load {
+            state.status = Some(load_pack(&path, state, world_def, undo));
+        }
+    });
+    if let Some(status) = &state.status {
+        ui.label(status);
+    }
+}
+
+/// Replace the world's cultures with the pack at `path`. Returns the outcome.
+fn load_pack(path: &Path, state: &mut CulturePanelState, world_def: &mut WorldDefinition, undo: &mut UndoStack) -> String {
+    match load_culture_pack(path) {
+        Ok(pack) => {
+            let description = format!("Load culture pack {}", pack.name);
+            let before = world_def.cultures.clone();
+            undo.execute(world_def, EditCultures::new(description, before, pack.cultures, false));
+            state.pack_name = pack.name;
+            state.selected = None;
+            format!("Loaded {}", path.display())
+        }
+        Err(e) => format!("Could not load {}: {}", path.display(), e),
+    }
+}
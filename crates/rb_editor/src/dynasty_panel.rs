@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use rb_core::AppMode;
+use rb_world::{HistoricalEventKind, Ruler, WorldDefinition};
+
+/// State of the Dynasties window.
+#[derive(Resource, Default)]
+pub struct DynastyPanelState {
+    pub open: bool,
+    /// Faction whose rulers are shown.
+    pub faction: Option<u32>,
+}
+
+/// Whether a historical event concerns a faction.
+fn involves(kind: &HistoricalEventKind, faction: u32) -> bool {
+    match *kind {
+        HistoricalEventKind::Founded { faction: f, .. } | HistoricalEventKind::Razed { by: f, .. } => f == faction,
+        HistoricalEventKind::WarDeclared { attacker: a, defender: b }
+        | HistoricalEventKind::Conquered { from: a, to: b, .. }
+        | HistoricalEventKind::PeaceMade { factions: (a, b) }
+        | HistoricalEventKind::Fell { faction: a, by: b } => a == faction || b == faction,
+    }
+}
+
+/// System to show the Dynasties window: a faction's rulers in order of
+/// reign, with their house, how they came to power and their traits, and
+/// the events of the faction's history. Read-only; the line is regenerated
+/// with the civilization.
+pub fn dynasty_panel_system(
+    mut contexts: EguiContexts,
+    mut state: ResMut<DynastyPanelState>,
+    world_def: Res<WorldDefinition>,
+    current_mode: Res<State<AppMode>>,
+) {
+    if *current_mode.get() != AppMode::WorldMapEditor || !state.open {
+        return;
+    }
+
+    let mut open = state.open;
+    egui::Window::new("Dynasties")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(380.0)
+        .show(contexts.ctx_mut(), |ui| {
+            if world_def.rulers.is_empty() {
+                ui.label("No rulers yet; generate a civilization first");
+                return;
+            }
+
+            let faction_name = |id: u32| world_def.factions.iter().find(|f| f.id == id).map(|f| f.name.as_str());
+            let selected = state.faction.and_then(faction_name).unwrap_or("Choose a faction");
+            egui::ComboBox::from_label("Faction")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for faction in &world_def.factions {
+                        ui.selectable_value(&mut state.faction, Some(faction.id), &faction.name);
+                    }
+                });
+            let Some(faction) = state.faction else { return };
+
+            ui.separator();
+            egui::ScrollArea::vertical().max_height(320.0).id_salt("dynasty_rulers").show(ui, |ui| {
+                egui::Grid::new("dynasty_rulers_grid").striped(true).show(ui, |ui| {
+                    for heading in ["Reign", "Ruler", "House", "Succession", "Traits"] {
+                        ui.strong(heading);
+                    }
+                    ui.end_row();
+                    for ruler in world_def.rulers.iter().filter(|r| r.faction_id == faction) {
+                        ruler_row(ui, ruler, &world_def);
+                    }
+                });
+            });
+
+            let events: Vec<_> = world_def.history.iter().filter(|e| involves(&e.kind, faction)).collect();
+            if !events.is_empty() {
+                ui.separator();
+                ui.collapsing(format!("History ({})", events.len()), |ui| {
+                    egui::ScrollArea::vertical().max_height(200.0).id_salt("dynasty_history").show(ui, |ui| {
+                        for event in events {
+                            ui.label(format!("Year {}: {}", event.year, event.summary));
+                        }
+                    });
+                });
+            }
+        });
+    state.open = open;
+}
+
+/// One row of the rulers grid.
+fn ruler_row(ui: &mut egui::Ui, ruler: &Ruler, world_def: &WorldDefinition) {
+    let reign = match ruler.reign_end {
+        Some(end) => format!("{}–{}", ruler.reign_start, end),
+        None => format!("{}–", ruler.reign_start),
+    };
+    ui.label(reign);
+    ui.label(&ruler.name);
+    let house = world_def.dynasties.iter().find(|d| d.id == ruler.dynasty_id);
+    ui.label(house.map_or("—", |d| d.name.as_str()));
+    ui.label(ruler.succession.name());
+    let traits: Vec<&str> = ruler.traits.iter().map(|t| t.name()).collect();
+    ui.label(traits.join(", "));
+    ui.end_row();
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use rb_core::AppMode;
+use rb_world::{CultureType, Faction, Stance, WorldDefinition};
+
+use crate::undo::{Edit, SetRelation, UndoStack};
+
+/// State of the Factions window.
+#[derive(Resource, Default)]
+pub struct FactionPanelState {
+    pub open: bool,
+    /// Faction whose properties are shown.
+    pub selected: Option<u32>,
+}
+
+/// Colour a stance is shown in.
+fn stance_color(stance: Stance) -> egui::Color32 {
+    match stance {
+        Stance::Allied => egui::Color32::from_rgb(110, 200, 110),
+        Stance::Neutral => egui::Color32::GRAY,
+        Stance::Hostile => egui::Color32::from_rgb(220, 90, 80),
+    }
+}
+
+/// The stance a click on a relation cell moves on to.
+fn next_stance(stance: Stance) -> Stance {
+    match stance {
+        Stance::Allied => Stance::Neutral,
+        Stance::Neutral => Stance::Hostile,
+        Stance::Hostile => Stance::Allied,
+    }
+}
+
+/// System to show the Factions window: every faction, the selected one's
+/// properties, and the relations between each pair. Edits go through the
+/// undo stack.
+pub fn faction_panel_system(
+    mut contexts: EguiContexts,
+    mut state: ResMut<FactionPanelState>,
+    mut world_def: ResMut<WorldDefinition>,
+    mut undo: ResMut<UndoStack>,
+    current_mode: Res<State<AppMode>>,
+) {
+    if *current_mode.get() != AppMode::WorldMapEditor || !state.open {
+        return;
+    }
+
+    let mut open = state.open;
+    egui::Window::new("Factions")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(360.0)
+        .show(contexts.ctx_mut(), |ui| {
+            if world_def.factions.is_empty() {
+                ui.label("No factions yet; generate a civilization first");
+                return;
+            }
+
+            for faction in &world_def.factions {
+                ui.horizontal(|ui| {
+                    let [r, g, b, _] = faction.color;
+                    ui.colored_label(egui::Color32::from_rgb(r, g, b), "■");
+                    let label = format!("{} ({} settlements)", faction.name, faction.settlement_count());
+                    if ui.selectable_label(state.selected == Some(faction.id), label).clicked() {
+                        state.selected = Some(faction.id);
+                    }
+                });
+            }
+
+            let selected = state.selected.and_then(|id| world_def.factions.iter().find(|f| f.id == id));
+            if let Some(faction) = selected {
+                ui.separator();
+                if let Some((edited, open)) = faction_properties(ui, faction, &world_def) {
+                    let before = faction.clone();
+                    undo.execute(&mut world_def, Edit::new(before, edited, open));
+                }
+            }
+
+            ui.separator();
+            ui.label("Relations (click to change):");
+            if let Some((a, b, stance)) = relations_matrix(ui, &world_def.factions) {
+                if let Some(command) = SetRelation::new(&world_def, a, b, stance.relation()) {
+                    undo.execute(&mut world_def, command);
+                }
+            }
+        });
+    state.open = open;
+}
+
+/// Widgets for one faction's properties. Returns the edited faction if
+/// anything changed, and whether the edit is still in progress (a name being
+/// typed or a slider held).
+fn faction_properties(ui: &mut egui::Ui, faction: &Faction, world_def: &WorldDefinition) -> Option<(Faction, bool)> {
+    let mut edited = faction.clone();
+    let mut open = false;
+
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        open |= ui.text_edit_singleline(&mut edited.name).has_focus();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Colour:");
+        // Held open while the picker is up, so dragging a colour is one step
+        ui.color_edit_button_srgba_unmultiplied(&mut edited.color);
+        open |= ui.memory(|memory| memory.any_popup_open());
+    });
+
+    egui::ComboBox::from_label("Culture")
+        .selected_text(edited.culture.name())
+        .show_ui(ui, |ui| {
+            for &culture in CultureType::all() {
+                ui.selectable_value(&mut edited.culture, culture, culture.name());
+            }
+        });
+
+    // The capital is one of the faction's own settlements
+    let city_name = |id: u32| world_def.cities.iter().find(|c| c.id == id).map(|c| c.name.as_str());
+    let capital = edited.capital_id.and_then(city_name).unwrap_or("None");
+    egui::ComboBox::from_label("Capital")
+        .selected_text(capital)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut edited.capital_id, None, "None");
+            for &id in &faction.settlement_ids {
+                if let Some(name) = city_name(id) {
+                    ui.selectable_value(&mut edited.capital_id, Some(id), name);
+                }
+            }
+        });
+
+    let disposition = &mut edited.disposition;
+    for (value, label) in [
+        (&mut disposition.aggressiveness, "Aggressiveness"),
+        (&mut disposition.trade_openness, "Trade openness"),
+        (&mut disposition.isolationism, "Isolationism"),
+    ] {
+        open |= ui.add(egui::Slider::new(value, 0.0..=1.0).text(label)).dragged();
+    }
+
+    (edited != *faction).then_some((edited, open))
+}
+
+/// Grid of every pair's stance. Returns the pair clicked and the stance it
+/// moves on to.
+fn relations_matrix(ui: &mut egui::Ui, factions: &[Faction]) -> Option<(u32, u32, Stance)> {
+    let mut clicked = None;
+    egui::ScrollArea::horizontal().show(ui, |ui| {
+        egui::Grid::new("faction_relations").striped(true).show(ui, |ui| {
+            ui.label("");
+            for faction in factions {
+                ui.label(&faction.name);
+            }
+            ui.end_row();
+
+            for row in factions {
+                ui.label(&row.name);
+                for column in factions {
+                    if row.id == column.id {
+                        ui.label("—");
+                        continue;
+                    }
+                    let stance = row.stance_toward(column.id);
+                    let text = egui::RichText::new(stance.name()).color(stance_color(stance));
+                    if ui.small_button(text).clicked() {
+                        clicked = Some((row.id, column.id, next_stance(stance)));
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    });
+    clicked
+}
@@ -1,9 +1,18 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 use rb_core::AppMode;
-use rb_noise::{NoiseBackend, NoiseLayer};
-use rb_persistence::{list_worlds, load_world, save_world, world_path};
-use rb_world::WorldDefinition;
+use rb_noise::{
+    rainfall_to_rgba, ClimateZone, GenerationTimings, NoiseBackend, NoiseBasis, NoiseLayer,
+    Symmetry, WorldChunks, RAINFALL_LEGEND,
+};
+use rb_artifacts::{list_artifacts, load_layers, ArtifactManifest, ARTIFACTS_DIR};
+use rb_persistence::{
+    import_heightmap, list_sketches, list_worlds, load_world, save_world, world_path,
+    HeightmapFormat, WorldFormat, SKETCHES_DIR,
+};
+use rb_world::{Point2D, ViewState, WorldDefinition, WorldIdGenerator};
+
+use crate::undo::UndoStack;
 
 /// Current visualization layer for World Generator mode.
 #[derive(Resource)]
@@ -26,6 +35,8 @@ pub struct GeneratorUiState {
     pub show_load_dialog: bool,
     /// Available world files for loading.
     pub available_worlds: Vec<std::path::PathBuf>,
+    /// Format the Save World button writes.
+    pub save_format: WorldFormat,
     /// Status message to display.
     pub status_message: Option<(String, f64)>,
     /// Current layer for display (synced from CurrentLayer resource).
@@ -34,6 +45,23 @@ pub struct GeneratorUiState {
     pub layer_changed: Option<NoiseLayer>,
     /// Whether to use GPU for noise generation.
     pub use_gpu: bool,
+    /// Show artifact dialog.
+    pub show_artifact_dialog: bool,
+    /// Saved artifacts, for the artifact dialog.
+    pub available_artifacts: Vec<ArtifactManifest>,
+    /// Artifact save requested by UI (read by main.rs, which holds the maps).
+    pub artifact_requested: bool,
+    /// Show continent sketch import dialog.
+    pub show_sketch_dialog: bool,
+    /// Heightmaps available to sketch continents from.
+    pub available_sketches: Vec<std::path::PathBuf>,
+    /// Show heightmap export dialog.
+    pub show_export_dialog: bool,
+    /// Format and resolution picked in the export dialog.
+    pub export_format: HeightmapFormat,
+    pub export_resolution: HeightmapResolution,
+    /// Heightmap export requested by UI (read by main.rs, which holds the maps).
+    pub export_requested: Option<(HeightmapFormat, HeightmapResolution)>,
 }
 
 impl GeneratorUiState {
@@ -47,20 +75,64 @@ impl GeneratorUiState {
     }
 }
 
+/// Which maps a heightmap export covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeightmapResolution {
+    /// The whole world, one sample per macro cell.
+    #[default]
+    Macro,
+    /// Every meso tile currently generated, one file each.
+    Meso,
+}
+
+impl HeightmapResolution {
+    pub fn all() -> &'static [Self] {
+        &[Self::Macro, Self::Meso]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Macro => "Macro (whole world)",
+            Self::Meso => "Meso (loaded tiles)",
+        }
+    }
+}
+
 /// Resource for signaling world regeneration is needed.
 #[derive(Resource, Default)]
 pub struct RegenerationRequest {
     pub pending: bool,
+    /// Move the camera and layer to the world's saved [`ViewState`], if it
+    /// has one. Set when a world is loaded from disk.
+    pub restore_view: bool,
+}
+
+/// Time of year shown in the World Generator.
+///
+/// Changing it re-tints the climate of the macro map in place rather than
+/// requesting a regeneration.
+#[derive(Resource, Default)]
+pub struct WorldTime {
+    /// Fraction of the way through the year, in [0.0, 1.0).
+    pub season: f64,
 }
 
 /// System to render the World Generator UI panel.
+#[allow(clippy::too_many_arguments)]
 pub fn generator_ui_system(
     mut contexts: EguiContexts,
     mut world_def: ResMut<WorldDefinition>,
+    mut id_gen: ResMut<WorldIdGenerator>,
     mut ui_state: ResMut<GeneratorUiState>,
     mut regen_request: ResMut<RegenerationRequest>,
+    mut world_time: ResMut<WorldTime>,
     current_mode: Res<State<AppMode>>,
     mut next_mode: ResMut<NextState<AppMode>>,
+    mut world_chunks: ResMut<WorldChunks>,
+    timings: Option<Res<GenerationTimings>>,
+    current_layer: Res<CurrentLayer>,
+    camera: Query<(&Transform, &OrthographicProjection), With<Camera2d>>,
+    mut undo: ResMut<UndoStack>,
 ) {
     // Initialize seed text from world definition
     if !ui_state.initialized {
@@ -149,49 +221,249 @@ pub fn generator_ui_system(
             ui.collapsing("Noise Parameters", |ui| {
                 let params = &mut world_def.noise_params;
 
-                ui.label("Continentalness:");
-                let mut cont_octaves = params.continentalness_octaves as i32;
-                if ui.add(egui::Slider::new(&mut cont_octaves, 1..=24).text("Octaves")).changed() {
-                    params.continentalness_octaves = cont_octaves as u32;
+                for (name, layer) in params.layers.named_mut() {
+                    ui.collapsing(name, |ui| {
+                        let mut changed = false;
+                        changed |= ui.add(egui::Slider::new(&mut layer.scale, 0.25..=4.0).logarithmic(true).text("Scale")).changed();
+                        changed |= ui.add(egui::Slider::new(&mut layer.octaves, 1..=24).text("Octaves")).changed();
+                        changed |= ui.add(egui::Slider::new(&mut layer.persistence, 0.1..=0.9).text("Persistence")).changed();
+                        changed |= ui.add(egui::Slider::new(&mut layer.lacunarity, 1.5..=3.0).text("Lacunarity")).changed();
+                        changed |= ui.add(egui::Slider::new(&mut layer.amplitude, 0.0..=2.0).text("Amplitude")).changed();
+                        if changed {
+                            regen_request.pending = true;
+                        }
+                    });
+                }
+                if ui.button("Reset Layers").clicked() {
+                    params.layers = Default::default();
                     regen_request.pending = true;
                 }
 
-                if ui.add(egui::Slider::new(&mut params.continentalness_persistence, 0.1..=0.9).text("Persistence")).changed() {
+                ui.add_space(8.0);
+                ui.label("Hydraulic Erosion:");
+                if ui.checkbox(&mut params.hydraulic_erosion, "Enabled")
+                    .on_hover_text("Run water droplets over the terrain to carve valleys")
+                    .changed()
+                {
                     regen_request.pending = true;
                 }
+                ui.add_enabled_ui(params.hydraulic_erosion, |ui| {
+                    if ui.add(egui::Slider::new(&mut params.erosion_iterations, 1_000..=500_000).logarithmic(true).text("Droplets")).changed() {
+                        regen_request.pending = true;
+                    }
+                });
 
-                if ui.add(egui::Slider::new(&mut params.continentalness_lacunarity, 1.5..=3.0).text("Lacunarity")).changed() {
+                ui.add_space(8.0);
+                ui.label("Domain Warp:");
+                if ui.add(egui::Slider::new(&mut params.warp_amplitude, 0.0..=100.0).text("Amplitude"))
+                    .on_hover_text("Bend coastlines and ridges into swirls (0 = off)")
+                    .changed()
+                {
                     regen_request.pending = true;
                 }
+                ui.add_enabled_ui(params.warp_amplitude > 0.0, |ui| {
+                    if ui.add(egui::Slider::new(&mut params.warp_frequency, 0.001..=0.02).logarithmic(true).text("Frequency")).changed() {
+                        regen_request.pending = true;
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.label("Noise Basis:");
+                let bases = &mut params.bases;
+                for (label, basis) in [
+                    ("Continents", &mut bases.continentalness),
+                    ("Erosion", &mut bases.erosion),
+                    ("Peaks", &mut bases.peaks_valleys),
+                    ("Humidity", &mut bases.humidity),
+                ] {
+                    egui::ComboBox::from_label(label)
+                        .selected_text(basis.name())
+                        .show_ui(ui, |ui| {
+                            for &choice in NoiseBasis::all() {
+                                if ui.selectable_value(basis, choice, choice.name()).changed() {
+                                    regen_request.pending = true;
+                                }
+                            }
+                        });
+                }
 
                 ui.add_space(8.0);
-                ui.label("Temperature:");
-                let mut temp_octaves = params.temperature_octaves as i32;
-                if ui.add(egui::Slider::new(&mut temp_octaves, 1..=16).text("Octaves")).changed() {
-                    params.temperature_octaves = temp_octaves as u32;
+                ui.label("Caves:");
+                if ui.add(egui::Slider::new(&mut params.cave_depth, 1.0..=64.0).text("Slice Depth"))
+                    .on_hover_text("How far below the surface the Caves layer looks")
+                    .changed()
+                {
                     regen_request.pending = true;
                 }
 
-                if ui.add(egui::Slider::new(&mut params.temperature_persistence, 0.1..=0.9).text("Persistence")).changed() {
+                ui.add_space(8.0);
+                ui.label("Volcanoes:");
+                if ui.checkbox(&mut params.volcanoes, "Enabled")
+                    .on_hover_text("Raise volcanoes where plates collide and over hotspots; ash around them makes fertile soil")
+                    .changed()
+                {
                     regen_request.pending = true;
                 }
             });
             ui.add_space(8.0);
 
+            ui.collapsing("Continent Sketch", |ui| {
+                let sketch_name = world_def.sketch_path.as_deref()
+                    .and_then(|path| path.file_name())
+                    .map_or("None".into(), |name| name.to_string_lossy());
+                ui.label(format!("Sketch: {}", sketch_name));
+                if ui.button("Import Heightmap...")
+                    .on_hover_text(format!("Shape the continents from a grayscale PNG or EXR in {}", SKETCHES_DIR))
+                    .clicked()
+                {
+                    ui_state.show_sketch_dialog = true;
+                    ui_state.available_sketches = list_sketches().unwrap_or_default();
+                }
+                ui.add_enabled_ui(world_def.sketch.is_some(), |ui| {
+                    if ui.add(egui::Slider::new(&mut world_def.sketch_weight, 0.0..=1.0).text("Weight"))
+                        .on_hover_text("1 follows the sketch exactly; lower values blend it with the noise")
+                        .changed()
+                    {
+                        regen_request.pending = true;
+                    }
+                    if ui.button("Clear Sketch").clicked() {
+                        world_def.sketch_path = None;
+                        world_def.sketch = None;
+                        regen_request.pending = true;
+                    }
+                });
+            });
+            ui.add_space(8.0);
+
             // Sea level
             ui.collapsing("Climate", |ui| {
                 if ui.add(egui::Slider::new(&mut world_def.sea_level, -0.5..=0.5).text("Sea Level")).changed() {
                     regen_request.pending = true;
                 }
+
+                if ui.add(egui::Slider::new(&mut world_def.border_ocean, 0.0..=128.0).text("Edge Ocean"))
+                    .on_hover_text("Fade terrain into sea within this many pixels of the map edge")
+                    .changed()
+                {
+                    regen_request.pending = true;
+                }
+
+                egui::ComboBox::from_label("Symmetry")
+                    .selected_text(world_def.symmetry.name())
+                    .show_ui(ui, |ui| {
+                        for &symmetry in Symmetry::all() {
+                            if ui
+                                .selectable_value(&mut world_def.symmetry, symmetry, symmetry.name())
+                                .changed()
+                            {
+                                regen_request.pending = true;
+                            }
+                        }
+                    });
+
+                // Only commit on release: each change resamples the climate
+                let mut season = world_time.season;
+                let response = ui.add(egui::Slider::new(&mut season, 0.0..=1.0).text("Season"))
+                    .on_hover_text("Time of year: libration swings the climate zones back and forth");
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    world_time.season = season;
+                }
+            });
+            ui.add_space(8.0);
+
+            // Drag the breakpoints between biome classes; the map reclassifies live
+            ui.collapsing("Biome Thresholds", |ui| {
+                let thresholds = &mut world_def.biome_thresholds;
+                ui.label("Temperature (°C):");
+                breakpoint_bar(ui, "temperature", &mut thresholds.temperature, -80.0..=150.0, &[
+                    ("Frozen", egui::Color32::from_rgb(200, 225, 255)),
+                    ("Cold", egui::Color32::from_rgb(120, 170, 220)),
+                    ("Temperate", egui::Color32::from_rgb(110, 180, 110)),
+                    ("Warm", egui::Color32::from_rgb(220, 190, 90)),
+                    ("Hot", egui::Color32::from_rgb(225, 120, 60)),
+                    ("Scorching", egui::Color32::from_rgb(170, 40, 30)),
+                ]);
+                ui.label("Humidity:");
+                breakpoint_bar(ui, "humidity", &mut thresholds.humidity, 0.0..=1.0, &[
+                    ("Arid", egui::Color32::from_rgb(220, 200, 140)),
+                    ("Dry", egui::Color32::from_rgb(190, 190, 110)),
+                    ("Moderate", egui::Color32::from_rgb(120, 170, 90)),
+                    ("Wet", egui::Color32::from_rgb(60, 140, 110)),
+                    ("Saturated", egui::Color32::from_rgb(50, 100, 160)),
+                ]);
+                ui.label("Elevation above sea:");
+                breakpoint_bar(ui, "elevation", &mut thresholds.elevation, 0.0..=0.5, &[
+                    ("Coast", egui::Color32::from_rgb(230, 215, 160)),
+                    ("Low", egui::Color32::from_rgb(110, 170, 90)),
+                    ("Mid", egui::Color32::from_rgb(140, 150, 90)),
+                    ("High", egui::Color32::from_rgb(140, 120, 100)),
+                    ("Peak", egui::Color32::from_rgb(235, 235, 240)),
+                ]);
+                ui.label("Erosion:");
+                breakpoint_bar(ui, "erosion", &mut thresholds.erosion, 0.0..=1.0, &[
+                    ("Rugged", egui::Color32::from_rgb(130, 110, 100)),
+                    ("Normal", egui::Color32::from_rgb(120, 160, 100)),
+                    ("Flat", egui::Color32::from_rgb(170, 200, 120)),
+                ]);
+                if ui.button("Reset Thresholds").clicked() {
+                    *thresholds = Default::default();
+                }
             });
+            ui.add_space(8.0);
+
+            // Chunk cache limits (trade memory for streaming speed)
+            ui.collapsing("Chunk Cache", |ui| {
+                let mut config = world_chunks.cache_config().clone();
+                let mut changed = false;
+                changed |= ui.add(egui::Slider::new(&mut config.macro_cache_size, 1..=512).text("Macro")).changed();
+                changed |= ui.add(egui::Slider::new(&mut config.meso_cache_size, 1..=2048).text("Meso")).changed();
+                changed |= ui.add(egui::Slider::new(&mut config.micro_cache_size, 1..=8192).text("Micro")).changed();
+                if changed {
+                    world_chunks.set_cache_config(config);
+                }
+
+                let cont = world_chunks.continentalness_hierarchy.stats();
+                let temp = world_chunks.temperature_hierarchy.stats();
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Cached: {} macro, {} meso, {} micro",
+                        cont.macro_chunks + temp.macro_chunks,
+                        cont.meso_chunks + temp.meso_chunks,
+                        cont.micro_chunks + temp.micro_chunks,
+                    ))
+                    .small(),
+                );
+            });
+
+            if let Some(timings) = &timings {
+                ui.add_space(8.0);
+                ui.collapsing("Timings", |ui| {
+                    for phase in timings.phases() {
+                        ui.label(format!("{}: {:.0} ms", phase.name, phase.duration_ms));
+                    }
+                    ui.label(format!("Total: {:.0} ms", timings.total_ms()));
+                });
+            }
             ui.add_space(16.0);
 
             ui.separator();
 
             // Save/Load buttons
+            ui.label("Save as:");
+            for &format in WorldFormat::all() {
+                ui.radio_value(&mut ui_state.save_format, format, format.name());
+            }
             if ui.button("Save World").clicked() {
-                let path = world_path(&world_def.name);
-                match save_world(&path, &world_def) {
+                if let Ok((transform, projection)) = camera.get_single() {
+                    let center = transform.translation;
+                    world_def.view = Some(ViewState {
+                        camera: Point2D::new(center.x as f64, center.y as f64),
+                        zoom: projection.scale,
+                        layer: current_layer.0,
+                    });
+                }
+                let path = world_path(&world_def.name, ui_state.save_format);
+                match save_world(&path, &world_def, ui_state.save_format) {
                     Ok(()) => {
                         ui_state.status_message = Some((format!("Saved to {}", path.display()), 3.0));
                         println!("Saved world to {}", path.display());
@@ -208,6 +480,23 @@ pub fn generator_ui_system(
                 ui_state.available_worlds = list_worlds().unwrap_or_default();
             }
 
+            if ui.button("Save Artifact")
+                .on_hover_text(format!("Store the generated layers and world under {}", ARTIFACTS_DIR))
+                .clicked()
+            {
+                ui_state.artifact_requested = true;
+            }
+
+            if ui.button("Artifacts...").clicked() {
+                ui_state.show_artifact_dialog = true;
+                ui_state.available_artifacts =
+                    list_artifacts(std::path::Path::new(ARTIFACTS_DIR)).unwrap_or_default();
+            }
+
+            if ui.button("Export Heightmap...").clicked() {
+                ui_state.show_export_dialog = true;
+            }
+
             // Status message
             if let Some((msg, _)) = &ui_state.status_message {
                 ui.add_space(8.0);
@@ -229,10 +518,14 @@ pub fn generator_ui_system(
                     NoiseLayer::Erosion,
                     NoiseLayer::PeaksValleys,
                     NoiseLayer::Humidity,
+                    NoiseLayer::Caves,
                 ];
 
                 let derived_layers = [
                     NoiseLayer::Rivers,
+                    NoiseLayer::Rainfall,
+                    NoiseLayer::FlowAccumulation,
+                    NoiseLayer::Climate,
                 ];
 
                 let resource_layers = [
@@ -277,6 +570,23 @@ pub fn generator_ui_system(
                             }
                         }
                     });
+
+                if current == NoiseLayer::Rainfall {
+                    ui.horizontal(|ui| {
+                        for (mm, label) in RAINFALL_LEGEND {
+                            let [r, g, b, _] = rainfall_to_rgba(mm);
+                            ui.colored_label(egui::Color32::from_rgb(r, g, b), label);
+                        }
+                    });
+                }
+                if current == NoiseLayer::Climate {
+                    ui.horizontal_wrapped(|ui| {
+                        for zone in ClimateZone::all() {
+                            let [r, g, b, _] = zone.color();
+                            ui.colored_label(egui::Color32::from_rgb(r, g, b), zone.name());
+                        }
+                    });
+                }
             }
         });
 
@@ -297,8 +607,11 @@ pub fn generator_ui_system(
                         let name = path.file_stem()
                             .and_then(|s| s.to_str())
                             .unwrap_or("Unknown");
+                        let binary = path.extension().and_then(|s| s.to_str())
+                            == Some(WorldFormat::Binary.extension());
+                        let label = if binary { format!("{} (binary)", name) } else { name.to_string() };
 
-                        if ui.selectable_label(false, name).clicked() {
+                        if ui.selectable_label(false, label).clicked() {
                             load_path = Some(path.clone());
                             close_dialog = true;
                         }
@@ -323,8 +636,11 @@ pub fn generator_ui_system(
             match load_world(&path) {
                 Ok(loaded) => {
                     *world_def = loaded;
+                    *id_gen = WorldIdGenerator::from_world(&world_def);
+                    undo.clear();
                     ui_state.seed_text = world_def.seed.to_string();
                     regen_request.pending = true;
+                    regen_request.restore_view = true;
                     ui_state.status_message = Some((format!("Loaded {}", path.display()), 3.0));
                     println!("Loaded world from {}", path.display());
                 }
@@ -335,6 +651,248 @@ pub fn generator_ui_system(
             }
         }
     }
+
+    artifact_dialog(contexts.ctx_mut(), &mut ui_state, &mut world_def, &mut id_gen, &mut regen_request, &mut undo);
+    sketch_import_dialog(contexts.ctx_mut(), &mut ui_state, &mut world_def, &mut regen_request);
+    heightmap_export_dialog(contexts.ctx_mut(), &mut ui_state);
+}
+
+/// Artifact dialog: reopen a world saved with "Save Artifact", in this or an
+/// earlier session.
+fn artifact_dialog(
+    ctx: &egui::Context,
+    ui_state: &mut GeneratorUiState,
+    world_def: &mut WorldDefinition,
+    id_gen: &mut WorldIdGenerator,
+    regen_request: &mut RegenerationRequest,
+    undo: &mut UndoStack,
+) {
+    if !ui_state.show_artifact_dialog {
+        return;
+    }
+    let mut close_dialog = false;
+    let mut load_tag: Option<String> = None;
+
+    egui::Window::new("Artifacts")
+        .collapsible(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label("Select an artifact to open:");
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for manifest in &ui_state.available_artifacts {
+                    let label = format!(
+                        "{} (seed {}, {}×{})",
+                        manifest.tag, manifest.seed, manifest.width, manifest.height
+                    );
+                    if ui.selectable_label(false, label).clicked() {
+                        load_tag = Some(manifest.tag.clone());
+                        close_dialog = true;
+                    }
+                }
+
+                if ui_state.available_artifacts.is_empty() {
+                    ui.label("No saved artifacts found.");
+                }
+            });
+
+            ui.separator();
+            if ui.button("Cancel").clicked() {
+                close_dialog = true;
+            }
+        });
+
+    if close_dialog {
+        ui_state.show_artifact_dialog = false;
+    }
+
+    if let Some(tag) = load_tag {
+        match load_layers(std::path::Path::new(ARTIFACTS_DIR), &tag) {
+            Ok(artifact) => {
+                *world_def = artifact.world;
+                if let Some(path) = &world_def.sketch_path {
+                    world_def.sketch = import_heightmap(path).ok().map(std::sync::Arc::new);
+                }
+                *id_gen = WorldIdGenerator::from_world(world_def);
+                undo.clear();
+                ui_state.seed_text = world_def.seed.to_string();
+                regen_request.pending = true;
+                regen_request.restore_view = true;
+                ui_state.status_message = Some((format!("Opened artifact {}", tag), 3.0));
+                println!("Opened artifact {}", tag);
+            }
+            Err(e) => {
+                ui_state.status_message = Some((format!("Open failed: {}", e), 5.0));
+                eprintln!("Failed to open artifact {}: {}", tag, e);
+            }
+        }
+    }
+}
+
+/// Continent sketch dialog: pick a heightmap from [`SKETCHES_DIR`] to shape
+/// the continents from.
+fn sketch_import_dialog(
+    ctx: &egui::Context,
+    ui_state: &mut GeneratorUiState,
+    world_def: &mut WorldDefinition,
+    regen_request: &mut RegenerationRequest,
+) {
+    if !ui_state.show_sketch_dialog {
+        return;
+    }
+    let mut close_dialog = false;
+    let mut sketch_path: Option<std::path::PathBuf> = None;
+
+    egui::Window::new("Import Heightmap")
+        .collapsible(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label("Select a heightmap to sketch continents from:");
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                for path in &ui_state.available_sketches {
+                    let name = path.file_name()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("Unknown");
+
+                    if ui.selectable_label(false, name).clicked() {
+                        sketch_path = Some(path.clone());
+                        close_dialog = true;
+                    }
+                }
+
+                if ui_state.available_sketches.is_empty() {
+                    ui.label(format!("No PNG or EXR heightmaps in {}.", SKETCHES_DIR));
+                }
+            });
+
+            ui.separator();
+            if ui.button("Cancel").clicked() {
+                close_dialog = true;
+            }
+        });
+
+    if close_dialog {
+        ui_state.show_sketch_dialog = false;
+    }
+
+    if let Some(path) = sketch_path {
+        match import_heightmap(&path) {
+            Ok(sketch) => {
+                ui_state.status_message = Some((
+                    format!("Sketch {}×{} from {}", sketch.width(), sketch.height(), path.display()),
+                    3.0,
+                ));
+                world_def.sketch = Some(std::sync::Arc::new(sketch));
+                world_def.sketch_path = Some(path);
+                regen_request.pending = true;
+            }
+            Err(e) => {
+                ui_state.status_message = Some((format!("Import failed: {}", e), 5.0));
+                eprintln!("Failed to import heightmap: {}", e);
+            }
+        }
+    }
+}
+
+/// Heightmap export dialog: pick a format and resolution, then hand the
+/// request to main.rs.
+fn heightmap_export_dialog(ctx: &egui::Context, ui_state: &mut GeneratorUiState) {
+    if !ui_state.show_export_dialog {
+        return;
+    }
+    let mut close_dialog = false;
+
+    egui::Window::new("Export Heightmap")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label("Format:");
+            for &format in HeightmapFormat::all() {
+                ui.radio_value(&mut ui_state.export_format, format, format.name());
+            }
+            ui.add_space(8.0);
+            ui.label("Resolution:");
+            for &resolution in HeightmapResolution::all() {
+                ui.radio_value(&mut ui_state.export_resolution, resolution, resolution.name());
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Export").clicked() {
+                    ui_state.export_requested = Some((ui_state.export_format, ui_state.export_resolution));
+                    close_dialog = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close_dialog = true;
+                }
+            });
+        });
+
+    if close_dialog {
+        ui_state.show_export_dialog = false;
+    }
+}
+
+/// A horizontal bar split into coloured class bands at `breakpoints`, each of
+/// which can be dragged between its neighbours.
+fn breakpoint_bar(
+    ui: &mut egui::Ui,
+    id: &str,
+    breakpoints: &mut [f64],
+    range: std::ops::RangeInclusive<f64>,
+    classes: &[(&str, egui::Color32)],
+) {
+    const HANDLE_WIDTH: f32 = 8.0;
+    let (min, max) = (*range.start(), *range.end());
+    let size = egui::vec2(ui.available_width(), 24.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let to_x = |value: f64| {
+        rect.left() + ((value - min) / (max - min)).clamp(0.0, 1.0) as f32 * rect.width()
+    };
+
+    for i in 0..breakpoints.len() {
+        let handle = egui::Rect::from_center_size(
+            egui::pos2(to_x(breakpoints[i]), rect.center().y),
+            egui::vec2(HANDLE_WIDTH, rect.height()),
+        );
+        let response = ui
+            .interact(handle, ui.id().with(id).with(i), egui::Sense::drag())
+            .on_hover_cursor(egui::CursorIcon::ResizeHorizontal)
+            .on_hover_text(format!("{:.2}", breakpoints[i]));
+        if response.dragged() {
+            let lo = if i > 0 { breakpoints[i - 1] } else { min };
+            let hi = breakpoints.get(i + 1).copied().unwrap_or(max);
+            let step = response.drag_delta().x as f64 / rect.width() as f64 * (max - min);
+            breakpoints[i] = (breakpoints[i] + step).clamp(lo, hi);
+        }
+    }
+
+    let painter = ui.painter_at(rect);
+    let edges: Vec<f64> = std::iter::once(min)
+        .chain(breakpoints.iter().copied())
+        .chain(std::iter::once(max))
+        .collect();
+    for (bounds, &(name, colour)) in edges.windows(2).zip(classes) {
+        let band = egui::Rect::from_x_y_ranges(to_x(bounds[0])..=to_x(bounds[1]), rect.y_range());
+        painter.rect_filled(band, 0.0, colour);
+        let font = egui::FontId::proportional(10.0);
+        let galley = painter.layout_no_wrap(name.to_string(), font, egui::Color32::BLACK);
+        if galley.size().x < band.width() - HANDLE_WIDTH {
+            painter.galley(band.center() - galley.size() / 2.0, galley, egui::Color32::BLACK);
+        }
+    }
+    for (i, &value) in breakpoints.iter().enumerate() {
+        let active = ui.ctx().is_being_dragged(ui.id().with(id).with(i));
+        let stroke = if active {
+            egui::Stroke::new(3.0, egui::Color32::WHITE)
+        } else {
+            egui::Stroke::new(2.0, egui::Color32::DARK_GRAY)
+        };
+        painter.vline(to_x(value), rect.y_range(), stroke);
+    }
 }
 
 /// Format a KeyCode for display.
@@ -344,12 +902,13 @@ fn format_keycode(key: KeyCode) -> String {
         KeyCode::F2 => "F2".to_string(),
         KeyCode::F3 => "F3".to_string(),
         KeyCode::F4 => "F4".to_string(),
+        KeyCode::F5 => "F5".to_string(),
         _ => format!("{:?}", key),
     }
 }
 
 /// Generate a random seed.
-fn rand_seed() -> u32 {
+pub(crate) fn rand_seed() -> u32 {
     use std::time::{SystemTime, UNIX_EPOCH};
     let duration = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -3,16 +3,34 @@ use bevy_egui::EguiPlugin;
 use rb_core::AppMode;
 
 pub mod chunk_editor_ui;
+pub mod culture_panel;
+pub mod dynasty_panel;
+pub mod faction_panel;
 pub mod generator_ui;
 pub mod launcher_ui;
 pub mod map_editor_ui;
+pub mod seed_explorer_ui;
+pub mod undo;
 pub mod world_overlay;
 
 pub use chunk_editor_ui::{ChunkEditorState, ChunkTool};
-pub use generator_ui::{CurrentLayer, GeneratorUiState, RegenerationRequest};
+pub use culture_panel::CulturePanelState;
+pub use dynasty_panel::DynastyPanelState;
+pub use faction_panel::FactionPanelState;
+pub use generator_ui::{
+    CurrentLayer, GeneratorUiState, HeightmapResolution, RegenerationRequest, WorldTime,
+};
 pub use launcher_ui::LauncherState;
-pub use map_editor_ui::{CityPlacementState, EditorSelection, EditorTool, LandmarkPlacementState};
-pub use world_overlay::OverlaySettings;
+pub use map_editor_ui::{
+    BiomeBrushState, CityPlacementState, EditorSelection, EditorTool, LandmarkPlacementState,
+    RegionDrawState, RoadEditState, TerrainBrushState, TerritoryBrushMode, TerritoryBrushState,
+};
+pub use seed_explorer_ui::SeedExplorerState;
+pub use undo::{
+    Authored, Delete, Edit, EditCultures, EditorCommand, PaintTerritory, Place, SculptTerrain, SetRelation,
+    TerritoryCell, UndoStack,
+};
+pub use world_overlay::{OverlayLayer, OverlaySettings};
 
 /// Editor plugin for Randlebrot.
 /// Provides egui-based authoring tools and debug overlays.
@@ -29,28 +47,50 @@ impl Plugin for RbEditorPlugin {
             // Generator resources
             .init_resource::<GeneratorUiState>()
             .init_resource::<RegenerationRequest>()
+            .init_resource::<WorldTime>()
             // Map editor resources
             .init_resource::<EditorTool>()
             .init_resource::<EditorSelection>()
             .init_resource::<CityPlacementState>()
             .init_resource::<LandmarkPlacementState>()
+            .init_resource::<BiomeBrushState>()
+            .init_resource::<TerrainBrushState>()
+            .init_resource::<RoadEditState>()
+            .init_resource::<TerritoryBrushState>()
+            .init_resource::<RegionDrawState>()
+            .init_resource::<UndoStack>()
+            .init_resource::<FactionPanelState>()
+            .init_resource::<CulturePanelState>()
+            .init_resource::<DynastyPanelState>()
             .init_resource::<OverlaySettings>()
             // Chunk editor resources
             .init_resource::<ChunkTool>()
             .init_resource::<ChunkEditorState>()
             // Launcher resources
             .init_resource::<LauncherState>()
+            // Seed explorer resources
+            .init_resource::<SeedExplorerState>()
             // Generator UI (runs in all modes for the top bar)
             .add_systems(Update, generator_ui::generator_ui_system)
             // Map editor systems
             .add_systems(Update, (
                 map_editor_ui::map_editor_ui_system,
                 map_editor_ui::map_editor_click_system,
+                map_editor_ui::road_edit_system,
+                map_editor_ui::territory_brush_system,
+                faction_panel::faction_panel_system,
+                culture_panel::culture_panel_system,
+                dynasty_panel::dynasty_panel_system,
+                map_editor_ui::authored_terrain_regeneration_system,
+                undo::undo_shortcut_system,
             ).run_if(in_state(AppMode::WorldMapEditor)))
             // Overlay systems
             .add_systems(OnEnter(AppMode::WorldMapEditor), world_overlay::spawn_overlays)
             .add_systems(OnExit(AppMode::WorldMapEditor), world_overlay::despawn_overlays)
             .add_systems(Update, world_overlay::update_overlays.run_if(in_state(AppMode::WorldMapEditor)))
+            .add_systems(Update, world_overlay::draw_line_overlays.run_if(in_state(AppMode::WorldMapEditor)))
+            .add_systems(Update, world_overlay::sync_region_fills.run_if(in_state(AppMode::WorldMapEditor)))
+            .add_systems(Update, world_overlay::apply_overlay_visibility)
             // Chunk editor systems
             .add_systems(Update, (
                 chunk_editor_ui::chunk_editor_ui_system,
@@ -65,6 +105,10 @@ impl Plugin for RbEditorPlugin {
                 launcher_ui::player_movement_system,
                 launcher_ui::escape_to_stop_system,
             ).run_if(in_state(AppMode::LevelLauncher)))
-            .add_systems(OnExit(AppMode::LevelLauncher), launcher_ui::despawn_test_player);
+            .add_systems(OnExit(AppMode::LevelLauncher), launcher_ui::despawn_test_player)
+            // Seed explorer systems
+            .add_systems(Update, seed_explorer_ui::seed_explorer_ui_system
+                .after(generator_ui::generator_ui_system)
+                .run_if(in_state(AppMode::SeedExplorer)));
     }
 }
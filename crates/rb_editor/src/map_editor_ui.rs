@@ -1,8 +1,19 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::{egui, EguiContexts};
-use rb_core::AppMode;
-use rb_world::{City, CityTier, Landmark, LandmarkKind, Point2D, WorldDefinition, WorldIdGenerator};
+use rb_core::{rng_for, world_to_map, AppMode, TileType, PALETTE};
+use rb_noise::{BiomeOverride, BiomeStroke, TerrainBrush, TerrainBrushMode, TerrainEdits};
+use crate::culture_panel::CulturePanelState;
+use crate::dynasty_panel::DynastyPanelState;
+use crate::faction_panel::FactionPanelState;
+use crate::generator_ui::RegenerationRequest;
+use crate::undo::{Delete, Edit, Place, PaintTerritory, SculptTerrain, TerritoryCell, UndoStack};
+use crate::world_overlay::OverlaySettings;
+use rb_world::{
+    City, CityTier, CultureType, Economy, Landmark, LandmarkKind, NameData, NameGenerator, NameKind, Point2D, Polygon,
+    Region, Road, RoadType, TradeGood, WorldDefinition, WorldIdGenerator, LOCKED_INFLUENCE,
+};
+use std::collections::BTreeMap;
 
 /// Currently selected editor tool.
 #[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
@@ -12,6 +23,10 @@ pub enum EditorTool {
     PlaceCity,
     PlaceLandmark,
     DrawRegion,
+    PaintBiome,
+    SculptTerrain,
+    EditRoad,
+    PaintTerritory,
 }
 
 /// Currently selected object in the editor.
@@ -20,6 +35,7 @@ pub struct EditorSelection {
     pub city_id: Option<u32>,
     pub landmark_id: Option<u32>,
     pub region_id: Option<u32>,
+    pub road_id: Option<u32>,
 }
 
 /// State for city placement.
@@ -36,7 +52,222 @@ pub struct LandmarkPlacementState {
     pub kind: LandmarkKind,
 }
 
+/// Second click within this many seconds of the first closes a region.
+const DOUBLE_CLICK_SECS: f64 = 0.35;
+
+/// State for region drawing.
+#[derive(Resource)]
+pub struct RegionDrawState {
+    pub name: String,
+    pub color: [u8; 4],
+    /// Map positions clicked so far, in order.
+    pub vertices: Vec<Point2D>,
+    /// Map position under the cursor, where the preview line runs to.
+    pub cursor: Option<Point2D>,
+    /// The outline is closed and waiting on the name and colour dialog.
+    pub closing: bool,
+    /// When the last vertex was clicked, in app seconds.
+    last_click: f64,
+}
+
+impl Default for RegionDrawState {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            color: [100, 100, 200, 128],
+            vertices: Vec::new(),
+            cursor: None,
+            closing: false,
+            last_click: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl RegionDrawState {
+    /// Close the outline and open the dialog, if it has enough vertices to
+    /// make a polygon.
+    pub fn close(&mut self) {
+        self.closing = self.vertices.len() >= 3;
+    }
+
+    /// Drop the outline.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.closing = false;
+    }
+}
+
+/// Strokes kept for undo; older ones are dropped.
+pub const MAX_BRUSH_HISTORY: usize = 32;
+
+/// State for the biome brush.
+///
+/// The editor only holds the settings and undo history; painting itself is
+/// done by the app, which owns the macro `BiomeMap`.
+#[derive(Resource)]
+pub struct BiomeBrushState {
+    pub biome: TileType,
+    pub radius: usize,
+    /// Finished strokes, most recent last.
+    pub history: Vec<BiomeStroke>,
+    /// Set by the Undo button; the app reverts the last stroke and clears it.
+    pub undo_requested: bool,
+}
+
+impl Default for BiomeBrushState {
+    fn default() -> Self {
+        Self {
+            biome: TileType::Plains,
+            radius: 4,
+            history: Vec::new(),
+            undo_requested: false,
+        }
+    }
+}
+
+impl BiomeBrushState {
+    /// Record a finished stroke, dropping the oldest past [`MAX_BRUSH_HISTORY`].
+    pub fn push_stroke(&mut self, stroke: BiomeStroke) {
+        if stroke.is_empty() {
+            return;
+        }
+        if self.history.len() == MAX_BRUSH_HISTORY {
+            self.history.remove(0);
+        }
+        self.history.push(stroke);
+    }
+}
+
+/// State for the terrain brush.
+///
+/// Like the biome brush, the editor only holds the settings; the app sculpts
+/// the macro continentalness and commits each finished stroke to the world's
+/// terrain edits through the [`UndoStack`].
+#[derive(Resource)]
+pub struct TerrainBrushState {
+    pub mode: TerrainBrushMode,
+    /// Radius in macro cells.
+    pub radius: f64,
+    pub strength: f64,
+    pub falloff: f64,
+    /// Map position under the cursor, where the radius circle is drawn.
+    pub cursor: Option<Point2D>,
+}
+
+impl Default for TerrainBrushState {
+    fn default() -> Self {
+        Self {
+            mode: TerrainBrushMode::Raise,
+            radius: 8.0,
+            strength: 0.05,
+            falloff: 0.5,
+            cursor: None,
+        }
+    }
+}
+
+impl TerrainBrushState {
+    /// The brush the current settings describe.
+    pub fn brush(&self) -> TerrainBrush {
+        TerrainBrush {
+            mode: self.mode,
+            radius: self.radius,
+            strength: self.strength,
+            falloff: self.falloff,
+        }
+    }
+}
+
+/// How near, in map units, a click must be to a road or waypoint to pick it.
+const ROAD_PICK_DISTANCE: f64 = 4.0;
+
+/// State for road editing. The selected road is [`EditorSelection::road_id`].
+#[derive(Resource, Default)]
+pub struct RoadEditState {
+    /// Selected waypoint of the selected road.
+    pub waypoint: Option<usize>,
+    /// Waypoint being dragged, and its road from before the drag.
+    drag: Option<(usize, Road)>,
+    /// Cities picked for a new road.
+    pub from: Option<u32>,
+    pub to: Option<u32>,
+    /// Set by the Connect button; the app routes the road over its macro map
+    /// and clears it.
+    pub connect_requested: Option<(u32, u32)>,
+    /// Why the last connection failed, if it did.
+    pub status: Option<String>,
+}
+
+impl RoadEditState {
+    /// Whether a waypoint is being dragged, so the drag doesn't pan the map.
+    pub fn dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+}
+
+/// What the territory brush does to the cells under it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TerritoryBrushMode {
+    /// Give the cells to the faction and lock them there, so territory
+    /// generation keeps them.
+    #[default]
+    PaintLocked,
+    /// Give the cells to the faction until territories are next generated.
+    /// Locks are left as they are.
+    Paint,
+    /// Unlock the cells, leaving their owner to generation again.
+    Unlock,
+}
+
+impl TerritoryBrushMode {
+    pub fn all() -> &'static [Self] {
+        &[Self::PaintLocked, Self::Paint, Self::Unlock]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PaintLocked => "Paint & lock",
+            Self::Paint => "Paint",
+            Self::Unlock => "Unlock",
+        }
+    }
+}
+
+/// State for the territory brush.
+#[derive(Resource)]
+pub struct TerritoryBrushState {
+    pub mode: TerritoryBrushMode,
+    /// Faction painted, 0 for unclaimed land.
+    pub faction: u32,
+    /// Radius in macro cells.
+    pub radius: usize,
+    /// Map position under the cursor, where the radius circle is drawn.
+    pub cursor: Option<Point2D>,
+    /// Cells the stroke being painted has touched, as they were before it.
+    stroke: BTreeMap<(usize, usize), TerritoryCell>,
+}
+
+impl Default for TerritoryBrushState {
+    fn default() -> Self {
+        Self {
+            mode: TerritoryBrushMode::PaintLocked,
+            faction: 0,
+            radius: 4,
+            cursor: None,
+            stroke: BTreeMap::new(),
+        }
+    }
+}
+
+impl TerritoryBrushState {
+    /// Whether a stroke is being painted, so the drag doesn't pan the map.
+    pub fn painting(&self) -> bool {
+        !self.stroke.is_empty()
+    }
+}
+
 /// System to render the World Map Editor UI panel.
+#[allow(clippy::too_many_arguments)]
 pub fn map_editor_ui_system(
     mut contexts: EguiContexts,
     mut world_def: ResMut<WorldDefinition>,
@@ -44,6 +275,18 @@ pub fn map_editor_ui_system(
     mut selection: ResMut<EditorSelection>,
     mut city_state: ResMut<CityPlacementState>,
     mut landmark_state: ResMut<LandmarkPlacementState>,
+    mut brush: ResMut<BiomeBrushState>,
+    (mut terrain_brush, mut territory_brush): (ResMut<TerrainBrushState>, ResMut<TerritoryBrushState>),
+    mut road_edit: ResMut<RoadEditState>,
+    mut overlays: ResMut<OverlaySettings>,
+    (mut region_draw, names): (ResMut<RegionDrawState>, Res<NameData>),
+    mut undo: ResMut<UndoStack>,
+    mut id_gen: ResMut<WorldIdGenerator>,
+    (mut faction_panel, mut culture_panel, mut dynasty_panel): (
+        ResMut<FactionPanelState>,
+        ResMut<CulturePanelState>,
+        ResMut<DynastyPanelState>,
+    ),
     current_mode: Res<State<AppMode>>,
 ) {
     // Only show in World Map Editor mode
@@ -72,6 +315,30 @@ pub fn map_editor_ui_system(
                 if ui.selectable_label(*current_tool == EditorTool::DrawRegion, "Region").clicked() {
                     *current_tool = EditorTool::DrawRegion;
                 }
+                if ui.selectable_label(*current_tool == EditorTool::PaintBiome, "Biome").clicked() {
+                    *current_tool = EditorTool::PaintBiome;
+                }
+                if ui.selectable_label(*current_tool == EditorTool::SculptTerrain, "Terrain").clicked() {
+                    *current_tool = EditorTool::SculptTerrain;
+                }
+                if ui.selectable_label(*current_tool == EditorTool::EditRoad, "Road").clicked() {
+                    *current_tool = EditorTool::EditRoad;
+                }
+                if ui.selectable_label(*current_tool == EditorTool::PaintTerritory, "Territory").clicked() {
+                    *current_tool = EditorTool::PaintTerritory;
+                }
+            });
+            ui.horizontal(|ui| {
+                let undo_description = undo.undo_description();
+                let button = ui.add_enabled(undo_description.is_some(), egui::Button::new("⟲ Undo"));
+                if button.on_hover_text(undo_description.unwrap_or_default() + " (Ctrl+Z)").clicked() {
+                    undo.undo(&mut world_def);
+                }
+                let redo_description = undo.redo_description();
+                let button = ui.add_enabled(redo_description.is_some(), egui::Button::new("⟳ Redo"));
+                if button.on_hover_text(redo_description.unwrap_or_default() + " (Ctrl+Shift+Z)").clicked() {
+                    undo.redo(&mut world_def);
+                }
             });
             ui.add_space(8.0);
 
@@ -115,17 +382,82 @@ pub fn map_editor_ui_system(
                     ui.separator();
                     ui.label("Region drawing:");
                     ui.label("Click to add vertices");
-                    ui.label("Double-click to close");
+                    ui.label("Double-click or Enter to close");
+                    ui.label("Esc to start over");
                     ui.add_space(4.0);
-                    ui.label("(Not yet implemented)");
+                    ui.label(format!("{} vertices", region_draw.vertices.len()));
+                    ui.horizontal(|ui| {
+                        let closable = region_draw.vertices.len() >= 3 && !region_draw.closing;
+                        if ui.add_enabled(closable, egui::Button::new("Close")).clicked() {
+                            region_draw.close();
+                        }
+                        if ui.add_enabled(!region_draw.vertices.is_empty(), egui::Button::new("Clear")).clicked() {
+                            region_draw.clear();
+                        }
+                    });
+                }
+                EditorTool::PaintBiome => {
+                    ui.separator();
+                    ui.label("Biome brush:");
+                    egui::ComboBox::from_id_salt("brush_biome")
+                        .selected_text(brush.biome.name())
+                        .show_ui(ui, |ui| {
+                            for entry in &PALETTE {
+                                ui.horizontal(|ui| {
+                                    let [r, g, b] = entry.rgb;
+                                    ui.colored_label(egui::Color32::from_rgb(r, g, b), "■");
+                                    ui.selectable_value(&mut brush.biome, entry.tile, entry.name);
+                                });
+                            }
+                        });
+                    ui.add(egui::Slider::new(&mut brush.radius, 1..=32).text("Radius"));
+
+                    ui.add_space(4.0);
+                    ui.label("Click and drag to paint");
+                    let undo_label = format!("Undo ({})", brush.history.len());
+                    if ui.add_enabled(!brush.history.is_empty(), egui::Button::new(undo_label)).clicked() {
+                        brush.undo_requested = true;
+                    }
+                }
+                EditorTool::SculptTerrain => {
+                    ui.separator();
+                    ui.label("Terrain brush:");
+                    ui.horizontal_wrapped(|ui| {
+                        for &mode in TerrainBrushMode::all() {
+                            ui.selectable_value(&mut terrain_brush.mode, mode, mode.name());
+                        }
+                    });
+                    ui.add(egui::Slider::new(&mut terrain_brush.radius, 1.0..=64.0).text("Radius"));
+                    ui.add(egui::Slider::new(&mut terrain_brush.strength, 0.005..=0.5).logarithmic(true).text("Strength"));
+                    ui.add(egui::Slider::new(&mut terrain_brush.falloff, 0.0..=1.0).text("Falloff"));
+
+                    ui.add_space(4.0);
+                    ui.label("Click and drag to sculpt continentalness");
+                    if ui.add_enabled(!world_def.terrain_edits.is_empty(), egui::Button::new("Reset terrain")).clicked() {
+                        let before = world_def.terrain_edits.clone();
+                        if let Some(reset) = SculptTerrain::new(&before, &TerrainEdits::default()) {
+                            undo.execute(&mut world_def, reset);
+                        }
+                    }
+                }
+                EditorTool::EditRoad => {
+                    ui.separator();
+                    road_panel(ui, &mut world_def, &mut selection, &mut road_edit, &mut undo);
+                }
+                EditorTool::PaintTerritory => {
+                    ui.separator();
+                    territory_panel(ui, &mut world_def, &mut territory_brush, &mut undo);
                 }
                 EditorTool::Select => {
                     // Show selected object properties
                     if let Some(city_id) = selection.city_id {
+                        let mut delete = false;
+                        let faiths = faith_lines(&world_def, city_id);
                         if let Some(city) = world_def.cities.iter_mut().find(|c| c.id == city_id) {
+                            let before = city.clone();
                             ui.separator();
                             ui.label("Selected City:");
-                            ui.text_edit_singleline(&mut city.name);
+                            let name = ui.text_edit_singleline(&mut city.name);
 
                             egui::ComboBox::from_id_salt("edit_city_tier")
                                 .selected_text(city.tier.name())
@@ -134,20 +466,39 @@ pub fn map_editor_ui_system(
                                     ui.selectable_value(&mut city.tier, CityTier::Town, "Town");
                                     ui.selectable_value(&mut city.tier, CityTier::Village, "Village");
                                 });
+                            let (min, max) = city.tier.population_range();
+                            let population = ui.add(
+                                egui::DragValue::new(&mut city.population)
+                                    .range(min..=max)
+                                    .speed(max as f64 / 500.0)
+                                    .prefix("Population: "),
+                            );
+
+                            if *city != before {
+                                undo.record(Edit::new(before, city.clone(), name.has_focus() || population.dragged()));
+                            }
 
                             ui.label(format!("Position: ({:.0}, {:.0})", city.position.x, city.position.y));
+                            for line in faiths {
+                                ui.label(line);
+                            }
+                            economy_section(ui, &city.economy);
 
-                            if ui.button("Delete").clicked() {
-                                let id = city_id;
-                                world_def.cities.retain(|c| c.id != id);
-                                selection.city_id = None;
+                            delete = ui.button("Delete").clicked();
+                        }
+                        if delete {
+                            if let Some(command) = Delete::<City>::new(&mut world_def, city_id) {
+                                undo.execute(&mut world_def, command);
                             }
+                            selection.city_id = None;
                         }
                     } else if let Some(landmark_id) = selection.landmark_id {
+                        let mut delete = false;
                         if let Some(landmark) = world_def.landmarks.iter_mut().find(|l| l.id == landmark_id) {
+                            let before = landmark.clone();
                             ui.separator();
                             ui.label("Selected Landmark:");
-                            ui.text_edit_singleline(&mut landmark.name);
+                            let name = ui.text_edit_singleline(&mut landmark.name);
 
                             egui::ComboBox::from_id_salt("edit_landmark_kind")
                                 .selected_text(landmark.kind.name())
@@ -157,13 +508,60 @@ pub fn map_editor_ui_system(
                                     }
                                 });
 
+                            if *landmark != before {
+                                undo.record(Edit::new(before, landmark.clone(), name.has_focus()));
+                            }
+
                             ui.label(format!("Position: ({:.0}, {:.0})", landmark.position.x, landmark.position.y));
 
-                            if ui.button("Delete").clicked() {
-                                let id = landmark_id;
-                                world_def.landmarks.retain(|l| l.id != id);
-                                selection.landmark_id = None;
+                            delete = ui.button("Delete").clicked();
+                        }
+                        if delete {
+                            if let Some(command) = Delete::<Landmark>::new(&mut world_def, landmark_id) {
+                                undo.execute(&mut world_def, command);
                             }
+                            selection.landmark_id = None;
+                        }
+                    } else if let Some(region_id) = selection.region_id {
+                        let mut delete = false;
+                        if let Some(region) = world_def.regions.iter_mut().find(|r| r.id == region_id) {
+                            let before = region.clone();
+                            ui.separator();
+                            ui.label("Selected Region:");
+                            let name = ui.text_edit_singleline(&mut region.name);
+
+                            ui.label("Biome override:");
+                            egui::ComboBox::from_id_salt("edit_region_biome")
+                                .selected_text(region.biome_override.as_deref().unwrap_or("None"))
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut region.biome_override, None, "None");
+                                    for entry in &PALETTE {
+                                        ui.horizontal(|ui| {
+                                            let [r, g, b] = entry.rgb;
+                                            ui.colored_label(egui::Color32::from_rgb(r, g, b), "■");
+                                            let biome = Some(entry.name.to_string());
+                                            ui.selectable_value(&mut region.biome_override, biome, entry.name);
+                                        });
+                                    }
+                                });
+                            ui.horizontal(|ui| {
+                                ui.label("Colour:");
+                                ui.color_edit_button_srgba_unmultiplied(&mut region.color);
+                            });
+
+                            if *region != before {
+                                undo.record(Edit::new(before, region.clone(), name.has_focus()));
+                            }
+
+                            ui.label(format!("{} vertices", region.bounds.vertices.len()));
+
+                            delete = ui.button("Delete").clicked();
+                        }
+                        if delete {
+                            if let Some(command) = Delete::<Region>::new(&mut world_def, region_id) {
+                                undo.execute(&mut world_def, command);
+                            }
+                            selection.region_id = None;
                         }
                     } else {
                         ui.label("Click to select");
@@ -174,6 +572,19 @@ pub fn map_editor_ui_system(
             ui.add_space(16.0);
             ui.separator();
 
+            ui.collapsing("Overlays", |ui| {
+                ui.checkbox(&mut overlays.show_cities, "Cities");
+                ui.checkbox(&mut overlays.show_landmarks, "Landmarks");
+                ui.checkbox(&mut overlays.show_regions, "Regions");
+                ui.checkbox(&mut overlays.show_roads, "Roads");
+                ui.checkbox(&mut overlays.show_sea_routes, "Sea routes");
+                ui.checkbox(&mut overlays.show_territory, "Territory");
+                ui.checkbox(&mut overlays.show_religion, "Religion");
+                ui.checkbox(&mut overlays.show_resources, "Resources");
+                ui.checkbox(&mut overlays.show_trade_flow, "Trade flow");
+                ui.checkbox(&mut overlays.show_chunk_grid, "Chunk grid");
+            });
+
             // Object lists
             ui.collapsing(format!("Cities ({})", world_def.cities.len()), |ui| {
                 for city in &world_def.cities {
@@ -201,6 +612,18 @@ pub fn map_editor_ui_system(
                 }
             });
 
+            ui.collapsing(format!("Roads ({})", world_def.roads.len()), |ui| {
+                for road in &world_def.roads {
+                    let selected = selection.road_id == Some(road.id);
+                    let label = format!("Road {} ({})", road.id, road.road_type.name());
+                    if ui.selectable_label(selected, label).clicked() {
+                        selection.road_id = Some(road.id);
+                        road_edit.waypoint = None;
+                        *current_tool = EditorTool::EditRoad;
+                    }
+                }
+            });
+
             ui.collapsing(format!("Regions ({})", world_def.regions.len()), |ui| {
                 for region in &world_def.regions {
                     let selected = selection.region_id == Some(region.id);
@@ -212,10 +635,183 @@ pub fn map_editor_ui_system(
                     }
                 }
             });
+
+            let factions = format!("Factions ({})", world_def.factions.len());
+            ui.horizontal(|ui| {
+                ui.toggle_value(&mut faction_panel.open, factions);
+                let cultures = format!("Cultures ({})", world_def.cultures.len());
+                ui.toggle_value(&mut culture_panel.open, cultures);
+            });
+            let dynasties = format!("Dynasties ({})", world_def.dynasties.len());
+            ui.toggle_value(&mut dynasty_panel.open, dynasties);
         });
+
+    if region_draw.closing {
+        new_region_dialog(contexts.ctx_mut(), &mut region_draw, &names, &mut world_def, &mut id_gen, &mut undo);
+    }
+}
+
+/// Name and colour dialog for a closed region outline; creating it adds the
+/// region to the world.
+fn new_region_dialog(
+    ctx: &egui::Context,
+    region_draw: &mut RegionDrawState,
+    names: &NameData,
+    world_def: &mut WorldDefinition,
+    id_gen: &mut WorldIdGenerator,
+    undo: &mut UndoStack,
+) {
+    let mut create = false;
+    let mut back = false;
+
+    egui::Window::new("New Region")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!("{} vertices", region_draw.vertices.len()));
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut region_draw.name);
+                if ui.button("Suggest").clicked() {
+                    region_draw.name = suggest_region_name(world_def, names, region_draw);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Colour:");
+                ui.color_edit_button_srgba_unmultiplied(&mut region_draw.color);
+            });
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                create = ui.button("Create").clicked();
+                back = ui.button("Keep drawing").clicked();
+            });
+        });
+
+    if create {
+        let name = if region_draw.name.is_empty() {
+            suggest_region_name(world_def, names, region_draw)
+        } else {
+            std::mem::take(&mut region_draw.name)
+        };
+        let bounds = Polygon::new(std::mem::take(&mut region_draw.vertices));
+        let mut region = Region::new(id_gen.next_region_id(), name, bounds);
+        region.color = region_draw.color;
+        println!("Drew region {} with {} vertices", region.name, region.bounds.vertices.len());
+        undo.execute(world_def, Place(region));
+        region_draw.clear();
+    } else if back {
+        region_draw.closing = false;
+    }
+}
+
+/// A settlement's prosperity and the goods it makes and uses, with what it
+/// has to spare or bring in.
+fn economy_section(ui: &mut egui::Ui, economy: &Economy) {
+    if economy.production.is_empty() && economy.consumption.is_empty() {
+        return;
+    }
+    ui.collapsing(format!("Economy (prosperity {:.0}%)", economy.prosperity * 100.0), |ui| {
+        egui::Grid::new("city_economy").striped(true).show(ui, |ui| {
+            for heading in ["Good", "Made", "Used", "Balance"] {
+                ui.strong(heading);
+            }
+            ui.end_row();
+            let goods = economy.production.iter().chain(&economy.consumption).map(|(good, _)| *good);
+            let mut listed = Vec::new();
+            for good in goods {
+                if listed.contains(&good) {
+                    continue;
+                }
+                listed.push(good);
+                let amount = |list: &[(TradeGood, f32)]| list.iter().find(|(g, _)| *g == good).map_or(0.0, |(_, a)| *a);
+                let surplus = economy.surplus(good);
+                let colour = if surplus >= 0.0 {
+                    egui::Color32::from_rgb(110, 200, 110)
+                } else {
+                    egui::Color32::from_rgb(220, 90, 80)
+                };
+                ui.label(good.name());
+                ui.label(format!("{:.1}", amount(&economy.production)));
+                ui.label(format!("{:.1}", amount(&economy.consumption)));
+                ui.colored_label(colour, format!("{:+.1}", surplus));
+                ui.end_row();
+            }
+        });
+    });
+}
+
+/// A settlement's faiths as "Name: 40%" lines, largest first, then whoever
+/// keeps to none.
+fn faith_lines(world_def: &WorldDefinition, city_id: u32) -> Vec<String> {
+    let Some(city) = world_def.cities.iter().find(|c| c.id == city_id) else { return Vec::new() };
+    if city.faiths.is_empty() {
+        return Vec::new();
+    }
+    let mut lines: Vec<String> = city
+        .faiths
+        .iter()
+        .map(|a| {
+            let name = world_def.religions.iter().find(|r| r.id == a.religion).map_or("Unknown faith", |r| r.name.as_str());
+            format!("{}: {:.0}%", name, a.share * 100.0)
+        })
+        .collect();
+    let rest = 1.0 - city.faiths.iter().map(|a| a.share).sum::<f32>();
+    if rest >= 0.005 {
+        lines.push(format!("No faith: {:.0}%", rest * 100.0));
+    }
+    lines
+}
+
+/// A region name in the tongue of the faction holding the outline's centre
+/// (or the twilight folk's, on unclaimed land), unlike any in the world or
+/// the one already in the name box.
+fn suggest_region_name(world_def: &WorldDefinition, names: &NameData, region_draw: &RegionDrawState) -> String {
+    let vertices = &region_draw.vertices;
+    let count = vertices.len().max(1) as f64;
+    let x = vertices.iter().map(|v| v.x).sum::<f64>() / count;
+    let y = vertices.iter().map(|v| v.y).sum::<f64>() / count;
+    let owner = world_def.territory_cache.as_ref().map_or(0, |t| t.get_owner(x as usize, y as usize));
+    let culture = world_def
+        .factions
+        .iter()
+        .find(|f| f.id == owner)
+        .map_or(CultureType::TwilightDweller, |f| f.culture);
+
+    let mut generator = NameGenerator::new(names);
+    let regions = world_def.regions.iter().map(|r| &r.name);
+    let cities = world_def.cities.iter().map(|c| &c.name);
+    let factions = world_def.factions.iter().map(|f| &f.name);
+    for name in regions.chain(cities).chain(factions).chain([&region_draw.name]) {
+        generator.reserve(name);
+    }
+    // Seeded by the current name, so each click moves on to a new one
+    let mut rng = rng_for(world_def.seed, &format!("region_name:{}", region_draw.name));
+    generator.name(culture, NameKind::Region, &mut rng)
+}
+
+/// System to regenerate the world when the biomes regions force or the
+/// sculpted terrain change, whether edited, drawn, deleted or undone.
+pub fn authored_terrain_regeneration_system(
+    world_def: Res<WorldDefinition>,
+    mut regen_request: ResMut<RegenerationRequest>,
+    mut applied: Local<Option<(Vec<BiomeOverride>, TerrainEdits)>>,
+) {
+    if !world_def.is_changed() {
+        return;
+    }
+    let overrides = world_def.biome_overrides();
+    match applied.as_ref() {
+        // First run: the world was generated with these
+        None => {}
+        Some((previous, edits)) if *previous == overrides && *edits == world_def.terrain_edits => return,
+        Some(_) => regen_request.pending = true,
+    }
+    *applied = Some((overrides, world_def.terrain_edits.clone()));
 }
 
 /// System to handle mouse clicks for placing objects.
+#[allow(clippy::too_many_arguments)]
 pub fn map_editor_click_system(
     mouse: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window, With<PrimaryWindow>>,
@@ -226,6 +822,11 @@ pub fn map_editor_click_system(
     mut id_gen: ResMut<WorldIdGenerator>,
     city_state: Res<CityPlacementState>,
     landmark_state: Res<LandmarkPlacementState>,
+    mut region_draw: ResMut<RegionDrawState>,
+    mut terrain_brush: ResMut<TerrainBrushState>,
+    mut undo: ResMut<UndoStack>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
     mut contexts: EguiContexts,
 ) {
     // Only process in World Map Editor mode
@@ -233,6 +834,20 @@ pub fn map_editor_click_system(
         return;
     }
 
+    let cursor = cursor_map_position(&windows, &camera_query, &world_def);
+    terrain_brush.cursor = cursor.filter(|_| *current_tool == EditorTool::SculptTerrain);
+    let drawing = *current_tool == EditorTool::DrawRegion && !region_draw.closing;
+    if drawing {
+        region_draw.cursor = cursor;
+        if !contexts.ctx_mut().wants_keyboard_input() {
+            if keyboard.just_pressed(KeyCode::Enter) {
+                region_draw.close();
+            } else if keyboard.just_pressed(KeyCode::Escape) {
+                region_draw.clear();
+            }
+        }
+    }
+
     // Skip if clicking on egui
     if contexts.ctx_mut().is_pointer_over_area() {
         return;
@@ -242,24 +857,8 @@ pub fn map_editor_click_system(
         return;
     }
 
-    // Get cursor position
-    let Ok(window) = windows.get_single() else { return };
-    let Some(cursor_pos) = window.cursor_position() else { return };
-    let Ok((camera, camera_transform)) = camera_query.get_single() else { return };
-
-    // Convert screen to world coordinates
-    let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) else { return };
-
-    // Convert to map coordinates (map is centered at origin)
-    let map_x = world_pos.x + (world_def.width as f32 / 2.0);
-    let map_y = (world_def.height as f32 / 2.0) - world_pos.y; // Flip Y
-
-    // Check bounds
-    if map_x < 0.0 || map_x >= world_def.width as f32 || map_y < 0.0 || map_y >= world_def.height as f32 {
-        return;
-    }
-
-    let position = Point2D::new(map_x as f64, map_y as f64);
+    let Some(position) = cursor else { return };
+    let (map_x, map_y) = (position.x, position.y);
 
     match *current_tool {
         EditorTool::PlaceCity => {
@@ -270,7 +869,7 @@ pub fn map_editor_click_system(
             };
 
             let city = City::new(id_gen.next_city_id(), name, position, city_state.tier);
-            world_def.cities.push(city);
+            undo.execute(&mut world_def, Place(city));
             println!("Placed city at ({:.0}, {:.0})", map_x, map_y);
         }
         EditorTool::PlaceLandmark => {
@@ -281,9 +880,290 @@ pub fn map_editor_click_system(
             };
 
             let landmark = Landmark::new(id_gen.next_landmark_id(), name, position, landmark_state.kind);
-            world_def.landmarks.push(landmark);
+            undo.execute(&mut world_def, Place(landmark));
             println!("Placed landmark at ({:.0}, {:.0})", map_x, map_y);
         }
+        EditorTool::DrawRegion if drawing => {
+            // The first click of a double-click already placed the last vertex
+            let now = time.elapsed_secs_f64();
+            if now - region_draw.last_click < DOUBLE_CLICK_SECS {
+                region_draw.close();
+            } else {
+                region_draw.vertices.push(position);
+            }
+            region_draw.last_click = now;
+        }
         _ => {}
     }
 }
+
+/// Properties of the selected road, and the controls for connecting two
+/// cities with a new one.
+fn road_panel(
+    ui: &mut egui::Ui,
+    world_def: &mut WorldDefinition,
+    selection: &mut EditorSelection,
+    road_edit: &mut RoadEditState,
+    undo: &mut UndoStack,
+) {
+    let city_name = |world_def: &WorldDefinition, id: u32| {
+        world_def.cities.iter().find(|c| c.id == id).map_or_else(|| format!("#{}", id), |c| c.name.clone())
+    };
+
+    let mut delete = false;
+    if let Some(road) = selection.road_id.and_then(|id| world_def.roads.iter().find(|r| r.id == id)) {
+        let mut edited = road.clone();
+        ui.label(format!(
+            "Selected road: {} to {}",
+            city_name(world_def, road.connects.0),
+            city_name(world_def, road.connects.1)
+        ));
+        egui::ComboBox::from_id_salt("edit_road_type")
+            .selected_text(edited.road_type.name())
+            .show_ui(ui, |ui| {
+                for &road_type in RoadType::all() {
+                    ui.selectable_value(&mut edited.road_type, road_type, road_type.name());
+                }
+            });
+        ui.label(format!("{} waypoints, {:.0} long", road.waypoints.len(), road.length()));
+
+        let waypoint = road_edit.waypoint.filter(|&i| i < road.waypoints.len());
+        let can_remove = waypoint.is_some() && road.waypoints.len() > 2;
+        if ui.add_enabled(can_remove, egui::Button::new("Delete waypoint")).clicked() {
+            if let Some(i) = waypoint {
+                edited.waypoints.remove(i);
+                road_edit.waypoint = None;
+            }
+        }
+        delete = ui.button("Delete road").clicked();
+
+        if edited != *road {
+            let before = road.clone();
+            undo.execute(world_def, Edit::new(before, edited, false));
+        }
+    } else {
+        ui.label("Click a road to select it");
+    }
+    if delete {
+        if let Some(command) = selection.road_id.and_then(|id| Delete::<Road>::new(world_def, id)) {
+            undo.execute(world_def, command);
+        }
+        selection.road_id = None;
+        road_edit.waypoint = None;
+    }
+    ui.label("Drag waypoints to move them; Shift+click the road to add one");
+
+    ui.add_space(4.0);
+    ui.label("New road:");
+    for (salt, label, pick) in [("road_from", "From", &mut road_edit.from), ("road_to", "To", &mut road_edit.to)] {
+        let selected = pick.map_or_else(|| "Choose city".to_string(), |id| city_name(world_def, id));
+        egui::ComboBox::from_id_salt(salt)
+            .selected_text(format!("{}: {}", label, selected))
+            .show_ui(ui, |ui| {
+                for city in &world_def.cities {
+                    ui.selectable_value(pick, Some(city.id), &city.name);
+                }
+            });
+    }
+    let pair = road_edit.from.zip(road_edit.to).filter(|(from, to)| from != to);
+    if ui.add_enabled(pair.is_some(), egui::Button::new("Connect")).clicked() {
+        road_edit.connect_requested = pair;
+    }
+    if let Some(status) = &road_edit.status {
+        ui.colored_label(egui::Color32::from_rgb(220, 120, 100), status);
+    }
+}
+
+/// System for picking and reshaping roads with the road tool: click a road
+/// to select it, drag a waypoint of the selected road to move it, Shift+click
+/// the road to add a waypoint there and drag it.
+#[allow(clippy::too_many_arguments)]
+pub fn road_edit_system(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    current_tool: Res<EditorTool>,
+    mut world_def: ResMut<WorldDefinition>,
+    mut selection: ResMut<EditorSelection>,
+    mut road_edit: ResMut<RoadEditState>,
+    mut undo: ResMut<UndoStack>,
+    mut contexts: EguiContexts,
+) {
+    if *current_tool != EditorTool::EditRoad {
+        road_edit.drag = None;
+        return;
+    }
+    let cursor = cursor_map_position(&windows, &camera_query, &world_def);
+
+    // Move the dragged waypoint with the cursor; record the drag once let go
+    if let Some((index, before)) = road_edit.drag.take() {
+        let Some(road) = world_def.roads.iter_mut().find(|r| r.id == before.id) else {
+            return;
+        };
+        if mouse.pressed(MouseButton::Left) {
+            if let (Some(position), Some(waypoint)) = (cursor, road.waypoints.get_mut(index)) {
+                *waypoint = position;
+            }
+            road_edit.drag = Some((index, before));
+        } else if *road != before {
+            undo.record(Edit::new(before, road.clone(), false));
+        }
+        return;
+    }
+
+    if !mouse.just_pressed(MouseButton::Left) || contexts.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+    let Some(position) = cursor else { return };
+
+    if let Some(road) = selection.road_id.and_then(|id| world_def.roads.iter_mut().find(|r| r.id == id)) {
+        let before = road.clone();
+        if let Some(index) = road.nearest_waypoint(position, ROAD_PICK_DISTANCE) {
+            road_edit.waypoint = Some(index);
+            road_edit.drag = Some((index, before));
+            return;
+        }
+        let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        if let Some((segment, _)) = road.nearest_segment(position, ROAD_PICK_DISTANCE).filter(|_| shift) {
+            road.waypoints.insert(segment + 1, position);
+            road_edit.waypoint = Some(segment + 1);
+            road_edit.drag = Some((segment + 1, before));
+            return;
+        }
+    }
+
+    selection.road_id = world_def
+        .roads
+        .iter()
+        .filter_map(|road| Some((road.id, road.nearest_segment(position, ROAD_PICK_DISTANCE)?.1)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id);
+    road_edit.waypoint = None;
+}
+
+/// Options of the territory brush, and the locks painted so far.
+fn territory_panel(
+    ui: &mut egui::Ui,
+    world_def: &mut WorldDefinition,
+    brush: &mut TerritoryBrushState,
+    undo: &mut UndoStack,
+) {
+    ui.label("Territory brush:");
+    ui.horizontal_wrapped(|ui| {
+        for &mode in TerritoryBrushMode::all() {
+            ui.selectable_value(&mut brush.mode, mode, mode.name());
+        }
+    });
+    if brush.mode != TerritoryBrushMode::Unlock {
+        let faction_name = |id: u32| world_def.factions.iter().find(|f| f.id == id).map(|f| f.name.as_str());
+        egui::ComboBox::from_label("Faction")
+            .selected_text(faction_name(brush.faction).unwrap_or("Unclaimed"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut brush.faction, 0, "Unclaimed");
+                for faction in &world_def.factions {
+                    ui.horizontal(|ui| {
+                        let [r, g, b, _] = faction.color;
+                        ui.colored_label(egui::Color32::from_rgb(r, g, b), "■");
+                        ui.selectable_value(&mut brush.faction, faction.id, &faction.name);
+                    });
+                }
+            });
+    }
+    ui.add(egui::Slider::new(&mut brush.radius, 1..=32).text("Radius"));
+
+    ui.add_space(4.0);
+    ui.label("Click and drag to paint");
+    ui.label(format!("{} locked cells", world_def.territory_locks.len()));
+    if ui.add_enabled(!world_def.territory_locks.is_empty(), egui::Button::new("Unlock all")).clicked() {
+        let locked: Vec<_> = world_def.territory_locks.cells().map(|(cell, _)| cell).collect();
+        let before = locked.iter().map(|&(x, y)| TerritoryCell::read(world_def, x, y)).collect();
+        for (x, y) in locked {
+            world_def.territory_locks.set(x, y, None);
+        }
+        if let Some(command) = PaintTerritory::new(world_def, before) {
+            undo.record(command);
+        }
+    }
+}
+
+/// System to paint territory with the territory brush.
+///
+/// Cells change in place as the stroke is painted, so the territory overlay
+/// follows it; once the mouse is released the stroke is recorded for undo.
+#[allow(clippy::too_many_arguments)]
+pub fn territory_brush_system(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    current_tool: Res<EditorTool>,
+    mut world_def: ResMut<WorldDefinition>,
+    mut brush: ResMut<TerritoryBrushState>,
+    mut undo: ResMut<UndoStack>,
+    mut contexts: EguiContexts,
+) {
+    let active = *current_tool == EditorTool::PaintTerritory;
+    let cursor = cursor_map_position(&windows, &camera_query, &world_def).filter(|_| active);
+    brush.cursor = cursor;
+
+    if !active || !mouse.pressed(MouseButton::Left) {
+        if brush.painting() {
+            let before = std::mem::take(&mut brush.stroke).into_values().collect();
+            if let Some(command) = PaintTerritory::new(&world_def, before) {
+                undo.record(command);
+            }
+        }
+        return;
+    }
+    if !brush.painting() && contexts.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+    let Some(position) = cursor else { return };
+
+    let (cx, cy) = (position.x.floor() as i64, position.y.floor() as i64);
+    let radius = brush.radius as i64;
+    let (mode, faction) = (brush.mode, brush.faction);
+    let influence = if faction == 0 { 0.0 } else { LOCKED_INFLUENCE };
+    for y in (cy - radius).max(0)..=(cy + radius).min(world_def.height as i64 - 1) {
+        for x in (cx - radius).max(0)..=(cx + radius).min(world_def.width as i64 - 1) {
+            if (x - cx).pow(2) + (y - cy).pow(2) > radius * radius {
+                continue;
+            }
+            let (x, y) = (x as usize, y as usize);
+            brush.stroke.entry((x, y)).or_insert_with(|| TerritoryCell::read(&world_def, x, y));
+
+            let mut cell = TerritoryCell::read(&world_def, x, y);
+            match mode {
+                TerritoryBrushMode::PaintLocked => {
+                    (cell.owner, cell.influence) = (faction, influence);
+                    cell.lock = Some(faction);
+                }
+                TerritoryBrushMode::Paint => (cell.owner, cell.influence) = (faction, influence),
+                TerritoryBrushMode::Unlock => cell.lock = None,
+            }
+            cell.write(&mut world_def);
+        }
+    }
+}
+
+/// Map position under the cursor, if it is over the map.
+fn cursor_map_position(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform)>,
+    world_def: &WorldDefinition,
+) -> Option<Point2D> {
+    let cursor_pos = windows.get_single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = camera_query.get_single().ok()?;
+
+    // Convert screen to world coordinates
+    let world_pos = camera.viewport_to_world_2d(camera_transform, cursor_pos).ok()?;
+
+    let map_size = Vec2::new(world_def.width as f32, world_def.height as f32);
+    let Vec2 { x: map_x, y: map_y } = world_to_map(world_pos, map_size);
+
+    // Check bounds
+    if map_x < 0.0 || map_x >= world_def.width as f32 || map_y < 0.0 || map_y >= world_def.height as f32 {
+        return None;
+    }
+    Some(Point2D::new(map_x as f64, map_y as f64))
+}
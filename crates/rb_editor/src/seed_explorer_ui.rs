@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use rb_core::AppMode;
+use rb_world::WorldDefinition;
+
+use crate::generator_ui::{rand_seed, GeneratorUiState, RegenerationRequest};
+
+/// Width each thumbnail is drawn at, in points.
+const THUMBNAIL_DISPLAY_WIDTH: f32 = 192.0;
+
+/// State for the Seed Explorer.
+#[derive(Resource)]
+pub struct SeedExplorerState {
+    /// First seed of the page.
+    pub base_seed: u32,
+    /// Number of seeds on a page.
+    pub count: usize,
+    /// Thumbnails of the page, in seed order.
+    pub thumbnails: Vec<(u32, egui::TextureHandle)>,
+    /// A page of thumbnails was requested (read by main.rs, which holds the
+    /// generation settings).
+    pub explore_requested: bool,
+    /// Thumbnails are being generated.
+    pub generating: bool,
+    /// Whether `base_seed` has been taken from the current world yet.
+    pub initialized: bool,
+}
+
+impl Default for SeedExplorerState {
+    fn default() -> Self {
+        Self {
+            base_seed: 0,
+            count: 12,
+            thumbnails: Vec::new(),
+            explore_requested: false,
+            generating: false,
+            initialized: false,
+        }
+    }
+}
+
+impl SeedExplorerState {
+    /// Seeds on the current page.
+    pub fn seeds(&self) -> Vec<u32> {
+        (0..self.count as u32).map(|i| self.base_seed.wrapping_add(i)).collect()
+    }
+
+    /// Replace the page with generated `(seed, rgba)` thumbnails, each
+    /// `width` × `height`.
+    pub fn set_thumbnails(
+        &mut self,
+        ctx: &egui::Context,
+        width: usize,
+        height: usize,
+        images: Vec<(u32, Vec<u8>)>,
+    ) {
+        self.thumbnails = images
+            .into_iter()
+            .map(|(seed, rgba)| {
+                let image = egui::ColorImage::from_rgba_unmultiplied([width, height], &rgba);
+                let texture = ctx.load_texture(format!("seed_{}", seed), image, egui::TextureOptions::NEAREST);
+                (seed, texture)
+            })
+            .collect();
+        self.generating = false;
+    }
+}
+
+/// System to render the Seed Explorer: a page of seed thumbnails, any of
+/// which can be adopted and generated in full.
+pub fn seed_explorer_ui_system(
+    mut contexts: EguiContexts,
+    mut state: ResMut<SeedExplorerState>,
+    mut world_def: ResMut<WorldDefinition>,
+    mut ui_state: ResMut<GeneratorUiState>,
+    mut regen_request: ResMut<RegenerationRequest>,
+    mut next_mode: ResMut<NextState<AppMode>>,
+) {
+    if !state.initialized {
+        state.base_seed = world_def.seed;
+        state.explore_requested = true;
+        state.initialized = true;
+    }
+
+    let ctx = contexts.ctx_mut();
+    egui::SidePanel::left("seed_explorer_panel")
+        .default_width(180.0)
+        .show(ctx, |ui| {
+            ui.heading("Seed Explorer");
+            ui.separator();
+
+            ui.label("First seed:");
+            ui.add(egui::DragValue::new(&mut state.base_seed));
+            ui.label("Seeds per page:");
+            ui.add(egui::Slider::new(&mut state.count, 4..=32));
+            ui.add_space(8.0);
+
+            ui.add_enabled_ui(!state.generating, |ui| {
+                if ui.button("Explore").clicked() {
+                    state.explore_requested = true;
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("◀ Prev").clicked() {
+                        state.base_seed = state.base_seed.wrapping_sub(state.count as u32);
+                        state.explore_requested = true;
+                    }
+                    if ui.button("Next ▶").clicked() {
+                        state.base_seed = state.base_seed.wrapping_add(state.count as u32);
+                        state.explore_requested = true;
+                    }
+                });
+                if ui.button("🎲 Random page").clicked() {
+                    state.base_seed = rand_seed();
+                    state.explore_requested = true;
+                }
+            });
+
+            ui.add_space(16.0);
+            ui.separator();
+            ui.label(format!("Current seed: {}", world_def.seed));
+            ui.label("Click a thumbnail to generate it in the World Generator.");
+        });
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        if state.generating {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!("Generating {} thumbnails...", state.count));
+            });
+        }
+
+        let mut adopted = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (seed, texture) in &state.thumbnails {
+                    let [w, h] = texture.size();
+                    let size = egui::vec2(THUMBNAIL_DISPLAY_WIDTH, THUMBNAIL_DISPLAY_WIDTH * h as f32 / w as f32);
+                    ui.vertical(|ui| {
+                        let image = egui::load::SizedTexture::new(texture.id(), size);
+                        let button = ui.add(egui::ImageButton::new(image).selected(*seed == world_def.seed));
+                        if button.on_hover_text("Generate this seed").clicked() {
+                            adopted = Some(*seed);
+                        }
+                        ui.label(seed.to_string());
+                    });
+                }
+            });
+        });
+
+        if let Some(seed) = adopted {
+            world_def.seed = seed;
+            ui_state.seed_text = seed.to_string();
+            regen_request.pending = true;
+            next_mode.set(AppMode::WorldGenerator);
+        }
+    });
+}
@@ -0,0 +1,609 @@
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, VecDeque};
+
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use rb_noise::TerrainEdits;
+use rb_world::{City, Culture, Faction, Landmark, Region, Road, WorldDefinition};
+
+/// Commands kept for undo; older ones are dropped.
+pub const MAX_UNDO_HISTORY: usize = 100;
+
+/// A reversible edit to the world definition.
+pub trait EditorCommand: Any + Send + Sync {
+    /// Short description for the Undo/Redo buttons, e.g. "Delete Harbor".
+    fn description(&self) -> String;
+
+    /// Make the edit.
+    fn apply(&self, world: &mut WorldDefinition);
+
+    /// Take the edit back. Only called after [`apply`](Self::apply).
+    fn revert(&self, world: &mut WorldDefinition);
+
+    /// Fold `next`, made right after this command, into it so both undo
+    /// together. Returns whether it was absorbed.
+    fn absorb(&mut self, _next: &dyn EditorCommand) -> bool {
+        false
+    }
+}
+
+/// Authored entities the editor places, deletes and edits by id.
+pub trait Authored: Clone + PartialEq + Send + Sync + 'static {
+    /// What the entity is, e.g. "city".
+    const KIND: &'static str;
+
+    fn id(&self) -> u32;
+
+    fn name(&self) -> Cow<'_, str>;
+
+    /// The world's list of these entities.
+    fn list(world: &mut WorldDefinition) -> &mut Vec<Self>;
+}
+
+impl Authored for City {
+    const KIND: &'static str = "city";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.name)
+    }
+
+    fn list(world: &mut WorldDefinition) -> &mut Vec<Self> {
+        &mut world.cities
+    }
+}
+
+impl Authored for Landmark {
+    const KIND: &'static str = "landmark";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.name)
+    }
+
+    fn list(world: &mut WorldDefinition) -> &mut Vec<Self> {
+        &mut world.landmarks
+    }
+}
+
+impl Authored for Region {
+    const KIND: &'static str = "region";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.name)
+    }
+
+    fn list(world: &mut WorldDefinition) -> &mut Vec<Self> {
+        &mut world.regions
+    }
+}
+
+impl Authored for Road {
+    const KIND: &'static str = "road";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Roads have no name of their own, so they go by id.
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Owned(format!("Road {}", self.id))
+    }
+
+    fn list(world: &mut WorldDefinition) -> &mut Vec<Self> {
+        &mut world.roads
+    }
+}
+
+impl Authored for Faction {
+    const KIND: &'static str = "faction";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.name)
+    }
+
+    fn list(world: &mut WorldDefinition) -> &mut Vec<Self> {
+        &mut world.factions
+    }
+}
+
+/// Replace the entity with `entity`'s id, or insert it at `index` (clamped)
+/// if there is none.
+fn upsert<T: Authored>(world: &mut WorldDefinition, entity: &T, index: usize) {
+    let list = T::list(world);
+    match list.iter_mut().find(|e| e.id() == entity.id()) {
+        Some(existing) => *existing = entity.clone(),
+        None => list.insert(index.min(list.len()), entity.clone()),
+    }
+}
+
+/// Add a new entity: a placed city or landmark, or a drawn region.
+pub struct Place<T>(pub T);
+
+impl<T: Authored> EditorCommand for Place<T> {
+    fn description(&self) -> String {
+        format!("Place {}", self.0.name())
+    }
+
+    fn apply(&self, world: &mut WorldDefinition) {
+        upsert(world, &self.0, usize::MAX);
+    }
+
+    fn revert(&self, world: &mut WorldDefinition) {
+        T::list(world).retain(|e| e.id() != self.0.id());
+    }
+}
+
+/// Delete an entity, restoring it in the same list position on undo.
+pub struct Delete<T> {
+    entity: T,
+    index: usize,
+}
+
+impl<T: Authored> Delete<T> {
+    /// A command deleting the entity `id` of `world`, or `None` if there is
+    /// no such entity.
+    pub fn new(world: &mut WorldDefinition, id: u32) -> Option<Self> {
+        let index = T::list(world).iter().position(|e| e.id() == id)?;
+        Some(Self {
+            entity: T::list(world)[index].clone(),
+            index,
+        })
+    }
+}
+
+impl<T: Authored> EditorCommand for Delete<T> {
+    fn description(&self) -> String {
+        format!("Delete {}", self.entity.name())
+    }
+
+    fn apply(&self, world: &mut WorldDefinition) {
+        T::list(world).retain(|e| e.id() != self.entity.id());
+    }
+
+    fn revert(&self, world: &mut WorldDefinition) {
+        upsert(world, &self.entity, self.index);
+    }
+}
+
+/// Change an entity's properties.
+pub struct Edit<T> {
+    before: T,
+    after: T,
+    /// Still being edited (e.g. its name is being typed): later edits to the
+    /// same entity fold into this one.
+    open: bool,
+}
+
+impl<T: Authored> Edit<T> {
+    /// An edit from `before` to `after`. `open` edits absorb the next edit
+    /// of the same entity, so typing a name undoes as one step.
+    pub fn new(before: T, after: T, open: bool) -> Self {
+        Self { before, after, open }
+    }
+}
+
+impl<T: Authored> EditorCommand for Edit<T> {
+    fn description(&self) -> String {
+        format!("Edit {} {}", T::KIND, self.after.name())
+    }
+
+    fn apply(&self, world: &mut WorldDefinition) {
+        upsert(world, &self.after, usize::MAX);
+    }
+
+    fn revert(&self, world: &mut WorldDefinition) {
+        upsert(world, &self.before, usize::MAX);
+    }
+
+    fn absorb(&mut self, next: &dyn EditorCommand) -> bool {
+        let next = next as &dyn Any;
+        match next.downcast_ref::<Self>() {
+            Some(next) if self.open && next.before.id() == self.after.id() => {
+                self.after = next.after.clone();
+                self.open = next.open;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Set how two factions stand toward each other. A relation is kept on both
+/// factions, so both sides are set and restored together.
+pub struct SetRelation {
+    factions: (u32, u32),
+    names: (String, String),
+    /// Each side's stored relation before, `None` if it had none.
+    before: (Option<f64>, Option<f64>),
+    after: f64,
+}
+
+impl SetRelation {
+    /// A command setting the relation between factions `a` and `b` of
+    /// `world` to `value`, or `None` if either is missing.
+    pub fn new(world: &WorldDefinition, a: u32, b: u32, value: f64) -> Option<Self> {
+        let faction = |id| world.factions.iter().find(|f| f.id == id);
+        let (first, second) = (faction(a)?, faction(b)?);
+        Some(Self {
+            factions: (a, b),
+            names: (first.name.clone(), second.name.clone()),
+            before: (first.relations.get(&b).copied(), second.relations.get(&a).copied()),
+            after: value,
+        })
+    }
+
+    fn set(world: &mut WorldDefinition, of: u32, toward: u32, value: Option<f64>) {
+        if let Some(faction) = world.factions.iter_mut().find(|f| f.id == of) {
+            match value {
+                Some(value) => faction.set_relation(toward, value),
+                None => {
+                    faction.relations.remove(&toward);
+                }
+            }
+        }
+    }
+}
+
+impl EditorCommand for SetRelation {
+    fn description(&self) -> String {
+        format!("Set relations of {} and {}", self.names.0, self.names.1)
+    }
+
+    fn apply(&self, world: &mut WorldDefinition) {
+        let (a, b) = self.factions;
+        Self::set(world, a, b, Some(self.after));
+        Self::set(world, b, a, Some(self.after));
+    }
+
+    fn revert(&self, world: &mut WorldDefinition) {
+        let (a, b) = self.factions;
+        Self::set(world, a, b, self.before.0);
+        Self::set(world, b, a, self.before.1);
+    }
+}
+
+/// A terrain brush stroke, kept as just the edit blocks it changed.
+pub struct SculptTerrain {
+    chunks: BTreeSet<(u32, u32)>,
+    before: TerrainEdits,
+    after: TerrainEdits,
+}
+
+impl SculptTerrain {
+    /// A command taking the world's terrain edits from `before` to `after`,
+    /// or `None` if they are the same.
+    pub fn new(before: &TerrainEdits, after: &TerrainEdits) -> Option<Self> {
+        let chunks = after.changed_chunks(before);
+        if chunks.is_empty() {
+            return None;
+        }
+        Some(Self {
+            before: before.subset(&chunks),
+            after: after.subset(&chunks),
+            chunks,
+        })
+    }
+}
+
+impl EditorCommand for SculptTerrain {
+    fn description(&self) -> String {
+        "Sculpt terrain".to_string()
+    }
+
+    fn apply(&self, world: &mut WorldDefinition) {
+        world.terrain_edits.splice(&self.after, &self.chunks);
+    }
+
+    fn revert(&self, world: &mut WorldDefinition) {
+        world.terrain_edits.splice(&self.before, &self.chunks);
+    }
+}
+
+/// Replace the world's cultures. They are few and have no ids, so the whole
+/// list is kept either side.
+pub struct EditCultures {
+    description: String,
+    before: Vec<Culture>,
+    after: Vec<Culture>,
+    /// Still being edited: the next change to the cultures folds into this.
+    open: bool,
+}
+
+impl EditCultures {
+    pub fn new(description: impl Into<String>, before: Vec<Culture>, after: Vec<Culture>, open: bool) -> Self {
+        Self { description: description.into(), before, after, open }
+    }
+}
+
+impl EditorCommand for EditCultures {
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn apply(&self, world: &mut WorldDefinition) {
+        world.cultures = self.after.clone();
+    }
+
+    fn revert(&self, world: &mut WorldDefinition) {
+        world.cultures = self.before.clone();
+    }
+
+    fn absorb(&mut self, next: &dyn EditorCommand) -> bool {
+        let next = next as &dyn Any;
+        match next.downcast_ref::<Self>() {
+            Some(next) if self.open => {
+                self.after = next.after.clone();
+                self.open = next.open;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// One cell's territory, as a territory brush stroke found or left it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerritoryCell {
+    pub x: usize,
+    pub y: usize,
+    pub owner: u32,
+    pub influence: f64,
+    /// Owner the cell is locked to, if it is locked.
+    pub lock: Option<u32>,
+}
+
+impl TerritoryCell {
+    /// Cell `(x, y)` of `world` as it is now.
+    pub fn read(world: &WorldDefinition, x: usize, y: usize) -> Self {
+        let territory = world.territory_cache.as_ref();
+        Self {
+            x,
+            y,
+            owner: territory.map_or(0, |t| t.get_owner(x, y)),
+            influence: territory.map_or(0.0, |t| t.get_influence(x, y)),
+            lock: world.territory_locks.get(x, y),
+        }
+    }
+
+    /// Put the cell into `world` as it was read.
+    pub fn write(&self, world: &mut WorldDefinition) {
+        if let Some(territory) = world.territory_cache.as_mut() {
+            territory.set(self.x, self.y, self.owner, self.influence);
+        }
+        world.territory_locks.set(self.x, self.y, self.lock);
+    }
+}
+
+/// A territory brush stroke, kept as just the cells it touched.
+pub struct PaintTerritory {
+    before: Vec<TerritoryCell>,
+    after: Vec<TerritoryCell>,
+}
+
+impl PaintTerritory {
+    /// A command for a stroke already painted onto `world`, from the cells it
+    /// touched as they were before. `None` if it changed nothing.
+    pub fn new(world: &WorldDefinition, before: Vec<TerritoryCell>) -> Option<Self> {
+        let after: Vec<_> = before.iter().map(|cell| TerritoryCell::read(world, cell.x, cell.y)).collect();
+        (after != before).then_some(Self { before, after })
+    }
+}
+
+impl EditorCommand for PaintTerritory {
+    fn description(&self) -> String {
+        "Paint territory".to_string()
+    }
+
+    fn apply(&self, world: &mut WorldDefinition) {
+        self.after.iter().for_each(|cell| cell.write(world));
+    }
+
+    fn revert(&self, world: &mut WorldDefinition) {
+        self.before.iter().for_each(|cell| cell.write(world));
+    }
+}
+
+/// Bounded history of editor commands, for undo and redo.
+#[derive(Resource)]
+pub struct UndoStack {
+    done: VecDeque<Box<dyn EditorCommand>>,
+    undone: Vec<Box<dyn EditorCommand>>,
+    capacity: usize,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::with_capacity(MAX_UNDO_HISTORY)
+    }
+}
+
+impl UndoStack {
+    /// A stack keeping the last `capacity` commands.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            done: VecDeque::new(),
+            undone: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Apply `command` to `world` and record it.
+    pub fn execute(&mut self, world: &mut WorldDefinition, command: impl EditorCommand) {
+        command.apply(world);
+        self.record(command);
+    }
+
+    /// Record a command whose edit has already been made, e.g. by a widget
+    /// bound to the world. Clears the redo history.
+    pub fn record(&mut self, command: impl EditorCommand) {
+        self.undone.clear();
+        if let Some(last) = self.done.back_mut() {
+            if last.absorb(&command) {
+                return;
+            }
+        }
+        if self.done.len() == self.capacity {
+            self.done.pop_front();
+        }
+        self.done.push_back(Box::new(command));
+    }
+
+    /// Revert the last command. Returns its description.
+    pub fn undo(&mut self, world: &mut WorldDefinition) -> Option<String> {
+        let command = self.done.pop_back()?;
+        command.revert(world);
+        let description = command.description();
+        self.undone.push(command);
+        Some(description)
+    }
+
+    /// Reapply the last undone command. Returns its description.
+    pub fn redo(&mut self, world: &mut WorldDefinition) -> Option<String> {
+        let command = self.undone.pop()?;
+        command.apply(world);
+        let description = command.description();
+        self.done.push_back(command);
+        Some(description)
+    }
+
+    /// Description of what Undo would revert.
+    pub fn undo_description(&self) -> Option<String> {
+        self.done.back().map(|c| c.description())
+    }
+
+    /// Description of what Redo would reapply.
+    pub fn redo_description(&self) -> Option<String> {
+        self.undone.last().map(|c| c.description())
+    }
+
+    /// Forget all history, e.g. when the world is replaced.
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+/// System for Ctrl+Z (undo) and Ctrl+Shift+Z (redo) in the map editor.
+/// Left to egui while a text field has focus, which has its own undo.
+pub fn undo_shortcut_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut contexts: EguiContexts,
+    mut undo: ResMut<UndoStack>,
+    mut world_def: ResMut<WorldDefinition>,
+) {
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl || !keyboard.just_pressed(KeyCode::KeyZ) || contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if shift {
+        if let Some(description) = undo.redo(&mut world_def) {
+            println!("Redo: {}", description);
+        }
+    } else if let Some(description) = undo.undo(&mut world_def) {
+        println!("Undo: {}", description);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_world::{CityTier, CultureType, Point2D, Stance, TerritoryMap};
+
+    #[test]
+    fn commands_undo_and_redo_in_order() {
+        let mut world = WorldDefinition::default();
+        let mut undo = UndoStack::with_capacity(3);
+        let capital = City::new(1, "Capital".into(), Point2D::new(10.0, 10.0), CityTier::Capital);
+        let port = City::new(2, "Port".into(), Point2D::new(20.0, 10.0), CityTier::Town);
+        undo.execute(&mut world, Place(capital.clone()));
+        undo.execute(&mut world, Place(port.clone()));
+
+        // Typing a name folds into one edit until the field is left
+        for (name, open) in [("C", true), ("Cr", true), ("Crown", false)] {
+            let before = world.cities[0].clone();
+            world.cities[0].name = name.into();
+            undo.record(Edit::new(before, world.cities[0].clone(), open));
+        }
+        assert_eq!(undo.undo_description().as_deref(), Some("Edit city Crown"));
+
+        // A misclicked delete comes back where it was
+        let delete = Delete::<City>::new(&mut world, 1).unwrap();
+        undo.execute(&mut world, delete);
+        assert_eq!(world.cities, std::slice::from_ref(&port));
+        assert_eq!(undo.undo(&mut world).as_deref(), Some("Delete Crown"));
+        assert_eq!(world.cities[0].name, "Crown");
+        assert_eq!(undo.undo(&mut world).as_deref(), Some("Edit city Crown"));
+        assert_eq!(world.cities, [capital.clone(), port]);
+
+        assert_eq!(undo.redo(&mut world).as_deref(), Some("Edit city Crown"));
+        assert_eq!(world.cities[0].name, "Crown");
+
+        // A new command drops the redo history; the oldest falls off the end
+        undo.execute(&mut world, Place(Landmark::new(1, "Spire".into(), Point2D::new(5.0, 5.0), Default::default())));
+        assert!(undo.redo_description().is_none());
+        while undo.undo(&mut world).is_some() {}
+        assert_eq!(world.cities, [capital]);
+        assert!(world.landmarks.is_empty());
+    }
+
+    #[test]
+    fn relations_change_on_both_sides() {
+        let mut world = WorldDefinition::default();
+        let mut north = Faction::new(1, "North".into(), CultureType::FrostKin);
+        north.set_relation(2, 0.2);
+        world.factions = vec![north, Faction::new(2, "South".into(), CultureType::SunForged)];
+        let original = world.factions.clone();
+        let mut undo = UndoStack::default();
+
+        let war = SetRelation::new(&world, 2, 1, Stance::Hostile.relation()).unwrap();
+        undo.execute(&mut world, war);
+        assert!(world.factions.iter().all(|f| f.stance_toward(3 - f.id) == Stance::Hostile));
+        assert_eq!(undo.undo(&mut world).as_deref(), Some("Set relations of South and North"));
+        assert_eq!(world.factions, original);
+        assert!(SetRelation::new(&world, 1, 9, 0.0).is_none());
+    }
+
+    #[test]
+    fn territory_strokes_restore_owners_and_locks() {
+        let mut world = WorldDefinition::default();
+        let mut territory = TerritoryMap::new(4, 4);
+        territory.set(1, 1, 1, 0.9);
+        world.territory_cache = Some(territory);
+        let original = world.clone();
+
+        let before: Vec<_> = [(1, 1), (2, 1)].map(|(x, y)| TerritoryCell::read(&world, x, y)).into();
+        for cell in &before {
+            let painted = TerritoryCell { owner: 2, influence: 0.5, lock: Some(2), ..*cell };
+            painted.write(&mut world);
+        }
+        let mut undo = UndoStack::default();
+        undo.record(PaintTerritory::new(&world, before).unwrap());
+        let painted = world.clone();
+
+        undo.undo(&mut world);
+        assert_eq!(world.territory_cache, original.territory_cache);
+        assert!(world.territory_locks.is_empty());
+        undo.redo(&mut world);
+        assert_eq!(world.territory_locks, painted.territory_locks);
+        assert_eq!(world.territory_cache.as_ref().unwrap().get_owner(2, 1), 2);
+        assert!(PaintTerritory::new(&world, vec![TerritoryCell::read(&world, 0, 0)]).is_none());
+    }
+}
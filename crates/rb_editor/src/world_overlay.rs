@@ -1,5 +1,34 @@
 use bevy::prelude::*;
-use rb_world::{CityTier, LandmarkKind, WorldDefinition};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use rb_core::{map_to_world, AppMode};
+use rb_world::{road_volumes, City, CityTier, LandmarkKind, Point2D, Region, WorldDefinition};
+
+use crate::map_editor_ui::{
+    EditorSelection, EditorTool, RegionDrawState, RoadEditState, TerrainBrushState, TerritoryBrushState,
+};
+
+/// Spacing of the chunk grid, matching the world map's meso tiles.
+const GRID_SPACING: f32 = 64.0;
+
+/// Depth of region fills: above the territory and resource images, below
+/// the markers.
+const REGION_FILL_Z: f32 = 0.4;
+
+/// Most parallel strands a road is drawn with on the trade flow overlay,
+/// for the busiest road in the world.
+const MAX_FLOW_STRANDS: usize = 5;
+
+/// World units between the strands of a busy road.
+const FLOW_STRAND_SPACING: f32 = 0.75;
+
+/// Length of each dash, and each gap, of a drawn sea lane.
+const SEA_LANE_DASH: f32 = 3.0;
+
+/// World-space position of a map-space point.
+fn world_position(world_def: &WorldDefinition, position: Point2D) -> Vec2 {
+    let map_size = Vec2::new(world_def.width as f32, world_def.height as f32);
+    map_to_world(Vec2::new(position.x as f32, position.y as f32), map_size)
+}
 
 /// Marker component for city sprites.
 #[derive(Component)]
@@ -19,12 +48,32 @@ pub struct RegionBoundary {
     pub region_id: u32,
 }
 
+/// Which overlay an entity belongs to, for toggling its visibility.
+///
+/// Markers spawned here carry one, as do the full-map territory, religion and
+/// resource images the app draws over the world map.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OverlayLayer {
+    Cities,
+    Landmarks,
+    Regions,
+    Territory,
+    Religion,
+    Resources,
+}
+
 /// Resource for overlay visibility settings.
 #[derive(Resource)]
 pub struct OverlaySettings {
     pub show_cities: bool,
     pub show_landmarks: bool,
     pub show_regions: bool,
+    pub show_roads: bool,
+    pub show_sea_routes: bool,
+    pub show_territory: bool,
+    pub show_religion: bool,
+    pub show_resources: bool,
+    pub show_trade_flow: bool,
     pub show_chunk_grid: bool,
 }
 
@@ -34,11 +83,31 @@ impl Default for OverlaySettings {
             show_cities: true,
             show_landmarks: true,
             show_regions: true,
+            show_roads: true,
+            show_sea_routes: true,
+            show_territory: true,
+            show_religion: false,
+            show_resources: false,
+            show_trade_flow: false,
             show_chunk_grid: false,
         }
     }
 }
 
+impl OverlaySettings {
+    /// Whether entities of `layer` should be shown.
+    pub fn shows(&self, layer: OverlayLayer) -> bool {
+        match layer {
+            OverlayLayer::Cities => self.show_cities,
+            OverlayLayer::Landmarks => self.show_landmarks,
+            OverlayLayer::Regions => self.show_regions,
+            OverlayLayer::Territory => self.show_territory,
+            OverlayLayer::Religion => self.show_religion,
+            OverlayLayer::Resources => self.show_resources,
+        }
+    }
+}
+
 /// System to spawn city markers when entering World Map Editor mode.
 pub fn spawn_overlays(
     mut commands: Commands,
@@ -53,12 +122,9 @@ pub fn spawn_overlays(
     // Spawn city markers
     for city in &world_def.cities {
         let color = city_color(city.tier);
-        let size = city_size(city.tier);
+        let size = city_size(city);
 
-        // Convert world position to screen position
-        // World is centered at origin, so offset by half width/height
-        let x = city.position.x as f32 - (world_def.width as f32 / 2.0);
-        let y = -(city.position.y as f32 - (world_def.height as f32 / 2.0)); // Flip Y
+        let pos = world_position(&world_def, city.position);
 
         commands.spawn((
             Sprite {
@@ -66,8 +132,9 @@ pub fn spawn_overlays(
                 custom_size: Some(Vec2::splat(size)),
                 ..default()
             },
-            Transform::from_xyz(x, y, 1.0),
+            Transform::from_translation(pos.extend(1.0)),
             CityMarker { city_id: city.id },
+            OverlayLayer::Cities,
         ));
     }
 
@@ -75,8 +142,7 @@ pub fn spawn_overlays(
     for landmark in &world_def.landmarks {
         let color = landmark_color(landmark.kind);
 
-        let x = landmark.position.x as f32 - (world_def.width as f32 / 2.0);
-        let y = -(landmark.position.y as f32 - (world_def.height as f32 / 2.0));
+        let pos = world_position(&world_def, landmark.position);
 
         commands.spawn((
             Sprite {
@@ -84,8 +150,9 @@ pub fn spawn_overlays(
                 custom_size: Some(Vec2::splat(8.0)),
                 ..default()
             },
-            Transform::from_xyz(x, y, 1.0),
+            Transform::from_translation(pos.extend(1.0)),
             LandmarkMarker { landmark_id: landmark.id },
+            OverlayLayer::Landmarks,
         ));
     }
 }
@@ -115,27 +182,233 @@ pub fn update_overlays(
     // Update city positions
     for (marker, mut transform, mut sprite) in &mut city_query {
         if let Some(city) = world_def.cities.iter().find(|c| c.id == marker.city_id) {
-            let x = city.position.x as f32 - (world_def.width as f32 / 2.0);
-            let y = -(city.position.y as f32 - (world_def.height as f32 / 2.0));
-            transform.translation.x = x;
-            transform.translation.y = y;
+            let pos = world_position(&world_def, city.position);
+            transform.translation.x = pos.x;
+            transform.translation.y = pos.y;
             sprite.color = city_color(city.tier);
-            sprite.custom_size = Some(Vec2::splat(city_size(city.tier)));
+            sprite.custom_size = Some(Vec2::splat(city_size(city)));
         }
     }
 
     // Update landmark positions
     for (marker, mut transform, mut sprite) in &mut landmark_query {
         if let Some(landmark) = world_def.landmarks.iter().find(|l| l.id == marker.landmark_id) {
-            let x = landmark.position.x as f32 - (world_def.width as f32 / 2.0);
-            let y = -(landmark.position.y as f32 - (world_def.height as f32 / 2.0));
-            transform.translation.x = x;
-            transform.translation.y = y;
+            let pos = world_position(&world_def, landmark.position);
+            transform.translation.x = pos.x;
+            transform.translation.y = pos.y;
             sprite.color = landmark_color(landmark.kind);
         }
     }
 }
 
+/// System to show or hide overlays according to [`OverlaySettings`].
+///
+/// Everything is hidden outside the World Map Editor, so the app's map-wide
+/// overlays don't linger over the generator view. Territory is always shown
+/// while it is being painted.
+pub fn apply_overlay_visibility(
+    settings: Res<OverlaySettings>,
+    current_mode: Res<State<AppMode>>,
+    tool: Res<EditorTool>,
+    mut query: Query<(&OverlayLayer, &mut Visibility)>,
+) {
+    let in_editor = *current_mode.get() == AppMode::WorldMapEditor;
+    let painting_territory = *tool == EditorTool::PaintTerritory;
+    for (layer, mut visibility) in &mut query {
+        let shown = settings.shows(*layer) || (*layer == OverlayLayer::Territory && painting_territory);
+        let target = if in_editor && shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(target);
+    }
+}
+
+/// System to draw roads, sea lanes, trade flow and the chunk grid when
+/// enabled.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_line_overlays(
+    settings: Res<OverlaySettings>,
+    world_def: Res<WorldDefinition>,
+    region_draw: Res<RegionDrawState>,
+    terrain_brush: Res<TerrainBrushState>,
+    territory_brush: Res<TerritoryBrushState>,
+    tool: Res<EditorTool>,
+    selection: Res<EditorSelection>,
+    road_edit: Res<RoadEditState>,
+    mut gizmos: Gizmos,
+) {
+    let half = Vec2::new(world_def.width as f32, world_def.height as f32) / 2.0;
+
+    // The road tool shows roads whatever the overlay settings say
+    let editing_roads = *tool == EditorTool::EditRoad;
+    if settings.show_roads || editing_roads {
+        for road in &world_def.roads {
+            let [r, g, b] = road.road_type.color();
+            let points = road.waypoints.iter().map(|&p| world_position(&world_def, p));
+            gizmos.linestrip_2d(points, Color::srgb_u8(r, g, b));
+        }
+    }
+
+    if settings.show_sea_routes {
+        let color = Color::srgb(0.55, 0.8, 1.0);
+        for lane in &world_def.sea_routes {
+            let points: Vec<Vec2> = lane.waypoints.iter().map(|&p| world_position(&world_def, p)).collect();
+            for (start, end) in dashes(&points, SEA_LANE_DASH) {
+                gizmos.line_2d(start, end, color);
+            }
+        }
+    }
+
+    // Goods carried along each road, the busiest drawn thickest
+    if settings.show_trade_flow {
+        let volumes = road_volumes(&world_def.trade_flows);
+        let busiest = volumes.values().copied().fold(0.0, f32::max);
+        let color = Color::srgba(1.0, 0.6, 0.1, 0.8);
+        for road in &world_def.roads {
+            let Some(&volume) = volumes.get(&road.id) else { continue };
+            let strands = flow_strands(volume, busiest);
+            let points: Vec<Vec2> = road.waypoints.iter().map(|&p| world_position(&world_def, p)).collect();
+            for segment in points.windows(2) {
+                let normal = (segment[1] - segment[0]).perp().normalize_or_zero();
+                for strand in 0..strands {
+                    let offset = (strand as f32 - (strands - 1) as f32 / 2.0) * FLOW_STRAND_SPACING;
+                    gizmos.line_2d(segment[0] + normal * offset, segment[1] + normal * offset, color);
+                }
+            }
+        }
+    }
+
+    // Selected road's waypoints, the selected one larger
+    let selected_road = selection.road_id.and_then(|id| world_def.roads.iter().find(|r| r.id == id));
+    if let Some(road) = selected_road.filter(|_| editing_roads) {
+        for (i, &waypoint) in road.waypoints.iter().enumerate() {
+            let radius = if road_edit.waypoint == Some(i) { 3.0 } else { 1.5 };
+            gizmos.circle_2d(world_position(&world_def, waypoint), radius, Color::WHITE);
+        }
+    }
+
+    if settings.show_regions {
+        for region in world_def.regions.iter().filter(|r| r.bounds.is_closed()) {
+            let [r, g, b, _] = region.color;
+            let points = region.bounds.vertices.iter().map(|&p| world_position(&world_def, p));
+            gizmos.linestrip_2d(points.clone().chain(points.take(1)), Color::srgb_u8(r, g, b));
+        }
+    }
+
+    // Region being drawn: its edges so far, then on to the cursor, or back
+    // to the start once closed
+    if let Some(&first) = region_draw.vertices.first() {
+        let [r, g, b, _] = region_draw.color;
+        let end = if region_draw.closing { Some(first) } else { region_draw.cursor };
+        let points = region_draw.vertices.iter().chain(end.as_ref()).map(|&p| world_position(&world_def, p));
+        gizmos.linestrip_2d(points, Color::srgb_u8(r, g, b));
+        for &vertex in &region_draw.vertices {
+            gizmos.circle_2d(world_position(&world_def, vertex), 2.0, Color::WHITE);
+        }
+    }
+
+    // Terrain brush reach, with the core it works at full strength inside
+    if let Some(cursor) = terrain_brush.cursor {
+        let centre = world_position(&world_def, cursor);
+        gizmos.circle_2d(centre, terrain_brush.radius as f32, Color::WHITE);
+        let core = terrain_brush.radius * (1.0 - terrain_brush.falloff);
+        if terrain_brush.falloff > 0.0 && core > 0.0 {
+            gizmos.circle_2d(centre, core as f32, Color::srgba(1.0, 1.0, 1.0, 0.4));
+        }
+    }
+
+    // Territory brush reach, in the colour of the faction it paints
+    if let Some(cursor) = territory_brush.cursor {
+        let color = world_def.factions.iter().find(|f| f.id == territory_brush.faction).map_or(Color::WHITE, |f| {
+            let [r, g, b, _] = f.color;
+            Color::srgb_u8(r, g, b)
+        });
+        gizmos.circle_2d(world_position(&world_def, cursor), territory_brush.radius as f32, color);
+    }
+
+    if settings.show_chunk_grid {
+        let color = Color::srgba(1.0, 1.0, 1.0, 0.2);
+        let mut x = 0.0;
+        while x <= world_def.width as f32 {
+            gizmos.line_2d(Vec2::new(x - half.x, -half.y), Vec2::new(x - half.x, half.y), color);
+            x += GRID_SPACING;
+        }
+        let mut y = 0.0;
+        while y <= world_def.height as f32 {
+            gizmos.line_2d(Vec2::new(-half.x, half.y - y), Vec2::new(half.x, half.y - y), color);
+            y += GRID_SPACING;
+        }
+    }
+}
+
+/// System to draw each region's fill as an image over its bounding box,
+/// redrawn whenever the regions change.
+pub fn sync_region_fills(
+    mut commands: Commands,
+    world_def: Res<WorldDefinition>,
+    mut images: ResMut<Assets<Image>>,
+    fills: Query<Entity, With<RegionBoundary>>,
+    mut drawn: Local<Vec<Region>>,
+) {
+    let closed = world_def.regions.iter().filter(|r| r.bounds.is_closed()).count();
+    if *drawn == world_def.regions && fills.iter().count() == closed {
+        return;
+    }
+
+    for entity in &fills {
+        commands.entity(entity).despawn();
+    }
+    for region in &world_def.regions {
+        let Some((min, size, rgba)) = region_fill(region) else { continue };
+        let image = Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            rgba,
+            TextureFormat::Rgba8UnormSrgb,
+            default(),
+        );
+        let centre = Point2D::new(min.x as f64 + size.x as f64 / 2.0, min.y as f64 + size.y as f64 / 2.0);
+        commands.spawn((
+            Sprite { image: images.add(image), ..default() },
+            Transform::from_translation(world_position(&world_def, centre).extend(REGION_FILL_Z)),
+            RegionBoundary { region_id: region.id },
+            OverlayLayer::Regions,
+        ));
+    }
+    *drawn = world_def.regions.clone();
+}
+
+/// A region's fill over the map cells of its bounding box: the box's
+/// top-left cell, its size, and RGBA pixels in the region's colour where a
+/// cell's centre is inside. `None` for regions that aren't closed.
+fn region_fill(region: &Region) -> Option<(IVec2, UVec2, Vec<u8>)> {
+    if !region.bounds.is_closed() {
+        return None;
+    }
+    let vertices = &region.bounds.vertices;
+    let min_x = vertices.iter().map(|v| v.x).fold(f64::INFINITY, f64::min).floor() as i32;
+    let min_y = vertices.iter().map(|v| v.y).fold(f64::INFINITY, f64::min).floor() as i32;
+    let max_x = vertices.iter().map(|v| v.x).fold(f64::NEG_INFINITY, f64::max).ceil() as i32;
+    let max_y = vertices.iter().map(|v| v.y).fold(f64::NEG_INFINITY, f64::max).ceil() as i32;
+    let size = UVec2::new((max_x - min_x).max(1) as u32, (max_y - min_y).max(1) as u32);
+
+    let mut rgba = vec![0; (size.x * size.y * 4) as usize];
+    for (i, pixel) in rgba.chunks_exact_mut(4).enumerate() {
+        let x = min_x as f64 + (i as u32 % size.x) as f64 + 0.5;
+        let y = min_y as f64 + (i as u32 / size.x) as f64 + 0.5;
+        if region.bounds.contains(Point2D::new(x, y)) {
+            pixel.copy_from_slice(&region.color);
+        }
+    }
+    Some((IVec2::new(min_x, min_y), size, rgba))
+}
+
 /// Get the display color for a city tier.
 fn city_color(tier: CityTier) -> Color {
     match tier {
@@ -145,13 +418,51 @@ fn city_color(tier: CityTier) -> Color {
     }
 }
 
-/// Get the display size for a city tier.
-fn city_size(tier: CityTier) -> f32 {
-    match tier {
+/// Get the display size for a city: its tier's size, grown or shrunk by up
+/// to a fifth by where its population falls in the tier's range.
+fn city_size(city: &City) -> f32 {
+    let base = match city.tier {
         CityTier::Capital => 16.0,
         CityTier::Town => 10.0,
         CityTier::Village => 6.0,
+    };
+    // Populations spread over orders of magnitude, so place them on a log scale
+    let (min, max) = city.tier.population_range();
+    let place = (city.population.clamp(min, max) as f32 / min as f32).ln() / (max as f32 / min as f32).ln();
+    base * (0.8 + 0.4 * place)
+}
+
+/// Dashes along a line through `points`, each `dash` long with as long a
+/// gap after it, as their start and end. The pattern carries on round
+/// corners rather than starting afresh on every segment.
+fn dashes(points: &[Vec2], dash: f32) -> Vec<(Vec2, Vec2)> {
+    let mut dashes = Vec::new();
+    let mut travelled = 0.0;
+    for segment in points.windows(2) {
+        let (start, length) = (segment[0], segment[0].distance(segment[1]));
+        let direction = (segment[1] - start).normalize_or_zero();
+        let mut along = 0.0;
+        while along < length {
+            let phase = travelled % (2.0 * dash);
+            let step = if phase < dash { dash - phase } else { 2.0 * dash - phase };
+            let step = step.min(length - along);
+            if phase < dash {
+                dashes.push((start + direction * along, start + direction * (along + step)));
+            }
+            along += step;
+            travelled += step;
+        }
+    }
+    dashes
+}
+
+/// Strands to draw a road carrying `volume` with, from one up to
+/// [`MAX_FLOW_STRANDS`] for the `busiest` road.
+fn flow_strands(volume: f32, busiest: f32) -> usize {
+    if busiest <= 0.0 {
+        return 1;
     }
+    1 + ((volume / busiest).clamp(0.0, 1.0) * (MAX_FLOW_STRANDS - 1) as f32).round() as usize
 }
 
 /// Get the display color for a landmark kind.
@@ -165,6 +476,94 @@ fn landmark_color(kind: LandmarkKind) -> Color {
         LandmarkKind::Monument => Color::srgb(0.9, 0.9, 0.9),
         LandmarkKind::Mine => Color::srgb(0.4, 0.3, 0.2),
         LandmarkKind::Port => Color::srgb(0.2, 0.5, 0.8),
+        LandmarkKind::Volcano => Color::srgb(0.9, 0.25, 0.1),
         LandmarkKind::Other => Color::srgb(0.5, 0.5, 0.5),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_world::Polygon;
+
+    #[test]
+    fn toggling_flag_updates_marker_visibility() {
+        let mut app = App::new();
+        app.insert_resource(OverlaySettings::default())
+            .insert_resource(State::new(AppMode::WorldMapEditor))
+            .init_resource::<EditorTool>()
+            .add_systems(Update, apply_overlay_visibility);
+
+        let city = app.world_mut().spawn((Visibility::default(), OverlayLayer::Cities)).id();
+        let landmark = app.world_mut().spawn((Visibility::default(), OverlayLayer::Landmarks)).id();
+
+        app.world_mut().resource_mut::<OverlaySettings>().show_cities = false;
+        app.update();
+        assert_eq!(app.world().get::<Visibility>(city), Some(&Visibility::Hidden));
+        assert_eq!(app.world().get::<Visibility>(landmark), Some(&Visibility::Inherited));
+
+        app.world_mut().resource_mut::<OverlaySettings>().show_cities = true;
+        app.update();
+        assert_eq!(app.world().get::<Visibility>(city), Some(&Visibility::Inherited));
+    }
+
+    #[test]
+    fn markers_grow_with_population_within_their_tier() {
+        let mut town = City::new(1, "Town".into(), Point2D::new(0.0, 0.0), CityTier::Town);
+        let (min, max) = CityTier::Town.population_range();
+        town.population = min;
+        assert!((city_size(&town) - 8.0).abs() < 1e-4);
+        town.population = max;
+        assert!((city_size(&town) - 12.0).abs() < 1e-4);
+
+        // The smallest capital still outsizes the largest town
+        let mut capital = City::new(2, "Capital".into(), Point2D::new(0.0, 0.0), CityTier::Capital);
+        capital.population = 0;
+        assert!(city_size(&capital) > city_size(&town));
+    }
+
+    #[test]
+    fn dashes_keep_their_rhythm_round_corners() {
+        let points = [Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(4.0, 5.0)];
+        let dashes = dashes(&points, 2.0);
+        let expected = [
+            (Vec2::ZERO, Vec2::new(2.0, 0.0)),
+            (Vec2::new(4.0, 0.0), Vec2::new(4.0, 2.0)),
+            (Vec2::new(4.0, 4.0), Vec2::new(4.0, 5.0)),
+        ];
+        assert_eq!(dashes.len(), expected.len());
+        for ((start, end), (want_start, want_end)) in dashes.into_iter().zip(expected) {
+            assert!(start.distance(want_start) < 1e-4 && end.distance(want_end) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn busier_roads_get_more_strands() {
+        assert_eq!(flow_strands(0.1, 100.0), 1);
+        assert_eq!(flow_strands(50.0, 100.0), 3);
+        assert_eq!(flow_strands(100.0, 100.0), MAX_FLOW_STRANDS);
+        assert_eq!(flow_strands(5.0, 0.0), 1);
+    }
+
+    #[test]
+    fn region_fill_covers_the_inside_of_its_box() {
+        let square = |offset: f64| {
+            let corners = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+            Polygon::new(corners.iter().map(|&(x, y)| Point2D::new(x + offset, y + offset)).collect())
+        };
+        let region = Region::new(1, "Square".into(), square(10.0));
+        let (min, size, rgba) = region_fill(&region).unwrap();
+        assert_eq!((min, size), (IVec2::new(10, 10), UVec2::new(4, 4)));
+        assert!(rgba.chunks_exact(4).all(|pixel| pixel == region.color));
+
+        // A triangle fills about half its box
+        let mut triangle = Region::new(2, "Triangle".into(), square(0.0));
+        triangle.bounds.vertices.remove(3);
+        let (_, _, rgba) = region_fill(&triangle).unwrap();
+        let filled = rgba.chunks_exact(4).filter(|pixel| pixel[3] > 0).count();
+        assert!((6..=10).contains(&filled), "{} cells filled", filled);
+
+        triangle.bounds.vertices.truncate(2);
+        assert!(region_fill(&triangle).is_none());
+    }
+}
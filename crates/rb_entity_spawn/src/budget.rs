@@ -0,0 +1,110 @@
+//! Per-chunk clutter budgets.
+//!
+//! A chunk of dense forest could otherwise ask for a tree on every tile. The
+//! budget caps what one chunk may spawn and shares that cap between its biomes
+//! by area and clutter density, so sparse biomes get little and water none.
+
+use rb_core::{TileType, PALETTE};
+
+/// Most clutter entities a single chunk may spawn.
+pub const MAX_SPAWNS_PER_CHUNK: u32 = 512;
+
+/// Share of [`MAX_SPAWNS_PER_CHUNK`] a chunk covered entirely by `tile` gets.
+///
+/// Mostly vegetation; rocky biomes keep a little for boulders and scree.
+pub fn clutter_density(tile: TileType) -> f32 {
+    match tile {
+        TileType::Jungle => 1.0,
+        TileType::Forest => 0.8,
+        TileType::Taiga => 0.7,
+        TileType::Marsh => 0.5,
+        TileType::Savanna => 0.3,
+        TileType::Plains => 0.25,
+        TileType::Steppe => 0.2,
+        TileType::Mountain | TileType::Badlands | TileType::Plateau => 0.15,
+        TileType::Tundra | TileType::Volcanic => 0.1,
+        TileType::Beach | TileType::Desert => 0.05,
+        TileType::Sahara | TileType::Snow | TileType::Glacier => 0.02,
+        TileType::White
+        | TileType::ShallowSea
+        | TileType::Sea
+        | TileType::DeepSea
+        | TileType::OceanTrench
+        | TileType::River
+        | TileType::Lake => 0.0,
+    }
+}
+
+/// How many clutter entities each biome in a chunk may spawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SpawnBudget {
+    per_biome: [u32; PALETTE.len()],
+}
+
+impl SpawnBudget {
+    /// Spawns allotted to `tile`.
+    pub fn get(&self, tile: TileType) -> u32 {
+        self.per_biome[tile.index()]
+    }
+
+    /// Spawns allotted across all biomes. Never above [`MAX_SPAWNS_PER_CHUNK`].
+    pub fn total(&self) -> u32 {
+        self.per_biome.iter().sum()
+    }
+
+    /// Biomes with a non-zero allotment, in [`PALETTE`] order.
+    pub fn iter(&self) -> impl Iterator<Item = (TileType, u32)> + '_ {
+        TileType::all()
+            .iter()
+            .zip(self.per_biome)
+            .filter(|&(_, count)| count > 0)
+            .map(|(&tile, count)| (tile, count))
+    }
+}
+
+/// Budget for a chunk given how many of its cells hold each biome.
+///
+/// Each biome gets `MAX_SPAWNS_PER_CHUNK × density × area share`, rounded
+/// down, so the total stays within the cap whatever the mix. Repeated biomes
+/// in `biome_counts` are summed.
+pub fn chunk_spawn_budget(biome_counts: &[(TileType, usize)]) -> SpawnBudget {
+    let mut area = [0usize; PALETTE.len()];
+    for &(tile, count) in biome_counts {
+        area[tile.index()] += count;
+    }
+
+    let total_cells: usize = area.iter().sum();
+    let mut budget = SpawnBudget::default();
+    if total_cells == 0 {
+        return budget;
+    }
+
+    for (tile, (slot, &cells)) in TileType::all()
+        .iter()
+        .zip(budget.per_biome.iter_mut().zip(&area))
+    {
+        let share = cells as f32 / total_cells as f32;
+        *slot = (MAX_SPAWNS_PER_CHUNK as f32 * clutter_density(*tile) * share) as u32;
+    }
+    budget
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn denser_forest_gets_more_trees_within_cap() {
+        let full = chunk_spawn_budget(&[(TileType::Forest, 1024)]);
+        let half = chunk_spawn_budget(&[(TileType::Forest, 512), (TileType::Sea, 512)]);
+
+        assert!(full.get(TileType::Forest) > half.get(TileType::Forest));
+        assert_eq!(half.get(TileType::Sea), 0);
+        assert!(full.total() <= MAX_SPAWNS_PER_CHUNK);
+
+        // The densest possible chunk still fits
+        let jungle = chunk_spawn_budget(&[(TileType::Jungle, 1024)]);
+        assert_eq!(jungle.total(), MAX_SPAWNS_PER_CHUNK);
+        assert!(chunk_spawn_budget(&[]).iter().next().is_none());
+    }
+}
@@ -1,5 +1,9 @@
 use bevy::prelude::*;
 
+pub mod budget;
+
+pub use budget::{chunk_spawn_budget, SpawnBudget, MAX_SPAWNS_PER_CHUNK};
+
 /// Entity spawn plugin for Randlebrot.
 /// Handles building, NPC, and clutter spawning from chunk parameters.
 pub struct RbEntitySpawnPlugin;
@@ -1,19 +1,26 @@
 //! Noise preview example - visualizes continentalness and temperature noise.
 //!
 //! Run with: cargo run -p rb_noise --example noise_preview
+//!
+//! Pass `--gpu` to draw the layers with the fragment-shader preview material
+//! instead of resampling them on the CPU after every pan.
 
 use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use rb_core::DetailLevel;
-use rb_noise::{ContinentalnessStrategy, TemperatureStrategy, WorldChunks};
+use rb_noise::preview::{NoisePreviewMaterial, NoisePreviewPlugin};
+use rb_noise::WorldChunks;
 
 const PREVIEW_WIDTH: u32 = 512;
 const PREVIEW_HEIGHT: u32 = 512;
 const WORLD_SCALE: f64 = 2.0; // World units per pixel
+const SEED: u32 = 42; // WorldChunks::default()
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
+    let gpu = std::env::args().any(|arg| arg == "--gpu");
+
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Noise Preview".into(),
                 resolution: (1024.0, 512.0).into(),
@@ -21,11 +28,19 @@ fn main() {
             }),
             ..default()
         }))
-        .init_resource::<WorldChunks>()
         .init_resource::<CameraOffset>()
-        .add_systems(Startup, setup)
-        .add_systems(Update, (handle_input, update_noise_textures))
-        .run();
+        .add_systems(Update, handle_input);
+
+    if gpu {
+        app.add_plugins(NoisePreviewPlugin)
+            .add_systems(Startup, setup_gpu)
+            .add_systems(Update, update_preview_materials);
+    } else {
+        app.init_resource::<WorldChunks>()
+            .add_systems(Startup, setup)
+            .add_systems(Update, update_noise_textures);
+    }
+    app.run();
 }
 
 #[derive(Resource, Default)]
@@ -79,6 +94,49 @@ fn setup(
     camera_offset.dirty = true;
 }
 
+/// Shader-driven variant of `setup`: one quad per layer, same layout.
+fn setup_gpu(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<NoisePreviewMaterial>>,
+    mut camera_offset: ResMut<CameraOffset>,
+) {
+    commands.spawn(Camera2d);
+
+    let quad = meshes.add(Rectangle::new(PREVIEW_WIDTH as f32, PREVIEW_HEIGHT as f32));
+    let layers = [
+        (NoisePreviewMaterial::continentalness(SEED), -256.0),
+        // WorldChunks offsets the temperature seed by one
+        (NoisePreviewMaterial::temperature(SEED.wrapping_add(1)), 256.0),
+    ];
+    for (material, x) in layers {
+        commands.spawn((
+            Mesh2d(quad.clone()),
+            MeshMaterial2d(materials.add(material)),
+            Transform::from_xyz(x, 0.0, 0.0),
+        ));
+    }
+
+    camera_offset.dirty = true;
+}
+
+/// Point every preview material at the current offset.
+fn update_preview_materials(
+    mut camera_offset: ResMut<CameraOffset>,
+    mut materials: ResMut<Assets<NoisePreviewMaterial>>,
+) {
+    if !camera_offset.dirty {
+        return;
+    }
+    camera_offset.dirty = false;
+
+    let offset = Vec2::new(camera_offset.x as f32, camera_offset.y as f32);
+    let extent = Vec2::new(PREVIEW_WIDTH as f32, PREVIEW_HEIGHT as f32) * WORLD_SCALE as f32;
+    for (_, material) in materials.iter_mut() {
+        material.set_view(offset, extent);
+    }
+}
+
 fn create_noise_image() -> Image {
     let size = Extent3d {
         width: PREVIEW_WIDTH,
@@ -0,0 +1,134 @@
+//! Post-pass that removes climatically impossible biome neighbours.
+//!
+//! Spline evaluation looks at one cell at a time, so nothing stops a Sahara
+//! cell from touching Snow. Each land biome sits in a thermal band; neighbours
+//! more than [`MAX_BAND_STEP`] bands apart are forbidden, and the warmer cell
+//! is replaced with a transition biome from the band in between.
+
+use rb_core::TileType;
+
+/// Largest thermal band difference allowed between 4-connected neighbours.
+pub const MAX_BAND_STEP: i8 = 2;
+
+/// Upper bound on relaxation passes; each pass resolves one ring of conflicts.
+const MAX_PASSES: usize = 8;
+
+/// Thermal band of a biome, from -2 (frozen) to 2 (scorching).
+///
+/// Water, rivers, beaches and high terrain are neutral and never conflict.
+pub fn thermal_band(biome: TileType) -> Option<i8> {
+    match biome {
+        TileType::White | TileType::Glacier | TileType::Snow => Some(-2),
+        TileType::Tundra | TileType::Taiga => Some(-1),
+        TileType::Plains | TileType::Forest | TileType::Marsh | TileType::Steppe => Some(0),
+        TileType::Savanna | TileType::Jungle | TileType::Desert | TileType::Badlands => Some(1),
+        TileType::Sahara => Some(2),
+        TileType::ShallowSea
+        | TileType::Sea
+        | TileType::DeepSea
+        | TileType::OceanTrench
+        | TileType::River
+        | TileType::Lake
+        | TileType::Beach
+        | TileType::Mountain
+        | TileType::Plateau
+        | TileType::Volcanic => None,
+    }
+}
+
+/// Whether two biomes may sit next to each other.
+pub fn is_allowed_adjacency(a: TileType, b: TileType) -> bool {
+    match (thermal_band(a), thermal_band(b)) {
+        (Some(ba), Some(bb)) => (ba - bb).abs() <= MAX_BAND_STEP,
+        _ => true,
+    }
+}
+
+/// Biome used to bridge into a band. Dry biomes stay dry.
+fn transition_biome(band: i8, replacing: TileType) -> TileType {
+    let arid = matches!(
+        replacing,
+        TileType::Sahara | TileType::Desert | TileType::Badlands | TileType::Steppe
+    );
+    match (band, arid) {
+        (b, _) if b <= -1 => TileType::Tundra,
+        (0, true) => TileType::Steppe,
+        (0, false) => TileType::Plains,
+        (_, true) => TileType::Desert,
+        (_, false) => TileType::Savanna,
+    }
+}
+
+/// Replace cells that border a forbidden neighbour with transition biomes.
+///
+/// Returns the number of cells changed.
+pub fn enforce_adjacency(biomes: &mut [TileType], width: usize, height: usize) -> usize {
+    let mut changed = 0;
+
+    for _ in 0..MAX_PASSES {
+        let snapshot = biomes.to_vec();
+        let mut pass_changed = 0;
+
+        for y in 0..height {
+            for x in 0..width {
+                let idx = y * width + x;
+                let Some(band) = thermal_band(snapshot[idx]) else {
+                    continue;
+                };
+
+                // Coldest neighbour this cell is too warm for
+                let neighbors = [
+                    (x > 0).then(|| idx - 1),
+                    (x + 1 < width).then(|| idx + 1),
+                    (y > 0).then(|| idx - width),
+                    (y + 1 < height).then(|| idx + width),
+                ];
+                let coldest = neighbors
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|n| thermal_band(snapshot[n]))
+                    .min();
+
+                if let Some(cold) = coldest {
+                    if band - cold > MAX_BAND_STEP {
+                        biomes[idx] = transition_biome(cold + MAX_BAND_STEP, snapshot[idx]);
+                        pass_changed += 1;
+                    }
+                }
+            }
+        }
+
+        changed += pass_changed;
+        if pass_changed == 0 {
+            break;
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sahara_next_to_snow_gets_transition() {
+        // Left half Snow, right half Sahara
+        let (w, h) = (6, 3);
+        let mut biomes: Vec<TileType> = (0..w * h)
+            .map(|i| if i % w < 3 { TileType::Snow } else { TileType::Sahara })
+            .collect();
+        assert!(!is_allowed_adjacency(TileType::Snow, TileType::Sahara));
+
+        let changed = enforce_adjacency(&mut biomes, w, h);
+
+        assert_eq!(changed, h);
+        for y in 0..h {
+            let boundary = biomes[y * w + 3];
+            assert_eq!(boundary, TileType::Steppe);
+            for x in 0..w - 1 {
+                assert!(is_allowed_adjacency(biomes[y * w + x], biomes[y * w + x + 1]));
+            }
+        }
+    }
+}
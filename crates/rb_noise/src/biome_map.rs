@@ -1,26 +1,49 @@
 use rayon::prelude::*;
-use rb_core::{NoiseStrategy, ResourceType, TileType};
+use bevy::math::Vec2;
+use rb_core::{
+    map_to_world, world_to_map, BiomeRegistry, NoiseStrategy, ResourceType, TileType, PALETTE,
+};
+use std::sync::mpsc::{self, Receiver};
 use std::sync::Arc;
-
-use crate::biome_splines::BiomeSplines;
-use crate::progress::{LayerId, LayerProgress};
-use crate::resource_map::ResourceMap;
+use std::thread::JoinHandle;
+
+use crate::adjacency;
+use crate::biome_override::{self, BiomeOverride};
+use crate::biome_splines::{BiomeDither, BiomeSplines, BiomeThresholds};
+use crate::coastline;
+use crate::elevation::terrain_elevation;
+use crate::histogram;
+use crate::hydraulic;
+use crate::lakes::{self, Lake};
+use crate::normalization::NormalizationContext;
+use crate::precision::{to_layer, LayerFloat, LayerValue};
+use crate::progress::{GenEvent, LayerId, LayerProgress};
+use crate::resource_map::{density_threshold, ResourceMap};
 use crate::rivers::RiverGenerator;
+use crate::sketch::ContinentSketch;
 use crate::strategy::resource::ResourceContext;
 use crate::strategy::{
-    ContinentalnessStrategy, ErosionStrategy, HumidityStrategy, PeaksAndValleysStrategy,
-    ResourceNoiseStrategy, TectonicPlatesStrategy,
+    CaveStrategy, ContinentalnessStrategy, DomainWarp, ErosionStrategy, FbmParams,
+    HumidityStrategy, LayerBases, LayerParams, OceanCurrentStrategy, PeaksAndValleysStrategy, ResourceNoiseStrategy,
+    TectonicPlatesStrategy, WindStrategy, DEFAULT_RESOURCE_THRESHOLD, transport_moisture,
 };
+use crate::symmetry::Symmetry;
+use crate::terrain_edit::TerrainEdits;
 use crate::tidally_locked::LatitudeTemperatureStrategy;
+use crate::volcanoes::{self, Volcano};
 use crate::visualization::{
-    grayscale_to_rgba, humidity_to_rgba, peaks_to_rgba, resource_to_rgba,
-    river_to_rgba, tectonic_to_rgba, temperature_to_rgba, NoiseLayer,
+    cave_to_rgba, flow_to_rgba, grayscale_to_rgba, humidity_to_rgba, peaks_to_rgba,
+    rainfall_to_rgba, resource_to_rgba, river_to_rgba, tectonic_to_rgba, temperature_to_rgba,
+    NoiseLayer,
 };
 
 /// Sea level threshold for continentalness.
 /// Values below this are ocean, values above are land.
 pub const SEA_LEVEL: f64 = -0.025;
 
+/// Continentalness the map edge is pulled down to when a border ocean is set.
+const BORDER_OCEAN_FLOOR: f64 = -0.3;
+
 /// Backend selection for noise generation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum NoiseBackend {
@@ -46,6 +69,388 @@ impl NoiseBackend {
     }
 }
 
+/// The six terrain noise strategies plus wind, currents and caves, seeded
+/// identically for every generation path so the macro map and meso tiles
+/// sample the same fields.
+struct LayerStrategies {
+    continentalness: ContinentalnessStrategy,
+    temperature: LatitudeTemperatureStrategy,
+    tectonic: TectonicPlatesStrategy,
+    erosion: ErosionStrategy,
+    peaks: PeaksAndValleysStrategy,
+    humidity: HumidityStrategy,
+    wind: WindStrategy,
+    currents: OceanCurrentStrategy,
+    caves: CaveStrategy,
+    /// Low-octave continentalness, probed upwind for moisture and barriers.
+    relief: ContinentalnessStrategy,
+    /// Macro baseline that continentalness is moved onto, for detail tiles.
+    normalization: Option<Arc<NormalizationContext>>,
+    world_height: f64,
+}
+
+impl LayerStrategies {
+    fn new(seed: u32, world_height: f64) -> Self {
+        Self {
+            continentalness: ContinentalnessStrategy::new(seed),
+            temperature: LatitudeTemperatureStrategy::new(seed.wrapping_add(1), world_height),
+            tectonic: TectonicPlatesStrategy::new(seed.wrapping_add(2)),
+            erosion: ErosionStrategy::new(seed.wrapping_add(3)),
+            peaks: PeaksAndValleysStrategy::new(seed.wrapping_add(4)),
+            humidity: HumidityStrategy::new(seed.wrapping_add(5)),
+            wind: WindStrategy::new(
+                LatitudeTemperatureStrategy::new(seed.wrapping_add(1), world_height),
+                seed.wrapping_add(6),
+            ),
+            currents: OceanCurrentStrategy::new(seed.wrapping_add(7)),
+            caves: CaveStrategy::new(seed.wrapping_add(8)),
+            relief: ContinentalnessStrategy::with_params(seed, 4, 1.0, 2.0, 0.59),
+            normalization: None,
+            world_height,
+        }
+    }
+
+    /// Move sampled continentalness onto a macro map's baseline, if given.
+    fn with_normalization(mut self, normalization: Option<Arc<NormalizationContext>>) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Strategies for a macro map `height` cells tall, shaped by `config`.
+    fn for_config(seed: u32, height: usize, config: &GenerationConfig) -> Self {
+        let mut strategies = Self::new(seed, height as f64);
+        if let Some(twilight_width) = config.twilight_width {
+            strategies = strategies.with_twilight(height as f64 / 2.0, twilight_width);
+        }
+        if !config.bases.is_default() {
+            strategies = strategies.with_bases(seed, &config.bases);
+        }
+        if !config.layers.is_default() {
+            strategies = strategies.with_params(&config.layers);
+        }
+        if config.warp_amplitude > 0.0 {
+            strategies = strategies.with_warp(seed, config.warp_amplitude, config.warp_frequency);
+        }
+        if let Some(depth) = config.cave_depth {
+            strategies = strategies.with_cave_depth(depth);
+        }
+        if config.season != 0.0 {
+            strategies = strategies.with_season(config.season);
+        }
+        strategies
+    }
+
+    /// Warp the four fBm layers' coordinates, each with its own offset field.
+    /// The relief the wind probes shares continentalness's warp so its
+    /// coastlines stay put.
+    fn with_warp(mut self, seed: u32, amplitude: f64, frequency: f64) -> Self {
+        let warp = |offset| DomainWarp::new(seed.wrapping_add(offset), amplitude, frequency);
+        self.continentalness = self.continentalness.with_warp(warp(10));
+        self.relief = self.relief.with_warp(warp(10));
+        self.erosion = self.erosion.with_warp(warp(11));
+        self.humidity = self.humidity.with_warp(warp(12));
+        self.peaks = self.peaks.with_warp(warp(13));
+        self
+    }
+
+    /// Build the fBm layers on the chosen noise bases, seeded as in `new`.
+    fn with_bases(mut self, seed: u32, bases: &LayerBases) -> Self {
+        self.continentalness =
+            self.continentalness.with_source(bases.continentalness.source(seed));
+        self.relief = self.relief.with_source(bases.continentalness.source(seed));
+        self.erosion = self.erosion.with_source(bases.erosion.source(seed.wrapping_add(3)));
+        self.peaks = self.peaks.with_source(bases.peaks_valleys.source(seed.wrapping_add(4)));
+        self.humidity = self.humidity.with_source(bases.humidity.source(seed.wrapping_add(5)));
+        self
+    }
+
+    /// Reshape each layer's noise. The relief the wind probes keeps its four
+    /// octaves but otherwise follows continentalness.
+    fn with_params(mut self, params: &LayerParams) -> Self {
+        self.continentalness = self.continentalness.with_fbm(&params.continentalness);
+        self.relief = self.relief.with_fbm(&FbmParams {
+            octaves: 4,
+            ..params.continentalness
+        });
+        self.temperature = self.temperature.with_fbm(&params.temperature);
+        self.wind = self.wind.with_temperature(self.temperature.clone());
+        self.tectonic = self.tectonic.with_fbm(&params.tectonic);
+        self.erosion = self.erosion.with_fbm(&params.erosion);
+        self.peaks = self.peaks.with_fbm(&params.peaks_valleys);
+        self.humidity = self.humidity.with_fbm(&params.humidity);
+        self
+    }
+
+    /// Narrow or widen the twilight band; the wind follows the temperature.
+    fn with_twilight(mut self, center: f64, width: f64) -> Self {
+        self.temperature = self.temperature.with_twilight(center, width);
+        self.wind = self.wind.with_temperature(self.temperature.clone());
+        self
+    }
+
+    /// Shift temperature, humidity and the wind to `season` through the year.
+    fn with_season(mut self, season: f64) -> Self {
+        self.temperature = self.temperature.with_season(season);
+        self.wind = self.wind.with_temperature(self.temperature.clone());
+        self.humidity = self.humidity.with_season(season);
+        self
+    }
+
+    /// Slice the caves `depth` world units below the surface.
+    fn with_cave_depth(mut self, depth: f64) -> Self {
+        self.caves = self.caves.with_depth(depth);
+        self
+    }
+
+    /// Erosion, humidity and precipitation at a point, given its (possibly
+    /// shaped) continentalness.
+    fn dependent(&self, wx: f64, wy: f64, detail_level: u32, cont: f64) -> (f64, f64, f64) {
+        let eros = self.erosion.generate_with_continentalness(wx, wy, detail_level, cont);
+        let (humid, precip) = self.moisture(wx, wy, detail_level, cont);
+        (eros, humid, precip)
+    }
+
+    /// Temperature, humidity and precipitation at a point, given its
+    /// continentalness: the layers the season changes.
+    fn climate(&self, wx: f64, wy: f64, cont: f64) -> (f64, f64, f64) {
+        let temp = self.temperature.generate(wx, wy, 0);
+        let (humid, precip) = self.moisture(wx, wy, 0, cont);
+        (temp, humid, precip)
+    }
+
+    /// Raw continentalness at a point, on the macro baseline if there is one.
+    fn normalized(&self, wx: f64, wy: f64, cont: f64) -> f64 {
+        match &self.normalization {
+            Some(context) => context.normalize(wx, wy, cont),
+            None => cont,
+        }
+    }
+
+    /// Humidity and precipitation from the moisture the wind brings in over
+    /// the relief upwind of a point.
+    fn moisture(&self, wx: f64, wy: f64, detail_level: u32, cont: f64) -> (f64, f64) {
+        let moisture = transport_moisture(&self.wind, wx, wy, cont, SEA_LEVEL, |x, y| {
+            self.relief.generate(x, y, 0)
+        });
+        let humid = self.humidity.generate_tidally_locked(
+            wx,
+            wy,
+            detail_level,
+            moisture.vapour,
+            self.world_height,
+        );
+        (humid, moisture.precipitation)
+    }
+
+    /// Wind and ocean current vectors over a `width` × `height` grid whose
+    /// first cell sits at (`world_x`, `world_y`), `scale` world units apart.
+    /// Currents are zero on land.
+    fn flow_fields(
+        &self,
+        (world_x, world_y, scale): (f64, f64, f64),
+        width: usize,
+        height: usize,
+        continentalness: &[f64],
+    ) -> (VectorField, VectorField) {
+        (0..width * height)
+            .into_par_iter()
+            .map(|idx| {
+                let wx = world_x + (idx % width) as f64 * scale;
+                let wy = world_y + (idx / width) as f64 * scale;
+                let current = if continentalness[idx] < SEA_LEVEL {
+                    self.currents.vector(wx, wy)
+                } else {
+                    (0.0, 0.0)
+                };
+                let store = |(x, y): (f64, f64)| (LayerFloat::from_f64(x), LayerFloat::from_f64(y));
+                (store(self.wind.vector(wx, wy)), store(current))
+            })
+            .unzip()
+    }
+
+    /// Cave density at the slice depth over the same kind of grid as
+    /// [`flow_fields`](Self::flow_fields).
+    fn cave_slice(
+        &self,
+        (world_x, world_y, scale): (f64, f64, f64),
+        width: usize,
+        height: usize,
+    ) -> Vec<f64> {
+        (0..width * height)
+            .into_par_iter()
+            .map(|idx| {
+                let wx = world_x + (idx % width) as f64 * scale;
+                let wy = world_y + (idx / width) as f64 * scale;
+                self.caves.generate(wx, wy, 0)
+            })
+            .collect()
+    }
+
+    /// Plate boundary uplift over the same kind of grid as
+    /// [`flow_fields`](Self::flow_fields).
+    fn plate_uplift(
+        &self,
+        (world_x, world_y, scale): (f64, f64, f64),
+        width: usize,
+        height: usize,
+    ) -> Vec<f64> {
+        (0..width * height)
+            .into_par_iter()
+            .map(|idx| {
+                let wx = world_x + (idx % width) as f64 * scale;
+                let wy = world_y + (idx / width) as f64 * scale;
+                self.tectonic.boundary(wx, wy).uplift()
+            })
+            .collect()
+    }
+
+    /// All seven layers at a point: (cont, temp, tectonic, peaks, erosion,
+    /// humidity, precipitation).
+    fn sample(&self, wx: f64, wy: f64, detail_level: u32) -> LayerSample {
+        let cont = self.continentalness.generate(wx, wy, detail_level);
+        let cont = self.normalized(wx, wy, cont);
+        let temp = self.temperature.generate(wx, wy, detail_level);
+        let tect = self.tectonic.generate(wx, wy, detail_level);
+        let peaks = self.peaks.generate(wx, wy, detail_level);
+        let (eros, humid, precip) = self.dependent(wx, wy, detail_level, cont);
+        (cont, temp, tect, peaks, eros, humid, precip)
+    }
+
+    /// [`sample`](Self::sample) at many points, with the four layers that
+    /// don't depend on continentalness generated as batches.
+    fn sample_batch(&self, points: &[(f64, f64)], detail_level: u32) -> Vec<LayerSample> {
+        let batch = |strategy: &dyn NoiseStrategy| {
+            let mut out = vec![0.0; points.len()];
+            strategy.generate_batch(points, detail_level, &mut out);
+            out
+        };
+        let cont = batch(&self.continentalness);
+        let temp = batch(&self.temperature);
+        let tect = batch(&self.tectonic);
+        let peaks = batch(&self.peaks);
+
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, &(wx, wy))| {
+                let cont = self.normalized(wx, wy, cont[i]);
+                let (eros, humid, precip) = self.dependent(wx, wy, detail_level, cont);
+                (cont, temp[i], tect[i], peaks[i], eros, humid, precip)
+            })
+            .collect()
+    }
+}
+
+/// One point's layers: (cont, temp, tectonic, peaks, erosion, humidity,
+/// precipitation).
+type LayerSample = (f64, f64, f64, f64, f64, f64, f64);
+
+/// Optional post-processing passes applied during macro map generation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerationConfig {
+    /// Equalize the continentalness histogram so values are spread uniformly
+    /// over [-1, 1]. This makes the land/sea split respond linearly to sea level
+    /// instead of flipping large areas around zero.
+    pub equalize_continentalness: bool,
+    /// Dither biome classification near spline thresholds so neighbouring
+    /// biomes blend through a speckled band instead of a hard edge.
+    pub dither_biomes: bool,
+    /// Width in cells of the ocean frame around the map edge (0 disables).
+    /// Continentalness is smoothly faded toward deep water within this
+    /// distance so the world is always surrounded by sea.
+    pub border_ocean: f64,
+    /// Mirror the map so both halves play the same.
+    pub symmetry: Symmetry,
+    /// Insert transition biomes between climatically impossible neighbours
+    /// (e.g. Sahara touching Snow).
+    pub enforce_adjacency: bool,
+    /// Width in world units of the habitable twilight band, centred on the
+    /// map. `None` keeps the default middle third.
+    pub twilight_width: Option<f64>,
+    /// Cut fjords into cold coasts and raise barrier islands off warm ones.
+    pub coastal_detail: bool,
+    /// Erosion droplets to run over continentalness (0 disables). A few per
+    /// cell carve visible valleys.
+    pub hydraulic_iterations: u32,
+    /// Largest domain-warp offset in world units for the continentalness,
+    /// erosion, humidity and peaks/valleys fBms (0 disables).
+    pub warp_amplitude: f64,
+    /// Frequency of the domain-warp field, per world unit.
+    pub warp_frequency: f64,
+    /// Noise generator each fBm layer is built on.
+    pub bases: LayerBases,
+    /// Scale, octaves and strength of each noise layer.
+    pub layers: LayerParams,
+    /// Where biome classification splits temperature, humidity, elevation
+    /// and erosion.
+    pub biome_thresholds: BiomeThresholds,
+    /// Per-biome resource biases applied on top of each resource's own.
+    pub biomes: BiomeRegistry,
+    /// Depth in world units below the surface of the cave layer's slice.
+    /// `None` uses [`DEFAULT_CAVE_DEPTH`](crate::DEFAULT_CAVE_DEPTH).
+    pub cave_depth: Option<f64>,
+    /// Fraction of the way through the year, in [0.0, 1.0). Libration
+    /// shifts the climate zones back and forth with the season; 0 and 0.5
+    /// are the mean position.
+    pub season: f64,
+    /// Seed volcanoes along colliding plates and over plate-interior
+    /// hotspots, warming and fertilising the land around them.
+    pub volcanoes: bool,
+    /// Hand-drawn continents to shape the map around.
+    pub sketch: Option<Arc<ContinentSketch>>,
+    /// How far continentalness is pulled toward the sketch: 1 replaces the
+    /// noise, 0 ignores the sketch.
+    pub sketch_weight: f64,
+    /// Authored zones forced to a biome after every other biome pass.
+    pub biome_overrides: Vec<BiomeOverride>,
+    /// Hand-sculpted continentalness, added once erosion is done.
+    pub terrain_edits: Arc<TerrainEdits>,
+}
+
+impl GenerationConfig {
+    /// Biome classifier for the macro map: these thresholds, dithered if
+    /// enabled.
+    fn splines(&self, seed: u32) -> BiomeSplines {
+        let splines = BiomeSplines::new(SEA_LEVEL).with_thresholds(self.biome_thresholds);
+        if self.dither_biomes {
+            splines.with_dither(BiomeDither::new(seed))
+        } else {
+            splines
+        }
+    }
+
+    /// Apply the continentalness passes (sketch, equalization, then border
+    /// fade) in place.
+    fn shape_continentalness(&self, continentalness: &mut [f64], width: usize, height: usize) {
+        if let Some(sketch) = &self.sketch {
+            sketch.blend_into(continentalness, width, height, self.sketch_weight);
+        }
+
+        if self.equalize_continentalness {
+            histogram::equalize(continentalness);
+        }
+
+        if self.border_ocean > 0.0 {
+            for y in 0..height {
+                for x in 0..width {
+                    let edge_dist = x.min(y).min(width - 1 - x).min(height - 1 - y) as f64;
+                    if edge_dist >= self.border_ocean {
+                        continue;
+                    }
+                    let t = edge_dist / self.border_ocean;
+                    let fade = t * t * (3.0 - 2.0 * t); // smoothstep
+                    let idx = y * width + x;
+                    continentalness[idx] =
+                        BORDER_OCEAN_FLOOR + (continentalness[idx] - BORDER_OCEAN_FLOOR) * fade;
+                }
+            }
+        }
+    }
+}
+
+/// One `(x, y)` vector per pixel, row-major.
+pub type VectorField = Vec<(LayerFloat, LayerFloat)>;
+
 /// A complete biome map storing noise values and computed biomes.
 ///
 /// This struct holds all the data needed to render different visualization
@@ -58,23 +463,37 @@ pub struct BiomeMap {
     /// Computed biome for each pixel
     pub biomes: Vec<TileType>,
     /// Raw continentalness values for each pixel
-    pub continentalness: Vec<f64>,
+    pub continentalness: Vec<LayerFloat>,
     /// Raw temperature values for each pixel
-    pub temperature: Vec<f64>,
+    pub temperature: Vec<LayerFloat>,
 
     // New terrain layers
     /// Tectonic plate boundary distance (0 = boundary, 1 = center)
-    pub tectonic: Vec<f64>,
+    pub tectonic: Vec<LayerFloat>,
+    /// Plate boundary uplift (1 = colliding plates, -1 = rifting, 0 = neither)
+    pub uplift: Vec<LayerFloat>,
     /// Erosion amount (0-1)
-    pub erosion: Vec<f64>,
+    pub erosion: Vec<LayerFloat>,
     /// Peaks and valleys ridgeline noise (-1 to 1)
-    pub peaks_valleys: Vec<f64>,
+    pub peaks_valleys: Vec<LayerFloat>,
     /// Humidity level (0-1)
-    pub humidity: Vec<f64>,
+    pub humidity: Vec<LayerFloat>,
+    /// Rain falling from the wind's moisture (0-1)
+    pub precipitation: Vec<LayerFloat>,
+    /// Prevailing wind per pixel as (x, y) components, length 0-1
+    pub wind: VectorField,
+    /// Ocean current per pixel as (x, y) components, length 0-1; zero on land
+    pub currents: VectorField,
+    /// Cave density (0 = rock, 1 = open) in a horizontal slice below the surface
+    pub caves: Vec<LayerFloat>,
 
     // Derived maps
     /// River flow accumulation (0-1, higher = larger river)
-    pub rivers: Vec<f64>,
+    pub rivers: Vec<LayerFloat>,
+    /// Lakes in closed basins; their cells are [`TileType::Lake`].
+    pub lakes: Vec<Lake>,
+    /// Volcanic vents, if the macro map placed any.
+    pub volcanoes: Vec<Volcano>,
 
     // Sparse resource map
     pub resources: ResourceMap,
@@ -88,15 +507,26 @@ impl BiomeMap {
     /// * `width` - Map width in pixels (e.g., 1024)
     /// * `height` - Map height in pixels (e.g., 512)
     /// * `backend` - CPU or GPU backend selection
+    /// * `config` - Post-processing options
     pub fn generate_with_backend(
         seed: u32,
         width: usize,
         height: usize,
         backend: NoiseBackend,
+        config: &GenerationConfig,
     ) -> Self {
         match backend {
-            NoiseBackend::Cpu => Self::generate(seed, width, height),
-            NoiseBackend::Gpu => Self::generate_gpu(seed, width, height),
+            // The shaders only know unwarped default OpenSimplex layers, at
+            // the mean season
+            NoiseBackend::Gpu
+                if config.warp_amplitude <= 0.0
+                    && config.bases.is_default()
+                    && config.layers.is_default()
+                    && config.season == 0.0 =>
+            {
+                Self::generate_gpu(seed, width, height, config)
+            }
+            _ => Self::generate_with_config(seed, width, height, config),
         }
     }
 
@@ -107,13 +537,23 @@ impl BiomeMap {
     /// * `width` - Map width in pixels (e.g., 1024)
     /// * `height` - Map height in pixels (e.g., 512)
     pub fn generate(seed: u32, width: usize, height: usize) -> Self {
-        // Create all strategies
-        let cont_strategy = ContinentalnessStrategy::new(seed);
-        let temp_strategy = LatitudeTemperatureStrategy::new(seed.wrapping_add(1), height as f64);
-        let tectonic_strategy = TectonicPlatesStrategy::new(seed.wrapping_add(2));
-        let erosion_strategy = ErosionStrategy::new(seed.wrapping_add(3));
-        let peaks_strategy = PeaksAndValleysStrategy::new(seed.wrapping_add(4));
-        let humidity_strategy = HumidityStrategy::new(seed.wrapping_add(5));
+        Self::generate_with_config(seed, width, height, &GenerationConfig::default())
+    }
+
+    /// Generate a biome map with optional post-processing passes.
+    ///
+    /// # Arguments
+    /// * `seed` - Random seed for noise generation
+    /// * `width` - Map width in pixels (e.g., 1024)
+    /// * `height` - Map height in pixels (e.g., 512)
+    /// * `config` - Post-processing options
+    pub fn generate_with_config(
+        seed: u32,
+        width: usize,
+        height: usize,
+        config: &GenerationConfig,
+    ) -> Self {
+        let strategies = LayerStrategies::for_config(seed, height, config);
 
         let total_pixels = width * height;
 
@@ -129,57 +569,117 @@ impl BiomeMap {
                 let fx = x as f64;
                 let fy = y as f64;
 
-                let cont = cont_strategy.generate(fx, fy, 0);
-                let temp = temp_strategy.generate(fx, fy, 0);
-                let tectonic = tectonic_strategy.generate(fx, fy, 0);
-                let peaks = peaks_strategy.generate(fx, fy, 0);
+                let cont = strategies.continentalness.generate(fx, fy, 0);
+                let temp = strategies.temperature.generate(fx, fy, 0);
+                let tectonic = strategies.tectonic.generate(fx, fy, 0);
+                let peaks = strategies.peaks.generate(fx, fy, 0);
 
                 (cont, temp, tectonic, peaks)
             })
             .collect();
 
+        // Shape before dependent layers so erosion and humidity see the final field
+        let mut continentalness: Vec<f64> = base_data.iter().map(|&(cont, _, _, _)| cont).collect();
+        let mut temperature: Vec<f64> = base_data.iter().map(|&(_, temp, _, _)| temp).collect();
+        config.shape_continentalness(&mut continentalness, width, height);
+        if config.coastal_detail {
+            coastline::shape_coastline(
+                &mut continentalness,
+                &temperature,
+                width,
+                height,
+                SEA_LEVEL,
+                seed,
+            );
+        }
+        if config.hydraulic_iterations > 0 {
+            hydraulic::erode(
+                &mut continentalness,
+                width,
+                height,
+                SEA_LEVEL,
+                config.hydraulic_iterations,
+                seed,
+            );
+        }
+        config.terrain_edits.apply(&mut continentalness, width, height);
+
+        // Mirror independent layers before anything is derived from them
+        let mut tectonic: Vec<f64> = base_data.iter().map(|&(_, _, tect, _)| tect).collect();
+        let mut peaks_valleys: Vec<f64> = base_data.iter().map(|&(_, _, _, peaks)| peaks).collect();
+        let mut uplift = strategies.plate_uplift((0.0, 0.0, 1.0), width, height);
+        for field in [
+            &mut continentalness,
+            &mut temperature,
+            &mut tectonic,
+            &mut uplift,
+            &mut peaks_valleys,
+        ] {
+            config.symmetry.apply(field, width, height);
+        }
+
         // Phase 2: Generate dependent layers (need continentalness)
         let dependent_data: Vec<_> = indices
             .par_iter()
             .enumerate()
             .map(|(idx, &(x, y))| {
-                let (cont, _, _, _) = base_data[idx];
+                let cont = continentalness[idx];
                 let fx = x as f64;
                 let fy = y as f64;
 
-                let erosion = erosion_strategy.generate_with_continentalness(fx, fy, 0, cont);
-                let humidity = humidity_strategy.generate_tidally_locked(fx, fy, 0, cont, height as f64);
-
-                (erosion, humidity)
+                strategies.dependent(fx, fy, 0, cont)
             })
             .collect();
 
-        // Unpack into separate vectors and compute biomes
+        let mut erosion: Vec<f64> = dependent_data.iter().map(|&(eros, _, _)| eros).collect();
+        let mut humidity: Vec<f64> = dependent_data.iter().map(|&(_, humid, _)| humid).collect();
+        let mut precipitation: Vec<f64> =
+            dependent_data.iter().map(|&(_, _, precip)| precip).collect();
+        config.symmetry.apply(&mut erosion, width, height);
+        config.symmetry.apply(&mut humidity, width, height);
+        config.symmetry.apply(&mut precipitation, width, height);
+
+        let volcanoes = if config.volcanoes {
+            volcanoes::place_volcanoes(
+                seed,
+                width,
+                height,
+                &continentalness,
+                &tectonic,
+                &uplift,
+                SEA_LEVEL,
+            )
+        } else {
+            Vec::new()
+        };
+        volcanoes::warm_climate(&volcanoes, &mut temperature, width, height);
+
+        // Compute biomes
         let mut biomes = Vec::with_capacity(total_pixels);
-        let mut continentalness = Vec::with_capacity(total_pixels);
-        let mut temperature = Vec::with_capacity(total_pixels);
-        let mut tectonic = Vec::with_capacity(total_pixels);
-        let mut peaks_valleys = Vec::with_capacity(total_pixels);
-        let mut erosion = Vec::with_capacity(total_pixels);
-        let mut humidity = Vec::with_capacity(total_pixels);
 
         // Use spline-based biome evaluation for consistency with meso tiles
-        let splines = BiomeSplines::new(SEA_LEVEL);
-
-        for (_idx, ((cont, temp, tect, peaks), (eros, humid))) in
-            base_data.iter().zip(dependent_data.iter()).enumerate()
-        {
-            continentalness.push(*cont);
-            temperature.push(*temp);
-            tectonic.push(*tect);
-            peaks_valleys.push(*peaks);
-            erosion.push(*eros);
-            humidity.push(*humid);
+        let splines = config.splines(seed);
 
+        for (idx, &(x, y)) in indices.iter().enumerate() {
             // Determine biome using splines (same as meso tiles)
-            let biome = splines.evaluate(*cont, *temp, *tect, *eros, *peaks, *humid);
+            let biome = splines.evaluate_at(
+                x as i64,
+                y as i64,
+                continentalness[idx],
+                temperature[idx],
+                tectonic[idx],
+                erosion[idx],
+                peaks_valleys[idx],
+                humidity[idx],
+                uplift[idx],
+            );
             biomes.push(biome);
         }
+        // Dither is position-hashed, so copy biomes rather than trusting the inputs
+        config.symmetry.apply(&mut biomes, width, height);
+        if config.enforce_adjacency {
+            adjacency::enforce_adjacency(&mut biomes, width, height);
+        }
 
         // Phase 3: Generate rivers using D8 flow accumulation
         // Compute elevation with tectonic amplification for mountain chains
@@ -187,35 +687,15 @@ impl BiomeMap {
             .iter()
             .zip(peaks_valleys.iter())
             .zip(erosion.iter())
-            .zip(tectonic.iter())
-            .map(|(((&cont, &peaks), &eros), &tect)| {
-                let is_land = cont >= SEA_LEVEL;
-                let boundary = 1.0 - tect; // 1 at boundary, 0 at center
-
-                // Mountains amplified at plate boundaries
-                let tectonic_amp = 1.0 + boundary * boundary * 2.0;
-                let erosion_damp = 1.0 - eros * 0.7;
-
-                let peak_height = if is_land {
-                    peaks.max(0.0) * 0.15 * tectonic_amp * erosion_damp
-                } else {
-                    0.0
-                };
-                let valley_depth = if is_land { peaks.min(0.0).abs() * 0.08 } else { 0.0 };
-
-                // Ocean trenches at convergent boundaries
-                let trench = if !is_land && boundary > 0.7 {
-                    (boundary - 0.7) * 0.5
-                } else {
-                    0.0
-                };
-
-                cont + peak_height - valley_depth - trench
+            .zip(uplift.iter())
+            .map(|(((&cont, &peaks), &eros), &lift)| {
+                terrain_elevation(cont, peaks, eros, lift, SEA_LEVEL)
             })
             .collect();
 
         let river_gen = RiverGenerator::for_map_size(SEA_LEVEL, width, height);
-        let rivers = river_gen.generate(&elevation, width, height);
+        let mut rivers = river_gen.generate(&elevation, width, height);
+        config.symmetry.apply(&mut rivers, width, height);
 
         // Override biomes where rivers flow - only in habitable climate zones
         // No rivers in: ocean, frozen regions (< -10°C), or scorched regions (> 70°C)
@@ -228,41 +708,237 @@ impl BiomeMap {
                 biomes[idx] = TileType::River;
             }
         }
+        let lakes = lakes::flood_lakes(
+            &mut biomes,
+            &elevation,
+            &temperature,
+            width,
+            height,
+            SEA_LEVEL,
+        );
+        volcanoes::mark_craters(&volcanoes, &mut biomes, width, height);
+        biome_override::apply_overrides(&config.biome_overrides, &mut biomes, width, height, (0.0, 0.0, 1.0));
 
         // Phase 4: Generate resources
-        let resources = Self::generate_resources(
+        let mut resources = Self::generate_resources(
             seed,
+            (0.0, 0.0, 1.0),
+            0,
+            true,
             width,
             height,
             &continentalness,
             &tectonic,
             &biomes,
+            &config.biomes,
         );
+        resources.apply_symmetry(config.symmetry);
+        volcanoes::spread_ash(&volcanoes, &mut resources, &biomes, width, height);
+
+        let (wind, currents) =
+            strategies.flow_fields((0.0, 0.0, 1.0), width, height, &continentalness);
+        let mut caves = strategies.cave_slice((0.0, 0.0, 1.0), width, height);
+        config.symmetry.apply(&mut caves, width, height);
 
         Self {
             width,
             height,
             biomes,
-            continentalness,
-            temperature,
-            tectonic,
-            erosion,
-            peaks_valleys,
-            humidity,
-            rivers,
+            continentalness: to_layer(continentalness),
+            temperature: to_layer(temperature),
+            tectonic: to_layer(tectonic),
+            uplift: to_layer(uplift),
+            erosion: to_layer(erosion),
+            peaks_valleys: to_layer(peaks_valleys),
+            humidity: to_layer(humidity),
+            precipitation: to_layer(precipitation),
+            wind,
+            currents,
+            caves: to_layer(caves),
+            rivers: to_layer(rivers),
+            lakes,
+            volcanoes,
             resources,
         }
     }
 
+    /// Biome RGBA image of a whole `width` × `height` world, sampling one
+    /// cell in `factor` along each axis, for seed thumbnails.
+    ///
+    /// Classifies the same fields as [`generate_with_config`](Self::generate_with_config)
+    /// at the sampled cells, but skips everything that needs the full grid:
+    /// coastline and hydraulic erosion, volcanoes, adjacency, rivers and
+    /// lakes. The image is `width.div_ceil(factor)` × `height.div_ceil(factor)`.
+    pub fn thumbnail(
+        seed: u32,
+        width: usize,
+        height: usize,
+        factor: usize,
+        config: &GenerationConfig,
+    ) -> Vec<u8> {
+        assert!(factor > 0, "thumbnail factor must be positive");
+        let strategies = LayerStrategies::for_config(seed, height, config);
+        let (thumb_width, thumb_height) = (width.div_ceil(factor), height.div_ceil(factor));
+        let cell = |idx: usize| ((idx % thumb_width * factor) as f64, (idx / thumb_width * factor) as f64);
+
+        let base_data: Vec<_> = (0..thumb_width * thumb_height)
+            .into_par_iter()
+            .map(|idx| {
+                let (fx, fy) = cell(idx);
+                (
+                    strategies.continentalness.generate(fx, fy, 0),
+                    strategies.temperature.generate(fx, fy, 0),
+                    strategies.tectonic.generate(fx, fy, 0),
+                    strategies.peaks.generate(fx, fy, 0),
+                )
+            })
+            .collect();
+
+        // The ocean border is measured in cells, so shrink it with the grid
+        let shaping = GenerationConfig {
+            border_ocean: config.border_ocean / factor as f64,
+            ..config.clone()
+        };
+        let mut continentalness: Vec<f64> = base_data.iter().map(|&(cont, _, _, _)| cont).collect();
+        shaping.shape_continentalness(&mut continentalness, thumb_width, thumb_height);
+
+        let mut temperature: Vec<f64> = base_data.iter().map(|&(_, temp, _, _)| temp).collect();
+        let mut tectonic: Vec<f64> = base_data.iter().map(|&(_, _, tect, _)| tect).collect();
+        let mut peaks_valleys: Vec<f64> = base_data.iter().map(|&(_, _, _, peaks)| peaks).collect();
+        let mut uplift = strategies.plate_uplift((0.0, 0.0, factor as f64), thumb_width, thumb_height);
+        for field in [
+            &mut continentalness,
+            &mut temperature,
+            &mut tectonic,
+            &mut uplift,
+            &mut peaks_valleys,
+        ] {
+            config.symmetry.apply(field, thumb_width, thumb_height);
+        }
+
+        let dependent_data: Vec<_> = (0..thumb_width * thumb_height)
+            .into_par_iter()
+            .map(|idx| {
+                let (fx, fy) = cell(idx);
+                strategies.dependent(fx, fy, 0, continentalness[idx])
+            })
+            .collect();
+        let mut erosion: Vec<f64> = dependent_data.iter().map(|&(eros, _, _)| eros).collect();
+        let mut humidity: Vec<f64> = dependent_data.iter().map(|&(_, humid, _)| humid).collect();
+        config.symmetry.apply(&mut erosion, thumb_width, thumb_height);
+        config.symmetry.apply(&mut humidity, thumb_width, thumb_height);
+
+        let splines = config.splines(seed);
+        let mut biomes: Vec<TileType> = (0..thumb_width * thumb_height)
+            .map(|idx| {
+                let (fx, fy) = cell(idx);
+                splines.evaluate_at(
+                    fx as i64,
+                    fy as i64,
+                    continentalness[idx],
+                    temperature[idx],
+                    tectonic[idx],
+                    erosion[idx],
+                    peaks_valleys[idx],
+                    humidity[idx],
+                    uplift[idx],
+                )
+            })
+            .collect();
+        config.symmetry.apply(&mut biomes, thumb_width, thumb_height);
+
+        biomes.iter().flat_map(|biome| biome.color()).collect()
+    }
+
+    /// Re-tint the climate for `config.season` without regenerating terrain.
+    ///
+    /// Temperature and humidity are resampled over the map's existing
+    /// continentalness, then the biomes reclassified as by
+    /// [`reclassify_biomes`](Self::reclassify_biomes). `seed` and the rest
+    /// of `config` must match the generation.
+    pub fn apply_season(&mut self, seed: u32, config: &GenerationConfig) {
+        let (width, height) = (self.width, self.height);
+        let strategies = LayerStrategies::for_config(seed, height, config);
+        let continentalness: Vec<f64> = self.continentalness.iter().map(|v| v.to_f64()).collect();
+
+        let climate: Vec<(f64, f64, f64)> = continentalness
+            .par_iter()
+            .enumerate()
+            .map(|(idx, &cont)| {
+                strategies.climate((idx % width) as f64, (idx / width) as f64, cont)
+            })
+            .collect();
+        let mut temperature: Vec<f64> = climate.iter().map(|&(temp, _, _)| temp).collect();
+        let mut humidity: Vec<f64> = climate.iter().map(|&(_, humid, _)| humid).collect();
+        let mut precipitation: Vec<f64> = climate.iter().map(|&(_, _, precip)| precip).collect();
+        for field in [&mut temperature, &mut humidity, &mut precipitation] {
+            config.symmetry.apply(field, width, height);
+        }
+        volcanoes::warm_climate(&self.volcanoes, &mut temperature, width, height);
+
+        self.temperature = to_layer(temperature);
+        self.humidity = to_layer(humidity);
+        self.precipitation = to_layer(precipitation);
+        self.reclassify_biomes(seed, config);
+    }
+
+    /// Re-evaluate every biome from the stored layers, e.g. after editing
+    /// `config.biome_thresholds`.
+    ///
+    /// Rivers and lakes keep their cells, and resources are left as they
+    /// were.
+    pub fn reclassify_biomes(&mut self, seed: u32, config: &GenerationConfig) {
+        let (width, height) = (self.width, self.height);
+        let splines = config.splines(seed);
+        let mut biomes: Vec<TileType> = (0..width * height)
+            .into_par_iter()
+            .map(|idx| {
+                splines.evaluate_at(
+                    (idx % width) as i64,
+                    (idx / width) as i64,
+                    self.continentalness[idx].to_f64(),
+                    self.temperature[idx].to_f64(),
+                    self.tectonic[idx].to_f64(),
+                    self.erosion[idx].to_f64(),
+                    self.peaks_valleys[idx].to_f64(),
+                    self.humidity[idx].to_f64(),
+                    self.uplift[idx].to_f64(),
+                )
+            })
+            .collect();
+        config.symmetry.apply(&mut biomes, width, height);
+        if config.enforce_adjacency {
+            adjacency::enforce_adjacency(&mut biomes, width, height);
+        }
+        for (biome, &old) in biomes.iter_mut().zip(&self.biomes) {
+            if matches!(old, TileType::River | TileType::Lake) {
+                *biome = old;
+            }
+        }
+        biome_override::apply_overrides(&config.biome_overrides, &mut biomes, width, height, (0.0, 0.0, 1.0));
+        self.biomes = biomes;
+    }
+
+    /// Force the biomes inside `overrides` on a map covering `world_size`
+    /// world units from `(world_x, world_y)`, e.g. a meso tile, which is
+    /// generated without a [`GenerationConfig`].
+    pub fn apply_biome_overrides(&mut self, overrides: &[BiomeOverride], world_x: f64, world_y: f64, world_size: f64) {
+        let scale = world_size / self.width as f64;
+        biome_override::apply_overrides(overrides, &mut self.biomes, self.width, self.height, (world_x, world_y, scale));
+    }
+
     /// Generate a biome map using GPU-accelerated noise generation.
     /// Falls back to CPU if GPU is unavailable.
+    ///
+    /// Continentalness passes from `config` are applied after the GPU
+    /// dispatch, so erosion and humidity still see the raw field.
     #[cfg(feature = "gpu")]
-    fn generate_gpu(seed: u32, width: usize, height: usize) -> Self {
+    fn generate_gpu(seed: u32, width: usize, height: usize, config: &GenerationConfig) -> Self {
         use crate::gpu::GpuNoiseContext;
 
         // Try to get GPU context, fallback to CPU if unavailable
         let Some(gpu) = GpuNoiseContext::global() else {
-            return Self::generate(seed, width, height);
+            return Self::generate_with_config(seed, width, height, config);
         };
 
         let total_pixels = width * height;
@@ -280,15 +956,79 @@ impl BiomeMap {
         );
 
         // Convert f32 GPU results to f64
-        let continentalness: Vec<f64> = layers.continentalness.iter().map(|&v| v as f64).collect();
-        let temperature: Vec<f64> = layers.temperature.iter().map(|&v| v as f64).collect();
-        let tectonic: Vec<f64> = layers.tectonic.iter().map(|&v| v as f64).collect();
-        let peaks_valleys: Vec<f64> = layers.peaks_valleys.iter().map(|&v| v as f64).collect();
-        let erosion: Vec<f64> = layers.erosion.iter().map(|&v| v as f64).collect();
-        let humidity: Vec<f64> = layers.humidity.iter().map(|&v| v as f64).collect();
+        let mut continentalness: Vec<f64> = layers.continentalness.iter().map(|&v| v as f64).collect();
+        config.shape_continentalness(&mut continentalness, width, height);
+        let mut temperature: Vec<f64> = layers.temperature.iter().map(|&v| v as f64).collect();
+        if config.coastal_detail {
+            coastline::shape_coastline(
+                &mut continentalness,
+                &temperature,
+                width,
+                height,
+                SEA_LEVEL,
+                seed,
+            );
+        }
+        if config.hydraulic_iterations > 0 {
+            hydraulic::erode(
+                &mut continentalness,
+                width,
+                height,
+                SEA_LEVEL,
+                config.hydraulic_iterations,
+                seed,
+            );
+        }
+        config.terrain_edits.apply(&mut continentalness, width, height);
+        let mut tectonic: Vec<f64> = layers.tectonic.iter().map(|&v| v as f64).collect();
+        let mut peaks_valleys: Vec<f64> = layers.peaks_valleys.iter().map(|&v| v as f64).collect();
+        let mut erosion: Vec<f64> = layers.erosion.iter().map(|&v| v as f64).collect();
+        let mut humidity: Vec<f64> = layers.humidity.iter().map(|&v| v as f64).collect();
+
+        // The shader's humidity only knows the distance to the sea; trace the
+        // wind's moisture here instead
+        let strategies = LayerStrategies::for_config(seed, height, config);
+        let mut uplift = strategies.plate_uplift((0.0, 0.0, 1.0), width, height);
+        let mut precipitation = vec![0.0; width * height];
+        humidity
+            .par_iter_mut()
+            .zip(precipitation.par_iter_mut())
+            .enumerate()
+            .for_each(|(idx, (humid, precip))| {
+                let (wx, wy) = ((idx % width) as f64, (idx / width) as f64);
+                (*humid, *precip) = strategies.moisture(wx, wy, 0, continentalness[idx]);
+            });
+
+        for field in [
+            &mut continentalness,
+            &mut temperature,
+            &mut tectonic,
+            &mut uplift,
+            &mut peaks_valleys,
+            &mut erosion,
+            &mut humidity,
+            &mut precipitation,
+        ] {
+            config.symmetry.apply(field, width, height);
+        }
+
+        let volcanoes = if config.volcanoes {
+            volcanoes::place_volcanoes(
+                seed,
+                width,
+                height,
+                &continentalness,
+                &tectonic,
+                &uplift,
+                SEA_LEVEL,
+            )
+        } else {
+            Vec::new()
+        };
+        volcanoes::warm_climate(&volcanoes, &mut temperature, width, height);
 
         // Compute biomes using splines (same as CPU path)
-        let splines = BiomeSplines::new(SEA_LEVEL);
+        let splines = BiomeSplines::new(SEA_LEVEL).with_thresholds(config.biome_thresholds);
         let mut biomes = Vec::with_capacity(total_pixels);
 
         for idx in 0..total_pixels {
@@ -299,42 +1039,28 @@ impl BiomeMap {
                 erosion[idx],
                 peaks_valleys[idx],
                 humidity[idx],
+                uplift[idx],
             );
             biomes.push(biome);
         }
+        if config.enforce_adjacency {
+            adjacency::enforce_adjacency(&mut biomes, width, height);
+        }
 
         // Generate rivers on CPU (D8 flow requires sequential processing)
         let elevation: Vec<f64> = continentalness
             .iter()
             .zip(peaks_valleys.iter())
             .zip(erosion.iter())
-            .zip(tectonic.iter())
-            .map(|(((&cont, &peaks), &eros), &tect)| {
-                let is_land = cont >= SEA_LEVEL;
-                let boundary = 1.0 - tect;
-
-                let tectonic_amp = 1.0 + boundary * boundary * 2.0;
-                let erosion_damp = 1.0 - eros * 0.7;
-
-                let peak_height = if is_land {
-                    peaks.max(0.0) * 0.15 * tectonic_amp * erosion_damp
-                } else {
-                    0.0
-                };
-                let valley_depth = if is_land { peaks.min(0.0).abs() * 0.08 } else { 0.0 };
-
-                let trench = if !is_land && boundary > 0.7 {
-                    (boundary - 0.7) * 0.5
-                } else {
-                    0.0
-                };
-
-                cont + peak_height - valley_depth - trench
+            .zip(uplift.iter())
+            .map(|(((&cont, &peaks), &eros), &lift)| {
+                terrain_elevation(cont, peaks, eros, lift, SEA_LEVEL)
             })
             .collect();
 
         let river_gen = RiverGenerator::for_map_size(SEA_LEVEL, width, height);
-        let rivers = river_gen.generate(&elevation, width, height);
+        let mut rivers = river_gen.generate(&elevation, width, height);
+        config.symmetry.apply(&mut rivers, width, height);
 
         // Override biomes where rivers flow
         for idx in 0..total_pixels {
@@ -346,117 +1072,278 @@ impl BiomeMap {
                 biomes[idx] = TileType::River;
             }
         }
+        let lakes = lakes::flood_lakes(
+            &mut biomes,
+            &elevation,
+            &temperature,
+            width,
+            height,
+            SEA_LEVEL,
+        );
+        volcanoes::mark_craters(&volcanoes, &mut biomes, width, height);
+        biome_override::apply_overrides(&config.biome_overrides, &mut biomes, width, height, (0.0, 0.0, 1.0));
 
         // Generate resources on CPU
-        let resources = Self::generate_resources(
+        let mut resources = Self::generate_resources(
             seed,
+            (0.0, 0.0, 1.0),
+            0,
+            true,
             width,
             height,
             &continentalness,
             &tectonic,
             &biomes,
+            &config.biomes,
         );
+        resources.apply_symmetry(config.symmetry);
+        volcanoes::spread_ash(&volcanoes, &mut resources, &biomes, width, height);
+
+        let (wind, currents) =
+            strategies.flow_fields((0.0, 0.0, 1.0), width, height, &continentalness);
+        let mut caves = strategies.cave_slice((0.0, 0.0, 1.0), width, height);
+        config.symmetry.apply(&mut caves, width, height);
 
         Self {
             width,
             height,
             biomes,
-            continentalness,
-            temperature,
-            tectonic,
-            erosion,
-            peaks_valleys,
-            humidity,
-            rivers,
+            continentalness: to_layer(continentalness),
+            temperature: to_layer(temperature),
+            tectonic: to_layer(tectonic),
+            uplift: to_layer(uplift),
+            erosion: to_layer(erosion),
+            peaks_valleys: to_layer(peaks_valleys),
+            humidity: to_layer(humidity),
+            precipitation: to_layer(precipitation),
+            wind,
+            currents,
+            caves: to_layer(caves),
+            rivers: to_layer(rivers),
+            lakes,
+            volcanoes,
             resources,
         }
     }
 
     /// GPU generation stub when gpu feature is disabled.
     #[cfg(not(feature = "gpu"))]
-    fn generate_gpu(seed: u32, width: usize, height: usize) -> Self {
+    fn generate_gpu(seed: u32, width: usize, height: usize, config: &GenerationConfig) -> Self {
         // GPU feature not enabled, fallback to CPU
-        Self::generate(seed, width, height)
+        Self::generate_with_config(seed, width, height, config)
     }
 
     /// Generate resources for all resource types.
+    ///
+    /// Noise is sampled in world coordinates (`world_x + px * scale`, same as
+    /// the terrain layers), so a deposit lands on the same spot of the world
+    /// whatever resolution the map is generated at.
+    ///
+    /// # Arguments
+    /// * `region` - `(world_x, world_y, scale)` mapping pixels to world units
+    /// * `detail_level` - Noise detail level
+    /// * `match_density` - Pick each resource's cutoff so it covers
+    ///   [`ResourceType::target_density`] of this map. The cutoff then depends
+    ///   on what the map covers, so partial regions that must agree with
+    ///   each other use [`DEFAULT_RESOURCE_THRESHOLD`] instead.
+    #[allow(clippy::too_many_arguments)]
     fn generate_resources(
         seed: u32,
+        region: (f64, f64, f64),
+        detail_level: u32,
+        match_density: bool,
         width: usize,
         height: usize,
         continentalness: &[f64],
         tectonic: &[f64],
         biomes: &[TileType],
+        registry: &BiomeRegistry,
     ) -> ResourceMap {
+        let (world_x, world_y, scale) = region;
         let mut resources = ResourceMap::new(width, height);
 
-        // Generate each resource type
         for resource_type in ResourceType::all() {
             let strategy = ResourceNoiseStrategy::new(seed, *resource_type);
 
-            for y in 0..height {
-                for x in 0..width {
-                    let idx = y * width + x;
-                    let context = ResourceContext {
-                        continentalness: continentalness[idx],
-                        tectonic_boundary_distance: tectonic[idx],
-                        water_distance: if continentalness[idx] < SEA_LEVEL {
-                            0.0
-                        } else {
-                            ((continentalness[idx] + 0.025) * 5.0).min(1.0)
-                        },
-                        biome: biomes[idx],
-                    };
-
-                    let abundance =
-                        strategy.generate_with_context(x as f64, y as f64, 0, &context);
-                    if abundance > 0.01 {
-                        resources.set(x, y, *resource_type, abundance as f32);
-                    }
-                }
+            let values: Vec<f64> = (0..width * height)
+                .map(|idx| {
+                    let context = ResourceContext::from_terrain(
+                        continentalness[idx],
+                        tectonic[idx],
+                        biomes[idx],
+                        SEA_LEVEL,
+                    );
+                    let wx = world_x + ((idx % width) as f64 * scale);
+                    let wy = world_y + ((idx / width) as f64 * scale);
+                    let bias = registry.resource_bias(biomes[idx], *resource_type);
+                    strategy.biased_value(wx, wy, detail_level, &context) * bias
+                })
+                .collect();
+
+            let threshold = if match_density {
+                density_threshold(&values, resource_type.target_density())
+            } else {
+                DEFAULT_RESOURCE_THRESHOLD
+            };
+            resources.set_threshold(*resource_type, threshold);
+            for (idx, &value) in values.iter().enumerate() {
+                let abundance = ResourceNoiseStrategy::abundance(value, threshold);
+                resources.set(idx % width, idx / width, *resource_type, abundance as f32);
             }
         }
 
         resources
     }
 
+    /// Regenerate resources inside `[x0, x1) × [y0, y1)` after the biomes
+    /// there were edited. See [`ResourceMap::recompute_rect`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn recompute_resources_rect(
+        &mut self,
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+        seed: u32,
+        registry: &BiomeRegistry,
+    ) {
+        let mut resources = std::mem::take(&mut self.resources);
+        resources.recompute_rect(self, x0, y0, x1, y1, seed, registry);
+        self.resources = resources;
+    }
+
+    /// Whether `layer` would show anything on this map.
+    ///
+    /// Resource layers are empty when no deposits of that kind exist (meso
+    /// tiles carry no resources at all) and the river layer when no river was
+    /// traced. Every other layer always has data.
+    pub fn has_layer_data(&self, layer: NoiseLayer) -> bool {
+        match layer.to_resource_type() {
+            Some(resource) => self.resources.contains(resource),
+            None if layer == NoiseLayer::Rivers => self.rivers.iter().any(|&r| r > 0.0),
+            None => true,
+        }
+    }
+
+    /// The layer after `current` in [`NoiseLayer::all`] order that has data on
+    /// this map, wrapping around. Returns `current` if no other layer does.
+    pub fn next_nonempty_layer(&self, current: NoiseLayer) -> NoiseLayer {
+        next_layer_where(current, |layer| self.has_layer_data(layer))
+    }
+
     /// Convert any layer to RGBA image bytes.
     pub fn to_layer_image(&self, layer: NoiseLayer) -> Vec<u8> {
         let mut data = Vec::with_capacity(self.width * self.height * 4);
+        let derived = self.derived_layer_values(layer);
 
         for y in 0..self.height {
             for x in 0..self.width {
-                let idx = y * self.width + x;
-                let color = match layer {
-                    NoiseLayer::Aggregate => self.biomes[idx].color(),
-                    NoiseLayer::Continentalness => {
-                        grayscale_to_rgba(self.continentalness[idx], -1.0, 1.0)
-                    }
-                    NoiseLayer::Temperature => temperature_to_rgba(self.temperature[idx]),
-                    NoiseLayer::Tectonic => tectonic_to_rgba(self.tectonic[idx]),
-                    NoiseLayer::Erosion => grayscale_to_rgba(self.erosion[idx], 0.0, 1.0),
-                    NoiseLayer::PeaksValleys => peaks_to_rgba(self.peaks_valleys[idx]),
-                    NoiseLayer::Humidity => humidity_to_rgba(self.humidity[idx]),
-                    NoiseLayer::Rivers => river_to_rgba(self.rivers[idx]),
-                    _ if layer.is_resource() => {
-                        let resource = layer.to_resource_type().unwrap();
-                        let abundance = self.resources.get(x, y, resource) as f64;
-                        resource_to_rgba(abundance, resource)
-                    }
-                    _ => [128, 128, 128, 255],
-                };
-                data.extend_from_slice(&color);
+                data.extend_from_slice(&self.layer_color(layer, x, y, &derived));
             }
         }
 
         data
     }
 
+    /// Redraw `[x0, x1) × [y0, y1)` of an image produced by
+    /// [`BiomeMap::to_layer_image`] after the biomes there were edited.
+    ///
+    /// Rainfall and flow are derived from elevation alone, which biome edits
+    /// leave unchanged, so those layers are left as they are.
+    pub fn patch_layer_image(
+        &self,
+        layer: NoiseLayer,
+        data: &mut [u8],
+        (x0, y0, x1, y1): (usize, usize, usize, usize),
+    ) {
+        if matches!(layer, NoiseLayer::Rainfall | NoiseLayer::FlowAccumulation) {
+            return;
+        }
+
+        for y in y0..y1.min(self.height) {
+            for x in x0..x1.min(self.width) {
+                let offset = (y * self.width + x) * 4;
+                data[offset..offset + 4].copy_from_slice(&self.layer_color(layer, x, y, &[]));
+            }
+        }
+    }
+
+    /// Per-cell values for layers computed over the whole map at once, ready
+    /// for [`BiomeMap::layer_color`]. Empty for layers stored per cell.
+    fn derived_layer_values(&self, layer: NoiseLayer) -> Vec<f64> {
+        match layer {
+            NoiseLayer::Rainfall => self.rainfall(),
+            NoiseLayer::FlowAccumulation => {
+                let accumulation = self.flow_accumulation();
+                let log_max = (*accumulation.iter().max().unwrap_or(&1) as f64).ln().max(1.0);
+                accumulation.iter().map(|&a| (a as f64).ln() / log_max).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Color of one cell in `layer`. `derived` must hold
+    /// [`BiomeMap::derived_layer_values`] for whole-map layers.
+    fn layer_color(&self, layer: NoiseLayer, x: usize, y: usize, derived: &[f64]) -> [u8; 4] {
+        let idx = y * self.width + x;
+        match layer {
+            NoiseLayer::Aggregate => self.biomes[idx].color(),
+            NoiseLayer::Continentalness => grayscale_to_rgba(self.continentalness[idx].to_f64(), -1.0, 1.0),
+            NoiseLayer::Temperature => temperature_to_rgba(self.temperature[idx].to_f64()),
+            NoiseLayer::Tectonic => tectonic_to_rgba(self.tectonic[idx].to_f64()),
+            NoiseLayer::Erosion => grayscale_to_rgba(self.erosion[idx].to_f64(), 0.0, 1.0),
+            NoiseLayer::PeaksValleys => peaks_to_rgba(self.peaks_valleys[idx].to_f64()),
+            NoiseLayer::Humidity => humidity_to_rgba(self.humidity[idx].to_f64()),
+            NoiseLayer::Rivers => river_to_rgba(self.rivers[idx].to_f64()),
+            NoiseLayer::Rainfall => rainfall_to_rgba(derived[idx]),
+            NoiseLayer::FlowAccumulation => flow_to_rgba(derived[idx]),
+            NoiseLayer::Climate => self.climate_zone(x, y).unwrap().color(),
+            NoiseLayer::Caves => cave_to_rgba(self.caves[idx].to_f64()),
+            _ if layer.is_resource() => {
+                let resource = layer.to_resource_type().unwrap();
+                let abundance = self.resources.get(x, y, resource) as f64;
+                resource_to_rgba(abundance, resource)
+            }
+            _ => [128, 128, 128, 255],
+        }
+    }
+
     /// Convert biome data to RGBA image bytes.
     pub fn to_biome_image(&self) -> Vec<u8> {
         self.to_layer_image(NoiseLayer::Aggregate)
     }
 
+    /// Biome RGBA image shrunk by `factor` along each axis, for thumbnails and
+    /// the minimap.
+    ///
+    /// Each output pixel takes the color of the most common biome in its
+    /// `factor × factor` block (ties go to the earlier [`PALETTE`] entry).
+    /// The image is `width.div_ceil(factor)` × `height.div_ceil(factor)`;
+    /// blocks on the far edges may be partial.
+    pub fn downscale(&self, factor: usize) -> Vec<u8> {
+        assert!(factor > 0, "downscale factor must be positive");
+        let out_width = self.width.div_ceil(factor);
+        let out_height = self.height.div_ceil(factor);
+        let mut data = Vec::with_capacity(out_width * out_height * 4);
+
+        for by in 0..out_height {
+            for bx in 0..out_width {
+                let mut counts = [0u32; PALETTE.len()];
+                for y in by * factor..((by + 1) * factor).min(self.height) {
+                    for x in bx * factor..((bx + 1) * factor).min(self.width) {
+                        counts[self.biomes[y * self.width + x].index()] += 1;
+                    }
+                }
+                // max_by_key keeps the last maximum, so scan in reverse
+                let (majority, _) =
+                    counts.iter().enumerate().rev().max_by_key(|&(_, &count)| count).unwrap();
+                data.extend_from_slice(&TileType::from_index(majority).unwrap().color());
+            }
+        }
+
+        data
+    }
+
     /// Convert temperature data to RGBA image bytes (blue-to-red gradient).
     pub fn to_temperature_image(&self) -> Vec<u8> {
         self.to_layer_image(NoiseLayer::Temperature)
@@ -476,10 +1363,51 @@ impl BiomeMap {
         }
     }
 
+    /// Cell under a world-space position, for a map drawn `width_extent` ×
+    /// `height_extent` world units wide and centered on the origin.
+    ///
+    /// The extents need not match the map resolution; a half-resolution
+    /// preview drawn at full size scales accordingly. `None` off the map.
+    pub fn world_to_cell(
+        &self,
+        world_pos: Vec2,
+        width_extent: f32,
+        height_extent: f32,
+    ) -> Option<(usize, usize)> {
+        let map_pos = world_to_map(world_pos, Vec2::new(width_extent, height_extent));
+        if map_pos.x < 0.0 || map_pos.y < 0.0 {
+            return None;
+        }
+        let x = (map_pos.x * self.width as f32 / width_extent) as usize;
+        let y = (map_pos.y * self.height as f32 / height_extent) as usize;
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /// World-space position of the center of cell `(x, y)`; the inverse of
+    /// [`BiomeMap::world_to_cell`].
+    pub fn cell_to_world(&self, x: usize, y: usize, width_extent: f32, height_extent: f32) -> Vec2 {
+        let map_pos = Vec2::new(
+            (x as f32 + 0.5) * width_extent / self.width as f32,
+            (y as f32 + 0.5) * height_extent / self.height as f32,
+        );
+        map_to_world(map_pos, Vec2::new(width_extent, height_extent))
+    }
+
+    /// Biome under a world-space position. See [`BiomeMap::world_to_cell`].
+    pub fn biome_at_world(
+        &self,
+        world_pos: Vec2,
+        width_extent: f32,
+        height_extent: f32,
+    ) -> Option<TileType> {
+        let (x, y) = self.world_to_cell(world_pos, width_extent, height_extent)?;
+        self.get_biome(x, y)
+    }
+
     /// Get temperature at specific coordinates.
     pub fn get_temperature(&self, x: usize, y: usize) -> Option<f64> {
         if x < self.width && y < self.height {
-            Some(self.temperature[y * self.width + x])
+            Some(self.temperature[y * self.width + x].to_f64())
         } else {
             None
         }
@@ -488,7 +1416,7 @@ impl BiomeMap {
     /// Get continentalness at specific coordinates.
     pub fn get_continentalness(&self, x: usize, y: usize) -> Option<f64> {
         if x < self.width && y < self.height {
-            Some(self.continentalness[y * self.width + x])
+            Some(self.continentalness[y * self.width + x].to_f64())
         } else {
             None
         }
@@ -497,7 +1425,7 @@ impl BiomeMap {
     /// Get tectonic boundary distance at specific coordinates.
     pub fn get_tectonic(&self, x: usize, y: usize) -> Option<f64> {
         if x < self.width && y < self.height {
-            Some(self.tectonic[y * self.width + x])
+            Some(self.tectonic[y * self.width + x].to_f64())
         } else {
             None
         }
@@ -506,7 +1434,7 @@ impl BiomeMap {
     /// Get erosion at specific coordinates.
     pub fn get_erosion(&self, x: usize, y: usize) -> Option<f64> {
         if x < self.width && y < self.height {
-            Some(self.erosion[y * self.width + x])
+            Some(self.erosion[y * self.width + x].to_f64())
         } else {
             None
         }
@@ -515,7 +1443,7 @@ impl BiomeMap {
     /// Get peaks/valleys value at specific coordinates.
     pub fn get_peaks_valleys(&self, x: usize, y: usize) -> Option<f64> {
         if x < self.width && y < self.height {
-            Some(self.peaks_valleys[y * self.width + x])
+            Some(self.peaks_valleys[y * self.width + x].to_f64())
         } else {
             None
         }
@@ -524,7 +1452,16 @@ impl BiomeMap {
     /// Get humidity at specific coordinates.
     pub fn get_humidity(&self, x: usize, y: usize) -> Option<f64> {
         if x < self.width && y < self.height {
-            Some(self.humidity[y * self.width + x])
+            Some(self.humidity[y * self.width + x].to_f64())
+        } else {
+            None
+        }
+    }
+
+    /// Get precipitation at specific coordinates.
+    pub fn get_precipitation(&self, x: usize, y: usize) -> Option<f64> {
+        if x < self.width && y < self.height {
+            Some(self.precipitation[y * self.width + x].to_f64())
         } else {
             None
         }
@@ -533,12 +1470,21 @@ impl BiomeMap {
     /// Get river flow at specific coordinates.
     pub fn get_river(&self, x: usize, y: usize) -> Option<f64> {
         if x < self.width && y < self.height {
-            Some(self.rivers[y * self.width + x])
+            Some(self.rivers[y * self.width + x].to_f64())
         } else {
             None
         }
     }
 
+    /// River flow at a cell, or 0.0 off the map or when the map carries no
+    /// river field (e.g. one assembled by hand).
+    pub fn river_flow(&self, x: usize, y: usize) -> f64 {
+        if self.rivers.len() != self.width * self.height {
+            return 0.0;
+        }
+        self.get_river(x, y).unwrap_or(0.0)
+    }
+
     /// Generate a meso-level (zoomed in) biome map for a specific world region.
     /// Note: This is a simplified version that only generates basic layers.
     pub fn generate_region(
@@ -550,17 +1496,12 @@ impl BiomeMap {
         world_height: f64,
         detail_level: u32,
     ) -> Self {
-        let cont_strategy = ContinentalnessStrategy::new(seed);
-        let temp_strategy =
-            LatitudeTemperatureStrategy::new(seed.wrapping_add(1), world_height);
-        let tectonic_strategy = TectonicPlatesStrategy::new(seed.wrapping_add(2));
-        let erosion_strategy = ErosionStrategy::new(seed.wrapping_add(3));
-        let peaks_strategy = PeaksAndValleysStrategy::new(seed.wrapping_add(4));
-        let humidity_strategy = HumidityStrategy::new(seed.wrapping_add(5));
+        let strategies = LayerStrategies::new(seed, world_height);
         let splines = BiomeSplines::new(SEA_LEVEL);
 
         let total_pixels = output_size * output_size;
         let scale = world_size / output_size as f64;
+        let uplift = strategies.plate_uplift((world_x, world_y, scale), output_size, output_size);
 
         let mut biomes = Vec::with_capacity(total_pixels);
         let mut continentalness = Vec::with_capacity(total_pixels);
@@ -569,21 +1510,19 @@ impl BiomeMap {
         let mut erosion = Vec::with_capacity(total_pixels);
         let mut peaks_valleys = Vec::with_capacity(total_pixels);
         let mut humidity = Vec::with_capacity(total_pixels);
+        let mut precipitation = Vec::with_capacity(total_pixels);
 
         for py in 0..output_size {
             for px in 0..output_size {
                 let wx = world_x + (px as f64 * scale);
                 let wy = world_y + (py as f64 * scale);
 
-                let cont = cont_strategy.generate(wx, wy, detail_level);
-                let temp = temp_strategy.generate(wx, wy, detail_level);
-                let tect = tectonic_strategy.generate(wx, wy, detail_level);
-                let peaks = peaks_strategy.generate(wx, wy, detail_level);
-                let eros = erosion_strategy.generate_with_continentalness(wx, wy, detail_level, cont);
-                let humid = humidity_strategy.generate_tidally_locked(wx, wy, detail_level, cont, world_height);
+                let (cont, temp, tect, peaks, eros, humid, precip) =
+                    strategies.sample(wx, wy, detail_level);
+                let lift = uplift[py * output_size + px];
 
                 // Use splines for consistency with macro map and generate_meso_full
-                let biome = splines.evaluate(cont, temp, tect, eros, peaks, humid);
+                let biome = splines.evaluate(cont, temp, tect, eros, peaks, humid, lift);
 
                 continentalness.push(cont);
                 temperature.push(temp);
@@ -591,6 +1530,7 @@ impl BiomeMap {
                 peaks_valleys.push(peaks);
                 erosion.push(eros);
                 humidity.push(humid);
+                precipitation.push(precip);
                 biomes.push(biome);
             }
         }
@@ -600,28 +1540,9 @@ impl BiomeMap {
             .iter()
             .zip(peaks_valleys.iter())
             .zip(erosion.iter())
-            .zip(tectonic.iter())
-            .map(|(((&cont, &peaks), &eros), &tect)| {
-                let is_land = cont >= SEA_LEVEL;
-                let boundary = 1.0 - tect;
-
-                let tectonic_amp = 1.0 + boundary * boundary * 2.0;
-                let erosion_damp = 1.0 - eros * 0.7;
-
-                let peak_height = if is_land {
-                    peaks.max(0.0) * 0.15 * tectonic_amp * erosion_damp
-                } else {
-                    0.0
-                };
-                let valley_depth = if is_land { peaks.min(0.0).abs() * 0.08 } else { 0.0 };
-
-                let trench = if !is_land && boundary > 0.7 {
-                    (boundary - 0.7) * 0.5
-                } else {
-                    0.0
-                };
-
-                cont + peak_height - valley_depth - trench
+            .zip(uplift.iter())
+            .map(|(((&cont, &peaks), &eros), &lift)| {
+                terrain_elevation(cont, peaks, eros, lift, SEA_LEVEL)
             })
             .collect();
 
@@ -638,24 +1559,61 @@ impl BiomeMap {
                 biomes[idx] = TileType::River;
             }
         }
+        let lakes = lakes::flood_lakes(
+            &mut biomes,
+            &elevation,
+            &temperature,
+            output_size,
+            output_size,
+            SEA_LEVEL,
+        );
+
+        let resources = Self::generate_resources(
+            seed,
+            (world_x, world_y, scale),
+            detail_level,
+            false,
+            output_size,
+            output_size,
+            &continentalness,
+            &tectonic,
+            &biomes,
+            &BiomeRegistry::default(),
+        );
+
+        let (wind, currents) = strategies.flow_fields(
+            (world_x, world_y, scale),
+            output_size,
+            output_size,
+            &continentalness,
+        );
+        let caves = strategies.cave_slice((world_x, world_y, scale), output_size, output_size);
 
         Self {
             width: output_size,
             height: output_size,
             biomes,
-            continentalness,
-            temperature,
-            tectonic,
-            erosion,
-            peaks_valleys,
-            humidity,
-            rivers,
-            resources: ResourceMap::new(output_size, output_size),
+            continentalness: to_layer(continentalness),
+            temperature: to_layer(temperature),
+            tectonic: to_layer(tectonic),
+            uplift: to_layer(uplift),
+            erosion: to_layer(erosion),
+            peaks_valleys: to_layer(peaks_valleys),
+            humidity: to_layer(humidity),
+            precipitation: to_layer(precipitation),
+            wind,
+            currents,
+            caves: to_layer(caves),
+            rivers: to_layer(rivers),
+            lakes,
+            volcanoes: Vec::new(),
+            resources,
         }
     }
 
     /// Fast biome-only generation for meso tiles.
-    /// Only computes continentalness, temperature, and biome - skips all other layers.
+    /// Samples the terrain layers and classifies with the same splines as the
+    /// full paths, but skips rivers and resources and keeps no layer data.
     pub fn generate_biome_only(
         seed: u32,
         world_x: f64,
@@ -665,9 +1623,8 @@ impl BiomeMap {
         world_height: f64,
         detail_level: u32,
     ) -> Vec<u8> {
-        let cont_strategy = ContinentalnessStrategy::new(seed);
-        let temp_strategy =
-            LatitudeTemperatureStrategy::new(seed.wrapping_add(1), world_height);
+        let strategies = LayerStrategies::new(seed, world_height);
+        let splines = BiomeSplines::new(SEA_LEVEL);
 
         let total_pixels = output_size * output_size;
         let scale = world_size / output_size as f64;
@@ -678,9 +1635,9 @@ impl BiomeMap {
                 let wx = world_x + (px as f64 * scale);
                 let wy = world_y + (py as f64 * scale);
 
-                let cont = cont_strategy.generate(wx, wy, detail_level);
-                let temp = temp_strategy.generate(wx, wy, detail_level);
-                let biome = TileType::from_climate(cont, temp, SEA_LEVEL);
+                let (cont, temp, tect, peaks, eros, humid, _) = strategies.sample(wx, wy, detail_level);
+                let lift = strategies.tectonic.boundary(wx, wy).uplift();
+                let biome = splines.evaluate(cont, temp, tect, eros, peaks, humid, lift);
 
                 image_data.extend_from_slice(&biome.color());
             }
@@ -702,6 +1659,9 @@ impl BiomeMap {
     /// * `world_height` - Total world height (for latitude-based temperature)
     /// * `detail_level` - Noise detail level (0=macro, 1=meso, 2=micro)
     /// * `progress` - Shared progress tracker for UI updates
+    /// * `normalization` - Macro baseline shared by all tiles; without one the
+    ///   tile uses its raw noise and ignores how the macro map was shaped
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_meso_full(
         seed: u32,
         world_x: f64,
@@ -711,18 +1671,15 @@ impl BiomeMap {
         world_height: f64,
         detail_level: u32,
         progress: &Arc<LayerProgress>,
+        normalization: Option<Arc<NormalizationContext>>,
     ) -> Self {
-        // Create all strategies
-        let cont_strategy = ContinentalnessStrategy::new(seed);
-        let temp_strategy = LatitudeTemperatureStrategy::new(seed.wrapping_add(1), world_height);
-        let tectonic_strategy = TectonicPlatesStrategy::new(seed.wrapping_add(2));
-        let erosion_strategy = ErosionStrategy::new(seed.wrapping_add(3));
-        let peaks_strategy = PeaksAndValleysStrategy::new(seed.wrapping_add(4));
-        let humidity_strategy = HumidityStrategy::new(seed.wrapping_add(5));
+        let strategies =
+            LayerStrategies::new(seed, world_height).with_normalization(normalization);
         let splines = BiomeSplines::new(SEA_LEVEL);
 
         let total_pixels = output_size * output_size;
         let scale = world_size / output_size as f64;
+        let uplift = strategies.plate_uplift((world_x, world_y, scale), output_size, output_size);
 
         // Progress chunk size - update every ~1% or 256 pixels minimum
         let progress_chunk = (total_pixels / 100).max(256);
@@ -736,29 +1693,26 @@ impl BiomeMap {
         let all_data: Vec<_> = indices
             .par_chunks(progress_chunk)
             .flat_map_iter(|chunk| {
-                let mut results = Vec::with_capacity(chunk.len());
-
-                for &idx in chunk {
-                    let py = idx / output_size;
-                    let px = idx % output_size;
-                    let wx = world_x + (px as f64 * scale);
-                    let wy = world_y + (py as f64 * scale);
-
-                    // Generate base layers
-                    let cont = cont_strategy.generate(wx, wy, detail_level);
-                    let temp = temp_strategy.generate(wx, wy, detail_level);
-                    let tect = tectonic_strategy.generate(wx, wy, detail_level);
-                    let peaks = peaks_strategy.generate(wx, wy, detail_level);
-
-                    // Generate dependent layers
-                    let eros = erosion_strategy.generate_with_continentalness(wx, wy, detail_level, cont);
-                    let humid = humidity_strategy.generate_tidally_locked(wx, wy, detail_level, cont, world_height);
-
-                    // Compute biome using splines
-                    let biome = splines.evaluate(cont, temp, tect, eros, peaks, humid);
-
-                    results.push((cont, temp, tect, peaks, eros, humid, biome));
-                }
+                let points: Vec<(f64, f64)> = chunk
+                    .iter()
+                    .map(|&idx| {
+                        let py = idx / output_size;
+                        let px = idx % output_size;
+                        (world_x + (px as f64 * scale), world_y + (py as f64 * scale))
+                    })
+                    .collect();
+
+                // Compute biome using splines
+                let results: Vec<_> = strategies
+                    .sample_batch(&points, detail_level)
+                    .into_iter()
+                    .zip(chunk)
+                    .map(|((cont, temp, tect, peaks, eros, humid, precip), &idx)| {
+                        let lift = uplift[idx];
+                        let biome = splines.evaluate(cont, temp, tect, eros, peaks, humid, lift);
+                        (cont, temp, tect, peaks, eros, humid, precip, biome)
+                    })
+                    .collect();
 
                 // Update progress for all layers
                 let n = chunk.len();
@@ -768,7 +1722,6 @@ impl BiomeMap {
                 progress.increment(LayerId::PeaksValleys, n);
                 progress.increment(LayerId::Erosion, n);
                 progress.increment(LayerId::Humidity, n);
-                progress.increment(LayerId::Resources, n);
 
                 results
             })
@@ -782,14 +1735,16 @@ impl BiomeMap {
         let mut peaks_valleys = Vec::with_capacity(total_pixels);
         let mut erosion = Vec::with_capacity(total_pixels);
         let mut humidity = Vec::with_capacity(total_pixels);
+        let mut precipitation = Vec::with_capacity(total_pixels);
 
-        for (cont, temp, tect, peaks, eros, humid, biome) in all_data {
+        for (cont, temp, tect, peaks, eros, humid, precip, biome) in all_data {
             continentalness.push(cont);
             temperature.push(temp);
             tectonic.push(tect);
             peaks_valleys.push(peaks);
             erosion.push(eros);
             humidity.push(humid);
+            precipitation.push(precip);
             biomes.push(biome);
         }
 
@@ -798,28 +1753,9 @@ impl BiomeMap {
             .iter()
             .zip(peaks_valleys.iter())
             .zip(erosion.iter())
-            .zip(tectonic.iter())
-            .map(|(((&cont, &peaks), &eros), &tect)| {
-                let is_land = cont >= SEA_LEVEL;
-                let boundary = 1.0 - tect;
-
-                let tectonic_amp = 1.0 + boundary * boundary * 2.0;
-                let erosion_damp = 1.0 - eros * 0.7;
-
-                let peak_height = if is_land {
-                    peaks.max(0.0) * 0.15 * tectonic_amp * erosion_damp
-                } else {
-                    0.0
-                };
-                let valley_depth = if is_land { peaks.min(0.0).abs() * 0.08 } else { 0.0 };
-
-                let trench = if !is_land && boundary > 0.7 {
-                    (boundary - 0.7) * 0.5
-                } else {
-                    0.0
-                };
-
-                cont + peak_height - valley_depth - trench
+            .zip(uplift.iter())
+            .map(|(((&cont, &peaks), &eros), &lift)| {
+                terrain_elevation(cont, peaks, eros, lift, SEA_LEVEL)
             })
             .collect();
 
@@ -836,21 +1772,45 @@ impl BiomeMap {
                 biomes[idx] = TileType::River;
             }
         }
+        let lakes = lakes::flood_lakes(
+            &mut biomes,
+            &elevation,
+            &temperature,
+            output_size,
+            output_size,
+            SEA_LEVEL,
+        );
 
         // Skip resource generation for meso tiles (too expensive, sparse anyway)
         let resources = ResourceMap::new(output_size, output_size);
+        progress.skip(LayerId::Resources);
+
+        let (wind, currents) = strategies.flow_fields(
+            (world_x, world_y, scale),
+            output_size,
+            output_size,
+            &continentalness,
+        );
+        let caves = strategies.cave_slice((world_x, world_y, scale), output_size, output_size);
 
         Self {
             width: output_size,
             height: output_size,
             biomes,
-            continentalness,
-            temperature,
-            tectonic,
-            erosion,
-            peaks_valleys,
-            humidity,
-            rivers,
+            continentalness: to_layer(continentalness),
+            temperature: to_layer(temperature),
+            tectonic: to_layer(tectonic),
+            uplift: to_layer(uplift),
+            erosion: to_layer(erosion),
+            peaks_valleys: to_layer(peaks_valleys),
+            humidity: to_layer(humidity),
+            precipitation: to_layer(precipitation),
+            wind,
+            currents,
+            caves: to_layer(caves),
+            rivers: to_layer(rivers),
+            lakes,
+            volcanoes: Vec::new(),
             resources,
         }
     }
@@ -866,6 +1826,8 @@ impl BiomeMap {
     /// * `detail_level` - Noise detail level (0=macro, 1=meso, 2=micro)
     /// * `progress` - Shared progress tracker for UI updates
     /// * `backend` - CPU or GPU backend selection
+    /// * `normalization` - Macro baseline shared by all tiles
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_meso_full_with_backend(
         seed: u32,
         world_x: f64,
@@ -876,6 +1838,7 @@ impl BiomeMap {
         detail_level: u32,
         progress: &Arc<LayerProgress>,
         backend: NoiseBackend,
+        normalization: Option<Arc<NormalizationContext>>,
     ) -> Self {
         match backend {
             NoiseBackend::Cpu => Self::generate_meso_full(
@@ -887,6 +1850,7 @@ impl BiomeMap {
                 world_height,
                 detail_level,
                 progress,
+                normalization,
             ),
             NoiseBackend::Gpu => Self::generate_meso_full_gpu(
                 seed,
@@ -897,12 +1861,54 @@ impl BiomeMap {
                 world_height,
                 detail_level,
                 progress,
+                normalization,
             ),
         }
     }
 
+    /// Generate a meso map on a background thread, reporting progress as
+    /// [`GenEvent`]s rather than through a shared [`LayerProgress`].
+    ///
+    /// Arguments are as for [`BiomeMap::generate_meso_full_with_backend`].
+    /// The receiver ends with [`GenEvent::Finished`] and closes once the
+    /// thread exits, so draining it with `iter()` terminates.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_with_events(
+        seed: u32,
+        world_x: f64,
+        world_y: f64,
+        world_size: f64,
+        output_size: usize,
+        world_height: f64,
+        detail_level: u32,
+        backend: NoiseBackend,
+        normalization: Option<Arc<NormalizationContext>>,
+    ) -> (JoinHandle<Self>, Receiver<GenEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            let progress =
+                Arc::new(LayerProgress::with_events(output_size * output_size, sender.clone()));
+            let map = Self::generate_meso_full_with_backend(
+                seed,
+                world_x,
+                world_y,
+                world_size,
+                output_size,
+                world_height,
+                detail_level,
+                &progress,
+                backend,
+                normalization,
+            );
+            let _ = sender.send(GenEvent::Finished);
+            map
+        });
+        (handle, receiver)
+    }
+
     /// GPU-accelerated meso generation with progress tracking.
     #[cfg(feature = "gpu")]
+    #[allow(clippy::too_many_arguments)]
     fn generate_meso_full_gpu(
         seed: u32,
         world_x: f64,
@@ -912,6 +1918,7 @@ impl BiomeMap {
         world_height: f64,
         detail_level: u32,
         progress: &Arc<LayerProgress>,
+        normalization: Option<Arc<NormalizationContext>>,
     ) -> Self {
         use crate::gpu::GpuNoiseContext;
 
@@ -926,6 +1933,7 @@ impl BiomeMap {
                 world_height,
                 detail_level,
                 progress,
+                normalization,
             );
         };
 
@@ -953,12 +1961,47 @@ impl BiomeMap {
         progress.increment(LayerId::Humidity, total_pixels);
 
         // Convert f32 GPU results to f64
-        let continentalness: Vec<f64> = layers.continentalness.iter().map(|&v| v as f64).collect();
+        let mut continentalness: Vec<f64> =
+            layers.continentalness.iter().map(|&v| v as f64).collect();
         let temperature: Vec<f64> = layers.temperature.iter().map(|&v| v as f64).collect();
         let tectonic: Vec<f64> = layers.tectonic.iter().map(|&v| v as f64).collect();
         let peaks_valleys: Vec<f64> = layers.peaks_valleys.iter().map(|&v| v as f64).collect();
-        let erosion: Vec<f64> = layers.erosion.iter().map(|&v| v as f64).collect();
-        let humidity: Vec<f64> = layers.humidity.iter().map(|&v| v as f64).collect();
+        let mut erosion: Vec<f64> = layers.erosion.iter().map(|&v| v as f64).collect();
+        let mut humidity: Vec<f64> = layers.humidity.iter().map(|&v| v as f64).collect();
+
+        let strategies = LayerStrategies::new(seed, world_height);
+
+        // The shader sees raw continentalness; move it onto the macro
+        // baseline and redo the erosion that depends on it
+        if let Some(context) = &normalization {
+            continentalness
+                .par_iter_mut()
+                .zip(erosion.par_iter_mut())
+                .enumerate()
+                .for_each(|(idx, (cont, eros))| {
+                    let wx = world_x + (idx % output_size) as f64 * scale;
+                    let wy = world_y + (idx / output_size) as f64 * scale;
+                    *cont = context.normalize(wx, wy, *cont);
+                    *eros = strategies
+                        .erosion
+                        .generate_with_continentalness(wx, wy, detail_level, *cont);
+                });
+        }
+
+        // The shader's humidity only knows the distance to the sea; trace the
+        // wind's moisture here instead
+        let uplift = strategies.plate_uplift((world_x, world_y, scale), output_size, output_size);
+        let mut precipitation = vec![0.0; total_pixels];
+        humidity
+            .par_iter_mut()
+            .zip(precipitation.par_iter_mut())
+            .enumerate()
+            .for_each(|(idx, (humid, precip))| {
+                let wx = world_x + (idx % output_size) as f64 * scale;
+                let wy = world_y + (idx / output_size) as f64 * scale;
+                let cont = continentalness[idx];
+                (*humid, *precip) = strategies.moisture(wx, wy, detail_level, cont);
+            });
 
         // Compute biomes using splines (same as CPU path)
         let splines = BiomeSplines::new(SEA_LEVEL);
@@ -972,6 +2015,7 @@ impl BiomeMap {
                 erosion[idx],
                 peaks_valleys[idx],
                 humidity[idx],
+                uplift[idx],
             );
             biomes.push(biome);
         }
@@ -981,28 +2025,9 @@ impl BiomeMap {
             .iter()
             .zip(peaks_valleys.iter())
             .zip(erosion.iter())
-            .zip(tectonic.iter())
-            .map(|(((&cont, &peaks), &eros), &tect)| {
-                let is_land = cont >= SEA_LEVEL;
-                let boundary = 1.0 - tect;
-
-                let tectonic_amp = 1.0 + boundary * boundary * 2.0;
-                let erosion_damp = 1.0 - eros * 0.7;
-
-                let peak_height = if is_land {
-                    peaks.max(0.0) * 0.15 * tectonic_amp * erosion_damp
-                } else {
-                    0.0
-                };
-                let valley_depth = if is_land { peaks.min(0.0).abs() * 0.08 } else { 0.0 };
-
-                let trench = if !is_land && boundary > 0.7 {
-                    (boundary - 0.7) * 0.5
-                } else {
-                    0.0
-                };
-
-                cont + peak_height - valley_depth - trench
+            .zip(uplift.iter())
+            .map(|(((&cont, &peaks), &eros), &lift)| {
+                terrain_elevation(cont, peaks, eros, lift, SEA_LEVEL)
             })
             .collect();
 
@@ -1019,26 +2044,51 @@ impl BiomeMap {
                 biomes[idx] = TileType::River;
             }
         }
+        let lakes = lakes::flood_lakes(
+            &mut biomes,
+            &elevation,
+            &temperature,
+            output_size,
+            output_size,
+            SEA_LEVEL,
+        );
 
-        progress.increment(LayerId::Resources, total_pixels);
+        // Resources are skipped, same as the CPU path
+        progress.skip(LayerId::Resources);
+
+        let (wind, currents) = strategies.flow_fields(
+            (world_x, world_y, scale),
+            output_size,
+            output_size,
+            &continentalness,
+        );
+        let caves = strategies.cave_slice((world_x, world_y, scale), output_size, output_size);
 
         Self {
             width: output_size,
             height: output_size,
             biomes,
-            continentalness,
-            temperature,
-            tectonic,
-            erosion,
-            peaks_valleys,
-            humidity,
-            rivers,
+            continentalness: to_layer(continentalness),
+            temperature: to_layer(temperature),
+            tectonic: to_layer(tectonic),
+            uplift: to_layer(uplift),
+            erosion: to_layer(erosion),
+            peaks_valleys: to_layer(peaks_valleys),
+            humidity: to_layer(humidity),
+            precipitation: to_layer(precipitation),
+            wind,
+            currents,
+            caves: to_layer(caves),
+            rivers: to_layer(rivers),
+            lakes,
+            volcanoes: Vec::new(),
             resources: ResourceMap::new(output_size, output_size),
         }
     }
 
     /// GPU meso generation stub when gpu feature is disabled.
     #[cfg(not(feature = "gpu"))]
+    #[allow(clippy::too_many_arguments)]
     fn generate_meso_full_gpu(
         seed: u32,
         world_x: f64,
@@ -1048,6 +2098,7 @@ impl BiomeMap {
         world_height: f64,
         detail_level: u32,
         progress: &Arc<LayerProgress>,
+        normalization: Option<Arc<NormalizationContext>>,
     ) -> Self {
         // GPU feature not enabled, fallback to CPU
         Self::generate_meso_full(
@@ -1059,13 +2110,26 @@ impl BiomeMap {
             world_height,
             detail_level,
             progress,
+            normalization,
         )
     }
 }
 
+/// The layer after `current` in [`NoiseLayer::all`] order, wrapping around,
+/// skipping layers for which `keep` is false.
+pub fn next_layer_where(current: NoiseLayer, keep: impl Fn(NoiseLayer) -> bool) -> NoiseLayer {
+    let all = NoiseLayer::all();
+    let start = all.iter().position(|&l| l == current).unwrap_or(0);
+    (1..all.len())
+        .map(|offset| all[(start + offset) % all.len()])
+        .find(|&layer| keep(layer))
+        .unwrap_or(current)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::precision::from_layer;
 
     #[test]
     fn generates_correct_size() {
@@ -1079,6 +2143,7 @@ mod tests {
         assert_eq!(map.erosion.len(), 64 * 32);
         assert_eq!(map.peaks_valleys.len(), 64 * 32);
         assert_eq!(map.humidity.len(), 64 * 32);
+        assert_eq!(map.precipitation.len(), 64 * 32);
         assert_eq!(map.rivers.len(), 64 * 32);
     }
 
@@ -1089,6 +2154,95 @@ mod tests {
         assert_eq!(image.len(), 64 * 32 * 4);
     }
 
+    #[test]
+    fn world_queries_map_centered_origin() {
+        let map = BiomeMap::generate(42, 64, 32);
+        // Drawn at twice the map resolution
+        let (w, h) = (128.0, 64.0);
+
+        assert_eq!(map.world_to_cell(Vec2::ZERO, w, h), Some((32, 16)));
+        assert_eq!(map.world_to_cell(Vec2::new(-64.0, 32.0), w, h), Some((0, 0)));
+        assert_eq!(map.world_to_cell(Vec2::new(63.9, -31.9), w, h), Some((63, 31)));
+        assert_eq!(map.world_to_cell(Vec2::new(-63.9, -31.9), w, h), Some((0, 31)));
+        assert_eq!(map.biome_at_world(Vec2::new(-64.0, 32.0), w, h), map.get_biome(0, 0));
+
+        // Far edges and beyond are off the map
+        assert_eq!(map.world_to_cell(Vec2::new(64.0, 0.0), w, h), None);
+        assert_eq!(map.world_to_cell(Vec2::new(0.0, -32.0), w, h), None);
+        assert_eq!(map.biome_at_world(Vec2::new(-64.1, 0.0), w, h), None);
+        assert_eq!(map.biome_at_world(Vec2::new(0.0, 32.1), w, h), None);
+
+        for (x, y) in [(0, 0), (5, 7), (63, 31)] {
+            let center = map.cell_to_world(x, y, w, h);
+            assert_eq!(map.world_to_cell(center, w, h), Some((x, y)));
+        }
+    }
+
+    #[test]
+    fn twilight_band_is_diverse_between_white_and_sahara() {
+        let (width, height) = (256, 128);
+        let config = GenerationConfig {
+            twilight_width: Some(48.0),
+            ..Default::default()
+        };
+        let map = BiomeMap::generate_with_config(42, width, height, &config);
+        let share = |rows: std::ops::Range<usize>, biome: TileType| {
+            let cells = rows.len() * width;
+            let hits = rows
+                .flat_map(|y| map.biomes[y * width..(y + 1) * width].iter())
+                .filter(|&&b| b == biome)
+                .count();
+            hits as f64 / cells as f64
+        };
+
+        // Dark edge (north) is ice, sun edge (south) is salt flats
+        assert!(share(0..8, TileType::White) > 0.9);
+        assert!(share(height - 8..height, TileType::Sahara) > 0.9);
+
+        let center = &map.biomes[height / 2 * width..(height / 2 + 1) * width];
+        let kinds: std::collections::HashSet<_> = center.iter().collect();
+        assert!(kinds.len() >= 5, "Only {} biomes in the twilight band", kinds.len());
+    }
+
+    #[test]
+    fn seasons_retint_the_climate_in_place() {
+        let config = GenerationConfig::default();
+        let mut map = BiomeMap::generate_with_config(42, 64, 32, &config);
+        let (temperature, biomes, rivers) =
+            (map.temperature.clone(), map.biomes.clone(), map.rivers.clone());
+
+        // The mean season reproduces the generated climate
+        map.apply_season(42, &config);
+        assert_eq!(map.temperature, temperature);
+        let same = map.biomes.iter().zip(&biomes).filter(|(a, b)| a == b).count();
+        assert!(same * 100 >= biomes.len() * 99, "{} of {} biomes kept", same, biomes.len());
+
+        let mean = |field: &[LayerFloat]| field.iter().map(|v| v.to_f64()).sum::<f64>();
+        map.apply_season(42, &GenerationConfig { season: 0.25, ..config });
+        assert!(mean(&map.temperature) > mean(&temperature));
+        assert_eq!(map.rivers, rivers);
+    }
+
+    #[test]
+    fn cycling_skips_empty_resource_layers() {
+        let mut map = BiomeMap::generate(42, 32, 16);
+        map.resources.clear();
+
+        let mut layer = NoiseLayer::Aggregate;
+        for _ in 0..NoiseLayer::all().len() {
+            layer = map.next_nonempty_layer(layer);
+            assert!(!layer.is_resource(), "Cycled onto empty {}", layer.name());
+        }
+
+        // Any data at all brings a resource layer back into the cycle
+        map.resources.set(3, 3, ResourceType::Gold, 0.8);
+        assert_eq!(
+            map.next_nonempty_layer(NoiseLayer::Caves),
+            NoiseLayer::ResourceGold
+        );
+        assert_eq!(next_layer_where(NoiseLayer::ResourceWildGame, |_| true), NoiseLayer::Aggregate);
+    }
+
     #[test]
     fn layer_images_all_work() {
         let map = BiomeMap::generate(42, 32, 16);
@@ -1128,4 +2282,320 @@ mod tests {
         );
     }
 
+    #[test]
+    fn equalized_continentalness_is_flatter() {
+        let raw = BiomeMap::generate(42, 128, 64);
+        let config = GenerationConfig {
+            equalize_continentalness: true,
+            ..Default::default()
+        };
+        let equalized = BiomeMap::generate_with_config(42, 128, 64, &config);
+
+        // Variance of bin counts around the uniform expectation
+        let spread = |layer: &[LayerFloat]| {
+            let values = from_layer(layer);
+            let counts = histogram::histogram(&values, 16, -1.0, 1.0);
+            let mean = values.len() as f64 / counts.len() as f64;
+            counts.iter().map(|&c| (c as f64 - mean).powi(2)).sum::<f64>() / counts.len() as f64
+        };
+
+        assert!(
+            spread(&equalized.continentalness) < spread(&raw.continentalness),
+            "Equalized histogram should be flatter than raw"
+        );
+    }
+
+    #[test]
+    fn border_ocean_surrounds_map() {
+        let (w, h) = (96, 48);
+        let config = GenerationConfig {
+            border_ocean: 8.0,
+            ..Default::default()
+        };
+        let map = BiomeMap::generate_with_config(42, w, h, &config);
+
+        let edges = (0..w)
+            .flat_map(|x| [(x, 0), (x, h - 1)])
+            .chain((0..h).flat_map(|y| [(0, y), (w - 1, y)]));
+        for (x, y) in edges {
+            let cont = map.get_continentalness(x, y).unwrap();
+            assert!(cont < SEA_LEVEL, "Edge ({}, {}) is land", x, y);
+
+            // Frozen and boiled-off seas keep their own tiles on the far sides
+            let biome = map.get_biome(x, y).unwrap();
+            assert!(
+                biome.is_ocean() || matches!(biome, TileType::White | TileType::Sahara),
+                "Edge ({}, {}) is {:?}",
+                x,
+                y,
+                biome
+            );
+        }
+    }
+
+    #[test]
+    fn warp_reshapes_continents() {
+        let (w, h) = (128, 64);
+        let plain = BiomeMap::generate_with_config(42, w, h, &GenerationConfig::default());
+        // Frequent enough that the warp varies across a map this small
+        let config = GenerationConfig {
+            warp_amplitude: 30.0,
+            warp_frequency: 0.02,
+            ..Default::default()
+        };
+        let warped = BiomeMap::generate_with_config(42, w, h, &config);
+
+        let flipped = plain
+            .continentalness
+            .iter()
+            .zip(&warped.continentalness)
+            .filter(|&(&a, &b)| (a.to_f64() < SEA_LEVEL) != (b.to_f64() < SEA_LEVEL))
+            .count();
+        assert!(flipped > w * h / 100, "only {} cells changed sides", flipped);
+        assert!(warped.continentalness.iter().all(|c| (-1.0..=1.0).contains(c)));
+    }
+
+    #[test]
+    fn resources_match_across_resolutions() {
+        let coarse = BiomeMap::generate_region(42, 0.0, 0.0, 128.0, 128, 128.0, 0);
+        let fine = BiomeMap::generate_region(42, 0.0, 0.0, 128.0, 256, 128.0, 0);
+
+        // Coarse pixel (x, y) and fine pixel (2x, 2y) sit on the same world point.
+        // Rivers depend on resolution, so only compare cells with the same biome.
+        let mut compared = 0;
+        for y in 0..coarse.height {
+            for x in 0..coarse.width {
+                if coarse.get_biome(x, y) != fine.get_biome(x * 2, y * 2) {
+                    continue;
+                }
+                for &resource in ResourceType::all() {
+                    let a = coarse.resources.get(x, y, resource);
+                    let b = fine.resources.get(x * 2, y * 2, resource);
+                    assert!(
+                        (a - b).abs() < 1e-6,
+                        "{:?} differs at world ({}, {}): {} vs {}",
+                        resource,
+                        x,
+                        y,
+                        a,
+                        b
+                    );
+                }
+                compared += 1;
+            }
+        }
+        assert!(compared > coarse.width * coarse.height / 2);
+        assert!(coarse.resources.cells_with_resources() > 0);
+    }
+
+    #[test]
+    fn biome_overrides_survive_regeneration_and_reclassification() {
+        let config = GenerationConfig {
+            biome_overrides: vec![BiomeOverride {
+                outline: vec![(10.0, 10.0), (30.0, 10.0), (30.0, 30.0), (10.0, 30.0)],
+                biome: TileType::Forest,
+            }],
+            ..Default::default()
+        };
+        let mut map = BiomeMap::generate_with_config(42, 64, 32, &config);
+        let forced = |map: &BiomeMap| (10..30).all(|y| (10..30).all(|x| map.get_biome(x, y) == Some(TileType::Forest)));
+        assert!(forced(&map));
+
+        map.reclassify_biomes(42, &GenerationConfig { season: 0.25, ..config.clone() });
+        assert!(forced(&map));
+        assert_ne!(map.biomes, BiomeMap::generate(42, 64, 32).biomes);
+    }
+
+    #[test]
+    fn horizontal_symmetry_mirrors_biomes() {
+        let config = GenerationConfig {
+            symmetry: Symmetry::Horizontal,
+            dither_biomes: true,
+            ..Default::default()
+        };
+        let (w, h) = (96, 48);
+        let map = BiomeMap::generate_with_config(42, w, h, &config);
+
+        for y in 0..h {
+            for x in 0..w {
+                assert_eq!(
+                    map.get_biome(x, y),
+                    map.get_biome(w - 1 - x, y),
+                    "Biome differs at ({}, {})",
+                    x,
+                    y
+                );
+                assert_eq!(map.get_river(x, y), map.get_river(w - 1 - x, y));
+            }
+        }
+        for (x, y, _) in map.resources.locations_with_resource(ResourceType::Iron) {
+            assert!(map.resources.get(w - 1 - x, y, ResourceType::Iron) > 0.0);
+        }
+    }
+
+    #[test]
+    fn meso_resource_progress_tracks_real_work() {
+        let progress = Arc::new(LayerProgress::new(32 * 32));
+        let map = BiomeMap::generate_meso_full(42, 0.0, 0.0, 32.0, 32, 512.0, 1, &progress, None);
+
+        assert_eq!(progress.fraction(LayerId::Continentalness), 1.0);
+        // No deposits are generated for meso tiles, so the bar must not fill
+        assert_eq!(map.resources.cells_with_resources(), 0);
+        assert_eq!(progress.fraction(LayerId::Resources), 0.0);
+        assert!(progress.is_skipped(LayerId::Resources));
+    }
+
+    #[test]
+    fn meso_tile_uses_requested_resolution() {
+        let progress = Arc::new(LayerProgress::new(256 * 256));
+        let map = BiomeMap::generate_meso_full(42, 0.0, 0.0, 64.0, 256, 512.0, 1, &progress, None);
+
+        assert_eq!((map.width, map.height), (256, 256));
+        assert_eq!(map.biomes.len(), 256 * 256);
+        assert_eq!(map.to_biome_image().len(), 256 * 256 * 4);
+    }
+
+    #[test]
+    fn generation_events_are_monotonic_and_finish() {
+        let size = 64;
+        let (handle, events) = BiomeMap::generate_with_events(
+            42, 0.0, 0.0, 64.0, size, 512.0, 1, NoiseBackend::Cpu, None,
+        );
+        let events: Vec<GenEvent> = events.iter().collect();
+        let map = handle.join().unwrap();
+
+        assert_eq!(map.width, size);
+        assert_eq!(events.last(), Some(&GenEvent::Finished));
+
+        let mut last_done = [None; 7];
+        for event in &events {
+            match *event {
+                GenEvent::PhaseStarted(layer) => {
+                    assert_eq!(last_done[layer.index()], None, "{:?} started twice", layer);
+                    last_done[layer.index()] = Some(0);
+                }
+                GenEvent::Progress { layer, done, total } => {
+                    let last = last_done[layer.index()].expect("progress before phase start");
+                    assert!(done > last, "{:?} went from {} to {}", layer, last, done);
+                    assert_eq!(total, size * size);
+                    last_done[layer.index()] = Some(done);
+                }
+                GenEvent::Finished => {}
+            }
+        }
+        assert_eq!(last_done[LayerId::Humidity.index()], Some(size * size));
+    }
+
+    #[test]
+    fn downscale_keeps_dominant_biomes() {
+        let (w, h) = (64, 32);
+        let mut map = BiomeMap::generate(42, w, h);
+        for (idx, biome) in map.biomes.iter_mut().enumerate() {
+            *biome = if idx % w < w / 2 { TileType::Plains } else { TileType::Desert };
+        }
+        // A lone Forest cell is outvoted within its 2×2 block
+        map.biomes[0] = TileType::Forest;
+
+        let image = map.downscale(2);
+        assert_eq!(image.len(), (w / 2) * (h / 2) * 4);
+        let pixel = |x: usize, y: usize| &image[(y * w / 2 + x) * 4..][..4];
+        assert_eq!(pixel(0, 0), TileType::Plains.color());
+        assert_eq!(pixel(w / 4 - 1, h / 2 - 1), TileType::Plains.color());
+        assert_eq!(pixel(w / 4, 0), TileType::Desert.color());
+
+        assert_eq!(map.downscale(1), map.to_biome_image());
+    }
+
+    #[test]
+    fn thumbnail_samples_the_macro_biomes() {
+        let (w, h, factor) = (128, 64, 4);
+        let full = BiomeMap::generate(42, w, h);
+        let thumbnail = BiomeMap::thumbnail(42, w, h, factor, &GenerationConfig::default());
+        assert_eq!(thumbnail.len(), (w / factor) * (h / factor) * 4);
+
+        let mut compared = 0;
+        for y in (0..h).step_by(factor) {
+            for x in (0..w).step_by(factor) {
+                let biome = full.get_biome(x, y).unwrap();
+                // Thumbnails have no rivers or lakes
+                if [TileType::River, TileType::Lake].contains(&biome) {
+                    continue;
+                }
+                let idx = ((y / factor) * (w / factor) + x / factor) * 4;
+                assert_eq!(&thumbnail[idx..idx + 4], &biome.color(), "Biome differs at ({}, {})", x, y);
+                compared += 1;
+            }
+        }
+        assert!(compared > (w / factor) * (h / factor) / 2);
+    }
+
+    #[test]
+    fn macro_and_meso_biomes_agree() {
+        let (w, h) = (64, 32);
+        let macro_map = BiomeMap::generate(42, w, h);
+
+        // Meso tile over macro columns 16..48 at 1:1 scale and macro detail
+        let (ox, oy, size) = (16, 0, 32);
+        let progress = Arc::new(LayerProgress::new(size * size));
+        let meso = BiomeMap::generate_meso_full(
+            42, ox as f64, oy as f64, size as f64, size, h as f64, 0, &progress, None,
+        );
+        let biome_only =
+            BiomeMap::generate_biome_only(42, ox as f64, oy as f64, size as f64, size, h as f64, 0);
+
+        let mut compared = 0;
+        for y in 0..h.min(size) {
+            for x in 0..size {
+                let macro_biome = macro_map.get_biome(ox + x, oy + y).unwrap();
+                let meso_biome = meso.get_biome(x, y).unwrap();
+                // River routing and lake filling depend on the extent of the map
+                let water = [TileType::River, TileType::Lake];
+                if water.contains(&macro_biome) || water.contains(&meso_biome) {
+                    continue;
+                }
+                assert_eq!(macro_biome, meso_biome, "Biome differs at ({}, {})", ox + x, oy + y);
+
+                let idx = (y * size + x) * 4;
+                assert_eq!(&biome_only[idx..idx + 4], &macro_biome.color());
+                compared += 1;
+            }
+        }
+        assert!(compared > size * h.min(size) / 2);
+    }
+
+    #[test]
+    fn meso_detail_keeps_macro_biomes() {
+        let (w, h) = (256, 128);
+        let macro_map = BiomeMap::generate(42, w, h);
+
+        // Same window at the detail level start_generation uses for meso tiles
+        let (ox, oy, size) = (64, 0, 128);
+        let progress = Arc::new(LayerProgress::new(size * size));
+        let meso = BiomeMap::generate_meso_full(
+            42, ox as f64, oy as f64, size as f64, size, h as f64, 1, &progress, None,
+        );
+
+        let strategies = LayerStrategies::new(42, h as f64);
+        let (mut compared, mut matching) = (0, 0);
+        for y in 0..size.min(h) {
+            for x in 0..size {
+                let (wx, wy) = ((ox + x) as f64, (oy + y) as f64);
+                let base = strategies.continentalness.generate(wx, wy, 0);
+                let detailed = strategies.continentalness.generate(wx, wy, 1);
+                // Only the 17th octave's amplitude separates the two
+                assert!((base - detailed).abs() < 1e-3, "{} vs {}", base, detailed);
+
+                let macro_biome = macro_map.get_biome(ox + x, oy + y).unwrap();
+                let meso_biome = meso.get_biome(x, y).unwrap();
+                if macro_biome == TileType::River || meso_biome == TileType::River {
+                    continue;
+                }
+                compared += 1;
+                matching += (macro_biome == meso_biome) as usize;
+            }
+        }
+        // Cells sitting right on a biome threshold may still flip
+        let agreement = matching as f64 / compared as f64;
+        assert!(agreement > 0.99, "Only {:.1}% of cells kept their biome", agreement * 100.0);
+    }
 }
@@ -0,0 +1,112 @@
+//! Authored zones that force a biome, whatever the noise says.
+//!
+//! Unlike a brush stroke, an override is part of the generation inputs: it
+//! is reapplied as the last biome pass of every macro map and meso tile, so
+//! an authored zone survives regeneration.
+
+use rb_core::TileType;
+
+/// A polygon of world units inside which every cell becomes `biome`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeOverride {
+    /// Outline vertices in order; the last joins back to the first.
+    pub outline: Vec<(f64, f64)>,
+    pub biome: TileType,
+}
+
+impl BiomeOverride {
+    /// Whether `(x, y)` is inside the outline, by ray casting.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        let n = self.outline.len();
+        if n < 3 {
+            return false;
+        }
+        let mut inside = false;
+        for i in 0..n {
+            let (xi, yi) = self.outline[i];
+            let (xj, yj) = self.outline[(i + 1) % n];
+            if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Bounding box `(min_x, min_y, max_x, max_y)` of the outline.
+    fn bounds(&self) -> (f64, f64, f64, f64) {
+        self.outline.iter().fold(
+            (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            |(x0, y0, x1, y1), &(x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+        )
+    }
+}
+
+/// Force the biome of every cell of a `width` × `height` grid whose centre
+/// lies inside an override. Later overrides win where they overlap.
+///
+/// `region` is `(world_x, world_y, scale)`, mapping cell `(px, py)` to world
+/// `(world_x + px * scale, world_y + py * scale)` as the generators do.
+pub fn apply_overrides(
+    overrides: &[BiomeOverride],
+    biomes: &mut [TileType],
+    width: usize,
+    height: usize,
+    region: (f64, f64, f64),
+) {
+    let (world_x, world_y, scale) = region;
+    for zone in overrides {
+        // Only visit the cells under the outline's bounding box
+        let (x0, y0, x1, y1) = zone.bounds();
+        let cells = |min: f64, max: f64, origin: f64, len: usize| {
+            let first = ((min - origin) / scale - 0.5).floor().max(0.0) as usize;
+            let last = (((max - origin) / scale).ceil().max(0.0) as usize).min(len);
+            first..last
+        };
+        for py in cells(y0, y1, world_y, height) {
+            let wy = world_y + (py as f64 + 0.5) * scale;
+            for px in cells(x0, x1, world_x, width) {
+                if zone.contains(world_x + (px as f64 + 0.5) * scale, wy) {
+                    biomes[py * width + px] = zone.biome;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, size: f64, biome: TileType) -> BiomeOverride {
+        BiomeOverride {
+            outline: vec![(x, y), (x + size, y), (x + size, y + size), (x, y + size)],
+            biome,
+        }
+    }
+
+    #[test]
+    fn overrides_cover_the_same_ground_at_any_scale() {
+        let zones = [square(4.0, 4.0, 8.0, TileType::Forest), square(10.0, 10.0, 4.0, TileType::Volcanic)];
+
+        // Macro: one cell per world unit
+        let mut macro_map = vec![TileType::Plains; 16 * 16];
+        apply_overrides(&zones, &mut macro_map, 16, 16, (0.0, 0.0, 1.0));
+        let count = |map: &[TileType], biome| map.iter().filter(|&&b| b == biome).count();
+        assert_eq!(count(&macro_map, TileType::Volcanic), 16);
+        assert_eq!(count(&macro_map, TileType::Forest), 64 - 4);
+        assert_eq!(macro_map[4 * 16 + 4], TileType::Forest);
+        assert_eq!(macro_map[3 * 16 + 4], TileType::Plains);
+
+        // Meso tile over world [8, 16) at four cells per unit
+        let mut tile = vec![TileType::Plains; 32 * 32];
+        apply_overrides(&zones, &mut tile, 32, 32, (8.0, 8.0, 0.25));
+        assert_eq!(tile[0], TileType::Forest);
+        assert_eq!(tile[31 * 32 + 31], TileType::Plains);
+        assert_eq!(tile[(2.5 * 4.0) as usize * 32 + 10], TileType::Volcanic);
+
+        // Degenerate outlines force nothing
+        let line = BiomeOverride { outline: vec![(0.0, 0.0), (16.0, 16.0)], biome: TileType::Sea };
+        apply_overrides(&[line], &mut macro_map, 16, 16, (0.0, 0.0, 1.0));
+        assert_eq!(count(&macro_map, TileType::Sea), 0);
+    }
+}
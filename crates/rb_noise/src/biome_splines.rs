@@ -1,4 +1,7 @@
 use rb_core::TileType;
+use serde::{Deserialize, Serialize};
+
+use crate::elevation::terrain_elevation;
 
 /// Climate classification for temperature-based biome selection.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -13,22 +16,27 @@ pub enum ClimateClass {
 
 impl ClimateClass {
     pub fn from_temperature(temp: f64) -> Self {
-        if temp < -20.0 {
-            Self::Frozen
-        } else if temp < 3.0 {
-            Self::Cold
-        } else if temp < 35.0 {
-            Self::Temperate
-        } else if temp < 55.0 {
-            Self::Warm
-        } else if temp < 80.0 {
-            Self::Hot
-        } else {
-            Self::Scorching
-        }
+        Self::from_temperature_with(temp, &BiomeThresholds::DEFAULT.temperature)
+    }
+
+    /// Class of `temp` given the upper bound of each class but the last.
+    pub fn from_temperature_with(temp: f64, bounds: &[f64; 5]) -> Self {
+        [Self::Frozen, Self::Cold, Self::Temperate, Self::Warm, Self::Hot, Self::Scorching]
+            [band(temp, bounds)]
     }
 }
 
+/// Index of the band `value` falls in, given ascending upper bounds.
+fn band(value: f64, bounds: &[f64]) -> usize {
+    bounds.iter().position(|&bound| value < bound).unwrap_or(bounds.len())
+}
+
+/// Below this (adjusted) temperature land is buried under the dark side's
+/// ice sheet, drawn like frozen ocean.
+pub const ICE_SHEET_TEMP: f64 = -55.0;
+/// Above this the sun side is bare salt flats whatever the moisture.
+pub const SCORCHED_TEMP: f64 = 120.0;
+
 /// Moisture classification for humidity-based biome selection.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MoistureClass {
@@ -41,17 +49,13 @@ pub enum MoistureClass {
 
 impl MoistureClass {
     pub fn from_humidity(humidity: f64) -> Self {
-        if humidity < 0.2 {
-            Self::Arid
-        } else if humidity < 0.4 {
-            Self::Dry
-        } else if humidity < 0.6 {
-            Self::Moderate
-        } else if humidity < 0.8 {
-            Self::Humid
-        } else {
-            Self::Saturated
-        }
+        Self::from_humidity_with(humidity, &BiomeThresholds::DEFAULT.humidity)
+    }
+
+    /// Class of `humidity` given the upper bound of each class but the last.
+    pub fn from_humidity_with(humidity: f64, bounds: &[f64; 4]) -> Self {
+        [Self::Arid, Self::Dry, Self::Moderate, Self::Humid, Self::Saturated]
+            [band(humidity, bounds)]
     }
 }
 
@@ -67,17 +71,13 @@ pub enum ElevationClass {
 
 impl ElevationClass {
     pub fn from_elevation(above_sea: f64) -> Self {
-        if above_sea < 0.02 {
-            Self::Coastal
-        } else if above_sea < 0.08 {
-            Self::Lowland
-        } else if above_sea < 0.18 {
-            Self::Upland
-        } else if above_sea < 0.28 {
-            Self::Highland
-        } else {
-            Self::Alpine
-        }
+        Self::from_elevation_with(above_sea, &BiomeThresholds::DEFAULT.elevation)
+    }
+
+    /// Class of `above_sea` given the upper bound of each class but the last.
+    pub fn from_elevation_with(above_sea: f64, bounds: &[f64; 4]) -> Self {
+        [Self::Coastal, Self::Lowland, Self::Upland, Self::Highland, Self::Alpine]
+            [band(above_sea, bounds)]
     }
 }
 
@@ -92,16 +92,119 @@ pub enum TerrainClass {
 impl TerrainClass {
     /// Note: High erosion = flat terrain, low erosion = rugged terrain
     pub fn from_erosion(erosion: f64) -> Self {
-        if erosion < 0.3 {
-            Self::Rugged // Low erosion = jagged peaks preserved
-        } else if erosion < 0.7 {
-            Self::Rolling
-        } else {
-            Self::Flat // High erosion = worn down
+        Self::from_erosion_with(erosion, &BiomeThresholds::DEFAULT.erosion)
+    }
+
+    /// Class of `erosion` given the upper bounds of rugged and rolling terrain.
+    pub fn from_erosion_with(erosion: f64, bounds: &[f64; 2]) -> Self {
+        // Low erosion = jagged peaks preserved, high erosion = worn down
+        [Self::Rugged, Self::Rolling, Self::Flat][band(erosion, bounds)]
+    }
+}
+
+/// Breakpoints between the classes each climate axis is split into, all
+/// ascending. Editing them moves biome boundaries without touching the
+/// noise.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BiomeThresholds {
+    /// °C where Frozen, Cold, Temperate, Warm and Hot end.
+    pub temperature: [f64; 5],
+    /// Humidity where Arid, Dry, Moderate and Humid end.
+    pub humidity: [f64; 4],
+    /// Height above sea level where Coastal, Lowland, Upland and Highland end.
+    pub elevation: [f64; 4],
+    /// Erosion where Rugged and Rolling terrain end.
+    pub erosion: [f64; 2],
+}
+
+impl BiomeThresholds {
+    pub const DEFAULT: Self = Self {
+        temperature: [-20.0, 3.0, 35.0, 55.0, 80.0],
+        humidity: [0.2, 0.4, 0.6, 0.8],
+        elevation: [0.02, 0.08, 0.18, 0.28],
+        erosion: [0.3, 0.7],
+    };
+}
+
+impl Default for BiomeThresholds {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// 4x4 Bayer matrix for ordered dithering.
+const BAYER_4X4: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// Ordered dithering of classification inputs near biome thresholds.
+///
+/// Each classified axis is nudged by a deterministic per-cell offset of at most
+/// its band width, so cells within that distance of a threshold fall on either
+/// side with probability proportional to how close they are. Cells further away
+/// than the band are never affected.
+#[derive(Clone, Copy, Debug)]
+pub struct BiomeDither {
+    seed: u32,
+    /// Half-width of the temperature transition band (°C).
+    pub temperature_band: f64,
+    /// Half-width of the humidity transition band.
+    pub humidity_band: f64,
+    /// Half-width of the elevation transition band (above sea level).
+    pub elevation_band: f64,
+    /// Half-width of the erosion transition band.
+    pub erosion_band: f64,
+}
+
+impl BiomeDither {
+    /// Create a dither pattern with default band widths.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            temperature_band: 3.0,
+            humidity_band: 0.04,
+            elevation_band: 0.01,
+            erosion_band: 0.05,
+        }
+    }
+
+    /// Ordered dither value in (-1, 1) for a cell and axis.
+    ///
+    /// Each axis reads the Bayer matrix at a seed-derived shift so the axes
+    /// don't switch in lockstep.
+    fn offset(&self, x: i64, y: i64, axis: u32) -> f64 {
+        let hash = self
+            .seed
+            .wrapping_add(axis.wrapping_mul(0x85EB_CA6B))
+            .wrapping_mul(0x9E37_79B1);
+        let sx = x.wrapping_add((hash >> 8) as i64).rem_euclid(4) as usize;
+        let sy = y.wrapping_add((hash >> 16) as i64).rem_euclid(4) as usize;
+        (BAYER_4X4[sy][sx] as f64 + 0.5) / 16.0 * 2.0 - 1.0
+    }
+
+    fn offsets(&self, x: i64, y: i64) -> DitherOffsets {
+        DitherOffsets {
+            temperature: self.offset(x, y, 0) * self.temperature_band,
+            humidity: self.offset(x, y, 1) * self.humidity_band,
+            elevation: self.offset(x, y, 2) * self.elevation_band,
+            erosion: self.offset(x, y, 3) * self.erosion_band,
         }
     }
 }
 
+/// Per-cell offsets added to classification inputs.
+#[derive(Clone, Copy, Debug, Default)]
+struct DitherOffsets {
+    temperature: f64,
+    humidity: f64,
+    elevation: f64,
+    erosion: f64,
+}
+
 /// Multi-axis biome determination using all noise layers.
 ///
 /// Uses Whittaker diagram-style classification with:
@@ -112,12 +215,30 @@ impl TerrainClass {
 /// - Tectonic → Mountain amplification & volcanic biomes
 pub struct BiomeSplines {
     sea_level: f64,
+    dither: Option<BiomeDither>,
+    thresholds: BiomeThresholds,
 }
 
 impl BiomeSplines {
     /// Create a new spline evaluator with the given sea level threshold.
     pub fn new(sea_level: f64) -> Self {
-        Self { sea_level }
+        Self {
+            sea_level,
+            dither: None,
+            thresholds: BiomeThresholds::DEFAULT,
+        }
+    }
+
+    /// Split the climate axes at `thresholds` instead of the defaults.
+    pub fn with_thresholds(mut self, thresholds: BiomeThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Enable ordered dithering near biome thresholds for `evaluate_at`.
+    pub fn with_dither(mut self, dither: BiomeDither) -> Self {
+        self.dither = Some(dither);
+        self
     }
 
     /// Determine biome from all noise layers using multi-axis classification.
@@ -129,6 +250,8 @@ impl BiomeSplines {
     /// * `erosion` - Erosion amount (0-1)
     /// * `peaks_valleys` - Ridgeline noise (-1 = valley, 1 = peak)
     /// * `humidity` - Moisture level (0 = dry, 1 = wet)
+    /// * `uplift` - Plate boundary stress (1 = colliding, -1 = rifting)
+    #[allow(clippy::too_many_arguments)]
     pub fn evaluate(
         &self,
         continentalness: f64,
@@ -137,9 +260,68 @@ impl BiomeSplines {
         erosion: f64,
         peaks_valleys: f64,
         humidity: f64,
+        uplift: f64,
+    ) -> TileType {
+        self.classify(
+            continentalness,
+            temperature,
+            tectonic,
+            erosion,
+            peaks_valleys,
+            humidity,
+            uplift,
+            DitherOffsets::default(),
+        )
+    }
+
+    /// Determine biome for a specific cell, applying dithering if enabled.
+    ///
+    /// Identical to [`evaluate`](Self::evaluate) when no dither is configured.
+    /// The coastline itself is never dithered; only the classification of land
+    /// biomes is.
+    #[allow(clippy::too_many_arguments)]
+    pub fn evaluate_at(
+        &self,
+        x: i64,
+        y: i64,
+        continentalness: f64,
+        temperature: f64,
+        tectonic: f64,
+        erosion: f64,
+        peaks_valleys: f64,
+        humidity: f64,
+        uplift: f64,
+    ) -> TileType {
+        let offsets = self
+            .dither
+            .map(|d| d.offsets(x, y))
+            .unwrap_or_default();
+        self.classify(
+            continentalness,
+            temperature,
+            tectonic,
+            erosion,
+            peaks_valleys,
+            humidity,
+            uplift,
+            offsets,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn classify(
+        &self,
+        continentalness: f64,
+        temperature: f64,
+        tectonic: f64,
+        erosion: f64,
+        peaks_valleys: f64,
+        humidity: f64,
+        uplift: f64,
+        offsets: DitherOffsets,
     ) -> TileType {
-        // Step 1: Compute effective elevation with tectonic amplification
-        let elevation = self.compute_elevation(continentalness, peaks_valleys, erosion, tectonic);
+        // Step 1: Compute effective elevation, raised or rifted by the plates
+        let elevation = self.compute_elevation(continentalness, peaks_valleys, erosion, uplift);
 
         // Step 2: Check for ocean biomes first
         if elevation < self.sea_level {
@@ -147,19 +329,28 @@ impl BiomeSplines {
         }
 
         // Step 3: Adjust temperature based on elevation (lapse rate) and tectonic heat
-        let adjusted_temp = self.adjust_temperature(temperature, elevation, tectonic);
+        let adjusted_temp =
+            self.adjust_temperature(temperature, elevation, tectonic) + offsets.temperature;
 
         // Step 4: Adjust humidity with rain shadow effect
-        let adjusted_humidity = self.adjust_humidity(humidity, elevation);
+        let adjusted_humidity = self.adjust_humidity(humidity, elevation) + offsets.humidity;
 
         // Step 5: Classify climate parameters
-        let climate = ClimateClass::from_temperature(adjusted_temp);
-        let moisture = MoistureClass::from_humidity(adjusted_humidity);
-        let above_sea = elevation - self.sea_level;
-        let elev_class = ElevationClass::from_elevation(above_sea);
-        let terrain = TerrainClass::from_erosion(erosion);
+        let bounds = &self.thresholds;
+        let climate = ClimateClass::from_temperature_with(adjusted_temp, &bounds.temperature);
+        let moisture = MoistureClass::from_humidity_with(adjusted_humidity, &bounds.humidity);
+        let above_sea = elevation - self.sea_level + offsets.elevation;
+        let elev_class = ElevationClass::from_elevation_with(above_sea, &bounds.elevation);
+        let terrain = TerrainClass::from_erosion_with(erosion + offsets.erosion, &bounds.erosion);
+
+        // Step 6: Check for special cases (uninhabitable extremes, volcanic, beach)
+        if adjusted_temp < ICE_SHEET_TEMP {
+            return TileType::White;
+        }
+        if adjusted_temp > SCORCHED_TEMP {
+            return TileType::Sahara;
+        }
 
-        // Step 6: Check for special cases (volcanic, beach)
         // Volcanic only at very close plate boundaries with high heat
         let boundary_proximity = 1.0 - tectonic;
         if boundary_proximity > 0.9 && adjusted_temp > 50.0 && above_sea > 0.08 {
@@ -167,7 +358,7 @@ impl BiomeSplines {
         }
 
         // Coastal beach check
-        if above_sea < 0.02 {
+        if elev_class == ElevationClass::Coastal {
             return match climate {
                 ClimateClass::Frozen => TileType::Glacier,
                 ClimateClass::Cold => TileType::Snow,
@@ -180,45 +371,9 @@ impl BiomeSplines {
         self.land_biome(climate, moisture, elev_class, terrain)
     }
 
-    /// Compute effective elevation with tectonic mountain chain amplification.
-    fn compute_elevation(
-        &self,
-        cont: f64,
-        pv: f64,
-        erosion: f64,
-        tectonic: f64,
-    ) -> f64 {
-        let is_land = cont >= self.sea_level;
-        let boundary_proximity = 1.0 - tectonic; // 1 at boundary, 0 at plate center
-
-        // Mountains form along plate boundaries
-        let tectonic_amp = 1.0 + boundary_proximity * boundary_proximity * 2.0;
-
-        // Erosion dampens peaks (high erosion = worn mountains)
-        let erosion_damp = 1.0 - erosion * 0.7;
-
-        // Peak contribution only on land
-        let peak_height = if is_land {
-            pv.max(0.0) * 0.15 * tectonic_amp * erosion_damp
-        } else {
-            0.0
-        };
-
-        // Valleys carve into terrain
-        let valley_depth = if is_land {
-            pv.min(0.0).abs() * 0.08
-        } else {
-            0.0
-        };
-
-        // Ocean trenches at convergent plate boundaries
-        let trench = if !is_land && boundary_proximity > 0.7 {
-            (boundary_proximity - 0.7) * 0.5
-        } else {
-            0.0
-        };
-
-        cont + peak_height - valley_depth - trench
+    /// Compute effective elevation with mountain chains along colliding plates.
+    fn compute_elevation(&self, cont: f64, pv: f64, erosion: f64, uplift: f64) -> f64 {
+        terrain_elevation(cont, pv, erosion, uplift, self.sea_level)
     }
 
     /// Determine ocean biome based on temperature and tectonic activity.
@@ -237,7 +392,7 @@ impl BiomeSplines {
             return TileType::OceanTrench;
         }
 
-        TileType::Sea
+        TileType::sea_from_depth(self.sea_level - elevation)
     }
 
     /// Adjust temperature based on elevation (lapse rate) and tectonic heat.
@@ -253,7 +408,42 @@ impl BiomeSplines {
         temp - lapse_rate + volcanic_heat
     }
 
+    /// Temperature after cooling with altitude and heating near plate
+    /// boundaries, as used to pick the climate class.
+    pub fn adjusted_temperature(&self, temp: f64, elevation: f64, tectonic: f64) -> f64 {
+        self.adjust_temperature(temp, elevation, tectonic)
+    }
+
     /// Adjust humidity with rain shadow effect at high elevations.
+    /// Humidity after the rain shadow cast by high ground.
+    pub fn adjusted_humidity(&self, humidity: f64, elevation: f64) -> f64 {
+        self.adjust_humidity(humidity, elevation)
+    }
+
+    /// Elevation range a biome's fine relief may span either way: tall for
+    /// broken mountain ground, next to nothing for marsh and open water.
+    pub fn detail_budget(biome: TileType) -> f64 {
+        use TileType::*;
+        match biome {
+            Mountain => 0.08,
+            Volcanic => 0.07,
+            Badlands => 0.05,
+            Plateau | Snow | Glacier => 0.04,
+            Sahara | OceanTrench => 0.03, // Dunes, and a ragged trench floor
+            Taiga | Tundra | Forest | Jungle | Desert => 0.025,
+            Plains | Steppe | Savanna => 0.015,
+            White | ShallowSea | Sea | DeepSea => 0.01,
+            Beach | Marsh => 0.005,
+            River | Lake => 0.0,
+        }
+    }
+
+    /// Elevation with `detail`, a finer texture normalized to [-1, 1], laid
+    /// over `coarse` within `biome`'s [budget](Self::detail_budget).
+    pub fn combine_detail(&self, biome: TileType, coarse: f64, detail: f64) -> f64 {
+        coarse + detail.clamp(-1.0, 1.0) * Self::detail_budget(biome)
+    }
+
     fn adjust_humidity(&self, humidity: f64, elevation: f64) -> f64 {
         let elevation_above_sea = (elevation - self.sea_level).max(0.0);
 
@@ -343,17 +533,42 @@ mod tests {
         BiomeSplines::new(-0.025) // Default sea level
     }
 
+    #[test]
+    fn detail_budget_follows_ruggedness() {
+        let budget = BiomeSplines::detail_budget;
+        assert!(budget(TileType::Mountain) > budget(TileType::Forest));
+        assert!(budget(TileType::Forest) > budget(TileType::Plains));
+        assert!(budget(TileType::Plains) > budget(TileType::Marsh));
+        assert_eq!(budget(TileType::River), 0.0);
+
+        let splines = splines();
+        assert_eq!(splines.combine_detail(TileType::Plains, 0.1, 0.0), 0.1);
+        let peak = splines.combine_detail(TileType::Mountain, 0.4, 2.0);
+        assert_eq!(peak, 0.4 + budget(TileType::Mountain));
+    }
+
     #[test]
     fn ocean_is_sea() {
         let s = splines();
-        let biome = s.evaluate(-0.5, 20.0, 0.5, 0.5, 0.0, 0.5);
+        let biome = s.evaluate(-0.1, 20.0, 0.5, 0.5, 0.0, 0.5, 0.0);
         assert_eq!(biome, TileType::Sea);
     }
 
+    #[test]
+    fn ocean_depth_tiers() {
+        let s = splines();
+        // Just below sea level is the shallow shelf
+        let shallow = s.evaluate(-0.03, 20.0, 0.5, 0.5, 0.0, 0.5, 0.0);
+        assert_eq!(shallow, TileType::ShallowSea);
+        // Far below sea level (away from plate boundaries) is deep ocean
+        let deep = s.evaluate(-0.5, 20.0, 0.5, 0.5, 0.0, 0.5, 0.0);
+        assert_eq!(deep, TileType::DeepSea);
+    }
+
     #[test]
     fn frozen_ocean_is_white() {
         let s = splines();
-        let biome = s.evaluate(-0.5, -30.0, 0.5, 0.5, 0.0, 0.5);
+        let biome = s.evaluate(-0.5, -30.0, 0.5, 0.5, 0.0, 0.5, 0.0);
         assert_eq!(biome, TileType::White);
     }
 
@@ -362,14 +577,14 @@ mod tests {
         let s = splines();
         // Deep ocean at plate boundary (tectonic = 0) in temperate water
         // Note: Needs to be deep enough (elevation < sea_level - 0.2)
-        let biome = s.evaluate(-0.6, 20.0, 0.0, 0.5, 0.0, 0.5);
+        let biome = s.evaluate(-0.6, 20.0, 0.0, 0.5, 0.0, 0.5, 0.0);
         assert_eq!(biome, TileType::OceanTrench);
     }
 
     #[test]
     fn coastal_is_beach() {
         let s = splines();
-        let biome = s.evaluate(-0.01, 25.0, 0.5, 0.5, 0.0, 0.5);
+        let biome = s.evaluate(-0.01, 25.0, 0.5, 0.5, 0.0, 0.5, 0.0);
         assert_eq!(biome, TileType::Beach);
     }
 
@@ -377,38 +592,52 @@ mod tests {
     fn frozen_land_is_glacier_or_snow() {
         let s = splines();
         // Frozen + dry = glacier
-        let biome = s.evaluate(0.1, -40.0, 0.5, 0.5, 0.0, 0.1);
+        let biome = s.evaluate(0.1, -40.0, 0.5, 0.5, 0.0, 0.1, 0.0);
         assert_eq!(biome, TileType::Glacier);
         // Frozen + humid = snow
-        let biome2 = s.evaluate(0.1, -40.0, 0.5, 0.5, 0.0, 0.7);
+        let biome2 = s.evaluate(0.1, -40.0, 0.5, 0.5, 0.0, 0.7, 0.0);
         assert_eq!(biome2, TileType::Snow);
     }
 
     #[test]
     fn cold_dry_is_tundra() {
         let s = splines();
-        let biome = s.evaluate(0.1, -10.0, 0.5, 0.5, 0.0, 0.1);
+        let biome = s.evaluate(0.1, -10.0, 0.5, 0.5, 0.0, 0.1, 0.0);
         assert_eq!(biome, TileType::Tundra);
     }
 
     #[test]
     fn cold_wet_is_taiga() {
         let s = splines();
-        let biome = s.evaluate(0.1, -10.0, 0.5, 0.5, 0.0, 0.6);
+        let biome = s.evaluate(0.1, -10.0, 0.5, 0.5, 0.0, 0.6, 0.0);
         assert_eq!(biome, TileType::Taiga);
     }
 
     #[test]
     fn temperate_dry_is_steppe() {
         let s = splines();
-        let biome = s.evaluate(0.1, 20.0, 0.5, 0.5, 0.0, 0.15);
+        let biome = s.evaluate(0.1, 20.0, 0.5, 0.5, 0.0, 0.15, 0.0);
         assert_eq!(biome, TileType::Steppe);
     }
 
+    #[test]
+    fn thresholds_move_the_class_boundaries() {
+        let dry = splines().evaluate(0.1, 20.0, 0.5, 0.5, 0.0, 0.15, 0.0);
+        let defaults = splines().with_thresholds(BiomeThresholds::default());
+        assert_eq!(defaults.evaluate(0.1, 20.0, 0.5, 0.5, 0.0, 0.15, 0.0), dry);
+
+        // Pull the moisture breakpoints below the cell and it turns to plains
+        let wetter = splines().with_thresholds(BiomeThresholds {
+            humidity: [0.05, 0.1, 0.6, 0.8],
+            ..Default::default()
+        });
+        assert_eq!(wetter.evaluate(0.1, 20.0, 0.5, 0.5, 0.0, 0.15, 0.0), TileType::Plains);
+    }
+
     #[test]
     fn temperate_wet_lowland_is_marsh() {
         let s = splines();
-        let biome = s.evaluate(0.02, 20.0, 0.5, 0.5, 0.0, 0.9);
+        let biome = s.evaluate(0.02, 20.0, 0.5, 0.5, 0.0, 0.9, 0.0);
         assert_eq!(biome, TileType::Marsh);
     }
 
@@ -416,21 +645,21 @@ mod tests {
     fn hot_dry_rugged_is_badlands() {
         let s = splines();
         // Hot + arid + rugged terrain (low erosion)
-        let biome = s.evaluate(0.1, 65.0, 0.5, 0.1, 0.0, 0.1);
+        let biome = s.evaluate(0.1, 65.0, 0.5, 0.1, 0.0, 0.1, 0.0);
         assert_eq!(biome, TileType::Badlands);
     }
 
     #[test]
     fn hot_humid_is_jungle() {
         let s = splines();
-        let biome = s.evaluate(0.1, 65.0, 0.5, 0.5, 0.0, 0.7);
+        let biome = s.evaluate(0.1, 65.0, 0.5, 0.5, 0.0, 0.7, 0.0);
         assert_eq!(biome, TileType::Jungle);
     }
 
     #[test]
     fn warm_moderate_is_savanna() {
         let s = splines();
-        let biome = s.evaluate(0.1, 45.0, 0.5, 0.5, 0.0, 0.5);
+        let biome = s.evaluate(0.1, 45.0, 0.5, 0.5, 0.0, 0.5, 0.0);
         assert_eq!(biome, TileType::Savanna);
     }
 
@@ -438,10 +667,10 @@ mod tests {
     fn scorching_is_sahara_or_desert() {
         let s = splines();
         // Scorching + arid = sahara
-        let biome = s.evaluate(0.1, 100.0, 0.5, 0.5, 0.0, 0.1);
+        let biome = s.evaluate(0.1, 100.0, 0.5, 0.5, 0.0, 0.1, 0.0);
         assert_eq!(biome, TileType::Sahara);
         // Scorching + some moisture = desert
-        let biome2 = s.evaluate(0.1, 100.0, 0.5, 0.5, 0.0, 0.4);
+        let biome2 = s.evaluate(0.1, 100.0, 0.5, 0.5, 0.0, 0.4, 0.0);
         assert_eq!(biome2, TileType::Desert);
     }
 
@@ -450,19 +679,33 @@ mod tests {
         let s = splines();
         // Very close to plate boundary (tectonic < 0.1) with hot temperature
         // Need higher elevation (above_sea > 0.08) and temp > 50 after adjustment
-        let biome = s.evaluate(0.15, 60.0, 0.05, 0.5, 0.0, 0.5);
+        let biome = s.evaluate(0.15, 60.0, 0.05, 0.5, 0.0, 0.5, 0.0);
         assert_eq!(biome, TileType::Volcanic);
     }
 
     #[test]
     fn mountains_at_plate_boundaries() {
         let s = splines();
-        // High peaks at plate boundary should create mountains
+        // High peaks where plates collide should create mountains
         // Use 50°C base temp because high elevation causes ~30°C cooling from lapse rate
-        let biome = s.evaluate(0.2, 50.0, 0.1, 0.2, 0.8, 0.5);
+        let biome = s.evaluate(0.2, 50.0, 0.1, 0.2, 0.8, 0.5, 0.8);
         assert_eq!(biome, TileType::Mountain);
     }
 
+    #[test]
+    fn plate_motion_decides_ranges_and_rifts() {
+        let s = splines();
+        // The same ridge only becomes a range where the plates collide
+        let transform = s.evaluate(0.1, 50.0, 0.1, 0.5, 0.5, 0.5, 0.0);
+        let collision = s.evaluate(0.1, 50.0, 0.1, 0.5, 0.5, 0.5, 0.8);
+        assert_ne!(transform, TileType::Mountain);
+        assert_eq!(collision, TileType::Mountain);
+
+        // Pulling apart sinks a low coast into the sea
+        let rift = s.evaluate(0.05, 20.0, 0.1, 0.5, 0.0, 0.5, -1.0);
+        assert!(rift.is_ocean(), "rift is {:?}", rift);
+    }
+
     #[test]
     fn volcanic_heat_affects_temperature() {
         let s = splines();
@@ -488,4 +731,50 @@ mod tests {
             humid_low
         );
     }
+
+    #[test]
+    fn dither_leaves_far_cells_unchanged() {
+        let plain = splines();
+        let dithered = splines().with_dither(BiomeDither::new(7));
+
+        // Well inside Temperate / Moderate / Upland / Rolling
+        let expected = plain.evaluate(0.1, 20.0, 0.5, 0.5, 0.0, 0.5, 0.0);
+        for y in 0..8 {
+            for x in 0..8 {
+                let biome = dithered.evaluate_at(x, y, 0.1, 20.0, 0.5, 0.5, 0.0, 0.5, 0.0);
+                assert_eq!(biome, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn dither_mixes_near_threshold() {
+        let dithered = splines().with_dither(BiomeDither::new(7));
+
+        // Base 40.5°C adjusts to exactly 35°C, the Temperate/Warm boundary
+        let mut plains = 0;
+        let mut savanna = 0;
+        for y in 0..8 {
+            for x in 0..8 {
+                match dithered.evaluate_at(x, y, 0.1, 40.5, 0.5, 0.5, 0.0, 0.5, 0.0) {
+                    TileType::Plains => plains += 1,
+                    TileType::Savanna => savanna += 1,
+                    other => panic!("Unexpected biome {:?}", other),
+                }
+            }
+        }
+        assert!(plains > 0 && savanna > 0, "Band should mix both biomes");
+    }
+
+    #[test]
+    fn dither_is_deterministic() {
+        let a = splines().with_dither(BiomeDither::new(7));
+        let b = splines().with_dither(BiomeDither::new(7));
+        for x in 0..16 {
+            assert_eq!(
+                a.evaluate_at(x, 3, 0.1, 40.5, 0.5, 0.5, 0.0, 0.5, 0.0),
+                b.evaluate_at(x, 3, 0.1, 40.5, 0.5, 0.5, 0.0, 0.5, 0.0)
+            );
+        }
+    }
 }
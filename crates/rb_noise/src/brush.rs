@@ -0,0 +1,146 @@
+//! Hand-painting biomes onto a generated map.
+//!
+//! A brush overwrites `biomes` inside a disk and records what it replaced so
+//! the editor can undo the stroke. Only the biome layer is touched; the noise
+//! layers underneath keep their generated values.
+
+use crate::biome_map::BiomeMap;
+use rb_core::TileType;
+use std::collections::HashSet;
+
+/// Cells overwritten by one brush stroke, with the biome each held before.
+///
+/// A stroke can span several dabs (e.g. a mouse drag); each cell is recorded
+/// once with its value from before the first dab that touched it.
+#[derive(Clone, Debug, Default)]
+pub struct BiomeStroke {
+    cells: Vec<(usize, TileType)>,
+    /// Bounding rect `[x0, x1) × [y0, y1)` of the touched cells.
+    bounds: Option<(usize, usize, usize, usize)>,
+}
+
+impl BiomeStroke {
+    /// Number of cells whose biome changed.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Cell indices touched by the stroke.
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.cells.iter().map(|&(idx, _)| idx)
+    }
+
+    /// Bounding rect `(x0, y0, x1, y1)` of the touched cells, exclusive on the
+    /// far edges. `None` for an empty stroke.
+    pub fn bounds(&self) -> Option<(usize, usize, usize, usize)> {
+        self.bounds
+    }
+
+    /// Fold a later dab into this stroke, keeping the earliest "before" value
+    /// for cells both touched.
+    pub fn merge(&mut self, later: BiomeStroke) {
+        let seen: HashSet<usize> = self.indices().collect();
+        self.cells
+            .extend(later.cells.into_iter().filter(|(idx, _)| !seen.contains(idx)));
+        self.bounds = match (self.bounds, later.bounds) {
+            (Some(a), Some(b)) => Some((a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+impl BiomeMap {
+    /// Overwrite every cell within `radius` of `(cx, cy)` with `biome`.
+    ///
+    /// Cells already holding `biome` are left out of the returned stroke, so
+    /// `len()` is the number of cells that actually flipped. Resources are not
+    /// refreshed here; call [`BiomeMap::recompute_resources_rect`] over
+    /// [`BiomeStroke::bounds`] once the stroke is finished.
+    pub fn paint_biome(
+        &mut self,
+        cx: usize,
+        cy: usize,
+        radius: usize,
+        biome: TileType,
+    ) -> BiomeStroke {
+        let mut stroke = BiomeStroke::default();
+        if cx >= self.width || cy >= self.height {
+            return stroke;
+        }
+
+        let r2 = (radius * radius) as i64;
+        let x0 = cx.saturating_sub(radius);
+        let y0 = cy.saturating_sub(radius);
+        let x1 = (cx + radius + 1).min(self.width);
+        let y1 = (cy + radius + 1).min(self.height);
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let dx = x as i64 - cx as i64;
+                let dy = y as i64 - cy as i64;
+                if dx * dx + dy * dy > r2 {
+                    continue;
+                }
+                let idx = y * self.width + x;
+                if self.biomes[idx] != biome {
+                    stroke.cells.push((idx, self.biomes[idx]));
+                    self.biomes[idx] = biome;
+                }
+            }
+        }
+
+        if !stroke.cells.is_empty() {
+            stroke.bounds = Some((x0, y0, x1, y1));
+        }
+        stroke
+    }
+
+    /// Restore the biomes a stroke overwrote.
+    pub fn revert_stroke(&mut self, stroke: &BiomeStroke) {
+        for &(idx, before) in &stroke.cells {
+            self.biomes[idx] = before;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uniform_map(biome: TileType) -> BiomeMap {
+        let mut map = BiomeMap::generate(42, 32, 32);
+        map.biomes.fill(biome);
+        map
+    }
+
+    #[test]
+    fn radius_three_brush_flips_disk() {
+        let mut map = uniform_map(TileType::Plains);
+        let stroke = map.paint_biome(16, 16, 3, TileType::Desert);
+
+        // Lattice points with dx² + dy² <= 9
+        assert_eq!(stroke.len(), 29);
+        assert_eq!(map.biomes.iter().filter(|&&b| b == TileType::Desert).count(), 29);
+        assert_eq!(stroke.bounds(), Some((13, 13, 20, 20)));
+
+        // Repainting the same biome changes nothing
+        assert!(map.paint_biome(16, 16, 3, TileType::Desert).is_empty());
+    }
+
+    #[test]
+    fn revert_restores_merged_stroke() {
+        let mut map = uniform_map(TileType::Plains);
+        map.biomes[0] = TileType::Tundra;
+        let before = map.biomes.clone();
+
+        let mut stroke = map.paint_biome(1, 1, 2, TileType::Desert);
+        stroke.merge(map.paint_biome(2, 1, 2, TileType::Mountain));
+        map.revert_stroke(&stroke);
+
+        assert_eq!(map.biomes, before);
+    }
+}
@@ -1,9 +1,10 @@
 use rb_core::{ChunkCoord, DetailLevel, NoiseStrategy};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Instant;
 
 /// Configuration for chunk caches.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CacheConfig {
     pub macro_cache_size: usize,
     pub meso_cache_size: usize,
@@ -91,16 +92,28 @@ impl MicroCache {
         chunk
     }
 
+    /// Change the capacity, evicting least recently used chunks if over it.
+    pub fn resize(&mut self, max_size: usize) {
+        self.max_size = max_size;
+        while self.chunks.len() > self.max_size {
+            self.evict_oldest();
+        }
+    }
+
     fn evict_if_needed(&mut self) {
         if self.chunks.len() >= self.max_size {
-            // Find oldest chunk
-            if let Some((&oldest_key, _)) = self
-                .chunks
-                .iter()
-                .min_by_key(|(_, chunk)| chunk.last_accessed)
-            {
-                self.chunks.remove(&oldest_key);
-            }
+            self.evict_oldest();
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        // Find oldest chunk
+        if let Some((&oldest_key, _)) = self
+            .chunks
+            .iter()
+            .min_by_key(|(_, chunk)| chunk.last_accessed)
+        {
+            self.chunks.remove(&oldest_key);
         }
     }
 }
@@ -187,15 +200,28 @@ impl MesoCache {
         self.micro_cache.get_or_create(coord, strategy, world_offset)
     }
 
+    /// Change the meso and micro capacities, evicting as needed.
+    pub fn resize(&mut self, max_size: usize, micro_cache_size: usize) {
+        self.max_size = max_size;
+        while self.chunks.len() > self.max_size {
+            self.evict_oldest();
+        }
+        self.micro_cache.resize(micro_cache_size);
+    }
+
     fn evict_if_needed(&mut self) {
         if self.chunks.len() >= self.max_size {
-            if let Some((&oldest_key, _)) = self
-                .chunks
-                .iter()
-                .min_by_key(|(_, chunk)| chunk.last_accessed)
-            {
-                self.chunks.remove(&oldest_key);
-            }
+            self.evict_oldest();
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some((&oldest_key, _)) = self
+            .chunks
+            .iter()
+            .min_by_key(|(_, chunk)| chunk.last_accessed)
+        {
+            self.chunks.remove(&oldest_key);
         }
     }
 }
@@ -323,6 +349,23 @@ impl ChunkHierarchy {
         }
     }
 
+    /// Sample at a continuous detail value, blending the cached samples of the
+    /// two adjacent levels.
+    pub fn sample_blended(
+        &mut self,
+        x: f64,
+        y: f64,
+        detail: f64,
+        strategy: &dyn NoiseStrategy,
+    ) -> f64 {
+        let (lower, upper, t) = DetailLevel::blend(detail);
+        let coarse = self.sample(x, y, lower, strategy);
+        if t <= 0.0 {
+            return coarse;
+        }
+        coarse + (self.sample(x, y, upper, strategy) - coarse) * t
+    }
+
     /// Convert chunk coordinate to world offset.
     fn coord_to_world_offset(coord: ChunkCoord, chunk_size: usize) -> (f64, f64) {
         (
@@ -344,16 +387,36 @@ impl ChunkHierarchy {
 
     fn evict_macro_if_needed(&mut self) {
         if self.macro_chunks.len() >= self.config.macro_cache_size {
-            if let Some((&oldest_key, _)) = self
-                .macro_chunks
-                .iter()
-                .min_by_key(|(_, chunk)| chunk.last_accessed)
-            {
-                self.macro_chunks.remove(&oldest_key);
-            }
+            self.evict_oldest_macro();
+        }
+    }
+
+    fn evict_oldest_macro(&mut self) {
+        if let Some((&oldest_key, _)) = self
+            .macro_chunks
+            .iter()
+            .min_by_key(|(_, chunk)| chunk.last_accessed)
+        {
+            self.macro_chunks.remove(&oldest_key);
         }
     }
 
+    /// Current cache configuration.
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    /// Apply a new cache configuration, evicting least recently used chunks
+    /// from any cache that is now over its limit. Cached data is otherwise kept.
+    pub fn set_config(&mut self, config: CacheConfig) {
+        self.config = config;
+        while self.macro_chunks.len() > self.config.macro_cache_size {
+            self.evict_oldest_macro();
+        }
+        self.meso_cache
+            .resize(self.config.meso_cache_size, self.config.micro_cache_size);
+    }
+
     /// Clear all caches.
     pub fn clear(&mut self) {
         self.macro_chunks.clear();
@@ -409,4 +472,27 @@ mod tests {
 
         assert_eq!(value1, value2);
     }
+
+    #[test]
+    fn shrinking_config_evicts_to_limit() {
+        let mut hierarchy = ChunkHierarchy::new(CacheConfig::default());
+        let strategy = ContinentalnessStrategy::new(42);
+
+        for i in 0..6 {
+            hierarchy.get_macro(ChunkCoord::new(i, 0), &strategy);
+            hierarchy.get_meso(ChunkCoord::new(i, 0), &strategy);
+            hierarchy.get_micro(ChunkCoord::new(i, 0), &strategy);
+        }
+
+        hierarchy.set_config(CacheConfig {
+            macro_cache_size: 2,
+            meso_cache_size: 3,
+            micro_cache_size: 4,
+        });
+
+        let stats = hierarchy.stats();
+        assert_eq!(stats.macro_chunks, 2);
+        assert_eq!(stats.meso_chunks, 3);
+        assert_eq!(stats.micro_chunks, 4);
+    }
 }
@@ -0,0 +1,145 @@
+//! Climate-dependent coastline detail.
+//!
+//! Continentalness alone gives every shore the same gently curving outline.
+//! This pass reshapes the band around sea level by local temperature: cold
+//! coasts are cut by narrow fjords, warm ones grow a chain of barrier islands
+//! offshore with a lagoon behind. Temperate coasts are left as they are.
+
+use noise::{NoiseFn, OpenSimplex};
+
+/// Continentalness distance from sea level the pass works within.
+const COAST_BAND: f64 = 0.08;
+
+/// Below this temperature (°C) coasts are fully fjorded.
+const FJORD_FULL_TEMP: f64 = -10.0;
+/// Above this temperature (°C) there are no fjords.
+const FJORD_NONE_TEMP: f64 = 5.0;
+/// Deepest a fjord cuts into continentalness.
+const FJORD_DEPTH: f64 = 0.12;
+/// Frequency of the fjord channels, per cell.
+const FJORD_FREQUENCY: f64 = 0.09;
+
+/// Below this temperature (°C) there are no barrier islands.
+const BARRIER_NONE_TEMP: f64 = 20.0;
+/// Above this temperature (°C) warm coasts get their full island chain.
+const BARRIER_FULL_TEMP: f64 = 35.0;
+/// Lift at the crest of the island chain; enough to raise mid-band shallows
+/// above sea level.
+const BARRIER_LIFT: f64 = 0.07;
+/// Frequency of the gaps (tidal inlets) between islands, per cell.
+const INLET_FREQUENCY: f64 = 0.05;
+
+/// Linear ramp from 0 at `from` to 1 at `to`, clamped.
+fn ramp(value: f64, from: f64, to: f64) -> f64 {
+    ((value - from) / (to - from)).clamp(0.0, 1.0)
+}
+
+/// Reshape coasts in place according to `temperature`.
+///
+/// Only cells within [`COAST_BAND`] of `sea_level` change, so open ocean and
+/// inland terrain keep their generated values.
+pub fn shape_coastline(
+    continentalness: &mut [f64],
+    temperature: &[f64],
+    width: usize,
+    height: usize,
+    sea_level: f64,
+    seed: u32,
+) {
+    let fjords = OpenSimplex::new(seed.wrapping_add(20));
+    let inlets = OpenSimplex::new(seed.wrapping_add(21));
+
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            let cont = continentalness[idx];
+            let depth = (cont - sea_level).abs();
+            if depth >= COAST_BAND {
+                continue;
+            }
+            let (fx, fy) = (x as f64, y as f64);
+            let temp = temperature[idx];
+
+            // Fjords: ridged noise peaks along thin winding lines, carved
+            // deepest at the shore and fading inland
+            let cold = ramp(temp, FJORD_NONE_TEMP, FJORD_FULL_TEMP);
+            if cold > 0.0 {
+                let ridge = 1.0 - fjords.get([fx * FJORD_FREQUENCY, fy * FJORD_FREQUENCY]).abs();
+                let shore = 1.0 - depth / COAST_BAND;
+                continentalness[idx] -= cold * shore * FJORD_DEPTH * ridge.powi(4);
+            }
+
+            // Barrier islands: a raised strip halfway out through the
+            // shallows, broken by inlets. Land is untouched, which leaves a
+            // lagoon between the strip and the shore.
+            let warm = ramp(temp, BARRIER_NONE_TEMP, BARRIER_FULL_TEMP);
+            if warm > 0.0 && cont < sea_level {
+                let offshore = depth / COAST_BAND;
+                let crest = (1.0 - (offshore - 0.5).abs() * 4.0).max(0.0);
+                let open = inlets.get([fx * INLET_FREQUENCY, fy * INLET_FREQUENCY]) > -0.2;
+                if open {
+                    continentalness[idx] += warm * crest * BARRIER_LIFT;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Land in the west sloping into sea in the east, at a uniform temperature.
+    fn straight_coast(width: usize, height: usize, temp: f64) -> (Vec<f64>, Vec<f64>) {
+        let continentalness = (0..width * height)
+            .map(|idx| 0.3 - 0.6 * (idx % width) as f64 / width as f64)
+            .collect();
+        (continentalness, vec![temp; width * height])
+    }
+
+    /// Standard deviation of the mainland shoreline's column across rows.
+    fn shoreline_roughness(continentalness: &[f64], width: usize, sea_level: f64) -> f64 {
+        let shore: Vec<f64> = continentalness
+            .chunks(width)
+            .map(|row| row.iter().position(|&c| c < sea_level).unwrap_or(width) as f64)
+            .collect();
+        let mean = shore.iter().sum::<f64>() / shore.len() as f64;
+        let variance = shore.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / shore.len() as f64;
+        variance.sqrt()
+    }
+
+    #[test]
+    fn cold_coasts_are_rougher_than_warm() {
+        let (width, height, sea_level) = (128, 256, 0.0);
+
+        let (mut cold, cold_temp) = straight_coast(width, height, -30.0);
+        shape_coastline(&mut cold, &cold_temp, width, height, sea_level, 42);
+        let (mut warm, warm_temp) = straight_coast(width, height, 40.0);
+        shape_coastline(&mut warm, &warm_temp, width, height, sea_level, 42);
+
+        let cold_roughness = shoreline_roughness(&cold, width, sea_level);
+        let warm_roughness = shoreline_roughness(&warm, width, sea_level);
+        assert!(
+            cold_roughness > warm_roughness,
+            "Cold coast roughness {} not above warm {}",
+            cold_roughness,
+            warm_roughness
+        );
+
+        // The warm coast gained islands offshore of its shoreline
+        let islands = warm
+            .chunks(width)
+            .filter(|row| {
+                let shore = row.iter().position(|&c| c < sea_level).unwrap();
+                row[shore..].iter().any(|&c| c >= sea_level)
+            })
+            .count();
+        assert!(islands > height / 4, "Only {} rows have barrier islands", islands);
+
+        // A temperate coast is left alone
+        let (mut mild, mild_temp) = straight_coast(width, height, 12.0);
+        let before = mild.clone();
+        shape_coastline(&mut mild, &mild_temp, width, height, sea_level, 42);
+        assert_eq!(mild, before);
+    }
+}
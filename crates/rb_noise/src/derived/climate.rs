@@ -0,0 +1,112 @@
+//! Köppen-style climate zones from temperature and humidity.
+//!
+//! Biomes mix climate with terrain; a zone map answers only "what is the
+//! weather like here", which is what worldbuilding notes tend to want. Both
+//! inputs are adjusted for altitude the same way biome selection does, so
+//! mountain tops read colder and rain-shadowed slopes drier.
+
+use crate::biome_map::{BiomeMap, SEA_LEVEL};
+use crate::biome_splines::{BiomeSplines, ClimateClass, MoistureClass};
+use crate::precision::LayerValue;
+
+/// Broad climate zone of a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClimateZone {
+    Tropical,
+    Arid,
+    Temperate,
+    Continental,
+    Polar,
+}
+
+impl ClimateZone {
+    /// Returns all zones, hottest to coldest.
+    pub fn all() -> &'static [ClimateZone] {
+        &[
+            Self::Tropical,
+            Self::Arid,
+            Self::Temperate,
+            Self::Continental,
+            Self::Polar,
+        ]
+    }
+
+    /// Zone for an altitude-adjusted temperature (°C) and humidity (0-1).
+    ///
+    /// Frozen and cold cells are polar and continental whatever the moisture;
+    /// anywhere warmer, dry air makes it arid before heat makes it tropical.
+    pub fn classify(temperature: f64, humidity: f64) -> Self {
+        let climate = ClimateClass::from_temperature(temperature);
+        let moisture = MoistureClass::from_humidity(humidity);
+        match (climate, moisture) {
+            (ClimateClass::Frozen, _) => Self::Polar,
+            (ClimateClass::Cold, _) => Self::Continental,
+            (_, MoistureClass::Arid | MoistureClass::Dry) => Self::Arid,
+            (ClimateClass::Temperate, _) => Self::Temperate,
+            _ => Self::Tropical,
+        }
+    }
+
+    /// Display name for UI.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Tropical => "Tropical",
+            Self::Arid => "Arid",
+            Self::Temperate => "Temperate",
+            Self::Continental => "Continental",
+            Self::Polar => "Polar",
+        }
+    }
+
+    /// Map color, after the usual Köppen chart hues.
+    pub fn color(&self) -> [u8; 4] {
+        match self {
+            Self::Tropical => [0, 120, 255, 255],
+            Self::Arid => [245, 165, 0, 255],
+            Self::Temperate => [50, 200, 50, 255],
+            Self::Continental => [150, 50, 200, 255],
+            Self::Polar => [170, 220, 230, 255],
+        }
+    }
+}
+
+impl BiomeMap {
+    /// Climate zone of cell `(x, y)`, or `None` outside the map.
+    pub fn climate_zone(&self, x: usize, y: usize) -> Option<ClimateZone> {
+        let elevation = self.elevation(x, y)?;
+        let idx = y * self.width + x;
+        let splines = BiomeSplines::new(SEA_LEVEL);
+        let temperature = splines.adjusted_temperature(
+            self.temperature[idx].to_f64(),
+            elevation,
+            self.tectonic[idx].to_f64(),
+        );
+        let humidity = splines.adjusted_humidity(self.humidity[idx].to_f64(), elevation);
+        Some(ClimateZone::classify(temperature, humidity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hot_wet_is_tropical_and_cold_dry_is_polar() {
+        assert_eq!(ClimateZone::classify(45.0, 0.9), ClimateZone::Tropical);
+        assert_eq!(ClimateZone::classify(-40.0, 0.05), ClimateZone::Polar);
+        assert_eq!(ClimateZone::classify(45.0, 0.1), ClimateZone::Arid);
+
+        // Same through the map, on flat lowland away from plate boundaries
+        let mut map = BiomeMap::generate(42, 2, 1);
+        map.continentalness.fill(0.1);
+        map.peaks_valleys.fill(0.0);
+        map.erosion.fill(0.5);
+        map.tectonic.fill(1.0);
+        map.temperature = vec![45.0, -40.0];
+        map.humidity = vec![0.9, 0.05];
+
+        assert_eq!(map.climate_zone(0, 0), Some(ClimateZone::Tropical));
+        assert_eq!(map.climate_zone(1, 0), Some(ClimateZone::Polar));
+        assert_eq!(map.climate_zone(2, 0), None);
+    }
+}
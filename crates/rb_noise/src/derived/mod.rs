@@ -1 +1,4 @@
-// Derived noise modules - currently empty after removal of political and trade cost layers
+// Derived layers computed from the base noise layers after generation
+
+pub mod climate;
+pub mod rainfall;
@@ -0,0 +1,116 @@
+//! Annual rainfall derived from humidity and distance to the sea.
+//!
+//! Raw humidity is a moisture field for biome selection. Rainfall applies the
+//! same rain shadow the splines use, then boosts cells near open water so
+//! coasts read wetter than continental interiors.
+
+use std::collections::VecDeque;
+
+use crate::biome_map::{BiomeMap, SEA_LEVEL};
+use crate::biome_splines::BiomeSplines;
+use crate::precision::LayerValue;
+
+/// Rainfall at fully saturated, maritime cells (mm per year).
+pub const MAX_RAINFALL_MM: f64 = 3000.0;
+
+/// Distance (cells) over which sea moisture decays by a factor of e.
+const MARITIME_REACH: f64 = 24.0;
+
+/// Share of rainfall driven by humidity; the rest comes from sea proximity.
+const HUMIDITY_WEIGHT: f64 = 0.6;
+
+/// Grid distance from each cell to the nearest water cell (8-connected BFS).
+/// Cells with no water on the map get `f64::INFINITY`.
+pub fn distance_to_water(is_water: &[bool], width: usize, height: usize) -> Vec<f64> {
+    let mut distance = vec![f64::INFINITY; width * height];
+    let mut queue = VecDeque::new();
+
+    for (idx, &water) in is_water.iter().enumerate() {
+        if water {
+            distance[idx] = 0.0;
+            queue.push_back(idx);
+        }
+    }
+
+    while let Some(idx) = queue.pop_front() {
+        let (x, y) = ((idx % width) as i64, (idx / width) as i64);
+        let next = distance[idx] + 1.0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= width as i64 || ny >= height as i64 {
+                    continue;
+                }
+                let nidx = ny as usize * width + nx as usize;
+                if next < distance[nidx] {
+                    distance[nidx] = next;
+                    queue.push_back(nidx);
+                }
+            }
+        }
+    }
+
+    distance
+}
+
+impl BiomeMap {
+    /// Annual rainfall per cell in millimetres.
+    pub fn rainfall(&self) -> Vec<f64> {
+        let splines = BiomeSplines::new(SEA_LEVEL);
+        let total = self.width * self.height;
+
+        let elevation: Vec<f64> = (0..total)
+            .map(|idx| self.elevation(idx % self.width, idx / self.width).unwrap())
+            .collect();
+        let is_water: Vec<bool> = elevation.iter().map(|&e| e < SEA_LEVEL).collect();
+        let coast_distance = distance_to_water(&is_water, self.width, self.height);
+
+        (0..total)
+            .map(|idx| {
+                let humidity = splines.adjusted_humidity(self.humidity[idx].to_f64(), elevation[idx]);
+                let maritime = (-coast_distance[idx] / MARITIME_REACH).exp();
+                let wetness = HUMIDITY_WEIGHT * humidity + (1.0 - HUMIDITY_WEIGHT) * maritime;
+                wetness.clamp(0.0, 1.0) * MAX_RAINFALL_MM
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_grows_away_from_water() {
+        let is_water = [true, false, false, false];
+        let distance = distance_to_water(&is_water, 4, 1);
+        assert_eq!(distance, vec![0.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn coasts_are_wetter_than_arid_interiors() {
+        // West half sea, east half flat land with uniform dry air
+        let (w, h) = (64, 16);
+        let mut map = BiomeMap::generate(42, w, h);
+        for y in 0..h {
+            for x in 0..w {
+                let idx = y * w + x;
+                map.continentalness[idx] = if x < 16 { -0.5 } else { 0.2 };
+                map.peaks_valleys[idx] = 0.0;
+                map.erosion[idx] = 0.5;
+                map.tectonic[idx] = 0.5;
+                map.humidity[idx] = 0.2;
+            }
+        }
+
+        let rainfall = map.rainfall();
+        let coast = rainfall[8 * w + 16];
+        let interior = rainfall[8 * w + 63];
+        assert!(
+            coast > interior,
+            "Coast {} mm should exceed interior {} mm",
+            coast,
+            interior
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Working out what a regeneration has to redo.
+//!
+//! Every pass over the macro map (histogram equalization, rivers, lakes,
+//! erosion) looks at the whole map, so it is rebuilt whenever anything that
+//! feeds it changes. Meso tiles are generated from the seeded noise and only
+//! see [`GenerationConfig`] through the macro baseline they are laid over, so
+//! most parameter tweaks leave them close enough that the cached tiles can be
+//! kept. A continent sketch is the exception: it moves whole coastlines. So
+//! are biome overrides, which the tiles apply themselves. Terrain brush edits
+//! are local, so only the tiles laid over the blocks they changed are redone.
+
+use crate::biome_map::GenerationConfig;
+use crate::terrain_edit::TERRAIN_EDIT_CHUNK;
+
+/// Everything a generated world depends on, kept so the next regeneration
+/// can tell what changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerationInputs {
+    pub seed: u32,
+    pub width: usize,
+    pub height: usize,
+    /// Meso tile resolution in pixels per side.
+    pub meso_resolution: usize,
+    pub config: GenerationConfig,
+}
+
+/// The parts of a world a regeneration must rebuild.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirtyRegion {
+    /// Rebuild and retexture the macro map.
+    pub macro_map: bool,
+    /// Regenerate the meso tiles.
+    pub meso_tiles: bool,
+    /// Meso tiles to regenerate, by chunk, when not all of them are.
+    pub meso_chunks: Vec<(i32, i32)>,
+}
+
+impl DirtyRegion {
+    /// Everything, for a world with nothing generated yet.
+    pub const ALL: Self = Self {
+        macro_map: true,
+        meso_tiles: true,
+        meso_chunks: Vec::new(),
+    };
+
+    /// What has to be regenerated to go from a world built from `old` to
+    /// one built from `new`.
+    pub fn between(old: &GenerationInputs, new: &GenerationInputs) -> Self {
+        let world_changed =
+            old.seed != new.seed || old.width != new.width || old.height != new.height;
+        let sketch_changed = old.config.sketch != new.config.sketch
+            || (new.config.sketch.is_some() && old.config.sketch_weight != new.config.sketch_weight);
+        let meso_tiles = world_changed
+            || sketch_changed
+            || old.config.biome_overrides != new.config.biome_overrides
+            || old.meso_resolution != new.meso_resolution;
+        let meso_chunks = if meso_tiles {
+            Vec::new()
+        } else {
+            edited_tiles(old, new)
+        };
+        Self {
+            macro_map: world_changed || old.config != new.config,
+            meso_tiles,
+            meso_chunks,
+        }
+    }
+
+    /// Whether nothing needs regenerating.
+    pub fn is_clean(&self) -> bool {
+        !self.macro_map && !self.meso_tiles && self.meso_chunks.is_empty()
+    }
+}
+
+/// Meso tiles reaching into a terrain edit block that changed. Edit blocks
+/// line up with the tiles, but a tile also interpolates toward the first
+/// column and row of the blocks right and below it.
+fn edited_tiles(old: &GenerationInputs, new: &GenerationInputs) -> Vec<(i32, i32)> {
+    let chunks_x = new.width.div_ceil(TERRAIN_EDIT_CHUNK) as i32;
+    let chunks_y = new.height.div_ceil(TERRAIN_EDIT_CHUNK) as i32;
+    let mut tiles: Vec<(i32, i32)> = new
+        .config
+        .terrain_edits
+        .changed_chunks(&old.config.terrain_edits)
+        .into_iter()
+        .flat_map(|(x, y)| {
+            let (x, y) = (x as i32, y as i32);
+            [(x, y), (x - 1, y), (x, y - 1), (x - 1, y - 1)]
+        })
+        .filter(|&(x, y)| (0..chunks_x).contains(&x) && (0..chunks_y).contains(&y))
+        .collect();
+    tiles.sort_unstable();
+    tiles.dedup();
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_what_changed_is_dirty() {
+        let old = GenerationInputs {
+            seed: 7,
+            width: 1024,
+            height: 512,
+            meso_resolution: 512,
+            config: GenerationConfig::default(),
+        };
+        assert!(DirtyRegion::between(&old, &old).is_clean());
+
+        // Macro-only options leave the meso tiles alone
+        let mut warped = old.clone();
+        warped.config.warp_amplitude = 20.0;
+        let dirty = DirtyRegion::between(&old, &warped);
+        assert!(dirty.macro_map && !dirty.meso_tiles);
+
+        let finer = GenerationInputs { meso_resolution: 1024, ..old.clone() };
+        let dirty = DirtyRegion::between(&old, &finer);
+        assert!(!dirty.macro_map && dirty.meso_tiles);
+
+        let mut sketched = old.clone();
+        sketched.config.sketch = Some(std::sync::Arc::new(crate::ContinentSketch::new(1, 1, vec![0])));
+        assert_eq!(DirtyRegion::between(&old, &sketched), DirtyRegion::ALL);
+
+        let mut cursed = old.clone();
+        cursed.config.biome_overrides.push(crate::BiomeOverride {
+            outline: vec![(0.0, 0.0), (8.0, 0.0), (0.0, 8.0)],
+            biome: rb_core::TileType::Forest,
+        });
+        assert_eq!(DirtyRegion::between(&old, &cursed), DirtyRegion::ALL);
+
+        // Sculpting only redoes the tiles under and just before the edit
+        let mut sculpted = old.clone();
+        let mut edits = crate::TerrainEdits::default();
+        let mut field = vec![0.0; 1024 * 512];
+        let brush = crate::TerrainBrush {
+            mode: crate::TerrainBrushMode::Raise,
+            radius: 4.0,
+            strength: 0.1,
+            falloff: 0.5,
+        };
+        edits.dab(&mut field, 1024, 512, (200.0, 10.0), &brush, 0.0);
+        sculpted.config.terrain_edits = std::sync::Arc::new(edits);
+        let dirty = DirtyRegion::between(&old, &sculpted);
+        assert!(dirty.macro_map && !dirty.meso_tiles);
+        assert_eq!(dirty.meso_chunks, [(2, 0), (3, 0)]);
+        assert!(DirtyRegion::between(&sculpted, &sculpted).is_clean());
+
+        let reseeded = GenerationInputs { seed: 8, ..old.clone() };
+        assert_eq!(DirtyRegion::between(&old, &reseeded), DirtyRegion::ALL);
+    }
+}
@@ -0,0 +1,299 @@
+//! Terrain elevation shared by river routing, biome classification and
+//! point queries.
+//!
+//! Elevation is derived from continentalness with mountain chains raised where
+//! tectonic plates collide, softened by erosion, and carved by valleys, rifts
+//! and ocean trenches. Consumers query it through [`ElevationSource`] so they don't
+//! care whether the data was precomputed into a [`BiomeMap`] or is streamed
+//! from the chunk hierarchy.
+
+use rb_core::{DetailLevel, ElevationSource, NoiseStrategy};
+use std::sync::Mutex;
+
+use crate::biome_map::{BiomeMap, SEA_LEVEL};
+use crate::biome_splines::BiomeSplines;
+use crate::chunk_hierarchy::{CacheConfig, ChunkHierarchy};
+use crate::precision::LayerValue;
+use crate::strategy::{
+    ContinentalnessStrategy, ErosionStrategy, HumidityStrategy, PeaksAndValleysStrategy,
+    TectonicPlatesStrategy,
+};
+use crate::tidally_locked::LatitudeTemperatureStrategy;
+
+/// Lowest and highest elevation [`terrain_elevation`] can return. Colliding
+/// plates triple peaks to 0.45 and lift land 0.1; trenches cut 0.15.
+pub const ELEVATION_RANGE: (f64, f64) = (-1.15, 1.55);
+
+/// Compute effective terrain elevation from the base noise layers.
+///
+/// # Arguments
+/// * `continentalness` - Base terrain height (-1 to 1)
+/// * `peaks_valleys` - Ridgeline noise (-1 = valley, 1 = peak)
+/// * `erosion` - Erosion amount (0-1)
+/// * `uplift` - Plate boundary stress (1 = colliding, -1 = rifting), see
+///   [`PlateBoundary::uplift`](crate::PlateBoundary::uplift)
+/// * `sea_level` - Land/ocean threshold for continentalness
+pub fn terrain_elevation(
+    continentalness: f64,
+    peaks_valleys: f64,
+    erosion: f64,
+    uplift: f64,
+    sea_level: f64,
+) -> f64 {
+    let is_land = continentalness >= sea_level;
+    let collision = uplift.max(0.0);
+    let rift = (-uplift).max(0.0);
+
+    // Mountains amplified where plates collide
+    let tectonic_amp = 1.0 + collision * 2.0;
+    // Erosion dampens peaks (high erosion = worn mountains)
+    let erosion_damp = 1.0 - erosion * 0.7;
+
+    let peak_height = if is_land {
+        peaks_valleys.max(0.0) * 0.15 * tectonic_amp * erosion_damp
+    } else {
+        0.0
+    };
+    let valley_depth = if is_land {
+        peaks_valleys.min(0.0).abs() * 0.08
+    } else {
+        0.0
+    };
+
+    // Collisions lift the crust into ranges on land and sink trenches at sea;
+    // separating plates open rift valleys on land and ridges on the seabed
+    let crust = if is_land {
+        collision * 0.1 - rift * 0.12
+    } else {
+        rift * 0.05 - collision * 0.15
+    };
+
+    continentalness + peak_height - valley_depth + crust
+}
+
+/// Bilinearly interpolate a lattice sampled at integer coordinates.
+fn bilinear(x: f64, y: f64, mut sample: impl FnMut(i64, i64) -> f64) -> f64 {
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let fx = x - x0;
+    let fy = y - y0;
+    let (x0, y0) = (x0 as i64, y0 as i64);
+
+    let top = sample(x0, y0) * (1.0 - fx) + sample(x0 + 1, y0) * fx;
+    let bottom = sample(x0, y0 + 1) * (1.0 - fx) + sample(x0 + 1, y0 + 1) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+impl BiomeMap {
+    /// Terrain elevation of a single cell.
+    pub fn elevation(&self, x: usize, y: usize) -> Option<f64> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let idx = y * self.width + x;
+        Some(terrain_elevation(
+            self.continentalness[idx].to_f64(),
+            self.peaks_valleys[idx].to_f64(),
+            self.erosion[idx].to_f64(),
+            self.uplift[idx].to_f64(),
+            SEA_LEVEL,
+        ))
+    }
+}
+
+/// Bilinear over the precomputed grid, clamped at the map edges.
+impl ElevationSource for BiomeMap {
+    fn height(&self, x: f64, y: f64) -> f64 {
+        if self.width == 0 || self.height == 0 {
+            return SEA_LEVEL;
+        }
+        let max_x = self.width as i64 - 1;
+        let max_y = self.height as i64 - 1;
+        bilinear(x, y, |sx, sy| {
+            let cx = sx.clamp(0, max_x) as usize;
+            let cy = sy.clamp(0, max_y) as usize;
+            self.elevation(cx, cy).unwrap_or(SEA_LEVEL)
+        })
+    }
+}
+
+/// Noise strategy producing terrain elevation directly, so it can be cached
+/// by a [`ChunkHierarchy`] like any single layer.
+///
+/// At [`DetailLevel::Micro`] the finer octaves would only nudge the terrain,
+/// so they are split off and laid back on with the biome's relief budget
+/// instead; the climate layers are sampled just to tell the biome.
+pub struct ElevationStrategy {
+    continentalness: ContinentalnessStrategy,
+    temperature: LatitudeTemperatureStrategy,
+    tectonic: TectonicPlatesStrategy,
+    erosion: ErosionStrategy,
+    peaks: PeaksAndValleysStrategy,
+    humidity: HumidityStrategy,
+    splines: BiomeSplines,
+}
+
+impl ElevationStrategy {
+    /// Create an elevation strategy using the same per-layer seeds as `BiomeMap`.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            continentalness: ContinentalnessStrategy::new(seed),
+            temperature: LatitudeTemperatureStrategy::default_for_map(seed.wrapping_add(1)),
+            tectonic: TectonicPlatesStrategy::new(seed.wrapping_add(2)),
+            erosion: ErosionStrategy::new(seed.wrapping_add(3)),
+            peaks: PeaksAndValleysStrategy::new(seed.wrapping_add(4)),
+            humidity: HumidityStrategy::new(seed.wrapping_add(5)),
+            splines: BiomeSplines::new(SEA_LEVEL),
+        }
+    }
+
+    /// Base terrain with its detail octaves stretched over the biome's
+    /// relief budget.
+    fn textured(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        let (cont, cont_detail) = self.continentalness.generate_split(x, y, detail_level);
+        let (peaks, peaks_detail) = self.peaks.generate_split(x, y, detail_level);
+        let uplift = self.tectonic.boundary(x, y).uplift();
+        let eros = self.erosion.generate_with_continentalness(x, y, 0, cont);
+        let coarse = terrain_elevation(cont, peaks, eros, uplift, SEA_LEVEL);
+
+        let biome = self.splines.evaluate(
+            cont,
+            self.temperature.generate(x, y, 0),
+            self.tectonic.generate(x, y, 0),
+            eros,
+            peaks,
+            self.humidity.generate(x, y, 0),
+            uplift,
+        );
+        let detail = (cont_detail + peaks_detail) * 0.5;
+        let (min, max) = self.output_range();
+        self.splines.combine_detail(biome, coarse, detail).clamp(min, max)
+    }
+}
+
+impl NoiseStrategy for ElevationStrategy {
+    fn generate(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        if detail_level >= DetailLevel::Micro.as_u32() {
+            return self.textured(x, y, detail_level);
+        }
+        let cont = self.continentalness.generate(x, y, detail_level);
+        let uplift = self.tectonic.boundary(x, y).uplift();
+        let peaks = self.peaks.generate(x, y, detail_level);
+        let eros = self
+            .erosion
+            .generate_with_continentalness(x, y, detail_level, cont);
+        terrain_elevation(cont, peaks, eros, uplift, SEA_LEVEL)
+    }
+
+    fn name(&self) -> &'static str {
+        "Elevation"
+    }
+
+    fn output_range(&self) -> (f64, f64) {
+        ELEVATION_RANGE
+    }
+
+    fn description(&self) -> &'static str {
+        "Terrain height: continentalness with mountains, valleys and trenches applied"
+    }
+}
+
+/// Elevation streamed on demand from a chunk hierarchy.
+///
+/// Samples are generated lazily and cached per chunk, so the world is
+/// unbounded and nothing needs to be precomputed.
+pub struct StreamedElevation {
+    hierarchy: Mutex<ChunkHierarchy>,
+    strategy: ElevationStrategy,
+    detail_level: DetailLevel,
+}
+
+impl StreamedElevation {
+    /// Create a streamed elevation source sampling at the given detail level.
+    pub fn new(seed: u32, detail_level: DetailLevel) -> Self {
+        Self::with_config(seed, detail_level, CacheConfig::default())
+    }
+
+    /// Create with a custom cache configuration.
+    pub fn with_config(seed: u32, detail_level: DetailLevel, config: CacheConfig) -> Self {
+        Self {
+            hierarchy: Mutex::new(ChunkHierarchy::new(config)),
+            strategy: ElevationStrategy::new(seed),
+            detail_level,
+        }
+    }
+}
+
+impl ElevationSource for StreamedElevation {
+    fn height(&self, x: f64, y: f64) -> f64 {
+        let mut hierarchy = self.hierarchy.lock().unwrap();
+        bilinear(x, y, |sx, sy| {
+            hierarchy.sample(sx as f64, sy as f64, self.detail_level, &self.strategy)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bilinear_interpolates_between_samples() {
+        let value = bilinear(0.5, 0.5, |x, y| (x + y) as f64);
+        assert!((value - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn trench_only_in_ocean() {
+        let land = terrain_elevation(0.1, 0.0, 0.5, 1.0, SEA_LEVEL);
+        let ocean = terrain_elevation(-0.3, 0.0, 0.5, 1.0, SEA_LEVEL);
+        assert!(land > 0.1);
+        assert!(ocean < -0.3);
+    }
+
+    #[test]
+    fn collisions_raise_ranges_and_rifts_sink() {
+        let ridge = |uplift| terrain_elevation(0.1, 0.6, 0.2, uplift, SEA_LEVEL);
+        assert_eq!(terrain_elevation(0.1, 0.0, 0.5, 0.0, SEA_LEVEL), 0.1);
+        assert!(ridge(1.0) > ridge(0.5) && ridge(0.5) > ridge(0.0));
+        assert!(ridge(-1.0) < ridge(0.0));
+        assert!(terrain_elevation(-0.3, 0.0, 0.5, -1.0, SEA_LEVEL) > -0.3);
+    }
+
+    #[test]
+    fn micro_terrain_gets_biome_relief() {
+        let strategy = ElevationStrategy::new(42);
+        let micro = DetailLevel::Micro.as_u32();
+        let (mut textured, mut added) = (0.0, 0.0);
+        for i in 0..400 {
+            let (x, y) = ((i % 20) as f64 * 0.7, 200.0 + (i / 20) as f64 * 0.9);
+            let base = strategy.generate(x, y, 0);
+            textured += (strategy.generate(x, y, micro) - base).abs() / 400.0;
+            added += (strategy.generate(x, y, micro - 1) - base).abs() / 400.0;
+        }
+        // Stacked on, the meso octave barely shows; split off, the micro ones do
+        assert!(textured > added * 10.0, "{:.5} vs {:.5}", textured, added);
+    }
+
+    #[test]
+    fn biome_map_and_stream_agree_at_cell_centers() {
+        let map = BiomeMap::generate(42, 64, 32);
+        let streamed = StreamedElevation::new(42, DetailLevel::Macro);
+
+        let sources: [&dyn ElevationSource; 2] = [&map, &streamed];
+        // The map's layers are rounded to f32 with that feature on
+        let tolerance = if cfg!(feature = "f32") { 1e-5 } else { 1e-9 };
+        for &(x, y) in &[(0, 0), (10, 5), (31, 17), (63, 31)] {
+            let (fx, fy) = (x as f64, y as f64);
+            let expected = map.elevation(x, y).unwrap();
+            for source in sources {
+                assert!(
+                    (source.height(fx, fy) - expected).abs() < tolerance,
+                    "Mismatch at ({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+}
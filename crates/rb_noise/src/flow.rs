@@ -0,0 +1,187 @@
+//! D8 water flow over an elevation grid.
+//!
+//! Each cell drains to whichever of its eight neighbours is steepest downhill.
+//! Accumulation then counts how many cells drain through each one. River
+//! tracing thresholds the accumulation; erosion can use it as a discharge
+//! estimate.
+
+use std::collections::VecDeque;
+
+use crate::biome_map::{BiomeMap, SEA_LEVEL};
+use crate::rivers::RiverGenerator;
+
+/// Direction offsets for D8 neighbors (dx, dy).
+/// Order: N, NE, E, SE, S, SW, W, NW
+pub const D8_OFFSETS: [(i32, i32); 8] = [
+    (0, -1),   // N
+    (1, -1),   // NE
+    (1, 0),    // E
+    (1, 1),    // SE
+    (0, 1),    // S
+    (-1, 1),   // SW
+    (-1, 0),   // W
+    (-1, -1),  // NW
+];
+
+/// Distance weights for diagonal vs cardinal directions.
+const D8_DISTANCES: [f64; 8] = [
+    1.0,
+    std::f64::consts::SQRT_2,
+    1.0,
+    std::f64::consts::SQRT_2,
+    1.0,
+    std::f64::consts::SQRT_2,
+    1.0,
+    std::f64::consts::SQRT_2,
+];
+
+/// No flow direction (pit, flat or masked cell).
+pub const NO_FLOW: u8 = 255;
+
+/// Index of the cell `dir` points to from `idx`, if it lies on the grid.
+pub fn downstream(idx: usize, dir: u8, width: usize, height: usize) -> Option<usize> {
+    if dir == NO_FLOW {
+        return None;
+    }
+    let (dx, dy) = D8_OFFSETS[dir as usize];
+    let nx = (idx % width) as i32 + dx;
+    let ny = (idx / width) as i32 + dy;
+    if nx < 0 || ny < 0 || nx >= width as i32 || ny >= height as i32 {
+        return None;
+    }
+    Some(ny as usize * width + nx as usize)
+}
+
+/// Steepest-descent D8 direction for each cell.
+///
+/// Values are indices into [`D8_OFFSETS`], or [`NO_FLOW`] where no neighbour
+/// is strictly lower.
+pub fn flow_direction(elevation: &[f64], width: usize, height: usize) -> Vec<u8> {
+    let mut flow_dir = vec![NO_FLOW; width * height];
+
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            let mut max_slope = 0.0;
+
+            for (dir, (dx, dy)) in D8_OFFSETS.iter().enumerate() {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                if nx < 0 || nx >= width as i32 || ny < 0 || ny >= height as i32 {
+                    continue;
+                }
+
+                let nidx = ny as usize * width + nx as usize;
+                let slope = (elevation[idx] - elevation[nidx]) / D8_DISTANCES[dir];
+                if slope > max_slope {
+                    max_slope = slope;
+                    flow_dir[idx] = dir as u8;
+                }
+            }
+        }
+    }
+
+    flow_dir
+}
+
+/// Number of cells draining through each cell, itself included.
+///
+/// Cells are processed in topological order (sources first), so the result
+/// doesn't depend on elevation ties the way a sort by height would.
+pub fn flow_accumulation(flow_dir: &[u8], width: usize, height: usize) -> Vec<u32> {
+    let total = width * height;
+    let mut accumulation = vec![1u32; total];
+
+    let mut in_degree = vec![0u32; total];
+    for (idx, &dir) in flow_dir.iter().enumerate() {
+        if let Some(next) = downstream(idx, dir, width, height) {
+            in_degree[next] += 1;
+        }
+    }
+
+    let mut queue: VecDeque<usize> = (0..total).filter(|&idx| in_degree[idx] == 0).collect();
+    while let Some(idx) = queue.pop_front() {
+        let Some(next) = downstream(idx, flow_dir[idx], width, height) else {
+            continue;
+        };
+        accumulation[next] = accumulation[next].saturating_add(accumulation[idx]);
+        in_degree[next] -= 1;
+        if in_degree[next] == 0 {
+            queue.push_back(next);
+        }
+    }
+
+    accumulation
+}
+
+impl BiomeMap {
+    /// Flow accumulation over the depression-filled terrain, as used for
+    /// rivers. Water stops at the coast, so ocean cells hold only themselves.
+    pub fn flow_accumulation(&self) -> Vec<u32> {
+        let total = self.width * self.height;
+        let elevation: Vec<f64> = (0..total)
+            .map(|idx| self.elevation(idx % self.width, idx / self.width).unwrap())
+            .collect();
+
+        let filled =
+            RiverGenerator::new(SEA_LEVEL).fill_depressions(&elevation, self.width, self.height);
+        let mut flow_dir = flow_direction(&filled, self.width, self.height);
+        for (dir, &height) in flow_dir.iter_mut().zip(&filled) {
+            if height <= SEA_LEVEL {
+                *dir = NO_FLOW;
+            }
+        }
+
+        flow_accumulation(&flow_dir, self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plane rising to the north-west, lowest at the south-east corner.
+    fn tilted_plane(width: usize, height: usize) -> Vec<f64> {
+        (0..width * height)
+            .map(|idx| {
+                let (x, y) = (idx % width, idx / width);
+                1.0 - 0.01 * x as f64 - 0.02 * y as f64
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tilted_plane_flows_downslope() {
+        let (width, height) = (12, 8);
+        let elevation = tilted_plane(width, height);
+        let flow_dir = flow_direction(&elevation, width, height);
+        let accumulation = flow_accumulation(&flow_dir, width, height);
+
+        for idx in 0..width * height {
+            match downstream(idx, flow_dir[idx], width, height) {
+                Some(next) => {
+                    assert!(elevation[next] < elevation[idx], "cell {} flows uphill", idx);
+                    assert!(accumulation[next] > accumulation[idx]);
+                }
+                // Only the lowest corner has nowhere to go
+                None => assert_eq!(idx, width * height - 1),
+            }
+        }
+        assert_eq!(accumulation[width * height - 1], (width * height) as u32);
+    }
+
+    #[test]
+    fn pits_and_flats_have_no_flow() {
+        #[rustfmt::skip]
+        let elevation = vec![
+            0.5, 0.5, 0.5,
+            0.5, 0.1, 0.5,
+            0.5, 0.5, 0.5,
+        ];
+        let flow_dir = flow_direction(&elevation, 3, 3);
+
+        assert_eq!(flow_dir[4], NO_FLOW);
+        assert!(flow_dir.iter().enumerate().all(|(i, &d)| i == 4 || d != NO_FLOW));
+        assert_eq!(flow_accumulation(&flow_dir, 3, 3)[4], 9);
+    }
+}
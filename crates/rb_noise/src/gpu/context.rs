@@ -1,7 +1,7 @@
 //! GPU context and noise generation dispatch.
 
 use super::{generate_permutation_table, GpuNoiseResult, NoisePipelines};
-use super::perm_table::permutation_table_to_u32;
+use crate::perm_table::permutation_table_to_u32;
 use bytemuck::{Pod, Zeroable};
 use std::sync::OnceLock;
 use wgpu::util::DeviceExt;
@@ -24,7 +24,8 @@ pub struct NoiseParams {
     pub world_x: f32,
     pub world_y: f32,
     pub world_height: f32,
-    pub _padding: f32,
+    /// Octaves the result is normalized by; any beyond these are detail.
+    pub base_octaves: u32,
 }
 
 /// GPU context for noise generation.
@@ -299,7 +300,7 @@ impl GpuNoiseContext {
             world_x: world_x as f32,
             world_y: world_y as f32,
             world_height: 0.0,
-            _padding: 0.0,
+            base_octaves: 16,
         };
 
         let total_pixels = width * height;
@@ -382,7 +383,7 @@ impl GpuNoiseContext {
             world_x: world_x as f32,
             world_y: world_y as f32,
             world_height: world_height as f32,
-            _padding: 0.0,
+            base_octaves: 8,
         };
 
         let total_pixels = width * height;
@@ -464,7 +465,7 @@ impl GpuNoiseContext {
             world_x: world_x as f32,
             world_y: world_y as f32,
             world_height: 0.0,
-            _padding: 0.0,
+            base_octaves: 0,
         };
 
         let total_pixels = width * height;
@@ -546,7 +547,7 @@ impl GpuNoiseContext {
             world_x: world_x as f32,
             world_y: world_y as f32,
             world_height: 0.0,
-            _padding: 0.0,
+            base_octaves: 8,
         };
 
         let total_pixels = width * height;
@@ -629,7 +630,7 @@ impl GpuNoiseContext {
             world_x: world_x as f32,
             world_y: world_y as f32,
             world_height: 0.0,
-            _padding: 0.0,
+            base_octaves: 6,
         };
 
         let total_pixels = width * height;
@@ -725,7 +726,7 @@ impl GpuNoiseContext {
             world_x: world_x as f32,
             world_y: world_y as f32,
             world_height: world_height as f32,
-            _padding: 0.0,
+            base_octaves: 5,
         };
 
         let total_pixels = width * height;
@@ -5,18 +5,27 @@
 //!
 //! # Usage
 //!
-//! Enable the `gpu` feature in Cargo.toml, then use `NoiseBackend::Gpu`:
+//! Build with the `gpu` feature (`cargo run --features gpu`), then pick
+//! `NoiseBackend::Gpu` for macro maps or meso tiles:
 //!
 //! ```ignore
-//! let map = BiomeMap::generate(seed, 1024, 512, NoiseBackend::Gpu);
+//! let config = GenerationConfig::default();
+//! let map = BiomeMap::generate_with_backend(seed, 1024, 512, NoiseBackend::Gpu, &config);
+//! let tile = BiomeMap::generate_meso_full_with_backend(
+//!     seed, world_x, world_y, 64.0, 512, 512.0, 1, &progress, NoiseBackend::Gpu, None,
+//! );
 //! ```
+//!
+//! The six base layers come from the shaders; rivers, lakes, wind and the
+//! other derived layers are still computed on the CPU from them. Without a
+//! usable adapter, or with options the shaders don't implement (domain warp,
+//! non-OpenSimplex bases), generation falls back to the CPU.
 
 mod context;
-mod perm_table;
 mod pipelines;
 
 pub use context::GpuNoiseContext;
-pub use perm_table::generate_permutation_table;
+pub use crate::perm_table::generate_permutation_table;
 pub use pipelines::NoisePipelines;
 
 /// Result of GPU noise generation containing all 6 base layers.
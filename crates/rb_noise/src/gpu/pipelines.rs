@@ -345,7 +345,7 @@ fn open_simplex_2d(x: f32, y: f32) -> f32 {
 }
 
 // fBm using OpenSimplex
-fn fbm_open_simplex(x: f32, y: f32, octaves: u32, freq: f32, persistence: f32, lacunarity: f32) -> f32 {
+fn fbm_open_simplex(x: f32, y: f32, octaves: u32, base_octaves: u32, freq: f32, persistence: f32, lacunarity: f32) -> f32 {
     var value = 0.0;
     var amplitude = 1.0;
     var f = freq;
@@ -353,7 +353,9 @@ fn fbm_open_simplex(x: f32, y: f32, octaves: u32, freq: f32, persistence: f32, l
 
     for (var i = 0u; i < octaves; i++) {
         value += open_simplex_2d(x * f, y * f) * amplitude;
-        max_amplitude += amplitude;
+        if (i < base_octaves) {
+            max_amplitude += amplitude;
+        }
         amplitude *= persistence;
         f *= lacunarity;
     }
@@ -362,7 +364,7 @@ fn fbm_open_simplex(x: f32, y: f32, octaves: u32, freq: f32, persistence: f32, l
 }
 
 // Ridged multifractal using OpenSimplex
-fn ridged_open_simplex(x: f32, y: f32, octaves: u32, freq: f32, persistence: f32, lacunarity: f32) -> f32 {
+fn ridged_open_simplex(x: f32, y: f32, octaves: u32, base_octaves: u32, freq: f32, persistence: f32, lacunarity: f32) -> f32 {
     var value = 0.0;
     var amplitude = 1.0;
     var f = freq;
@@ -370,8 +372,12 @@ fn ridged_open_simplex(x: f32, y: f32, octaves: u32, freq: f32, persistence: f32
 
     for (var i = 0u; i < octaves; i++) {
         let n = open_simplex_2d(x * f, y * f);
-        value += (1.0 - abs(n)) * amplitude;
-        max_amplitude += amplitude;
+        if (i < base_octaves) {
+            value += (1.0 - abs(n)) * amplitude;
+            max_amplitude += amplitude;
+        } else {
+            value += (0.5 - abs(n)) * amplitude;
+        }
         amplitude *= persistence;
         f *= lacunarity;
     }
@@ -394,7 +400,7 @@ struct Params {
     world_x: f32,
     world_y: f32,
     world_height: f32,
-    _padding: f32,
+    base_octaves: u32,
 }
 
 @group(0) @binding(0) var<uniform> params: Params;
@@ -416,7 +422,7 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     let nx = wx * 0.01;
     let ny = wy * 0.01;
 
-    output[idx] = fbm_open_simplex(nx, ny, params.octaves, params.frequency, params.persistence, params.lacunarity);
+    output[idx] = fbm_open_simplex(nx, ny, params.octaves, params.base_octaves, params.frequency, params.persistence, params.lacunarity);
 }
 "#;
 
@@ -436,7 +442,7 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     // Noise for variation (scale 0.02)
     let nx = wx * 0.02;
     let ny = wy * 0.02;
-    let noise = fbm_open_simplex(nx, ny, params.octaves, params.frequency, params.persistence, params.lacunarity);
+    let noise = fbm_open_simplex(nx, ny, params.octaves, params.base_octaves, params.frequency, params.persistence, params.lacunarity);
 
     // Temperature formula: (latitude * 150) - 50 + (noise * 100)
     let temperature = latitude_factor * 150.0 - 50.0 + noise * 100.0;
@@ -530,7 +536,7 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     let nx = wx * 0.015;
     let ny = wy * 0.015;
 
-    output[idx] = ridged_open_simplex(nx, ny, params.octaves, params.frequency, params.persistence, params.lacunarity);
+    output[idx] = ridged_open_simplex(nx, ny, params.octaves, params.base_octaves, params.frequency, params.persistence, params.lacunarity);
 }
 "#;
 
@@ -548,7 +554,7 @@ struct Params {
     world_x: f32,
     world_y: f32,
     world_height: f32,
-    _padding: f32,
+    base_octaves: u32,
 }
 
 @group(0) @binding(0) var<uniform> params: Params;
@@ -571,7 +577,7 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     // Erosion with continentalness modulation (scale 0.02 matching CPU)
     let nx = wx * 0.02;
     let ny = wy * 0.02;
-    let base_erosion = fbm_open_simplex(nx, ny, params.octaves, params.frequency, params.persistence, params.lacunarity);
+    let base_erosion = fbm_open_simplex(nx, ny, params.octaves, params.base_octaves, params.frequency, params.persistence, params.lacunarity);
 
     // Normalize to [0, 1] and modulate by elevation
     let normalized = (base_erosion + 1.0) * 0.5;
@@ -603,7 +609,7 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     // Noise component (scale 0.015 matching CPU)
     let nx = wx * 0.015;
     let ny = wy * 0.015;
-    let noise = fbm_open_simplex(nx, ny, params.octaves, params.frequency, params.persistence, params.lacunarity);
+    let noise = fbm_open_simplex(nx, ny, params.octaves, params.base_octaves, params.frequency, params.persistence, params.lacunarity);
 
     // Combine factors
     let base_humidity = ocean_proximity * 0.5 + (1.0 - latitude_factor) * 0.3;
@@ -0,0 +1,84 @@
+//! Histogram utilities for reshaping noise field distributions.
+//!
+//! Raw fBm output is roughly Gaussian, so most samples sit near zero and a
+//! threshold placed there (such as sea level) is extremely sensitive. Rank-based
+//! equalization spreads the samples uniformly over [-1, 1] while preserving
+//! their ordering, so a threshold at `t` splits the field at `(t + 1) / 2`.
+
+use rayon::prelude::*;
+
+/// Equalize a field in place so its values are uniformly distributed in [-1, 1].
+///
+/// Each value is replaced by its normalized rank. Ordering is preserved, and
+/// ties are broken by index so the result is deterministic.
+pub fn equalize(values: &mut [f64]) {
+    let n = values.len();
+    if n < 2 {
+        return;
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.par_sort_unstable_by(|&a, &b| values[a].total_cmp(&values[b]).then(a.cmp(&b)));
+
+    let scale = 2.0 / (n - 1) as f64;
+    for (rank, idx) in order.into_iter().enumerate() {
+        values[idx] = rank as f64 * scale - 1.0;
+    }
+}
+
+/// Count values into `bins` equal-width buckets spanning [min, max].
+///
+/// Values outside the range are clamped into the first or last bucket.
+pub fn histogram(values: &[f64], bins: usize, min: f64, max: f64) -> Vec<usize> {
+    let mut counts = vec![0; bins];
+    if bins == 0 {
+        return counts;
+    }
+
+    for &v in values {
+        let t = ((v - min) / (max - min)).clamp(0.0, 1.0);
+        let bin = ((t * bins as f64) as usize).min(bins - 1);
+        counts[bin] += 1;
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equalize_spans_full_range() {
+        let mut values = vec![0.01, -0.02, 0.0, 0.03, -0.01];
+        equalize(&mut values);
+
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!(min, -1.0);
+        assert_eq!(max, 1.0);
+    }
+
+    #[test]
+    fn equalize_preserves_order() {
+        let original = vec![0.3, -0.5, 0.1, 0.9, -0.2];
+        let mut values = original.clone();
+        equalize(&mut values);
+
+        for i in 0..original.len() {
+            for j in 0..original.len() {
+                if original[i] < original[j] {
+                    assert!(values[i] < values[j]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn histogram_counts_all_values() {
+        let values = [-1.0, -0.5, 0.0, 0.5, 1.0, 2.0];
+        let counts = histogram(&values, 4, -1.0, 1.0);
+        assert_eq!(counts.iter().sum::<usize>(), values.len());
+        assert_eq!(counts[3], 3);
+    }
+}
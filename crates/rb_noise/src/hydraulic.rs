@@ -0,0 +1,173 @@
+//! Droplet-based hydraulic erosion.
+//!
+//! Fractal noise gives rounded, blobby highlands with no sense of where water
+//! would go. This pass rains droplets onto the field one at a time; each rolls
+//! downhill, picks up material where it speeds up and drops it where it slows
+//! or pools. Thousands of them wear branching valleys into slopes and leave
+//! sediment fans at their feet, much like real drainage basins.
+
+use rand::{Rng, SeedableRng};
+use rand_xorshift::XorShiftRng;
+
+/// Steps a droplet takes before it evaporates completely.
+const MAX_LIFETIME: usize = 48;
+/// How much a droplet keeps its previous direction (0 = follows the slope exactly).
+const INERTIA: f64 = 0.05;
+/// Sediment a droplet can carry per unit of speed, water and drop.
+const CAPACITY_FACTOR: f64 = 4.0;
+/// Capacity floor so droplets still erode on near-flat ground.
+const MIN_CAPACITY: f64 = 0.0005;
+/// Fraction of spare capacity filled by erosion each step.
+const ERODE_SPEED: f64 = 0.3;
+/// Fraction of excess sediment dropped each step.
+const DEPOSIT_SPEED: f64 = 0.3;
+/// Fraction of water lost each step.
+const EVAPORATE_SPEED: f64 = 0.02;
+/// Acceleration from height lost, per step.
+const GRAVITY: f64 = 4.0;
+
+/// Field height and gradient at `(x, y)`, bilinear over the four surrounding cells.
+fn height_and_gradient(heights: &[f64], width: usize, x: f64, y: f64) -> (f64, f64, f64) {
+    let (cx, cy) = (x as usize, y as usize);
+    let (u, v) = (x - cx as f64, y - cy as f64);
+    let idx = cy * width + cx;
+    let (nw, ne) = (heights[idx], heights[idx + 1]);
+    let (sw, se) = (heights[idx + width], heights[idx + width + 1]);
+
+    let gx = (ne - nw) * (1.0 - v) + (se - sw) * v;
+    let gy = (sw - nw) * (1.0 - u) + (se - ne) * u;
+    let h = nw * (1.0 - u) * (1.0 - v) + ne * u * (1.0 - v) + sw * (1.0 - u) * v + se * u * v;
+    (h, gx, gy)
+}
+
+/// Add `amount` at `(x, y)`, split between the four surrounding cells.
+fn spread(heights: &mut [f64], width: usize, x: f64, y: f64, amount: f64) {
+    let (cx, cy) = (x as usize, y as usize);
+    let (u, v) = (x - cx as f64, y - cy as f64);
+    let idx = cy * width + cx;
+    heights[idx] += amount * (1.0 - u) * (1.0 - v);
+    heights[idx + 1] += amount * u * (1.0 - v);
+    heights[idx + width] += amount * (1.0 - u) * v;
+    heights[idx + width + 1] += amount * u * v;
+}
+
+/// Run `iterations` droplets over `heights` in place.
+///
+/// Droplets start at seeded random positions on land and stop when they
+/// reach `sea_level`, dropping whatever they still carry on the shore. The
+/// result depends only on the input field, `iterations` and `seed`.
+pub fn erode(
+    heights: &mut [f64],
+    width: usize,
+    height: usize,
+    sea_level: f64,
+    iterations: u32,
+    seed: u32,
+) {
+    if width < 2 || height < 2 {
+        return;
+    }
+    let mut rng = XorShiftRng::seed_from_u64(u64::from(seed.wrapping_add(22)));
+    let (max_x, max_y) = ((width - 1) as f64, (height - 1) as f64);
+
+    for _ in 0..iterations {
+        let mut x = rng.gen_range(0.0..max_x);
+        let mut y = rng.gen_range(0.0..max_y);
+        let (mut dir_x, mut dir_y) = (0.0, 0.0);
+        let mut speed: f64 = 1.0;
+        let mut water = 1.0;
+        let mut sediment = 0.0;
+
+        for _ in 0..MAX_LIFETIME {
+            let (h, gx, gy) = height_and_gradient(heights, width, x, y);
+            if h <= sea_level {
+                spread(heights, width, x, y, sediment);
+                break;
+            }
+
+            dir_x = dir_x * INERTIA - gx * (1.0 - INERTIA);
+            dir_y = dir_y * INERTIA - gy * (1.0 - INERTIA);
+            let len = dir_x.hypot(dir_y);
+            if len < 1e-12 {
+                spread(heights, width, x, y, sediment);
+                break;
+            }
+            dir_x /= len;
+            dir_y /= len;
+
+            let (next_x, next_y) = (x + dir_x, y + dir_y);
+            if !(0.0..max_x).contains(&next_x) || !(0.0..max_y).contains(&next_y) {
+                // Carried off the map
+                break;
+            }
+
+            let (next_h, _, _) = height_and_gradient(heights, width, next_x, next_y);
+            let drop = h - next_h;
+            let capacity = (drop * speed * water * CAPACITY_FACTOR).max(MIN_CAPACITY);
+
+            if drop < 0.0 || sediment > capacity {
+                // Uphill fills the pit behind; otherwise shed the excess
+                let amount = if drop < 0.0 {
+                    sediment.min(-drop)
+                } else {
+                    (sediment - capacity) * DEPOSIT_SPEED
+                };
+                sediment -= amount;
+                spread(heights, width, x, y, amount);
+            } else {
+                // Never dig below the next point, or droplets carve pits
+                let amount = ((capacity - sediment) * ERODE_SPEED).min(drop);
+                sediment += amount;
+                spread(heights, width, x, y, -amount);
+            }
+
+            speed = (speed * speed + drop * GRAVITY).max(0.0).sqrt();
+            water *= 1.0 - EVAPORATE_SPEED;
+            x = next_x;
+            y = next_y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bumpy slope falling from the west edge into sea in the east.
+    fn bumpy_slope(width: usize, height: usize) -> Vec<f64> {
+        (0..width * height)
+            .map(|idx| {
+                let (x, y) = ((idx % width) as f64, (idx / width) as f64);
+                0.6 - 0.8 * x / width as f64 + 0.05 * (x * 0.4).sin() * (y * 0.3).cos()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn erosion_moves_material_downhill() {
+        let (width, height) = (96, 64);
+        let original = bumpy_slope(width, height);
+
+        let mut eroded = original.clone();
+        erode(&mut eroded, width, height, 0.0, 4000, 7);
+        let mut again = original.clone();
+        erode(&mut again, width, height, 0.0, 4000, 7);
+        assert_eq!(eroded, again);
+        assert_ne!(eroded, original);
+
+        // Material is only moved or washed away, never created
+        let before: f64 = original.iter().sum();
+        let after: f64 = eroded.iter().sum();
+        assert!(after <= before + 1e-9, "gained mass: {} -> {}", before, after);
+
+        // Uplands lose height to the lowlands below them
+        let upland = |field: &[f64]| -> f64 {
+            field.chunks(width).map(|row| row[..width / 3].iter().sum::<f64>()).sum()
+        };
+        assert!(upland(&eroded) < upland(&original));
+
+        let mut untouched = original.clone();
+        erode(&mut untouched, width, height, 0.0, 0, 7);
+        assert_eq!(untouched, original);
+    }
+}
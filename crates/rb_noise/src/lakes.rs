@@ -0,0 +1,169 @@
+//! Lakes in closed inland basins.
+//!
+//! River tracing fills every depression so water can always reach the sea.
+//! The cells raised by that fill are exactly where water would pool before
+//! spilling over the basin rim, so each connected patch of them deep enough
+//! to matter becomes a lake, draining out through the lowest point of its rim.
+
+use std::collections::VecDeque;
+
+use rb_core::TileType;
+
+use crate::flow::D8_OFFSETS;
+use crate::rivers::RiverGenerator;
+
+/// Shallowest a basin may be, at its deepest point, to hold a lake. Below
+/// this the fill is just the slope added to flats.
+pub const LAKE_MIN_DEPTH: f64 = 0.004;
+/// Fewest cells a lake may cover.
+pub const LAKE_MIN_AREA: usize = 4;
+
+/// Lakes freeze solid below this temperature (°C) and dry out above
+/// [`LAKE_MAX_TEMP`], matching where rivers run.
+const LAKE_MIN_TEMP: f64 = -10.0;
+const LAKE_MAX_TEMP: f64 = 70.0;
+
+/// An inland body of standing water.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lake {
+    /// Surface area in cells.
+    pub area: usize,
+    /// Cell on the rim the lake spills out through, as `(x, y)`.
+    pub outlet: (usize, usize),
+    /// Elevation of the water surface.
+    pub surface: f64,
+}
+
+/// Every lake in an elevation grid, with the cells (row-major indices) it
+/// covers. Cells at or below `sea_level` are sea and never part of a lake.
+pub fn find_lakes(
+    elevation: &[f64],
+    width: usize,
+    height: usize,
+    sea_level: f64,
+) -> Vec<(Lake, Vec<usize>)> {
+    let filled = RiverGenerator::new(sea_level).fill_depressions(elevation, width, height);
+    let flooded = |idx: usize| elevation[idx] > sea_level && filled[idx] > elevation[idx];
+
+    let neighbours = |idx: usize| {
+        let (x, y) = ((idx % width) as i32, (idx / width) as i32);
+        D8_OFFSETS.iter().filter_map(move |&(dx, dy)| {
+            let (nx, ny) = (x + dx, y + dy);
+            (nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height)
+                .then(|| ny as usize * width + nx as usize)
+        })
+    };
+
+    let mut visited = vec![false; width * height];
+    let mut lakes = Vec::new();
+    for start in 0..width * height {
+        if visited[start] || !flooded(start) {
+            continue;
+        }
+
+        // Flood the basin, tracking its depth and the lowest cell on its rim
+        let mut cells = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        visited[start] = true;
+        let (mut depth, mut surface) = (0.0_f64, f64::MIN);
+        let mut outlet: Option<usize> = None;
+        while let Some(idx) = queue.pop_front() {
+            cells.push(idx);
+            depth = depth.max(filled[idx] - elevation[idx]);
+            surface = surface.max(filled[idx]);
+            for next in neighbours(idx) {
+                if flooded(next) {
+                    if !visited[next] {
+                        visited[next] = true;
+                        queue.push_back(next);
+                    }
+                } else if outlet.is_none_or(|o| filled[next] < filled[o]) {
+                    outlet = Some(next);
+                }
+            }
+        }
+
+        if depth < LAKE_MIN_DEPTH || cells.len() < LAKE_MIN_AREA {
+            continue;
+        }
+        let Some(outlet) = outlet else {
+            continue;
+        };
+        let lake = Lake {
+            area: cells.len(),
+            outlet: (outlet % width, outlet / width),
+            surface,
+        };
+        lakes.push((lake, cells));
+    }
+    lakes
+}
+
+/// Turn basins into [`TileType::Lake`] cells and return the lakes.
+///
+/// A basin whose outlet is frozen or scorched stays dry and is left out.
+pub(crate) fn flood_lakes(
+    biomes: &mut [TileType],
+    elevation: &[f64],
+    temperature: &[f64],
+    width: usize,
+    height: usize,
+    sea_level: f64,
+) -> Vec<Lake> {
+    find_lakes(elevation, width, height, sea_level)
+        .into_iter()
+        .filter(|(lake, _)| {
+            let temp = temperature[lake.outlet.1 * width + lake.outlet.0];
+            temp > LAKE_MIN_TEMP && temp < LAKE_MAX_TEMP
+        })
+        .map(|(lake, cells)| {
+            for idx in cells {
+                biomes[idx] = TileType::Lake;
+            }
+            lake
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crater_fills_to_its_rim() {
+        // Land sloping down to sea in the east, with a bowl dug into it
+        let (width, height, sea_level) = (32, 24, 0.0);
+        let (cx, cy) = (10.0, 12.0);
+        let elevation: Vec<f64> = (0..width * height)
+            .map(|idx| {
+                let (x, y) = ((idx % width) as f64, (idx / width) as f64);
+                let slope = 0.5 - 0.6 * x / width as f64;
+                let r = (x - cx).hypot(y - cy);
+                slope - (0.2 * (1.0 - r / 5.0)).max(0.0)
+            })
+            .collect();
+
+        let lakes = find_lakes(&elevation, width, height, sea_level);
+        assert_eq!(lakes.len(), 1, "{:?}", lakes.iter().map(|(l, _)| l).collect::<Vec<_>>());
+        let (lake, cells) = &lakes[0];
+        assert_eq!(lake.area, cells.len());
+        assert!(cells.contains(&(12 * width + 10)));
+        assert!(lake.area >= LAKE_MIN_AREA);
+
+        // Water leaves through the seaward (east) side of the rim
+        let (ox, oy) = lake.outlet;
+        assert!(ox as f64 > cx && (oy as f64 - cy).abs() <= 5.0);
+        assert!(!cells.contains(&(oy * width + ox)));
+
+        // A frozen basin holds no lake
+        let mut biomes = vec![TileType::Plains; width * height];
+        let frozen = vec![-30.0; width * height];
+        let dry = flood_lakes(&mut biomes, &elevation, &frozen, width, height, sea_level);
+        assert!(dry.is_empty() && !biomes.contains(&TileType::Lake));
+
+        let mild = vec![15.0; width * height];
+        let wet = flood_lakes(&mut biomes, &elevation, &mild, width, height, sea_level);
+        assert_eq!(wet.len(), 1);
+        assert_eq!(biomes.iter().filter(|&&b| b == TileType::Lake).count(), wet[0].area);
+    }
+}
@@ -1,34 +1,75 @@
 use bevy::prelude::*;
 
+pub mod adjacency;
 pub mod biome_map;
+pub mod biome_override;
 pub mod biome_splines;
+pub mod brush;
 pub mod chunk_hierarchy;
+pub mod coastline;
 pub mod derived;
+pub mod dirty;
+pub mod elevation;
+pub mod flow;
 #[cfg(feature = "gpu")]
 pub mod gpu;
+pub mod histogram;
+pub mod hydraulic;
+pub mod lakes;
+pub mod normalization;
+pub mod perm_table;
+pub mod precision;
+pub mod preview;
 pub mod progress;
 pub mod resource;
 pub mod resource_map;
+pub mod river_network;
 pub mod rivers;
+pub mod sketch;
 pub mod strategy;
+pub mod symmetry;
+pub mod terrain_edit;
 pub mod tidally_locked;
+pub mod timings;
 pub mod visualization;
+pub mod volcanoes;
 
-pub use biome_map::{BiomeMap, NoiseBackend, SEA_LEVEL};
-pub use biome_splines::BiomeSplines;
+pub use biome_map::{
+    next_layer_where, BiomeMap, GenerationConfig, NoiseBackend, VectorField, SEA_LEVEL,
+};
+pub use biome_override::BiomeOverride;
+pub use biome_splines::{BiomeDither, BiomeSplines, BiomeThresholds};
+pub use brush::BiomeStroke;
 pub use chunk_hierarchy::{
     CacheConfig, CacheStats, ChunkHierarchy, MacroChunk, MesoChunk, MicroChunk,
 };
-pub use progress::{LayerId, LayerProgress};
+pub use derived::climate::ClimateZone;
+pub use dirty::{DirtyRegion, GenerationInputs};
+pub use elevation::{terrain_elevation, ElevationStrategy, StreamedElevation, ELEVATION_RANGE};
+pub use lakes::Lake;
+pub use normalization::NormalizationContext;
+pub use precision::{LayerFloat, LayerValue};
+pub use progress::{GenEvent, LayerId, LayerProgress};
 pub use resource::WorldChunks;
 pub use resource_map::ResourceMap;
+pub use river_network::{GlobalRiverNetwork, RiverStrategy};
 pub use rivers::RiverGenerator;
+pub use sketch::ContinentSketch;
 pub use strategy::{
-    ContinentalnessStrategy, ErosionStrategy, HumidityStrategy, PeaksAndValleysStrategy,
-    ResourceContext, ResourceNoiseStrategy, TectonicPlatesStrategy, TemperatureStrategy,
+    all_strategies, BoundaryKind, CaveStrategy, ContinentalnessStrategy, DomainWarp, ErosionStrategy,
+    FbmParams, HumidityStrategy, LayerBases, Moisture, LayerParams, NoiseBasis, NoiseSource,
+    OceanCurrentStrategy, PeaksAndValleysStrategy, PlateBoundary, ResourceContext, ResourceNoiseStrategy,
+    TectonicPlatesStrategy, TemperatureStrategy, WindStrategy, DEFAULT_CAVE_DEPTH,
+    DEFAULT_WARP_FREQUENCY,
+};
+pub use symmetry::Symmetry;
+pub use terrain_edit::{TerrainBrush, TerrainBrushMode, TerrainEdits, TERRAIN_EDIT_CHUNK};
+pub use tidally_locked::{
+    libration, LatitudeTemperatureStrategy, TidallyLockedTemperatureStrategy, LIBRATION_AMPLITUDE,
 };
-pub use tidally_locked::{LatitudeTemperatureStrategy, TidallyLockedTemperatureStrategy};
-pub use visualization::NoiseLayer;
+pub use timings::{GenerationTimings, PhaseTiming};
+pub use visualization::{rainfall_to_rgba, NoiseLayer, RAINFALL_LEGEND};
+pub use volcanoes::{Volcano, VolcanoKind};
 
 #[cfg(feature = "gpu")]
 pub use gpu::GpuNoiseContext;
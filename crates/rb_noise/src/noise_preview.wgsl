@@ -0,0 +1,129 @@
+// Per-fragment continentalness / temperature for the live noise preview.
+//
+// Mirrors ContinentalnessStrategy and TemperatureStrategy at detail level 0.
+// The OpenSimplex code is the same port as the compute shaders in gpu/, with
+// the permutation table packed four entries per vec4 for std140.
+
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+const PREVIEW_CONTINENTALNESS: u32 = 0u;
+const PREVIEW_TEMPERATURE: u32 = 1u;
+
+struct PreviewParams {
+    offset: vec2<f32>,
+    extent: vec2<f32>,
+    layer: u32,
+    octaves: u32,
+    frequency: f32,
+    persistence: f32,
+    lacunarity: f32,
+    noise_scale: f32,
+    noise_weight: f32,
+}
+
+struct PreviewPermTable {
+    entries: array<vec4<u32>, 64>,
+}
+
+@group(2) @binding(0) var<uniform> params: PreviewParams;
+@group(2) @binding(1) var<uniform> perm_table: PreviewPermTable;
+
+const STRETCH_2D: f32 = -0.211324865405187; // (1/sqrt(2+1)-1)/2
+const SQUISH_2D: f32 = 0.366025403784439;   // (sqrt(2+1)-1)/2
+const NORM_2D: f32 = 1.0 / 14.0;
+const DIAG: f32 = 0.7071067811865476;       // 1/sqrt(2)
+
+fn perm(i: u32) -> u32 {
+    return perm_table.entries[(i & 255u) >> 2u][i & 3u];
+}
+
+fn perm_hash(x: i32, y: i32) -> u32 {
+    return perm(perm(u32(x & 255)) ^ u32(y & 255));
+}
+
+fn grad2(index: u32) -> vec2<f32> {
+    switch (index % 8u) {
+        case 0u: { return vec2<f32>(1.0, 0.0); }
+        case 1u: { return vec2<f32>(-1.0, 0.0); }
+        case 2u: { return vec2<f32>(0.0, 1.0); }
+        case 3u: { return vec2<f32>(0.0, -1.0); }
+        case 4u: { return vec2<f32>(DIAG, DIAG); }
+        case 5u: { return vec2<f32>(-DIAG, DIAG); }
+        case 6u: { return vec2<f32>(DIAG, -DIAG); }
+        default: { return vec2<f32>(-DIAG, -DIAG); }
+    }
+}
+
+fn surflet(index: u32, point: vec2<f32>) -> f32 {
+    let t = 2.0 - dot(point, point);
+    if (t > 0.0) {
+        let t2 = t * t;
+        return t2 * t2 * dot(point, grad2(index));
+    }
+    return 0.0;
+}
+
+fn open_simplex_2d(p: vec2<f32>) -> f32 {
+    let stretched = p + (p.x + p.y) * STRETCH_2D;
+    let xsb = i32(floor(stretched.x));
+    let ysb = i32(floor(stretched.y));
+
+    let origin = vec2<f32>(f32(xsb), f32(ysb)) + f32(xsb + ysb) * SQUISH_2D;
+    let ins = stretched - vec2<f32>(f32(xsb), f32(ysb));
+    let d0 = p - origin;
+
+    var value = surflet(perm_hash(xsb + 1, ysb), d0 - vec2<f32>(1.0 + SQUISH_2D, SQUISH_2D));
+    value += surflet(perm_hash(xsb, ysb + 1), d0 - vec2<f32>(SQUISH_2D, 1.0 + SQUISH_2D));
+
+    if (ins.x + ins.y > 1.0) {
+        value += surflet(perm_hash(xsb + 1, ysb + 1), d0 - vec2<f32>(1.0 + 2.0 * SQUISH_2D));
+    } else {
+        value += surflet(perm_hash(xsb, ysb), d0);
+    }
+
+    return value * NORM_2D;
+}
+
+fn fbm(world: vec2<f32>) -> f32 {
+    var value = 0.0;
+    var amplitude = 1.0;
+    var freq = params.frequency;
+    var max_amplitude = 0.0;
+
+    for (var i = 0u; i < params.octaves; i++) {
+        value += open_simplex_2d(world * freq * params.noise_scale) * amplitude;
+        max_amplitude += amplitude;
+        amplitude *= params.persistence;
+        freq *= params.lacunarity;
+    }
+
+    return value / max_amplitude;
+}
+
+// The CPU preview writes these ramps as sRGB bytes; the render target
+// expects linear values.
+fn srgb_to_linear(c: vec3<f32>) -> vec4<f32> {
+    return vec4<f32>(pow(c, vec3<f32>(2.2)), 1.0);
+}
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let world = params.offset + mesh.uv * params.extent;
+    let noise = fbm(world);
+
+    if (params.layer == PREVIEW_TEMPERATURE) {
+        // Equator (y = 0) hot, poles cold, as in TemperatureStrategy
+        let latitude = abs(tanh(world.y / 1000.0));
+        let latitude_temp = (1.0 - latitude) * 2.0 - 1.0;
+        let combined = noise * params.noise_weight + latitude_temp * (1.0 - params.noise_weight);
+
+        // Same blue-to-red ramp as the CPU preview
+        let t = clamp((combined + 1.0) * 0.5, 0.0, 1.0);
+        let g = (1.0 - abs(t - 0.5) * 2.0) * 0.5;
+        return srgb_to_linear(vec3<f32>(t, g, 1.0 - t));
+    }
+
+    // PREVIEW_CONTINENTALNESS
+    let gray = clamp((noise + 1.0) * 0.5, 0.0, 1.0);
+    return srgb_to_linear(vec3<f32>(gray));
+}
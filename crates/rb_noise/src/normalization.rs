@@ -0,0 +1,153 @@
+//! A shared amplitude baseline for detail-level noise.
+//!
+//! Meso and micro tiles sample the macro map's fBm with extra octaves on top,
+//! but the macro continentalness is then reshaped as a whole: equalized,
+//! faded at the border, eroded, mirrored. Each tile only ever sees itself, so
+//! a tile working from its own raw noise disagrees with the map it zooms
+//! into, and with its neighbours along the seams. A [`NormalizationContext`]
+//! records the macro field once and every tile lays its detail over it.
+
+use rayon::prelude::*;
+use rb_core::NoiseStrategy;
+
+use crate::biome_map::BiomeMap;
+use crate::precision::LayerValue;
+use crate::strategy::ContinentalnessStrategy;
+
+/// The macro map's continentalness before and after shaping, for generating
+/// any region of it at a higher detail level.
+///
+/// Both fields are row-major over the macro grid, whose cells sit one world
+/// unit apart from the origin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizationContext {
+    width: usize,
+    height: usize,
+    /// The base octaves of the noise at each cell, as tiles sample it.
+    coarse: Vec<f64>,
+    /// What the macro map made of each cell.
+    shaped: Vec<f64>,
+    /// Ratio of the shaped field's range to the raw one's, so detail keeps
+    /// its size relative to the terrain it sits on.
+    gain: f64,
+}
+
+impl NormalizationContext {
+    /// Baseline for tiles of `map`, generated from `seed`.
+    pub fn from_macro(map: &BiomeMap, seed: u32) -> Self {
+        let strategy = ContinentalnessStrategy::new(seed);
+        let width = map.width;
+        let coarse = (0..width * map.height)
+            .into_par_iter()
+            .map(|idx| strategy.generate((idx % width) as f64, (idx / width) as f64, 0))
+            .collect();
+        let shaped = map.continentalness.iter().map(|v| v.to_f64()).collect();
+        Self::new(width, map.height, coarse, shaped)
+    }
+
+    /// Baseline from the raw base-octave noise of a `width` × `height` grid
+    /// and the field it was shaped into.
+    pub fn new(width: usize, height: usize, coarse: Vec<f64>, shaped: Vec<f64>) -> Self {
+        assert!(width > 0 && height > 0, "empty macro grid");
+        assert_eq!(coarse.len(), width * height, "one raw value per cell");
+        assert_eq!(shaped.len(), width * height, "one shaped value per cell");
+
+        let span = |field: &[f64]| {
+            let (min, max) = field
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+            max - min
+        };
+        let raw_span = span(&coarse);
+        let gain = if raw_span > 1e-9 { span(&shaped) / raw_span } else { 1.0 };
+
+        Self {
+            width,
+            height,
+            coarse,
+            shaped,
+            gain,
+        }
+    }
+
+    /// `raw` continentalness at world `(x, y)`, sampled at any detail level,
+    /// moved onto the macro map's scale.
+    ///
+    /// The shaped macro value is interpolated between cells and whatever the
+    /// sample has beyond the base octaves is added on top. On a macro cell at
+    /// detail level 0 this gives back exactly the macro value. Points off the
+    /// grid use its nearest edge.
+    pub fn normalize(&self, x: f64, y: f64, raw: f64) -> f64 {
+        let baseline = self.interpolate(&self.shaped, x, y);
+        baseline + (raw - self.interpolate(&self.coarse, x, y)) * self.gain
+    }
+
+    /// Bilinear sample of a grid field.
+    fn interpolate(&self, field: &[f64], x: f64, y: f64) -> f64 {
+        let x = x.clamp(0.0, (self.width - 1) as f64);
+        let y = y.clamp(0.0, (self.height - 1) as f64);
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (tx, ty) = (x - x0 as f64, y - y0 as f64);
+
+        let at = |x: usize, y: usize| field[y * self.width + x];
+        let top = at(x0, y0) + (at(x1, y0) - at(x0, y0)) * tx;
+        let bottom = at(x0, y1) + (at(x1, y1) - at(x0, y1)) * tx;
+        top + (bottom - top) * ty
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biome_map::GenerationConfig;
+    use crate::progress::LayerProgress;
+    use std::sync::Arc;
+
+    #[test]
+    fn tiles_share_the_macro_baseline() {
+        let (w, h) = (96, 48);
+        let config = GenerationConfig {
+            equalize_continentalness: true,
+            border_ocean: 6.0,
+            ..Default::default()
+        };
+        let macro_map = BiomeMap::generate_with_config(42, w, h, &config);
+        let context = Arc::new(NormalizationContext::from_macro(&macro_map, 42));
+
+        // Two neighbouring tiles at meso detail, one cell per world unit
+        let size = 32;
+        let tile = |ox: usize, normalization| {
+            let progress = Arc::new(LayerProgress::new(size * size));
+            let map = BiomeMap::generate_meso_full(
+                42, ox as f64, 0.0, size as f64, size, h as f64, 1, &progress, normalization,
+            );
+            map.continentalness.iter().map(|v| v.to_f64()).collect::<Vec<_>>()
+        };
+        let (left, right) = (tile(16, Some(context.clone())), tile(48, Some(context)));
+        let raw = tile(16, None);
+
+        let error = |tile: &[f64], ox: usize| {
+            let mut worst: f64 = 0.0;
+            for y in 0..size {
+                for x in 0..size {
+                    let expected = macro_map.continentalness[y * w + ox + x].to_f64();
+                    worst = worst.max((tile[y * size + x] - expected).abs());
+                }
+            }
+            worst
+        };
+        // Only the meso octave separates a normalized tile from the macro map
+        assert!(error(&left, 16) < 0.01, "left tile off by {:.4}", error(&left, 16));
+        assert!(error(&right, 48) < 0.01, "right tile off by {:.4}", error(&right, 48));
+        assert!(error(&raw, 16) > 0.1, "raw noise already matched the equalized map");
+
+        // Across the seam the field steps just as the macro map does
+        for y in 0..size {
+            let seam = right[y * size] - left[y * size + size - 1];
+            let expected = macro_map.continentalness[y * w + 48].to_f64()
+                - macro_map.continentalness[y * w + 47].to_f64();
+            assert!((seam - expected).abs() < 0.01, "row {}: {:.3} vs {:.3}", y, seam, expected);
+        }
+    }
+}
@@ -49,7 +49,6 @@ pub fn permutation_table_to_u32(table: &[u8; TABLE_SIZE]) -> [u32; TABLE_SIZE] {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use noise::{NoiseFn, OpenSimplex};
 
     #[test]
     fn permutation_table_is_valid() {
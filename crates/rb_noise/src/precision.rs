@@ -0,0 +1,50 @@
+//! Number type BiomeMap layers are stored in.
+//!
+//! Generation always computes in `f64`; only the finished per-pixel layers
+//! are narrowed. With the `f32` feature a map takes about half the memory,
+//! which adds up across a full cache of meso tiles, at a precision far
+//! beyond what the views and placement rules can tell apart.
+
+/// Per-pixel layer value: `f32` with the `f32` feature, `f64` otherwise.
+#[cfg(feature = "f32")]
+pub type LayerFloat = f32;
+/// Per-pixel layer value: `f32` with the `f32` feature, `f64` otherwise.
+#[cfg(not(feature = "f32"))]
+pub type LayerFloat = f64;
+
+/// Conversions between `f64` and a layer storage type, so code reading
+/// layers compiles unchanged whichever type the feature picks.
+pub trait LayerValue: Copy {
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl LayerValue for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl LayerValue for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// Store computed values as a layer.
+pub fn to_layer(values: Vec<f64>) -> Vec<LayerFloat> {
+    values.into_iter().map(LayerFloat::from_f64).collect()
+}
+
+/// A layer's values widened back to `f64`, for passes that work in `f64`.
+pub fn from_layer(layer: &[LayerFloat]) -> Vec<f64> {
+    layer.iter().map(|v| v.to_f64()).collect()
+}
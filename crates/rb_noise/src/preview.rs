@@ -0,0 +1,203 @@
+//! Fragment-shader noise preview.
+//!
+//! Panning a CPU-generated preview means resampling every pixel on every move.
+//! [`NoisePreviewMaterial`] evaluates continentalness or temperature per
+//! fragment instead, so moving the view only rewrites a uniform. It follows
+//! the CPU strategies' octave math and permutation tables closely enough for
+//! a preview, but runs in f32 and without detail-level octaves; the CPU
+//! strategies stay authoritative for anything that gets generated.
+//!
+//! Add [`NoisePreviewPlugin`] and draw a `Mesh2d` with the material.
+
+use bevy::asset::load_internal_asset;
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef, ShaderType};
+use bevy::sprite::{Material2d, Material2dPlugin};
+
+use crate::perm_table::generate_permutation_table;
+
+/// Handle of the embedded preview shader.
+pub const NOISE_PREVIEW_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x5e2d_41c7_9b0a_4f63_a1d8_7c3e_0b92_f4a6);
+
+/// Which noise layer the shader evaluates. Values match `PREVIEW_*` in the
+/// shader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreviewLayer {
+    Continentalness = 0,
+    Temperature = 1,
+}
+
+pub use uniforms::{PreviewParams, PreviewPermTable};
+
+// The ShaderType derive emits per-field layout checks that rustc reports as
+// unused, so the uniform structs live in their own module.
+mod uniforms {
+    #![allow(dead_code)]
+
+    use super::*;
+
+    /// Uniform block at `@binding(0)`.
+    ///
+    /// Field order is chosen so std140 needs no padding between members; the
+    /// block is rounded up to 48 bytes at the end.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, ShaderType)]
+    pub struct PreviewParams {
+        /// World position of the quad's top-left corner.
+        pub offset: Vec2,
+        /// World units covered by the quad along each axis.
+        pub extent: Vec2,
+        /// A [`PreviewLayer`] discriminant.
+        pub layer: u32,
+        pub octaves: u32,
+        pub frequency: f32,
+        pub persistence: f32,
+        pub lacunarity: f32,
+        /// Multiplier taking world units to noise input space (before frequency).
+        pub noise_scale: f32,
+        /// Share of the noise in temperature; the rest comes from latitude.
+        pub noise_weight: f32,
+    }
+
+    /// Uniform block at `@binding(1)`: the 256-entry OpenSimplex permutation
+    /// table, four entries per vector since std140 arrays stride 16 bytes.
+    #[derive(Clone, Copy, Debug, PartialEq, ShaderType)]
+    pub struct PreviewPermTable {
+        pub entries: [UVec4; 64],
+    }
+
+    impl PreviewPermTable {
+        /// Pack the table the `noise` crate builds for `seed`.
+        pub fn new(seed: u32) -> Self {
+            let table = generate_permutation_table(seed);
+            let mut entries = [UVec4::ZERO; 64];
+            for (entry, chunk) in entries.iter_mut().zip(table.chunks_exact(4)) {
+                *entry = UVec4::new(
+                    chunk[0] as u32,
+                    chunk[1] as u32,
+                    chunk[2] as u32,
+                    chunk[3] as u32,
+                );
+            }
+            Self { entries }
+        }
+    }
+}
+
+/// 2D material that draws a noise layer for a window of the world.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct NoisePreviewMaterial {
+    #[uniform(0)]
+    pub params: PreviewParams,
+    #[uniform(1)]
+    pub perm_table: PreviewPermTable,
+}
+
+impl NoisePreviewMaterial {
+    /// Continentalness with [`ContinentalnessStrategy::new`]'s parameters.
+    ///
+    /// [`ContinentalnessStrategy::new`]: crate::ContinentalnessStrategy::new
+    pub fn continentalness(seed: u32) -> Self {
+        Self {
+            params: PreviewParams {
+                layer: PreviewLayer::Continentalness as u32,
+                octaves: 16,
+                frequency: 1.0,
+                persistence: 0.59,
+                lacunarity: 2.0,
+                noise_scale: 0.01,
+                ..default()
+            },
+            perm_table: PreviewPermTable::new(seed),
+        }
+    }
+
+    /// Temperature with [`TemperatureStrategy::new`]'s parameters. Pass the
+    /// same seed as the strategy (`WorldChunks` offsets it by one).
+    ///
+    /// [`TemperatureStrategy::new`]: crate::TemperatureStrategy::new
+    pub fn temperature(seed: u32) -> Self {
+        Self {
+            params: PreviewParams {
+                layer: PreviewLayer::Temperature as u32,
+                octaves: 4,
+                frequency: 1.0,
+                persistence: 0.5,
+                lacunarity: 2.0,
+                noise_scale: 1.0 / 150.0,
+                noise_weight: 0.3,
+                ..default()
+            },
+            perm_table: PreviewPermTable::new(seed),
+        }
+    }
+
+    /// Show the world window starting at `offset` and spanning `extent`.
+    pub fn set_view(&mut self, offset: Vec2, extent: Vec2) {
+        self.params.offset = offset;
+        self.params.extent = extent;
+    }
+}
+
+impl Material2d for NoisePreviewMaterial {
+    fn fragment_shader() -> ShaderRef {
+        NOISE_PREVIEW_SHADER_HANDLE.into()
+    }
+}
+
+/// Registers the preview shader and [`NoisePreviewMaterial`]. Needs the
+/// render plugins.
+pub struct NoisePreviewPlugin;
+
+impl Plugin for NoisePreviewPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            NOISE_PREVIEW_SHADER_HANDLE,
+            "noise_preview.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins(Material2dPlugin::<NoisePreviewMaterial>::default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::encase::{ShaderSize, UniformBuffer};
+
+    #[test]
+    fn uniforms_match_std140_layout() {
+        PreviewParams::assert_uniform_compat();
+        PreviewPermTable::assert_uniform_compat();
+
+        assert_eq!(PreviewParams::SHADER_SIZE.get(), 48);
+        assert_eq!(PreviewPermTable::SHADER_SIZE.get(), 1024);
+
+        // Spot-check member offsets against the WGSL struct
+        let mut material = NoisePreviewMaterial::continentalness(42);
+        material.set_view(Vec2::new(-100.0, 50.0), Vec2::new(512.0, 256.0));
+        let mut buffer = UniformBuffer::new(Vec::<u8>::new());
+        buffer.write(&material.params).unwrap();
+        let bytes = buffer.into_inner();
+        let word = |offset: usize| <[u8; 4]>::try_from(&bytes[offset..offset + 4]).unwrap();
+        let f32_at = |offset: usize| f32::from_le_bytes(word(offset));
+        let u32_at = |offset: usize| u32::from_le_bytes(word(offset));
+
+        assert_eq!(bytes.len(), 48);
+        assert_eq!(f32_at(0), -100.0);
+        assert_eq!(f32_at(12), 256.0);
+        assert_eq!(u32_at(16), PreviewLayer::Continentalness as u32);
+        assert_eq!(u32_at(20), 16);
+        assert_eq!(f32_at(36), 0.01);
+    }
+
+    #[test]
+    fn perm_table_packs_in_order() {
+        let table = generate_permutation_table(7);
+        let packed = PreviewPermTable::new(7);
+        for (i, &value) in table.iter().enumerate() {
+            assert_eq!(packed.entries[i / 4][i % 4], value as u32);
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
 
 /// Layer identifiers for progress tracking during parallel generation.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -53,11 +55,31 @@ impl LayerId {
     }
 }
 
+/// Progress reported over a channel, for frontends that would rather receive
+/// updates than poll a shared [`LayerProgress`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenEvent {
+    /// First pixels of a layer are done.
+    PhaseStarted(LayerId),
+    /// `done` of `total` pixels of a layer are finished. `done` only grows.
+    Progress {
+        layer: LayerId,
+        done: usize,
+        total: usize,
+    },
+    /// Generation is complete; the result is ready on the join handle.
+    Finished,
+}
+
 /// Thread-safe progress tracker for parallel layer generation.
 /// Uses atomic counters for lock-free progress updates from multiple threads.
 pub struct LayerProgress {
     counters: [AtomicUsize; 7],
+    skipped: [AtomicBool; 7],
     total_pixels: usize,
+    /// Where to forward updates, if anyone is listening. Increments hold the
+    /// lock so events leave in counter order.
+    events: Option<Mutex<Sender<GenEvent>>>,
 }
 
 impl LayerProgress {
@@ -65,14 +87,41 @@ impl LayerProgress {
     pub fn new(total_pixels: usize) -> Self {
         Self {
             counters: Default::default(),
+            skipped: Default::default(),
             total_pixels,
+            events: None,
+        }
+    }
+
+    /// A tracker that also sends a [`GenEvent`] for every increment.
+    ///
+    /// Send errors are ignored: a dropped receiver just stops the updates.
+    pub fn with_events(total_pixels: usize, events: Sender<GenEvent>) -> Self {
+        Self {
+            events: Some(Mutex::new(events)),
+            ..Self::new(total_pixels)
         }
     }
 
     /// Increment the counter for a specific layer.
     /// Safe to call from multiple threads concurrently.
     pub fn increment(&self, layer: LayerId, amount: usize) {
-        self.counters[layer.index()].fetch_add(amount, Ordering::Relaxed);
+        let counter = &self.counters[layer.index()];
+        let Some(events) = &self.events else {
+            counter.fetch_add(amount, Ordering::Relaxed);
+            return;
+        };
+
+        let events = events.lock().unwrap();
+        let before = counter.fetch_add(amount, Ordering::Relaxed);
+        if before == 0 {
+            let _ = events.send(GenEvent::PhaseStarted(layer));
+        }
+        let _ = events.send(GenEvent::Progress {
+            layer,
+            done: before + amount,
+            total: self.total_pixels,
+        });
     }
 
     /// Get the current count for a specific layer.
@@ -88,6 +137,17 @@ impl LayerProgress {
         self.get(layer) as f32 / self.total_pixels as f32
     }
 
+    /// Mark a layer as not generated in this run, so UIs can hide its bar
+    /// instead of showing it stuck at 0%.
+    pub fn skip(&self, layer: LayerId) {
+        self.skipped[layer.index()].store(true, Ordering::Relaxed);
+    }
+
+    /// Whether a layer was marked as skipped.
+    pub fn is_skipped(&self, layer: LayerId) -> bool {
+        self.skipped[layer.index()].load(Ordering::Relaxed)
+    }
+
     /// Get the total pixel count being tracked.
     pub fn total_pixels(&self) -> usize {
         self.total_pixels
@@ -98,6 +158,9 @@ impl LayerProgress {
         for counter in &self.counters {
             counter.store(0, Ordering::Relaxed);
         }
+        for skipped in &self.skipped {
+            skipped.store(false, Ordering::Relaxed);
+        }
     }
 }
 
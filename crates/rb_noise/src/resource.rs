@@ -62,6 +62,17 @@ impl WorldChunks {
             .sample(x, y, detail_level, self.temperature_strategy.as_ref())
     }
 
+    /// Current cache configuration (shared by both hierarchies).
+    pub fn cache_config(&self) -> &CacheConfig {
+        self.continentalness_hierarchy.config()
+    }
+
+    /// Resize the caches of both hierarchies at runtime.
+    pub fn set_cache_config(&mut self, config: CacheConfig) {
+        self.continentalness_hierarchy.set_config(config.clone());
+        self.temperature_hierarchy.set_config(config);
+    }
+
     /// Clear all caches.
     pub fn clear_caches(&mut self) {
         self.continentalness_hierarchy.clear();
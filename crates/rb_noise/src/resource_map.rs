@@ -1,7 +1,34 @@
-use rb_core::ResourceType;
+use rb_core::{BiomeRegistry, ResourceType};
 use smallvec::SmallVec;
 use std::collections::HashMap;
 
+use crate::biome_map::{BiomeMap, SEA_LEVEL};
+use crate::precision::LayerValue;
+use crate::strategy::{ResourceContext, ResourceNoiseStrategy, DEFAULT_RESOURCE_THRESHOLD};
+use crate::symmetry::Symmetry;
+
+/// Smallest abundance a cell stores; anything less counts as no deposit.
+pub const MIN_ABUNDANCE: f32 = 0.01;
+
+/// Cutoff for [`ResourceNoiseStrategy::abundance`] that leaves `density` of
+/// `values` with at least [`MIN_ABUNDANCE`].
+///
+/// `values` are biased resource values, one per cell. At least one cell
+/// always qualifies so a tiny map isn't left without the resource.
+pub fn density_threshold(values: &[f64], density: f64) -> f64 {
+    if values.is_empty() {
+        return DEFAULT_RESOURCE_THRESHOLD;
+    }
+    let keep = ((values.len() as f64 * density).round() as usize).clamp(1, values.len());
+    let mut ranked = values.to_vec();
+    let (_, &mut cutoff, _) = ranked.select_nth_unstable_by(keep - 1, |a, b| b.total_cmp(a));
+
+    // Abundance is (v - t) / (1 - t); place t so the cutoff maps to the
+    // smallest stored abundance rather than to zero
+    let min = MIN_ABUNDANCE as f64;
+    (cutoff - min) / (1.0 - min)
+}
+
 /// Sparse resource map - only stores cells with resources above threshold.
 /// This avoids storing 12 dense vectors for resource types (would be ~50MB at 1024x512).
 pub struct ResourceMap {
@@ -10,6 +37,9 @@ pub struct ResourceMap {
     /// Map from pixel index (y * width + x) to resource abundances.
     /// Uses SmallVec since most cells have 0-3 resources.
     resources: HashMap<usize, SmallVec<[(ResourceType, f32); 3]>>,
+    /// Cutoff each resource was generated with, reused when regenerating
+    /// part of the map.
+    thresholds: HashMap<ResourceType, f64>,
 }
 
 impl ResourceMap {
@@ -18,18 +48,33 @@ impl ResourceMap {
             width,
             height,
             resources: HashMap::new(),
+            thresholds: HashMap::new(),
         }
     }
 
+    /// Cutoff `resource` was generated with, or
+    /// [`DEFAULT_RESOURCE_THRESHOLD`] if none was recorded.
+    pub fn threshold(&self, resource: ResourceType) -> f64 {
+        self.thresholds
+            .get(&resource)
+            .copied()
+            .unwrap_or(DEFAULT_RESOURCE_THRESHOLD)
+    }
+
+    /// Record the cutoff used for `resource`.
+    pub fn set_threshold(&mut self, resource: ResourceType, threshold: f64) {
+        self.thresholds.insert(resource, threshold);
+    }
+
     /// Set the abundance of a resource at a location.
-    /// Values below 0.01 are ignored to save memory.
+    /// Values below [`MIN_ABUNDANCE`] are ignored to save memory.
     pub fn set(&mut self, x: usize, y: usize, resource: ResourceType, abundance: f32) {
-        if abundance < 0.01 {
+        if abundance < MIN_ABUNDANCE {
             return; // Don't store negligible amounts
         }
 
         let idx = y * self.width + x;
-        let entry = self.resources.entry(idx).or_insert_with(SmallVec::new);
+        let entry = self.resources.entry(idx).or_default();
 
         // Update existing or add new
         if let Some(existing) = entry.iter_mut().find(|(r, _)| *r == resource) {
@@ -59,17 +104,61 @@ impl ResourceMap {
             .unwrap_or(&[])
     }
 
+    /// RGBA overlay showing the most abundant resource in each cell, with
+    /// empty cells transparent so the map shows through.
+    pub fn to_overlay_image(&self) -> Vec<u8> {
+        let mut data = vec![0u8; self.width * self.height * 4];
+        for (&idx, deposits) in &self.resources {
+            let Some(&(resource, abundance)) =
+                deposits.iter().max_by(|a, b| a.1.total_cmp(&b.1))
+            else {
+                continue;
+            };
+            let [r, g, b, _] = resource.color();
+            let alpha = (80.0 + abundance.min(1.0) * 175.0) as u8;
+            data[idx * 4..idx * 4 + 4].copy_from_slice(&[r, g, b, alpha]);
+        }
+        data
+    }
+
     /// Check if any resources exist at a location.
     pub fn has_resources(&self, x: usize, y: usize) -> bool {
         let idx = y * self.width + x;
         self.resources.contains_key(&idx)
     }
 
+    /// Replace resources outside the source half with the mirror image of
+    /// those inside it.
+    pub fn apply_symmetry(&mut self, symmetry: Symmetry) {
+        if symmetry == Symmetry::None {
+            return;
+        }
+        let (width, height) = (self.width, self.height);
+        self.resources
+            .retain(|&idx, _| symmetry.is_source(idx % width, idx / width, width, height));
+        let mirrored: Vec<_> = self
+            .resources
+            .iter()
+            .map(|(&idx, deposits)| {
+                let (mx, my) = symmetry.mirror_cell(idx % width, idx / width, width, height);
+                (my * width + mx, deposits.clone())
+            })
+            .collect();
+        self.resources.extend(mirrored);
+    }
+
     /// Get the total number of cells with resources.
     pub fn cells_with_resources(&self) -> usize {
         self.resources.len()
     }
 
+    /// Whether any cell holds `resource`.
+    pub fn contains(&self, resource: ResourceType) -> bool {
+        self.resources
+            .values()
+            .any(|cell| cell.iter().any(|(r, _)| *r == resource))
+    }
+
     /// Get all locations with a specific resource type.
     pub fn locations_with_resource(&self, resource: ResourceType) -> Vec<(usize, usize, f32)> {
         self.resources
@@ -87,6 +176,52 @@ impl ResourceMap {
             .collect()
     }
 
+    /// Regenerate deposits inside `[x0, x1) × [y0, y1)` from the current
+    /// layers of `biome_map`, leaving the rest of the map untouched.
+    ///
+    /// Call after editing biomes so deposits follow the new terrain. Uses the
+    /// macro mapping (pixel = world unit), matching `BiomeMap::generate`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn recompute_rect(
+        &mut self,
+        biome_map: &BiomeMap,
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+        seed: u32,
+        registry: &BiomeRegistry,
+    ) {
+        let x1 = x1.min(self.width).min(biome_map.width);
+        let y1 = y1.min(self.height).min(biome_map.height);
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                self.resources.remove(&(y * self.width + x));
+            }
+        }
+
+        for resource_type in ResourceType::all() {
+            let strategy = ResourceNoiseStrategy::new(seed, *resource_type);
+            let threshold = self.threshold(*resource_type);
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let idx = y * biome_map.width + x;
+                    let context = ResourceContext::from_terrain(
+                        biome_map.continentalness[idx].to_f64(),
+                        biome_map.tectonic[idx].to_f64(),
+                        biome_map.biomes[idx],
+                        SEA_LEVEL,
+                    );
+                    let bias = registry.resource_bias(biome_map.biomes[idx], *resource_type);
+                    let value = strategy.biased_value(x as f64, y as f64, 0, &context) * bias;
+                    let abundance = ResourceNoiseStrategy::abundance(value, threshold);
+                    self.set(x, y, *resource_type, abundance as f32);
+                }
+            }
+        }
+    }
+
     /// Clear all resources.
     pub fn clear(&mut self) {
         self.resources.clear();
@@ -110,6 +245,7 @@ impl Default for ResourceMap {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rb_core::TileType;
 
     #[test]
     fn sparse_storage_works() {
@@ -166,4 +302,56 @@ mod tests {
         assert!((map.get(15, 15, ResourceType::Copper) - 0.9).abs() < 0.001);
         assert_eq!(map.get_all(15, 15).len(), 1);
     }
+
+    #[test]
+    fn recompute_follows_mountain_override() {
+        let mut biome_map = BiomeMap::generate(42, 128, 64);
+        let (x0, y0, x1, y1) = (32, 16, 96, 48);
+        let metals = |map: &ResourceMap| {
+            let mut total = 0.0;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    total += map.get(x, y, ResourceType::Iron) + map.get(x, y, ResourceType::Copper);
+                }
+            }
+            total
+        };
+        let before = metals(&biome_map.resources);
+        let outside = biome_map.resources.get_all(0, 0).to_vec();
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let idx = y * biome_map.width + x;
+                if biome_map.continentalness[idx].to_f64() >= SEA_LEVEL {
+                    biome_map.biomes[idx] = TileType::Mountain;
+                }
+            }
+        }
+        biome_map.recompute_resources_rect(x0, y0, x1, y1, 42, &BiomeRegistry::default());
+
+        let after = metals(&biome_map.resources);
+        assert!(after > before, "Metal deposits {} should exceed {}", after, before);
+        assert_eq!(biome_map.resources.get_all(0, 0), outside.as_slice());
+    }
+
+    #[test]
+    fn resources_cover_their_target_share() {
+        for (width, height) in [(128, 64), (256, 128)] {
+            let map = BiomeMap::generate(42, width, height);
+            let cells = (width * height) as f64;
+            for &resource in ResourceType::all() {
+                let realized = map.resources.locations_with_resource(resource).len() as f64;
+                let target = resource.target_density() * cells;
+                assert!(
+                    (realized - target).abs() <= (target * 0.05).max(2.0),
+                    "{} covers {} cells at {}x{}, expected ~{}",
+                    resource.name(),
+                    realized,
+                    width,
+                    height,
+                    target
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,208 @@
+//! Rivers traced once over the macro map and sampled at any detail level.
+//!
+//! Meso tiles run their own flow accumulation, which only sees the tile and
+//! so loses rivers at tile edges. [`GlobalRiverNetwork`] keeps the macro
+//! result instead: each river cell and the D8 direction it drains in. Finer
+//! levels draw a channel along the line from each river cell to its
+//! downstream neighbour, so a river stays one connected course however far
+//! the view zooms in.
+
+use std::sync::Arc;
+
+use rb_core::NoiseStrategy;
+
+use crate::biome_map::{BiomeMap, SEA_LEVEL};
+use crate::flow::{downstream, flow_direction, NO_FLOW};
+use crate::precision::from_layer;
+use crate::rivers::RiverGenerator;
+
+/// Channel half-width, in macro cells, of the smallest river.
+const MIN_HALF_WIDTH: f64 = 0.15;
+/// Extra half-width, in macro cells, of the largest river.
+const FLOW_HALF_WIDTH: f64 = 0.35;
+
+/// River flow and drainage directions for a whole macro map.
+#[derive(Clone, Debug)]
+pub struct GlobalRiverNetwork {
+    width: usize,
+    height: usize,
+    /// River strength per cell (0 = no river, up to 1), as in `BiomeMap::rivers`.
+    flow: Vec<f64>,
+    /// D8 direction each cell drains in, or [`NO_FLOW`].
+    drains: Vec<u8>,
+}
+
+impl GlobalRiverNetwork {
+    /// Trace the network for `seed` on a `width × height` macro map.
+    pub fn generate(seed: u32, width: usize, height: usize) -> Self {
+        Self::from_biome_map(&BiomeMap::generate(seed, width, height))
+    }
+
+    /// Network for an already generated macro map, matching its `rivers`.
+    ///
+    /// Drainage follows the depression-filled terrain elevation, which
+    /// combines continentalness with the peaks/valleys and erosion layers,
+    /// so water runs from the highlands down valleys to the sea.
+    pub fn from_biome_map(map: &BiomeMap) -> Self {
+        let (width, height) = (map.width, map.height);
+        let elevation: Vec<f64> = (0..width * height)
+            .map(|idx| map.elevation(idx % width, idx / width).unwrap())
+            .collect();
+        let filled = RiverGenerator::new(SEA_LEVEL).fill_depressions(&elevation, width, height);
+        let mut drains = flow_direction(&filled, width, height);
+        for (dir, &level) in drains.iter_mut().zip(&filled) {
+            if level <= SEA_LEVEL {
+                *dir = NO_FLOW;
+            }
+        }
+
+        Self {
+            width,
+            height,
+            flow: from_layer(&map.rivers),
+            drains,
+        }
+    }
+
+    /// River strength of macro cell `(x, y)`; 0 off the map or away from rivers.
+    pub fn flow_at_cell(&self, x: usize, y: usize) -> f64 {
+        if x < self.width && y < self.height {
+            self.flow[y * self.width + x]
+        } else {
+            0.0
+        }
+    }
+
+    /// River strength at world position `(x, y)` (macro cell units).
+    ///
+    /// Detail level 0 returns the nearest cell's value. Finer levels return a
+    /// river's strength only within its channel, which runs from each river
+    /// cell's centre to the centre of the cell it drains into and widens with
+    /// flow.
+    pub fn sample(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        if detail_level == 0 {
+            if x < -0.5 || y < -0.5 {
+                return 0.0;
+            }
+            return self.flow_at_cell(x.round() as usize, y.round() as usize);
+        }
+
+        let (cx, cy) = (x.floor() as i64, y.floor() as i64);
+        let mut strongest: f64 = 0.0;
+        for ny in cy - 1..=cy + 2 {
+            for nx in cx - 1..=cx + 2 {
+                if nx < 0 || ny < 0 || nx >= self.width as i64 || ny >= self.height as i64 {
+                    continue;
+                }
+                let idx = ny as usize * self.width + nx as usize;
+                let flow = self.flow[idx];
+                if flow <= strongest {
+                    continue;
+                }
+
+                let (sx, sy) = (nx as f64, ny as f64);
+                let (ex, ey) = match downstream(idx, self.drains[idx], self.width, self.height) {
+                    Some(next) => ((next % self.width) as f64, (next / self.width) as f64),
+                    None => (sx, sy),
+                };
+                let half_width = MIN_HALF_WIDTH + FLOW_HALF_WIDTH * flow;
+                if distance_to_segment(x, y, sx, sy, ex, ey) <= half_width {
+                    strongest = flow;
+                }
+            }
+        }
+        strongest
+    }
+}
+
+/// Distance from `(px, py)` to the segment `(ax, ay)`–`(bx, by)`.
+fn distance_to_segment(px: f64, py: f64, ax: f64, ay: f64, bx: f64, by: f64) -> f64 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq > 0.0 {
+        (((px - ax) * dx + (py - ay) * dy) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (px - (ax + t * dx)).hypot(py - (ay + t * dy))
+}
+
+/// Samples a [`GlobalRiverNetwork`] through the [`NoiseStrategy`] interface.
+///
+/// Output range: [0.0, 1.0], 0 away from rivers.
+pub struct RiverStrategy {
+    network: Arc<GlobalRiverNetwork>,
+}
+
+impl RiverStrategy {
+    pub fn new(network: Arc<GlobalRiverNetwork>) -> Self {
+        Self { network }
+    }
+
+    /// The network being sampled.
+    pub fn network(&self) -> &GlobalRiverNetwork {
+        &self.network
+    }
+}
+
+impl NoiseStrategy for RiverStrategy {
+    fn generate(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        self.network.sample(x, y, detail_level)
+    }
+
+    fn name(&self) -> &'static str {
+        "Rivers"
+    }
+
+    fn output_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "River strength from macro drainage; 0 on dry land and at sea"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rivers_are_deterministic_and_continuous_across_levels() {
+        let (w, h) = (128, 64);
+        let map = BiomeMap::generate(42, w, h);
+        let network = GlobalRiverNetwork::from_biome_map(&map);
+        let again = GlobalRiverNetwork::generate(42, w, h);
+        assert_eq!(network.flow, again.flow);
+        assert_eq!(network.drains, again.drains);
+
+        let strategy = RiverStrategy::new(Arc::new(network));
+        let network = strategy.network();
+        let mut segments = 0;
+        for y in 0..h {
+            for x in 0..w {
+                let flow = network.flow_at_cell(x, y);
+                assert_eq!(strategy.generate(x as f64, y as f64, 0), flow);
+                if flow == 0.0 {
+                    continue;
+                }
+
+                // Halfway to the next river cell downstream is still river
+                let idx = y * w + x;
+                let Some(next) = downstream(idx, network.drains[idx], w, h) else {
+                    continue;
+                };
+                if network.flow[next] == 0.0 {
+                    continue;
+                }
+                let mx = (x + next % w) as f64 / 2.0;
+                let my = (y + next / w) as f64 / 2.0;
+                for level in 1..=2 {
+                    assert!(strategy.generate(mx, my, level) > 0.0, "gap at ({}, {})", mx, my);
+                }
+                segments += 1;
+            }
+        }
+        assert!(segments > 0, "no rivers to check");
+    }
+}
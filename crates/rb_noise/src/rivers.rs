@@ -6,33 +6,7 @@
 //! 3. Accumulate flow (count upstream drainage area)
 //! 4. Extract rivers where accumulation exceeds threshold
 
-/// Direction offsets for D8 neighbors (dx, dy).
-/// Order: N, NE, E, SE, S, SW, W, NW
-const D8_OFFSETS: [(i32, i32); 8] = [
-    (0, -1),   // N
-    (1, -1),   // NE
-    (1, 0),    // E
-    (1, 1),    // SE
-    (0, 1),    // S
-    (-1, 1),   // SW
-    (-1, 0),   // W
-    (-1, -1),  // NW
-];
-
-/// Distance weights for diagonal vs cardinal directions.
-const D8_DISTANCES: [f64; 8] = [
-    1.0,
-    std::f64::consts::SQRT_2,
-    1.0,
-    std::f64::consts::SQRT_2,
-    1.0,
-    std::f64::consts::SQRT_2,
-    1.0,
-    std::f64::consts::SQRT_2,
-];
-
-/// No flow direction (ocean or sink).
-const NO_FLOW: u8 = 255;
+use crate::flow::{flow_accumulation, flow_direction, D8_OFFSETS, NO_FLOW};
 
 /// Generates rivers based on D8 flow accumulation.
 pub struct RiverGenerator {
@@ -85,7 +59,7 @@ impl RiverGenerator {
         let flow_dir = self.compute_flow_directions(&filled, width, height);
 
         // Step 3: Compute flow accumulation
-        let accumulation = self.compute_flow_accumulation(&flow_dir, &filled, width, height);
+        let accumulation = flow_accumulation(&flow_dir, width, height);
 
         // Step 4: Extract rivers based on threshold
         self.extract_rivers(&accumulation, width, height)
@@ -93,7 +67,7 @@ impl RiverGenerator {
 
     /// Fill depressions using a simplified Planchon-Darboux algorithm.
     /// This ensures all land cells can drain to the ocean.
-    fn fill_depressions(&self, elevation: &[f64], width: usize, height: usize) -> Vec<f64> {
+    pub(crate) fn fill_depressions(&self, elevation: &[f64], width: usize, height: usize) -> Vec<f64> {
         let mut filled = elevation.to_vec();
         let epsilon = 1e-5;
 
@@ -154,105 +128,18 @@ impl RiverGenerator {
         filled
     }
 
-    /// Compute D8 flow direction for each cell.
+    /// Compute D8 flow direction for each cell, with ocean cells as sinks.
     /// Returns array where each value is 0-7 (direction index) or 255 (no flow).
     fn compute_flow_directions(&self, elevation: &[f64], width: usize, height: usize) -> Vec<u8> {
-        let mut flow_dir = vec![NO_FLOW; width * height];
-
-        for y in 0..height {
-            for x in 0..width {
-                let idx = y * width + x;
-
-                // Ocean cells don't flow
-                if elevation[idx] <= self.sea_level {
-                    continue;
-                }
-
-                // Find steepest downslope neighbor
-                let mut max_slope = 0.0;
-                let mut best_dir = NO_FLOW;
-
-                for (dir, (dx, dy)) in D8_OFFSETS.iter().enumerate() {
-                    let nx = x as i32 + dx;
-                    let ny = y as i32 + dy;
-
-                    if nx < 0 || nx >= width as i32 || ny < 0 || ny >= height as i32 {
-                        continue;
-                    }
-
-                    let nidx = ny as usize * width + nx as usize;
-                    let drop = elevation[idx] - elevation[nidx];
-                    let slope = drop / D8_DISTANCES[dir];
-
-                    if slope > max_slope {
-                        max_slope = slope;
-                        best_dir = dir as u8;
-                    }
-                }
-
-                flow_dir[idx] = best_dir;
+        let mut flow_dir = flow_direction(elevation, width, height);
+        for (dir, &height) in flow_dir.iter_mut().zip(elevation) {
+            if height <= self.sea_level {
+                *dir = NO_FLOW;
             }
         }
-
         flow_dir
     }
 
-    /// Compute flow accumulation using topological sort.
-    /// Each cell's accumulation = 1 + sum of all upstream cells.
-    fn compute_flow_accumulation(
-        &self,
-        flow_dir: &[u8],
-        elevation: &[f64],
-        width: usize,
-        height: usize,
-    ) -> Vec<u32> {
-        let total = width * height;
-        let mut accumulation = vec![1u32; total]; // Each cell contributes 1 (itself)
-
-        // Count incoming flows for each cell
-        let mut in_degree = vec![0u32; total];
-        for idx in 0..total {
-            if flow_dir[idx] != NO_FLOW {
-                let x = idx % width;
-                let y = idx / width;
-                let (dx, dy) = D8_OFFSETS[flow_dir[idx] as usize];
-                let nx = (x as i32 + dx) as usize;
-                let ny = (y as i32 + dy) as usize;
-                if nx < width && ny < height {
-                    in_degree[ny * width + nx] += 1;
-                }
-            }
-        }
-
-        // Sort cells by elevation (highest first) for topological processing
-        let mut sorted_indices: Vec<usize> = (0..total).collect();
-        sorted_indices.sort_by(|&a, &b| {
-            elevation[b]
-                .partial_cmp(&elevation[a])
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        // Process cells from highest to lowest
-        for &idx in &sorted_indices {
-            if flow_dir[idx] == NO_FLOW {
-                continue;
-            }
-
-            let x = idx % width;
-            let y = idx / width;
-            let (dx, dy) = D8_OFFSETS[flow_dir[idx] as usize];
-            let nx = (x as i32 + dx) as usize;
-            let ny = (y as i32 + dy) as usize;
-
-            if nx < width && ny < height {
-                let target_idx = ny * width + nx;
-                accumulation[target_idx] = accumulation[target_idx].saturating_add(accumulation[idx]);
-            }
-        }
-
-        accumulation
-    }
-
     /// Extract rivers from accumulation map.
     /// Returns values in [0, 1] where higher = larger river (log normalized).
     fn extract_rivers(&self, accumulation: &[u32], width: usize, height: usize) -> Vec<f64> {
@@ -342,7 +229,7 @@ mod tests {
         let flow_dir = gen.compute_flow_directions(&elevation, width, height);
 
         // Center cell (0.1) should flow toward lower cells
-        let center_idx = 1 * width + 1;
+        let center_idx = width + 1;
         assert_ne!(flow_dir[center_idx], NO_FLOW);
 
         // Ocean cell should have no flow
@@ -367,7 +254,7 @@ mod tests {
         ];
 
         let flow_dir = gen.compute_flow_directions(&elevation, width, height);
-        let accumulation = gen.compute_flow_accumulation(&flow_dir, &elevation, width, height);
+        let accumulation = flow_accumulation(&flow_dir, width, height);
 
         // Center should have highest accumulation (valley bottom)
         let center_idx = 2 * width + 2;
@@ -381,8 +268,10 @@ mod tests {
 
     #[test]
     fn test_river_extraction_threshold() {
-        let mut gen = RiverGenerator::default();
-        gen.min_accumulation = 5;
+        let gen = RiverGenerator {
+            min_accumulation: 5,
+            ..Default::default()
+        };
 
         let accumulation = vec![1, 2, 5, 10, 100];
         let rivers = gen.extract_rivers(&accumulation, 5, 1);
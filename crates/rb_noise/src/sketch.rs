@@ -0,0 +1,126 @@
+//! Hand-drawn continent shapes under the procedural terrain.
+//!
+//! A [`ContinentSketch`] is a grayscale heightmap stretched over the whole
+//! macro map. Black is deep ocean, white the highest land and mid-grey the
+//! coast. It is blended into continentalness before the macro map is
+//! shaped, so climate, rivers and biomes all follow it, and meso tiles pick
+//! it up through their [`NormalizationContext`](crate::NormalizationContext).
+
+/// A grayscale heightmap, row-major, of any size.
+///
+/// Samples are kept at 16 bits, as fine as the images they come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContinentSketch {
+    width: usize,
+    height: usize,
+    samples: Vec<u16>,
+}
+
+impl ContinentSketch {
+    pub fn new(width: usize, height: usize, samples: Vec<u16>) -> Self {
+        assert!(width > 0 && height > 0, "empty sketch");
+        assert_eq!(samples.len(), width * height, "one sample per pixel");
+        Self {
+            width,
+            height,
+            samples,
+        }
+    }
+
+    /// A sketch from brightness values in [0.0, 1.0]; others are clamped.
+    pub fn from_unit(width: usize, height: usize, values: &[f64]) -> Self {
+        let samples = values
+            .iter()
+            .map(|v| (v.clamp(0.0, 1.0) * u16::MAX as f64).round() as u16)
+            .collect();
+        Self::new(width, height, samples)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Continentalness the sketch asks for at `(u, v)`, the fraction of the
+    /// way across and down the map. Pixels are interpolated bilinearly.
+    pub fn continentalness(&self, u: f64, v: f64) -> f64 {
+        // Pixel centres sit half a pixel in from the edges
+        let x = (u * self.width as f64 - 0.5).clamp(0.0, (self.width - 1) as f64);
+        let y = (v * self.height as f64 - 0.5).clamp(0.0, (self.height - 1) as f64);
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (tx, ty) = (x - x0 as f64, y - y0 as f64);
+
+        let at = |x: usize, y: usize| self.samples[y * self.width + x] as f64 / u16::MAX as f64;
+        let top = at(x0, y0) + (at(x1, y0) - at(x0, y0)) * tx;
+        let bottom = at(x0, y1) + (at(x1, y1) - at(x0, y1)) * tx;
+        (top + (bottom - top) * ty) * 2.0 - 1.0
+    }
+
+    /// Pull a `width` × `height` continentalness field toward the sketch.
+    /// A `weight` of 1 replaces it, 0 leaves it alone.
+    pub fn blend_into(&self, continentalness: &mut [f64], width: usize, height: usize, weight: f64) {
+        let weight = weight.clamp(0.0, 1.0);
+        for (idx, cont) in continentalness.iter_mut().enumerate() {
+            let u = ((idx % width) as f64 + 0.5) / width as f64;
+            let v = ((idx / width) as f64 + 0.5) / height as f64;
+            *cont += (self.continentalness(u, v) - *cont) * weight;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biome_map::{BiomeMap, GenerationConfig, SEA_LEVEL};
+    use crate::normalization::NormalizationContext;
+    use crate::precision::LayerValue;
+    use crate::progress::LayerProgress;
+    use std::sync::Arc;
+
+    #[test]
+    fn sketched_continents_reach_the_meso_tiles() {
+        // A small sketch: land on the left, sea on the right
+        let (w, h) = (128, 64);
+        let values: Vec<f64> = (0..16 * 8).map(|i| if i % 16 < 8 { 0.9 } else { 0.05 }).collect();
+        let sketch = Arc::new(ContinentSketch::from_unit(16, 8, &values));
+        let config = GenerationConfig {
+            sketch: Some(sketch.clone()),
+            sketch_weight: 1.0,
+            ..Default::default()
+        };
+        let map = BiomeMap::generate_with_config(42, w, h, &config);
+
+        let land = |cont: &[f64], x0: usize, x1: usize, width: usize| {
+            let cells: Vec<_> = (0..cont.len()).filter(|i| (x0..x1).contains(&(i % width))).collect();
+            cells.iter().filter(|&&i| cont[i] >= SEA_LEVEL).count() as f64 / cells.len() as f64
+        };
+        let cont: Vec<f64> = map.continentalness.iter().map(|v| v.to_f64()).collect();
+        assert_eq!(land(&cont, 8, 56, w), 1.0);
+        assert_eq!(land(&cont, 72, 120, w), 0.0);
+
+        // Half weight keeps some of the procedural field
+        let half = BiomeMap::generate_with_config(42, w, h, &GenerationConfig {
+            sketch_weight: 0.5,
+            ..config
+        });
+        let plain = BiomeMap::generate(42, w, h);
+        let idx = 10 * w + 20;
+        let expected = (plain.continentalness[idx].to_f64() + sketch.continentalness(20.5 / w as f64, 10.5 / h as f64)) / 2.0;
+        assert!((half.continentalness[idx].to_f64() - expected).abs() < 1e-6);
+
+        // A meso tile laid over the sketched map keeps its coastline
+        let context = Arc::new(NormalizationContext::from_macro(&map, 42));
+        let size = 32;
+        let progress = Arc::new(LayerProgress::new(size * size));
+        let tile = BiomeMap::generate_meso_full(
+            42, 48.0, 16.0, size as f64, size, h as f64, 1, &progress, Some(context),
+        );
+        let cont: Vec<f64> = tile.continentalness.iter().map(|v| v.to_f64()).collect();
+        assert_eq!(land(&cont, 0, 8, size), 1.0);
+        assert_eq!(land(&cont, 24, 32, size), 0.0);
+    }
+}
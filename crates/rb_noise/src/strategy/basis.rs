@@ -0,0 +1,129 @@
+use noise::core::worley::{distance_functions, worley_2d, ReturnType};
+use noise::permutationtable::PermutationTable;
+use noise::{MultiFractal, NoiseFn, OpenSimplex, Perlin, RidgedMulti, Vector2};
+use serde::{Deserialize, Serialize};
+
+/// A 2D coherent noise function an fBm strategy sums octaves of.
+///
+/// Anything from the `noise` crate that is `Send + Sync` already qualifies;
+/// implement it directly to plug in a generator of your own.
+pub trait NoiseSource: Send + Sync {
+    /// Noise at a point, roughly in [-1.0, 1.0].
+    fn get(&self, point: [f64; 2]) -> f64;
+}
+
+impl<T: NoiseFn<f64, 2> + Send + Sync> NoiseSource for T {
+    fn get(&self, point: [f64; 2]) -> f64 {
+        NoiseFn::get(self, point)
+    }
+}
+
+/// Cellular noise: distance to the nearest of a jittered grid of points.
+///
+/// The `noise` crate's `Worley` keeps its distance function in an `Rc`, so
+/// it can't be shared between the threads that generate a map.
+struct Worley {
+    perm_table: PermutationTable,
+}
+
+impl NoiseSource for Worley {
+    fn get(&self, point: [f64; 2]) -> f64 {
+        worley_2d(
+            &self.perm_table,
+            distance_functions::euclidean,
+            ReturnType::Distance,
+            Vector2::from(point),
+        )
+    }
+}
+
+/// The built-in noise generators a layer can be built on.
+///
+/// Changing the basis changes the character of a layer without touching how
+/// its octaves are combined: Perlin is blockier than OpenSimplex, ridged
+/// multifractal gives sharp crests, and Worley breaks the terrain into cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NoiseBasis {
+    #[default]
+    OpenSimplex,
+    Perlin,
+    RidgedMulti,
+    Worley,
+}
+
+impl NoiseBasis {
+    /// All bases, for UI selection.
+    pub fn all() -> &'static [NoiseBasis] {
+        &[
+            NoiseBasis::OpenSimplex,
+            NoiseBasis::Perlin,
+            NoiseBasis::RidgedMulti,
+            NoiseBasis::Worley,
+        ]
+    }
+
+    /// Display name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoiseBasis::OpenSimplex => "OpenSimplex",
+            NoiseBasis::Perlin => "Perlin",
+            NoiseBasis::RidgedMulti => "Ridged Multi",
+            NoiseBasis::Worley => "Worley",
+        }
+    }
+
+    /// A seeded generator of this kind.
+    pub fn source(&self, seed: u32) -> Box<dyn NoiseSource> {
+        match self {
+            NoiseBasis::OpenSimplex => Box::new(OpenSimplex::new(seed)),
+            NoiseBasis::Perlin => Box::new(Perlin::new(seed)),
+            // Two octaves: the strategies layer their own on top
+            NoiseBasis::RidgedMulti => Box::new(RidgedMulti::<OpenSimplex>::new(seed).set_octaves(2)),
+            NoiseBasis::Worley => Box::new(Worley {
+                perm_table: PermutationTable::new(seed),
+            }),
+        }
+    }
+}
+
+/// Basis for each fBm layer world generation lets you choose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LayerBases {
+    #[serde(default)]
+    pub continentalness: NoiseBasis,
+    #[serde(default)]
+    pub erosion: NoiseBasis,
+    #[serde(default)]
+    pub peaks_valleys: NoiseBasis,
+    #[serde(default)]
+    pub humidity: NoiseBasis,
+}
+
+impl LayerBases {
+    /// Whether every layer uses OpenSimplex, the only basis the shaders have.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bases_are_seeded_and_bounded() {
+        for basis in NoiseBasis::all() {
+            let a = basis.source(1);
+            let b = basis.source(2);
+            let mut differs = false;
+            for i in 0..400 {
+                let p = [(i % 20) as f64 * 0.37, (i / 20) as f64 * 0.41];
+                let value = a.get(p);
+                assert!((-1.0..=1.0).contains(&value), "{} gave {}", basis.name(), value);
+                assert_eq!(value, basis.source(1).get(p));
+                differs |= value != b.get(p);
+            }
+            assert!(differs, "{} ignores its seed", basis.name());
+        }
+    }
+}
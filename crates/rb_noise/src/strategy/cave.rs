@@ -0,0 +1,122 @@
+use noise::{NoiseFn, OpenSimplex};
+use rb_core::NoiseStrategy;
+
+/// Depth (world units below the surface) of the slice shown by default.
+pub const DEFAULT_CAVE_DEPTH: f64 = 8.0;
+
+/// Depth over which caves fade in below the surface, so the ground isn't
+/// riddled with sinkholes.
+const CRUST_DEPTH: f64 = 3.0;
+/// World-to-noise scale of the cheese caverns, and how much faster the noise
+/// changes with depth than across the map.
+const CHEESE_SCALE: f64 = 0.03;
+const CHEESE_DEPTH_SCALE: f64 = 2.0;
+/// Cheese noise above which rock is hollowed out, and the band over which the
+/// cavern walls fade from open to solid.
+const CHEESE_THRESHOLD: f64 = 0.25;
+const CHEESE_EDGE: f64 = 0.1;
+/// World-to-noise scale of the spaghetti tunnels.
+const SPAGHETTI_SCALE: f64 = 0.05;
+/// Tunnel radius, in units of the two noise fields.
+const SPAGHETTI_RADIUS: f64 = 0.08;
+
+/// Underground caves in 3D: wide "cheese" caverns where one noise field runs
+/// high, joined by "spaghetti" tunnels along the curves where two others
+/// both cross zero.
+///
+/// [`density`](Self::density) samples any point underground. As a
+/// [`NoiseStrategy`] it gives the slice at the strategy's depth, in
+/// [0.0, 1.0] where 1 is open cave and 0 solid rock.
+#[derive(Clone)]
+pub struct CaveStrategy {
+    cheese: OpenSimplex,
+    tunnel_a: OpenSimplex,
+    tunnel_b: OpenSimplex,
+    depth: f64,
+}
+
+impl CaveStrategy {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            cheese: OpenSimplex::new(seed),
+            tunnel_a: OpenSimplex::new(seed.wrapping_add(1)),
+            tunnel_b: OpenSimplex::new(seed.wrapping_add(2)),
+            depth: DEFAULT_CAVE_DEPTH,
+        }
+    }
+
+    /// The same caves, sliced at `depth` below the surface.
+    pub fn with_depth(mut self, depth: f64) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// How open the ground is at `(x, y)`, `depth` world units below the
+    /// surface: 1 inside a cave, 0 in solid rock, in between at cave walls.
+    pub fn density(&self, x: f64, y: f64, depth: f64) -> f64 {
+        if depth <= 0.0 {
+            return 0.0;
+        }
+        let crust = (depth / CRUST_DEPTH).min(1.0);
+
+        let cz = depth * CHEESE_SCALE * CHEESE_DEPTH_SCALE;
+        let cheese = self.cheese.get([x * CHEESE_SCALE, y * CHEESE_SCALE, cz]);
+        let cavern = ((cheese - CHEESE_THRESHOLD) / CHEESE_EDGE).clamp(0.0, 1.0);
+        if cavern >= 1.0 {
+            return crust;
+        }
+
+        let point = [x * SPAGHETTI_SCALE, y * SPAGHETTI_SCALE, depth * SPAGHETTI_SCALE];
+        let offset = self.tunnel_a.get(point).abs().max(self.tunnel_b.get(point).abs());
+        let tunnel = (1.0 - offset / SPAGHETTI_RADIUS).clamp(0.0, 1.0);
+
+        cavern.max(tunnel) * crust
+    }
+
+    /// Whether a point underground is open cave rather than rock.
+    pub fn is_cave(&self, x: f64, y: f64, depth: f64) -> bool {
+        self.density(x, y, depth) >= 0.5
+    }
+}
+
+impl NoiseStrategy for CaveStrategy {
+    fn generate(&self, x: f64, y: f64, _detail_level: u32) -> f64 {
+        self.density(x, y, self.depth)
+    }
+
+    fn name(&self) -> &'static str {
+        "Caves"
+    }
+
+    fn output_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Cave openness at a depth slice: caverns joined by winding tunnels"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caves_open_below_a_solid_crust() {
+        let caves = CaveStrategy::new(42);
+        let share = |depth: f64| {
+            let open = (0..2500)
+                .filter(|i| caves.is_cave((i % 50) as f64 * 3.0, (i / 50) as f64 * 3.0, depth))
+                .count();
+            open as f64 / 2500.0
+        };
+
+        assert_eq!(share(0.0), 0.0);
+        let deep = share(DEFAULT_CAVE_DEPTH);
+        assert!(deep > 0.03 && deep < 0.3, "{:.3} of the slice is open", deep);
+
+        // Tunnels continue from one slice to the next
+        let (a, b) = (share(20.0), share(20.5));
+        assert!((a - b).abs() < 0.05, "{:.3} vs {:.3}", a, b);
+    }
+}
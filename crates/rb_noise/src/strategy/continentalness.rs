@@ -1,27 +1,36 @@
-use noise::{NoiseFn, OpenSimplex};
+use noise::OpenSimplex;
 use rb_core::NoiseStrategy;
 
+use super::basis::NoiseSource;
+use super::params::FbmParams;
+use super::warp::{warped, DomainWarp};
+
 /// Generates continentalness values using 16-octave fBm.
 /// Matches fungal-jungle parameters.
 ///
 /// Output range: approximately [-1.0, 1.0]
 /// Higher values = more continental (land), lower values = more oceanic (water)
 pub struct ContinentalnessStrategy {
-    noise: OpenSimplex,
+    noise: Box<dyn NoiseSource>,
+    warp: Option<DomainWarp>,
     octaves: u32,
     frequency: f64,
     lacunarity: f64,
     persistence: f64,
+    /// Multiplier on the normalized noise.
+    amplitude: f64,
 }
 
 impl ContinentalnessStrategy {
     pub fn new(seed: u32) -> Self {
         Self {
-            noise: OpenSimplex::new(seed),
+            noise: Box::new(OpenSimplex::new(seed)),
+            warp: None,
             octaves: 16,       // fungal-jungle uses 16 octaves
             frequency: 1.0,    // continent_frequency
             lacunarity: 2.0,   // continent_lacunarity
             persistence: 0.59, // fungal-jungle persistence
+            amplitude: 1.0,
         }
     }
 
@@ -33,17 +42,42 @@ impl ContinentalnessStrategy {
         persistence: f64,
     ) -> Self {
         Self {
-            noise: OpenSimplex::new(seed),
+            noise: Box::new(OpenSimplex::new(seed)),
+            warp: None,
             octaves,
             frequency,
             lacunarity,
             persistence,
+            amplitude: 1.0,
         }
     }
 
+    /// Reshape the fBm with `params`, relative to how it was built.
+    pub fn with_fbm(mut self, params: &FbmParams) -> Self {
+        self.frequency /= params.scale;
+        self.octaves = params.octaves;
+        self.persistence = params.persistence;
+        self.lacunarity = params.lacunarity;
+        self.amplitude = params.amplitude;
+        self
+    }
+
+    /// Sum octaves of `source` instead of OpenSimplex.
+    pub fn with_source(mut self, source: Box<dyn NoiseSource>) -> Self {
+        self.noise = source;
+        self
+    }
+
+    /// Warp sample coordinates with `warp` before every fBm.
+    pub fn with_warp(mut self, warp: DomainWarp) -> Self {
+        self.warp = Some(warp);
+        self
+    }
+
     /// Generate fBm (fractal Brownian motion) noise.
     /// Uses 0.01 scale factor like fungal-jungle.
     fn fbm(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        let (x, y) = warped(&self.warp, x, y);
         let mut value = 0.0;
         let mut amplitude = 1.0;
         let mut freq = self.frequency;
@@ -51,18 +85,21 @@ impl ContinentalnessStrategy {
 
         let total_octaves = self.octaves + detail_level;
 
-        for _ in 0..total_octaves {
+        for octave in 0..total_octaves {
             // Apply 0.01 scale factor like fungal-jungle
             let nx = x * freq * 0.01;
             let ny = y * freq * 0.01;
             value += self.noise.get([nx, ny]) * amplitude;
-            max_amplitude += amplitude;
+            // Detail octaves add on top without rescaling the base ones
+            if octave < self.octaves {
+                max_amplitude += amplitude;
+            }
             amplitude *= self.persistence;
             freq *= self.lacunarity;
         }
 
         // Normalize to [-1, 1]
-        value / max_amplitude
+        value / max_amplitude * self.amplitude
     }
 }
 
@@ -71,9 +108,64 @@ impl NoiseStrategy for ContinentalnessStrategy {
         self.fbm(x, y, detail_level)
     }
 
+    /// [`fbm`](Self::fbm) with the octave loop outermost.
+    fn generate_batch(&self, points: &[(f64, f64)], detail_level: u32, out: &mut [f64]) {
+        assert_eq!(points.len(), out.len(), "one output per point");
+        let points: Vec<_> = points.iter().map(|&(x, y)| warped(&self.warp, x, y)).collect();
+        out.fill(0.0);
+        let mut amplitude = 1.0;
+        let mut freq = self.frequency;
+        let mut max_amplitude = 0.0;
+
+        for octave in 0..self.octaves + detail_level {
+            for (value, &(x, y)) in out.iter_mut().zip(&points) {
+                *value += self.noise.get([x * freq * 0.01, y * freq * 0.01]) * amplitude;
+            }
+            if octave < self.octaves {
+                max_amplitude += amplitude;
+            }
+            amplitude *= self.persistence;
+            freq *= self.lacunarity;
+        }
+
+        for value in out {
+            *value = *value / max_amplitude * self.amplitude;
+        }
+    }
+
+    /// The base octaves as [`fbm`](Self::fbm) normalizes them, and the
+    /// detail octaves over their own total amplitude.
+    fn generate_split(&self, x: f64, y: f64, detail_level: u32) -> (f64, f64) {
+        let (x, y) = warped(&self.warp, x, y);
+        let (mut coarse, mut detail) = (0.0, 0.0);
+        let (mut coarse_amplitude, mut detail_amplitude) = (0.0, 0.0);
+        let mut amplitude = 1.0;
+        let mut freq = self.frequency;
+
+        for octave in 0..self.octaves + detail_level {
+            let value = self.noise.get([x * freq * 0.01, y * freq * 0.01]) * amplitude;
+            if octave < self.octaves {
+                coarse += value;
+                coarse_amplitude += amplitude;
+            } else {
+                detail += value;
+                detail_amplitude += amplitude;
+            }
+            amplitude *= self.persistence;
+            freq *= self.lacunarity;
+        }
+
+        let detail = if detail_level == 0 { 0.0 } else { detail / detail_amplitude };
+        (coarse / coarse_amplitude * self.amplitude, detail)
+    }
+
     fn name(&self) -> &'static str {
         "Continentalness"
     }
+
+    fn description(&self) -> &'static str {
+        "Land versus ocean; values below sea level are water"
+    }
 }
 
 #[cfg(test)]
@@ -84,7 +176,7 @@ mod tests {
     fn continentalness_generates_values() {
         let strategy = ContinentalnessStrategy::new(42);
         let value = strategy.generate(0.0, 0.0, 0);
-        assert!(value >= -1.0 && value <= 1.0, "Value {} out of range", value);
+        assert!((-1.0..=1.0).contains(&value), "Value {} out of range", value);
     }
 
     #[test]
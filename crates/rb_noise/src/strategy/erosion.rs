@@ -1,26 +1,35 @@
-use noise::{NoiseFn, OpenSimplex};
+use noise::OpenSimplex;
 use rb_core::NoiseStrategy;
 
+use super::basis::NoiseSource;
+use super::params::FbmParams;
+use super::warp::{warped, DomainWarp};
+
 /// Generates erosion patterns. Valleys erode more than peaks.
 ///
 /// Output range: [0.0, 1.0] where 1 = heavily eroded
 /// Depends on continentalness - lower elevations erode more.
 pub struct ErosionStrategy {
-    noise: OpenSimplex,
+    noise: Box<dyn NoiseSource>,
+    warp: Option<DomainWarp>,
     octaves: u32,
     frequency: f64,
     persistence: f64,
     lacunarity: f64,
+    /// Multiplier on the normalized noise.
+    amplitude: f64,
 }
 
 impl ErosionStrategy {
     pub fn new(seed: u32) -> Self {
         Self {
-            noise: OpenSimplex::new(seed),
+            noise: Box::new(OpenSimplex::new(seed)),
+            warp: None,
             octaves: 6,
             frequency: 2.0, // Fine-grained detail
             persistence: 0.55,
             lacunarity: 2.2,
+            amplitude: 1.0,
         }
     }
 
@@ -32,16 +41,41 @@ impl ErosionStrategy {
         lacunarity: f64,
     ) -> Self {
         Self {
-            noise: OpenSimplex::new(seed),
+            noise: Box::new(OpenSimplex::new(seed)),
+            warp: None,
             octaves,
             frequency,
             persistence,
             lacunarity,
+            amplitude: 1.0,
         }
     }
 
+    /// Reshape the fBm with `params`, relative to how it was built.
+    pub fn with_fbm(mut self, params: &FbmParams) -> Self {
+        self.frequency /= params.scale;
+        self.octaves = params.octaves;
+        self.persistence = params.persistence;
+        self.lacunarity = params.lacunarity;
+        self.amplitude = params.amplitude;
+        self
+    }
+
+    /// Sum octaves of `source` instead of OpenSimplex.
+    pub fn with_source(mut self, source: Box<dyn NoiseSource>) -> Self {
+        self.noise = source;
+        self
+    }
+
+    /// Warp sample coordinates with `warp` before every fBm.
+    pub fn with_warp(mut self, warp: DomainWarp) -> Self {
+        self.warp = Some(warp);
+        self
+    }
+
     /// Standard fBm for base erosion patterns.
     fn fbm(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        let (x, y) = warped(&self.warp, x, y);
         let mut value = 0.0;
         let mut amplitude = 1.0;
         let mut freq = self.frequency;
@@ -49,21 +83,24 @@ impl ErosionStrategy {
 
         let total_octaves = self.octaves + detail_level;
 
-        for _ in 0..total_octaves {
+        for octave in 0..total_octaves {
             let nx = x * freq * 0.01;
             let ny = y * freq * 0.01;
             value += self.noise.get([nx, ny]) * amplitude;
-            max_amplitude += amplitude;
+            if octave < self.octaves {
+                max_amplitude += amplitude;
+            }
             amplitude *= self.persistence;
             freq *= self.lacunarity;
         }
 
-        value / max_amplitude
+        value / max_amplitude * self.amplitude
     }
 
     /// Ridged noise creates sharp valleys/channels for erosion patterns.
     /// Different from smooth fBm - produces distinct erosion features.
     fn ridged_fbm(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        let (x, y) = warped(&self.warp, x, y);
         let mut value = 0.0;
         let mut amplitude = 1.0;
         let mut freq = self.frequency;
@@ -90,7 +127,7 @@ impl ErosionStrategy {
         }
 
         // Normalize to 0-1 range
-        (value * 0.5).clamp(0.0, 1.0)
+        (value * 0.5 * self.amplitude).clamp(0.0, 1.0)
     }
 
     /// Generate erosion value that depends on continentalness.
@@ -132,6 +169,14 @@ impl NoiseStrategy for ErosionStrategy {
     fn name(&self) -> &'static str {
         "Erosion"
     }
+
+    fn output_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Ridged erosion channels: 1 is heavily eroded"
+    }
 }
 
 #[cfg(test)]
@@ -145,7 +190,7 @@ mod tests {
             let x = i as f64 * 10.0;
             let y = i as f64 * 10.0;
             let val = strategy.generate(x, y, 0);
-            assert!(val >= 0.0 && val <= 1.0, "Value {} out of range", val);
+            assert!((0.0..=1.0).contains(&val), "Value {} out of range", val);
         }
     }
 
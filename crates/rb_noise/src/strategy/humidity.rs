@@ -1,25 +1,39 @@
-use noise::{NoiseFn, OpenSimplex};
+use noise::OpenSimplex;
 use rb_core::NoiseStrategy;
 
-/// Generates humidity values that naturally decay with distance from water.
+use super::basis::NoiseSource;
+use super::params::FbmParams;
+use super::warp::{warped, DomainWarp};
+use crate::tidally_locked::libration;
+
+/// Generates humidity from the moisture the wind brings, varied by noise and
+/// dried out toward the sun side.
 ///
-/// Output range: [0.0, 1.0] where 1 = very humid (near water)
+/// Output range: [0.0, 1.0] where 1 = very humid
 pub struct HumidityStrategy {
-    noise: OpenSimplex,
+    noise: Box<dyn NoiseSource>,
+    warp: Option<DomainWarp>,
     octaves: u32,
     frequency: f64,
     persistence: f64,
     lacunarity: f64,
+    /// Seasonal shift of the dry sun side, as a fraction of map height.
+    libration: f64,
+    /// Multiplier on the normalized noise.
+    amplitude: f64,
 }
 
 impl HumidityStrategy {
     pub fn new(seed: u32) -> Self {
         Self {
-            noise: OpenSimplex::new(seed),
+            noise: Box::new(OpenSimplex::new(seed)),
+            warp: None,
             octaves: 5,
             frequency: 1.0,
             persistence: 0.5,
             lacunarity: 2.0,
+            libration: 0.0,
+            amplitude: 1.0,
         }
     }
 
@@ -31,15 +45,48 @@ impl HumidityStrategy {
         lacunarity: f64,
     ) -> Self {
         Self {
-            noise: OpenSimplex::new(seed),
+            noise: Box::new(OpenSimplex::new(seed)),
+            warp: None,
             octaves,
             frequency,
             persistence,
             lacunarity,
+            libration: 0.0,
+            amplitude: 1.0,
         }
     }
 
+    /// Reshape the fBm with `params`, relative to how it was built.
+    pub fn with_fbm(mut self, params: &FbmParams) -> Self {
+        self.frequency /= params.scale;
+        self.octaves = params.octaves;
+        self.persistence = params.persistence;
+        self.lacunarity = params.lacunarity;
+        self.amplitude = params.amplitude;
+        self
+    }
+
+    /// Sum octaves of `source` instead of OpenSimplex.
+    pub fn with_source(mut self, source: Box<dyn NoiseSource>) -> Self {
+        self.noise = source;
+        self
+    }
+
+    /// Humidity at `season` through the year, with the sun side's dryness
+    /// following the climate zones as they shift.
+    pub fn with_season(mut self, season: f64) -> Self {
+        self.libration = libration(season);
+        self
+    }
+
+    /// Warp sample coordinates with `warp` before every fBm.
+    pub fn with_warp(mut self, warp: DomainWarp) -> Self {
+        self.warp = Some(warp);
+        self
+    }
+
     fn fbm(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        let (x, y) = warped(&self.warp, x, y);
         let mut value = 0.0;
         let mut amplitude = 1.0;
         let mut freq = self.frequency;
@@ -47,59 +94,37 @@ impl HumidityStrategy {
 
         let total_octaves = self.octaves + detail_level;
 
-        for _ in 0..total_octaves {
+        for octave in 0..total_octaves {
             // Use much larger scale than erosion (0.003 vs 0.015) for broad humidity zones
             let nx = x * freq * 0.003;
             let ny = y * freq * 0.003;
             value += self.noise.get([nx, ny]) * amplitude;
-            max_amplitude += amplitude;
+            if octave < self.octaves {
+                max_amplitude += amplitude;
+            }
             amplitude *= self.persistence;
             freq *= self.lacunarity;
         }
 
-        value / max_amplitude
-    }
-
-    /// Generate humidity considering distance from water.
-    ///
-    /// # Arguments
-    /// * `water_distance_factor` - 0 = on water, 1 = far from water
-    pub fn generate_with_water_distance(
-        &self,
-        x: f64,
-        y: f64,
-        detail_level: u32,
-        water_distance_factor: f64,
-    ) -> f64 {
-        let base_humidity = (self.fbm(x, y, detail_level) + 1.0) * 0.5;
-
-        // Humidity decays exponentially with distance from water
-        // Near coast: high humidity
-        // Far inland: low humidity (modified by noise for local variations)
-        let decay = (-water_distance_factor * 3.0).exp();
-
-        // Base humidity provides local variation
-        // Decay provides global gradient from coast
-        let combined = base_humidity * 0.4 + decay * 0.6;
-
-        combined.clamp(0.0, 1.0)
+        value / max_amplitude * self.amplitude
     }
 
     /// Generate humidity for a tidally locked planet.
     ///
     /// Takes into account:
-    /// - Continentalness (distance from water)
+    /// - Vapour carried in on the wind (see [`transport_moisture`](super::transport_moisture))
     /// - Latitude (y position) - sun side is extremely dry
     /// - Noise variation for natural-looking zone boundaries
     ///
     /// # Arguments
+    /// * `vapour` - Water vapour in the air, 0 = bone dry, 1 = saturated
     /// * `world_height` - Total height of the world map
     pub fn generate_tidally_locked(
         &self,
         x: f64,
         y: f64,
         detail_level: u32,
-        continentalness: f64,
+        vapour: f64,
         world_height: f64,
     ) -> f64 {
         let base_humidity = (self.fbm(x, y, detail_level) + 1.0) * 0.5;
@@ -109,7 +134,7 @@ impl HumidityStrategy {
         let latitude_offset = boundary_noise * 0.15;
 
         // Latitude factor with noise offset: 0 = top (dark/frozen), 1 = bottom (sun/scorched)
-        let latitude = ((y / world_height) + latitude_offset).clamp(0.0, 1.0);
+        let latitude = ((y / world_height) + latitude_offset + self.libration).clamp(0.0, 1.0);
 
         // Sun-side dryness multiplier
         // - Dark side (0-0.33): normal humidity possible
@@ -127,69 +152,29 @@ impl HumidityStrategy {
             0.7 - t * 0.6  // 0.7 to 0.1
         };
 
-        // Use continentalness as proxy for water distance
-        let water_factor = if continentalness < -0.025 {
-            // In water - high humidity (but reduced on sun side)
-            1.0
-        } else if continentalness < 0.05 {
-            // Coastal
-            0.85 - (continentalness + 0.025) * 4.0
-        } else if continentalness < 0.15 {
-            // Near coast
-            0.55 - (continentalness - 0.05) * 3.0
-        } else {
-            // Inland
-            0.25 - (continentalness - 0.15) * 0.8
-        };
-
         // Combine factors
-        let combined = (base_humidity * 0.4 + water_factor.max(0.0) * 0.6) * latitude_multiplier;
-        combined.clamp(0.0, 1.0)
-    }
-
-    /// Generate humidity based on continentalness (proxy for water distance).
-    /// Useful when water distance isn't precomputed.
-    pub fn generate_with_continentalness(
-        &self,
-        x: f64,
-        y: f64,
-        detail_level: u32,
-        continentalness: f64,
-    ) -> f64 {
-        let base_humidity = (self.fbm(x, y, detail_level) + 1.0) * 0.5;
-
-        // Use continentalness as proxy for water distance
-        // Negative continentalness = water (high humidity)
-        // Positive continentalness = land (decreasing humidity inland)
-        let water_factor = if continentalness < -0.025 {
-            // In water - very high humidity
-            1.0
-        } else if continentalness < 0.05 {
-            // Coastal - high humidity, gradual decrease
-            0.85 - (continentalness + 0.025) * 4.0
-        } else if continentalness < 0.15 {
-            // Near coast - moderate humidity
-            0.55 - (continentalness - 0.05) * 3.0
-        } else {
-            // Inland - can get quite dry
-            0.25 - (continentalness - 0.15) * 0.8
-        };
-
-        // Allow humidity to go quite low inland (no floor)
-        let combined = base_humidity * 0.4 + water_factor.max(0.0) * 0.6;
+        let combined = (base_humidity * 0.4 + vapour * 0.6) * latitude_multiplier;
         combined.clamp(0.0, 1.0)
     }
 }
 
 impl NoiseStrategy for HumidityStrategy {
     fn generate(&self, x: f64, y: f64, detail_level: u32) -> f64 {
-        // Without water distance context, return base humidity noise
+        // Without the moisture the wind brings, return base humidity noise
         (self.fbm(x, y, detail_level) + 1.0) * 0.5
     }
 
     fn name(&self) -> &'static str {
         "Humidity"
     }
+
+    fn output_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Base moisture noise, before the wind carries vapour in"
+    }
 }
 
 #[cfg(test)]
@@ -203,37 +188,23 @@ mod tests {
             let x = i as f64 * 10.0;
             let y = i as f64 * 10.0;
             let val = strategy.generate(x, y, 0);
-            assert!(val >= 0.0 && val <= 1.0, "Value {} out of range", val);
+            assert!((0.0..=1.0).contains(&val), "Value {} out of range", val);
         }
     }
 
     #[test]
-    fn humidity_decreases_from_water() {
-        let strategy = HumidityStrategy::new(42);
-
-        let near_water = strategy.generate_with_water_distance(100.0, 100.0, 0, 0.0);
-        let far_water = strategy.generate_with_water_distance(100.0, 100.0, 0, 1.0);
-
-        assert!(
-            near_water > far_water,
-            "Near water ({}) should be more humid than far ({})",
-            near_water,
-            far_water
-        );
-    }
-
-    #[test]
-    fn humidity_with_continentalness() {
+    fn humidity_follows_vapour() {
         let strategy = HumidityStrategy::new(42);
 
-        let ocean = strategy.generate_with_continentalness(100.0, 100.0, 0, -0.5);
-        let inland = strategy.generate_with_continentalness(100.0, 100.0, 0, 0.5);
+        // Dark side, where the sun doesn't dry anything out
+        let saturated = strategy.generate_tidally_locked(100.0, 20.0, 0, 1.0, 512.0);
+        let dry = strategy.generate_tidally_locked(100.0, 20.0, 0, 0.1, 512.0);
 
         assert!(
-            ocean > inland,
-            "Ocean ({}) should be more humid than inland ({})",
-            ocean,
-            inland
+            saturated > dry,
+            "Saturated air ({}) should be more humid than dry air ({})",
+            saturated,
+            dry
         );
     }
 }
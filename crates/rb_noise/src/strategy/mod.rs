@@ -1,15 +1,133 @@
+mod basis;
+mod cave;
 mod continentalness;
 mod erosion;
 mod humidity;
+mod moisture;
+mod ocean_current;
+mod params;
 mod peaks_valleys;
 pub mod resource;
 mod tectonic;
 mod temperature;
+mod warp;
+mod wind;
 
+pub use basis::{LayerBases, NoiseBasis, NoiseSource};
+pub use cave::{CaveStrategy, DEFAULT_CAVE_DEPTH};
 pub use continentalness::ContinentalnessStrategy;
 pub use erosion::ErosionStrategy;
 pub use humidity::HumidityStrategy;
+pub use moisture::{transport_moisture, Moisture};
+pub use ocean_current::OceanCurrentStrategy;
+pub use params::{FbmParams, LayerParams};
 pub use peaks_valleys::PeaksAndValleysStrategy;
-pub use resource::{ResourceNoiseStrategy, ResourceContext};
-pub use tectonic::TectonicPlatesStrategy;
+pub use resource::{ResourceNoiseStrategy, ResourceContext, DEFAULT_RESOURCE_THRESHOLD};
+pub use tectonic::{BoundaryKind, PlateBoundary, TectonicPlatesStrategy};
 pub use temperature::TemperatureStrategy;
+pub use warp::{DomainWarp, DEFAULT_WARP_FREQUENCY};
+pub use wind::WindStrategy;
+
+use rb_core::NoiseStrategy;
+
+use crate::elevation::ElevationStrategy;
+use crate::tidally_locked::LatitudeTemperatureStrategy;
+
+/// One of each layer strategy world generation uses, seeded the way
+/// `BiomeMap` seeds them, plus the combined elevation. Temperature assumes
+/// the default 512-pixel map height.
+///
+/// Meant for previews and tooling that list strategies through
+/// [`NoiseStrategy::name`], [`NoiseStrategy::output_range`] and
+/// [`NoiseStrategy::description`].
+pub fn all_strategies(seed: u32) -> Vec<Box<dyn NoiseStrategy>> {
+    vec![
+        Box::new(ContinentalnessStrategy::new(seed)),
+        Box::new(LatitudeTemperatureStrategy::default_for_map(seed.wrapping_add(1))),
+        Box::new(TectonicPlatesStrategy::new(seed.wrapping_add(2))),
+        Box::new(ErosionStrategy::new(seed.wrapping_add(3))),
+        Box::new(PeaksAndValleysStrategy::new(seed.wrapping_add(4))),
+        Box::new(HumidityStrategy::new(seed.wrapping_add(5))),
+        Box::new(ElevationStrategy::new(seed)),
+        Box::new(WindStrategy::new(
+            LatitudeTemperatureStrategy::default_for_map(seed.wrapping_add(1)),
+            seed.wrapping_add(6),
+        )),
+        Box::new(OceanCurrentStrategy::new(seed.wrapping_add(7))),
+        Box::new(CaveStrategy::new(seed.wrapping_add(8))),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_match_single_points() {
+        let points: Vec<(f64, f64)> =
+            (0..300).map(|i| ((i % 30) as f64 * 7.9 - 50.0, (i / 30) as f64 * 11.3)).collect();
+        let warped = ContinentalnessStrategy::new(42).with_warp(DomainWarp::new(9, 20.0, 0.01));
+        let mut strategies = all_strategies(42);
+        strategies.push(Box::new(warped));
+
+        for strategy in &strategies {
+            for detail_level in 0..=2 {
+                let mut batch = vec![0.0; points.len()];
+                strategy.generate_batch(&points, detail_level, &mut batch);
+                for (&(x, y), &value) in points.iter().zip(&batch) {
+                    assert_eq!(value, strategy.generate(x, y, detail_level), "{}", strategy.name());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn splits_keep_the_base_and_stretch_the_detail() {
+        let split: [Box<dyn NoiseStrategy>; 2] = [
+            Box::new(ContinentalnessStrategy::new(42)),
+            Box::new(PeaksAndValleysStrategy::new(46)),
+        ];
+        for strategy in &split {
+            let mut spread = 0.0;
+            for i in 0..500 {
+                let (x, y) = ((i % 25) as f64 * 1.3, (i / 25) as f64 * 1.7);
+                let (coarse, detail) = strategy.generate_split(x, y, 2);
+                assert!((coarse - strategy.generate(x, y, 0)).abs() < 1e-12);
+                assert!((-1.0..=1.0).contains(&detail), "{} detail {}", strategy.name(), detail);
+                assert_eq!(strategy.generate_split(x, y, 0), (coarse, 0.0));
+
+                // Added on top, the same octaves barely move the value
+                assert!((strategy.generate(x, y, 2) - coarse).abs() < 0.02);
+                spread += detail.abs() / 500.0;
+            }
+            assert!(spread > 0.1, "{} detail averages only {:.3}", strategy.name(), spread);
+        }
+    }
+
+    #[test]
+    fn strategies_stay_within_declared_range() {
+        let strategies = all_strategies(42);
+        for strategy in &strategies {
+            let (min, max) = strategy.output_range();
+            assert!(min < max, "{} has an empty range", strategy.name());
+            assert!(!strategy.description().is_empty(), "{} is undescribed", strategy.name());
+
+            for detail_level in 0..=2 {
+                for i in 0..2000 {
+                    let x = (i % 50) as f64 * 10.3 - 200.0;
+                    let y = (i / 50) as f64 * 12.7;
+                    let value = strategy.generate(x, y, detail_level);
+                    assert!(
+                        (min..=max).contains(&value),
+                        "{} gave {} at ({}, {}), outside {:?}",
+                        strategy.name(),
+                        value,
+                        x,
+                        y,
+                        (min, max)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use super::wind::WindStrategy;
+
+/// Steps an air parcel is followed over on its way to a cell, and their
+/// length in world units.
+const TRANSPORT_STEPS: usize = 16;
+const STEP_LENGTH: f64 = 5.0;
+/// Vapour in air that starts its journey over land rather than the sea.
+const DRY_AIR: f64 = 0.2;
+/// Share of its remaining capacity air takes up per step over open sea.
+const EVAPORATION: f64 = 0.3;
+/// Share of its vapour air rains out per step over flat land at sea level.
+const RAINOUT: f64 = 0.06;
+/// Extra rainout per unit the ground rises over a step, as air is lifted and
+/// cools.
+const OROGRAPHIC_RAINOUT: f64 = 2.5;
+/// Extra rainout per unit of height above the sea.
+const ALTITUDE_RAINOUT: f64 = 0.1;
+/// Share of the rain that falls on land and evaporates back into the air.
+const RECYCLING: f64 = 0.25;
+/// Rain in one step, as a share of saturated air, that counts as a downpour.
+const HEAVY_RAIN: f64 = 0.2;
+
+/// Moisture reaching a point on the prevailing wind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moisture {
+    /// Water vapour in the air, in [0.0, 1.0] where 1 is saturated sea air.
+    pub vapour: f64,
+    /// How hard it rains here, in [0.0, 1.0].
+    pub precipitation: f64,
+}
+
+/// Follow the air arriving at `(x, y)` from [`TRANSPORT_STEPS`] steps
+/// upwind: it takes up water over the sea, carries it inland and drops it as
+/// rain, most heavily where the ground rises under it.
+///
+/// `relief(x, y)` gives large-scale continentalness anywhere; the cell itself
+/// uses `continentalness`. Calm air stays as the ground beneath it leaves it.
+pub fn transport_moisture(
+    wind: &WindStrategy,
+    x: f64,
+    y: f64,
+    continentalness: f64,
+    sea_level: f64,
+    relief: impl Fn(f64, f64) -> f64,
+) -> Moisture {
+    let (u, v) = wind.vector(x, y);
+    let speed = u.hypot(v);
+    let (dx, dy) = if speed < 1e-9 {
+        (0.0, 0.0)
+    } else {
+        (u / speed * STEP_LENGTH, v / speed * STEP_LENGTH)
+    };
+
+    let ground = |step: usize| {
+        if step == 0 {
+            continentalness
+        } else {
+            relief(x - dx * step as f64, y - dy * step as f64)
+        }
+    };
+    let mut previous = ground(TRANSPORT_STEPS);
+    let mut vapour = if previous < sea_level { 1.0 } else { DRY_AIR };
+    let mut rain = 0.0;
+
+    // Walk downwind, ending on the cell
+    for step in (0..TRANSPORT_STEPS).rev() {
+        let height = ground(step);
+        let rainout = if height < sea_level {
+            vapour += EVAPORATION * (1.0 - vapour);
+            RAINOUT
+        } else {
+            // Coming ashore, the climb starts at the shoreline, not the seabed
+            let rise = (height - previous.max(sea_level)).max(0.0);
+            RAINOUT + rise * OROGRAPHIC_RAINOUT + (height - sea_level) * ALTITUDE_RAINOUT
+        };
+        rain = vapour * rainout.min(1.0);
+        vapour -= rain;
+        if height >= sea_level {
+            vapour += rain * RECYCLING;
+        }
+        previous = height;
+    }
+
+    Moisture {
+        vapour: vapour.clamp(0.0, 1.0),
+        precipitation: (rain / HEAVY_RAIN).min(1.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tidally_locked::LatitudeTemperatureStrategy;
+
+    #[test]
+    fn rain_falls_on_the_windward_slope() {
+        let wind = WindStrategy::new(LatitudeTemperatureStrategy::default_for_map(43), 48);
+        let (x, y) = (300.0, 250.0);
+        let (u, v) = wind.vector(x, y);
+        let speed = u.hypot(v);
+        let (dx, dy) = (u / speed, v / speed);
+        let along = |px: f64, py: f64| (px - x) * dx + (py - y) * dy;
+
+        // Sea far upwind, then flat land
+        let flat = |px: f64, py: f64| if along(px, py) < -35.0 { -0.5 } else { 0.1 };
+        let open = transport_moisture(&wind, x, y, 0.1, 0.0, flat);
+
+        // Sea air soaks the coast and dries out far inland
+        let inland = transport_moisture(&wind, x, y, 0.1, 0.0, |_, _| 0.1);
+        assert!(open.vapour > inland.vapour + 0.2, "{:?} vs {:?}", open, inland);
+
+        // A ridge across the wind before the cell wrings the air out
+        let ridge = |px: f64, py: f64| match along(px, py) {
+            a if a < -35.0 => -0.5,
+            a if (-20.0..=-8.0).contains(&a) => 0.6,
+            _ => 0.1,
+        };
+        let lee = transport_moisture(&wind, x, y, 0.1, 0.0, ridge);
+        assert!(lee.vapour < open.vapour * 0.5, "lee {:?} vs open {:?}", lee, open);
+
+        // Climbing the ridge rains harder than crossing the plain
+        let slope = transport_moisture(&wind, x, y, 0.6, 0.0, |px, py| {
+            if along(px, py) < -35.0 {
+                -0.5
+            } else {
+                0.1
+            }
+        });
+        assert!(slope.precipitation > open.precipitation, "{:?} vs {:?}", slope, open);
+
+        // Air over open sea stays close to saturated
+        let sea = transport_moisture(&wind, x, y, -0.5, 0.0, |_, _| -0.5);
+        assert!(sea.vapour > 0.8 && sea.vapour > open.vapour, "{:?}", sea);
+    }
+}
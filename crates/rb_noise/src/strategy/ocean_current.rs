@@ -0,0 +1,95 @@
+use noise::{NoiseFn, OpenSimplex};
+use rb_core::NoiseStrategy;
+
+/// World-to-noise scale of the gyres; a gyre spans a few hundred units.
+const GYRE_SCALE: f64 = 0.005;
+/// Step (world units) for differentiating the stream function.
+const STREAM_STEP: f64 = 1.0;
+/// Stream function slope (per world unit) of a fast current.
+const MAX_SLOPE: f64 = 0.01;
+
+/// Large ocean gyres and whether they run warm or cold.
+///
+/// Currents follow the contours of a low-frequency stream function, so they
+/// circulate in closed gyres without piling water up anywhere. Water heading
+/// toward the dark side (north, −y) carries heat from the sun side and runs
+/// warm; water heading sunward runs cold.
+///
+/// [`vector`](Self::vector) gives the current itself; as a [`NoiseStrategy`]
+/// it yields the current's warmth in [-1.0, 1.0] (positive = warm).
+#[derive(Clone)]
+pub struct OceanCurrentStrategy {
+    stream: OpenSimplex,
+}
+
+impl OceanCurrentStrategy {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            stream: OpenSimplex::new(seed),
+        }
+    }
+
+    /// Stream function: two octaves so gyres aren't perfect circles.
+    fn stream(&self, x: f64, y: f64) -> f64 {
+        let (nx, ny) = (x * GYRE_SCALE, y * GYRE_SCALE);
+        self.stream.get([nx, ny]) + 0.4 * self.stream.get([nx * 2.3, ny * 2.3])
+    }
+
+    /// Current at a point as `(x, y)` world-space components, with length
+    /// from 0 (slack) to 1. Defined everywhere; callers mask out land.
+    pub fn vector(&self, x: f64, y: f64) -> (f64, f64) {
+        let d = STREAM_STEP;
+        let ds_dx = (self.stream(x + d, y) - self.stream(x - d, y)) / (2.0 * d);
+        let ds_dy = (self.stream(x, y + d) - self.stream(x, y - d)) / (2.0 * d);
+
+        // Perpendicular to the gradient, so flow runs along the contours
+        let (u, v) = (ds_dy / MAX_SLOPE, -ds_dx / MAX_SLOPE);
+        let speed = u.hypot(v);
+        if speed > 1.0 {
+            (u / speed, v / speed)
+        } else {
+            (u, v)
+        }
+    }
+}
+
+impl NoiseStrategy for OceanCurrentStrategy {
+    fn generate(&self, x: f64, y: f64, _detail_level: u32) -> f64 {
+        // Northward flow (toward the dark side) is warm
+        -self.vector(x, y).1
+    }
+
+    fn name(&self) -> &'static str {
+        "OceanCurrents"
+    }
+
+    fn output_range(&self) -> (f64, f64) {
+        (-1.0, 1.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Ocean current warmth: positive flowing to the dark side, negative to the sun"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gyres_carry_warm_and_cold_water() {
+        let currents = OceanCurrentStrategy::new(42);
+        let (mut warm, mut cold) = (0, 0);
+        for i in 0..400 {
+            let (x, y) = ((i % 20) as f64 * 37.0, (i / 20) as f64 * 29.0);
+            let (u, v) = currents.vector(x, y);
+            assert!(u.hypot(v) <= 1.0 + 1e-9);
+            match currents.generate(x, y, 0) {
+                w if w > 0.1 => warm += 1,
+                w if w < -0.1 => cold += 1,
+                _ => {}
+            }
+        }
+        assert!(warm > 40 && cold > 40, "{} warm, {} cold", warm, cold);
+    }
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+/// Shape of one layer's fBm: feature size, octave stack and strength.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FbmParams {
+    /// Feature size relative to the layer's default; 2 makes features twice
+    /// as large.
+    pub scale: f64,
+    pub octaves: u32,
+    /// Amplitude kept from one octave to the next.
+    pub persistence: f64,
+    /// Frequency gained from one octave to the next.
+    pub lacunarity: f64,
+    /// Multiplier on the layer's normalized noise. Above 1 exaggerates the
+    /// layer and can push it past its usual range.
+    pub amplitude: f64,
+}
+
+impl FbmParams {
+    const fn new(octaves: u32, persistence: f64, lacunarity: f64) -> Self {
+        Self {
+            scale: 1.0,
+            octaves,
+            persistence,
+            lacunarity,
+            amplitude: 1.0,
+        }
+    }
+}
+
+/// [`FbmParams`] for each noise layer. The defaults are what the strategies
+/// are built with, so a default set changes nothing.
+///
+/// Tectonic plates are Voronoi cells rather than an fBm: its scale sizes the
+/// plates, and the octaves and amplitude shape the noise that roughens their
+/// boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayerParams {
+    pub continentalness: FbmParams,
+    pub temperature: FbmParams,
+    pub tectonic: FbmParams,
+    pub erosion: FbmParams,
+    pub peaks_valleys: FbmParams,
+    pub humidity: FbmParams,
+}
+
+impl Default for LayerParams {
+    fn default() -> Self {
+        Self {
+            continentalness: FbmParams::new(16, 0.59, 2.0),
+            temperature: FbmParams::new(8, 0.59, 2.0),
+            tectonic: FbmParams::new(1, 0.5, 2.0),
+            erosion: FbmParams::new(6, 0.55, 2.2),
+            peaks_valleys: FbmParams::new(8, 0.6, 2.0),
+            humidity: FbmParams::new(5, 0.5, 2.0),
+        }
+    }
+}
+
+impl LayerParams {
+    /// Whether every layer is as the strategies build it, the only shape the
+    /// shaders know.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Each layer's display name and parameters, for UI editing.
+    pub fn named_mut(&mut self) -> [(&'static str, &mut FbmParams); 6] {
+        [
+            ("Continentalness", &mut self.continentalness),
+            ("Temperature", &mut self.temperature),
+            ("Tectonic Plates", &mut self.tectonic),
+            ("Erosion", &mut self.erosion),
+            ("Peaks & Valleys", &mut self.peaks_valleys),
+            ("Humidity", &mut self.humidity),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{
+        ContinentalnessStrategy, ErosionStrategy, HumidityStrategy, PeaksAndValleysStrategy,
+        TectonicPlatesStrategy,
+    };
+    use crate::tidally_locked::LatitudeTemperatureStrategy;
+    use rb_core::NoiseStrategy;
+
+    #[test]
+    fn default_params_change_nothing() {
+        let layers = LayerParams::default();
+        let temperature = LatitudeTemperatureStrategy::default_for_map(2);
+        let pairs: Vec<(Box<dyn NoiseStrategy>, Box<dyn NoiseStrategy>)> = vec![
+            (
+                Box::new(ContinentalnessStrategy::new(1)),
+                Box::new(ContinentalnessStrategy::new(1).with_fbm(&layers.continentalness)),
+            ),
+            (
+                Box::new(temperature.clone()),
+                Box::new(temperature.with_fbm(&layers.temperature)),
+            ),
+            (
+                Box::new(TectonicPlatesStrategy::new(3)),
+                Box::new(TectonicPlatesStrategy::new(3).with_fbm(&layers.tectonic)),
+            ),
+            (
+                Box::new(ErosionStrategy::new(4)),
+                Box::new(ErosionStrategy::new(4).with_fbm(&layers.erosion)),
+            ),
+            (
+                Box::new(PeaksAndValleysStrategy::new(5)),
+                Box::new(PeaksAndValleysStrategy::new(5).with_fbm(&layers.peaks_valleys)),
+            ),
+            (
+                Box::new(HumidityStrategy::new(6)),
+                Box::new(HumidityStrategy::new(6).with_fbm(&layers.humidity)),
+            ),
+        ];
+        for (built, shaped) in &pairs {
+            for i in 0..100 {
+                let (x, y) = ((i % 10) as f64 * 23.0, (i / 10) as f64 * 17.0);
+                assert_eq!(built.generate(x, y, 1), shaped.generate(x, y, 1), "{}", built.name());
+            }
+        }
+    }
+
+    #[test]
+    fn scale_stretches_features() {
+        let params = FbmParams {
+            scale: 2.0,
+            ..LayerParams::default().continentalness
+        };
+        let built = ContinentalnessStrategy::new(7);
+        let stretched = ContinentalnessStrategy::new(7).with_fbm(&params);
+        for i in 0..50 {
+            let (x, y) = (i as f64 * 13.0, i as f64 * 7.0);
+            assert_eq!(stretched.generate(x * 2.0, y * 2.0, 0), built.generate(x, y, 0));
+        }
+    }
+}
@@ -1,26 +1,35 @@
-use noise::{NoiseFn, OpenSimplex};
+use noise::OpenSimplex;
 use rb_core::NoiseStrategy;
 
+use super::basis::NoiseSource;
+use super::params::FbmParams;
+use super::warp::{warped, DomainWarp};
+
 /// Generates peaks and valleys using ridged multifractal noise.
 /// Creates distinct mountain ridgelines and valley networks.
 ///
 /// Output range: [-1.0, 1.0] where -1 = deep valley, +1 = sharp ridge
 pub struct PeaksAndValleysStrategy {
-    noise: OpenSimplex,
+    noise: Box<dyn NoiseSource>,
+    warp: Option<DomainWarp>,
     octaves: u32,
     frequency: f64,
     persistence: f64,
     lacunarity: f64,
+    /// Multiplier on the normalized noise.
+    amplitude: f64,
 }
 
 impl PeaksAndValleysStrategy {
     pub fn new(seed: u32) -> Self {
         Self {
-            noise: OpenSimplex::new(seed),
+            noise: Box::new(OpenSimplex::new(seed)),
+            warp: None,
             octaves: 8,
             frequency: 1.5,
             persistence: 0.6,
             lacunarity: 2.0,
+            amplitude: 1.0,
         }
     }
 
@@ -32,17 +41,42 @@ impl PeaksAndValleysStrategy {
         lacunarity: f64,
     ) -> Self {
         Self {
-            noise: OpenSimplex::new(seed),
+            noise: Box::new(OpenSimplex::new(seed)),
+            warp: None,
             octaves,
             frequency,
             persistence,
             lacunarity,
+            amplitude: 1.0,
         }
     }
 
+    /// Reshape the fBm with `params`, relative to how it was built.
+    pub fn with_fbm(mut self, params: &FbmParams) -> Self {
+        self.frequency /= params.scale;
+        self.octaves = params.octaves;
+        self.persistence = params.persistence;
+        self.lacunarity = params.lacunarity;
+        self.amplitude = params.amplitude;
+        self
+    }
+
+    /// Sum octaves of `source` instead of OpenSimplex.
+    pub fn with_source(mut self, source: Box<dyn NoiseSource>) -> Self {
+        self.noise = source;
+        self
+    }
+
+    /// Warp sample coordinates with `warp` before every fBm.
+    pub fn with_warp(mut self, warp: DomainWarp) -> Self {
+        self.warp = Some(warp);
+        self
+    }
+
     /// Ridged multifractal noise produces sharp ridges.
     /// Based on the ridged multifractal algorithm from Musgrave et al.
     fn ridged_fbm(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        let (x, y) = warped(&self.warp, x, y);
         let mut value = 0.0;
         let mut amplitude = 1.0;
         let mut freq = self.frequency;
@@ -51,7 +85,7 @@ impl PeaksAndValleysStrategy {
 
         let total_octaves = self.octaves + detail_level;
 
-        for _ in 0..total_octaves {
+        for octave in 0..total_octaves {
             let nx = x * freq * 0.01;
             let ny = y * freq * 0.01;
 
@@ -66,19 +100,27 @@ impl PeaksAndValleysStrategy {
             let signal = signal * weight;
             weight = (signal * 2.0).clamp(0.0, 1.0);
 
-            value += signal * amplitude;
-            max_value += amplitude;
+            // Ridges are all positive, so centre the detail octaves rather
+            // than let them lift the base shape
+            if octave < self.octaves {
+                value += signal * amplitude;
+                max_value += amplitude;
+            } else {
+                value += (signal - 0.5) * amplitude;
+            }
 
             amplitude *= self.persistence;
             freq *= self.lacunarity;
         }
 
         // Normalize and shift to [-1, 1]
-        (value / max_value) * 2.0 - 1.0
+        ((value / max_value) * 2.0 - 1.0) * self.amplitude
     }
 
     /// Alternative: standard valleys (inverted peaks)
+    #[allow(dead_code)]
     fn valleys(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        let (x, y) = warped(&self.warp, x, y);
         let mut value = 0.0;
         let mut amplitude = 1.0;
         let mut freq = self.frequency;
@@ -86,15 +128,18 @@ impl PeaksAndValleysStrategy {
 
         let total_octaves = self.octaves + detail_level;
 
-        for _ in 0..total_octaves {
+        for octave in 0..total_octaves {
             let nx = x * freq * 0.01;
             let ny = y * freq * 0.01;
 
             // Get noise and take absolute value for valleys
             let n = self.noise.get([nx, ny]).abs();
-            value += (1.0 - n) * amplitude;
-
-            max_amplitude += amplitude;
+            if octave < self.octaves {
+                value += (1.0 - n) * amplitude;
+                max_amplitude += amplitude;
+            } else {
+                value += (0.5 - n) * amplitude;
+            }
             amplitude *= self.persistence;
             freq *= self.lacunarity;
         }
@@ -109,9 +154,73 @@ impl NoiseStrategy for PeaksAndValleysStrategy {
         self.ridged_fbm(x, y, detail_level)
     }
 
+    /// [`ridged_fbm`](Self::ridged_fbm) with the octave loop outermost.
+    fn generate_batch(&self, points: &[(f64, f64)], detail_level: u32, out: &mut [f64]) {
+        assert_eq!(points.len(), out.len(), "one output per point");
+        let points: Vec<_> = points.iter().map(|&(x, y)| warped(&self.warp, x, y)).collect();
+        out.fill(0.0);
+        let mut weights = vec![1.0; points.len()];
+        let mut amplitude = 1.0;
+        let mut freq = self.frequency;
+        let mut max_value = 0.0;
+
+        for octave in 0..self.octaves + detail_level {
+            let base = octave < self.octaves;
+            for ((value, weight), &(x, y)) in out.iter_mut().zip(&mut weights).zip(&points) {
+                let signal = 1.0 - self.noise.get([x * freq * 0.01, y * freq * 0.01]).abs();
+                let signal = signal * signal * *weight;
+                *weight = (signal * 2.0).clamp(0.0, 1.0);
+                *value += (if base { signal } else { signal - 0.5 }) * amplitude;
+            }
+            if base {
+                max_value += amplitude;
+            }
+            amplitude *= self.persistence;
+            freq *= self.lacunarity;
+        }
+
+        for value in out {
+            *value = ((*value / max_value) * 2.0 - 1.0) * self.amplitude;
+        }
+    }
+
+    /// The ridges of the base octaves as [`ridged_fbm`](Self::ridged_fbm)
+    /// shapes them, and the centred detail octaves stretched to [-1, 1].
+    fn generate_split(&self, x: f64, y: f64, detail_level: u32) -> (f64, f64) {
+        let (x, y) = warped(&self.warp, x, y);
+        let (mut coarse, mut detail) = (0.0, 0.0);
+        let (mut max_value, mut detail_amplitude) = (0.0, 0.0);
+        let mut amplitude = 1.0;
+        let mut freq = self.frequency;
+        let mut weight = 1.0;
+
+        for octave in 0..self.octaves + detail_level {
+            let signal = 1.0 - self.noise.get([x * freq * 0.01, y * freq * 0.01]).abs();
+            let signal = signal * signal * weight;
+            weight = (signal * 2.0).clamp(0.0, 1.0);
+            if octave < self.octaves {
+                coarse += signal * amplitude;
+                max_value += amplitude;
+            } else {
+                detail += (signal - 0.5) * amplitude;
+                detail_amplitude += amplitude;
+            }
+            amplitude *= self.persistence;
+            freq *= self.lacunarity;
+        }
+
+        // Centred signals reach at most half an octave's amplitude
+        let detail = if detail_level == 0 { 0.0 } else { detail / (detail_amplitude * 0.5) };
+        (((coarse / max_value) * 2.0 - 1.0) * self.amplitude, detail)
+    }
+
     fn name(&self) -> &'static str {
         "PeaksValleys"
     }
+
+    fn description(&self) -> &'static str {
+        "Ridgelines: -1 is a valley floor, 1 a sharp peak"
+    }
 }
 
 #[cfg(test)]
@@ -126,7 +235,7 @@ mod tests {
             let y = i as f64 * 10.0;
             let val = strategy.generate(x, y, 0);
             assert!(
-                val >= -1.0 && val <= 1.0,
+                (-1.0..=1.0).contains(&val),
                 "Value {} out of range at ({}, {})",
                 val,
                 x,
@@ -1,6 +1,10 @@
 use noise::{NoiseFn, OpenSimplex};
 use rb_core::{NoiseStrategy, ResourceType, TileType};
 
+/// Cutoff for [`ResourceNoiseStrategy::generate_with_context`], and for maps
+/// generated before per-resource cutoffs existed.
+pub const DEFAULT_RESOURCE_THRESHOLD: f64 = 0.55;
+
 /// Generates noise for resource deposits with terrain-aware biasing.
 /// Each resource type gets a unique noise pattern biased by terrain.
 pub struct ResourceNoiseStrategy {
@@ -50,11 +54,13 @@ impl ResourceNoiseStrategy {
 
         let total_octaves = self.octaves + detail_level;
 
-        for _ in 0..total_octaves {
+        for octave in 0..total_octaves {
             let nx = x * freq * 0.015;
             let ny = y * freq * 0.015;
             value += self.noise.get([nx, ny]) * amplitude;
-            max_amplitude += amplitude;
+            if octave < self.octaves {
+                max_amplitude += amplitude;
+            }
             amplitude *= self.persistence;
             freq *= self.frequency;
         }
@@ -62,24 +68,31 @@ impl ResourceNoiseStrategy {
         value / max_amplitude
     }
 
-    /// Generate resource abundance with terrain biasing.
+    /// Generate resource abundance with terrain biasing, cut off at
+    /// [`DEFAULT_RESOURCE_THRESHOLD`].
     pub fn generate_with_context(&self, x: f64, y: f64, detail_level: u32, context: &ResourceContext) -> f64 {
-        let base_value = (self.fbm(x, y, detail_level) + 1.0) * 0.5;
+        let biased_value = self.biased_value(x, y, detail_level, context);
+        Self::abundance(biased_value, DEFAULT_RESOURCE_THRESHOLD)
+    }
 
-        // Apply terrain bias
+    /// Base noise in `[0, 1]` scaled by the resource's terrain bias, before
+    /// any cutoff.
+    pub fn biased_value(&self, x: f64, y: f64, detail_level: u32, context: &ResourceContext) -> f64 {
+        let base_value = (self.fbm(x, y, detail_level) + 1.0) * 0.5;
         let bias_multiplier = self.resource_type.terrain_bias().calculate(
             context.continentalness,
             context.tectonic_boundary_distance,
             context.water_distance,
             context.biome,
         );
+        base_value * bias_multiplier
+    }
 
-        // Resources are sparse - use threshold to create distinct deposits
-        let threshold = 0.55;
-        let biased_value = base_value * bias_multiplier;
-
+    /// Abundance of a biased value: zero up to `threshold`, then rising
+    /// linearly to 1. Resources are sparse, so the cutoff is what separates
+    /// distinct deposits.
+    pub fn abundance(biased_value: f64, threshold: f64) -> f64 {
         if biased_value > threshold {
-            // Normalize above-threshold values to [0, 1]
             ((biased_value - threshold) / (1.0 - threshold)).clamp(0.0, 1.0)
         } else {
             0.0
@@ -96,6 +109,14 @@ impl NoiseStrategy for ResourceNoiseStrategy {
     fn name(&self) -> &'static str {
         self.resource_type.name()
     }
+
+    fn output_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Deposit likelihood before terrain bias"
+    }
 }
 
 /// Context data needed for resource generation with terrain biasing.
@@ -107,6 +128,22 @@ pub struct ResourceContext {
     pub biome: TileType,
 }
 
+impl ResourceContext {
+    /// Build the context for a cell from its terrain layers.
+    pub fn from_terrain(continentalness: f64, tectonic: f64, biome: TileType, sea_level: f64) -> Self {
+        Self {
+            continentalness,
+            tectonic_boundary_distance: tectonic,
+            water_distance: if continentalness < sea_level {
+                0.0
+            } else {
+                ((continentalness + 0.025) * 5.0).min(1.0)
+            },
+            biome,
+        }
+    }
+}
+
 impl Default for ResourceContext {
     fn default() -> Self {
         Self {
@@ -129,7 +166,7 @@ mod tests {
             let x = i as f64 * 10.0;
             let y = i as f64 * 10.0;
             let val = strategy.generate(x, y, 0);
-            assert!(val >= 0.0 && val <= 1.0, "Value {} out of range", val);
+            assert!((0.0..=1.0).contains(&val), "Value {} out of range", val);
         }
     }
 
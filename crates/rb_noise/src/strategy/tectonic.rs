@@ -1,14 +1,77 @@
 use noise::{NoiseFn, OpenSimplex};
 use rb_core::NoiseStrategy;
 
+use super::params::FbmParams;
+
+/// Convergence either side of zero within which plates are taken to slide
+/// past each other.
+const TRANSFORM_BAND: f64 = 0.25;
+/// Boundary distance over which uplift and rifting fade out toward the plate
+/// interior.
+const UPLIFT_WIDTH: f64 = 0.35;
+
+/// A plate cell near a point: `(cell, centre, distance)` in scaled
+/// coordinates.
+type NearbyPlate = ((i32, i32), (f64, f64), f64);
+
+/// How the plates either side of a boundary move relative to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryKind {
+    /// Plates collide: mountain ranges on land, trenches at sea.
+    Convergent,
+    /// Plates pull apart: rift valleys.
+    Divergent,
+    /// Plates slide past each other.
+    Transform,
+}
+
+/// The plate boundary nearest a point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlateBoundary {
+    /// 0 on the boundary, 1 at the plate's centre, as the tectonic layer.
+    pub distance: f64,
+    /// How fast the plates close across the boundary, in [-1.0, 1.0]:
+    /// positive where they collide, negative where they separate.
+    pub convergence: f64,
+}
+
+impl PlateBoundary {
+    pub fn kind(&self) -> BoundaryKind {
+        if self.convergence > TRANSFORM_BAND {
+            BoundaryKind::Convergent
+        } else if self.convergence < -TRANSFORM_BAND {
+            BoundaryKind::Divergent
+        } else {
+            BoundaryKind::Transform
+        }
+    }
+
+    /// Crustal uplift the boundary drives here, in [-1.0, 1.0]: positive
+    /// near convergent boundaries, negative near divergent ones, fading to 0
+    /// into the plate and along transform faults.
+    pub fn uplift(&self) -> f64 {
+        let proximity = (1.0 - self.distance / UPLIFT_WIDTH).max(0.0);
+        let stress = (self.convergence.abs() - TRANSFORM_BAND).max(0.0) / (1.0 - TRANSFORM_BAND);
+        stress.copysign(self.convergence) * proximity * proximity
+    }
+}
+
 /// Generates tectonic plate boundaries using Voronoi cells.
 ///
 /// Output range: [0.0, 1.0] where 0 = on plate boundary, 1 = center of plate
 /// Uses Voronoi noise for distinct plates with visible boundaries.
+///
+/// Each plate drifts in its own direction, so every boundary is convergent,
+/// divergent or transform; see [`boundary`](Self::boundary).
 pub struct TectonicPlatesStrategy {
     seed: u32,
     noise: OpenSimplex,
     plate_scale: f64, // Controls plate size
+    /// Octave stack and strength of the boundary roughness.
+    octaves: u32,
+    persistence: f64,
+    lacunarity: f64,
+    roughness: f64,
 }
 
 impl TectonicPlatesStrategy {
@@ -17,6 +80,10 @@ impl TectonicPlatesStrategy {
             seed,
             noise: OpenSimplex::new(seed),
             plate_scale: 0.004, // Creates ~8-12 plates across typical world
+            octaves: 1,
+            persistence: 0.5,
+            lacunarity: 2.0,
+            roughness: 1.0,
         }
     }
 
@@ -25,7 +92,37 @@ impl TectonicPlatesStrategy {
             seed,
             noise: OpenSimplex::new(seed),
             plate_scale,
+            octaves: 1,
+            persistence: 0.5,
+            lacunarity: 2.0,
+            roughness: 1.0,
+        }
+    }
+
+    /// Resize the plates by `params.scale` and shape their boundary
+    /// roughness with the rest of `params`.
+    pub fn with_fbm(mut self, params: &FbmParams) -> Self {
+        self.plate_scale /= params.scale;
+        self.octaves = params.octaves;
+        self.persistence = params.persistence;
+        self.lacunarity = params.lacunarity;
+        self.roughness = params.amplitude;
+        self
+    }
+
+    /// Noise that wobbles the plate boundaries, in [-1, 1] at unit roughness.
+    fn boundary_noise(&self, x: f64, y: f64) -> f64 {
+        let mut value = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 0.02;
+        let mut max_amplitude = 0.0;
+        for _ in 0..self.octaves {
+            value += self.noise.get([x * frequency, y * frequency]) * amplitude;
+            max_amplitude += amplitude;
+            amplitude *= self.persistence;
+            frequency *= self.lacunarity;
         }
+        value / max_amplitude * self.roughness
     }
 
     /// Hash function to generate pseudo-random cell center offsets.
@@ -54,49 +151,49 @@ impl TectonicPlatesStrategy {
         (n & 0xFF) as f64 / 255.0
     }
 
-    /// Generate tectonic value using Voronoi cells.
-    /// Returns boundary distance: 0 = at boundary, 1 = center of plate
-    pub fn generate_voronoi(&self, x: f64, y: f64) -> (f64, f64) {
-        // Scale coordinates for plate size
-        let sx = x * self.plate_scale;
-        let sy = y * self.plate_scale;
-
-        // Get integer cell coordinates
-        let ix = sx.floor() as i32;
-        let iy = sy.floor() as i32;
+    /// Drift of the plate in cell `(ix, iy)`: a direction and a speed
+    /// between half and full, as an `(x, y)` vector.
+    fn drift(&self, ix: i32, iy: i32) -> (f64, f64) {
+        let n = (ix.wrapping_mul(73856093) as u32)
+            ^ (iy.wrapping_mul(19349663) as u32)
+            ^ self.seed.wrapping_mul(83492791);
+        let n = n.wrapping_mul(1103515245).wrapping_add(12345);
+        let angle = (n & 0xFFFF) as f64 / 65535.0 * std::f64::consts::TAU;
+        let speed = 0.5 + ((n >> 16) & 0x7FFF) as f64 / 0x7FFF as f64 * 0.5;
+        (angle.cos() * speed, angle.sin() * speed)
+    }
 
-        // Find distances to nearest cell centers
-        let mut min_dist = f64::MAX;
-        let mut second_dist = f64::MAX;
-        let mut nearest_cell = (0i32, 0i32);
+    /// The two plate cells nearest a point in scaled coordinates, nearest
+    /// first.
+    fn nearest_plates(&self, sx: f64, sy: f64) -> [NearbyPlate; 2] {
+        let (ix, iy) = (sx.floor() as i32, sy.floor() as i32);
+        let far = ((0, 0), (0.0, 0.0), f64::MAX);
+        let mut nearest = [far, far];
 
         // Check 3x3 grid of cells
         for dx in -1..=1 {
             for dy in -1..=1 {
-                let cell_x = ix + dx;
-                let cell_y = iy + dy;
-
-                // Get cell center offset (0-1 within cell)
-                let (ox, oy) = self.hash(cell_x, cell_y);
+                let cell = (ix + dx, iy + dy);
 
                 // Cell center in scaled coordinates
-                let cx = cell_x as f64 + ox;
-                let cy = cell_y as f64 + oy;
-
-                // Distance from point to this cell center
-                let dist_sq = (sx - cx).powi(2) + (sy - cy).powi(2);
-                let dist = dist_sq.sqrt();
-
-                if dist < min_dist {
-                    second_dist = min_dist;
-                    min_dist = dist;
-                    nearest_cell = (cell_x, cell_y);
-                } else if dist < second_dist {
-                    second_dist = dist;
+                let (ox, oy) = self.hash(cell.0, cell.1);
+                let centre = (cell.0 as f64 + ox, cell.1 as f64 + oy);
+                let dist = (sx - centre.0).hypot(sy - centre.1);
+
+                if dist < nearest[0].2 {
+                    nearest[1] = nearest[0];
+                    nearest[0] = (cell, centre, dist);
+                } else if dist < nearest[1].2 {
+                    nearest[1] = (cell, centre, dist);
                 }
             }
         }
+        nearest
+    }
 
+    /// Boundary distance at `(x, y)` given its distances to the two nearest
+    /// plate centres: 0 = at boundary, 1 = center of plate.
+    fn boundary_distance(&self, x: f64, y: f64, min_dist: f64, second_dist: f64) -> f64 {
         // Boundary distance: how close are we to being equidistant from two cells?
         // At boundary: min_dist ≈ second_dist → ratio ≈ 1 → boundary_dist ≈ 0
         // At center: min_dist << second_dist → ratio ≈ 0 → boundary_dist ≈ 1
@@ -111,13 +208,38 @@ impl TectonicPlatesStrategy {
         let boundary_dist = (1.0 - ratio).clamp(0.0, 1.0);
 
         // Add some noise to make boundaries less perfectly straight
-        let roughness = self.noise.get([x * 0.02, y * 0.02]) * 0.1;
-        let adjusted_boundary = (boundary_dist + roughness).clamp(0.0, 1.0);
+        let roughness = self.boundary_noise(x, y) * 0.1;
+        (boundary_dist + roughness).clamp(0.0, 1.0)
+    }
+
+    /// Generate tectonic value using Voronoi cells.
+    /// Returns boundary distance: 0 = at boundary, 1 = center of plate
+    pub fn generate_voronoi(&self, x: f64, y: f64) -> (f64, f64) {
+        let [(cell, _, min_dist), (_, _, second_dist)] =
+            self.nearest_plates(x * self.plate_scale, y * self.plate_scale);
+        let boundary_dist = self.boundary_distance(x, y, min_dist, second_dist);
 
         // Get plate ID for coloring
-        let plate_id = self.plate_id_hash(nearest_cell.0, nearest_cell.1);
+        (self.plate_id_hash(cell.0, cell.1), boundary_dist)
+    }
 
-        (plate_id, adjusted_boundary)
+    /// The plate boundary nearest `(x, y)` and how the plates either side of
+    /// it move relative to each other.
+    pub fn boundary(&self, x: f64, y: f64) -> PlateBoundary {
+        let [(cell, centre, min_dist), (other, other_centre, second_dist)] =
+            self.nearest_plates(x * self.plate_scale, y * self.plate_scale);
+
+        // Closing speed along the line between the two plate centres
+        let (nx, ny) = (other_centre.0 - centre.0, other_centre.1 - centre.1);
+        let length = nx.hypot(ny).max(1e-9);
+        let (ax, ay) = self.drift(cell.0, cell.1);
+        let (bx, by) = self.drift(other.0, other.1);
+        let closing = ((ax - bx) * nx + (ay - by) * ny) / length;
+
+        PlateBoundary {
+            distance: self.boundary_distance(x, y, min_dist, second_dist),
+            convergence: (closing / 2.0).clamp(-1.0, 1.0),
+        }
     }
 
     /// Returns distance from nearest plate boundary.
@@ -142,6 +264,14 @@ impl NoiseStrategy for TectonicPlatesStrategy {
     fn name(&self) -> &'static str {
         "Tectonic"
     }
+
+    fn output_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Distance from the nearest plate boundary: 0 on the boundary, 1 at a plate's centre"
+    }
 }
 
 #[cfg(test)]
@@ -155,7 +285,7 @@ mod tests {
             let x = i as f64 * 10.0;
             let y = i as f64 * 10.0;
             let val = strategy.generate(x, y, 0);
-            assert!(val >= 0.0 && val <= 1.0, "Value {} out of range", val);
+            assert!((0.0..=1.0).contains(&val), "Value {} out of range", val);
         }
     }
 
@@ -163,7 +293,7 @@ mod tests {
     fn boundary_distance_is_normalized() {
         let strategy = TectonicPlatesStrategy::new(42);
         let dist = strategy.plate_boundary_distance(100.0, 100.0, 0);
-        assert!(dist >= 0.0 && dist <= 1.0);
+        assert!((0.0..=1.0).contains(&dist));
     }
 
     #[test]
@@ -171,15 +301,39 @@ mod tests {
         let strat1 = TectonicPlatesStrategy::new(42);
         let strat2 = TectonicPlatesStrategy::new(123);
 
-        let (id1, _) = strat1.generate_voronoi(500.0, 500.0);
-        let (id2, _) = strat2.generate_voronoi(500.0, 500.0);
+        // Any single point may coincide, so compare across a handful of samples
+        let differs = (0..16).any(|i| {
+            let p = i as f64 * 250.0;
+            let (id1, _) = strat1.generate_voronoi(p, p);
+            let (id2, _) = strat2.generate_voronoi(p, p);
+            (id1 - id2).abs() > 0.001
+        });
+        assert!(differs, "Seeds should produce different layouts");
+    }
 
-        // Different seeds should generally produce different plate IDs
-        // (not a guarantee but likely)
-        assert!(
-            (id1 - id2).abs() > 0.001 || true,
-            "Seeds should produce different layouts"
-        );
+    #[test]
+    fn boundaries_collide_and_rift() {
+        let strategy = TectonicPlatesStrategy::new(42);
+        let mut kinds = Vec::new();
+        for i in 0..4000 {
+            let (x, y) = ((i % 80) as f64 * 25.0, (i / 80) as f64 * 25.0);
+            let boundary = strategy.boundary(x, y);
+            let uplift = boundary.uplift();
+            assert!((-1.0..=1.0).contains(&uplift));
+            match boundary.kind() {
+                BoundaryKind::Convergent => assert!(uplift >= 0.0),
+                BoundaryKind::Divergent => assert!(uplift <= 0.0),
+                BoundaryKind::Transform => assert_eq!(uplift, 0.0),
+            }
+            if boundary.distance > UPLIFT_WIDTH {
+                assert_eq!(uplift, 0.0, "uplift in a plate interior");
+            }
+            assert_eq!(boundary.distance, strategy.plate_boundary_distance(x, y, 0));
+            kinds.push(boundary.kind());
+        }
+        for kind in [BoundaryKind::Convergent, BoundaryKind::Divergent, BoundaryKind::Transform] {
+            assert!(kinds.contains(&kind), "no {:?} boundaries", kind);
+        }
     }
 
     #[test]
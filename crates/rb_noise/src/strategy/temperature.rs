@@ -1,6 +1,12 @@
 use noise::{NoiseFn, OpenSimplex};
 use rb_core::NoiseStrategy;
 
+use crate::tidally_locked::libration;
+
+/// World units per unit of latitude: the factor reaches tanh(1) this far
+/// from the equator.
+const LATITUDE_SCALE: f64 = 1000.0;
+
 /// Generates temperature values using 4-octave fBm blended with latitude.
 ///
 /// Output range: [-100.0, 100.0] (Celsius-like scale)
@@ -15,6 +21,8 @@ pub struct TemperatureStrategy {
     noise_weight: f64,
     /// Weight for latitude component (0.0 to 1.0)
     latitude_weight: f64,
+    /// Where the hottest latitude sits, in world units from y = 0.
+    equator: f64,
 }
 
 impl TemperatureStrategy {
@@ -27,6 +35,7 @@ impl TemperatureStrategy {
             lacunarity: 2.0,
             noise_weight: 0.3,
             latitude_weight: 0.7,
+            equator: 0.0,
         }
     }
 
@@ -46,9 +55,17 @@ impl TemperatureStrategy {
             lacunarity,
             noise_weight,
             latitude_weight: 1.0 - noise_weight,
+            equator: 0.0,
         }
     }
 
+    /// Climate at `season` through the year: the warm band drifts north
+    /// and south as the planet wobbles under its star.
+    pub fn with_season(mut self, season: f64) -> Self {
+        self.equator = libration(season) * LATITUDE_SCALE;
+        self
+    }
+
     fn fbm(&self, x: f64, y: f64, detail_level: u32) -> f64 {
         let mut value = 0.0;
         let mut amplitude = 1.0;
@@ -57,11 +74,13 @@ impl TemperatureStrategy {
 
         let total_octaves = self.octaves + detail_level;
 
-        for _ in 0..total_octaves {
+        for octave in 0..total_octaves {
             let nx = x * frequency / self.scale;
             let ny = y * frequency / self.scale;
             value += self.noise.get([nx, ny]) * amplitude;
-            max_amplitude += amplitude;
+            if octave < self.octaves {
+                max_amplitude += amplitude;
+            }
             amplitude *= self.persistence;
             frequency *= self.lacunarity;
         }
@@ -75,7 +94,7 @@ impl TemperatureStrategy {
     fn latitude_factor(&self, y: f64) -> f64 {
         // Normalize Y to a latitude factor
         // Using a sigmoid-like curve for more realistic temperature distribution
-        let normalized = (y / 1000.0).tanh();
+        let normalized = ((y - self.equator) / LATITUDE_SCALE).tanh();
         normalized.abs()
     }
 }
@@ -103,6 +122,14 @@ impl NoiseStrategy for TemperatureStrategy {
     fn name(&self) -> &'static str {
         "Temperature"
     }
+
+    fn output_range(&self) -> (f64, f64) {
+        (-100.0, 100.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Latitude-banded temperature, hot at the equator and cold at the poles"
+    }
 }
 
 #[cfg(test)]
@@ -114,7 +141,7 @@ mod tests {
         let strategy = TemperatureStrategy::new(42);
         let value = strategy.generate(0.0, 0.0, 0);
         assert!(
-            value >= -100.0 && value <= 100.0,
+            (-100.0..=100.0).contains(&value),
             "Value {} out of range",
             value
         );
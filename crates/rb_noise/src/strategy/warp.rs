@@ -0,0 +1,65 @@
+use noise::{NoiseFn, OpenSimplex};
+
+/// Default warp frequency, per world unit: a few wobbles across a continent.
+pub const DEFAULT_WARP_FREQUENCY: f64 = 0.004;
+
+/// Domain warp: shifts sample coordinates by a smooth noise field before an
+/// fBm is evaluated.
+///
+/// Plain fBm contours are blobby and evenly rounded. Pushing the lookup point
+/// around bends them into the swirls, peninsulas and inlets real coastlines
+/// have, without adding any high-frequency noise of its own.
+#[derive(Clone, Copy)]
+pub struct DomainWarp {
+    noise: OpenSimplex,
+    /// Largest offset, in world units.
+    pub amplitude: f64,
+    /// Frequency of the offset field, per world unit.
+    pub frequency: f64,
+}
+
+impl DomainWarp {
+    pub fn new(seed: u32, amplitude: f64, frequency: f64) -> Self {
+        Self {
+            noise: OpenSimplex::new(seed),
+            amplitude,
+            frequency,
+        }
+    }
+
+    /// Where the fBm should be sampled for world position `(x, y)`.
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let (fx, fy) = (x * self.frequency, y * self.frequency);
+        // Offset the second lookup so the two axes move independently
+        let dx = self.noise.get([fx, fy]);
+        let dy = self.noise.get([fx + 31.7, fy - 17.3]);
+        (x + dx * self.amplitude, y + dy * self.amplitude)
+    }
+}
+
+/// Apply `warp`, if any, to `(x, y)`.
+pub(crate) fn warped(warp: &Option<DomainWarp>, x: f64, y: f64) -> (f64, f64) {
+    match warp {
+        Some(warp) => warp.apply(x, y),
+        None => (x, y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warp_stays_within_amplitude() {
+        let warp = DomainWarp::new(7, 25.0, DEFAULT_WARP_FREQUENCY);
+        let mut moved = 0;
+        for i in 0..500 {
+            let (x, y) = ((i % 25) as f64 * 40.0, (i / 25) as f64 * 40.0);
+            let (wx, wy) = warp.apply(x, y);
+            assert!((wx - x).abs() <= 25.0 && (wy - y).abs() <= 25.0);
+            moved += ((wx - x).hypot(wy - y) > 5.0) as usize;
+        }
+        assert!(moved > 100, "only {} points moved noticeably", moved);
+        assert_eq!(warped(&None, 3.0, 4.0), (3.0, 4.0));
+    }
+}
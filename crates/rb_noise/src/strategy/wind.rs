@@ -0,0 +1,102 @@
+use noise::{NoiseFn, OpenSimplex};
+use rb_core::NoiseStrategy;
+
+use crate::tidally_locked::LatitudeTemperatureStrategy;
+
+/// World units between the samples the temperature gradient is taken over.
+const GRADIENT_STEP: f64 = 4.0;
+/// Temperature gradient (°C per world unit) that gives a moderate wind,
+/// about what the twilight band has across it.
+const GRADIENT_SCALE: f64 = 0.4;
+/// Largest angle (radians) local weather turns the wind off the gradient.
+const MAX_VEER: f64 = 0.6;
+
+/// Prevailing surface wind on a tidally locked planet.
+///
+/// Air sinks over the frozen dark side and rises over the sun side, so the
+/// surface wind blows from cold to hot, up the gradient of the large-scale
+/// temperature field. It is strongest where that field changes fastest (the
+/// edges of the twilight band) and meanders with low-frequency noise.
+///
+/// [`vector`](Self::vector) gives the wind itself; as a [`NoiseStrategy`] it
+/// yields wind speed in [0.0, 1.0].
+#[derive(Clone)]
+pub struct WindStrategy {
+    temperature: LatitudeTemperatureStrategy,
+    veer: OpenSimplex,
+}
+
+impl WindStrategy {
+    /// Wind driven by `temperature`, which should be the strategy the world's
+    /// temperature layer uses.
+    pub fn new(temperature: LatitudeTemperatureStrategy, seed: u32) -> Self {
+        Self {
+            temperature,
+            veer: OpenSimplex::new(seed),
+        }
+    }
+
+    /// The same wind, driven by a different temperature field.
+    pub fn with_temperature(mut self, temperature: LatitudeTemperatureStrategy) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Wind at a point as `(x, y)` world-space components. The length is the
+    /// speed, from 0 (calm) to 1.
+    pub fn vector(&self, x: f64, y: f64) -> (f64, f64) {
+        let temperature = |x, y| self.temperature.zonal_temperature(x, y);
+        let here = temperature(x, y);
+        let gx = (temperature(x + GRADIENT_STEP, y) - here) / GRADIENT_STEP;
+        let gy = (temperature(x, y + GRADIENT_STEP) - here) / GRADIENT_STEP;
+
+        let gradient = gx.hypot(gy);
+        if gradient < 1e-9 {
+            return (0.0, 0.0);
+        }
+        let speed = (gradient / GRADIENT_SCALE).tanh();
+        let angle = gy.atan2(gx) + MAX_VEER * self.veer.get([x * 0.004, y * 0.004]);
+        (angle.cos() * speed, angle.sin() * speed)
+    }
+}
+
+impl NoiseStrategy for WindStrategy {
+    fn generate(&self, x: f64, y: f64, _detail_level: u32) -> f64 {
+        let (u, v) = self.vector(x, y);
+        u.hypot(v)
+    }
+
+    fn name(&self) -> &'static str {
+        "Wind"
+    }
+
+    fn output_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Prevailing wind speed, blowing from the dark side toward the sun"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wind() -> WindStrategy {
+        WindStrategy::new(LatitudeTemperatureStrategy::default_for_map(43), 48)
+    }
+
+    #[test]
+    fn wind_blows_toward_the_sun_side() {
+        let wind = wind();
+        let mut southward = 0.0;
+        for i in 0..200 {
+            let (x, y) = ((i % 20) as f64 * 50.0, (i / 20) as f64 * 50.0 + 10.0);
+            let (u, v) = wind.vector(x, y);
+            assert!(u.hypot(v) <= 1.0 + 1e-9);
+            southward += v;
+        }
+        assert!(southward > 0.0, "mean wind points north ({})", southward);
+    }
+}
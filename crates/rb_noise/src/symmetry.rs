@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+/// Mirror symmetry applied to a generated map, for balanced multiplayer.
+///
+/// One half of the map is the source; every other cell copies its mirror
+/// image from that half.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Symmetry {
+    /// No symmetry.
+    #[default]
+    None,
+    /// Mirror left-right across the vertical centre line.
+    Horizontal,
+    /// 180° rotation about the map centre.
+    Rotational,
+}
+
+impl Symmetry {
+    /// All symmetry modes, for UI selection.
+    pub fn all() -> &'static [Symmetry] {
+        &[Symmetry::None, Symmetry::Horizontal, Symmetry::Rotational]
+    }
+
+    /// Display name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::None => "None",
+            Symmetry::Horizontal => "Horizontal",
+            Symmetry::Rotational => "Rotational",
+        }
+    }
+
+    /// Mirror image of a cell.
+    pub fn mirror_cell(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Symmetry::None => (x, y),
+            Symmetry::Horizontal => (width - 1 - x, y),
+            Symmetry::Rotational => (width - 1 - x, height - 1 - y),
+        }
+    }
+
+    /// Mirror image of a continuous position in pixel units.
+    ///
+    /// Consistent with [`mirror_cell`](Self::mirror_cell) for cell coordinates.
+    pub fn mirror_point(&self, x: f64, y: f64, width: usize, height: usize) -> (f64, f64) {
+        let (w, h) = ((width - 1) as f64, (height - 1) as f64);
+        match self {
+            Symmetry::None => (x, y),
+            Symmetry::Horizontal => (w - x, y),
+            Symmetry::Rotational => (w - x, h - y),
+        }
+    }
+
+    /// Whether a cell lies in the source half (cells on the axis count).
+    pub fn is_source(&self, x: usize, y: usize, width: usize, height: usize) -> bool {
+        let (mx, my) = self.mirror_cell(x, y, width, height);
+        (y, x) <= (my, mx)
+    }
+
+    /// Copy the source half of a row-major field onto its mirror image.
+    pub fn apply<T: Copy>(&self, field: &mut [T], width: usize, height: usize) {
+        if *self == Symmetry::None {
+            return;
+        }
+        for y in 0..height {
+            for x in 0..width {
+                if !self.is_source(x, y, width, height) {
+                    let (sx, sy) = self.mirror_cell(x, y, width, height);
+                    field[y * width + x] = field[sy * width + sx];
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applied_fields_are_symmetric() {
+        let (w, h) = (7, 4);
+        for &symmetry in Symmetry::all() {
+            let mut field: Vec<usize> = (0..w * h).collect();
+            symmetry.apply(&mut field, w, h);
+            for y in 0..h {
+                for x in 0..w {
+                    let (mx, my) = symmetry.mirror_cell(x, y, w, h);
+                    assert_eq!(field[y * w + x], field[my * w + mx], "{:?}", symmetry);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Hand-sculpted continentalness.
+//!
+//! The terrain brush raises, lowers, smooths or flattens the land. Its work
+//! is kept as an offset to the generated continentalness, stored only for
+//! the [`TERRAIN_EDIT_CHUNK`]-sized blocks that were painted. The offsets are
+//! added after erosion, so what was sculpted is what the map shows, and meso
+//! and micro tiles pick them up through their
+//! [`NormalizationContext`](crate::NormalizationContext) baseline.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::precision::LayerValue;
+
+/// Cells along each side of an edit block: the ground one meso tile covers.
+pub const TERRAIN_EDIT_CHUNK: usize = 64;
+
+/// What a terrain brush dab does to the cells under it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TerrainBrushMode {
+    #[default]
+    Raise,
+    Lower,
+    /// Pull each cell toward the mean of its neighbours.
+    Smooth,
+    /// Pull each cell toward the height where the stroke started.
+    Flatten,
+}
+
+impl TerrainBrushMode {
+    pub fn all() -> &'static [Self] {
+        &[Self::Raise, Self::Lower, Self::Smooth, Self::Flatten]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Raise => "Raise",
+            Self::Lower => "Lower",
+            Self::Smooth => "Smooth",
+            Self::Flatten => "Flatten",
+        }
+    }
+}
+
+/// Shape and force of a terrain brush dab.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerrainBrush {
+    pub mode: TerrainBrushMode,
+    /// Radius in macro cells.
+    pub radius: f64,
+    /// How far one dab moves a cell at full weight: continentalness for
+    /// Raise and Lower, a fraction of the way to the target otherwise.
+    pub strength: f64,
+    /// Fraction of the radius, from the rim inward, over which the dab fades
+    /// out. 0 is a hard edge.
+    pub falloff: f64,
+}
+
+impl TerrainBrush {
+    /// Weight of a dab `distance` cells from its centre, 1 in the core down
+    /// to 0 at the rim.
+    pub fn weight(&self, distance: f64) -> f64 {
+        if distance >= self.radius {
+            return 0.0;
+        }
+        let core = self.radius * (1.0 - self.falloff.clamp(0.0, 1.0));
+        if distance <= core {
+            return 1.0;
+        }
+        let t = (distance - core) / (self.radius - core);
+        1.0 - t * t * (3.0 - 2.0 * t)
+    }
+}
+
+/// Sparse continentalness offsets over the macro grid, by edit block.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TerrainEdits {
+    /// Offsets of each edited block, row-major and [`TERRAIN_EDIT_CHUNK`]
+    /// wide, keyed by block `(x, y)`. Unedited blocks are left out.
+    chunks: BTreeMap<(u32, u32), Vec<f32>>,
+}
+
+/// Block holding cell `(x, y)`, and the cell's index within it.
+fn split(x: usize, y: usize) -> ((u32, u32), usize) {
+    let chunk = ((x / TERRAIN_EDIT_CHUNK) as u32, (y / TERRAIN_EDIT_CHUNK) as u32);
+    let local = (y % TERRAIN_EDIT_CHUNK) * TERRAIN_EDIT_CHUNK + x % TERRAIN_EDIT_CHUNK;
+    (chunk, local)
+}
+
+impl TerrainEdits {
+    /// Whether nothing has been sculpted.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Number of edited blocks.
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Offset of cell `(x, y)`.
+    pub fn offset(&self, x: usize, y: usize) -> f64 {
+        let (chunk, local) = split(x, y);
+        self.chunks.get(&chunk).map_or(0.0, |offsets| f64::from(offsets[local]))
+    }
+
+    fn add_offset(&mut self, x: usize, y: usize, delta: f64) {
+        let (chunk, local) = split(x, y);
+        let offsets = self
+            .chunks
+            .entry(chunk)
+            .or_insert_with(|| vec![0.0; TERRAIN_EDIT_CHUNK * TERRAIN_EDIT_CHUNK]);
+        offsets[local] += delta as f32;
+    }
+
+    /// Add the offsets to a `width` × `height` continentalness field, keeping
+    /// it within [-1, 1].
+    pub fn apply(&self, continentalness: &mut [f64], width: usize, height: usize) {
+        for (&(chunk_x, chunk_y), offsets) in &self.chunks {
+            let (x0, y0) = (chunk_x as usize * TERRAIN_EDIT_CHUNK, chunk_y as usize * TERRAIN_EDIT_CHUNK);
+            for y in y0..(y0 + TERRAIN_EDIT_CHUNK).min(height) {
+                for x in x0..(x0 + TERRAIN_EDIT_CHUNK).min(width) {
+                    let offset = f64::from(offsets[(y - y0) * TERRAIN_EDIT_CHUNK + x - x0]);
+                    let cont = &mut continentalness[y * width + x];
+                    *cont = (*cont + offset).clamp(-1.0, 1.0);
+                }
+            }
+        }
+    }
+
+    /// Sculpt one dab centred on cell `(cx, cy)` of a `width` × `height`
+    /// map whose current continentalness is `field`, already including these
+    /// edits. The offsets are recorded and `field` is updated to match, so
+    /// the map can be redrawn before it is regenerated.
+    ///
+    /// `target` is the height Flatten pulls toward. Returns the bounding
+    /// rect `(x0, y0, x1, y1)` of the cells changed, exclusive on the far
+    /// edges, or `None` if the dab missed the map.
+    pub fn dab<T: LayerValue>(
+        &mut self,
+        field: &mut [T],
+        width: usize,
+        height: usize,
+        (cx, cy): (f64, f64),
+        brush: &TerrainBrush,
+        target: f64,
+    ) -> Option<(usize, usize, usize, usize)> {
+        let span = |centre: f64, len: usize| {
+            let first = (centre - brush.radius).ceil().max(0.0) as usize;
+            let last = ((centre + brush.radius).floor() + 1.0).clamp(0.0, len as f64) as usize;
+            (first, last)
+        };
+        let ((x0, x1), (y0, y1)) = (span(cx, width), span(cy, height));
+        if x0 >= x1 || y0 >= y1 {
+            return None;
+        }
+
+        let at = |x: usize, y: usize| field[y * width + x].to_f64();
+        let mut changes = Vec::new();
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let weight = brush.weight(((x as f64 - cx).powi(2) + (y as f64 - cy).powi(2)).sqrt());
+                if weight <= 0.0 {
+                    continue;
+                }
+                let current = at(x, y);
+                let delta = match brush.mode {
+                    TerrainBrushMode::Raise => brush.strength * weight,
+                    TerrainBrushMode::Lower => -brush.strength * weight,
+                    TerrainBrushMode::Smooth => {
+                        let (mut sum, mut count) = (0.0, 0.0);
+                        for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                            for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                                sum += at(nx, ny);
+                                count += 1.0;
+                            }
+                        }
+                        (sum / count - current) * brush.strength.min(1.0) * weight
+                    }
+                    TerrainBrushMode::Flatten => (target - current) * brush.strength.min(1.0) * weight,
+                };
+                // Only what still fits in range is kept, so pushing past the
+                // limit does not bank offset that has to be painted back off
+                let delta = (current + delta).clamp(-1.0, 1.0) - current;
+                changes.push((x, y, delta));
+            }
+        }
+
+        for &(x, y, delta) in &changes {
+            self.add_offset(x, y, delta);
+            let cell = &mut field[y * width + x];
+            *cell = T::from_f64(cell.to_f64() + delta);
+        }
+        Some((x0, y0, x1, y1))
+    }
+
+    /// Blocks whose offsets differ between `self` and `other`.
+    pub fn changed_chunks(&self, other: &Self) -> BTreeSet<(u32, u32)> {
+        let keys = self.chunks.keys().chain(other.chunks.keys());
+        keys.filter(|&key| self.chunks.get(key) != other.chunks.get(key))
+            .copied()
+            .collect()
+    }
+
+    /// Just the blocks in `chunks`, e.g. to keep for undoing a stroke.
+    pub fn subset(&self, chunks: &BTreeSet<(u32, u32)>) -> Self {
+        Self {
+            chunks: chunks
+                .iter()
+                .filter_map(|key| Some((*key, self.chunks.get(key)?.clone())))
+                .collect(),
+        }
+    }
+
+    /// Replace the blocks in `chunks` with those of `from`, dropping any it
+    /// doesn't have.
+    pub fn splice(&mut self, from: &Self, chunks: &BTreeSet<(u32, u32)>) {
+        for key in chunks {
+            match from.chunks.get(key) {
+                Some(offsets) => self.chunks.insert(*key, offsets.clone()),
+                None => self.chunks.remove(key),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dabs_record_offsets_that_replay_onto_the_field() {
+        let (w, h) = (96, 48);
+        let generated = vec![0.0; w * h];
+        let mut field = generated.clone();
+        let mut edits = TerrainEdits::default();
+        let brush = TerrainBrush {
+            mode: TerrainBrushMode::Raise,
+            radius: 6.0,
+            strength: 0.25,
+            falloff: 0.5,
+        };
+
+        // A dab straddling the first two blocks
+        let bounds = edits.dab(&mut field, w, h, (64.0, 10.0), &brush, 0.0).unwrap();
+        assert_eq!(bounds, (58, 4, 71, 17));
+        assert_eq!(field[10 * w + 64], 0.25);
+        assert!(field[10 * w + 68] > 0.0 && field[10 * w + 68] < 0.25);
+        assert_eq!(field[10 * w + 70], 0.0);
+        let touched = edits.changed_chunks(&TerrainEdits::default());
+        assert_eq!(touched, BTreeSet::from([(0, 0), (1, 0)]));
+
+        // Regenerating and applying the layer gives back the sculpted field
+        let mut regenerated = generated.clone();
+        edits.apply(&mut regenerated, w, h);
+        assert!(regenerated.iter().zip(&field).all(|(a, b)| (a - b).abs() < 1e-6));
+
+        // Flatten pulls toward the target, smooth toward the neighbours
+        let before = edits.clone();
+        let flatten = TerrainBrush { mode: TerrainBrushMode::Flatten, strength: 1.0, falloff: 0.0, ..brush };
+        edits.dab(&mut field, w, h, (64.0, 10.0), &flatten, -0.5);
+        assert!((field[10 * w + 64] + 0.5).abs() < 1e-6);
+        let smooth = TerrainBrush { mode: TerrainBrushMode::Smooth, radius: 12.0, ..flatten };
+        edits.dab(&mut field, w, h, (64.0, 10.0), &smooth, 0.0);
+        assert!(field[10 * w + 69] > -0.5 && field[10 * w + 70] < 0.0);
+
+        // Undoing restores just the touched blocks
+        let mut undone = edits.clone();
+        undone.splice(&before.subset(&touched), &touched);
+        assert_eq!(undone, before);
+
+        // Offsets stop at the edge of the range and the map
+        let lower = TerrainBrush { mode: TerrainBrushMode::Lower, strength: 5.0, ..brush };
+        edits.dab(&mut field, w, h, (0.0, 0.0), &lower, 0.0);
+        assert_eq!(field[0], -1.0);
+        assert_eq!(edits.offset(0, 0), -1.0);
+        assert!(edits.dab(&mut field, w, h, (-20.0, 10.0), &brush, 0.0).is_none());
+    }
+}
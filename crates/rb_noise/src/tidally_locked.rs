@@ -1,6 +1,24 @@
+use std::f64::consts::TAU;
+
 use noise::{NoiseFn, OpenSimplex};
 use rb_core::NoiseStrategy;
 
+use crate::strategy::FbmParams;
+
+/// Furthest the climate zones swing over a year, as a fraction of map height.
+pub const LIBRATION_AMPLITUDE: f64 = 0.04;
+
+/// How far libration has pushed the sun side toward the dark edge at
+/// `season`, a fraction of the orbit in [0.0, 1.0), as a fraction of map
+/// height. Zero at the start of the orbit and halfway round.
+///
+/// A tidally locked planet on a slightly eccentric orbit rocks back and
+/// forth under its star, so the twilight band creeps toward the dark side
+/// for half the year and back toward the sun for the other half.
+pub fn libration(season: f64) -> f64 {
+    LIBRATION_AMPLITUDE * (season * TAU).sin()
+}
+
 /// Temperature strategy for a tidally locked planet.
 ///
 /// The world has three distinct zones:
@@ -9,13 +27,23 @@ use rb_core::NoiseStrategy;
 /// - **South (sun side)**: Scorching desert, perpetually facing the star
 ///
 /// Temperature uses a non-linear curve with reduced noise at extremes
-/// (frozen and scorched regions are more uniformly hostile).
+/// (frozen and scorched regions are more uniformly hostile). The terminator
+/// band defaults to the middle third of the map; see
+/// [`with_twilight`](Self::with_twilight).
+#[derive(Clone)]
 pub struct LatitudeTemperatureStrategy {
     noise: OpenSimplex,
     octaves: u32,
     persistence: f64,
     lacunarity: f64,
+    /// Base frequency, and multiplier on the normalized fBm.
+    frequency: f64,
+    amplitude: f64,
     map_height: f64,
+    /// Habitable band as fractions of map height: (start, end).
+    twilight: (f64, f64),
+    /// Seasonal shift of every zone, as a fraction of map height.
+    libration: f64,
 }
 
 impl LatitudeTemperatureStrategy {
@@ -25,10 +53,40 @@ impl LatitudeTemperatureStrategy {
             octaves: 8,
             persistence: 0.59,
             lacunarity: 2.0,
+            frequency: 1.0,
+            amplitude: 1.0,
             map_height,
+            twilight: (0.33, 0.66),
+            libration: 0.0,
         }
     }
 
+    /// Reshape the local-variation fBm with `params`.
+    pub fn with_fbm(mut self, params: &FbmParams) -> Self {
+        self.frequency /= params.scale;
+        self.octaves = params.octaves;
+        self.persistence = params.persistence;
+        self.lacunarity = params.lacunarity;
+        self.amplitude = params.amplitude;
+        self
+    }
+
+    /// Climate at `season` through the year; see [`libration`].
+    pub fn with_season(mut self, season: f64) -> Self {
+        self.libration = libration(season);
+        self
+    }
+
+    /// Place the habitable twilight band `width` world units wide, centred
+    /// `center` units from the dark edge. Narrow bands push the frozen and
+    /// scorched zones toward the middle of the map.
+    pub fn with_twilight(mut self, center: f64, width: f64) -> Self {
+        let half = (width / 2.0).max(1.0) / self.map_height;
+        let center = (center / self.map_height).clamp(half, 1.0 - half);
+        self.twilight = (center - half, center + half);
+        self
+    }
+
     pub fn default_for_map(seed: u32) -> Self {
         Self::new(seed, 512.0)
     }
@@ -37,26 +95,30 @@ impl LatitudeTemperatureStrategy {
     fn fbm(&self, x: f64, y: f64, detail_level: u32) -> f64 {
         let mut value = 0.0;
         let mut amplitude = 1.0;
-        let mut frequency = 1.0;
+        let mut frequency = self.frequency;
         let mut max_amplitude = 0.0;
 
         let total_octaves = self.octaves + detail_level;
 
-        for _ in 0..total_octaves {
+        for octave in 0..total_octaves {
             let nx = x * frequency * 0.01;
             let ny = y * frequency * 0.01;
             value += self.noise.get([nx, ny]) * amplitude;
-            max_amplitude += amplitude;
+            if octave < self.octaves {
+                max_amplitude += amplitude;
+            }
             amplitude *= self.persistence;
             frequency *= self.lacunarity;
         }
 
-        value / max_amplitude
+        value / max_amplitude * self.amplitude
     }
 }
 
-impl NoiseStrategy for LatitudeTemperatureStrategy {
-    fn generate(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+impl LatitudeTemperatureStrategy {
+    /// Effective latitude at a point: 0 = top (dark side), 1 = bottom (sun
+    /// side), shifted by noise so zone boundaries wave.
+    fn latitude(&self, x: f64, y: f64) -> f64 {
         // Get noise for boundary variation (use different coordinates for variety)
         let boundary_noise = self.fbm(x * 0.5, y * 0.3, 0);
 
@@ -64,33 +126,53 @@ impl NoiseStrategy for LatitudeTemperatureStrategy {
         // The noise shifts the boundary up/down by up to ~15% of map height
         let latitude_offset = boundary_noise * 0.15;
 
-        // Normalized position with noise offset: 0 = top (dark side), 1 = bottom (sun side)
-        let t = ((y / self.map_height) + latitude_offset).clamp(0.0, 1.0);
+        ((y / self.map_height) + latitude_offset + self.libration).clamp(0.0, 1.0)
+    }
 
+    /// Temperature curve over effective latitude `t`, before local noise.
+    fn zonal_curve(&self, t: f64) -> f64 {
         // Non-linear temperature curve for tidally locked planet:
-        // - Top third (0-0.33): Frozen, -80°C to -20°C
-        // - Middle third (0.33-0.66): Habitable, -10°C to +60°C
-        // - Bottom third (0.66-1.0): Scorching, +80°C to +150°C
-        let base_temp = if t < 0.33 {
+        // - Dark side (above the band): Frozen, -80°C to -20°C
+        // - Twilight band: Habitable, -10°C to +60°C
+        // - Sun side (below the band): Scorching, +80°C to +150°C
+        let (dusk, dawn) = self.twilight;
+        if t < dusk {
             // Dark side: frozen
-            let local_t = t / 0.33;
+            let local_t = t / dusk;
             -80.0 + local_t * 60.0  // -80 to -20
-        } else if t < 0.66 {
+        } else if t < dawn {
             // Terminator: habitable band
-            let local_t = (t - 0.33) / 0.33;
+            let local_t = (t - dusk) / (dawn - dusk);
             -10.0 + local_t * 70.0  // -10 to +60
         } else {
             // Sun side: scorching
-            let local_t = (t - 0.66) / 0.34;
+            let local_t = (t - dawn) / (1.0 - dawn);
             80.0 + local_t * 70.0   // +80 to +150
-        };
+        }
+    }
 
-        // Local noise variation for terrain detail
+    /// Large-scale temperature in °C: the latitude curve with its wavy zone
+    /// boundaries but without local noise. Smooth enough to take gradients
+    /// of, e.g. for wind.
+    pub fn zonal_temperature(&self, x: f64, y: f64) -> f64 {
+        self.zonal_curve(self.latitude(x, y))
+    }
+}
+
+impl NoiseStrategy for LatitudeTemperatureStrategy {
+    fn generate(&self, x: f64, y: f64, detail_level: u32) -> f64 {
+        let t = self.latitude(x, y);
+        let base_temp = self.zonal_curve(t);
+        let (dusk, dawn) = self.twilight;
+
+        // Local noise variation for terrain detail. The transition zones
+        // reach 40% of the band's width beyond each edge.
         let local_noise = self.fbm(x, y, detail_level);
-        let noise_scale = if t < 0.2 || t > 0.8 {
+        let margin = (dawn - dusk) * 0.4;
+        let noise_scale = if !(dusk - margin..=dawn + margin).contains(&t) {
             // Extreme zones: less variation
             25.0
-        } else if t < 0.33 || t > 0.66 {
+        } else if !(dusk..=dawn).contains(&t) {
             // Transition zones: moderate variation
             40.0
         } else {
@@ -104,6 +186,16 @@ impl NoiseStrategy for LatitudeTemperatureStrategy {
     fn name(&self) -> &'static str {
         "TidallyLockedTemperature"
     }
+
+    fn output_range(&self) -> (f64, f64) {
+        // The -80..150 curve plus local noise, with headroom for the
+        // transition zones' wider swing
+        (-130.0, 200.0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Temperature in °C across the dark side, twilight band and sun side"
+    }
 }
 
 // Alias for backwards compatibility
@@ -129,6 +221,23 @@ mod tests {
         assert!(temp > 100.0, "Sun side temp {} should be scorching", temp);
     }
 
+    #[test]
+    fn narrow_twilight_band_freezes_more_of_the_map() {
+        let default = LatitudeTemperatureStrategy::default_for_map(42);
+        let narrow = LatitudeTemperatureStrategy::default_for_map(42).with_twilight(256.0, 64.0);
+        // Just north of the narrow band, but inside the default one
+        let (x, y) = (512.0, 200.0);
+        assert!(narrow.generate(x, y, 0) < default.generate(x, y, 0));
+    }
+
+    #[test]
+    fn libration_warms_and_cools_the_band() {
+        let strategy = LatitudeTemperatureStrategy::default_for_map(42);
+        let at = |season: f64| strategy.clone().with_season(season).generate(512.0, 256.0, 0);
+        assert!(at(0.25) > at(0.0) && at(0.0) > at(0.75));
+        assert!((at(0.5) - at(0.0)).abs() < 1e-9);
+    }
+
     #[test]
     fn terminator_is_habitable() {
         let strategy = LatitudeTemperatureStrategy::default_for_map(42);
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Wall-clock timing of a single generation phase.
+#[derive(Clone, Debug)]
+pub struct PhaseTiming {
+    /// Phase name for display.
+    pub name: &'static str,
+    /// Milliseconds from the start of generation to the start of this phase.
+    pub start_ms: f64,
+    /// Milliseconds spent in this phase.
+    pub duration_ms: f64,
+}
+
+/// Per-phase timings for one world generation run.
+///
+/// Phases are recorded in the order they start. Meso tiles run in parallel, so
+/// their per-tile durations are kept separately from the wall-clock phase.
+#[derive(Resource, Clone, Debug)]
+pub struct GenerationTimings {
+    origin: Instant,
+    phases: Vec<PhaseTiming>,
+    tile_ms: Vec<f64>,
+}
+
+impl GenerationTimings {
+    /// Start timing a generation run from now.
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            phases: Vec::new(),
+            tile_ms: Vec::new(),
+        }
+    }
+
+    /// Run `f` and record how long it took as a phase.
+    pub fn time<T>(&mut self, name: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(name, start, start.elapsed());
+        result
+    }
+
+    /// Record a phase that started at `start` and ran for `duration`.
+    /// Used for phases that finish asynchronously.
+    pub fn record(&mut self, name: &'static str, start: Instant, duration: Duration) {
+        let timing = PhaseTiming {
+            name,
+            start_ms: start.saturating_duration_since(self.origin).as_secs_f64() * 1000.0,
+            duration_ms: duration.as_secs_f64() * 1000.0,
+        };
+        // Keep phases ordered by start even if recorded out of order
+        let pos = self.phases.partition_point(|p| p.start_ms <= timing.start_ms);
+        self.phases.insert(pos, timing);
+    }
+
+    /// Record the duration of one meso tile.
+    pub fn record_tile(&mut self, duration_ms: f64) {
+        self.tile_ms.push(duration_ms);
+    }
+
+    /// Recorded phases, ordered by start time.
+    pub fn phases(&self) -> &[PhaseTiming] {
+        &self.phases
+    }
+
+    /// Per-tile durations in milliseconds, in completion order.
+    pub fn tile_ms(&self) -> &[f64] {
+        &self.tile_ms
+    }
+
+    /// Milliseconds from the start of generation to the end of the last phase.
+    pub fn total_ms(&self) -> f64 {
+        self.phases
+            .iter()
+            .map(|p| p.start_ms + p.duration_ms)
+            .fold(0.0, f64::max)
+    }
+}
+
+impl Default for GenerationTimings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for GenerationTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Generation timings:")?;
+        for phase in &self.phases {
+            writeln!(f, "  {:<16} {:>10.1} ms", phase.name, phase.duration_ms)?;
+        }
+        if !self.tile_ms.is_empty() {
+            let mean = self.tile_ms.iter().sum::<f64>() / self.tile_ms.len() as f64;
+            let max = self.tile_ms.iter().cloned().fold(0.0, f64::max);
+            writeln!(
+                f,
+                "  {} tiles: mean {:.1} ms, max {:.1} ms",
+                self.tile_ms.len(),
+                mean,
+                max
+            )?;
+        }
+        write!(f, "  {:<16} {:>10.1} ms", "Total", self.total_ms())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_are_non_negative_and_ordered() {
+        let mut timings = GenerationTimings::new();
+        timings.time("Macro noise", || std::thread::sleep(Duration::from_millis(2)));
+        let meso_start = Instant::now();
+        timings.time("Civilization", || ());
+        timings.record("Meso tiles", meso_start, meso_start.elapsed());
+
+        let phases = timings.phases();
+        assert_eq!(phases.len(), 3);
+        for phase in phases {
+            assert!(phase.start_ms >= 0.0 && phase.duration_ms >= 0.0);
+        }
+        for pair in phases.windows(2) {
+            assert!(pair[0].start_ms <= pair[1].start_ms);
+        }
+        assert!(timings.total_ms() >= phases[0].duration_ms);
+    }
+}
@@ -1,7 +1,14 @@
 use rb_core::ResourceType;
+use serde::{Deserialize, Serialize};
+
+use crate::derived::rainfall::MAX_RAINFALL_MM;
 
 /// Noise layers that can be visualized in the map view.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+///
+/// Serialized as its [`id`](NoiseLayer::id), so saved state survives
+/// reordering the variants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(into = "u16", try_from = "u16")]
 pub enum NoiseLayer {
     #[default]
     Aggregate,
@@ -12,6 +19,10 @@ pub enum NoiseLayer {
     PeaksValleys,
     Humidity,
     Rivers,
+    Rainfall,
+    FlowAccumulation,
+    Climate,
+    Caves,
     // Resource layers
     ResourceIron,
     ResourceGold,
@@ -39,6 +50,10 @@ impl NoiseLayer {
             Self::PeaksValleys,
             Self::Humidity,
             Self::Rivers,
+            Self::Rainfall,
+            Self::FlowAccumulation,
+            Self::Climate,
+            Self::Caves,
             Self::ResourceIron,
             Self::ResourceGold,
             Self::ResourceCopper,
@@ -54,6 +69,43 @@ impl NoiseLayer {
         ]
     }
 
+    /// Stable numeric id for saving and config files. Never reuse or change
+    /// an id; new layers take the next free one. Resource layers start at
+    /// 100 so base layers can grow without colliding.
+    pub const fn id(&self) -> u16 {
+        match self {
+            Self::Aggregate => 0,
+            Self::Continentalness => 1,
+            Self::Temperature => 2,
+            Self::Tectonic => 3,
+            Self::Erosion => 4,
+            Self::PeaksValleys => 5,
+            Self::Humidity => 6,
+            Self::Rivers => 7,
+            Self::Rainfall => 8,
+            Self::FlowAccumulation => 9,
+            Self::Climate => 10,
+            Self::Caves => 11,
+            Self::ResourceIron => 100,
+            Self::ResourceGold => 101,
+            Self::ResourceCopper => 102,
+            Self::ResourceSilver => 103,
+            Self::ResourceGems => 104,
+            Self::ResourceCoal => 105,
+            Self::ResourceStone => 106,
+            Self::ResourceSalt => 107,
+            Self::ResourceTimber => 108,
+            Self::ResourceFish => 109,
+            Self::ResourceFertileSoil => 110,
+            Self::ResourceWildGame => 111,
+        }
+    }
+
+    /// Layer with the given [`id`](NoiseLayer::id), if any.
+    pub fn from_id(id: u16) -> Option<Self> {
+        Self::all().iter().copied().find(|layer| layer.id() == id)
+    }
+
     /// Returns the display name for this layer.
     pub fn name(&self) -> &'static str {
         match self {
@@ -65,6 +117,10 @@ impl NoiseLayer {
             Self::PeaksValleys => "Peaks & Valleys",
             Self::Humidity => "Humidity",
             Self::Rivers => "Rivers",
+            Self::Rainfall => "Rainfall",
+            Self::FlowAccumulation => "Flow Accumulation",
+            Self::Climate => "Climate Zones",
+            Self::Caves => "Caves",
             Self::ResourceIron => "Iron Deposits",
             Self::ResourceGold => "Gold Deposits",
             Self::ResourceCopper => "Copper Deposits",
@@ -137,7 +193,21 @@ impl NoiseLayer {
     }
 }
 
-/// Color conversion utilities for visualization.
+impl From<NoiseLayer> for u16 {
+    fn from(layer: NoiseLayer) -> Self {
+        layer.id()
+    }
+}
+
+impl TryFrom<u16> for NoiseLayer {
+    type Error = String;
+
+    fn try_from(id: u16) -> Result<Self, Self::Error> {
+        Self::from_id(id).ok_or_else(|| format!("unknown noise layer id {}", id))
+    }
+}
+
+// Color conversion utilities for visualization.
 
 /// Convert a grayscale value to RGBA.
 pub fn grayscale_to_rgba(value: f64, min: f64, max: f64) -> [u8; 4] {
@@ -174,7 +244,7 @@ pub fn peaks_to_rgba(value: f64) -> [u8; 4] {
     // value in [-1, 1]
     if value < 0.0 {
         // Valley - blue tint
-        let intensity = (1.0 + value) as f64; // 0 at -1, 1 at 0
+        let intensity = 1.0 + value; // 0 at -1, 1 at 0
         let b = 255;
         let rg = (intensity * 200.0) as u8;
         [rg, rg, b, 255]
@@ -218,6 +288,57 @@ pub fn river_to_rgba(flow: f64) -> [u8; 4] {
     }
 }
 
+/// Convert annual rainfall (mm) to RGBA.
+/// Pale yellow-green (arid) through green to deep blue (very wet).
+pub fn rainfall_to_rgba(mm: f64) -> [u8; 4] {
+    let t = (mm / MAX_RAINFALL_MM).clamp(0.0, 1.0);
+    if t < 0.5 {
+        // Arid to lush - pale yellow-green to green
+        let s = t * 2.0;
+        let r = (230.0 - s * 190.0) as u8;
+        let g = (230.0 - s * 70.0) as u8;
+        let b = (150.0 - s * 90.0) as u8;
+        [r, g, b, 255]
+    } else {
+        // Lush to saturated - green to deep blue
+        let s = (t - 0.5) * 2.0;
+        let r = (40.0 - s * 30.0) as u8;
+        let g = (160.0 - s * 110.0) as u8;
+        let b = (60.0 + s * 140.0) as u8;
+        [r, g, b, 255]
+    }
+}
+
+/// Convert log-normalized flow accumulation (0-1) to RGBA.
+/// Every land cell carries some flow, so ridges read dark blue-grey and
+/// drainage lines brighten towards white.
+pub fn flow_to_rgba(flow: f64) -> [u8; 4] {
+    let t = flow.clamp(0.0, 1.0);
+    let r = (20.0 + t * 200.0) as u8;
+    let g = (30.0 + t * 215.0) as u8;
+    let b = (50.0 + t * 205.0) as u8;
+    [r, g, b, 255]
+}
+
+/// Convert cave density (0-1) to RGBA.
+/// Solid rock is dark grey-brown; open caves glow amber, walls in between.
+pub fn cave_to_rgba(density: f64) -> [u8; 4] {
+    let t = density.clamp(0.0, 1.0);
+    let r = (45.0 + t * 200.0) as u8;
+    let g = (40.0 + t * 140.0) as u8;
+    let b = (35.0 + t * 35.0) as u8;
+    [r, g, b, 255]
+}
+
+/// Legend stops for the rainfall layer: (mm per year, label).
+pub const RAINFALL_LEGEND: [(f64, &str); 5] = [
+    (0.0, "0 mm"),
+    (750.0, "750 mm"),
+    (1500.0, "1500 mm"),
+    (2250.0, "2250 mm"),
+    (MAX_RAINFALL_MM, "3000+ mm"),
+];
+
 /// Convert resource abundance to RGBA.
 pub fn resource_to_rgba(abundance: f64, resource: ResourceType) -> [u8; 4] {
     if abundance < 0.01 {
@@ -249,6 +370,15 @@ mod tests {
         assert_eq!(names.len(), original_len, "Duplicate layer names found");
     }
 
+    #[test]
+    fn layer_ids_round_trip() {
+        for &layer in NoiseLayer::all() {
+            assert_eq!(NoiseLayer::from_id(layer.id()), Some(layer));
+        }
+        assert_eq!(NoiseLayer::from_id(99), None);
+        assert!(NoiseLayer::try_from(99).is_err());
+    }
+
     #[test]
     fn resource_layer_conversion() {
         for layer in NoiseLayer::all() {
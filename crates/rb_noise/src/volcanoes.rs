@@ -0,0 +1,237 @@
+//! Volcanoes: arcs along colliding plate boundaries and hotspots rising
+//! through plate interiors.
+//!
+//! Vents are picked from the finished terrain layers. Each one warms the
+//! climate around it, turns its crater to volcanic rock and leaves the land
+//! nearby rich in ash, so fertile soil gathers on its slopes.
+
+use rand::seq::SliceRandom;
+use rb_core::{rng_for, ResourceType, TileType};
+
+use crate::resource_map::ResourceMap;
+
+/// Candidate vents are sampled every N cells.
+const SAMPLE_STRIDE: usize = 4;
+/// Minimum distance between vents, in cells.
+pub const MIN_VOLCANO_DISTANCE: f64 = 24.0;
+/// A map gets at most one vent per this many cells.
+const CELLS_PER_VOLCANO: usize = 32_768;
+/// Plate uplift above which land can host an arc volcano.
+const ARC_UPLIFT: f64 = 0.4;
+/// Tectonic distance past which a cell is deep enough inside its plate to sit
+/// over a hotspot.
+const HOTSPOT_INTERIOR: f64 = 0.8;
+/// Radius of the crater, in cells.
+const VENT_RADIUS: f64 = 1.5;
+/// Radius over which a vent warms the air, and the warming at the vent (°C).
+const HEAT_RADIUS: f64 = 10.0;
+const VENT_HEAT: f64 = 12.0;
+/// Radius of the ash fall around a vent, and fertile soil abundance where it
+/// lies thickest.
+const ASH_RADIUS: f64 = 14.0;
+const ASH_FERTILITY: f64 = 0.8;
+
+/// What drives a volcano.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolcanoKind {
+    /// One of a chain along a convergent plate boundary.
+    Arc,
+    /// Fed by a mantle plume under the middle of a plate.
+    Hotspot,
+}
+
+impl VolcanoKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Arc => "Volcanic Arc",
+            Self::Hotspot => "Hotspot",
+        }
+    }
+}
+
+/// A volcanic vent on the macro map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Volcano {
+    pub x: usize,
+    pub y: usize,
+    pub kind: VolcanoKind,
+}
+
+impl Volcano {
+    /// Row-major indices of the cells within `radius` of the vent, with
+    /// their distance from it.
+    fn cells_within(
+        &self,
+        radius: f64,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = (usize, f64)> {
+        let reach = radius.ceil() as usize;
+        let (vx, vy) = (self.x, self.y);
+        let xs = vx.saturating_sub(reach)..(vx + reach + 1).min(width);
+        (vy.saturating_sub(reach)..(vy + reach + 1).min(height)).flat_map(move |y| {
+            xs.clone().filter_map(move |x| {
+                let d = (x as f64 - vx as f64).hypot(y as f64 - vy as f64);
+                (d <= radius).then_some((y * width + x, d))
+            })
+        })
+    }
+}
+
+/// Pick vents on land: arcs where colliding plates lift the crust, and
+/// hotspots far from any boundary.
+///
+/// Kinds take turns, two arcs to each hotspot, and vents keep
+/// [`MIN_VOLCANO_DISTANCE`] apart. The result depends only on the inputs
+/// and `seed`.
+pub(crate) fn place_volcanoes(
+    seed: u32,
+    width: usize,
+    height: usize,
+    continentalness: &[f64],
+    tectonic: &[f64],
+    uplift: &[f64],
+    sea_level: f64,
+) -> Vec<Volcano> {
+    const TURNS: [VolcanoKind; 3] = [VolcanoKind::Arc, VolcanoKind::Arc, VolcanoKind::Hotspot];
+
+    let mut arcs = Vec::new();
+    let mut hotspots = Vec::new();
+    for y in (0..height).step_by(SAMPLE_STRIDE) {
+        for x in (0..width).step_by(SAMPLE_STRIDE) {
+            let idx = y * width + x;
+            if continentalness[idx] < sea_level {
+                continue;
+            }
+            if uplift[idx] > ARC_UPLIFT {
+                arcs.push((x, y));
+            } else if tectonic[idx] > HOTSPOT_INTERIOR {
+                hotspots.push((x, y));
+            }
+        }
+    }
+    let mut rng = rng_for(seed, "volcanoes");
+    arcs.shuffle(&mut rng);
+    hotspots.shuffle(&mut rng);
+
+    let max_volcanoes = (width * height / CELLS_PER_VOLCANO).max(1);
+    let mut volcanoes: Vec<Volcano> = Vec::new();
+    let mut turn = 0;
+    while volcanoes.len() < max_volcanoes && !(arcs.is_empty() && hotspots.is_empty()) {
+        let kind = TURNS[turn % TURNS.len()];
+        turn += 1;
+        let candidates = match kind {
+            VolcanoKind::Arc => &mut arcs,
+            VolcanoKind::Hotspot => &mut hotspots,
+        };
+
+        // Take the next candidate that respects spacing
+        while let Some((x, y)) = candidates.pop() {
+            let clear = volcanoes.iter().all(|v| {
+                (v.x as f64 - x as f64).hypot(v.y as f64 - y as f64) >= MIN_VOLCANO_DISTANCE
+            });
+            if clear {
+                volcanoes.push(Volcano { x, y, kind });
+                break;
+            }
+        }
+    }
+    volcanoes
+}
+
+/// Warm the air around each vent, most at the vent itself.
+pub(crate) fn warm_climate(
+    volcanoes: &[Volcano],
+    temperature: &mut [f64],
+    width: usize,
+    height: usize,
+) {
+    for volcano in volcanoes {
+        for (idx, d) in volcano.cells_within(HEAT_RADIUS, width, height) {
+            let falloff = 1.0 - d / HEAT_RADIUS;
+            temperature[idx] += VENT_HEAT * falloff * falloff;
+        }
+    }
+}
+
+/// Turn each vent's crater into volcanic rock, leaving the sea alone.
+pub(crate) fn mark_craters(
+    volcanoes: &[Volcano],
+    biomes: &mut [TileType],
+    width: usize,
+    height: usize,
+) {
+    for volcano in volcanoes {
+        for (idx, _) in volcano.cells_within(VENT_RADIUS, width, height) {
+            if !biomes[idx].is_ocean() {
+                biomes[idx] = TileType::Volcanic;
+            }
+        }
+    }
+}
+
+/// Lay fertile ash soil around each vent, thinning with distance. Craters
+/// and water get none, and richer soil already there is kept.
+pub(crate) fn spread_ash(
+    volcanoes: &[Volcano],
+    resources: &mut ResourceMap,
+    biomes: &[TileType],
+    width: usize,
+    height: usize,
+) {
+    for volcano in volcanoes {
+        for (idx, d) in volcano.cells_within(ASH_RADIUS, width, height) {
+            let biome = biomes[idx];
+            if biome.is_ocean() || matches!(biome, TileType::Volcanic | TileType::Lake) {
+                continue;
+            }
+            let (x, y) = (idx % width, idx / width);
+            let ash = (ASH_FERTILITY * (1.0 - d / ASH_RADIUS)) as f32;
+            if ash > resources.get(x, y, ResourceType::FertileSoil) {
+                resources.set(x, y, ResourceType::FertileSoil, ash);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biome_map::{BiomeMap, GenerationConfig};
+    use crate::precision::LayerValue;
+
+    #[test]
+    fn volcanoes_follow_the_plates_and_enrich_their_slopes() {
+        let config = GenerationConfig {
+            volcanoes: true,
+            ..Default::default()
+        };
+        let plain = BiomeMap::generate(42, 512, 256);
+        let map = BiomeMap::generate_with_config(42, 512, 256, &config);
+        assert!(!map.volcanoes.is_empty());
+        assert!(plain.volcanoes.is_empty());
+
+        for (i, volcano) in map.volcanoes.iter().enumerate() {
+            let idx = volcano.y * map.width + volcano.x;
+            match volcano.kind {
+                VolcanoKind::Arc => assert!(map.uplift[idx].to_f64() > ARC_UPLIFT),
+                VolcanoKind::Hotspot => assert!(map.tectonic[idx].to_f64() > HOTSPOT_INTERIOR),
+            }
+            assert_eq!(map.biomes[idx], TileType::Volcanic);
+            let heat = map.temperature[idx].to_f64() - plain.temperature[idx].to_f64();
+            assert!((heat - VENT_HEAT).abs() < 1e-3, "vent only {:.2}°C warmer", heat);
+
+            // Just past the crater the ash lies thick
+            let (x, y) = (volcano.x + 3, volcano.y);
+            let slope = map.get_biome(x, y);
+            if slope.is_some_and(|b| !b.is_ocean() && b != TileType::Lake) {
+                assert!(map.resources.get(x, y, ResourceType::FertileSoil) > 0.5);
+            }
+
+            for other in &map.volcanoes[i + 1..] {
+                let d = (volcano.x as f64 - other.x as f64).hypot(volcano.y as f64 - other.y as f64);
+                assert!(d >= MIN_VOLCANO_DISTANCE);
+            }
+        }
+    }
+}
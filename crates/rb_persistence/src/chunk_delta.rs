@@ -0,0 +1,232 @@
+//! Hand edits to chunks, stored apart from the world.
+//!
+//! Chunk contents are regenerated from the world seed, so only what was
+//! authored on top needs saving: painted tiles and placed buildings, keyed by
+//! chunk. Applying a [`ChunkDelta`] to freshly generated chunks reproduces
+//! the edited state. It lives in its own file next to the world save
+//! ([`chunk_delta_path`]), so the world can be regenerated or re-saved
+//! without losing the edits.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rb_core::{ChunkCoord, TileCoord};
+use serde::{Deserialize, Serialize};
+
+use crate::world_io::{file_stem, WorldIoError, WORLDS_DIR};
+
+/// Tiles along each side of a chunk.
+pub const CHUNK_TILES: i32 = 64;
+
+/// Suffix of chunk delta files, after the world's file stem.
+const CHUNK_DELTA_SUFFIX: &str = ".chunks.ron";
+
+/// Buildings the chunk editor can place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BuildingKind {
+    House,
+    Shop,
+    Tavern,
+    Tower,
+}
+
+impl BuildingKind {
+    pub fn all() -> &'static [Self] {
+        &[Self::House, Self::Shop, Self::Tavern, Self::Tower]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::House => "House",
+            Self::Shop => "Shop",
+            Self::Tavern => "Tavern",
+            Self::Tower => "Tower",
+        }
+    }
+}
+
+/// A building placed in a chunk, anchored at a tile local to the chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlacedBuilding {
+    pub kind: BuildingKind,
+    pub x: u8,
+    pub y: u8,
+}
+
+/// Edits to one chunk. Positions are local to the chunk, `0..CHUNK_TILES`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkEdits {
+    /// Painted tiles by `(x, y)`, as indices into the tile palette.
+    pub tiles: BTreeMap<(u8, u8), u16>,
+    /// Placed buildings, at most one per anchor tile.
+    pub buildings: Vec<PlacedBuilding>,
+}
+
+impl ChunkEdits {
+    /// Whether nothing was edited.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty() && self.buildings.is_empty()
+    }
+
+    /// Overwrite painted tiles in a generated chunk's row-major tile ids,
+    /// `CHUNK_TILES` wide.
+    pub fn apply(&self, tiles: &mut [u16]) {
+        for (&(x, y), &tile) in &self.tiles {
+            let index = y as usize * CHUNK_TILES as usize + x as usize;
+            if let Some(slot) = tiles.get_mut(index) {
+                *slot = tile;
+            }
+        }
+    }
+}
+
+/// Per-chunk edits of a world, saved separately from its definition.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkDelta {
+    /// Seed of the world the edits were made on. Edits on top of another
+    /// seed's terrain would no longer line up.
+    pub seed: u32,
+    /// Edits by chunk. Chunks without edits are left out.
+    pub chunks: BTreeMap<(i32, i32), ChunkEdits>,
+}
+
+/// Chunk holding `tile`, and the tile's position within it.
+fn split(tile: TileCoord) -> ((i32, i32), (u8, u8)) {
+    let chunk = (tile.x.div_euclid(CHUNK_TILES), tile.y.div_euclid(CHUNK_TILES));
+    let local = (tile.x.rem_euclid(CHUNK_TILES) as u8, tile.y.rem_euclid(CHUNK_TILES) as u8);
+    (chunk, local)
+}
+
+impl ChunkDelta {
+    /// An empty delta for the world with `seed`.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            chunks: BTreeMap::new(),
+        }
+    }
+
+    /// Whether no chunk was edited.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Edits to `coord`, if any.
+    pub fn chunk(&self, coord: ChunkCoord) -> Option<&ChunkEdits> {
+        self.chunks.get(&(coord.x, coord.y))
+    }
+
+    /// Paint the tile at the global position `tile` with palette index `id`.
+    pub fn paint_tile(&mut self, tile: TileCoord, id: u16) {
+        let (chunk, local) = split(tile);
+        self.chunks.entry(chunk).or_default().tiles.insert(local, id);
+    }
+
+    /// Place a building anchored at `tile`, replacing any already there.
+    pub fn place_building(&mut self, tile: TileCoord, kind: BuildingKind) {
+        let (chunk, (x, y)) = split(tile);
+        let buildings = &mut self.chunks.entry(chunk).or_default().buildings;
+        buildings.retain(|b| (b.x, b.y) != (x, y));
+        buildings.push(PlacedBuilding { kind, x, y });
+    }
+
+    /// Remove the building anchored at `tile`. Returns whether there was one.
+    pub fn remove_building(&mut self, tile: TileCoord) -> bool {
+        let (chunk, (x, y)) = split(tile);
+        let Some(edits) = self.chunks.get_mut(&chunk) else {
+            return false;
+        };
+        let before = edits.buildings.len();
+        edits.buildings.retain(|b| (b.x, b.y) != (x, y));
+        let removed = edits.buildings.len() != before;
+        if edits.is_empty() {
+            self.chunks.remove(&chunk);
+        }
+        removed
+    }
+
+    /// Drop every edit to `coord`, restoring its generated state.
+    pub fn reset_chunk(&mut self, coord: ChunkCoord) {
+        self.chunks.remove(&(coord.x, coord.y));
+    }
+}
+
+/// Path of a world's chunk delta, e.g. `worlds/my_world.chunks.ron`.
+pub fn chunk_delta_path(world_name: &str) -> PathBuf {
+    Path::new(WORLDS_DIR).join(format!("{}{}", file_stem(world_name), CHUNK_DELTA_SUFFIX))
+}
+
+/// Whether `path` names a chunk delta rather than a world save.
+pub(crate) fn is_chunk_delta(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(CHUNK_DELTA_SUFFIX))
+}
+
+/// Save chunk edits to a RON file.
+pub fn save_chunk_delta(path: &Path, delta: &ChunkDelta) -> Result<(), WorldIoError> {
+    let pretty_config = ron::ser::PrettyConfig::new().depth_limit(3);
+    fs::write(path, ron::ser::to_string_pretty(delta, pretty_config)?)?;
+    Ok(())
+}
+
+/// Load chunk edits for the world with `seed`. A missing file is an empty
+/// delta; one made for a different seed is refused.
+pub fn load_chunk_delta(path: &Path, seed: u32) -> Result<ChunkDelta, WorldIoError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ChunkDelta::new(seed)),
+        Err(e) => return Err(e.into()),
+    };
+    let delta: ChunkDelta = ron::from_str(&contents)?;
+    if delta.seed != seed {
+        return Err(WorldIoError::Unsupported(format!(
+            "{} holds edits for seed {}, not {}",
+            path.display(),
+            delta.seed,
+            seed
+        )));
+    }
+    Ok(delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn edits_replay_onto_regenerated_chunks() {
+        let mut delta = ChunkDelta::new(42);
+        delta.paint_tile(TileCoord::new(65, 2), 3);
+        delta.paint_tile(TileCoord::new(-1, 0), 4);
+        delta.place_building(TileCoord::new(70, 10), BuildingKind::House);
+        delta.place_building(TileCoord::new(70, 10), BuildingKind::Tavern);
+
+        let edits = delta.chunk(ChunkCoord::new(1, 0)).unwrap();
+        assert_eq!(edits.buildings, [PlacedBuilding { kind: BuildingKind::Tavern, x: 6, y: 10 }]);
+        assert_eq!(delta.chunk(ChunkCoord::new(-1, 0)).unwrap().tiles[&(63, 0)], 4);
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("world.chunks.ron");
+        assert!(is_chunk_delta(&path));
+        save_chunk_delta(&path, &delta).unwrap();
+        let loaded = load_chunk_delta(&path, 42).unwrap();
+        assert_eq!(loaded, delta);
+        assert!(load_chunk_delta(&path, 7).is_err());
+        assert!(load_chunk_delta(&dir.path().join("none.chunks.ron"), 7).unwrap().is_empty());
+
+        // Generated tiles, then the painted ones on top
+        let mut tiles = vec![0u16; (CHUNK_TILES * CHUNK_TILES) as usize];
+        loaded.chunk(ChunkCoord::new(1, 0)).unwrap().apply(&mut tiles);
+        assert_eq!(tiles[2 * CHUNK_TILES as usize + 1], 3);
+        assert_eq!(tiles.iter().filter(|&&t| t != 0).count(), 1);
+
+        assert!(delta.remove_building(TileCoord::new(70, 10)));
+        assert!(!delta.remove_building(TileCoord::new(70, 10)));
+        delta.reset_chunk(ChunkCoord::new(1, 0));
+        delta.reset_chunk(ChunkCoord::new(-1, 0));
+        assert!(delta.is_empty());
+    }
+}
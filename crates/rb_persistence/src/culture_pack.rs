@@ -0,0 +1,95 @@
+//! Culture packs: sets of authored peoples, saved as RON.
+//!
+//! A pack holds the cultures settlement placement chooses between. Packs
+//! live in [`CULTURES_DIR`], apart from any world, so the same peoples can
+//! be loaded into many worlds.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rb_world::Culture;
+use serde::{Deserialize, Serialize};
+
+use crate::world_io::{file_stem, WorldIoError};
+
+/// Directory the editor saves and lists culture packs in.
+pub const CULTURES_DIR: &str = "assets/cultures";
+
+/// A named set of cultures.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CulturePack {
+    pub name: String,
+    pub cultures: Vec<Culture>,
+}
+
+/// Path of the pack called `name`, e.g. `assets/cultures/sea_peoples.ron`.
+pub fn culture_pack_path(name: &str) -> PathBuf {
+    Path::new(CULTURES_DIR).join(format!("{}.ron", file_stem(name)))
+}
+
+/// List the packs in [`CULTURES_DIR`], sorted by name.
+pub fn list_culture_packs() -> Result<Vec<PathBuf>, WorldIoError> {
+    let dir = Path::new(CULTURES_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut packs = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "ron") {
+            packs.push(path);
+        }
+    }
+    packs.sort();
+    Ok(packs)
+}
+
+/// Save a culture pack to a RON file, creating its directory if needed.
+pub fn save_culture_pack(path: &Path, pack: &CulturePack) -> Result<(), WorldIoError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let pretty_config = ron::ser::PrettyConfig::new().depth_limit(4);
+    fs::write(path, ron::ser::to_string_pretty(pack, pretty_config)?)?;
+    Ok(())
+}
+
+/// Load a culture pack. A pack without cultures is refused, as it would
+/// leave settlement placement nobody to settle.
+pub fn load_culture_pack(path: &Path) -> Result<CulturePack, WorldIoError> {
+    let pack: CulturePack = ron::from_str(&fs::read_to_string(path)?)?;
+    if pack.cultures.is_empty() {
+        return Err(WorldIoError::Unsupported(format!("{} holds no cultures", path.display())));
+    }
+    Ok(pack)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_world::CultureType;
+    use tempfile::tempdir;
+
+    #[test]
+    fn packs_round_trip_through_ron() {
+        let mut reef_folk = Culture::from_type(CultureType::TideWalker);
+        reef_folk.name = "Reef Folk".into();
+        reef_folk.biome_preferences.jungle = 0.9;
+        reef_folk.temperature_range = (30.0, 60.0);
+        let pack = CulturePack {
+            name: "Sea Peoples".into(),
+            cultures: vec![reef_folk, Culture::tide_walker()],
+        };
+        assert!(culture_pack_path(&pack.name).ends_with("sea_peoples.ron"));
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("packs").join("sea_peoples.ron");
+        save_culture_pack(&path, &pack).unwrap();
+        assert_eq!(load_culture_pack(&path).unwrap(), pack);
+
+        let empty = CulturePack { name: "Nobody".into(), cultures: Vec::new() };
+        save_culture_pack(&path, &empty).unwrap();
+        assert!(load_culture_pack(&path).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rb_noise::{BiomeMap, ELEVATION_RANGE};
+
+use crate::world_io::{file_stem, WorldIoError};
+
+/// Directory exported heightmaps are written to.
+pub const EXPORTS_DIR: &str = "assets/exports";
+
+/// File format of an exported heightmap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeightmapFormat {
+    /// 16-bit grayscale PNG, as Blender's displace modifier reads it.
+    #[default]
+    Png16,
+    /// Headerless little-endian 16-bit samples, top row first, as Unity and
+    /// Unreal terrain import them.
+    Raw16,
+}
+
+impl HeightmapFormat {
+    pub fn all() -> &'static [Self] {
+        &[Self::Png16, Self::Raw16]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Png16 => "16-bit PNG",
+            Self::Raw16 => "16-bit RAW",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png16 => "png",
+            Self::Raw16 => "raw",
+        }
+    }
+}
+
+/// Filename of a world's heightmap.
+pub fn heightmap_filename(world_name: &str, format: HeightmapFormat) -> String {
+    format!("{}_heightmap.{}", file_stem(world_name), format.extension())
+}
+
+/// Directory a world's meso tile heightmaps are written to.
+pub fn heightmap_tiles_dirname(world_name: &str) -> String {
+    format!("{}_heightmap_tiles", file_stem(world_name))
+}
+
+/// Filename of one meso tile's heightmap.
+pub fn heightmap_tile_filename(tile: (i32, i32), format: HeightmapFormat) -> String {
+    format!("tile_{}_{}.{}", tile.0, tile.1, format.extension())
+}
+
+/// Elevation as a 16-bit sample. The whole of [`ELEVATION_RANGE`] maps to
+/// 0..=65535, so every map and tile exported shares one vertical scale.
+pub fn height_sample(elevation: f64) -> u16 {
+    let (min, max) = ELEVATION_RANGE;
+    let t = ((elevation - min) / (max - min)).clamp(0.0, 1.0);
+    (t * u16::MAX as f64).round() as u16
+}
+
+/// Write the terrain elevation of `map` to `path`, one sample per cell.
+///
+/// Works the same for the macro map and a meso tile; the parent directory is
+/// created if needed.
+pub fn export_heightmap(map: &BiomeMap, format: HeightmapFormat, path: &Path) -> Result<(), WorldIoError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let samples: Vec<u16> = (0..map.height)
+        .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+        .filter_map(|(x, y)| map.elevation(x, y))
+        .map(height_sample)
+        .collect();
+
+    let mut out = BufWriter::new(File::create(path)?);
+    match format {
+        HeightmapFormat::Png16 => {
+            // PNG stores 16-bit samples big-endian
+            let data: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+            let mut encoder = png::Encoder::new(out, map.width as u32, map.height as u32);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder.set_depth(png::BitDepth::Sixteen);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&data)?;
+            writer.finish()?;
+        }
+        HeightmapFormat::Raw16 => {
+            for sample in &samples {
+                out.write_all(&sample.to_le_bytes())?;
+            }
+            out.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Write one heightmap per meso tile into `dir`, named by tile coordinate.
+/// Returns the written paths in the order the tiles were given.
+pub fn export_heightmap_tiles<'a>(
+    dir: &Path,
+    tiles: impl IntoIterator<Item = ((i32, i32), &'a BiomeMap)>,
+    format: HeightmapFormat,
+) -> Result<Vec<PathBuf>, WorldIoError> {
+    fs::create_dir_all(dir)?;
+
+    let mut paths = Vec::new();
+    for (tile, map) in tiles {
+        let path = dir.join(heightmap_tile_filename(tile, format));
+        export_heightmap(map, format, &path)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn heightmaps_round_trip_in_both_formats() {
+        let dir = tempdir().unwrap();
+        let map = BiomeMap::generate(42, 24, 12);
+        let expected: Vec<u16> = (0..map.width * map.height)
+            .map(|idx| height_sample(map.elevation(idx % map.width, idx / map.width).unwrap()))
+            .collect();
+
+        let png_path = dir.path().join(heightmap_filename("My World", HeightmapFormat::Png16));
+        export_heightmap(&map, HeightmapFormat::Png16, &png_path).unwrap();
+        assert!(png_path.ends_with("my_world_heightmap.png"));
+
+        let decoder = png::Decoder::new(std::io::BufReader::new(File::open(&png_path).unwrap()));
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (24, 12));
+        assert_eq!(info.bit_depth, png::BitDepth::Sixteen);
+        let decoded: Vec<u16> = buf.chunks_exact(2).map(|b| u16::from_be_bytes([b[0], b[1]])).collect();
+        assert_eq!(decoded, expected);
+
+        let paths = export_heightmap_tiles(dir.path(), [((-1, 2), &map)], HeightmapFormat::Raw16).unwrap();
+        assert!(paths[0].ends_with("tile_-1_2.raw"));
+        let raw = fs::read(&paths[0]).unwrap();
+        let decoded: Vec<u16> = raw.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect();
+        assert_eq!(decoded, expected);
+
+        // Samples span the full range, so sea and mountains stay apart
+        assert_eq!(height_sample(ELEVATION_RANGE.0), 0);
+        assert_eq!(height_sample(ELEVATION_RANGE.1), u16::MAX);
+        assert!(height_sample(-0.5) < height_sample(0.5));
+    }
+}
@@ -1,21 +1,80 @@
 use bevy::prelude::*;
+use rb_core::BiomeRegistry;
+use rb_world::NameData;
+use std::path::Path;
 
+pub mod chunk_delta;
+pub mod culture_pack;
+pub mod heightmap_export;
+pub mod mask_export;
+pub mod migration;
+pub mod preview_export;
+pub mod sketch_import;
+pub mod world_diff;
 pub mod world_io;
 
+pub use chunk_delta::{
+    chunk_delta_path, load_chunk_delta, save_chunk_delta, BuildingKind, ChunkDelta, ChunkEdits,
+    PlacedBuilding, CHUNK_TILES,
+};
+pub use culture_pack::{
+    culture_pack_path, list_culture_packs, load_culture_pack, save_culture_pack, CulturePack, CULTURES_DIR,
+};
+pub use heightmap_export::{
+    export_heightmap, export_heightmap_tiles, heightmap_filename, heightmap_tile_filename,
+    heightmap_tiles_dirname, height_sample, HeightmapFormat, EXPORTS_DIR,
+};
+pub use mask_export::{export_faction_masks, faction_mask_filename};
+pub use migration::{save_version, Migration, MigrationError, MigrationRegistry, SaveVersion};
+pub use preview_export::{export_layer_previews, layer_slug, preview_filename, write_rgba_png};
+pub use sketch_import::{import_heightmap, list_sketches, SKETCHES_DIR};
+pub use world_diff::{diff_worlds, EntityChange, FieldChange, WorldDiff};
 pub use world_io::{
-    ensure_worlds_dir, list_worlds, load_world, save_world, world_filename, world_path,
-    WorldIoError, WORLDS_DIR,
+    ensure_worlds_dir, list_worlds, load_biome_registry, load_name_data, load_world, load_world_with_delta,
+    save_delta, save_world, world_filename, world_path,
+    WorldFormat, WorldIoError, BIOMES_PATH, NAMES_PATH, WORLDS_DIR,
 };
 
 /// Persistence plugin for Randlebrot.
-/// Handles delta storage and save/load functionality using RON format.
+/// Handles world saves, world and chunk deltas, and exports.
 pub struct RbPersistencePlugin;
 
 impl Plugin for RbPersistencePlugin {
-    fn build(&self, _app: &mut App) {
+    fn build(&self, app: &mut App) {
         // Ensure worlds directory exists on startup
         if let Err(e) = ensure_worlds_dir() {
             eprintln!("Warning: Could not create worlds directory: {}", e);
         }
+
+        app.insert_resource(load_biomes_or_default())
+            .insert_resource(load_names_or_default());
+    }
+}
+
+/// The biome registry from [`BIOMES_PATH`], or the built-ins if it can't be
+/// read.
+pub fn load_biomes_or_default() -> BiomeRegistry {
+    // Custom biomes are optional: without the file the built-ins apply
+    match load_biome_registry(Path::new(BIOMES_PATH)) {
+        Ok(biomes) => biomes,
+        Err(WorldIoError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            BiomeRegistry::default()
+        }
+        Err(e) => {
+            eprintln!("Warning: Could not load {}: {}", BIOMES_PATH, e);
+            BiomeRegistry::default()
+        }
+    }
+}
+
+/// Culture naming from [`NAMES_PATH`], or the built-ins if it can't be read.
+pub fn load_names_or_default() -> NameData {
+    match load_name_data(Path::new(NAMES_PATH)) {
+        Ok(names) => names,
+        Err(WorldIoError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => NameData::default(),
+        Err(e) => {
+            eprintln!("Warning: Could not load {}: {}", NAMES_PATH, e);
+            NameData::default()
+        }
     }
 }
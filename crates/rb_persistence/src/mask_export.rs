@@ -0,0 +1,89 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use rb_world::{Faction, TerritoryMap};
+
+use crate::world_io::WorldIoError;
+
+/// Filename of a faction's territory mask.
+pub fn faction_mask_filename(faction_id: u32) -> String {
+    format!("faction_{}.png", faction_id)
+}
+
+/// Write one 1-bit grayscale PNG per faction into `dir`.
+///
+/// White pixels are owned by the faction, black pixels are not. The directory
+/// is created if needed. Returns the written paths in faction order.
+pub fn export_faction_masks(
+    dir: &Path,
+    territory: &TerritoryMap,
+    factions: &[Faction],
+) -> Result<Vec<PathBuf>, WorldIoError> {
+    fs::create_dir_all(dir)?;
+
+    let mut paths = Vec::with_capacity(factions.len());
+    for faction in factions {
+        let path = dir.join(faction_mask_filename(faction.id));
+        let mask = territory.faction_mask(faction.id);
+        write_mask_png(&path, &mask, territory.width, territory.height)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+/// Pack a row-major mask into 1-bit rows (MSB first) and write it as a PNG.
+fn write_mask_png(path: &Path, mask: &[bool], width: usize, height: usize) -> Result<(), WorldIoError> {
+    let row_bytes = width.div_ceil(8);
+    let mut data = vec![0u8; row_bytes * height];
+    for (idx, _) in mask.iter().enumerate().filter(|(_, &owned)| owned) {
+        let (x, y) = (idx % width, idx / width);
+        data[y * row_bytes + x / 8] |= 0x80 >> (x % 8);
+    }
+
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::One);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_world::CultureType;
+    use tempfile::tempdir;
+
+    #[test]
+    fn exported_mask_round_trips() {
+        let dir = tempdir().unwrap();
+        let mut territory = TerritoryMap::new(11, 3);
+        territory.set(0, 0, 1, 1.0);
+        territory.set(9, 1, 1, 1.0);
+        territory.set(10, 2, 2, 1.0);
+        let factions = vec![
+            Faction::new(1, "North".into(), CultureType::FrostKin),
+            Faction::new(2, "South".into(), CultureType::SunForged),
+        ];
+
+        let paths = export_faction_masks(dir.path(), &territory, &factions).unwrap();
+        assert_eq!(paths.len(), 2);
+
+        let decoder = png::Decoder::new(std::io::BufReader::new(File::open(&paths[0]).unwrap()));
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (11, 3));
+        assert_eq!(info.bit_depth, png::BitDepth::One);
+
+        let row_bytes = info.line_size;
+        let bit = |x: usize, y: usize| buf[y * row_bytes + x / 8] & (0x80 >> (x % 8)) != 0;
+        assert!(bit(0, 0));
+        assert!(bit(9, 1));
+        assert!(!bit(10, 2));
+    }
+}
@@ -0,0 +1,245 @@
+//! Versioned world saves.
+//!
+//! Every save starts with a [`SaveVersion`] header:
+//!
+//! ```text
+//! (
+//!     version: 1,
+//!     world: ( name: "...", seed: 42, ... ),
+//! )
+//! ```
+//!
+//! Files written by an older format are upgraded on load by running the
+//! [`Migration`]s of a [`MigrationRegistry`] in turn, one version at a time.
+//! Saves from before the header existed are version 0: a bare
+//! `WorldDefinition`.
+//!
+//! Adding a field with a `#[serde(default)]` needs no migration. Renaming,
+//! moving or reinterpreting one does: bump [`SaveVersion::CURRENT`] and
+//! register a migration from the previous version.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the world save format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SaveVersion(pub u32);
+
+impl SaveVersion {
+    /// Saves without a header, from before versioning.
+    pub const LEGACY: Self = Self(0);
+    /// Version written by [`save_world`](crate::save_world).
+    pub const CURRENT: Self = Self(1);
+
+    /// The version after this one.
+    pub fn next(self) -> Self {
+        Self(self.0 + 1)
+    }
+}
+
+impl fmt::Display for SaveVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// Why a save couldn't be brought up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationError {
+    /// The save was written by a newer build than this one.
+    TooNew { found: SaveVersion, newest: SaveVersion },
+    /// No migration is registered from this version.
+    NoMigration { from: SaveVersion },
+    /// A migration ran but couldn't convert the save.
+    Failed { from: SaveVersion, reason: String },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooNew { found, newest } => write!(
+                f,
+                "save format {} is newer than the newest this build reads ({}); update randlebrot",
+                found, newest
+            ),
+            Self::NoMigration { from } => write!(f, "no migration from save format {}", from),
+            Self::Failed { from, reason } => {
+                write!(f, "migrating from save format {} failed: {}", from, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+/// Upgrade of a save from one version to the next.
+///
+/// Migrations work on the RON source, since a save from an older version
+/// may no longer deserialize into today's `WorldDefinition`. One that needs
+/// structure can parse into its own snapshot of the old types.
+pub trait Migration: Send + Sync {
+    /// Version this migration reads. It writes the version after.
+    fn source_version(&self) -> SaveVersion;
+
+    /// What changed, for the log.
+    fn description(&self) -> &'static str;
+
+    /// Rewrite a save of [`source_version`](Self::source_version) as the next
+    /// version.
+    fn migrate(&self, source: &str) -> Result<String, String>;
+}
+
+/// Wraps a bare `WorldDefinition` in the version header.
+struct AddVersionHeader;
+
+impl Migration for AddVersionHeader {
+    fn source_version(&self) -> SaveVersion {
+        SaveVersion::LEGACY
+    }
+
+    fn description(&self) -> &'static str {
+        "add save version header"
+    }
+
+    fn migrate(&self, source: &str) -> Result<String, String> {
+        Ok(format!("(\n    version: 1,\n    world: {},\n)\n", source.trim()))
+    }
+}
+
+/// Migrations by the version they upgrade from.
+pub struct MigrationRegistry {
+    migrations: Vec<Box<dyn Migration>>,
+}
+
+impl Default for MigrationRegistry {
+    /// Every migration shipped with this build.
+    fn default() -> Self {
+        Self::empty().with(AddVersionHeader)
+    }
+}
+
+impl MigrationRegistry {
+    /// A registry with no migrations; only current saves load through it.
+    pub fn empty() -> Self {
+        Self { migrations: Vec::new() }
+    }
+
+    /// Add a migration, replacing any registered from the same version.
+    pub fn with(mut self, migration: impl Migration + 'static) -> Self {
+        self.migrations.retain(|m| m.source_version() != migration.source_version());
+        self.migrations.push(Box::new(migration));
+        self
+    }
+
+    /// Upgrade `source`, saved as `version`, to [`SaveVersion::CURRENT`].
+    /// Returns the upgraded source and the description of each migration run.
+    pub fn upgrade(
+        &self,
+        source: &str,
+        version: SaveVersion,
+    ) -> Result<(String, Vec<&'static str>), MigrationError> {
+        self.upgrade_to(source, version, SaveVersion::CURRENT)
+    }
+
+    fn upgrade_to(
+        &self,
+        source: &str,
+        mut version: SaveVersion,
+        target: SaveVersion,
+    ) -> Result<(String, Vec<&'static str>), MigrationError> {
+        if version > target {
+            return Err(MigrationError::TooNew { found: version, newest: target });
+        }
+
+        let mut source = source.to_string();
+        let mut applied = Vec::new();
+        while version < target {
+            let migration = self
+                .migrations
+                .iter()
+                .find(|m| m.source_version() == version)
+                .ok_or(MigrationError::NoMigration { from: version })?;
+            source = migration
+                .migrate(&source)
+                .map_err(|reason| MigrationError::Failed { from: version, reason })?;
+            applied.push(migration.description());
+            version = version.next();
+        }
+        Ok((source, applied))
+    }
+}
+
+/// Just the header of a save. Unversioned saves have no `version` field.
+#[derive(Deserialize)]
+struct SaveHeader {
+    #[serde(default = "legacy_version")]
+    version: SaveVersion,
+}
+
+fn legacy_version() -> SaveVersion {
+    SaveVersion::LEGACY
+}
+
+/// Version a save's source declares, [`SaveVersion::LEGACY`] if none.
+pub fn save_version(source: &str) -> Result<SaveVersion, ron::error::SpannedError> {
+    Ok(ron::from_str::<SaveHeader>(source)?.version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renames `title` to `name` in a made-up version 1 format.
+    struct RenameTitle;
+
+    impl Migration for RenameTitle {
+        fn source_version(&self) -> SaveVersion {
+            SaveVersion(1)
+        }
+
+        fn description(&self) -> &'static str {
+            "rename title to name"
+        }
+
+        fn migrate(&self, source: &str) -> Result<String, String> {
+            if !source.contains("title:") {
+                return Err("no title".to_string());
+            }
+            Ok(source.replace("title:", "name:").replace("version: 1", "version: 2"))
+        }
+    }
+
+    #[test]
+    fn upgrades_chain_and_fail_clearly() {
+        let legacy = "(title: \"Old\")";
+        assert_eq!(save_version(legacy).unwrap(), SaveVersion::LEGACY);
+
+        let (upgraded, applied) = MigrationRegistry::default().upgrade(legacy, SaveVersion::LEGACY).unwrap();
+        assert_eq!(applied, ["add save version header"]);
+        assert_eq!(save_version(&upgraded).unwrap(), SaveVersion::CURRENT);
+        let (same, applied) = MigrationRegistry::default().upgrade(&upgraded, SaveVersion::CURRENT).unwrap();
+        assert_eq!((same, applied.len()), (upgraded, 0));
+
+        // Steps run in order up to the target
+        let registry = MigrationRegistry::default().with(RenameTitle);
+        let (source, applied) = registry.upgrade_to(legacy, SaveVersion::LEGACY, SaveVersion(2)).unwrap();
+        assert_eq!(applied, ["add save version header", "rename title to name"]);
+        assert_eq!(save_version(&source).unwrap(), SaveVersion(2));
+        assert!(source.contains("name: \"Old\""));
+
+        assert_eq!(
+            MigrationRegistry::default().upgrade_to(legacy, SaveVersion::LEGACY, SaveVersion(2)),
+            Err(MigrationError::NoMigration { from: SaveVersion(1) })
+        );
+        assert_eq!(
+            registry.upgrade_to("(version: 1, world: ())", SaveVersion(1), SaveVersion(2)),
+            Err(MigrationError::Failed { from: SaveVersion(1), reason: "no title".to_string() })
+        );
+        assert_eq!(
+            registry.upgrade(legacy, SaveVersion(99)),
+            Err(MigrationError::TooNew { found: SaveVersion(99), newest: SaveVersion::CURRENT })
+        );
+    }
+}
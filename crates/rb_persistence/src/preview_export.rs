@@ -0,0 +1,75 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use rb_noise::{BiomeMap, NoiseLayer};
+
+use crate::world_io::WorldIoError;
+
+/// A layer's name in snake case, e.g. `peaks_valleys`.
+pub fn layer_slug(layer: NoiseLayer) -> String {
+    let words: Vec<&str> = layer
+        .name()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    words.join("_").to_lowercase()
+}
+
+/// Filename of a layer's preview, e.g. `peaks_valleys.png`.
+pub fn preview_filename(layer: NoiseLayer) -> String {
+    format!("{}.png", layer_slug(layer))
+}
+
+/// Write each of `layers` of `map` into `dir` as a PNG, coloured as the
+/// editor shows it. Returns the written paths in layer order.
+pub fn export_layer_previews(
+    dir: &Path,
+    map: &BiomeMap,
+    layers: &[NoiseLayer],
+) -> Result<Vec<PathBuf>, WorldIoError> {
+    fs::create_dir_all(dir)?;
+
+    let mut paths = Vec::with_capacity(layers.len());
+    for &layer in layers {
+        let path = dir.join(preview_filename(layer));
+        write_rgba_png(&path, &map.to_layer_image(layer), map.width, map.height)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Write row-major RGBA8 pixels as a PNG.
+pub fn write_rgba_png(path: &Path, rgba: &[u8], width: usize, height: usize) -> Result<(), WorldIoError> {
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(rgba)?;
+    writer.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn previews_are_named_after_their_layers() {
+        let dir = tempdir().unwrap();
+        let map = BiomeMap::generate(42, 32, 16);
+        let layers = [NoiseLayer::Aggregate, NoiseLayer::PeaksValleys];
+
+        let paths = export_layer_previews(dir.path(), &map, &layers).unwrap();
+        assert!(paths[1].ends_with("peaks_valleys.png"));
+
+        let decoder = png::Decoder::new(std::io::BufReader::new(File::open(&paths[0]).unwrap()));
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (32, 16));
+        assert_eq!(&buf[..info.buffer_size()], &map.to_layer_image(NoiseLayer::Aggregate)[..]);
+    }
+}
@@ -0,0 +1,147 @@
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use rb_noise::ContinentSketch;
+
+use crate::world_io::WorldIoError;
+
+/// Directory the editor lists continent sketches from.
+pub const SKETCHES_DIR: &str = "assets/sketches";
+
+/// Image extensions [`import_heightmap`] reads.
+const SKETCH_EXTENSIONS: [&str; 2] = ["png", "exr"];
+
+/// List the heightmaps in [`SKETCHES_DIR`], sorted by name.
+pub fn list_sketches() -> Result<Vec<PathBuf>, WorldIoError> {
+    let dir = Path::new(SKETCHES_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sketches = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if extension(&path).is_some_and(|ext| SKETCH_EXTENSIONS.contains(&ext.as_str())) {
+            sketches.push(path);
+        }
+    }
+    sketches.sort();
+    Ok(sketches)
+}
+
+/// Read a grayscale heightmap to sketch continents from.
+///
+/// PNGs may be 8 or 16 bits deep; colour images are averaged to gray and
+/// alpha is ignored. OpenEXR images have no fixed range, so their first
+/// luminance or red channel is stretched from its lowest to highest value.
+pub fn import_heightmap(path: &Path) -> Result<ContinentSketch, WorldIoError> {
+    match extension(path).as_deref() {
+        Some("png") => import_png(path),
+        Some("exr") => import_exr(path),
+        _ => Err(WorldIoError::Unsupported(format!(
+            "{} is not a PNG or EXR heightmap",
+            path.display()
+        ))),
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase)
+}
+
+fn import_png(path: &Path) -> Result<ContinentSketch, WorldIoError> {
+    let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    // Palettes and sub-byte depths become plain 8-bit samples
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size().unwrap_or_default()];
+    let info = reader.next_frame(&mut buf)?;
+
+    let (colors, alpha) = match info.color_type {
+        png::ColorType::Grayscale => (1, 0),
+        png::ColorType::GrayscaleAlpha => (1, 1),
+        png::ColorType::Rgb => (3, 0),
+        png::ColorType::Rgba => (3, 1),
+        png::ColorType::Indexed => {
+            return Err(WorldIoError::Unsupported("unexpanded palette PNG".to_string()))
+        }
+    };
+    let wide = info.bit_depth == png::BitDepth::Sixteen;
+    let bytes = if wide { 2 } else { 1 };
+    let (width, height) = (info.width as usize, info.height as usize);
+
+    let samples = buf[..info.buffer_size()]
+        .chunks_exact((colors + alpha) * bytes)
+        .map(|pixel| {
+            let sum: u32 = pixel[..colors * bytes]
+                .chunks_exact(bytes)
+                .map(|s| if wide { u16::from_be_bytes([s[0], s[1]]) } else { s[0] as u16 * 257 } as u32)
+                .sum();
+            (sum / colors as u32) as u16
+        })
+        .collect();
+    Ok(ContinentSketch::new(width, height, samples))
+}
+
+fn import_exr(path: &Path) -> Result<ContinentSketch, WorldIoError> {
+    let image = exr::prelude::read_first_flat_layer_from_file(path)?;
+    let layer = image.layer_data;
+    let channels = &layer.channel_data.list;
+    let channel = ["Y", "R"]
+        .iter()
+        .find_map(|name| channels.iter().find(|c| c.name == **name))
+        .or(channels.first())
+        .ok_or_else(|| WorldIoError::Unsupported(format!("{} has no channels", path.display())))?;
+
+    let values: Vec<f64> = (0..channel.sample_data.len())
+        .map(|i| channel.sample_data.value_by_flat_index(i).to_f32() as f64)
+        .collect();
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let span = if max > min { max - min } else { 1.0 };
+    let unit: Vec<f64> = values.iter().map(|v| (v - min) / span).collect();
+    Ok(ContinentSketch::from_unit(layer.size.width(), layer.size.height(), &unit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufWriter;
+    use tempfile::tempdir;
+
+    #[test]
+    fn png_and_exr_sketches_import() {
+        let dir = tempdir().unwrap();
+
+        // 16-bit grayscale, bright on the left
+        let png_path = dir.path().join("continents.png");
+        let samples: [u16; 6] = [65535, 32768, 0, 65535, 32768, 0];
+        let mut encoder = png::Encoder::new(BufWriter::new(File::create(&png_path).unwrap()), 3, 2);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Sixteen);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&samples.iter().flat_map(|s| s.to_be_bytes()).collect::<Vec<_>>()).unwrap();
+        writer.finish().unwrap();
+
+        let sketch = import_heightmap(&png_path).unwrap();
+        assert_eq!((sketch.width(), sketch.height()), (3, 2));
+        assert_eq!(sketch, ContinentSketch::new(3, 2, samples.to_vec()));
+        assert!((sketch.continentalness(0.0, 0.0) - 1.0).abs() < 1e-9);
+        assert!(sketch.continentalness(0.5, 0.5).abs() < 1e-3); // Mid-grey is the coast
+
+        // Float heights in metres, stretched to their own range
+        let exr_path = dir.path().join("continents.exr");
+        exr::prelude::write_rgb_file(&exr_path, 4, 1, |x, _| {
+            let metres = x as f32 * 500.0 - 500.0;
+            (metres, metres, metres)
+        })
+        .unwrap();
+        let sketch = import_heightmap(&exr_path).unwrap();
+        assert_eq!(sketch, ContinentSketch::from_unit(4, 1, &[0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0]));
+
+        let jpeg = dir.path().join("continents.jpg");
+        assert!(matches!(import_heightmap(&jpeg), Err(WorldIoError::Unsupported(_))));
+    }
+}
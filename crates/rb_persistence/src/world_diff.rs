@@ -0,0 +1,416 @@
+//! Readable differences between two world definitions, for reviewing what
+//! changed between saves.
+//!
+//! Unlike [`WorldDelta`](rb_world::WorldDelta), which stores just enough to
+//! patch one world into another, a [`WorldDiff`] names every setting and
+//! entity field that changed. Its order depends only on the two worlds, so
+//! the same pair always gives the same report.
+
+use std::fmt;
+
+use rb_world::{City, Faction, Landmark, Point2D, Region, Road, TradeRoute, WorldDefinition};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Longest list or map printed in full; longer ones show their length.
+const MAX_INLINE_LEN: usize = 48;
+
+/// A field that differs, by its path from the top of what was compared,
+/// e.g. `noise_params.layers.continentalness.octaves`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub path: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// How one entity differs between the two worlds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntityChange {
+    Added { id: u32, name: String },
+    Removed { id: u32, name: String },
+    Modified {
+        id: u32,
+        name: String,
+        /// Old and new position, if the entity has one and it moved.
+        moved: Option<(Point2D, Point2D)>,
+        /// Every other field that changed.
+        fields: Vec<FieldChange>,
+    },
+}
+
+impl EntityChange {
+    pub fn id(&self) -> u32 {
+        match self {
+            Self::Added { id, .. } | Self::Removed { id, .. } | Self::Modified { id, .. } => *id,
+        }
+    }
+}
+
+/// Everything that differs between two worlds. Entity changes are sorted by
+/// ID, fields by path.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorldDiff {
+    /// Seed, size, noise parameters and every other non-entity setting.
+    /// Where the camera was left is not compared.
+    pub settings: Vec<FieldChange>,
+    pub cities: Vec<EntityChange>,
+    pub landmarks: Vec<EntityChange>,
+    pub regions: Vec<EntityChange>,
+    pub factions: Vec<EntityChange>,
+    pub roads: Vec<EntityChange>,
+    pub trade_routes: Vec<EntityChange>,
+}
+
+impl WorldDiff {
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty() && self.entity_sections().iter().all(|(_, changes)| changes.is_empty())
+    }
+
+    fn entity_sections(&self) -> [(&'static str, &[EntityChange]); 6] {
+        [
+            ("Cities", &self.cities),
+            ("Landmarks", &self.landmarks),
+            ("Regions", &self.regions),
+            ("Factions", &self.factions),
+            ("Roads", &self.roads),
+            ("Trade routes", &self.trade_routes),
+        ]
+    }
+}
+
+impl fmt::Display for WorldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences.");
+        }
+
+        if !self.settings.is_empty() {
+            writeln!(f, "Settings:")?;
+            for change in &self.settings {
+                writeln!(f, "  {}", change)?;
+            }
+        }
+
+        for (title, changes) in self.entity_sections() {
+            if changes.is_empty() {
+                continue;
+            }
+            let count = |pred: fn(&EntityChange) -> bool| changes.iter().filter(|c| pred(c)).count();
+            writeln!(
+                f,
+                "{}: {} added, {} removed, {} changed",
+                title,
+                count(|c| matches!(c, EntityChange::Added { .. })),
+                count(|c| matches!(c, EntityChange::Removed { .. })),
+                count(|c| matches!(c, EntityChange::Modified { .. })),
+            )?;
+            for change in changes {
+                match change {
+                    EntityChange::Added { id, name } => writeln!(f, "  + #{} {}", id, name)?,
+                    EntityChange::Removed { id, name } => writeln!(f, "  - #{} {}", id, name)?,
+                    EntityChange::Modified { id, name, moved, fields } => {
+                        writeln!(f, "  ~ #{} {}", id, name)?;
+                        if let Some((from, to)) = moved {
+                            writeln!(f, "      moved ({}, {}) -> ({}, {})", from.x, from.y, to.x, to.y)?;
+                        }
+                        for field in fields {
+                            writeln!(f, "      {}", field)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.path, self.before, self.after)
+    }
+}
+
+/// Compare two worlds: settings field by field, and each kind of entity by
+/// ID.
+pub fn diff_worlds(a: &WorldDefinition, b: &WorldDefinition) -> WorldDiff {
+    let road_name = |road: &Road, world: &WorldDefinition| {
+        let city = |id: u32| {
+            world.cities.iter().find(|c| c.id == id).map_or_else(|| format!("#{}", id), |c| c.name.clone())
+        };
+        format!("{} - {}", city(road.connects.0), city(road.connects.1))
+    };
+
+    WorldDiff {
+        settings: settings_changes(a, b),
+        cities: diff_entities(&a.cities, &b.cities, |city, _| city.name.clone(), a, b),
+        landmarks: diff_entities(&a.landmarks, &b.landmarks, |landmark, _| landmark.name.clone(), a, b),
+        regions: diff_entities(&a.regions, &b.regions, |region, _| region.name.clone(), a, b),
+        factions: diff_entities(&a.factions, &b.factions, |faction, _| faction.name.clone(), a, b),
+        roads: diff_entities(&a.roads, &b.roads, road_name, a, b),
+        trade_routes: diff_entities(
+            &a.trade_routes,
+            &b.trade_routes,
+            |route, _| format!("{} roads", route.road_ids.len()),
+            a,
+            b,
+        ),
+    }
+}
+
+/// Entities compared by [`diff_worlds`].
+trait Entity: Serialize {
+    fn id(&self) -> u32;
+
+    /// Position reported as a move rather than as changed fields.
+    fn position(&self) -> Option<Point2D> {
+        None
+    }
+}
+
+impl Entity for City {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn position(&self) -> Option<Point2D> {
+        Some(self.position)
+    }
+}
+
+impl Entity for Landmark {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn position(&self) -> Option<Point2D> {
+        Some(self.position)
+    }
+}
+
+impl Entity for Region {
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Entity for Faction {
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Entity for Road {
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Entity for TradeRoute {
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+/// Changes from `before` to `after`, matched by ID. Entities are named by
+/// `name` in the world they appear in (the newer one if both).
+fn diff_entities<T: Entity>(
+    before: &[T],
+    after: &[T],
+    name: impl Fn(&T, &WorldDefinition) -> String,
+    a: &WorldDefinition,
+    b: &WorldDefinition,
+) -> Vec<EntityChange> {
+    let mut changes = Vec::new();
+    for old in before {
+        if !after.iter().any(|new| new.id() == old.id()) {
+            changes.push(EntityChange::Removed { id: old.id(), name: name(old, a) });
+        }
+    }
+    for new in after {
+        let Some(old) = before.iter().find(|old| old.id() == new.id()) else {
+            changes.push(EntityChange::Added { id: new.id(), name: name(new, b) });
+            continue;
+        };
+
+        let mut fields = diff_values("", &to_value(old), &to_value(new));
+        let moved = match (old.position(), new.position()) {
+            (Some(from), Some(to)) if from != to => Some((from, to)),
+            _ => None,
+        };
+        if old.position().is_some() {
+            fields.retain(|field| field.path != "position" && !field.path.starts_with("position."));
+        }
+        if moved.is_some() || !fields.is_empty() {
+            changes.push(EntityChange::Modified { id: new.id(), name: name(new, b), moved, fields });
+        }
+    }
+    changes.sort_by_key(EntityChange::id);
+    changes
+}
+
+/// Settings that differ. Sculpted terrain and locked territory are keyed by
+/// cell, which JSON can't hold, so they are compared directly and reported by
+/// size; so is the timeline, which would otherwise list every event.
+fn settings_changes(a: &WorldDefinition, b: &WorldDefinition) -> Vec<FieldChange> {
+    let mut changes = diff_values("", &settings_value(a), &settings_value(b));
+    let mut sized = |path: &str, differs: bool, unit: &str, sizes: (usize, usize)| {
+        if differs {
+            changes.push(FieldChange {
+                path: path.to_string(),
+                before: format!("{} {}", sizes.0, unit),
+                after: format!("{} {}", sizes.1, unit),
+            });
+        }
+    };
+    let (edits, locks) = ((&a.terrain_edits, &b.terrain_edits), (&a.territory_locks, &b.territory_locks));
+    sized("terrain_edits", edits.0 != edits.1, "blocks", (edits.0.len(), edits.1.len()));
+    sized("territory_locks", locks.0 != locks.1, "cells", (locks.0.len(), locks.1.len()));
+    let history = (&a.history, &b.history);
+    sized("history", history.0 != history.1, "events", (history.0.len(), history.1.len()));
+    let (dynasties, rulers) = ((&a.dynasties, &b.dynasties), (&a.rulers, &b.rulers));
+    sized("dynasties", dynasties.0 != dynasties.1, "houses", (dynasties.0.len(), dynasties.1.len()));
+    sized("rulers", rulers.0 != rulers.1, "rulers", (rulers.0.len(), rulers.1.len()));
+    let religions = (&a.religions, &b.religions);
+    sized("religions", religions.0 != religions.1, "religions", (religions.0.len(), religions.1.len()));
+    let lanes = (&a.sea_routes, &b.sea_routes);
+    sized("sea_routes", lanes.0 != lanes.1, "lanes", (lanes.0.len(), lanes.1.len()));
+    let flows = (&a.trade_flows, &b.trade_flows);
+    sized("trade_flows", flows.0 != flows.1, "flows", (flows.0.len(), flows.1.len()));
+    changes.sort_by(|x, y| x.path.cmp(&y.path));
+    changes
+}
+
+/// The world without its entities, saved camera, timeline, rulers, faiths, sea lanes, trade flows or cell-keyed
+/// layers, which are compared separately or not at all.
+fn settings_value(world: &WorldDefinition) -> Value {
+    let settings = WorldDefinition {
+        cities: Vec::new(),
+        landmarks: Vec::new(),
+        regions: Vec::new(),
+        factions: Vec::new(),
+        roads: Vec::new(),
+        trade_routes: Vec::new(),
+        view: None,
+        terrain_edits: Default::default(),
+        territory_locks: Default::default(),
+        history: Vec::new(),
+        dynasties: Vec::new(),
+        rulers: Vec::new(),
+        religions: Vec::new(),
+        sea_routes: Vec::new(),
+        trade_flows: Vec::new(),
+        territory_cache: None,
+        ..world.clone()
+    };
+    to_value(&settings)
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    // Only maps with non-string keys fail, and settings_value leaves those out
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Differing leaves of two values. Objects are walked field by field
+/// (sorted by name); anything else that differs is one change.
+fn diff_values(path: &str, before: &Value, after: &Value) -> Vec<FieldChange> {
+    if before == after {
+        return Vec::new();
+    }
+    let (Value::Object(old), Value::Object(new)) = (before, after) else {
+        return vec![FieldChange {
+            path: path.to_string(),
+            before: format_value(before),
+            after: format_value(after),
+        }];
+    };
+
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .flat_map(|key| {
+            let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+            diff_values(
+                &child,
+                old.get(key).unwrap_or(&Value::Null),
+                new.get(key).unwrap_or(&Value::Null),
+            )
+        })
+        .collect()
+}
+
+/// A value as it reads in the report; long lists and maps are summarised.
+fn format_value(value: &Value) -> String {
+    let text = value.to_string();
+    if text.len() <= MAX_INLINE_LEN {
+        return text;
+    }
+    match value {
+        Value::Array(items) => format!("[{} items]", items.len()),
+        Value::Object(fields) => format!("{{{} fields}}", fields.len()),
+        _ => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_noise::NoiseLayer;
+    use rb_world::{CityTier, LandmarkKind, Polygon, RoadType, ViewState};
+
+    fn sample_world() -> WorldDefinition {
+        let mut world = WorldDefinition::default();
+        for id in 1..=3 {
+            let pos = Point2D::new(id as f64 * 10.0, 5.0);
+            world.cities.push(City::new(id, format!("City {}", id), pos, CityTier::Village));
+        }
+        world.roads.push(Road::new(1, (1, 2), RoadType::Trail));
+        world.landmarks.push(Landmark::new(1, "Old Ruins".into(), Point2D::new(3.0, 4.0), LandmarkKind::Ruin));
+        world.regions.push(Region::new(1, "North".into(), Polygon::default()));
+        world
+    }
+
+    #[test]
+    fn diff_reports_settings_and_entity_changes() {
+        let a = sample_world();
+        assert!(diff_worlds(&a, &a.clone()).is_empty());
+
+        let mut b = a.clone();
+        b.seed = 7;
+        b.noise_params.layers.continentalness.octaves += 1;
+        b.view = Some(ViewState {
+            camera: Point2D::new(1.0, 2.0),
+            zoom: 1.0,
+            layer: NoiseLayer::Aggregate,
+        });
+        b.cities[0].position = Point2D::new(12.0, 6.0);
+        b.cities[1].name = "Renamed".into();
+        b.cities.remove(2);
+        b.cities.push(City::new(9, "New Town".into(), Point2D::new(50.0, 50.0), CityTier::Town));
+        b.roads[0].road_type = RoadType::Imperial;
+        b.territory_locks.set(3, 4, Some(1));
+
+        let diff = diff_worlds(&a, &b);
+        let paths: Vec<&str> = diff.settings.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["noise_params.layers.continentalness.octaves", "seed", "territory_locks"]);
+        assert_eq!(diff.settings[1].to_string(), "seed: 42 -> 7");
+        assert_eq!(diff.settings[2].to_string(), "territory_locks: 0 cells -> 1 cells");
+
+        assert_eq!(diff.cities.iter().map(EntityChange::id).collect::<Vec<_>>(), [1, 2, 3, 9]);
+        assert!(matches!(
+            &diff.cities[0],
+            EntityChange::Modified { moved: Some(_), fields, .. } if fields.is_empty()
+        ));
+        assert!(matches!(&diff.cities[2], EntityChange::Removed { name, .. } if name == "City 3"));
+        assert!(matches!(&diff.cities[3], EntityChange::Added { id: 9, .. }));
+        assert!(diff.landmarks.is_empty() && diff.regions.is_empty());
+
+        let report = diff.to_string();
+        assert!(report.contains("Cities: 1 added, 1 removed, 2 changed"));
+        assert!(report.contains("moved (10, 5) -> (12, 6)"));
+        assert!(report.contains("name: \"City 2\" -> \"Renamed\""));
+        assert!(report.contains("~ #1 City 1 - Renamed"));
+        assert_eq!(report, diff_worlds(&a, &b).to_string());
+    }
+}
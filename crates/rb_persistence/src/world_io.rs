@@ -1,16 +1,77 @@
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use rb_world::WorldDefinition;
+use std::sync::Arc;
+use rb_core::{BiomeRegistry, BiomeRegistryError};
+use rb_world::{NameData, NameDataError, WorldDefinition, WorldDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::chunk_delta::is_chunk_delta;
+use crate::migration::{save_version, MigrationError, MigrationRegistry, SaveVersion};
+use crate::sketch_import::import_heightmap;
 
 /// Default directory for world saves.
 pub const WORLDS_DIR: &str = "assets/worlds";
 
+/// Biome registry file read at startup, if present.
+pub const BIOMES_PATH: &str = "assets/biomes.ron";
+
+/// Culture naming file read at startup, if present.
+pub const NAMES_PATH: &str = "assets/names.ron";
+
+/// First bytes of a binary world save.
+const BINARY_MAGIC: &[u8; 8] = b"RBWORLD\0";
+
+/// zstd level for binary saves: well compressed, still quick to write.
+const BINARY_COMPRESSION_LEVEL: i32 = 9;
+
+/// File format of a world save. Loading tells them apart by content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WorldFormat {
+    /// Human-readable RON.
+    #[default]
+    Ron,
+    /// bincode compressed with zstd. Much smaller and faster to load for
+    /// worlds with long roads, but not migrated between save versions.
+    Binary,
+}
+
+impl WorldFormat {
+    pub fn all() -> &'static [Self] {
+        &[Self::Ron, Self::Binary]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ron => "RON (text)",
+            Self::Binary => "Binary (compressed)",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Ron => "ron",
+            Self::Binary => "rbw",
+        }
+    }
+}
+
 /// Error type for world I/O operations.
 #[derive(Debug)]
 pub enum WorldIoError {
     Io(std::io::Error),
     Ron(ron::Error),
     RonSpanned(ron::error::SpannedError),
+    Png(png::EncodingError),
+    PngDecode(png::DecodingError),
+    Exr(exr::error::Error),
+    Biomes(BiomeRegistryError),
+    Names(NameDataError),
+    Bincode(bincode::Error),
+    /// A save in a format that can't be brought up to date.
+    Migration(MigrationError),
+    /// A file this crate can't read, such as a heightmap in another format.
+    Unsupported(String),
 }
 
 impl From<std::io::Error> for WorldIoError {
@@ -31,50 +92,200 @@ impl From<ron::error::SpannedError> for WorldIoError {
     }
 }
 
+impl From<png::EncodingError> for WorldIoError {
+    fn from(err: png::EncodingError) -> Self {
+        Self::Png(err)
+    }
+}
+
+impl From<png::DecodingError> for WorldIoError {
+    fn from(err: png::DecodingError) -> Self {
+        Self::PngDecode(err)
+    }
+}
+
+impl From<exr::error::Error> for WorldIoError {
+    fn from(err: exr::error::Error) -> Self {
+        Self::Exr(err)
+    }
+}
+
+impl From<BiomeRegistryError> for WorldIoError {
+    fn from(err: BiomeRegistryError) -> Self {
+        Self::Biomes(err)
+    }
+}
+
+impl From<NameDataError> for WorldIoError {
+    fn from(err: NameDataError) -> Self {
+        Self::Names(err)
+    }
+}
+
+impl From<bincode::Error> for WorldIoError {
+    fn from(err: bincode::Error) -> Self {
+        Self::Bincode(err)
+    }
+}
+
+impl From<MigrationError> for WorldIoError {
+    fn from(err: MigrationError) -> Self {
+        Self::Migration(err)
+    }
+}
+
 impl std::fmt::Display for WorldIoError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Io(e) => write!(f, "IO error: {}", e),
             Self::Ron(e) => write!(f, "RON serialization error: {}", e),
             Self::RonSpanned(e) => write!(f, "RON parse error: {}", e),
+            Self::Png(e) => write!(f, "PNG encoding error: {}", e),
+            Self::PngDecode(e) => write!(f, "PNG decoding error: {}", e),
+            Self::Exr(e) => write!(f, "EXR error: {}", e),
+            Self::Biomes(e) => write!(f, "Biome registry error: {}", e),
+            Self::Names(e) => write!(f, "Name data error: {}", e),
+            Self::Bincode(e) => write!(f, "Binary save error: {}", e),
+            Self::Migration(e) => write!(f, "Save format error: {}", e),
+            Self::Unsupported(what) => write!(f, "Unsupported file: {}", what),
         }
     }
 }
 
 impl std::error::Error for WorldIoError {}
 
-/// Save a world definition to a RON file.
+/// A world save: the format version, then the world.
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    version: SaveVersion,
+    world: &'a WorldDefinition,
+}
+
+#[derive(Deserialize)]
+struct LoadedSave {
+    version: SaveVersion,
+    world: WorldDefinition,
+}
+
+/// Save a world definition in `format`, with the
+/// [current save version](SaveVersion::CURRENT).
+///
+/// Binary saves are the magic bytes `RBWORLD\0` then the zstd-compressed
+/// bincode of the same versioned save the RON format writes.
 ///
 /// # Arguments
 /// * `path` - File path to save to
 /// * `world` - World definition to save
-pub fn save_world(path: &Path, world: &WorldDefinition) -> Result<(), WorldIoError> {
-    let pretty_config = ron::ser::PrettyConfig::new()
-        .depth_limit(4)
-        .separate_tuple_members(true)
-        .enumerate_arrays(true);
+/// * `format` - RON text or compressed binary
+pub fn save_world(path: &Path, world: &WorldDefinition, format: WorldFormat) -> Result<(), WorldIoError> {
+    let save = SaveFile {
+        version: SaveVersion::CURRENT,
+        world,
+    };
 
-    let ron_string = ron::ser::to_string_pretty(world, pretty_config)?;
-    fs::write(path, ron_string)?;
+    match format {
+        WorldFormat::Ron => {
+            let pretty_config = ron::ser::PrettyConfig::new()
+                .depth_limit(5)
+                .separate_tuple_members(true)
+                .enumerate_arrays(true);
+            fs::write(path, ron::ser::to_string_pretty(&save, pretty_config)?)?;
+        }
+        WorldFormat::Binary => {
+            let mut file = fs::File::create(path)?;
+            file.write_all(BINARY_MAGIC)?;
+            let mut encoder = zstd::Encoder::new(file, BINARY_COMPRESSION_LEVEL)?;
+            bincode::serialize_into(&mut encoder, &save)?;
+            encoder.finish()?;
+        }
+    }
     Ok(())
 }
 
-/// Load a world definition from a RON file.
+/// Load a world definition saved in either [`WorldFormat`], told apart by
+/// the binary save's magic bytes rather than the extension.
+///
+/// RON saves in an older version are upgraded through the built-in
+/// [`MigrationRegistry`]; binary ones can't be, and saves from a newer
+/// build are refused. Inconsistencies such as off-map positions or dangling
+/// ids are repaired (see [`WorldDefinition::repair`]). Both are reported on
+/// stderr.
 ///
 /// # Arguments
 /// * `path` - File path to load from
 pub fn load_world(path: &Path) -> Result<WorldDefinition, WorldIoError> {
-    let contents = fs::read_to_string(path)?;
-    let world: WorldDefinition = ron::from_str(&contents)?;
+    let bytes = fs::read(path)?;
+    let mut world = match bytes.strip_prefix(BINARY_MAGIC) {
+        Some(compressed) => {
+            let save: LoadedSave = bincode::deserialize_from(zstd::Decoder::new(compressed)?)?;
+            match save.version {
+                SaveVersion::CURRENT => save.world,
+                found if found > SaveVersion::CURRENT => {
+                    return Err(MigrationError::TooNew { found, newest: SaveVersion::CURRENT }.into())
+                }
+                found => return Err(MigrationError::NoMigration { from: found }.into()),
+            }
+        }
+        None => {
+            let contents = String::from_utf8(bytes)
+                .map_err(|_| WorldIoError::Unsupported(format!("{} is neither RON nor a binary save", path.display())))?;
+            let version = save_version(&contents)?;
+            let (contents, applied) = MigrationRegistry::default().upgrade(&contents, version)?;
+            for migration in applied {
+                eprintln!("Note: upgraded {} from save format {}: {}", path.display(), version, migration);
+            }
+            ron::from_str::<LoadedSave>(&contents)?.world
+        }
+    };
+    for issue in world.repair() {
+        eprintln!("Warning: repaired {}: {}", path.display(), issue);
+    }
+    // A missing sketch leaves the continents procedural rather than failing the load
+    if let Some(sketch_path) = &world.sketch_path {
+        match import_heightmap(sketch_path) {
+            Ok(sketch) => world.sketch = Some(Arc::new(sketch)),
+            Err(e) => eprintln!("Warning: could not read sketch {}: {}", sketch_path.display(), e),
+        }
+    }
     Ok(world)
 }
 
+/// Save a world delta to a RON file.
+///
+/// Deltas are applied on top of a base save with
+/// [`WorldDefinition::apply_delta`].
+pub fn save_delta(path: &Path, delta: &WorldDelta) -> Result<(), WorldIoError> {
+    let ron_string = ron::ser::to_string_pretty(delta, ron::ser::PrettyConfig::new())?;
+    fs::write(path, ron_string)?;
+    Ok(())
+}
+
+/// Load a base world and apply a delta file on top of it.
+pub fn load_world_with_delta(base: &Path, delta: &Path) -> Result<WorldDefinition, WorldIoError> {
+    let mut world = load_world(base)?;
+    let delta: WorldDelta = ron::from_str(&fs::read_to_string(delta)?)?;
+    world.apply_delta(&delta);
+    Ok(world)
+}
+
+/// Load a biome registry: the built-in biomes with the file's entries
+/// applied on top (see [`BiomeRegistry::from_ron`]).
+pub fn load_biome_registry(path: &Path) -> Result<BiomeRegistry, WorldIoError> {
+    Ok(BiomeRegistry::from_ron(&fs::read_to_string(path)?)?)
+}
+
+/// Load culture naming: the built-in tongues with the file's entries
+/// replacing them (see [`NameData::from_ron`]).
+pub fn load_name_data(path: &Path) -> Result<NameData, WorldIoError> {
+    Ok(NameData::from_ron(&fs::read_to_string(path)?)?)
+}
+
 /// Ensure the worlds directory exists.
 pub fn ensure_worlds_dir() -> Result<(), std::io::Error> {
     fs::create_dir_all(WORLDS_DIR)
 }
 
-/// List all world files in the worlds directory.
+/// List all world files, in either format, in the worlds directory.
 pub fn list_worlds() -> Result<Vec<std::path::PathBuf>, std::io::Error> {
     let dir = Path::new(WORLDS_DIR);
     if !dir.exists() {
@@ -85,7 +296,9 @@ pub fn list_worlds() -> Result<Vec<std::path::PathBuf>, std::io::Error> {
     for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
-        if path.extension().and_then(|s| s.to_str()) == Some("ron") {
+        let extension = path.extension().and_then(|s| s.to_str());
+        let is_world = WorldFormat::all().iter().any(|format| Some(format.extension()) == extension);
+        if is_world && !is_chunk_delta(&path) {
             worlds.push(path);
         }
     }
@@ -96,36 +309,151 @@ pub fn list_worlds() -> Result<Vec<std::path::PathBuf>, std::io::Error> {
 
 /// Generate a filename from a world name.
 pub fn world_filename(name: &str) -> String {
+    format!("{}.ron", file_stem(name))
+}
+
+/// A world name made safe to use in filenames.
+pub(crate) fn file_stem(name: &str) -> String {
     let sanitized: String = name
         .chars()
         .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
         .collect();
-    format!("{}.ron", sanitized.to_lowercase())
+    sanitized.to_lowercase()
 }
 
-/// Get the full path for a world file.
-pub fn world_path(name: &str) -> std::path::PathBuf {
-    Path::new(WORLDS_DIR).join(world_filename(name))
+/// Get the full path for a world file saved in `format`.
+pub fn world_path(name: &str, format: WorldFormat) -> std::path::PathBuf {
+    Path::new(WORLDS_DIR).join(world_filename(name)).with_extension(format.extension())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rb_core::TileType;
     use tempfile::tempdir;
 
+    #[test]
+    fn shipped_biome_registry_loads() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..").join(BIOMES_PATH);
+        let biomes = load_biome_registry(&path).unwrap();
+        assert_eq!(biomes.get(TileType::Plains).name, "Plains");
+    }
+
+    #[test]
+    fn shipped_name_data_loads() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..").join(NAMES_PATH);
+        assert_eq!(load_name_data(&path).unwrap(), NameData::default());
+    }
+
     #[test]
     fn save_and_load_world() {
         let dir = tempdir().unwrap();
         let path = dir.path().join("test_world.ron");
 
         let world = WorldDefinition::default();
-        save_world(&path, &world).unwrap();
+        save_world(&path, &world, WorldFormat::Ron).unwrap();
 
         let loaded = load_world(&path).unwrap();
         assert_eq!(loaded.name, world.name);
         assert_eq!(loaded.seed, world.seed);
     }
 
+    #[test]
+    fn delta_round_trips_through_disk() {
+        let dir = tempdir().unwrap();
+        let base_path = dir.path().join("base.ron");
+        let delta_path = dir.path().join("base.delta.ron");
+
+        let base = WorldDefinition::default();
+        let mut edited = base.clone();
+        edited.cities.push(rb_world::City::new(
+            1,
+            "Harbor".into(),
+            rb_world::Point2D::new(10.0, 20.0),
+            rb_world::CityTier::Town,
+        ));
+
+        save_world(&base_path, &base, WorldFormat::Ron).unwrap();
+        save_delta(&delta_path, &base.diff(&edited)).unwrap();
+
+        let loaded = load_world_with_delta(&base_path, &delta_path).unwrap();
+        assert_eq!(loaded.cities, edited.cities);
+    }
+
+    #[test]
+    fn binary_saves_round_trip_smaller() {
+        let dir = tempdir().unwrap();
+        let mut world = WorldDefinition::default();
+        for id in [1, 2] {
+            let position = rb_world::Point2D::new(id as f64 * 100.0, 100.0);
+            world.cities.push(rb_world::City::new(id, format!("City {}", id), position, rb_world::CityTier::Town));
+        }
+        let mut road = rb_world::Road::new(1, (1, 2), rb_world::RoadType::Provincial);
+        road.waypoints = (0..5000).map(|i| rb_world::Point2D::new((i % 1000) as f64 + 0.25, 100.0)).collect();
+        world.roads.push(road);
+
+        let ron_path = dir.path().join("world.ron");
+        let binary_path = dir.path().join("world.rbw");
+        save_world(&ron_path, &world, WorldFormat::Ron).unwrap();
+        save_world(&binary_path, &world, WorldFormat::Binary).unwrap();
+        let size = |path: &Path| fs::metadata(path).unwrap().len();
+        assert!(size(&binary_path) * 4 < size(&ron_path));
+
+        // Told apart by content, not by extension
+        let renamed = dir.path().join("binary.ron");
+        fs::rename(&binary_path, &renamed).unwrap();
+        let loaded = load_world(&renamed).unwrap();
+        assert_eq!(loaded.cities, world.cities);
+        assert_eq!(loaded.roads[0].waypoints, world.roads[0].waypoints);
+    }
+
+    #[test]
+    fn legacy_saves_upgrade_and_newer_ones_are_refused() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("legacy.ron");
+
+        let world = WorldDefinition {
+            name: "Old Save".into(),
+            ..Default::default()
+        };
+        fs::write(&path, ron::ser::to_string_pretty(&world, ron::ser::PrettyConfig::new()).unwrap()).unwrap();
+        assert_eq!(load_world(&path).unwrap().name, "Old Save");
+
+        save_world(&path, &world, WorldFormat::Ron).unwrap();
+        let saved = fs::read_to_string(&path).unwrap();
+        assert_eq!(save_version(&saved).unwrap(), SaveVersion::CURRENT);
+
+        fs::write(&path, saved.replacen("version: 1", "version: 99", 1)).unwrap();
+        assert!(matches!(
+            load_world(&path),
+            Err(WorldIoError::Migration(MigrationError::TooNew { .. }))
+        ));
+    }
+
+    #[test]
+    fn load_repairs_broken_world() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("broken.ron");
+
+        let mut world = WorldDefinition::default();
+        world.cities.push(rb_world::City::new(
+            1,
+            "Nowhere".into(),
+            rb_world::Point2D::new(-10.0, 20.0),
+            rb_world::CityTier::Town,
+        ));
+        let mut faction =
+            rb_world::Faction::new(1, "Lost".into(), rb_world::CultureType::TwilightDweller);
+        faction.capital_id = Some(99);
+        world.factions.push(faction);
+        save_world(&path, &world, WorldFormat::Ron).unwrap();
+
+        let loaded = load_world(&path).unwrap();
+        assert!(loaded.validate().is_empty());
+        assert_eq!(loaded.cities[0].position.x, 0.0);
+        assert_eq!(loaded.factions[0].capital_id, None);
+    }
+
     #[test]
     fn world_filename_sanitizes() {
         assert_eq!(world_filename("My World"), "my_world.ron");
@@ -0,0 +1,80 @@
+//! Timings for the main generation phases at fixed sizes and seeds.
+//!
+//! Run with `cargo bench -p rb_world`. Compare against a saved baseline with
+//! `cargo bench -p rb_world -- --save-baseline main` / `--baseline main`.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rb_noise::{BiomeMap, LayerProgress};
+use rb_world::settlement_placement::place_settlements;
+use rb_world::{CivilizationConfig, CivilizationGenerator, Culture, WorldDefinition};
+
+const SEED: u32 = 42;
+const MACRO_SIZE: (usize, usize) = (256, 128);
+const MESO_SIZE: usize = 128;
+const MAX_SETTLEMENTS: usize = 20;
+
+fn terrain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("terrain");
+    group.sample_size(10);
+
+    group.bench_function("macro_generate_256x128", |b| {
+        b.iter(|| BiomeMap::generate(SEED, MACRO_SIZE.0, MACRO_SIZE.1))
+    });
+
+    group.bench_function("meso_generate_full_128", |b| {
+        b.iter(|| {
+            let progress = Arc::new(LayerProgress::new(MESO_SIZE * MESO_SIZE));
+            BiomeMap::generate_meso_full(
+                SEED,
+                0.0,
+                0.0,
+                64.0,
+                MESO_SIZE,
+                MACRO_SIZE.1 as f64,
+                1,
+                &progress,
+                None,
+            )
+        })
+    });
+
+    group.finish();
+}
+
+fn civilization(c: &mut Criterion) {
+    let biome_map = BiomeMap::generate(SEED, MACRO_SIZE.0, MACRO_SIZE.1);
+    let cultures = Culture::all_defaults();
+
+    // Cities and factions for the road and territory benches
+    let config = CivilizationConfig {
+        max_settlements: MAX_SETTLEMENTS,
+        ..Default::default()
+    };
+    let generator = CivilizationGenerator::new(SEED, config);
+    let mut world_def = WorldDefinition::default();
+    generator.generate(&biome_map, &mut world_def);
+
+    let mut group = c.benchmark_group("civilization");
+    group.sample_size(10);
+
+    group.bench_function("place_settlements", |b| {
+        b.iter(|| place_settlements(&biome_map, &cultures, SEED, MAX_SETTLEMENTS))
+    });
+
+    group.bench_function("generate_roads", |b| {
+        b.iter(|| generator.generate_roads(&biome_map, &world_def.cities))
+    });
+
+    group.bench_function("generate_territories", |b| {
+        b.iter(|| {
+            generator.generate_territories(&biome_map, &world_def.cities, &world_def.factions, &world_def.territory_locks)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, terrain, civilization);
+criterion_main!(benches);
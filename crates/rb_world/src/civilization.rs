@@ -1,16 +1,35 @@
 //! Civilization generation orchestrator.
 //!
 //! This module ties together all civilization generation:
-//! cultures, settlements, factions, roads, and territories.
+//! cultures, settlements, factions, roads, territories, economies,
+//! landmarks, sea routes and religions.
 
 use crate::culture::{Culture, CultureType};
 use crate::definition::{City, CityTier, Point2D, WorldDefinition};
+use crate::dynasty::generate_dynasties;
+use crate::economy::simulate_economy;
 use crate::faction::{Faction, FactionDisposition};
-use crate::roads::{terrain_movement_cost, Road, RoadType, TradeGood, TradeRoute};
-use crate::settlement_placement::place_settlements;
-use crate::territory::{terrain_influence_decay, TerritoryMap};
+use crate::history::{history_landmarks, HistoryConfig, HistoryGenerator};
+use crate::landmarks::place_landmarks;
+use crate::namegen::{NameData, NameGenerator, NameKind};
+use crate::religion::found_religions;
+use crate::roads::{calculate_trade_cost, Road, RoadGraph, RoadType, TradeGood, TradeRoute};
+use crate::sea_routes::generate_sea_routes;
+use crate::settlement_placement::{
+    apply_faction_names, place_settlements_with_symmetry, tag_crossroads,
+};
+use crate::territory::{influence_field, TerritoryLocks, TerritoryMap};
+use crate::trade_flow::simulate_trade_flows;
 use pathfinding::prelude::astar;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use rb_core::{rng_for, BiomeRegistry};
 use rb_noise::BiomeMap;
+use serde::{Deserialize, Serialize};
+
+/// Capitals scoring below this suitability for their faction's culture are
+/// considered mismatched and get reassigned.
+const CAPITAL_FIT_THRESHOLD: f64 = 0.5;
 
 /// Configuration for civilization generation.
 #[derive(Debug, Clone)]
@@ -25,6 +44,35 @@ pub struct CivilizationConfig {
     pub generate_territories: bool,
     /// Minimum influence for territory expansion.
     pub territory_threshold: f64,
+    /// Whether to scatter landmarks (replaces any existing ones).
+    pub generate_landmarks: bool,
+    /// Maximum number of landmarks to place.
+    pub max_landmarks: usize,
+    /// Longest water crossing (world units) a ferry may bridge when no land
+    /// path exists. 0 disables ferries.
+    pub max_ferry_span: f64,
+    /// Restyle capital and town names after their owning faction.
+    pub faction_names: bool,
+    /// Compute each faction's influence independently in parallel and take
+    /// the strongest per cell, instead of one shared flood fill.
+    pub parallel_territories: bool,
+    /// Movement costs, influence decay and trade goods of each biome.
+    pub biomes: BiomeRegistry,
+    /// Each culture's tongue for settlement, faction and landmark names.
+    pub names: NameData,
+    /// Years of wars, conquest and expansion to play out once factions are
+    /// founded (0 for none); see [`HistoryGenerator`].
+    pub history_years: u32,
+    /// Found religions and spread them between settlements, raising their
+    /// temples.
+    pub generate_religions: bool,
+    /// Chart shipping lanes between settlements with ports. Ports are
+    /// landmarks, so this needs `generate_landmarks`.
+    pub generate_sea_routes: bool,
+    /// Size every settlement and work out its economy once territories are
+    /// known, instead of leaving each at its tier's midpoint, then the goods
+    /// carried between them.
+    pub simulate_economy: bool,
 }
 
 impl Default for CivilizationConfig {
@@ -35,17 +83,44 @@ impl Default for CivilizationConfig {
             generate_trade_routes: true,
             generate_territories: true,
             territory_threshold: 0.1,
+            generate_landmarks: false,
+            max_landmarks: 24,
+            max_ferry_span: 32.0,
+            faction_names: false,
+            parallel_territories: false,
+            biomes: BiomeRegistry::default(),
+            names: NameData::default(),
+            history_years: 0,
+            generate_religions: false,
+            generate_sea_routes: false,
+            simulate_economy: true,
         }
     }
 }
 
 /// Result of civilization generation.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CivilizationResult {
     pub settlements_placed: usize,
     pub factions_created: usize,
     pub roads_built: usize,
     pub trade_routes_created: usize,
+    pub landmarks_placed: usize,
+    /// Settlements outside the largest road-connected group.
+    pub disconnected_settlements: usize,
+    /// Entries in the world's timeline.
+    #[serde(default)]
+    pub historical_events: usize,
+    /// Rulers across every faction's line.
+    #[serde(default)]
+    pub rulers: usize,
+    #[serde(default)]
+    pub religions: usize,
+    #[serde(default)]
+    pub sea_routes: usize,
+    /// Settlement pairs goods are carried between.
+    #[serde(default)]
+    pub trade_flows: usize,
 }
 
 /// Main civilization generator.
@@ -66,30 +141,62 @@ impl CivilizationGenerator {
         biome_map: &BiomeMap,
         world_def: &mut WorldDefinition,
     ) -> CivilizationResult {
-        // Step 1: Create default cultures
-        let cultures = Culture::all_defaults();
-        world_def.cultures = cultures.clone();
+        // Step 1: Cultures: the world's authored ones, or the defaults
+        if world_def.cultures.is_empty() {
+            world_def.cultures = Culture::all_defaults();
+        }
+        let cultures = world_def.cultures.clone();
+
+        // One generator for the whole world, so no two things share a name;
+        // authored regions keep theirs
+        let mut names = NameGenerator::new(&self.config.names);
+        for region in &world_def.regions {
+            names.reserve(&region.name);
+        }
 
         // Step 2: Place settlements
-        let placement_result = place_settlements(
+        let placement_result = place_settlements_with_symmetry(
             biome_map,
             &cultures,
+            &world_def.landmarks,
+            &mut names,
             self.seed,
             self.config.max_settlements,
+            world_def.symmetry,
         );
         world_def.cities = placement_result.settlements;
 
         // Step 3: Create factions and assign settlements
-        let factions = self.create_factions(&world_def.cities, self.seed);
+        let factions = self.create_factions(&world_def.cities, &cultures, biome_map, &mut names, self.seed);
         world_def.factions = factions;
+        if self.config.faction_names {
+            apply_faction_names(&mut world_def.cities, &world_def.factions, self.seed);
+        }
+
+        // Step 3b: Play out the years after founding, before anything is
+        // built between the settlements that survive them
+        world_def.history.clear();
+        if self.config.history_years > 0 {
+            let config = HistoryConfig {
+                years: self.config.history_years,
+                ..Default::default()
+            };
+            HistoryGenerator::new(self.seed, config).simulate(biome_map, world_def, &mut names);
+        }
+        let (dynasties, rulers) =
+            generate_dynasties(world_def, &world_def.history, self.config.history_years, self.seed, &mut names);
+        world_def.dynasties = dynasties;
+        world_def.rulers = rulers;
 
         // Step 4: Generate road network
-        let roads_built = if self.config.generate_roads {
+        let (roads_built, disconnected_settlements) = if self.config.generate_roads {
             let roads = self.generate_roads(biome_map, &world_def.cities);
             world_def.roads = roads;
-            world_def.roads.len()
+            tag_crossroads(&mut world_def.cities, &world_def.roads);
+            let disconnected = disconnected_settlements(&world_def.roads, &world_def.cities);
+            (world_def.roads.len(), disconnected.len())
         } else {
-            0
+            (0, 0)
         };
 
         // Step 5: Generate trade routes
@@ -112,22 +219,77 @@ impl CivilizationGenerator {
                 biome_map,
                 &world_def.cities,
                 &world_def.factions,
+                &world_def.territory_locks,
             );
             world_def.territory_cache = Some(territory);
         }
 
+        // Step 6b: Grow each settlement by how well placed it turned out,
+        // reweigh the trade between them and send goods where they're short
+        world_def.trade_flows.clear();
+        if self.config.simulate_economy {
+            simulate_economy(biome_map, &self.config.biomes, world_def);
+            world_def.trade_flows = simulate_trade_flows(world_def);
+        }
+
+        // Step 7: Scatter landmarks, and ruins where history razed settlements
+        let landmarks_placed = if self.config.generate_landmarks {
+            world_def.landmarks = place_landmarks(
+                biome_map,
+                &world_def.cities,
+                &cultures,
+                &mut names,
+                self.seed,
+                self.config.max_landmarks,
+            );
+            let first_id = world_def.landmarks.len() as u32 + 1;
+            let ruins = history_landmarks(&world_def.history, first_id);
+            world_def.landmarks.extend(ruins);
+            world_def.landmarks.len()
+        } else {
+            0
+        };
+
+        // Step 7b: Chart shipping lanes between the ports
+        world_def.sea_routes.clear();
+        if self.config.generate_sea_routes {
+            world_def.sea_routes = generate_sea_routes(biome_map, world_def);
+        }
+
+        // Step 8: Found religions, which spread along the roads, sea lanes
+        // and through the realms laid out above
+        world_def.religions.clear();
+        if self.config.generate_religions {
+            found_religions(biome_map, world_def, &mut names, self.seed);
+        }
+
         CivilizationResult {
             settlements_placed: world_def.cities.len(),
             factions_created: world_def.factions.len(),
             roads_built,
             trade_routes_created,
+            landmarks_placed,
+            disconnected_settlements,
+            historical_events: world_def.history.len(),
+            rulers: world_def.rulers.len(),
+            religions: world_def.religions.len(),
+            sea_routes: world_def.sea_routes.len(),
+            trade_flows: world_def.trade_flows.len(),
         }
     }
 
     /// Create factions from settled cultures.
-    fn create_factions(&self, cities: &[City], seed: u32) -> Vec<Faction> {
+    fn create_factions(
+        &self,
+        cities: &[City],
+        cultures: &[Culture],
+        biome_map: &BiomeMap,
+        names: &mut NameGenerator,
+        seed: u32,
+    ) -> Vec<Faction> {
         let mut factions = Vec::new();
         let mut faction_id = 1u32;
+        let mut rng = rng_for(seed, "faction_titles");
 
         // Group cities by culture (determined by their position in the biome map)
         // For now, assign culture based on city name patterns or just distribute
@@ -142,11 +304,8 @@ impl CivilizationGenerator {
                 .or_else(|| cities.iter().find(|c| c.tier == CityTier::Town));
 
             if capital.is_some() {
-                let mut faction = Faction::new(
-                    faction_id,
-                    culture_type.default_faction_name().to_string(),
-                    *culture_type,
-                );
+                let name = names.name(*culture_type, NameKind::Faction, &mut rng);
+                let mut faction = Faction::new(faction_id, name, *culture_type);
                 faction.disposition = FactionDisposition::from_culture_and_seed(
                     *culture_type,
                     seed.wrapping_add(faction_id),
@@ -169,11 +328,17 @@ impl CivilizationGenerator {
             }
         }
 
+        validate_capitals(&mut factions, cities, cultures, biome_map);
+
         factions
     }
 
     /// Generate road network using A* pathfinding.
-    fn generate_roads(&self, biome_map: &BiomeMap, cities: &[City]) -> Vec<Road> {
+    ///
+    /// Pairs with no land path are bridged by a ferry when the gap is short
+    /// enough; otherwise they're skipped, and a city that can't be reached
+    /// from the network starts a network of its own.
+    pub fn generate_roads(&self, biome_map: &BiomeMap, cities: &[City]) -> Vec<Road> {
         let mut roads = Vec::new();
         let mut road_id = 1u32;
 
@@ -181,6 +346,7 @@ impl CivilizationGenerator {
         // Start with capitals, then connect to nearest neighbors
         let mut connected: Vec<u32> = Vec::new();
         let mut unconnected: Vec<u32> = cities.iter().map(|c| c.id).collect();
+        let mut failed: HashSet<(u32, u32)> = HashSet::new();
 
         if unconnected.is_empty() {
             return roads;
@@ -197,6 +363,9 @@ impl CivilizationGenerator {
                 let conn_city = cities.iter().find(|c| c.id == conn_id).unwrap();
 
                 for &unconn_id in &unconnected {
+                    if failed.contains(&(conn_id, unconn_id)) {
+                        continue;
+                    }
                     let unconn_city = cities.iter().find(|c| c.id == unconn_id).unwrap();
 
                     let dx = unconn_city.position.x - conn_city.position.x;
@@ -213,10 +382,10 @@ impl CivilizationGenerator {
                 let from_city = cities.iter().find(|c| c.id == from_id).unwrap();
                 let to_city = cities.iter().find(|c| c.id == to_id).unwrap();
 
-                // Find path using A*
-                let waypoints = self.find_path(biome_map, from_city.position, to_city.position);
-
-                let road_type = determine_road_type(from_city.tier, to_city.tier);
+                let Some((waypoints, road_type)) = self.route(biome_map, from_city, to_city) else {
+                    failed.insert((from_id, to_id));
+                    continue;
+                };
 
                 roads.push(Road {
                     id: road_id,
@@ -230,15 +399,28 @@ impl CivilizationGenerator {
                 unconnected.retain(|&id| id != to_id);
                 connected.push(to_id);
             } else {
-                break;
+                // Nothing reachable from the current network - seed a new one
+                connected.push(unconnected.remove(0));
             }
         }
 
         roads
     }
 
-    /// Find path between two points using A*.
-    fn find_path(&self, biome_map: &BiomeMap, from: Point2D, to: Point2D) -> Vec<Point2D> {
+    /// Route a road from `from` to `to` with A*, falling back to a ferry
+    /// across a gap of up to `max_ferry_span`. Returns its waypoints and
+    /// type, or `None` if the cities can't be joined.
+    pub fn route(&self, biome_map: &BiomeMap, from: &City, to: &City) -> Option<(Vec<Point2D>, RoadType)> {
+        if let Some(path) = self.find_path(biome_map, from.position, to.position) {
+            return Some((path, determine_road_type(from.tier, to.tier)));
+        }
+        let span = (to.position.x - from.position.x).hypot(to.position.y - from.position.y);
+        (span <= self.config.max_ferry_span).then(|| (vec![from.position, to.position], RoadType::Ferry))
+    }
+
+    /// Find path between two points using A*, or `None` if water or
+    /// impassable terrain separates them.
+    fn find_path(&self, biome_map: &BiomeMap, from: Point2D, to: Point2D) -> Option<Vec<Point2D>> {
         let start = (from.x as i32, from.y as i32);
         let goal = (to.x as i32, to.y as i32);
 
@@ -260,16 +442,14 @@ impl CivilizationGenerator {
                             && (nx as usize) < biome_map.width
                             && (ny as usize) < biome_map.height
                         {
-                            if let Some(biome) = biome_map.get_biome(nx as usize, ny as usize) {
-                                let cost = terrain_movement_cost(biome);
-                                if cost.is_finite() {
-                                    let move_cost = if dx != 0 && dy != 0 {
-                                        (cost * 1.414) as i32 // Diagonal
-                                    } else {
-                                        cost as i32
-                                    };
-                                    neighbors.push(((nx, ny), move_cost.max(1)));
-                                }
+                            let cost = calculate_trade_cost(&self.config.biomes, biome_map, nx as usize, ny as usize);
+                            if cost.is_finite() {
+                                let move_cost = if dx != 0 && dy != 0 {
+                                    (cost * 1.414) as i32 // Diagonal
+                                } else {
+                                    cost as i32
+                                };
+                                neighbors.push(((nx, ny), move_cost.max(1)));
                             }
                         }
                     }
@@ -278,21 +458,13 @@ impl CivilizationGenerator {
             },
             |&(x, y)| {
                 // Heuristic: Manhattan distance
-                ((x - goal.0).abs() + (y - goal.1).abs()) as i32
+                (x - goal.0).abs() + (y - goal.1).abs()
             },
             |&pos| pos == goal,
         );
 
-        match result {
-            Some((path, _cost)) => {
-                // Simplify path - only keep waypoints where direction changes
-                simplify_path(&path)
-            }
-            None => {
-                // No path found - return direct line
-                vec![from, to]
-            }
-        }
+        // Simplify path - only keep waypoints where direction changes
+        result.map(|(path, _cost)| simplify_path(&path))
     }
 
     /// Generate trade routes between faction capitals.
@@ -305,6 +477,7 @@ impl CivilizationGenerator {
     ) -> Vec<TradeRoute> {
         let mut trade_routes = Vec::new();
         let mut route_id = 1u32;
+        let graph = RoadGraph::new(roads);
 
         // Create trade routes between each pair of factions
         for (i, faction_a) in factions.iter().enumerate() {
@@ -312,12 +485,10 @@ impl CivilizationGenerator {
                 let Some(cap_a_id) = faction_a.capital_id else { continue };
                 let Some(cap_b_id) = faction_b.capital_id else { continue };
 
-                // Find roads that connect these capitals
-                let route_roads = find_route_roads(roads, cap_a_id, cap_b_id);
-
-                if route_roads.is_empty() {
-                    continue;
-                }
+                // Shortest way between the capitals over the road network,
+                // calling at every settlement on the way
+                let Some(path) = graph.shortest_path(cap_a_id, cap_b_id) else { continue };
+                let route_roads = graph.path_roads(&path);
 
                 // Determine goods based on faction regions
                 let cap_a = cities.iter().find(|c| c.id == cap_a_id);
@@ -329,10 +500,10 @@ impl CivilizationGenerator {
                     let biome_b = biome_map.get_biome(b.position.x as usize, b.position.y as usize);
 
                     if let Some(ba) = biome_a {
-                        goods.extend(TradeGood::from_biome(ba));
+                        goods.extend(TradeGood::from_biome(&self.config.biomes, ba));
                     }
                     if let Some(bb) = biome_b {
-                        for g in TradeGood::from_biome(bb) {
+                        for g in TradeGood::from_biome(&self.config.biomes, bb) {
                             if !goods.contains(&g) {
                                 goods.push(g);
                             }
@@ -340,14 +511,16 @@ impl CivilizationGenerator {
                     }
                 }
 
-                trade_routes.push(TradeRoute {
+                let mut route = TradeRoute {
                     id: route_id,
                     road_ids: route_roads,
                     faction_ids: vec![faction_a.id, faction_b.id],
-                    settlement_ids: vec![cap_a_id, cap_b_id],
+                    settlement_ids: path,
                     goods,
-                    importance: 0.7, // Capital-to-capital routes are important
-                });
+                    importance: 0.0,
+                };
+                route.importance = route.compute_importance(cities);
+                trade_routes.push(route);
                 route_id += 1;
             }
         }
@@ -355,37 +528,38 @@ impl CivilizationGenerator {
         trade_routes
     }
 
-    /// Generate territory map via flood-fill.
-    fn generate_territories(
+    /// Generate territory map via flood-fill, keeping the cells in `locks`.
+    pub fn generate_territories(
         &self,
         biome_map: &BiomeMap,
         cities: &[City],
         factions: &[Faction],
+        locks: &TerritoryLocks,
     ) -> TerritoryMap {
+        if self.config.parallel_territories {
+            let fields = faction_influence_fields(
+                &self.config.biomes,
+                biome_map,
+                cities,
+                factions,
+                locks,
+                self.config.territory_threshold,
+            );
+            let mut territory = TerritoryMap::from_influence_fields(biome_map.width, biome_map.height, &fields);
+            locks.apply(&mut territory);
+            return territory;
+        }
+
         let mut territory = TerritoryMap::new(biome_map.width, biome_map.height);
+        let locked = locks.to_grid(biome_map.width, biome_map.height);
 
-        // Initialize settlements with faction ownership
+        // Initialize settlements with faction ownership, then the locked cells
         for faction in factions {
-            for &city_id in &faction.settlement_ids {
-                if let Some(city) = cities.iter().find(|c| c.id == city_id) {
-                    let x = city.position.x as usize;
-                    let y = city.position.y as usize;
-                    if x < territory.width && y < territory.height {
-                        // Capital has stronger initial influence
-                        let influence = if faction.capital_id == Some(city_id) {
-                            1.0
-                        } else {
-                            match city.tier {
-                                CityTier::Capital => 1.0,
-                                CityTier::Town => 0.8,
-                                CityTier::Village => 0.5,
-                            }
-                        };
-                        territory.set(x, y, faction.id, influence);
-                    }
-                }
+            for (x, y, influence) in territory_seeds(faction, cities) {
+                territory.set(x, y, faction.id, influence);
             }
         }
+        locks.apply(&mut territory);
 
         // Flood-fill expansion
         let mut changed = true;
@@ -398,7 +572,7 @@ impl CivilizationGenerator {
 
             for y in 0..territory.height {
                 for x in 0..territory.width {
-                    if territory.is_claimed(x, y) {
+                    if territory.is_claimed(x, y) || locked[y * territory.width + x].is_some() {
                         continue;
                     }
 
@@ -408,7 +582,7 @@ impl CivilizationGenerator {
                     };
 
                     // Skip impassable terrain
-                    let decay = terrain_influence_decay(biome);
+                    let decay = self.config.biomes.influence_decay(biome);
                     if decay == 0.0 {
                         continue;
                     }
@@ -441,6 +615,173 @@ impl CivilizationGenerator {
     }
 }
 
+/// Starting influence `(x, y, strength)` at each of a faction's settlements.
+fn territory_seeds(faction: &Faction, cities: &[City]) -> Vec<(usize, usize, f64)> {
+    faction
+        .settlement_ids
+        .iter()
+        .filter_map(|&city_id| cities.iter().find(|c| c.id == city_id))
+        .map(|city| {
+            // Capital has stronger initial influence
+            let influence = if faction.capital_id == Some(city.id) {
+                1.0
+            } else {
+                match city.tier {
+                    CityTier::Capital => 1.0,
+                    CityTier::Town => 0.8,
+                    CityTier::Village => 0.5,
+                }
+            };
+            (city.position.x as usize, city.position.y as usize, influence)
+        })
+        .collect()
+}
+
+/// Influence field of every faction, computed in parallel.
+///
+/// Each field is independent of the others, so besides feeding the combined
+/// territory map they show where factions overlap. Cells in `locks` seed
+/// their owner's field and are closed to everyone else's.
+pub fn faction_influence_fields(
+    registry: &BiomeRegistry,
+    biome_map: &BiomeMap,
+    cities: &[City],
+    factions: &[Faction],
+    locks: &TerritoryLocks,
+    threshold: f64,
+) -> Vec<(u32, Vec<f64>)> {
+    let width = biome_map.width;
+    let locked = locks.to_grid(width, biome_map.height);
+    factions
+        .par_iter()
+        .map(|faction| {
+            let blocked = |idx: usize| locked[idx].is_some_and(|owner| owner != faction.id);
+            let mut seeds = territory_seeds(faction, cities);
+            seeds.retain(|&(x, y, _)| !blocked(y * width + x));
+            seeds.extend(locks.seeds(faction.id));
+            let field = influence_field(
+                registry,
+                &biome_map.biomes,
+                width,
+                biome_map.height,
+                &seeds,
+                threshold,
+                blocked,
+            );
+            (faction.id, field)
+        })
+        .collect()
+}
+
+/// Suitability of a city's site for a culture.
+fn capital_fit(culture: &Culture, city: &City, biome_map: &BiomeMap) -> f64 {
+    let x = city.position.x as usize;
+    let y = city.position.y as usize;
+    match (
+        biome_map.get_biome(x, y),
+        biome_map.get_temperature(x, y),
+        biome_map.get_continentalness(x, y),
+    ) {
+        (Some(biome), Some(temp), Some(cont)) => culture.calculate_suitability(biome, temp, cont),
+        _ => 0.0,
+    }
+}
+
+/// Make sure each faction's capital sits in terrain its culture can live in.
+///
+/// Capitals that fit (or have no better option) are kept. Factions without a
+/// fitting capital are matched greedily, best score first, to the remaining
+/// capitals and towns; the chosen city moves to the faction if another owned it.
+fn validate_capitals(
+    factions: &mut [Faction],
+    cities: &[City],
+    cultures: &[Culture],
+    biome_map: &BiomeMap,
+) {
+    let culture_of = |faction: &Faction| {
+        cultures
+            .iter()
+            .find(|c| c.culture_type == faction.culture)
+            .cloned()
+            .unwrap_or_else(|| Culture::from_type(faction.culture))
+    };
+
+    // Keep capitals that already fit
+    let mut claimed: Vec<u32> = Vec::new();
+    let mut mismatched: Vec<usize> = Vec::new();
+    for (i, faction) in factions.iter().enumerate() {
+        let fits = faction
+            .capital_id
+            .and_then(|id| cities.iter().find(|c| c.id == id))
+            .is_some_and(|city| {
+                capital_fit(&culture_of(faction), city, biome_map) >= CAPITAL_FIT_THRESHOLD
+            });
+        if fits {
+            claimed.push(faction.capital_id.unwrap());
+        } else {
+            mismatched.push(i);
+        }
+    }
+
+    // Score every mismatched faction against every unclaimed capital/town
+    let mut pairs: Vec<(usize, u32, f64)> = Vec::new();
+    for &i in &mismatched {
+        let culture = culture_of(&factions[i]);
+        for city in cities {
+            if matches!(city.tier, CityTier::Capital | CityTier::Town) && !claimed.contains(&city.id) {
+                pairs.push((i, city.id, capital_fit(&culture, city, biome_map)));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.0.cmp(&b.0)).then(a.1.cmp(&b.1)));
+
+    let mut assigned: Vec<usize> = Vec::new();
+    for (i, city_id, score) in pairs {
+        if assigned.contains(&i) || claimed.contains(&city_id) {
+            continue;
+        }
+        // Only move the capital if it's an improvement
+        let current = factions[i]
+            .capital_id
+            .and_then(|id| cities.iter().find(|c| c.id == id))
+            .map(|city| capital_fit(&culture_of(&factions[i]), city, biome_map))
+            .unwrap_or(f64::NEG_INFINITY);
+        if score <= current {
+            continue;
+        }
+
+        for other in factions.iter_mut() {
+            other.settlement_ids.retain(|&id| id != city_id);
+            if other.capital_id == Some(city_id) {
+                other.capital_id = None;
+            }
+        }
+        factions[i].set_capital(city_id);
+        claimed.push(city_id);
+        assigned.push(i);
+    }
+}
+
+/// Settlements outside the largest road-connected group, sorted by ID.
+pub fn disconnected_settlements(roads: &[Road], cities: &[City]) -> Vec<u32> {
+    let components = RoadGraph::new(roads)
+        .with_cities(cities.iter().map(|c| c.id))
+        .connected_components();
+    let Some(largest) = components.iter().map(Vec::len).max() else {
+        return Vec::new();
+    };
+    let main = components.iter().position(|c| c.len() == largest).unwrap();
+
+    let mut isolated: Vec<u32> = components
+        .into_iter()
+        .enumerate()
+        .filter(|&(i, _)| i != main)
+        .flat_map(|(_, component)| component)
+        .collect();
+    isolated.sort_unstable();
+    isolated
+}
+
 /// Determine road type based on connected city tiers.
 fn determine_road_type(tier_a: CityTier, tier_b: CityTier) -> RoadType {
     match (tier_a, tier_b) {
@@ -454,7 +795,7 @@ fn determine_road_type(tier_a: CityTier, tier_b: CityTier) -> RoadType {
 }
 
 /// Simplify a path by keeping only direction changes.
-fn simplify_path(path: &[(i32, i32)]) -> Vec<Point2D> {
+pub(crate) fn simplify_path(path: &[(i32, i32)]) -> Vec<Point2D> {
     if path.len() < 2 {
         return path
             .iter()
@@ -484,36 +825,153 @@ fn simplify_path(path: &[(i32, i32)]) -> Vec<Point2D> {
     result
 }
 
-/// Find roads connecting two settlements (simplified - returns direct roads).
-fn find_route_roads(roads: &[Road], from_id: u32, to_id: u32) -> Vec<u32> {
-    // Simplified: just find direct road if it exists
-    for road in roads {
-        if (road.connects.0 == from_id && road.connects.1 == to_id)
-            || (road.connects.0 == to_id && road.connects.1 == from_id)
-        {
-            return vec![road.id];
-        }
-    }
-
-    // TODO: Implement multi-hop route finding
-    Vec::new()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rb_core::TileType;
+    use rb_noise::LayerValue;
 
     #[test]
     fn generator_creates_factions() {
         let biome_map = BiomeMap::generate(42, 256, 128);
-        let mut world_def = WorldDefinition::default();
-        world_def.seed = 42;
+        let mut world_def = WorldDefinition {
+            seed: 42,
+            ..Default::default()
+        };
 
         let generator = CivilizationGenerator::new(42, CivilizationConfig::default());
         let result = generator.generate(&biome_map, &mut world_def);
 
         assert!(result.settlements_placed > 0);
         assert!(result.factions_created > 0);
+        assert_eq!(world_def.cultures, Culture::all_defaults());
+
+        // Routes run capital to capital, over one road per hop
+        let capitals: Vec<u32> = world_def.factions.iter().filter_map(|f| f.capital_id).collect();
+        for route in &world_def.trade_routes {
+            assert_eq!(route.road_ids.len() + 1, route.settlement_ids.len());
+            assert!(capitals.contains(&route.settlement_ids[0]));
+            assert!(capitals.contains(route.settlement_ids.last().unwrap()));
+        }
+
+        // Authored cultures are kept and settled instead of the built-ins
+        let mut hermits = Culture::stone_born();
+        hermits.name = "Hermits".into();
+        world_def.cultures = vec![hermits.clone()];
+        let result = generator.generate(&biome_map, &mut world_def);
+        assert!(result.settlements_placed > 0);
+        assert_eq!(world_def.cultures, [hermits]);
+    }
+
+    #[test]
+    fn parallel_territories_match_flood_fill() {
+        // Two plains separated by a sea channel, with a forest belt in the east
+        let mut biome_map = BiomeMap::generate(42, 64, 32);
+        biome_map.biomes.fill(TileType::Plains);
+        for y in 0..32 {
+            biome_map.biomes[y * 64 + 30] = TileType::Sea;
+            biome_map.biomes[y * 64 + 40] = TileType::Forest;
+        }
+
+        let cities = vec![
+            City::new(1, "West".into(), Point2D::new(10.0, 16.0), CityTier::Capital),
+            City::new(2, "East".into(), Point2D::new(52.0, 10.0), CityTier::Capital),
+            City::new(3, "Outpost".into(), Point2D::new(44.0, 26.0), CityTier::Village),
+        ];
+        let mut factions = vec![
+            Faction::new(1, "A".into(), CultureType::TwilightDweller),
+            Faction::new(2, "B".into(), CultureType::StoneBorn),
+        ];
+        factions[0].add_settlement(1);
+        factions[0].set_capital(1);
+        factions[1].add_settlement(2);
+        factions[1].add_settlement(3);
+        factions[1].set_capital(2);
+
+        let sequential = CivilizationGenerator::new(42, CivilizationConfig::default())
+            .generate_territories(&biome_map, &cities, &factions, &TerritoryLocks::default());
+        let parallel_config = CivilizationConfig {
+            parallel_territories: true,
+            ..Default::default()
+        };
+        let parallel = CivilizationGenerator::new(42, parallel_config)
+            .generate_territories(&biome_map, &cities, &factions, &TerritoryLocks::default());
+
+        let mismatched = sequential
+            .ownership
+            .iter()
+            .zip(&parallel.ownership)
+            .filter(|(a, b)| a != b)
+            .count();
+        let max_influence_diff = sequential
+            .influence
+            .iter()
+            .zip(&parallel.influence)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        assert!(parallel.total_claimed_area() > 0);
+        assert!(
+            mismatched * 20 <= sequential.ownership.len(),
+            "{} of {} cells differ",
+            mismatched,
+            sequential.ownership.len()
+        );
+        // The sweep can settle on a weaker path than the best one
+        assert!(max_influence_diff < 0.1, "Influence differs by {}", max_influence_diff);
+    }
+
+    #[test]
+    fn locked_cells_hold_against_both_flood_fills() {
+        let mut biome_map = BiomeMap::generate(42, 64, 32);
+        biome_map.biomes.fill(TileType::Plains);
+        let cities = vec![
+            City::new(1, "West".into(), Point2D::new(10.0, 16.0), CityTier::Capital),
+            City::new(2, "East".into(), Point2D::new(52.0, 16.0), CityTier::Capital),
+        ];
+        let mut factions = vec![
+            Faction::new(1, "A".into(), CultureType::TwilightDweller),
+            Faction::new(2, "B".into(), CultureType::StoneBorn),
+        ];
+        for (faction, city) in factions.iter_mut().zip([1, 2]) {
+            faction.add_settlement(city);
+            faction.set_capital(city);
+        }
+
+        // A wall of B's land close to A's capital, and a patch kept wild
+        let mut locks = TerritoryLocks::default();
+        for y in 0..32 {
+            locks.set(20, y, Some(2));
+        }
+        locks.set(8, 16, Some(0));
+        locks.set(9, 16, Some(0));
+
+        for parallel_territories in [false, true] {
+            let config = CivilizationConfig { parallel_territories, ..Default::default() };
+            let territory = CivilizationGenerator::new(42, config)
+                .generate_territories(&biome_map, &cities, &factions, &locks);
+            assert!((0..32).all(|y| territory.get_owner(20, y) == 2));
+            assert!((21..64).all(|x| territory.get_owner(x, 4) != 1));
+            assert_eq!(territory.get_owner(8, 16), 0);
+            assert_eq!(territory.get_owner(10, 16), 1);
+        }
+    }
+
+    #[test]
+    fn benched_phases_run_at_tiny_size() {
+        // Same calls as benches/generation.rs, small enough for a debug build
+        let biome_map = BiomeMap::generate(42, 64, 32);
+        let progress = std::sync::Arc::new(rb_noise::LayerProgress::new(16 * 16));
+        BiomeMap::generate_meso_full(42, 0.0, 0.0, 16.0, 16, 32.0, 1, &progress, None);
+
+        let cultures = Culture::all_defaults();
+        let mut names = NameGenerator::new(&NameData::default());
+        let cities =
+            place_settlements_with_symmetry(&biome_map, &cultures, &[], &mut names, 42, 4, Default::default())
+                .settlements;
+        let generator = CivilizationGenerator::new(42, CivilizationConfig::default());
+        let factions = generator.create_factions(&cities, &cultures, &biome_map, &mut names, 42);
+        generator.generate_roads(&biome_map, &cities);
+        generator.generate_territories(&biome_map, &cities, &factions, &TerritoryLocks::default());
     }
 
     #[test]
@@ -527,4 +985,92 @@ mod tests {
             RoadType::Trail
         );
     }
+
+    #[test]
+    fn frost_kin_capital_avoids_desert() {
+        let mut biome_map = BiomeMap::generate(42, 64, 32);
+        let mut set_site = |x: usize, y: usize, biome: TileType, temp: f64| {
+            let idx = y * biome_map.width + x;
+            biome_map.biomes[idx] = biome;
+            biome_map.temperature[idx] = LayerValue::from_f64(temp);
+            biome_map.continentalness[idx] = 0.2;
+        };
+        set_site(10, 10, TileType::Desert, 60.0);
+        set_site(50, 5, TileType::Snow, -20.0);
+        set_site(20, 20, TileType::Plains, 20.0);
+        set_site(30, 20, TileType::Plains, 20.0);
+        set_site(40, 20, TileType::Plains, 20.0);
+
+        // Round-robin hands the desert capital (index 1) to FrostKin
+        let cities = vec![
+            City::new(1, "A".into(), Point2D::new(20.0, 20.0), CityTier::Town),
+            City::new(2, "Dune".into(), Point2D::new(10.0, 10.0), CityTier::Capital),
+            City::new(3, "B".into(), Point2D::new(30.0, 20.0), CityTier::Town),
+            City::new(4, "C".into(), Point2D::new(40.0, 20.0), CityTier::Town),
+            City::new(5, "Frost".into(), Point2D::new(50.0, 5.0), CityTier::Town),
+        ];
+
+        let generator = CivilizationGenerator::new(42, CivilizationConfig::default());
+        let factions = generator.create_factions(
+            &cities,
+            &Culture::all_defaults(),
+            &biome_map,
+            &mut NameGenerator::new(&NameData::default()),
+            42,
+        );
+
+        let frost = factions.iter().find(|f| f.culture == CultureType::FrostKin).unwrap();
+        let capital = cities.iter().find(|c| Some(c.id) == frost.capital_id).unwrap();
+        let biome = biome_map
+            .get_biome(capital.position.x as usize, capital.position.y as usize)
+            .unwrap();
+        assert!(
+            !matches!(biome, TileType::Desert | TileType::Sahara),
+            "FrostKin capital {} is in {:?}",
+            capital.name,
+            biome
+        );
+        assert!(frost.settlement_ids.contains(&capital.id));
+    }
+
+    /// 64x32 plains split by a sea strait at x = 30..40.
+    fn strait_map() -> BiomeMap {
+        let mut biome_map = BiomeMap::generate(42, 64, 32);
+        for y in 0..biome_map.height {
+            for x in 0..biome_map.width {
+                let idx = y * biome_map.width + x;
+                biome_map.biomes[idx] = if (30..40).contains(&x) {
+                    TileType::Sea
+                } else {
+                    TileType::Plains
+                };
+            }
+        }
+        biome_map
+    }
+
+    #[test]
+    fn island_city_reported_disconnected() {
+        let biome_map = strait_map();
+        let cities = vec![
+            City::new(1, "West".into(), Point2D::new(10.0, 10.0), CityTier::Capital),
+            City::new(2, "South".into(), Point2D::new(20.0, 20.0), CityTier::Town),
+            City::new(3, "Isle".into(), Point2D::new(50.0, 16.0), CityTier::Village),
+        ];
+
+        let no_ferries = CivilizationConfig {
+            max_ferry_span: 5.0,
+            ..Default::default()
+        };
+        let roads = CivilizationGenerator::new(42, no_ferries).generate_roads(&biome_map, &cities);
+        assert_eq!(disconnected_settlements(&roads, &cities), vec![3]);
+
+        let ferries = CivilizationConfig {
+            max_ferry_span: 40.0,
+            ..Default::default()
+        };
+        let roads = CivilizationGenerator::new(42, ferries).generate_roads(&biome_map, &cities);
+        assert!(disconnected_settlements(&roads, &cities).is_empty());
+        assert!(roads.iter().any(|r| r.road_type == RoadType::Ferry));
+    }
 }
@@ -69,7 +69,7 @@ impl CultureType {
 
 /// Biome suitability preferences for a culture.
 /// Values range from -1.0 (hostile) to 1.0 (ideal).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BiomePreferences {
     // Water types
     pub sea: f64,
@@ -141,13 +141,41 @@ impl Default for BiomePreferences {
 }
 
 impl BiomePreferences {
+    /// Every preference with its biome's name, for editing.
+    pub fn entries_mut(&mut self) -> [(&'static str, &mut f64); 21] {
+        [
+            ("Sea", &mut self.sea),
+            ("Ocean trench", &mut self.ocean_trench),
+            ("River", &mut self.river),
+            ("Beach", &mut self.beach),
+            ("Ice", &mut self.white),
+            ("Glacier", &mut self.glacier),
+            ("Snow", &mut self.snow),
+            ("Tundra", &mut self.tundra),
+            ("Taiga", &mut self.taiga),
+            ("Plains", &mut self.plains),
+            ("Forest", &mut self.forest),
+            ("Marsh", &mut self.marsh),
+            ("Steppe", &mut self.steppe),
+            ("Mountain", &mut self.mountain),
+            ("Plateau", &mut self.plateau),
+            ("Savanna", &mut self.savanna),
+            ("Jungle", &mut self.jungle),
+            ("Desert", &mut self.desert),
+            ("Sahara", &mut self.sahara),
+            ("Badlands", &mut self.badlands),
+            ("Volcanic", &mut self.volcanic),
+        ]
+    }
+
     /// Get the preference score for a given tile type.
     pub fn get(&self, tile: TileType) -> f64 {
         match tile {
             // Water
-            TileType::Sea => self.sea,
+            TileType::ShallowSea | TileType::Sea | TileType::DeepSea => self.sea,
             TileType::OceanTrench => self.ocean_trench,
-            TileType::River => self.river,
+            // Lakes are fresh water, so cultures treat them like rivers
+            TileType::River | TileType::Lake => self.river,
 
             // Coastal
             TileType::Beach => self.beach,
@@ -179,7 +207,7 @@ impl BiomePreferences {
 }
 
 /// Traits affecting settlement patterns and expansion.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CultureTraits {
     /// 0.0 = nomadic, 1.0 = settled. Affects village density.
     pub settlement_tendency: f64,
@@ -206,8 +234,10 @@ impl Default for CultureTraits {
 }
 
 /// Complete culture definition with environmental preferences.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Culture {
+    /// Archetype the culture is based on, which names its settlements and
+    /// factions. Authored cultures may share one.
     pub culture_type: CultureType,
     pub name: String,
     /// Biome suitability scores.
@@ -492,6 +522,11 @@ impl Culture {
         temperature: f64,
         continentalness: f64,
     ) -> f64 {
+        // Open water is never settleable, whatever the climate
+        if biome.is_ocean() {
+            return 0.0;
+        }
+
         // Biome preference (40% weight)
         let biome_score = (self.biome_preferences.get(biome) + 1.0) / 2.0; // Normalize to [0, 1]
 
@@ -545,6 +580,6 @@ mod tests {
     fn suitability_in_range() {
         let culture = Culture::twilight_dweller();
         let score = culture.calculate_suitability(TileType::Plains, 25.0, 0.1);
-        assert!(score >= 0.0 && score <= 1.0);
+        assert!((0.0..=1.0).contains(&score));
     }
 }
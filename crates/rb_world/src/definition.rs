@@ -1,10 +1,52 @@
 use bevy::prelude::*;
+use rb_core::TileType;
+use rb_noise::{
+    BiomeOverride, BiomeThresholds, ContinentSketch, LayerBases, LayerParams, NoiseLayer, Symmetry, TerrainEdits,
+};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::culture::Culture;
 use crate::faction::Faction;
+use crate::dynasty::{Dynasty, Ruler};
+use crate::economy::Economy;
+use crate::history::HistoricalEvent;
+use crate::religion::{Adherence, Religion};
 use crate::roads::{Road, TradeRoute};
-use crate::territory::TerritoryMap;
+use crate::sea_routes::SeaRoute;
+use crate::territory::{TerritoryLocks, TerritoryMap};
+use crate::trade_flow::TradeFlow;
+
+/// Meso tile resolutions offered in the editor.
+pub const MESO_RESOLUTIONS: [usize; 3] = [256, 512, 1024];
+
+/// Meso tile resolution for new worlds and saves that predate the setting.
+pub const DEFAULT_MESO_RESOLUTION: usize = 512;
+
+fn default_meso_resolution() -> usize {
+    DEFAULT_MESO_RESOLUTION
+}
+
+fn default_erosion_iterations() -> u32 {
+    50_000
+}
+
+fn default_warp_frequency() -> f64 {
+    rb_noise::DEFAULT_WARP_FREQUENCY
+}
+
+fn default_cave_depth() -> f64 {
+    rb_noise::DEFAULT_CAVE_DEPTH
+}
+
+fn default_volcanoes() -> bool {
+    true
+}
+
+fn default_sketch_weight() -> f64 {
+    1.0
+}
 
 /// World definition resource containing all authored world data.
 ///
@@ -26,6 +68,34 @@ pub struct WorldDefinition {
     pub terminator_x: f64,
     /// Width of the habitable twilight zone.
     pub twilight_width: f64,
+    /// Width in pixels of the ocean frame around the map edge (0 = none).
+    #[serde(default)]
+    pub border_ocean: f64,
+    /// Mirror symmetry for balanced multiplayer maps.
+    #[serde(default)]
+    pub symmetry: Symmetry,
+    /// Breakpoints biome classification splits the climate axes at.
+    #[serde(default)]
+    pub biome_thresholds: BiomeThresholds,
+    /// Grayscale heightmap the continents are drawn from, if any. Only the
+    /// path is saved; the image itself is read again on load.
+    #[serde(default)]
+    pub sketch_path: Option<PathBuf>,
+    /// How far continentalness follows the sketch: 1 replaces the noise,
+    /// lower values blend the two.
+    #[serde(default = "default_sketch_weight")]
+    pub sketch_weight: f64,
+    /// The image at `sketch_path` (loaded with the world, not serialized).
+    #[serde(skip)]
+    pub sketch: Option<Arc<ContinentSketch>>,
+    /// Continentalness sculpted with the terrain brush, on top of the noise
+    /// and sketch.
+    #[serde(default)]
+    pub terrain_edits: TerrainEdits,
+    /// Pixel resolution of each meso tile (one of [`MESO_RESOLUTIONS`]).
+    /// Lower values trade detail for generation speed and memory.
+    #[serde(default = "default_meso_resolution")]
+    pub meso_resolution: usize,
     /// Noise parameters for world generation.
     pub noise_params: NoiseParams,
     /// Authored regions (countries, territories).
@@ -42,6 +112,31 @@ pub struct WorldDefinition {
     pub roads: Vec<Road>,
     /// Trade routes.
     pub trade_routes: Vec<TradeRoute>,
+    /// Where the author last left the camera. `None` for worlds that have
+    /// never been saved from the editor.
+    #[serde(default)]
+    pub view: Option<ViewState>,
+    /// Hand-painted territory that generation keeps.
+    #[serde(default)]
+    pub territory_locks: TerritoryLocks,
+    /// Timeline of wars, conquests and foundings since the world was settled.
+    #[serde(default)]
+    pub history: Vec<HistoricalEvent>,
+    /// Ruling houses of every faction.
+    #[serde(default)]
+    pub dynasties: Vec<Dynasty>,
+    /// Every faction's rulers, in order of reign.
+    #[serde(default)]
+    pub rulers: Vec<Ruler>,
+    /// Faiths followed across the world.
+    #[serde(default)]
+    pub religions: Vec<Religion>,
+    /// Shipping lanes between port settlements.
+    #[serde(default)]
+    pub sea_routes: Vec<SeaRoute>,
+    /// Goods carried between settlements each year.
+    #[serde(default)]
+    pub trade_flows: Vec<TradeFlow>,
     /// Cached territory ownership (regenerated on load, not serialized).
     #[serde(skip)]
     pub territory_cache: Option<TerritoryMap>,
@@ -57,6 +152,14 @@ impl Default for WorldDefinition {
             sea_level: -0.025,
             terminator_x: 512.0,
             twilight_width: 200.0,
+            border_ocean: 0.0,
+            symmetry: Symmetry::None,
+            biome_thresholds: BiomeThresholds::default(),
+            sketch_path: None,
+            sketch_weight: default_sketch_weight(),
+            sketch: None,
+            terrain_edits: TerrainEdits::default(),
+            meso_resolution: DEFAULT_MESO_RESOLUTION,
             noise_params: NoiseParams::default(),
             regions: Vec::new(),
             cities: Vec::new(),
@@ -65,34 +168,92 @@ impl Default for WorldDefinition {
             factions: Vec::new(),
             roads: Vec::new(),
             trade_routes: Vec::new(),
+            view: None,
+            territory_locks: TerritoryLocks::default(),
+            history: Vec::new(),
+            dynasties: Vec::new(),
+            rulers: Vec::new(),
+            religions: Vec::new(),
+            sea_routes: Vec::new(),
+            trade_flows: Vec::new(),
             territory_cache: None,
         }
     }
 }
 
+impl WorldDefinition {
+    /// The biome each region forces, for generation. Regions without an
+    /// override, or naming a biome generation can't produce, are left out.
+    pub fn biome_overrides(&self) -> Vec<BiomeOverride> {
+        self.regions
+            .iter()
+            .filter(|region| region.bounds.is_closed())
+            .filter_map(|region| {
+                let biome = TileType::from_name(region.biome_override.as_deref()?)?;
+                let outline = region.bounds.vertices.iter().map(|p| (p.x, p.y)).collect();
+                Some(BiomeOverride { outline, biome })
+            })
+            .collect()
+    }
+
+    /// The ruler on a faction's throne now, if it still has one.
+    pub fn ruler_of(&self, faction_id: u32) -> Option<&Ruler> {
+        self.rulers.iter().find(|r| r.faction_id == faction_id && r.reign_end.is_none())
+    }
+}
+
+/// Editor camera and layer, saved so a reopened world resumes where the
+/// author left off.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ViewState {
+    /// Camera centre in world coordinates.
+    pub camera: Point2D,
+    /// Orthographic projection scale (world units per screen pixel).
+    pub zoom: f32,
+    /// Layer shown in the generator.
+    pub layer: NoiseLayer,
+}
+
 /// Noise generation parameters.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct NoiseParams {
-    /// Number of octaves for continentalness noise.
-    pub continentalness_octaves: u32,
-    /// Persistence for continentalness noise.
-    pub continentalness_persistence: f64,
-    /// Lacunarity for continentalness noise.
-    pub continentalness_lacunarity: f64,
-    /// Number of octaves for temperature noise.
-    pub temperature_octaves: u32,
-    /// Persistence for temperature noise.
-    pub temperature_persistence: f64,
+    /// Scale, octaves and strength of each noise layer.
+    #[serde(default)]
+    pub layers: LayerParams,
+    /// Run the hydraulic erosion pass over continentalness.
+    #[serde(default)]
+    pub hydraulic_erosion: bool,
+    /// Droplets the erosion pass simulates.
+    #[serde(default = "default_erosion_iterations")]
+    pub erosion_iterations: u32,
+    /// Domain-warp offset in world units for the fBm layers (0 disables).
+    #[serde(default)]
+    pub warp_amplitude: f64,
+    /// Frequency of the domain-warp field.
+    #[serde(default = "default_warp_frequency")]
+    pub warp_frequency: f64,
+    /// Noise generator under each fBm layer.
+    #[serde(default)]
+    pub bases: LayerBases,
+    /// Depth below the surface of the slice the cave layer shows.
+    #[serde(default = "default_cave_depth")]
+    pub cave_depth: f64,
+    /// Seed volcanoes along colliding plates and over hotspots.
+    #[serde(default = "default_volcanoes")]
+    pub volcanoes: bool,
 }
 
 impl Default for NoiseParams {
     fn default() -> Self {
         Self {
-            continentalness_octaves: 16,
-            continentalness_persistence: 0.59,
-            continentalness_lacunarity: 2.0,
-            temperature_octaves: 8,
-            temperature_persistence: 0.59,
+            layers: LayerParams::default(),
+            hydraulic_erosion: false,
+            erosion_iterations: default_erosion_iterations(),
+            warp_amplitude: 0.0,
+            warp_frequency: default_warp_frequency(),
+            bases: LayerBases::default(),
+            cave_depth: default_cave_depth(),
+            volcanoes: default_volcanoes(),
         }
     }
 }
@@ -111,7 +272,7 @@ impl Point2D {
 }
 
 /// A polygon defined by a series of vertices.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct Polygon {
     pub vertices: Vec<Point2D>,
 }
@@ -152,7 +313,7 @@ impl Polygon {
 }
 
 /// An authored region (country, territory, biome override zone).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Region {
     /// Unique identifier.
     pub id: u32,
@@ -213,7 +374,7 @@ impl CityTier {
 }
 
 /// An authored city.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct City {
     /// Unique identifier.
     pub id: u32,
@@ -223,12 +384,20 @@ pub struct City {
     pub position: Point2D,
     /// Population tier.
     pub tier: CityTier,
-    /// Estimated population.
+    /// Estimated population. New settlements start at their tier's
+    /// midpoint until the economy is simulated.
     pub population: u32,
     /// Whether this city has custom chunk data.
     pub is_authored: bool,
     /// Industry types (e.g., "mining", "fishing", "trade").
     pub industries: Vec<String>,
+    /// Religions held here, largest share first. Whoever is left over keeps
+    /// to no faith.
+    #[serde(default)]
+    pub faiths: Vec<Adherence>,
+    /// Goods made and used here.
+    #[serde(default)]
+    pub economy: Economy,
 }
 
 impl City {
@@ -244,6 +413,8 @@ impl City {
             population,
             is_authored: matches!(tier, CityTier::Capital),
             industries: Vec::new(),
+            faiths: Vec::new(),
+            economy: Economy::default(),
         }
     }
 }
@@ -260,6 +431,7 @@ pub enum LandmarkKind {
     Monument,
     Mine,
     Port,
+    Volcano,
     Other,
 }
 
@@ -274,6 +446,7 @@ impl LandmarkKind {
             Self::Monument => "Monument",
             Self::Mine => "Mine",
             Self::Port => "Port",
+            Self::Volcano => "Volcano",
             Self::Other => "Other",
         }
     }
@@ -288,13 +461,14 @@ impl LandmarkKind {
             Self::Monument,
             Self::Mine,
             Self::Port,
+            Self::Volcano,
             Self::Other,
         ]
     }
 }
 
 /// An authored landmark.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Landmark {
     /// Unique identifier.
     pub id: u32,
@@ -339,6 +513,23 @@ pub struct WorldIdGenerator {
 }
 
 impl WorldIdGenerator {
+    /// A generator whose ids all come after the ones already in `world`, so
+    /// entities added after a load never reuse a loaded id.
+    pub fn from_world(world: &WorldDefinition) -> Self {
+        fn next_after(ids: impl Iterator<Item = u32>) -> u32 {
+            ids.max().map_or(0, |max| max + 1)
+        }
+
+        Self {
+            next_region_id: next_after(world.regions.iter().map(|r| r.id)),
+            next_city_id: next_after(world.cities.iter().map(|c| c.id)),
+            next_landmark_id: next_after(world.landmarks.iter().map(|l| l.id)),
+            next_faction_id: next_after(world.factions.iter().map(|f| f.id)),
+            next_road_id: next_after(world.roads.iter().map(|r| r.id)),
+            next_trade_route_id: next_after(world.trade_routes.iter().map(|t| t.id)),
+        }
+    }
+
     pub fn next_region_id(&mut self) -> u32 {
         let id = self.next_region_id;
         self.next_region_id += 1;
@@ -393,6 +584,26 @@ mod tests {
         assert!(!square.contains(Point2D::new(15.0, 5.0)));
     }
 
+    #[test]
+    fn regions_with_known_biomes_become_overrides() {
+        let square = Polygon::new(vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(10.0, 0.0),
+            Point2D::new(10.0, 10.0),
+        ]);
+        let mut world = WorldDefinition::default();
+        for (id, biome) in [(1, Some("forest")), (2, None), (3, Some("Salt Flats"))] {
+            let mut region = Region::new(id, format!("Region {}", id), square.clone());
+            region.biome_override = biome.map(String::from);
+            world.regions.push(region);
+        }
+
+        let overrides = world.biome_overrides();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].biome, TileType::Forest);
+        assert_eq!(overrides[0].outline[1], (10.0, 0.0));
+    }
+
     #[test]
     fn world_definition_serializes() {
         let world = WorldDefinition::default();
@@ -400,6 +611,44 @@ mod tests {
         let _: WorldDefinition = ron::from_str(&ron).unwrap();
     }
 
+    #[test]
+    fn view_state_round_trips() {
+        let world = WorldDefinition {
+            view: Some(ViewState {
+                camera: Point2D::new(-120.5, 64.0),
+                zoom: 0.35,
+                layer: NoiseLayer::ResourceIron,
+            }),
+            ..Default::default()
+        };
+        let ron = ron::to_string(&world).unwrap();
+        let loaded: WorldDefinition = ron::from_str(&ron).unwrap();
+        assert_eq!(loaded.view, world.view);
+
+        // Saves from before the field load with no view to restore
+        let fresh = ron::to_string(&WorldDefinition::default()).unwrap();
+        let legacy = fresh.replace(",view:None", "");
+        assert_ne!(legacy, fresh);
+        assert_eq!(ron::from_str::<WorldDefinition>(&legacy).unwrap().view, None);
+    }
+
+    #[test]
+    fn id_generator_continues_after_loaded_ids() {
+        let mut world = WorldDefinition::default();
+        for id in [2, 5, 3] {
+            let position = Point2D::new(10.0 * id as f64, 10.0);
+            world.cities.push(City::new(id, format!("City {}", id), position, CityTier::Town));
+        }
+        let loaded: WorldDefinition = ron::from_str(&ron::to_string(&world).unwrap()).unwrap();
+
+        let mut id_gen = WorldIdGenerator::from_world(&loaded);
+        let new_ids = [id_gen.next_city_id(), id_gen.next_city_id()];
+        assert_eq!(new_ids, [6, 7]);
+        assert!(new_ids.iter().all(|id| loaded.cities.iter().all(|c| c.id != *id)));
+        // Empty collections start where a fresh generator would
+        assert_eq!(id_gen.next_landmark_id(), 0);
+    }
+
     #[test]
     fn city_tier_has_valid_ranges() {
         for tier in [CityTier::Capital, CityTier::Town, CityTier::Village] {
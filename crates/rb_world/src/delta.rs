@@ -0,0 +1,161 @@
+//! Compact differences between two world definitions.
+//!
+//! A [`WorldDelta`] records the authored entities that were added, changed or
+//! removed, so the editor can save small deltas on top of a base save instead
+//! of rewriting the whole world.
+
+use serde::{Deserialize, Serialize};
+
+use crate::definition::{City, Landmark, Region, WorldDefinition};
+
+/// Entities that can be tracked by ID in a delta.
+trait Keyed {
+    fn key(&self) -> u32;
+}
+
+impl Keyed for City {
+    fn key(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Keyed for Landmark {
+    fn key(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Keyed for Region {
+    fn key(&self) -> u32 {
+        self.id
+    }
+}
+
+/// Changes to one collection of entities.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityDelta<T> {
+    /// New or changed entities, stored whole.
+    pub upserted: Vec<T>,
+    /// IDs of removed entities.
+    pub removed: Vec<u32>,
+}
+
+impl<T> Default for EntityDelta<T> {
+    fn default() -> Self {
+        Self {
+            upserted: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+}
+
+/// Entities in `target` that are new or differ from `base`, and IDs only in `base`.
+fn diff_entities<T: Keyed + Clone + PartialEq>(base: &[T], target: &[T]) -> EntityDelta<T> {
+    let upserted = target
+        .iter()
+        .filter(|t| base.iter().find(|b| b.key() == t.key()) != Some(*t))
+        .cloned()
+        .collect();
+    let removed = base
+        .iter()
+        .filter(|b| !target.iter().any(|t| t.key() == b.key()))
+        .map(Keyed::key)
+        .collect();
+    EntityDelta { upserted, removed }
+}
+
+/// Remove, then replace in place or append.
+fn apply_entities<T: Keyed + Clone>(delta: &EntityDelta<T>, items: &mut Vec<T>) {
+    items.retain(|item| !delta.removed.contains(&item.key()));
+    for entity in &delta.upserted {
+        match items.iter_mut().find(|item| item.key() == entity.key()) {
+            Some(existing) => *existing = entity.clone(),
+            None => items.push(entity.clone()),
+        }
+    }
+}
+
+impl<T> EntityDelta<T> {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.upserted.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Authored-content changes between two worlds.
+///
+/// Only cities, landmarks and regions are tracked; everything else comes from
+/// the base save.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldDelta {
+    pub cities: EntityDelta<City>,
+    pub landmarks: EntityDelta<Landmark>,
+    pub regions: EntityDelta<Region>,
+}
+
+impl WorldDelta {
+    /// Whether applying this delta would change nothing.
+    pub fn is_empty(&self) -> bool {
+        self.cities.is_empty() && self.landmarks.is_empty() && self.regions.is_empty()
+    }
+}
+
+impl WorldDefinition {
+    /// Changes that turn `self` into `other`.
+    pub fn diff(&self, other: &WorldDefinition) -> WorldDelta {
+        WorldDelta {
+            cities: diff_entities(&self.cities, &other.cities),
+            landmarks: diff_entities(&self.landmarks, &other.landmarks),
+            regions: diff_entities(&self.regions, &other.regions),
+        }
+    }
+
+    /// Apply a delta produced by [`diff`](Self::diff).
+    ///
+    /// Changed entities keep their position; new ones are appended.
+    pub fn apply_delta(&mut self, delta: &WorldDelta) {
+        apply_entities(&delta.cities, &mut self.cities);
+        apply_entities(&delta.landmarks, &mut self.landmarks);
+        apply_entities(&delta.regions, &mut self.regions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definition::{CityTier, LandmarkKind, Point2D, Polygon};
+
+    fn sample_world() -> WorldDefinition {
+        let mut world = WorldDefinition::default();
+        for id in 1..=4 {
+            let pos = Point2D::new(id as f64 * 10.0, 5.0);
+            world.cities.push(City::new(id, format!("City {}", id), pos, CityTier::Village));
+        }
+        let ruin_pos = Point2D::new(3.0, 4.0);
+        world.landmarks.push(Landmark::new(1, "Old Ruins".into(), ruin_pos, LandmarkKind::Ruin));
+        world.regions.push(Region::new(1, "North".into(), Polygon::default()));
+        world
+    }
+
+    #[test]
+    fn applying_diff_reproduces_target() {
+        let a = sample_world();
+        let mut b = a.clone();
+        b.cities[1].name = "Renamed".into();
+        b.cities.remove(2);
+        b.cities.push(City::new(9, "New Town".into(), Point2D::new(50.0, 50.0), CityTier::Town));
+        b.landmarks.clear();
+        b.regions[0].color = [255, 0, 0, 128];
+
+        let delta = a.diff(&b);
+        assert_eq!(delta.cities.upserted.len(), 2);
+        assert_eq!(delta.cities.removed, vec![3]);
+
+        let mut patched = a.clone();
+        patched.apply_delta(&delta);
+        assert_eq!(patched.cities, b.cities);
+        assert_eq!(patched.landmarks, b.landmarks);
+        assert_eq!(patched.regions, b.regions);
+        assert!(patched.diff(&b).is_empty());
+    }
+}
@@ -0,0 +1,328 @@
+//! Ruling dynasties and the rulers of each faction.
+//!
+//! Every faction is ruled from its founding (year 0) to the end of the
+//! simulated history, or until it falls. Cultures differ in how power passes
+//! on: hereditary realms keep one house on the throne until someone usurps
+//! it, while leagues and confederacies elect their next ruler, often from a
+//! new house. Heirs take family names, so a long line has its Aldric II.
+
+use crate::culture::CultureType;
+use crate::definition::WorldDefinition;
+use crate::faction::FactionDisposition;
+use crate::history::{HistoricalEvent, HistoricalEventKind};
+use crate::namegen::{NameGenerator, NameKind};
+use rand::prelude::*;
+use rb_core::rng_for;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Shortest and longest reign, in years.
+const REIGN_YEARS: (u32, u32) = (5, 35);
+
+/// Chance a hereditary throne passes to the heir rather than a usurper.
+const HEREDITARY_CONTINUITY: f64 = 0.85;
+
+/// Chance an elective throne stays with the ruling house.
+const ELECTIVE_CONTINUITY: f64 = 0.35;
+
+/// Given names a house hands down its line.
+const FAMILY_NAMES: usize = 4;
+
+/// Traits each ruler has.
+const TRAITS_PER_RULER: usize = 2;
+
+/// A ruling house.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dynasty {
+    pub id: u32,
+    pub name: String,
+    pub faction_id: u32,
+    /// Year the house first took the throne.
+    pub founded: u32,
+}
+
+/// How a ruler came to power.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Succession {
+    /// First ruler of the faction.
+    Founded,
+    Inherited,
+    Elected,
+    /// Seized the throne from another house.
+    Usurped,
+}
+
+impl Succession {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Succession::Founded => "Founded",
+            Succession::Inherited => "Inherited",
+            Succession::Elected => "Elected",
+            Succession::Usurped => "Usurped",
+        }
+    }
+}
+
+/// A ruler's character, for quests and NPCs to draw on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RulerTrait {
+    Warlike,
+    Peaceful,
+    Cruel,
+    Just,
+    Mercantile,
+    Reclusive,
+    Pious,
+    Ambitious,
+}
+
+impl RulerTrait {
+    pub fn all() -> &'static [RulerTrait] {
+        &[
+            RulerTrait::Warlike,
+            RulerTrait::Peaceful,
+            RulerTrait::Cruel,
+            RulerTrait::Just,
+            RulerTrait::Mercantile,
+            RulerTrait::Reclusive,
+            RulerTrait::Pious,
+            RulerTrait::Ambitious,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RulerTrait::Warlike => "Warlike",
+            RulerTrait::Peaceful => "Peaceful",
+            RulerTrait::Cruel => "Cruel",
+            RulerTrait::Just => "Just",
+            RulerTrait::Mercantile => "Mercantile",
+            RulerTrait::Reclusive => "Reclusive",
+            RulerTrait::Pious => "Pious",
+            RulerTrait::Ambitious => "Ambitious",
+        }
+    }
+
+    /// How likely a faction of this disposition is to be ruled so.
+    fn weight(&self, disposition: &FactionDisposition) -> f64 {
+        let d = disposition;
+        match self {
+            RulerTrait::Warlike => d.aggressiveness,
+            RulerTrait::Peaceful => 1.0 - d.aggressiveness,
+            RulerTrait::Cruel => 0.5 * d.aggressiveness,
+            RulerTrait::Just => 0.5 * (1.0 - d.aggressiveness),
+            RulerTrait::Mercantile => d.trade_openness,
+            RulerTrait::Reclusive => d.isolationism,
+            RulerTrait::Pious | RulerTrait::Ambitious => 0.4,
+        }
+    }
+}
+
+/// One ruler of a faction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ruler {
+    pub id: u32,
+    /// Given name, with a regnal number if an earlier ruler of the house bore it.
+    pub name: String,
+    pub faction_id: u32,
+    pub dynasty_id: u32,
+    /// Year the reign began.
+    pub reign_start: u32,
+    /// Year it ended, or `None` for the ruler on the throne now.
+    pub reign_end: Option<u32>,
+    pub traits: Vec<RulerTrait>,
+    pub succession: Succession,
+}
+
+/// Whether a culture's throne passes by blood.
+fn hereditary(culture: CultureType) -> bool {
+    match culture {
+        CultureType::FrostKin | CultureType::StoneBorn | CultureType::SunForged => true,
+        CultureType::TideWalker | CultureType::TwilightDweller => false,
+    }
+}
+
+/// Lines of rulers for every faction, from year 0 to `years` or the year a
+/// faction fell in `history`. Returns the houses and their rulers, numbered
+/// from 1.
+pub fn generate_dynasties(
+    world_def: &WorldDefinition,
+    history: &[HistoricalEvent],
+    years: u32,
+    seed: u32,
+    names: &mut NameGenerator,
+) -> (Vec<Dynasty>, Vec<Ruler>) {
+    let mut rng = rng_for(seed, "dynasties");
+    let fell: HashMap<u32, u32> = history
+        .iter()
+        .filter_map(|event| match event.kind {
+            HistoricalEventKind::Fell { faction, .. } => Some((faction, event.year)),
+            _ => None,
+        })
+        .collect();
+
+    let mut dynasties: Vec<Dynasty> = Vec::new();
+    let mut rulers: Vec<Ruler> = Vec::new();
+    for faction in &world_def.factions {
+        let end = fell.get(&faction.id).copied();
+        let horizon = end.unwrap_or(years);
+        let mut family: Vec<(String, u32)> = Vec::new();
+        let mut year = 0;
+        let (mut succession, mut new_house) = (Succession::Founded, true);
+
+        loop {
+            if new_house {
+                dynasties.push(Dynasty {
+                    id: dynasties.len() as u32 + 1,
+                    name: names.name(faction.culture, NameKind::Dynasty, &mut rng),
+                    faction_id: faction.id,
+                    founded: year,
+                });
+                family = (0..FAMILY_NAMES)
+                    .map(|_| (names.name(faction.culture, NameKind::Person, &mut rng), 0))
+                    .collect();
+            }
+
+            let given = &mut family[rng.gen_range(0..FAMILY_NAMES)];
+            given.1 += 1;
+            let name = match given.1 {
+                1 => given.0.clone(),
+                n => format!("{} {}", given.0, regnal_number(n)),
+            };
+            let reign_end = year + rng.gen_range(REIGN_YEARS.0..=REIGN_YEARS.1);
+            let still_reigning = reign_end >= horizon;
+            rulers.push(Ruler {
+                id: rulers.len() as u32 + 1,
+                name,
+                faction_id: faction.id,
+                dynasty_id: dynasties.len() as u32,
+                reign_start: year,
+                reign_end: if still_reigning { end } else { Some(reign_end) },
+                traits: pick_traits(&faction.disposition, &mut rng),
+                succession,
+            });
+            if still_reigning {
+                break;
+            }
+
+            year = reign_end;
+            let by_blood = hereditary(faction.culture);
+            let continuity = if by_blood { HEREDITARY_CONTINUITY } else { ELECTIVE_CONTINUITY };
+            let keeps_house = rng.gen::<f64>() < continuity;
+            succession = match (by_blood, keeps_house) {
+                (true, true) => Succession::Inherited,
+                (true, false) => Succession::Usurped,
+                (false, _) => Succession::Elected,
+            };
+            new_house = !keeps_house;
+        }
+    }
+    (dynasties, rulers)
+}
+
+/// Distinct traits for a ruler, drawn by the faction's disposition.
+fn pick_traits(disposition: &FactionDisposition, rng: &mut impl Rng) -> Vec<RulerTrait> {
+    let mut pool: Vec<RulerTrait> = RulerTrait::all().to_vec();
+    let mut traits = Vec::new();
+    while traits.len() < TRAITS_PER_RULER && !pool.is_empty() {
+        let index = pool
+            .choose_weighted(rng, |t| t.weight(disposition).max(0.01))
+            .ok()
+            .and_then(|t| pool.iter().position(|p| p == t))
+            .unwrap_or(0);
+        traits.push(pool.remove(index));
+    }
+    traits
+}
+
+/// Roman numeral for a regnal number.
+fn regnal_number(n: u32) -> String {
+    const NUMERALS: [(u32, &str); 9] = [
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut n = n;
+    let mut numeral = String::new();
+    for (value, symbol) in NUMERALS {
+        while n >= value {
+            numeral.push_str(symbol);
+            n -= value;
+        }
+    }
+    numeral
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::faction::Faction;
+    use crate::namegen::NameData;
+
+    fn world() -> WorldDefinition {
+        WorldDefinition {
+            factions: vec![
+                Faction::new(1, "Holds".into(), CultureType::FrostKin),
+                Faction::new(2, "League".into(), CultureType::TideWalker),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn generate(world: &WorldDefinition, history: &[HistoricalEvent], years: u32) -> (Vec<Dynasty>, Vec<Ruler>) {
+        generate_dynasties(world, history, years, 11, &mut NameGenerator::new(&NameData::default()))
+    }
+
+    #[test]
+    fn reigns_run_unbroken_to_the_present() {
+        let world = world();
+        let (dynasties, rulers) = generate(&world, &[], 300);
+
+        for faction in &world.factions {
+            let line: Vec<&Ruler> = rulers.iter().filter(|r| r.faction_id == faction.id).collect();
+            assert!(line.len() > 3);
+            assert_eq!(line[0].reign_start, 0);
+            assert_eq!(line[0].succession, Succession::Founded);
+            for pair in line.windows(2) {
+                assert_eq!(pair[0].reign_end, Some(pair[1].reign_start));
+            }
+            assert_eq!(line.last().unwrap().reign_end, None);
+            assert!(line.iter().all(|r| dynasties[r.dynasty_id as usize - 1].faction_id == faction.id));
+        }
+
+        // Only elective thrones see elections, only hereditary ones usurpers
+        let holds = rulers.iter().filter(|r| r.faction_id == 1);
+        assert!(holds.clone().all(|r| r.succession != Succession::Elected));
+        assert!(holds.clone().any(|r| r.succession == Succession::Inherited));
+        let league = rulers.iter().filter(|r| r.faction_id == 2);
+        assert!(league.clone().all(|r| !matches!(r.succession, Succession::Inherited | Succession::Usurped)));
+    }
+
+    #[test]
+    fn fallen_factions_stop_ruling_when_they_fall() {
+        let world = world();
+        let fell = HistoricalEvent {
+            year: 60,
+            kind: HistoricalEventKind::Fell { faction: 2, by: 1 },
+            summary: "League fell to Holds".into(),
+        };
+        let (_, rulers) = generate(&world, &[fell], 200);
+
+        let last = rulers.iter().rfind(|r| r.faction_id == 2).unwrap();
+        assert_eq!(last.reign_end, Some(60));
+        assert!(last.reign_start < 60);
+    }
+
+    #[test]
+    fn regnal_numbers() {
+        assert_eq!(regnal_number(2), "II");
+        assert_eq!(regnal_number(4), "IV");
+        assert_eq!(regnal_number(14), "XIV");
+    }
+}
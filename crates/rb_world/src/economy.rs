@@ -0,0 +1,299 @@
+//! Settlement populations and what each settlement makes and needs.
+//!
+//! A settlement's size within its tier's range follows its prosperity, a mix
+//! of four things: how firmly its faction holds the land around it, the
+//! resources within reach, the trade passing through, and how stable its
+//! faction has been. Its economy follows from the same resources and from
+//! how many mouths there are to feed.
+
+use crate::definition::{City, CityTier, WorldDefinition};
+use crate::faction::Stance;
+use crate::history::HistoricalEventKind;
+use crate::roads::TradeGood;
+use rb_core::{BiomeRegistry, ResourceType};
+use rb_noise::BiomeMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Cells around a settlement it draws resources from.
+const ECONOMY_RADIUS: usize = 6;
+
+/// Resource abundance at which the resource factor is full.
+const RICH_RESOURCES: f32 = 12.0;
+
+/// Weights of the political, resource, trade and stability factors.
+const FACTOR_WEIGHTS: [f64; 4] = [0.35, 0.3, 0.2, 0.15];
+
+/// Summed trade route importance at which the trade factor is full.
+const BUSY_TRADE: f64 = 1.5;
+
+/// Stability lost for each settlement a faction has had taken from it.
+const LOSS_PENALTY: f64 = 0.15;
+
+/// Stability lost for each faction one is at war with.
+const WAR_PENALTY: f64 = 0.1;
+
+/// Prosperity assumed for a factor that can't be judged, such as the
+/// political hold of a settlement no faction owns.
+const UNKNOWN_FACTOR: f64 = 0.5;
+
+/// People one load of food feeds for a year.
+const PEOPLE_PER_FOOD: f32 = 100.0;
+
+/// A settlement's trade: goods made and used, in loads a year.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Economy {
+    /// How well the settlement is doing, 0 to 1.
+    pub prosperity: f32,
+    /// Goods made, most first.
+    pub production: Vec<(TradeGood, f32)>,
+    /// Goods used, most first.
+    pub consumption: Vec<(TradeGood, f32)>,
+}
+
+impl Economy {
+    /// Loads of a good made beyond what is used; negative where the
+    /// settlement has to bring it in.
+    pub fn surplus(&self, good: TradeGood) -> f32 {
+        let amount = |list: &[(TradeGood, f32)]| list.iter().find(|(g, _)| *g == good).map_or(0.0, |(_, a)| *a);
+        amount(&self.production) - amount(&self.consumption)
+    }
+}
+
+/// The good a natural resource is traded as.
+fn good_of(resource: ResourceType) -> TradeGood {
+    match resource {
+        ResourceType::Iron | ResourceType::Copper | ResourceType::Coal | ResourceType::Stone => TradeGood::Ore,
+        ResourceType::Gold | ResourceType::Silver | ResourceType::Gems => TradeGood::Luxury,
+        ResourceType::Salt => TradeGood::Salt,
+        ResourceType::Timber => TradeGood::Timber,
+        ResourceType::Fish => TradeGood::Fish,
+        ResourceType::FertileSoil => TradeGood::Food,
+        ResourceType::WildGame => TradeGood::Furs,
+    }
+}
+
+/// Abundance of every resource within [`ECONOMY_RADIUS`] of a cell.
+fn resources_near(biome_map: &BiomeMap, x: usize, y: usize) -> HashMap<ResourceType, f32> {
+    let mut found = HashMap::new();
+    let x_end = (x + ECONOMY_RADIUS).min(biome_map.width - 1);
+    let y_end = (y + ECONOMY_RADIUS).min(biome_map.height - 1);
+    for ny in y.saturating_sub(ECONOMY_RADIUS)..=y_end {
+        for nx in x.saturating_sub(ECONOMY_RADIUS)..=x_end {
+            for &(resource, amount) in biome_map.resources.get_all(nx, ny) {
+                *found.entry(resource).or_insert(0.0) += amount;
+            }
+        }
+    }
+    found
+}
+
+/// Set every settlement's population and economy from the world around it.
+/// Trade route importance is reweighed for the new populations.
+pub fn simulate_economy(biome_map: &BiomeMap, biomes: &BiomeRegistry, world_def: &mut WorldDefinition) {
+    let owners: HashMap<u32, u32> = world_def
+        .factions
+        .iter()
+        .flat_map(|f| f.settlement_ids.iter().map(move |&city| (city, f.id)))
+        .collect();
+    let stability: HashMap<u32, f64> = world_def.factions.iter().map(|f| (f.id, faction_stability(world_def, f.id))).collect();
+    let at_war: HashMap<u32, bool> = world_def
+        .factions
+        .iter()
+        .map(|f| (f.id, world_def.factions.iter().any(|o| f.stance_toward(o.id) == Stance::Hostile)))
+        .collect();
+
+    let mut economies = Vec::with_capacity(world_def.cities.len());
+    for city in &world_def.cities {
+        let (x, y) = (city.position.x.max(0.0) as usize, city.position.y.max(0.0) as usize);
+        let (x, y) = (x.min(biome_map.width - 1), y.min(biome_map.height - 1));
+        let resources = resources_near(biome_map, x, y);
+        let owner = owners.get(&city.id).copied();
+
+        let factors = [
+            owner.map_or(UNKNOWN_FACTOR, |faction| political_hold(world_def, city, faction)),
+            (resources.values().sum::<f32>() / RICH_RESOURCES).min(1.0) as f64,
+            trade_access(world_def, city.id),
+            owner.and_then(|f| stability.get(&f).copied()).unwrap_or(UNKNOWN_FACTOR),
+        ];
+        let prosperity: f64 = factors.iter().zip(FACTOR_WEIGHTS).map(|(f, w)| f * w).sum();
+        let population = population_for(city.tier, prosperity);
+
+        let biome_goods = biome_map.get_biome(x, y).map(|b| TradeGood::from_biome(biomes, b)).unwrap_or_default();
+        let at_war = owner.is_some_and(|f| at_war[&f]);
+        economies.push((population, economy(city.tier, population, prosperity, &resources, &biome_goods, at_war)));
+    }
+
+    for (city, (population, economy)) in world_def.cities.iter_mut().zip(economies) {
+        city.population = population;
+        city.economy = economy;
+    }
+    for route in &mut world_def.trade_routes {
+        route.importance = route.compute_importance(&world_def.cities);
+    }
+}
+
+/// How firmly a faction holds a settlement: its influence there, or full if
+/// territory hasn't been generated.
+fn political_hold(world_def: &WorldDefinition, city: &City, faction: u32) -> f64 {
+    let Some(territory) = &world_def.territory_cache else { return 1.0 };
+    let (x, y) = (city.position.x as usize, city.position.y as usize);
+    if territory.get_owner(x, y) == faction {
+        territory.get_influence(x, y)
+    } else {
+        // Held, but in land another faction dominates
+        0.25 * territory.get_influence(x, y)
+    }
+}
+
+/// Trade through a settlement: the importance of every route calling there.
+fn trade_access(world_def: &WorldDefinition, city_id: u32) -> f64 {
+    let importance: f64 = world_def
+        .trade_routes
+        .iter()
+        .filter(|r| r.settlement_ids.contains(&city_id))
+        .map(|r| r.importance)
+        .sum();
+    (importance / BUSY_TRADE).min(1.0)
+}
+
+/// How settled a faction's rule is: lowered by every settlement it has lost
+/// and every war it is fighting.
+fn faction_stability(world_def: &WorldDefinition, faction_id: u32) -> f64 {
+    let losses = world_def
+        .history
+        .iter()
+        .filter(|e| matches!(e.kind, HistoricalEventKind::Conquered { from, .. } if from == faction_id))
+        .count();
+    let wars = world_def
+        .factions
+        .iter()
+        .find(|f| f.id == faction_id)
+        .map_or(0, |f| world_def.factions.iter().filter(|o| f.stance_toward(o.id) == Stance::Hostile).count());
+    (1.0 - LOSS_PENALTY * losses as f64 - WAR_PENALTY * wars as f64).clamp(0.0, 1.0)
+}
+
+/// Population within a tier's range for a prosperity. The range is walked
+/// geometrically, so prosperity multiplies a settlement's size rather than
+/// adding to it.
+fn population_for(tier: CityTier, prosperity: f64) -> u32 {
+    let (min, max) = tier.population_range();
+    let population = min as f64 * (max as f64 / min as f64).powf(prosperity.clamp(0.0, 1.0));
+    let step = if population >= 10_000.0 { 100.0 } else { 10.0 };
+    ((population / step).round() * step) as u32
+}
+
+/// Goods a settlement makes from its resources and land, and those its
+/// people use.
+fn economy(
+    tier: CityTier,
+    population: u32,
+    prosperity: f64,
+    resources: &HashMap<ResourceType, f32>,
+    biome_goods: &[TradeGood],
+    at_war: bool,
+) -> Economy {
+    let people = population as f32 / PEOPLE_PER_FOOD;
+    // A settlement with rich stores of one resource makes as many loads of
+    // it as it eats of food
+    let labour = people / RICH_RESOURCES;
+
+    let mut production: HashMap<TradeGood, f32> = HashMap::new();
+    for (&resource, &amount) in resources {
+        *production.entry(good_of(resource)).or_insert(0.0) += amount * labour;
+    }
+    for &good in biome_goods {
+        *production.entry(good).or_insert(0.0) += 0.25 * people;
+    }
+    // Towns and capitals work raw goods into finished ones
+    if tier != CityTier::Village {
+        let ore = production.get(&TradeGood::Ore).copied().unwrap_or(0.0);
+        let fibre = production.get(&TradeGood::Furs).copied().unwrap_or(0.0) + 0.25 * production.get(&TradeGood::Food).copied().unwrap_or(0.0);
+        production.insert(TradeGood::Weapons, 0.3 * ore);
+        production.insert(TradeGood::Textiles, 0.5 * fibre);
+    }
+
+    let mut consumption = vec![
+        (TradeGood::Food, people),
+        (TradeGood::Timber, 0.25 * people),
+        (TradeGood::Textiles, 0.2 * people),
+        (TradeGood::Salt, 0.1 * people),
+    ];
+    if tier != CityTier::Village {
+        consumption.push((TradeGood::Luxury, 0.05 * people * prosperity as f32));
+    }
+    if at_war {
+        consumption.push((TradeGood::Weapons, 0.1 * people));
+    }
+
+    Economy {
+        prosperity: ((prosperity * 100.0).round() / 100.0) as f32,
+        production: largest_first(production.into_iter().collect()),
+        consumption: largest_first(consumption),
+    }
+}
+
+/// Goods with any amount, largest first, rounded to a tenth of a load.
+fn largest_first(mut goods: Vec<(TradeGood, f32)>) -> Vec<(TradeGood, f32)> {
+    goods.retain(|(_, amount)| *amount >= 0.05);
+    for (_, amount) in &mut goods {
+        *amount = (*amount * 10.0).round() / 10.0;
+    }
+    goods.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.name().cmp(b.0.name())));
+    goods
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::culture::CultureType;
+    use crate::faction::Faction;
+    use crate::history::HistoricalEvent;
+
+    #[test]
+    fn prosperity_walks_the_tier_range() {
+        assert_eq!(population_for(CityTier::Village, 0.0), 100);
+        assert_eq!(population_for(CityTier::Village, 1.0), 5_000);
+        assert_eq!(population_for(CityTier::Capital, 1.0), 500_000);
+        let town = |p| population_for(CityTier::Town, p);
+        assert!(town(0.2) < town(0.5) && town(0.5) < town(0.8));
+    }
+
+    #[test]
+    fn lost_settlements_and_wars_unsettle_a_faction() {
+        let mut world = WorldDefinition {
+            factions: vec![
+                Faction::new(1, "Holds".into(), CultureType::FrostKin),
+                Faction::new(2, "League".into(), CultureType::TideWalker),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(faction_stability(&world, 1), 1.0);
+
+        world.history.push(HistoricalEvent {
+            year: 10,
+            kind: HistoricalEventKind::Conquered { city: 3, from: 1, to: 2 },
+            summary: String::new(),
+        });
+        world.factions[0].set_relation(2, -1.0);
+        assert!((faction_stability(&world, 1) - (1.0 - LOSS_PENALTY - WAR_PENALTY)).abs() < 1e-9);
+        assert_eq!(faction_stability(&world, 2), 1.0);
+    }
+
+    #[test]
+    fn towns_feed_themselves_and_work_their_ore() {
+        let resources = HashMap::from([(ResourceType::Iron, 8.0), (ResourceType::FertileSoil, 2.0)]);
+        let town = economy(CityTier::Town, 16_000, 0.6, &resources, &[TradeGood::Food], true);
+
+        assert_eq!(town.production[0].0, TradeGood::Ore);
+        assert!(town.surplus(TradeGood::Weapons) > 0.0);
+        let food = 2.0 * 160.0 / RICH_RESOURCES + 0.25 * 160.0;
+        assert!((town.surplus(TradeGood::Food) - (food - 160.0)).abs() < 0.1);
+        assert!(town.consumption.iter().any(|(good, _)| *good == TradeGood::Luxury));
+        assert!(town.production.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        let village = economy(CityTier::Village, 400, 0.3, &resources, &[], false);
+        assert_eq!(village.surplus(TradeGood::Weapons), 0.0);
+        assert!(village.consumption.iter().all(|(good, _)| *good != TradeGood::Luxury));
+    }
+}
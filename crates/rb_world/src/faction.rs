@@ -3,11 +3,13 @@
 //! Factions are political entities that control settlements and territories.
 
 use crate::culture::CultureType;
+use rand::Rng;
+use rb_core::rng_for;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// A political faction controlling settlements and territory.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Faction {
     /// Unique identifier.
     pub id: u32,
@@ -76,14 +78,62 @@ impl Faction {
         self.get_relation(other_faction_id) > 0.5
     }
 
+    /// How this faction stands toward another.
+    pub fn stance_toward(&self, other_faction_id: u32) -> Stance {
+        Stance::of(self.get_relation(other_faction_id))
+    }
+
     /// Get the number of settlements.
     pub fn settlement_count(&self) -> usize {
         self.settlement_ids.len()
     }
 }
 
+/// How one faction stands toward another, as the editor shows relations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stance {
+    Allied,
+    Neutral,
+    Hostile,
+}
+
+impl Stance {
+    pub fn all() -> &'static [Stance] {
+        &[Stance::Allied, Stance::Neutral, Stance::Hostile]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stance::Allied => "Allied",
+            Stance::Neutral => "Neutral",
+            Stance::Hostile => "Hostile",
+        }
+    }
+
+    /// Stance a relation value amounts to, by the thresholds of
+    /// [`Faction::is_allied_with`] and [`Faction::is_hostile_to`].
+    pub fn of(relation: f64) -> Self {
+        if relation > 0.5 {
+            Stance::Allied
+        } else if relation < -0.3 {
+            Stance::Hostile
+        } else {
+            Stance::Neutral
+        }
+    }
+
+    /// Relation value stored when the stance is set by hand.
+    pub fn relation(&self) -> f64 {
+        match self {
+            Stance::Allied => 0.75,
+            Stance::Neutral => 0.0,
+            Stance::Hostile => -0.75,
+        }
+    }
+}
+
 /// Faction disposition affecting behavior and expansion.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FactionDisposition {
     /// How aggressive the faction is (0.0 = peaceful, 1.0 = warlike).
     pub aggressiveness: f64,
@@ -106,11 +156,11 @@ impl Default for FactionDisposition {
 impl FactionDisposition {
     /// Generate a random disposition based on culture type and seed.
     pub fn from_culture_and_seed(culture: CultureType, seed: u32) -> Self {
-        // Use seed to create pseudo-random but deterministic values
-        let hash = seed.wrapping_mul(2654435761);
-        let r1 = ((hash >> 0) & 0xFF) as f64 / 255.0;
-        let r2 = ((hash >> 8) & 0xFF) as f64 / 255.0;
-        let r3 = ((hash >> 16) & 0xFF) as f64 / 255.0;
+        // Deterministic stream per seed, separate from naming and placement
+        let mut rng = rng_for(seed, "faction_disposition");
+        let r1: f64 = rng.gen();
+        let r2: f64 = rng.gen();
+        let r3: f64 = rng.gen();
 
         // Base values from culture, with random variation
         let (base_agg, base_trade, base_iso) = match culture {
@@ -155,6 +205,19 @@ mod tests {
         assert_eq!(faction.get_relation(999), 0.0);
     }
 
+    #[test]
+    fn stances_match_the_relation_thresholds() {
+        let mut faction = Faction::new(1, "Test".into(), CultureType::TwilightDweller);
+        for &stance in Stance::all() {
+            faction.set_relation(2, stance.relation());
+            assert_eq!(faction.stance_toward(2), stance);
+        }
+        assert!(faction.is_hostile_to(2));
+        faction.set_relation(2, 0.4);
+        assert_eq!(faction.stance_toward(2), Stance::Neutral);
+        assert_eq!(faction.stance_toward(3), Stance::Neutral);
+    }
+
     #[test]
     fn disposition_from_seed_is_deterministic() {
         let d1 = FactionDisposition::from_culture_and_seed(CultureType::FrostKin, 42);
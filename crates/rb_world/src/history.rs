@@ -0,0 +1,527 @@
+//! Historical simulation run after civilization generation.
+//!
+//! Factions found new villages, go to war with their neighbours over the
+//! settlements along their borders, take or raze them, and make peace again.
+//! Every happening is recorded in the world's timeline, and razed settlements
+//! leave ruins behind (see [`history_landmarks`]).
+
+use crate::culture::Culture;
+use crate::definition::{City, CityTier, Landmark, LandmarkKind, Point2D, WorldDefinition};
+use crate::faction::{Faction, Stance};
+use crate::namegen::{NameGenerator, NameKind};
+use crate::settlement_placement::{derive_industries, MIN_SETTLEMENT_DISTANCE, SETTLEMENT_THRESHOLD};
+use rand::prelude::*;
+use rb_core::rng_for;
+use rb_noise::BiomeMap;
+use serde::{Deserialize, Serialize};
+
+/// Sites tried around a settlement before a founding is given up.
+const FOUNDING_ATTEMPTS: usize = 8;
+
+/// Something that happened, naming factions and settlements by id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HistoricalEventKind {
+    /// A faction founded a new village.
+    Founded { faction: u32, city: u32 },
+    WarDeclared { attacker: u32, defender: u32 },
+    /// A settlement passed from one faction to another.
+    Conquered { city: u32, from: u32, to: u32 },
+    /// A settlement was destroyed. Its id no longer exists, so its name and
+    /// position are kept here.
+    Razed { city: u32, name: String, position: Point2D, by: u32 },
+    PeaceMade { factions: (u32, u32) },
+    /// A faction lost its last settlement.
+    Fell { faction: u32, by: u32 },
+}
+
+/// One entry of a world's timeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoricalEvent {
+    /// Years after the world was settled.
+    pub year: u32,
+    pub kind: HistoricalEventKind,
+    /// What happened, with names as they were at the time.
+    pub summary: String,
+}
+
+/// Configuration for the historical simulation.
+#[derive(Debug, Clone)]
+pub struct HistoryConfig {
+    /// Years to simulate.
+    pub years: u32,
+    /// Yearly chance neighbours go to war, scaled by the more aggressive
+    /// one's aggressiveness.
+    pub war_chance: f64,
+    /// Yearly chance a war ends.
+    pub peace_chance: f64,
+    /// Chance a taken village is razed rather than kept, scaled by the
+    /// victor's aggressiveness. Towns are razed half as often, capitals never.
+    pub raze_chance: f64,
+    /// Yearly chance a faction founds a village, lowered by isolationism.
+    pub founding_chance: f64,
+    /// Factions with settlements this close (world units) share a border.
+    pub border_distance: f64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            years: 100,
+            war_chance: 0.08,
+            peace_chance: 0.2,
+            raze_chance: 0.5,
+            founding_chance: 0.1,
+            border_distance: 120.0,
+        }
+    }
+}
+
+/// Tally of a simulation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryResult {
+    pub wars: usize,
+    pub conquered: usize,
+    pub razed: usize,
+    pub founded: usize,
+    pub fallen: usize,
+}
+
+/// Plays out the years after a world's civilization is generated.
+pub struct HistoryGenerator {
+    seed: u32,
+    config: HistoryConfig,
+}
+
+impl HistoryGenerator {
+    pub fn new(seed: u32, config: HistoryConfig) -> Self {
+        Self { seed, config }
+    }
+
+    /// Simulate the configured years on `world_def`, replacing its timeline.
+    ///
+    /// Settlements change hands, are razed (along with their roads and trade
+    /// routes) or are founded; territory is left for the caller to
+    /// regenerate. New villages are named by `names`.
+    pub fn simulate(
+        &self,
+        biome_map: &BiomeMap,
+        world_def: &mut WorldDefinition,
+        names: &mut NameGenerator,
+    ) -> HistoryResult {
+        let mut rng = rng_for(self.seed, "history");
+        let mut timeline = Timeline::default();
+
+        for year in 1..=self.config.years {
+            timeline.year = year;
+            self.found_villages(biome_map, world_def, names, &mut timeline, &mut rng);
+            self.declare_wars(world_def, &mut timeline, &mut rng);
+            self.fight(world_def, &mut timeline, &mut rng);
+
+            for (a, b) in wars(world_def) {
+                if rng.gen::<f64>() < self.config.peace_chance {
+                    set_stance(world_def, a, b, Stance::Neutral);
+                    let summary = format!("{} and {} made peace", faction_name(world_def, a), faction_name(world_def, b));
+                    timeline.record(HistoricalEventKind::PeaceMade { factions: (a, b) }, summary);
+                }
+            }
+        }
+
+        world_def.history = timeline.events;
+        timeline.result
+    }
+
+    /// Each faction may found a village near one of its settlements.
+    fn found_villages(
+        &self,
+        biome_map: &BiomeMap,
+        world_def: &mut WorldDefinition,
+        names: &mut NameGenerator,
+        timeline: &mut Timeline,
+        rng: &mut impl Rng,
+    ) {
+        for index in 0..world_def.factions.len() {
+            let faction = &world_def.factions[index];
+            let chance = self.config.founding_chance * (1.0 - 0.5 * faction.disposition.isolationism);
+            if faction.settlement_ids.is_empty() || rng.gen::<f64>() >= chance {
+                continue;
+            }
+            let Some(position) = founding_site(biome_map, world_def, index, rng) else { continue };
+
+            let faction = &world_def.factions[index];
+            let id = world_def.cities.iter().map(|c| c.id).max().unwrap_or(0) + 1;
+            let mut city = City::new(id, names.name(faction.culture, NameKind::Settlement, rng), position, CityTier::Village);
+            city.industries = derive_industries(biome_map, position.x as usize, position.y as usize, faction.culture);
+            let summary = format!("{} founded {}", faction.name, city.name);
+            timeline.record(HistoricalEventKind::Founded { faction: faction.id, city: id }, summary);
+            timeline.result.founded += 1;
+
+            world_def.factions[index].add_settlement(id);
+            world_def.cities.push(city);
+        }
+    }
+
+    /// Neighbours at peace may go to war, the more aggressive attacking.
+    fn declare_wars(&self, world_def: &mut WorldDefinition, timeline: &mut Timeline, rng: &mut impl Rng) {
+        let living = living_factions(world_def);
+        for (i, &a) in living.iter().enumerate() {
+            for &b in &living[i + 1..] {
+                let (fa, fb) = (faction(world_def, a), faction(world_def, b));
+                if fa.stance_toward(b) != Stance::Neutral || !self.borders(world_def, a, b) {
+                    continue;
+                }
+                let (aggr_a, aggr_b) = (fa.disposition.aggressiveness, fb.disposition.aggressiveness);
+                if rng.gen::<f64>() >= self.config.war_chance * aggr_a.max(aggr_b) {
+                    continue;
+                }
+
+                let (attacker, defender) = if aggr_a >= aggr_b { (a, b) } else { (b, a) };
+                set_stance(world_def, a, b, Stance::Hostile);
+                let summary = format!(
+                    "{} declared war on {}",
+                    faction_name(world_def, attacker),
+                    faction_name(world_def, defender)
+                );
+                timeline.record(HistoricalEventKind::WarDeclared { attacker, defender }, summary);
+                timeline.result.wars += 1;
+            }
+        }
+    }
+
+    /// One battle a year in each war, over the defender's settlement nearest
+    /// the attacker. The side on the offensive is drawn by aggressiveness.
+    fn fight(&self, world_def: &mut WorldDefinition, timeline: &mut Timeline, rng: &mut impl Rng) {
+        for (a, b) in wars(world_def) {
+            let (aggr_a, aggr_b) = (
+                faction(world_def, a).disposition.aggressiveness,
+                faction(world_def, b).disposition.aggressiveness,
+            );
+            let a_attacks = rng.gen::<f64>() * (aggr_a + aggr_b) < aggr_a || aggr_a + aggr_b == 0.0;
+            let (attacker, defender, aggression) = if a_attacks { (a, b, aggr_a) } else { (b, a, aggr_b) };
+            let Some((target, span)) = front(world_def, attacker, defender) else { continue };
+            if span > self.config.border_distance {
+                continue;
+            }
+
+            let tier = city(world_def, target).tier;
+            let attack = strength(world_def, attacker) * (0.5 + aggression);
+            let defence = strength(world_def, defender) * 0.75 + tier_weight(tier);
+            if rng.gen::<f64>() * (attack + defence) >= attack {
+                continue;
+            }
+
+            let raze_chance = match tier {
+                CityTier::Village => self.config.raze_chance * aggression,
+                CityTier::Town => 0.5 * self.config.raze_chance * aggression,
+                CityTier::Capital => 0.0,
+            };
+            let (attacker_name, defender_name) = (faction_name(world_def, attacker), faction_name(world_def, defender));
+            if rng.gen::<f64>() < raze_chance {
+                let razed = raze(world_def, target, defender);
+                let summary = format!("{} razed {} of {}", attacker_name, razed.name, defender_name);
+                let kind = HistoricalEventKind::Razed {
+                    city: target,
+                    name: razed.name,
+                    position: razed.position,
+                    by: attacker,
+                };
+                timeline.record(kind, summary);
+                timeline.result.razed += 1;
+            } else {
+                let summary = format!("{} took {} from {}", attacker_name, city(world_def, target).name, defender_name);
+                transfer(world_def, target, defender, attacker);
+                timeline.record(HistoricalEventKind::Conquered { city: target, from: defender, to: attacker }, summary);
+                timeline.result.conquered += 1;
+            }
+
+            if faction(world_def, defender).settlement_ids.is_empty() {
+                // A fallen faction is at war with no one
+                let others: Vec<u32> = world_def.factions.iter().map(|f| f.id).filter(|&id| id != defender).collect();
+                for other in others {
+                    set_stance(world_def, defender, other, Stance::Neutral);
+                }
+                let summary = format!("{} fell to {}", defender_name, attacker_name);
+                timeline.record(HistoricalEventKind::Fell { faction: defender, by: attacker }, summary);
+                timeline.result.fallen += 1;
+            }
+        }
+    }
+
+    /// Whether two factions have settlements within the border distance.
+    fn borders(&self, world_def: &WorldDefinition, a: u32, b: u32) -> bool {
+        front(world_def, a, b).is_some_and(|(_, span)| span <= self.config.border_distance)
+    }
+}
+
+/// A ruin for each settlement razed in `history`, numbered from `first_id`.
+pub fn history_landmarks(history: &[HistoricalEvent], first_id: u32) -> Vec<Landmark> {
+    let razed = history.iter().filter_map(|event| match &event.kind {
+        HistoricalEventKind::Razed { name, position, .. } => Some((event, name, *position)),
+        _ => None,
+    });
+    (first_id..)
+        .zip(razed)
+        .map(|(id, (event, name, position))| Landmark {
+            description: Some(format!("Year {}: {}", event.year, event.summary)),
+            ..Landmark::new(id, format!("Ruins of {}", name), position, LandmarkKind::Ruin)
+        })
+        .collect()
+}
+
+/// Events recorded so far and the year they fall in.
+#[derive(Default)]
+struct Timeline {
+    year: u32,
+    events: Vec<HistoricalEvent>,
+    result: HistoryResult,
+}
+
+impl Timeline {
+    fn record(&mut self, kind: HistoricalEventKind, summary: String) {
+        self.events.push(HistoricalEvent { year: self.year, kind, summary });
+    }
+}
+
+fn distance(a: Point2D, b: Point2D) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+fn faction(world_def: &WorldDefinition, id: u32) -> &Faction {
+    world_def.factions.iter().find(|f| f.id == id).expect("faction in world")
+}
+
+fn faction_name(world_def: &WorldDefinition, id: u32) -> String {
+    faction(world_def, id).name.clone()
+}
+
+fn city(world_def: &WorldDefinition, id: u32) -> &City {
+    world_def.cities.iter().find(|c| c.id == id).expect("city in world")
+}
+
+/// Factions that still hold a settlement, by id.
+fn living_factions(world_def: &WorldDefinition) -> Vec<u32> {
+    world_def
+        .factions
+        .iter()
+        .filter(|f| !f.settlement_ids.is_empty())
+        .map(|f| f.id)
+        .collect()
+}
+
+/// Pairs of living factions at war, lower id first.
+fn wars(world_def: &WorldDefinition) -> Vec<(u32, u32)> {
+    let living = living_factions(world_def);
+    let mut pairs = Vec::new();
+    for (i, &a) in living.iter().enumerate() {
+        for &b in &living[i + 1..] {
+            if faction(world_def, a).stance_toward(b) == Stance::Hostile {
+                pairs.push((a, b));
+            }
+        }
+    }
+    pairs
+}
+
+fn set_stance(world_def: &mut WorldDefinition, a: u32, b: u32, stance: Stance) {
+    for faction in &mut world_def.factions {
+        if faction.id == a {
+            faction.set_relation(b, stance.relation());
+        } else if faction.id == b {
+            faction.set_relation(a, stance.relation());
+        }
+    }
+}
+
+/// The settlement of `defender` nearest any of `attacker`'s, and how far it
+/// lies from them.
+fn front(world_def: &WorldDefinition, attacker: u32, defender: u32) -> Option<(u32, f64)> {
+    let held = |id: u32| faction(world_def, id).settlement_ids.iter().map(|&c| city(world_def, c));
+    held(defender)
+        .flat_map(|target| held(attacker).map(move |own| (target.id, distance(own.position, target.position))))
+        .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+}
+
+fn tier_weight(tier: CityTier) -> f64 {
+    match tier {
+        CityTier::Capital => 3.0,
+        CityTier::Town => 2.0,
+        CityTier::Village => 1.0,
+    }
+}
+
+/// Military weight of a faction: its settlements by tier.
+fn strength(world_def: &WorldDefinition, id: u32) -> f64 {
+    let held = &faction(world_def, id).settlement_ids;
+    held.iter().map(|&c| tier_weight(city(world_def, c).tier)).sum()
+}
+
+/// A free site within reach of one of a faction's settlements that its
+/// culture would settle, if one turns up.
+fn founding_site(biome_map: &BiomeMap, world_def: &WorldDefinition, index: usize, rng: &mut impl Rng) -> Option<Point2D> {
+    let faction = &world_def.factions[index];
+    let culture = world_def
+        .cultures
+        .iter()
+        .find(|c| c.culture_type == faction.culture)
+        .cloned()
+        .unwrap_or_else(|| Culture::from_type(faction.culture));
+    let home = city(world_def, *faction.settlement_ids.choose(rng)?).position;
+
+    for _ in 0..FOUNDING_ATTEMPTS {
+        let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+        let reach = rng.gen_range(MIN_SETTLEMENT_DISTANCE..1.5 * MIN_SETTLEMENT_DISTANCE);
+        let site = Point2D::new(home.x + reach * angle.cos(), home.y + reach * angle.sin());
+        if site.x < 0.0 || site.y < 0.0 {
+            continue;
+        }
+        let (x, y) = (site.x as usize, site.y as usize);
+        let (Some(biome), Some(temperature), Some(continentalness)) =
+            (biome_map.get_biome(x, y), biome_map.get_temperature(x, y), biome_map.get_continentalness(x, y))
+        else {
+            continue;
+        };
+        let suitable = culture.calculate_suitability(biome, temperature, continentalness) >= SETTLEMENT_THRESHOLD;
+        if suitable && world_def.cities.iter().all(|c| distance(c.position, site) >= MIN_SETTLEMENT_DISTANCE) {
+            return Some(site);
+        }
+    }
+    None
+}
+
+/// Take a settlement from its faction, which moves its seat to its next
+/// largest settlement if this was its capital.
+fn release(world_def: &mut WorldDefinition, city_id: u32, from: u32) {
+    let index = world_def.factions.iter().position(|f| f.id == from).expect("faction in world");
+    world_def.factions[index].settlement_ids.retain(|&id| id != city_id);
+    if world_def.factions[index].capital_id != Some(city_id) {
+        return;
+    }
+
+    let seat = world_def.factions[index]
+        .settlement_ids
+        .iter()
+        .map(|&id| city(world_def, id))
+        .max_by(|a, b| tier_weight(a.tier).total_cmp(&tier_weight(b.tier)).then(b.id.cmp(&a.id)))
+        .map(|c| c.id);
+    world_def.factions[index].capital_id = seat;
+    if let Some(seat) = seat {
+        world_def.cities.iter_mut().find(|c| c.id == seat).unwrap().tier = CityTier::Capital;
+    }
+}
+
+/// Hand a settlement from one faction to another. A captured capital is
+/// demoted unless the victor has no seat of its own.
+fn transfer(world_def: &mut WorldDefinition, city_id: u32, from: u32, to: u32) {
+    release(world_def, city_id, from);
+    let victor = world_def.factions.iter_mut().find(|f| f.id == to).expect("faction in world");
+    victor.add_settlement(city_id);
+    if victor.capital_id.is_none() {
+        victor.set_capital(city_id);
+    } else {
+        let city = world_def.cities.iter_mut().find(|c| c.id == city_id).unwrap();
+        if city.tier == CityTier::Capital {
+            city.tier = CityTier::Town;
+        }
+    }
+}
+
+/// Destroy a settlement along with the roads to it and the trade routes
+/// through it, returning what it was.
+fn raze(world_def: &mut WorldDefinition, city_id: u32, from: u32) -> City {
+    release(world_def, city_id, from);
+    let index = world_def.cities.iter().position(|c| c.id == city_id).expect("city in world");
+    let razed = world_def.cities.remove(index);
+
+    let cut: Vec<u32> = world_def
+        .roads
+        .iter()
+        .filter(|r| r.connects.0 == city_id || r.connects.1 == city_id)
+        .map(|r| r.id)
+        .collect();
+    world_def.roads.retain(|r| !cut.contains(&r.id));
+    world_def.trade_routes.retain(|route| {
+        !route.settlement_ids.contains(&city_id) && !route.road_ids.iter().any(|id| cut.contains(id))
+    });
+    razed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::namegen::NameData;
+    use crate::roads::{Road, RoadType};
+    use crate::culture::CultureType;
+
+    /// Two aggressive neighbours with a row of settlements each, facing one
+    /// another across a short border.
+    fn rivals() -> WorldDefinition {
+        let mut world = WorldDefinition::default();
+        let mut west = Faction::new(1, "West".into(), CultureType::StoneBorn);
+        let mut east = Faction::new(2, "East".into(), CultureType::FrostKin);
+        for (faction, x0) in [(&mut west, 10.0), (&mut east, 100.0)] {
+            for i in 0..4u32 {
+                let id = faction.id * 10 + i;
+                let tier = if i == 0 { CityTier::Capital } else { CityTier::Village };
+                let position = Point2D::new(x0 + 15.0 * i as f64, 30.0);
+                world.cities.push(City::new(id, format!("City {}", id), position, tier));
+                faction.add_settlement(id);
+            }
+            faction.set_capital(faction.id * 10);
+            faction.disposition.aggressiveness = 1.0;
+        }
+        world.factions = vec![west, east];
+        world.roads.push(Road::new(1, (13, 20), RoadType::Trail));
+        world
+    }
+
+    fn simulate(world: &mut WorldDefinition, years: u32) -> HistoryResult {
+        let biome_map = BiomeMap::generate(7, 160, 64);
+        let config = HistoryConfig {
+            years,
+            war_chance: 1.0,
+            peace_chance: 0.0,
+            founding_chance: 0.0,
+            ..Default::default()
+        };
+        let mut names = NameGenerator::new(&NameData::default());
+        HistoryGenerator::new(7, config).simulate(&biome_map, world, &mut names)
+    }
+
+    #[test]
+    fn wars_move_and_raze_border_settlements() {
+        let mut world = rivals();
+        let result = simulate(&mut world, 40);
+
+        assert!(result.wars >= 1);
+        assert!(result.conquered + result.razed > 0, "{:?}", result);
+        assert!(matches!(world.history[0].kind, HistoricalEventKind::WarDeclared { .. }));
+
+        // Every surviving settlement belongs to exactly one faction
+        for city in &world.cities {
+            let owners = world.factions.iter().filter(|f| f.settlement_ids.contains(&city.id)).count();
+            assert_eq!(owners, 1, "{}", city.name);
+        }
+        assert_eq!(world.cities.len(), 8 - result.razed);
+
+        // Each living faction keeps a capital among its own settlements
+        for faction in world.factions.iter().filter(|f| !f.settlement_ids.is_empty()) {
+            assert!(faction.settlement_ids.contains(&faction.capital_id.unwrap()));
+        }
+
+        // Razed settlements leave ruins and no roads
+        let ruins = history_landmarks(&world.history, 1);
+        assert_eq!(ruins.len(), result.razed);
+        assert!(ruins.iter().all(|r| r.kind == LandmarkKind::Ruin && r.name.starts_with("Ruins of ")));
+        for road in &world.roads {
+            assert!(world.cities.iter().any(|c| c.id == road.connects.0));
+            assert!(world.cities.iter().any(|c| c.id == road.connects.1));
+        }
+    }
+
+    #[test]
+    fn history_is_deterministic() {
+        let (mut a, mut b) = (rivals(), rivals());
+        simulate(&mut a, 25);
+        simulate(&mut b, 25);
+        assert_eq!(a.history, b.history);
+    }
+}
@@ -0,0 +1,296 @@
+//! Landmark placement for procedural civilization generation.
+//!
+//! Scatters landmarks where the terrain suggests them: ports on coasts near
+//! settlements, caves in mountains, ruins in remote interiors and monuments
+//! around capitals. Volcanoes the macro map placed are recorded as they are.
+
+use crate::culture::{Culture, CultureType};
+use crate::definition::{City, CityTier, Landmark, LandmarkKind, Point2D};
+use crate::namegen::{NameGenerator, NameKind};
+use crate::settlement_placement::find_best_culture;
+use rand::prelude::*;
+use rb_core::{rng_for, TileType};
+use rb_noise::BiomeMap;
+
+/// Minimum distance between landmarks (in world units).
+const MIN_LANDMARK_DISTANCE: f64 = 24.0;
+
+/// Landmarks keep at least this far from settlements.
+const MIN_CITY_DISTANCE: f64 = 4.0;
+
+/// Candidate cells are sampled every N pixels.
+const SAMPLE_STRIDE: usize = 4;
+
+/// Ports must lie within this distance of a settlement.
+pub(crate) const HARBOR_RADIUS: f64 = 32.0;
+
+/// Ruins need no sea within this many cells...
+const REMOTE_WATER_RADIUS: usize = 10;
+/// ...and no settlement within this distance.
+const REMOTE_CITY_DISTANCE: f64 = 48.0;
+
+/// Monuments are placed in a ring around capitals.
+const MONUMENT_RING: (f64, f64) = (4.0, 12.0);
+
+/// Check whether a land cell touches open water.
+pub fn is_coastal(biome_map: &BiomeMap, x: usize, y: usize) -> bool {
+    let Some(biome) = biome_map.get_biome(x, y) else {
+        return false;
+    };
+    if biome.is_ocean() {
+        return false;
+    }
+
+    [(0, -1), (1, 0), (0, 1), (-1, 0)].iter().any(|&(dx, dy)| {
+        let nx = x as i64 + dx;
+        let ny = y as i64 + dy;
+        nx >= 0
+            && ny >= 0
+            && biome_map
+                .get_biome(nx as usize, ny as usize)
+                .is_some_and(|b| b.is_ocean())
+    })
+}
+
+/// Check if no ocean lies within `radius` cells.
+fn is_remote_interior(biome_map: &BiomeMap, x: usize, y: usize, radius: usize) -> bool {
+    let x_end = (x + radius).min(biome_map.width - 1);
+    let y_end = (y + radius).min(biome_map.height - 1);
+    for ny in y.saturating_sub(radius)..=y_end {
+        for nx in x.saturating_sub(radius)..=x_end {
+            if biome_map.get_biome(nx, ny).is_some_and(|b| b.is_ocean()) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+fn distance(a: Point2D, b: Point2D) -> f64 {
+    let dx = a.x - b.x;
+    let dy = a.y - b.y;
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Nearest settlement to a position.
+fn nearest_city(cities: &[City], pos: Point2D) -> Option<(&City, f64)> {
+    cities
+        .iter()
+        .map(|c| (c, distance(c.position, pos)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Whether a land cell suits a landmark kind.
+fn suits(
+    kind: LandmarkKind,
+    biome_map: &BiomeMap,
+    cities: &[City],
+    x: usize,
+    y: usize,
+) -> bool {
+    let pos = Point2D::new(x as f64, y as f64);
+    let nearest = nearest_city(cities, pos).map(|(_, d)| d);
+
+    match kind {
+        LandmarkKind::Port => {
+            is_coastal(biome_map, x, y) && nearest.is_some_and(|d| d <= HARBOR_RADIUS)
+        }
+        LandmarkKind::Cave => biome_map.get_biome(x, y) == Some(TileType::Mountain),
+        LandmarkKind::Ruin => {
+            nearest.is_none_or(|d| d >= REMOTE_CITY_DISTANCE)
+                && is_remote_interior(biome_map, x, y, REMOTE_WATER_RADIUS)
+        }
+        LandmarkKind::Monument => cities.iter().any(|c| {
+            let d = distance(c.position, pos);
+            c.tier == CityTier::Capital && d >= MONUMENT_RING.0 && d <= MONUMENT_RING.1
+        }),
+        _ => false,
+    }
+}
+
+/// The culture that would settle at `pos`, whose tongue names a landmark there.
+pub(crate) fn culture_at(biome_map: &BiomeMap, cultures: &[Culture], pos: Point2D) -> CultureType {
+    let (x, y) = (pos.x as usize, pos.y as usize);
+    let biome = biome_map.get_biome(x, y).unwrap_or(TileType::Plains);
+    let temperature = biome_map.get_temperature(x, y).unwrap_or(20.0);
+    let continentalness = biome_map.get_continentalness(x, y).unwrap_or(0.1);
+    find_best_culture(cultures, biome, temperature, continentalness).0
+}
+
+/// Generate a name for a landmark. Ports and monuments are named after the
+/// settlement they serve, unless another already is.
+pub(crate) fn landmark_name(
+    kind: LandmarkKind,
+    near: Option<&City>,
+    culture: CultureType,
+    names: &mut NameGenerator,
+    rng: &mut impl Rng,
+) -> String {
+    let after_city = match (kind, near) {
+        (LandmarkKind::Port, Some(city)) => Some(format!("Port {}", city.name)),
+        (LandmarkKind::Monument, Some(city)) => Some(format!("Monument of {}", city.name)),
+        _ => None,
+    };
+    if let Some(name) = after_city.filter(|name| names.reserve(name)) {
+        return name;
+    }
+
+    let name = names.name(culture, NameKind::Landmark, rng);
+    match kind {
+        LandmarkKind::Ruin => format!("{} Ruins", name),
+        LandmarkKind::Cave => format!("{} Cave", name),
+        LandmarkKind::Volcano => format!("{} Peak", name),
+        kind => format!("{} {}", name, kind.name()),
+    }
+}
+
+/// A landmark for each of the map's volcanoes, numbered from 1.
+fn volcano_landmarks(
+    biome_map: &BiomeMap,
+    cultures: &[Culture],
+    names: &mut NameGenerator,
+    rng: &mut impl Rng,
+) -> Vec<Landmark> {
+    (1..)
+        .zip(&biome_map.volcanoes)
+        .map(|(id, volcano)| {
+            let pos = Point2D::new(volcano.x as f64, volcano.y as f64);
+            let culture = culture_at(biome_map, cultures, pos);
+            let name = landmark_name(LandmarkKind::Volcano, None, culture, names, rng);
+            Landmark {
+                description: Some(volcano.kind.name().to_string()),
+                ..Landmark::new(id, name, pos, LandmarkKind::Volcano)
+            }
+        })
+        .collect()
+}
+
+/// Every volcano on the map, then up to `max_landmarks` more landmarks
+/// scattered across it.
+///
+/// Kinds take turns so a map with lots of coast doesn't end up all ports.
+/// Names are in the tongue of the culture suiting each spot best. The result
+/// depends only on the inputs and `seed`.
+pub fn place_landmarks(
+    biome_map: &BiomeMap,
+    cities: &[City],
+    cultures: &[Culture],
+    names: &mut NameGenerator,
+    seed: u32,
+    max_landmarks: usize,
+) -> Vec<Landmark> {
+    const KINDS: [LandmarkKind; 4] = [
+        LandmarkKind::Port,
+        LandmarkKind::Cave,
+        LandmarkKind::Ruin,
+        LandmarkKind::Monument,
+    ];
+
+    let mut rng = rng_for(seed, "landmarks");
+
+    // Collect and shuffle candidates for each kind
+    let mut candidates: Vec<Vec<Point2D>> = KINDS.iter().map(|_| Vec::new()).collect();
+    for y in (0..biome_map.height).step_by(SAMPLE_STRIDE) {
+        for x in (0..biome_map.width).step_by(SAMPLE_STRIDE) {
+            for (i, &kind) in KINDS.iter().enumerate() {
+                if suits(kind, biome_map, cities, x, y) {
+                    candidates[i].push(Point2D::new(x as f64, y as f64));
+                }
+            }
+        }
+    }
+    for list in &mut candidates {
+        list.shuffle(&mut rng);
+    }
+
+    let mut landmarks = volcano_landmarks(biome_map, cultures, names, &mut rng);
+    let mut next_id = landmarks.len() as u32 + 1;
+    let limit = landmarks.len() + max_landmarks;
+    let mut exhausted = false;
+
+    while landmarks.len() < limit && !exhausted {
+        exhausted = true;
+        for (i, &kind) in KINDS.iter().enumerate() {
+            if landmarks.len() >= limit {
+                break;
+            }
+
+            // Take the next candidate that respects spacing
+            while let Some(pos) = candidates[i].pop() {
+                let clear_of_landmarks = landmarks
+                    .iter()
+                    .all(|l| distance(l.position, pos) >= MIN_LANDMARK_DISTANCE);
+                let nearest = nearest_city(cities, pos);
+                let clear_of_cities = nearest.is_none_or(|(_, d)| d >= MIN_CITY_DISTANCE);
+                if !(clear_of_landmarks && clear_of_cities) {
+                    continue;
+                }
+
+                let culture = culture_at(biome_map, cultures, pos);
+                let name = landmark_name(kind, nearest.map(|(c, _)| c), culture, names, &mut rng);
+                landmarks.push(Landmark::new(next_id, name, pos, kind));
+                next_id += 1;
+                exhausted = false;
+                break;
+            }
+        }
+    }
+
+    landmarks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::namegen::NameData;
+    use crate::settlement_placement::place_settlements;
+    use rb_noise::{Volcano, VolcanoKind};
+
+    fn names() -> NameGenerator {
+        NameGenerator::new(&NameData::default())
+    }
+
+    #[test]
+    fn ports_are_coastal() {
+        let biome_map = BiomeMap::generate(42, 256, 128);
+        let cities = place_settlements(&biome_map, &Culture::all_defaults(), 42, 20).settlements;
+        let landmarks = place_landmarks(&biome_map, &cities, &Culture::all_defaults(), &mut names(), 42, 40);
+
+        let ports: Vec<_> = landmarks.iter().filter(|l| l.kind == LandmarkKind::Port).collect();
+        assert!(!ports.is_empty(), "Expected at least one port");
+        for port in ports {
+            let (x, y) = (port.position.x as usize, port.position.y as usize);
+            assert!(is_coastal(&biome_map, x, y), "Port {} at ({}, {}) is inland", port.name, x, y);
+        }
+    }
+
+    #[test]
+    fn volcanoes_are_recorded_first() {
+        let mut biome_map = BiomeMap::generate(7, 128, 64);
+        biome_map.volcanoes.push(Volcano {
+            x: 10,
+            y: 12,
+            kind: VolcanoKind::Hotspot,
+        });
+        let landmarks = place_landmarks(&biome_map, &[], &[], &mut names(), 7, 3);
+
+        let volcano = &landmarks[0];
+        assert_eq!(volcano.kind, LandmarkKind::Volcano);
+        assert_eq!(volcano.position, Point2D::new(10.0, 12.0));
+        assert_eq!(volcano.description.as_deref(), Some("Hotspot"));
+        assert!(landmarks.len() <= 4);
+        assert!(landmarks[1..].iter().all(|l| l.kind != LandmarkKind::Volcano));
+    }
+
+    #[test]
+    fn landmarks_respect_spacing() {
+        let biome_map = BiomeMap::generate(7, 128, 64);
+        let landmarks = place_landmarks(&biome_map, &[], &[], &mut names(), 7, 30);
+
+        for (i, a) in landmarks.iter().enumerate() {
+            for b in &landmarks[i + 1..] {
+                assert!(distance(a.position, b.position) >= MIN_LANDMARK_DISTANCE);
+            }
+        }
+    }
+}
@@ -3,20 +3,47 @@ use bevy::prelude::*;
 pub mod civilization;
 pub mod culture;
 pub mod definition;
+pub mod delta;
+pub mod dynasty;
+pub mod economy;
 pub mod faction;
+pub mod history;
+pub mod landmarks;
+pub mod namegen;
+pub mod religion;
 pub mod roads;
+pub mod sea_routes;
 pub mod settlement_placement;
 pub mod territory;
+pub mod trade_flow;
+pub mod validation;
 
-pub use civilization::{CivilizationConfig, CivilizationGenerator, CivilizationResult};
+pub use civilization::{
+    disconnected_settlements, faction_influence_fields, CivilizationConfig, CivilizationGenerator,
+    CivilizationResult,
+};
 pub use culture::{BiomePreferences, Culture, CultureTraits, CultureType};
 pub use definition::{
     City, CityTier, Landmark, LandmarkKind, NoiseParams, Point2D, Polygon, Region,
-    SelectedChunk, WorldDefinition, WorldIdGenerator,
+    SelectedChunk, ViewState, WorldDefinition, WorldIdGenerator, DEFAULT_MESO_RESOLUTION, MESO_RESOLUTIONS,
+};
+pub use delta::{EntityDelta, WorldDelta};
+pub use dynasty::{generate_dynasties, Dynasty, Ruler, RulerTrait, Succession};
+pub use economy::{simulate_economy, Economy};
+pub use faction::{Faction, FactionDisposition, Stance};
+pub use history::{
+    history_landmarks, HistoricalEvent, HistoricalEventKind, HistoryConfig, HistoryGenerator, HistoryResult,
+};
+pub use landmarks::place_landmarks;
+pub use namegen::{
+    CultureNames, MarkovChain, NameData, NameDataError, NameGenerator, NameKind, NameModel, SyllableGrammar,
 };
-pub use faction::{Faction, FactionDisposition};
-pub use roads::{Road, RoadType, TradeGood, TradeRoute};
-pub use territory::TerritoryMap;
+pub use religion::{found_religions, religion_image, spread_religions, Adherence, Religion};
+pub use roads::{Road, RoadEdge, RoadGraph, RoadType, TradeGood, TradeRoute};
+pub use sea_routes::{generate_sea_routes, SeaRoute};
+pub use territory::{influence_field, TerritoryLocks, TerritoryMap, LOCKED_INFLUENCE};
+pub use trade_flow::{road_volumes, simulate_trade_flows, TradeFlow};
+pub use validation::{EntityKind, ValidationIssue};
 
 /// World plugin for Randlebrot.
 /// Manages world definition, plates, coastlines, and climate baking.
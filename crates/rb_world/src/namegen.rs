@@ -0,0 +1,488 @@
+//! Procedural names for places and peoples.
+//!
+//! Each culture archetype has a [`NameModel`]: a syllable grammar, or a
+//! Markov chain trained on example words. The model invents a bare word and
+//! the culture's templates dress it for what is being named, so a FrostKin
+//! river and a TideWalker sea sound nothing alike. Built-ins cover every
+//! culture; `assets/names.ron` can replace any of them (see
+//! [`NameData::from_ron`]).
+
+use crate::culture::CultureType;
+use bevy::prelude::Resource;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Attempts at a fresh name before falling back to numbering a repeat.
+const NAME_ATTEMPTS: usize = 24;
+
+/// Longest word a Markov chain may produce, in characters.
+const MAX_MARKOV_LEN: usize = 12;
+
+/// Shortest word a Markov chain may produce, in characters.
+const MIN_MARKOV_LEN: usize = 3;
+
+/// What is being named. Each kind has its own templates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum NameKind {
+    Settlement,
+    Landmark,
+    Faction,
+    Region,
+    River,
+    Sea,
+    /// A ruling house.
+    Dynasty,
+    /// A person's given name.
+    Person,
+    Religion,
+}
+
+impl NameKind {
+    /// Templates for cultures that don't give their own; `{}` is the word.
+    fn default_templates(self) -> &'static [&'static str] {
+        match self {
+            NameKind::Settlement => &["{}"],
+            // Landmark names are followed by the landmark's own noun ("Cave")
+            NameKind::Landmark => &["{}", "Old {}", "{}'s"],
+            NameKind::Faction => &["{} Realm", "Realm of {}"],
+            NameKind::Region => &["{}", "{} Reach", "{} Lands"],
+            NameKind::River => &["{} River", "River {}"],
+            NameKind::Sea => &["{} Sea", "Sea of {}"],
+            NameKind::Dynasty => &["House {}"],
+            NameKind::Person => &["{}"],
+            NameKind::Religion => &["The {} Faith", "Way of {}", "Cult of {}"],
+        }
+    }
+}
+
+/// Words built from syllables: an onset, a nucleus and a coda each, then an
+/// ending.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyllableGrammar {
+    /// Opening consonants ("" for a bare vowel).
+    pub onsets: Vec<String>,
+    /// Vowel sounds at the heart of each syllable.
+    pub nuclei: Vec<String>,
+    /// Closing consonants ("" for an open syllable).
+    pub codas: Vec<String>,
+    /// Fewest and most syllables in a word.
+    pub syllables: (usize, usize),
+    /// Word endings ("" for none).
+    #[serde(default)]
+    pub endings: Vec<String>,
+}
+
+impl SyllableGrammar {
+    fn word(&self, rng: &mut impl Rng) -> String {
+        fn pick<'a>(parts: &'a [String], rng: &mut impl Rng) -> &'a str {
+            parts.choose(rng).map_or("", |part| part.as_str())
+        }
+        let (min, max) = (self.syllables.0.max(1), self.syllables.1.max(self.syllables.0.max(1)));
+        let mut word = String::new();
+        for _ in 0..rng.gen_range(min..=max) {
+            word.push_str(pick(&self.onsets, rng));
+            word.push_str(pick(&self.nuclei, rng));
+            word.push_str(pick(&self.codas, rng));
+        }
+        word.push_str(pick(&self.endings, rng));
+        word
+    }
+}
+
+/// How a culture invents words.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum NameModel {
+    Syllables(SyllableGrammar),
+    /// Letter chain of the given order trained on `words`; higher orders keep
+    /// closer to the examples.
+    Markov { order: usize, words: Vec<String> },
+}
+
+/// A culture archetype's naming: its model and any templates of its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CultureNames {
+    pub culture: CultureType,
+    pub model: NameModel,
+    /// Templates per kind, `{}` standing for the word. Kinds left out use
+    /// the defaults.
+    #[serde(default)]
+    pub templates: BTreeMap<NameKind, Vec<String>>,
+}
+
+/// Error loading naming data.
+#[derive(Debug)]
+pub enum NameDataError {
+    Parse(ron::error::SpannedError),
+    /// A Markov model was given no words to learn from.
+    NoWords(CultureType),
+}
+
+impl From<ron::error::SpannedError> for NameDataError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl std::fmt::Display for NameDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "RON parse error: {}", e),
+            Self::NoWords(culture) => write!(f, "{} names need at least one word", culture.name()),
+        }
+    }
+}
+
+impl std::error::Error for NameDataError {}
+
+/// Naming for every culture archetype.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct NameData {
+    pub cultures: Vec<CultureNames>,
+}
+
+impl Default for NameData {
+    fn default() -> Self {
+        Self {
+            cultures: CultureType::all().iter().map(|&culture| builtin_names(culture)).collect(),
+        }
+    }
+}
+
+impl NameData {
+    /// The built-ins with a file's entries applied on top. An entry replaces
+    /// the built-in naming of its culture outright.
+    pub fn from_ron(source: &str) -> Result<Self, NameDataError> {
+        let entries: Vec<CultureNames> = ron::from_str(source)?;
+        let mut data = Self::default();
+        for entry in entries {
+            if let NameModel::Markov { words, .. } = &entry.model {
+                if words.iter().all(|w| w.trim().is_empty()) {
+                    return Err(NameDataError::NoWords(entry.culture));
+                }
+            }
+            match data.cultures.iter_mut().find(|c| c.culture == entry.culture) {
+                Some(existing) => *existing = entry,
+                None => data.cultures.push(entry),
+            }
+        }
+        Ok(data)
+    }
+
+    /// Naming of a culture, falling back to its built-in.
+    fn culture(&self, culture: CultureType) -> CultureNames {
+        self.cultures
+            .iter()
+            .find(|c| c.culture == culture)
+            .cloned()
+            .unwrap_or_else(|| builtin_names(culture))
+    }
+}
+
+/// Order-`order` letter chain: which letters followed each run of letters in
+/// the training words.
+#[derive(Debug, Clone)]
+pub struct MarkovChain {
+    order: usize,
+    next: HashMap<String, Vec<char>>,
+    /// The training words, so the chain can avoid repeating them.
+    known: HashSet<String>,
+}
+
+/// Pads the start of a word.
+const START: char = '^';
+/// Marks the end of a word.
+const END: char = '$';
+
+impl MarkovChain {
+    /// Learn from `words` (case is ignored).
+    pub fn train(words: &[String], order: usize) -> Self {
+        let order = order.max(1);
+        let mut next: HashMap<String, Vec<char>> = HashMap::new();
+        let mut known = HashSet::new();
+        for word in words {
+            let word = word.trim().to_lowercase();
+            if word.is_empty() {
+                continue;
+            }
+            let padded: Vec<char> = std::iter::repeat_n(START, order)
+                .chain(word.chars())
+                .chain(std::iter::once(END))
+                .collect();
+            for window in padded.windows(order + 1) {
+                next.entry(window[..order].iter().collect()).or_default().push(window[order]);
+            }
+            known.insert(word);
+        }
+        Self { order, next, known }
+    }
+
+    /// A new word in the style of the training words, or `None` if the chain
+    /// keeps reproducing them (as it will with very few).
+    pub fn generate(&self, rng: &mut impl Rng) -> Option<String> {
+        for _ in 0..NAME_ATTEMPTS {
+            let mut state: Vec<char> = vec![START; self.order];
+            let mut word = String::new();
+            while let Some(options) = self.next.get(&state.iter().collect::<String>()) {
+                let c = options[rng.gen_range(0..options.len())];
+                if c == END || word.chars().count() > MAX_MARKOV_LEN {
+                    break;
+                }
+                word.push(c);
+                state.remove(0);
+                state.push(c);
+            }
+            let len = word.chars().count();
+            if (MIN_MARKOV_LEN..=MAX_MARKOV_LEN).contains(&len) && !self.known.contains(&word) {
+                return Some(word);
+            }
+        }
+        None
+    }
+
+    /// One of the training words, for when no new one comes.
+    fn known_word(&self, rng: &mut impl Rng) -> Option<String> {
+        let mut words: Vec<&String> = self.known.iter().collect();
+        words.sort();
+        words.choose(rng).map(|w| w.to_string())
+    }
+}
+
+/// Makes names for one world, never giving out the same name twice.
+pub struct NameGenerator {
+    cultures: HashMap<CultureType, CultureNames>,
+    chains: HashMap<CultureType, MarkovChain>,
+    used: HashSet<String>,
+}
+
+impl NameGenerator {
+    pub fn new(data: &NameData) -> Self {
+        let mut cultures = HashMap::new();
+        let mut chains = HashMap::new();
+        for &culture in CultureType::all() {
+            let names = data.culture(culture);
+            if let NameModel::Markov { order, words } = &names.model {
+                chains.insert(culture, MarkovChain::train(words, *order));
+            }
+            cultures.insert(culture, names);
+        }
+        Self {
+            cultures,
+            chains,
+            used: HashSet::new(),
+        }
+    }
+
+    /// Mark a name as taken, such as an authored one. Returns whether it was
+    /// still free.
+    pub fn reserve(&mut self, name: &str) -> bool {
+        self.used.insert(name.to_string())
+    }
+
+    /// A bare word in a culture's style, capitalised.
+    pub fn word(&self, culture: CultureType, rng: &mut impl Rng) -> String {
+        let word = match &self.cultures[&culture].model {
+            NameModel::Syllables(grammar) => grammar.word(rng),
+            NameModel::Markov { .. } => {
+                let chain = &self.chains[&culture];
+                chain.generate(rng).or_else(|| chain.known_word(rng)).unwrap_or_default()
+            }
+        };
+        capitalise(&word)
+    }
+
+    /// A name for a `kind` of thing belonging to `culture`, unlike any given
+    /// out before.
+    pub fn name(&mut self, culture: CultureType, kind: NameKind, rng: &mut impl Rng) -> String {
+        let templates: Vec<String> = match self.cultures[&culture].templates.get(&kind) {
+            Some(templates) if !templates.is_empty() => templates.clone(),
+            _ => kind.default_templates().iter().map(|t| t.to_string()).collect(),
+        };
+
+        let mut name = String::new();
+        for _ in 0..NAME_ATTEMPTS {
+            let template = &templates[rng.gen_range(0..templates.len())];
+            name = template.replace("{}", &self.word(culture, rng));
+            if !self.used.contains(&name) {
+                self.used.insert(name.clone());
+                return name;
+            }
+        }
+
+        // Every attempt was taken (a tiny word list): number the repeat
+        let numbered = (2..)
+            .map(|n| format!("{} {}", name, n))
+            .find(|n| !self.used.contains(n))
+            .unwrap();
+        self.used.insert(numbered.clone());
+        numbered
+    }
+}
+
+/// `word` with its first letter in upper case.
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Built-in naming of a culture archetype.
+fn builtin_names(culture: CultureType) -> CultureNames {
+    let list = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let grammar = |onsets: &[&str], nuclei: &[&str], codas: &[&str], syllables, endings: &[&str]| {
+        NameModel::Syllables(SyllableGrammar {
+            onsets: list(onsets),
+            nuclei: list(nuclei),
+            codas: list(codas),
+            syllables,
+            endings: list(endings),
+        })
+    };
+
+    let (model, templates): (NameModel, &[(NameKind, &[&str])]) = match culture {
+        // Soft, drawn-out sounds of the dusk band
+        CultureType::TwilightDweller => (
+            grammar(
+                &["l", "v", "s", "n", "m", "th", "r", "", "el"],
+                &["a", "e", "i", "ae", "io", "ue"],
+                &["", "", "n", "l", "s", "r"],
+                (1, 2),
+                &["", "", "a", "is", "iel", "ume"],
+            ),
+            &[
+                (NameKind::Faction, &["{} Confederacy", "Concord of {}"]),
+                (NameKind::Region, &["{} Marches", "{} Dusklands", "{}"]),
+                (NameKind::Sea, &["Sea of {}", "{} Shallows"]),
+            ],
+        ),
+        // Hard consonant clusters of the frozen north
+        CultureType::FrostKin => (
+            grammar(
+                &["k", "sk", "th", "br", "h", "v", "gr", "tr", "s", "j", "bj"],
+                &["a", "o", "e", "i", "u", "ei", "ja", "y"],
+                &["rn", "k", "ld", "r", "g", "nd", "st", ""],
+                (1, 2),
+                &["", "", "heim", "vik", "gard", "stad"],
+            ),
+            &[
+                (NameKind::Faction, &["{} Holds", "Jarldom of {}"]),
+                (NameKind::Region, &["{}mark", "{} Fells", "{}"]),
+                (NameKind::River, &["{} Elv", "{} River"]),
+                (NameKind::Sea, &["{} Ice Sea", "{} Sea"]),
+                (NameKind::Dynasty, &["House {}", "{}ling Line"]),
+            ],
+        ),
+        // Dry, guttural sounds of the sun-scorched dayside
+        CultureType::SunForged => (
+            grammar(
+                &["z", "q", "kh", "s", "r", "m", "t", "d", "h", "sh", "j"],
+                &["a", "aa", "i", "u", "e", "ai"],
+                &["", "r", "n", "m", "sh", "d", "b"],
+                (1, 2),
+                &["", "", "ar", "esh", "un", "ut"],
+            ),
+            &[
+                (NameKind::Faction, &["{} Tribes", "Sunward Tribes of {}"]),
+                (NameKind::Region, &["{} Sands", "{} Wastes", "{}"]),
+                (NameKind::River, &["Wadi {}", "{} River"]),
+                (NameKind::Dynasty, &["Clan {}", "Banu {}"]),
+            ],
+        ),
+        // Open, vowel-rich sounds of the coasts
+        CultureType::TideWalker => (
+            grammar(
+                &["", "k", "l", "m", "n", "p", "h", "w", "t"],
+                &["a", "e", "i", "o", "u", "au", "ai"],
+                &[""],
+                (2, 3),
+                &["", "", "a", "ua", "oa"],
+            ),
+            &[
+                (NameKind::Faction, &["{} League", "League of {}"]),
+                (NameKind::Region, &["{} Isles", "{} Coast", "{}"]),
+                (NameKind::Sea, &["{} Deep", "Sea of {}", "{} Sea"]),
+                (NameKind::Dynasty, &["{} Family", "House {}"]),
+            ],
+        ),
+        // Heavy, blunt sounds of the mountain halls
+        CultureType::StoneBorn => (
+            grammar(
+                &["d", "g", "b", "k", "th", "dr", "gr", "kh", "m", "n"],
+                &["u", "a", "o", "e", "ai"],
+                &["r", "m", "n", "k", "rd", "rn", "z", "nd", "l"],
+                (1, 2),
+                &["", "", "dur", "grim", "dun", "zad"],
+            ),
+            &[
+                (NameKind::Faction, &["{} Kingdoms", "Kingdom of {}"]),
+                (NameKind::Region, &["{} Heights", "{} Deeps", "{}"]),
+                (NameKind::Landmark, &["{}", "Deep {}", "{}'s"]),
+                (NameKind::Dynasty, &["Clan {}", "House {}"]),
+            ],
+        ),
+    };
+
+    CultureNames {
+        culture,
+        model,
+        templates: templates.iter().map(|&(kind, items)| (kind, list(items))).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_core::rng_for;
+
+    #[test]
+    fn names_do_not_repeat() {
+        let data = NameData::default();
+        let mut names = NameGenerator::new(&data);
+        let mut rng = rng_for(3, "test");
+        let mut seen = HashSet::new();
+        for &culture in CultureType::all() {
+            for kind in [NameKind::Settlement, NameKind::Faction, NameKind::River, NameKind::Sea] {
+                for _ in 0..40 {
+                    assert!(seen.insert(names.name(culture, kind, &mut rng)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cultures_sound_different() {
+        let names = NameGenerator::new(&NameData::default());
+        let mut rng = rng_for(5, "test");
+        let mut letters = |culture| -> HashSet<char> {
+            (0..60).flat_map(|_| names.word(culture, &mut rng).to_lowercase().chars().collect::<Vec<_>>()).collect()
+        };
+        // The soft coastal tongue has none of the mountain halls' d or z
+        let tide = letters(CultureType::TideWalker);
+        let stone = letters(CultureType::StoneBorn);
+        assert!(stone.contains(&'r') && stone.contains(&'d'));
+        assert!(!tide.contains(&'z') && !tide.contains(&'d'));
+    }
+
+    #[test]
+    fn markov_names_come_from_the_data_file() {
+        let source = r#"[(
+            culture: FrostKin,
+            model: Markov(order: 2, words: ["bergen", "bergholm", "holmvik", "narvik", "tromsholm", "vikberg"]),
+            templates: { Faction: ["{} Compact"] },
+        )]"#;
+        let data = NameData::from_ron(source).unwrap();
+        let mut names = NameGenerator::new(&data);
+        let mut rng = rng_for(9, "test");
+
+        let faction = names.name(CultureType::FrostKin, NameKind::Faction, &mut rng);
+        assert!(faction.ends_with(" Compact"), "{}", faction);
+        // Kinds the file leaves out fall back to the defaults
+        let river = names.name(CultureType::FrostKin, NameKind::River, &mut rng);
+        assert!(river.contains("River"), "{}", river);
+
+        let empty = "[(culture: SunForged, model: Markov(order: 2, words: []))]";
+        assert!(matches!(NameData::from_ron(empty), Err(NameDataError::NoWords(CultureType::SunForged))));
+    }
+}
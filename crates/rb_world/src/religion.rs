@@ -0,0 +1,395 @@
+//! Religions and how far each has spread.
+//!
+//! A handful of faiths arise in settlements far apart, each with a temple by
+//! its birthplace. Belief then spreads from settlement to settlement, along
+//! roads and sea lanes and from each faction's capital to the rest of its
+//! realm. After the
+//! spread a second temple goes up wherever the faith took strongest hold away
+//! from home.
+
+use crate::culture::CultureType;
+use crate::definition::{City, CityTier, Landmark, LandmarkKind, Point2D, WorldDefinition};
+use crate::landmarks::{culture_at, landmark_name};
+use crate::namegen::{NameGenerator, NameKind};
+use rand::prelude::*;
+use rb_core::rng_for;
+use rb_noise::BiomeMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Fewest and most religions a world is given.
+const RELIGION_COUNT: (usize, usize) = (2, 4);
+
+/// Rounds of spreading between neighbouring settlements.
+const SPREAD_STEPS: usize = 12;
+
+/// How far each round moves a settlement's beliefs toward its neighbours'.
+const SPREAD_RATE: f32 = 0.5;
+
+/// Weight of a settlement's tie to its capital, against 1 for a road.
+const CAPITAL_TIE: f32 = 0.5;
+
+/// Shares below this are dropped from a settlement's faiths.
+const MIN_SHARE: f32 = 0.01;
+
+/// Temples stand this far from the settlement they serve.
+const TEMPLE_RING: (f64, f64) = (3.0, 8.0);
+
+/// Sites tried around a settlement before a temple is given up.
+const TEMPLE_ATTEMPTS: usize = 12;
+
+/// Temples keep at least this far from other landmarks.
+const MIN_LANDMARK_DISTANCE: f64 = 4.0;
+
+/// Overlay colours, one per religion in order.
+const RELIGION_COLOURS: [[u8; 4]; RELIGION_COUNT.1] = [
+    [240, 210, 90, 180],
+    [170, 100, 210, 180],
+    [60, 190, 170, 180],
+    [210, 70, 80, 180],
+];
+
+/// Settlements further than this from any cell are not drawn on it.
+const OVERLAY_REACH: f64 = 48.0;
+
+/// A faith and its holy places.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Religion {
+    pub id: u32,
+    pub name: String,
+    /// Overlay colour (RGBA).
+    pub color: [u8; 4],
+    /// Culture whose tongue names the faith.
+    pub culture: CultureType,
+    /// Settlement the faith arose in. Its people hold to it whatever their
+    /// neighbours believe.
+    pub origin: u32,
+    /// Landmarks sacred to the faith, the temple at its birthplace first.
+    pub holy_sites: Vec<u32>,
+}
+
+/// Share of a settlement's people holding to a religion.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Adherence {
+    pub religion: u32,
+    /// Fraction of the population, 0 to 1.
+    pub share: f32,
+}
+
+fn distance(a: Point2D, b: Point2D) -> f64 {
+    let dx = a.x - b.x;
+    let dy = a.y - b.y;
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Give the world 2–4 religions, spread them between its settlements and
+/// raise their temples. Replaces any religions already there; their holy
+/// sites are left standing as ordinary temples.
+pub fn found_religions(biome_map: &BiomeMap, world_def: &mut WorldDefinition, names: &mut NameGenerator, seed: u32) {
+    let mut rng = rng_for(seed, "religions");
+    world_def.religions.clear();
+    let count = rng.gen_range(RELIGION_COUNT.0..=RELIGION_COUNT.1);
+
+    for (id, origin) in (1..).zip(origins(world_def, count, &mut rng)) {
+        let city = world_def.cities.iter().find(|c| c.id == origin).expect("origin is a settlement");
+        let position = city.position;
+        let culture = world_def
+            .factions
+            .iter()
+            .find(|f| f.settlement_ids.contains(&origin))
+            .map_or_else(|| culture_at(biome_map, &world_def.cultures, position), |f| f.culture);
+        let religion = Religion {
+            id,
+            name: names.name(culture, NameKind::Religion, &mut rng),
+            color: RELIGION_COLOURS[(id as usize - 1) % RELIGION_COLOURS.len()],
+            culture,
+            origin,
+            holy_sites: Vec::new(),
+        };
+        world_def.religions.push(religion);
+    }
+    spread_religions(world_def, SPREAD_STEPS);
+
+    for index in 0..world_def.religions.len() {
+        let religion = &world_def.religions[index];
+        let (id, origin) = (religion.id, religion.origin);
+        // The settlement outside the birthplace where the faith holds best
+        let pilgrimage = world_def
+            .cities
+            .iter()
+            .filter(|c| c.id != origin)
+            .filter_map(|c| Some((c.id, c.faiths.iter().find(|a| a.religion == id)?.share)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(city, _)| city);
+
+        for city in [Some(origin), pilgrimage].into_iter().flatten() {
+            if let Some(temple) = raise_temple(biome_map, world_def, city, index, names, &mut rng) {
+                world_def.religions[index].holy_sites.push(temple);
+            }
+        }
+    }
+}
+
+/// Birthplaces for `count` religions: a random capital, then each time the
+/// capital or town furthest from those already chosen.
+fn origins(world_def: &WorldDefinition, count: usize, rng: &mut impl Rng) -> Vec<u32> {
+    let major: Vec<_> = world_def.cities.iter().filter(|c| c.tier != CityTier::Village).collect();
+    let candidates = if major.is_empty() { world_def.cities.iter().collect() } else { major };
+    let capitals: Vec<_> = candidates.iter().filter(|c| c.tier == CityTier::Capital).collect();
+    let Some(first) = capitals.choose(rng).map(|c| **c).or_else(|| candidates.choose(rng).copied()) else {
+        return Vec::new();
+    };
+
+    let mut chosen = vec![first];
+    while chosen.len() < count.min(candidates.len()) {
+        let remoteness = |c: &&City| {
+            chosen.iter().map(|o| distance(o.position, c.position)).fold(f64::INFINITY, f64::min)
+        };
+        let furthest = candidates
+            .iter()
+            .filter(|c| chosen.iter().all(|o| o.id != c.id))
+            .max_by(|a, b| remoteness(a).total_cmp(&remoteness(b)));
+        chosen.extend(furthest.copied());
+    }
+    chosen.iter().map(|c| c.id).collect()
+}
+
+/// Spread each religion out from its birthplace for `steps` rounds, setting
+/// every settlement's faiths. Each round a settlement's beliefs move part way
+/// toward the average of its neighbours': the settlements it shares a road
+/// or sea lane with, and its faction's capital.
+pub fn spread_religions(world_def: &mut WorldDefinition, steps: usize) {
+    let index: HashMap<u32, usize> = world_def.cities.iter().enumerate().map(|(i, c)| (c.id, i)).collect();
+    let religions = world_def.religions.len();
+    let mut shares = vec![vec![0.0f32; religions]; world_def.cities.len()];
+    let mut fixed = vec![false; world_def.cities.len()];
+    for (k, religion) in world_def.religions.iter().enumerate() {
+        if let Some(&i) = index.get(&religion.origin) {
+            shares[i][k] = 1.0;
+            fixed[i] = true;
+        }
+    }
+
+    let mut ties: Vec<Vec<(usize, f32)>> = vec![Vec::new(); world_def.cities.len()];
+    let mut tie = |a: u32, b: u32, weight: f32| {
+        if let (Some(&i), Some(&j)) = (index.get(&a), index.get(&b)) {
+            if i != j {
+                ties[i].push((j, weight));
+                ties[j].push((i, weight));
+            }
+        }
+    };
+    for road in &world_def.roads {
+        tie(road.connects.0, road.connects.1, 1.0);
+    }
+    for lane in &world_def.sea_routes {
+        tie(lane.connects.0, lane.connects.1, 1.0);
+    }
+    for faction in &world_def.factions {
+        let Some(capital) = faction.capital_id else { continue };
+        for &city in &faction.settlement_ids {
+            tie(capital, city, CAPITAL_TIE);
+        }
+    }
+
+    for _ in 0..steps {
+        let before = shares.clone();
+        for (i, neighbours) in ties.iter().enumerate() {
+            let total: f32 = neighbours.iter().map(|&(_, w)| w).sum();
+            if fixed[i] || total <= 0.0 {
+                continue;
+            }
+            for k in 0..religions {
+                let mean = neighbours.iter().map(|&(j, w)| w * before[j][k]).sum::<f32>() / total;
+                shares[i][k] = (1.0 - SPREAD_RATE) * before[i][k] + SPREAD_RATE * mean;
+            }
+        }
+    }
+
+    let ids: Vec<u32> = world_def.religions.iter().map(|r| r.id).collect();
+    for (city, shares) in world_def.cities.iter_mut().zip(shares) {
+        city.faiths = ids
+            .iter()
+            .zip(shares)
+            .filter(|&(_, share)| share >= MIN_SHARE)
+            .map(|(&religion, share)| Adherence { religion, share })
+            .collect();
+        city.faiths.sort_by(|a, b| b.share.total_cmp(&a.share));
+    }
+}
+
+/// Build a temple on land near a settlement, in the tongue of the religion
+/// at `index`. Returns the new landmark's id, or `None` if no site was found.
+fn raise_temple(
+    biome_map: &BiomeMap,
+    world_def: &mut WorldDefinition,
+    city_id: u32,
+    index: usize,
+    names: &mut NameGenerator,
+    rng: &mut impl Rng,
+) -> Option<u32> {
+    let city = world_def.cities.iter().find(|c| c.id == city_id)?;
+    let religion = &world_def.religions[index];
+    for _ in 0..TEMPLE_ATTEMPTS {
+        let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+        let reach = rng.gen_range(TEMPLE_RING.0..TEMPLE_RING.1);
+        let site = Point2D::new(city.position.x + reach * angle.cos(), city.position.y + reach * angle.sin());
+        if site.x < 0.0 || site.y < 0.0 {
+            continue;
+        }
+        let on_land = biome_map.get_biome(site.x as usize, site.y as usize).is_some_and(|b| !b.is_ocean());
+        let clear = world_def.landmarks.iter().all(|l| distance(l.position, site) >= MIN_LANDMARK_DISTANCE);
+        if !on_land || !clear {
+            continue;
+        }
+
+        let id = world_def.landmarks.iter().map(|l| l.id).max().unwrap_or(0) + 1;
+        let name = landmark_name(LandmarkKind::Temple, Some(city), religion.culture, names, rng);
+        let mut temple = Landmark::new(id, name, site, LandmarkKind::Temple);
+        temple.description = Some(format!("Holy site of {}", religion.name));
+        world_def.landmarks.push(temple);
+        return Some(id);
+    }
+    None
+}
+
+/// RGBA overlay of each cell's prevailing faith: the main religion of the
+/// nearest settlement within reach, more opaque the more of its people hold
+/// to it. Where territory has been generated, only claimed cells are drawn.
+pub fn religion_image(world_def: &WorldDefinition) -> Vec<u8> {
+    let (width, height) = (world_def.width, world_def.height);
+    let mut rgba = vec![0; width * height * 4];
+    let colours: HashMap<u32, [u8; 4]> = world_def.religions.iter().map(|r| (r.id, r.color)).collect();
+    let believers: Vec<_> = world_def
+        .cities
+        .iter()
+        .filter_map(|c| {
+            let main = c.faiths.first()?;
+            Some((c.position, colours.get(&main.religion)?, main.share))
+        })
+        .collect();
+    if believers.is_empty() {
+        return rgba;
+    }
+
+    for (i, pixel) in rgba.chunks_exact_mut(4).enumerate() {
+        let (x, y) = (i % width, i / width);
+        let territory = world_def.territory_cache.as_ref();
+        if territory.is_some_and(|t| !t.is_claimed(x, y)) {
+            continue;
+        }
+        let cell = Point2D::new(x as f64 + 0.5, y as f64 + 0.5);
+        let nearest = believers
+            .iter()
+            .map(|b| (b, distance(b.0, cell)))
+            .filter(|(_, d)| *d <= OVERLAY_REACH)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((&(_, &[r, g, b, a], share), _)) = nearest {
+            pixel.copy_from_slice(&[r, g, b, (a as f32 * share) as u8]);
+        }
+    }
+    rgba
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::faction::Faction;
+    use crate::roads::{Road, RoadType};
+    use crate::sea_routes::SeaRoute;
+
+    /// A row of five towns joined by roads, the first and last capitals.
+    fn world() -> WorldDefinition {
+        let mut world = WorldDefinition {
+            width: 128,
+            height: 32,
+            ..Default::default()
+        };
+        for id in 1..=5 {
+            let tier = if id == 1 || id == 5 { CityTier::Capital } else { CityTier::Town };
+            world.cities.push(City::new(id, format!("City {}", id), Point2D::new(id as f64 * 20.0, 16.0), tier));
+        }
+        for id in 1..5 {
+            world.roads.push(Road::new(id, (id, id + 1), RoadType::Provincial));
+        }
+        world
+    }
+
+    fn religion(id: u32, origin: u32) -> Religion {
+        Religion {
+            id,
+            name: format!("Faith {}", id),
+            color: RELIGION_COLOURS[id as usize - 1],
+            culture: CultureType::TideWalker,
+            origin,
+            holy_sites: Vec::new(),
+        }
+    }
+
+    fn share(world: &WorldDefinition, city: u32, religion: u32) -> f32 {
+        let city = world.cities.iter().find(|c| c.id == city).unwrap();
+        city.faiths.iter().find(|a| a.religion == religion).map_or(0.0, |a| a.share)
+    }
+
+    #[test]
+    fn faith_spreads_along_roads_and_fades_with_distance() {
+        let mut world = world();
+        world.religions = vec![religion(1, 1), religion(2, 5)];
+        spread_religions(&mut world, SPREAD_STEPS);
+
+        assert_eq!(share(&world, 1, 1), 1.0);
+        assert_eq!(share(&world, 5, 2), 1.0);
+        assert!(share(&world, 2, 1) > share(&world, 3, 1));
+        assert!(share(&world, 3, 1) > share(&world, 4, 1));
+        // The middle town is torn evenly between the two
+        assert!((share(&world, 3, 1) - share(&world, 3, 2)).abs() < 1e-4);
+
+        for city in &world.cities {
+            let total: f32 = city.faiths.iter().map(|a| a.share).sum();
+            assert!(total <= 1.0 + 1e-4);
+            assert!(city.faiths.windows(2).all(|pair| pair[0].share >= pair[1].share));
+        }
+    }
+
+    #[test]
+    fn capitals_carry_their_faith_across_the_realm() {
+        let mut world = world();
+        world.roads.clear();
+        world.cities.push(City::new(6, "Outpost".into(), Point2D::new(100.0, 30.0), CityTier::Village));
+        let mut realm = Faction::new(1, "Realm".into(), CultureType::FrostKin);
+        realm.set_capital(1);
+        realm.add_settlement(6);
+        world.factions.push(realm);
+        world.religions = vec![religion(1, 1)];
+        spread_religions(&mut world, SPREAD_STEPS);
+
+        assert!(share(&world, 6, 1) > 0.5);
+        assert_eq!(share(&world, 3, 1), 0.0);
+    }
+
+    #[test]
+    fn sea_lanes_carry_faith_overseas() {
+        let mut world = world();
+        world.roads.clear();
+        world.sea_routes.push(SeaRoute {
+            id: 1,
+            connects: (1, 4),
+            ports: (1, 2),
+            waypoints: Vec::new(),
+        });
+        world.religions = vec![religion(1, 1)];
+        spread_religions(&mut world, SPREAD_STEPS);
+
+        assert!(share(&world, 4, 1) > 0.5);
+        assert_eq!(share(&world, 2, 1), 0.0);
+    }
+
+    #[test]
+    fn origins_are_far_apart() {
+        let world = world();
+        let mut rng = rng_for(3, "test");
+        let mut chosen = origins(&world, 2, &mut rng);
+        chosen.sort();
+        assert_eq!(chosen, [1, 5]);
+    }
+}
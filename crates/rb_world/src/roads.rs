@@ -2,9 +2,12 @@
 //!
 //! Roads connect settlements, and trade routes represent economic connections.
 
-use crate::definition::Point2D;
-use rb_core::TileType;
+use crate::definition::{City, CityTier, Point2D};
+use pathfinding::prelude::{build_path, dijkstra, dijkstra_all};
+use rb_core::{BiomeRegistry, TileType};
+use rb_noise::BiomeMap;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Road quality/importance type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -15,15 +18,33 @@ pub enum RoadType {
     Provincial,
     /// Minor path to villages.
     Trail,
+    /// Boat crossing over water where no land path exists.
+    Ferry,
 }
 
 impl RoadType {
+    /// Every road type, from most to least important.
+    pub fn all() -> &'static [RoadType] {
+        &[RoadType::Imperial, RoadType::Provincial, RoadType::Trail, RoadType::Ferry]
+    }
+
+    /// Get a display name for this road type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RoadType::Imperial => "Imperial",
+            RoadType::Provincial => "Provincial",
+            RoadType::Trail => "Trail",
+            RoadType::Ferry => "Ferry",
+        }
+    }
+
     /// Get the display width in pixels for this road type.
     pub fn width(&self) -> f32 {
         match self {
             RoadType::Imperial => 3.0,
             RoadType::Provincial => 2.0,
             RoadType::Trail => 1.0,
+            RoadType::Ferry => 1.0,
         }
     }
 
@@ -33,12 +54,13 @@ impl RoadType {
             RoadType::Imperial => [220, 180, 80],   // Gold
             RoadType::Provincial => [180, 180, 180], // Silver
             RoadType::Trail => [140, 110, 80],       // Brown
+            RoadType::Ferry => [70, 130, 180],       // Steel blue
         }
     }
 }
 
 /// A road connecting two settlements.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Road {
     /// Unique identifier.
     pub id: u32,
@@ -81,6 +103,41 @@ impl Road {
     pub fn connects_settlement(&self, city_id: u32) -> bool {
         self.connects.0 == city_id || self.connects.1 == city_id
     }
+
+    /// Index of the waypoint closest to `point`, if any is within
+    /// `max_distance`.
+    pub fn nearest_waypoint(&self, point: Point2D, max_distance: f64) -> Option<usize> {
+        self.waypoints
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, (p.x - point.x).hypot(p.y - point.y)))
+            .filter(|&(_, distance)| distance <= max_distance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// The segment closest to `point`, as the index of its first waypoint,
+    /// and the distance to it, if within `max_distance`.
+    pub fn nearest_segment(&self, point: Point2D, max_distance: f64) -> Option<(usize, f64)> {
+        self.waypoints
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| (i, distance_to_segment(point, w[0], w[1])))
+            .filter(|&(_, distance)| distance <= max_distance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Distance from `point` to the segment from `a` to `b`.
+fn distance_to_segment(point: Point2D, a: Point2D, b: Point2D) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0.0 {
+        (((point.x - a.x) * dx + (point.y - a.y) * dy) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (a.x + t * dx - point.x).hypot(a.y + t * dy - point.y)
 }
 
 /// Types of tradeable goods.
@@ -122,40 +179,37 @@ impl TradeGood {
         }
     }
 
+    /// Returns all trade goods.
+    pub fn all() -> &'static [TradeGood] {
+        &[
+            TradeGood::Food,
+            TradeGood::Ore,
+            TradeGood::Timber,
+            TradeGood::Textiles,
+            TradeGood::Luxury,
+            TradeGood::Weapons,
+            TradeGood::Salt,
+            TradeGood::Fish,
+            TradeGood::Furs,
+        ]
+    }
+
+    /// Trade good by variant name, as biome registries list them.
+    pub fn from_name(name: &str) -> Option<TradeGood> {
+        TradeGood::all()
+            .iter()
+            .copied()
+            .find(|good| format!("{:?}", good) == name)
+    }
+
     /// Get typical goods produced by a biome.
-    pub fn from_biome(biome: TileType) -> Vec<TradeGood> {
-        match biome {
-            // Water
-            TileType::Sea => vec![TradeGood::Fish],
-            TileType::OceanTrench => vec![TradeGood::Fish],
-            TileType::River => vec![TradeGood::Fish, TradeGood::Food],
-
-            // Coastal
-            TileType::Beach => vec![TradeGood::Fish, TradeGood::Salt],
-
-            // Frozen
-            TileType::White => vec![TradeGood::Fish, TradeGood::Furs],
-            TileType::Glacier => vec![TradeGood::Furs],
-            TileType::Snow => vec![TradeGood::Furs],
-            TileType::Tundra => vec![TradeGood::Furs],
-            TileType::Taiga => vec![TradeGood::Timber, TradeGood::Furs],
-
-            // Temperate
-            TileType::Plains => vec![TradeGood::Food, TradeGood::Textiles],
-            TileType::Forest => vec![TradeGood::Timber, TradeGood::Furs],
-            TileType::Marsh => vec![TradeGood::Fish, TradeGood::Food],
-            TileType::Steppe => vec![TradeGood::Food, TradeGood::Furs],
-            TileType::Mountain => vec![TradeGood::Ore, TradeGood::Weapons],
-            TileType::Plateau => vec![TradeGood::Ore, TradeGood::Food],
-
-            // Hot
-            TileType::Savanna => vec![TradeGood::Food, TradeGood::Furs],
-            TileType::Jungle => vec![TradeGood::Timber, TradeGood::Luxury],
-            TileType::Desert => vec![TradeGood::Salt, TradeGood::Luxury],
-            TileType::Sahara => vec![TradeGood::Luxury],
-            TileType::Badlands => vec![TradeGood::Ore],
-            TileType::Volcanic => vec![TradeGood::Ore, TradeGood::Luxury],
-        }
+    pub fn from_biome(registry: &BiomeRegistry, biome: TileType) -> Vec<TradeGood> {
+        registry
+            .get(biome)
+            .trade_goods
+            .iter()
+            .filter_map(|name| TradeGood::from_name(name))
+            .collect()
     }
 }
 
@@ -198,43 +252,184 @@ impl TradeRoute {
     pub fn is_international(&self) -> bool {
         self.faction_ids.len() > 1
     }
+
+    /// Estimate economic importance [0.0, 1.0] from the settlements it serves.
+    ///
+    /// Gravity model: volume grows with the geometric mean of endpoint
+    /// populations (log scaled) and falls off with distance travelled between
+    /// settlements. Carrying more kinds of goods adds up to a quarter on top.
+    pub fn compute_importance(&self, cities: &[City]) -> f64 {
+        let stops: Vec<&City> = self
+            .settlement_ids
+            .iter()
+            .filter_map(|id| cities.iter().find(|c| c.id == *id))
+            .collect();
+        let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+            return 0.0;
+        };
+
+        let geo_mean = (first.population as f64 * last.population as f64).sqrt();
+        let max_population = CityTier::Capital.population_range().1 as f64;
+        let population_score = ((1.0 + geo_mean).ln() / (1.0 + max_population).ln()).min(1.0);
+
+        let length: f64 = stops
+            .windows(2)
+            .map(|w| {
+                let dx = w[1].position.x - w[0].position.x;
+                let dy = w[1].position.y - w[0].position.y;
+                (dx * dx + dy * dy).sqrt()
+            })
+            .sum();
+        let distance_factor = 1.0 / (1.0 + length / 512.0);
+
+        let diversity = self.goods.len() as f64 / TradeGood::all().len() as f64;
+
+        (population_score * distance_factor * (0.75 + 0.25 * diversity)).clamp(0.0, 1.0)
+    }
+}
+
+/// One direction of a road in a [`RoadGraph`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoadEdge {
+    /// Settlement at the other end.
+    pub to: u32,
+    /// Road this edge travels along.
+    pub road_id: u32,
+    /// Road length in world units.
+    pub length: f64,
+}
+
+/// Settlement adjacency built from a road network.
+///
+/// Nodes are settlement IDs, edges are roads (traversable both ways).
+#[derive(Debug, Clone, Default)]
+pub struct RoadGraph {
+    adjacency: BTreeMap<u32, Vec<RoadEdge>>,
 }
 
-/// Movement cost for pathfinding through different terrain.
-pub fn terrain_movement_cost(biome: TileType) -> f64 {
-    match biome {
-        // Impassable by land
-        TileType::Sea | TileType::OceanTrench | TileType::White | TileType::Glacier => {
-            f64::INFINITY
+impl RoadGraph {
+    /// Build the graph from a list of roads.
+    pub fn new(roads: &[Road]) -> Self {
+        let mut adjacency: BTreeMap<u32, Vec<RoadEdge>> = BTreeMap::new();
+        for road in roads {
+            let (a, b) = road.connects;
+            let length = road.length();
+            adjacency.entry(a).or_default().push(RoadEdge { to: b, road_id: road.id, length });
+            adjacency.entry(b).or_default().push(RoadEdge { to: a, road_id: road.id, length });
+        }
+        Self { adjacency }
+    }
+
+    /// Include settlements that no road touches, so they show up as their
+    /// own components.
+    pub fn with_cities(mut self, city_ids: impl IntoIterator<Item = u32>) -> Self {
+        for id in city_ids {
+            self.adjacency.entry(id).or_default();
+        }
+        self
+    }
+
+    /// Roads leaving a settlement.
+    pub fn neighbors(&self, city_id: u32) -> &[RoadEdge] {
+        self.adjacency.get(&city_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Shortest route by road length, as settlement IDs from `from` to `to`
+    /// inclusive. Returns `None` if either is unknown or they aren't connected.
+    pub fn shortest_path(&self, from: u32, to: u32) -> Option<Vec<u32>> {
+        if !self.adjacency.contains_key(&from) || !self.adjacency.contains_key(&to) {
+            return None;
         }
-        TileType::Volcanic => 10.0, // Dangerous
-
-        // Difficult terrain
-        TileType::Mountain => 8.0,
-        TileType::Snow | TileType::Tundra => 6.0,
-        TileType::Badlands => 5.5,
-        TileType::Plateau => 5.0,
-        TileType::Jungle | TileType::Marsh => 4.5,
-        TileType::Desert | TileType::Sahara => 4.0,
-        TileType::Taiga => 3.5,
-
-        // Moderate terrain
-        TileType::Forest => 3.0,
-        TileType::Steppe | TileType::Savanna => 1.5,
-        TileType::Beach => 1.5,
-        TileType::River => 2.0, // Need to ford or build bridges
-
-        // Easy terrain
-        TileType::Plains => 1.0, // Ideal for roads
+
+        // Dijkstra needs an Ord cost, so measure in thousandths of a unit
+        dijkstra(
+            &from,
+            |&id| {
+                self.neighbors(id)
+                    .iter()
+                    .map(|edge| (edge.to, (edge.length * 1000.0).round() as u64))
+                    .collect::<Vec<_>>()
+            },
+            |&id| id == to,
+        )
+        .map(|(path, _)| path)
+    }
+
+    /// Shortest routes by road length from `from` to every other settlement
+    /// it can reach: the settlement path, `from` to destination inclusive,
+    /// and its length, keyed by destination.
+    pub fn paths_from(&self, from: u32) -> BTreeMap<u32, (Vec<u32>, f64)> {
+        let parents = dijkstra_all(&from, |&id| {
+            self.neighbors(id)
+                .iter()
+                .map(|edge| (edge.to, (edge.length * 1000.0).round() as u64))
+                .collect::<Vec<_>>()
+        });
+        parents
+            .iter()
+            .filter(|(&to, _)| to != from)
+            .map(|(&to, &(_, cost))| (to, (build_path(&to, &parents), cost as f64 / 1000.0)))
+            .collect()
+    }
+
+    /// Road IDs travelled along a settlement path, picking the shortest road
+    /// where two settlements are joined by more than one.
+    pub fn path_roads(&self, path: &[u32]) -> Vec<u32> {
+        path.windows(2)
+            .filter_map(|pair| {
+                self.neighbors(pair[0])
+                    .iter()
+                    .filter(|edge| edge.to == pair[1])
+                    .min_by(|a, b| a.length.total_cmp(&b.length))
+                    .map(|edge| edge.road_id)
+            })
+            .collect()
+    }
+
+    /// Groups of mutually reachable settlements, each sorted by ID.
+    pub fn connected_components(&self) -> Vec<Vec<u32>> {
+        let mut visited = std::collections::HashSet::new();
+        let mut components = Vec::new();
+
+        for &start in self.adjacency.keys() {
+            if !visited.insert(start) {
+                continue;
+            }
+            let mut component = vec![start];
+            let mut stack = vec![start];
+            while let Some(id) = stack.pop() {
+                for edge in self.neighbors(id) {
+                    if visited.insert(edge.to) {
+                        component.push(edge.to);
+                        stack.push(edge.to);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
     }
 }
 
+/// Share of the movement cost saved at full river flow, where goods can go
+/// by boat instead of overland.
+const RIVER_TRADE_DISCOUNT: f64 = 0.6;
+
+/// Cost of moving goods through a cell: the terrain's movement cost, cut
+/// along river courses in proportion to flow.
+pub fn calculate_trade_cost(registry: &BiomeRegistry, biome_map: &BiomeMap, x: usize, y: usize) -> f64 {
+    let Some(biome) = biome_map.get_biome(x, y) else {
+        return f64::INFINITY;
+    };
+    let flow = biome_map.river_flow(x, y).clamp(0.0, 1.0);
+    registry.movement_cost(biome) * (1.0 - RIVER_TRADE_DISCOUNT * flow)
+}
+
 /// Check if terrain is passable for road building.
 pub fn is_passable(biome: TileType) -> bool {
-    !matches!(
-        biome,
-        TileType::Sea | TileType::OceanTrench | TileType::White | TileType::Glacier
-    )
+    !(biome.is_ocean() || matches!(biome, TileType::White | TileType::Glacier))
 }
 
 #[cfg(test)]
@@ -256,18 +451,133 @@ mod tests {
 
     #[test]
     fn plains_are_cheapest() {
-        assert!(terrain_movement_cost(TileType::Plains) < terrain_movement_cost(TileType::Mountain));
+        let registry = BiomeRegistry::default();
+        assert!(registry.movement_cost(TileType::Plains) < registry.movement_cost(TileType::Mountain));
     }
 
     #[test]
     fn sea_is_impassable() {
         assert!(!is_passable(TileType::Sea));
-        assert!(terrain_movement_cost(TileType::Sea).is_infinite());
+        assert!(BiomeRegistry::default().movement_cost(TileType::Sea).is_infinite());
+    }
+
+    #[test]
+    fn lakes_are_crossed_by_ferry() {
+        assert!(is_passable(TileType::Lake));
+        let registry = BiomeRegistry::default();
+        let lake = registry.movement_cost(TileType::Lake);
+        assert!(lake.is_finite() && lake > registry.movement_cost(TileType::River));
+    }
+
+    #[test]
+    fn rivers_lower_trade_cost() {
+        let mut biome_map = BiomeMap::generate(42, 16, 16);
+        biome_map.biomes.fill(TileType::Plains);
+        biome_map.rivers.fill(0.0);
+        biome_map.rivers[5 * 16 + 5] = 0.8;
+        let registry = BiomeRegistry::default();
+
+        let dry = calculate_trade_cost(&registry, &biome_map, 8, 8);
+        assert_eq!(dry, registry.movement_cost(TileType::Plains));
+        assert!(calculate_trade_cost(&registry, &biome_map, 5, 5) < dry);
+
+        // No river field at all: terrain cost only
+        biome_map.rivers.clear();
+        assert_eq!(calculate_trade_cost(&registry, &biome_map, 5, 5), dry);
+    }
+
+    #[test]
+    fn fish_come_from_shallows() {
+        let registry = BiomeRegistry::default();
+        assert!(TradeGood::from_biome(&registry, TileType::ShallowSea).contains(&TradeGood::Fish));
+        assert!(!TradeGood::from_biome(&registry, TileType::DeepSea).contains(&TradeGood::Fish));
+        assert!(!is_passable(TileType::DeepSea));
     }
 
     #[test]
     fn plains_produce_food() {
-        let goods = TradeGood::from_biome(TileType::Plains);
+        let goods = TradeGood::from_biome(&BiomeRegistry::default(), TileType::Plains);
         assert!(goods.contains(&TradeGood::Food));
+        assert_eq!(TradeGood::from_name("Luxury"), Some(TradeGood::Luxury));
+    }
+
+    #[test]
+    fn capital_routes_outrank_village_routes() {
+        let cities = vec![
+            City::new(1, "A".into(), Point2D::new(0.0, 0.0), CityTier::Capital),
+            City::new(2, "B".into(), Point2D::new(200.0, 0.0), CityTier::Capital),
+            City::new(3, "C".into(), Point2D::new(0.0, 100.0), CityTier::Village),
+            City::new(4, "D".into(), Point2D::new(200.0, 100.0), CityTier::Village),
+        ];
+
+        let mut capitals = TradeRoute::new(1);
+        capitals.settlement_ids = vec![1, 2];
+        capitals.goods = vec![TradeGood::Food];
+        let mut villages = TradeRoute::new(2);
+        villages.settlement_ids = vec![3, 4];
+        villages.goods = vec![TradeGood::Food];
+
+        let capital_importance = capitals.compute_importance(&cities);
+        let village_importance = villages.compute_importance(&cities);
+        assert!(capital_importance > village_importance);
+        assert!((0.0..=1.0).contains(&capital_importance));
+    }
+
+    fn straight_road(id: u32, connects: (u32, u32), from: (f64, f64), to: (f64, f64)) -> Road {
+        let mut road = Road::new(id, connects, RoadType::Trail);
+        road.waypoints = vec![Point2D::new(from.0, from.1), Point2D::new(to.0, to.1)];
+        road
+    }
+
+    #[test]
+    fn shortest_path_prefers_shorter_roads() {
+        // 1 -> 2 -> 3 is 20 long, the direct 1 -> 3 road is 50
+        let roads = vec![
+            straight_road(1, (1, 2), (0.0, 0.0), (10.0, 0.0)),
+            straight_road(2, (2, 3), (10.0, 0.0), (20.0, 0.0)),
+            straight_road(3, (1, 3), (0.0, 0.0), (0.0, 50.0)),
+        ];
+        let graph = RoadGraph::new(&roads);
+
+        let path = graph.shortest_path(1, 3).unwrap();
+        assert_eq!(path, vec![1, 2, 3]);
+        assert_eq!(graph.path_roads(&path), vec![1, 2]);
+        assert_eq!(graph.neighbors(1).len(), 2);
+        assert!(graph.shortest_path(1, 99).is_none());
+
+        let paths = graph.paths_from(1);
+        assert_eq!(paths.keys().copied().collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(paths[&3], (vec![1, 2, 3], 20.0));
+    }
+
+    #[test]
+    fn roads_are_picked_by_waypoint_or_segment() {
+        let mut road = straight_road(1, (1, 2), (0.0, 0.0), (10.0, 0.0));
+        road.waypoints.push(Point2D::new(10.0, 10.0));
+
+        assert_eq!(road.nearest_waypoint(Point2D::new(9.0, 1.0), 3.0), Some(1));
+        assert_eq!(road.nearest_waypoint(Point2D::new(5.0, 0.0), 3.0), None);
+        assert_eq!(road.nearest_segment(Point2D::new(5.0, 1.0), 3.0), Some((0, 1.0)));
+        assert_eq!(road.nearest_segment(Point2D::new(12.0, 6.0), 3.0), Some((1, 2.0)));
+        // Past the end of a segment counts from its end point
+        assert_eq!(road.nearest_segment(Point2D::new(-3.0, 4.0), 10.0), Some((0, 5.0)));
+        assert_eq!(road.nearest_segment(Point2D::new(5.0, 5.0), 3.0), None);
+        assert_eq!(RoadType::all().iter().map(|t| t.name()).collect::<Vec<_>>(), ["Imperial", "Provincial", "Trail", "Ferry"]);
+    }
+
+    #[test]
+    fn components_split_disconnected_networks() {
+        let roads = vec![
+            straight_road(1, (1, 2), (0.0, 0.0), (1.0, 0.0)),
+            straight_road(2, (3, 4), (5.0, 0.0), (6.0, 0.0)),
+            straight_road(3, (4, 5), (6.0, 0.0), (7.0, 0.0)),
+        ];
+        let graph = RoadGraph::new(&roads).with_cities([6]);
+
+        assert_eq!(
+            graph.connected_components(),
+            vec![vec![1, 2], vec![3, 4, 5], vec![6]]
+        );
+        assert!(graph.shortest_path(1, 5).is_none());
     }
 }
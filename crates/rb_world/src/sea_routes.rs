@@ -0,0 +1,314 @@
+//! Shipping lanes between port settlements.
+//!
+//! Every settlement with a port sails to the nearest few other ports it can
+//! reach over open water; TideWalker ports, being seafarers, to more of them
+//! and further. Courses are found by A* over sea cells. Frozen ocean isn't
+//! open water, so ships go around the ice or not at all.
+
+use crate::culture::CultureType;
+use crate::definition::{City, LandmarkKind, Point2D, WorldDefinition};
+use crate::landmarks::{culture_at, HARBOR_RADIUS};
+use pathfinding::prelude::astar;
+use rb_noise::BiomeMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+
+/// Other ports each port sails to.
+const SEA_LANES: usize = 2;
+
+/// Other ports each TideWalker port sails to.
+const TIDE_WALKER_LANES: usize = 4;
+
+/// Furthest apart, straight across, two ports can be and still trade.
+const SEA_RANGE: f64 = 192.0;
+
+/// How far TideWalker ports sail.
+const TIDE_WALKER_RANGE: f64 = 384.0;
+
+/// A shipping lane between two port settlements.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeaRoute {
+    pub id: u32,
+    /// Settlements at either end.
+    pub connects: (u32, u32),
+    /// Port landmarks the ships sail between, in the same order.
+    pub ports: (u32, u32),
+    /// Course from the first port to the second.
+    pub waypoints: Vec<Point2D>,
+}
+
+impl SeaRoute {
+    /// Length of the course in world units.
+    pub fn length(&self) -> f64 {
+        self.waypoints
+            .windows(2)
+            .map(|w| {
+                let dx = w[1].x - w[0].x;
+                let dy = w[1].y - w[0].y;
+                (dx * dx + dy * dy).sqrt()
+            })
+            .sum()
+    }
+}
+
+/// A settlement's port: the settlement, the port landmark, the sea cell
+/// ships leave from and how far and often they sail.
+struct Harbour {
+    city: u32,
+    port: u32,
+    position: Point2D,
+    berth: (usize, usize),
+    lanes: usize,
+    range: f64,
+}
+
+/// Whether ships can cross a cell: open sea, not land or frozen ocean.
+fn sailable(biome_map: &BiomeMap, x: usize, y: usize) -> bool {
+    biome_map.get_biome(x, y).is_some_and(|b| b.is_ocean())
+}
+
+fn distance(a: Point2D, b: Point2D) -> f64 {
+    let dx = a.x - b.x;
+    let dy = a.y - b.y;
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Shipping lanes between the world's port settlements, numbered from 1.
+/// A port belongs to the nearest settlement within harbour reach; a
+/// settlement with several uses the closest.
+pub fn generate_sea_routes(biome_map: &BiomeMap, world_def: &WorldDefinition) -> Vec<SeaRoute> {
+    let harbours = harbours(biome_map, world_def);
+    if harbours.len() < 2 {
+        return Vec::new();
+    }
+    let bodies = water_bodies(biome_map);
+    let body = |h: &Harbour| bodies[h.berth.1 * biome_map.width + h.berth.0];
+
+    // Each harbour's nearest reachable harbours, each pair once
+    let mut pairs = BTreeSet::new();
+    for (i, harbour) in harbours.iter().enumerate() {
+        let mut others: Vec<(usize, f64)> = harbours
+            .iter()
+            .enumerate()
+            .filter(|&(j, other)| j != i && body(other) == body(harbour))
+            .map(|(j, other)| (j, distance(harbour.position, other.position)))
+            .filter(|&(_, d)| d <= harbour.range)
+            .collect();
+        others.sort_by(|a, b| a.1.total_cmp(&b.1));
+        pairs.extend(others.iter().take(harbour.lanes).map(|&(j, _)| (i.min(j), i.max(j))));
+    }
+
+    let mut routes = Vec::new();
+    for (i, j) in pairs {
+        let (from, to) = (&harbours[i], &harbours[j]);
+        let Some(course) = sail(biome_map, from.berth, to.berth) else { continue };
+        let mut waypoints = vec![from.position];
+        waypoints.extend(crate::civilization::simplify_path(&course));
+        waypoints.push(to.position);
+        waypoints.dedup();
+        routes.push(SeaRoute {
+            id: routes.len() as u32 + 1,
+            connects: (from.city, to.city),
+            ports: (from.port, to.port),
+            waypoints,
+        });
+    }
+    routes
+}
+
+/// Every settlement with a port, by settlement ID.
+fn harbours(biome_map: &BiomeMap, world_def: &WorldDefinition) -> Vec<Harbour> {
+    let mut harbours: Vec<Harbour> = Vec::new();
+    for port in world_def.landmarks.iter().filter(|l| l.kind == LandmarkKind::Port) {
+        let nearest = world_def
+            .cities
+            .iter()
+            .map(|c| (c, distance(c.position, port.position)))
+            .filter(|&(_, d)| d <= HARBOR_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((city, reach)) = nearest else { continue };
+        let Some(berth) = berth(biome_map, port.position) else { continue };
+
+        if let Some(existing) = harbours.iter_mut().find(|h| h.city == city.id) {
+            if reach < distance(city.position, existing.position) {
+                (existing.port, existing.position, existing.berth) = (port.id, port.position, berth);
+            }
+            continue;
+        }
+        let seafaring = culture_of(biome_map, world_def, city) == CultureType::TideWalker;
+        harbours.push(Harbour {
+            city: city.id,
+            port: port.id,
+            position: port.position,
+            berth,
+            lanes: if seafaring { TIDE_WALKER_LANES } else { SEA_LANES },
+            range: if seafaring { TIDE_WALKER_RANGE } else { SEA_RANGE },
+        });
+    }
+    harbours.sort_by_key(|h| h.city);
+    harbours
+}
+
+/// The culture of a settlement: its faction's, or failing that the one
+/// best suited to where it stands.
+fn culture_of(biome_map: &BiomeMap, world_def: &WorldDefinition, city: &City) -> CultureType {
+    world_def
+        .factions
+        .iter()
+        .find(|f| f.settlement_ids.contains(&city.id))
+        .map_or_else(|| culture_at(biome_map, &world_def.cultures, city.position), |f| f.culture)
+}
+
+/// A sea cell beside a port, preferring those straight off it to diagonals.
+fn berth(biome_map: &BiomeMap, port: Point2D) -> Option<(usize, usize)> {
+    const OFFSETS: [(i64, i64); 8] = [(0, -1), (1, 0), (0, 1), (-1, 0), (-1, -1), (1, -1), (1, 1), (-1, 1)];
+    OFFSETS.iter().find_map(|&(dx, dy)| {
+        let (x, y) = (port.x as i64 + dx, port.y as i64 + dy);
+        (x >= 0 && y >= 0 && sailable(biome_map, x as usize, y as usize)).then_some((x as usize, y as usize))
+    })
+}
+
+/// Label every sailable cell with the body of water it is part of, counting
+/// from 1; land and ice are 0. Ports on different bodies can't trade by sea.
+fn water_bodies(biome_map: &BiomeMap) -> Vec<u32> {
+    let (width, height) = (biome_map.width, biome_map.height);
+    let mut labels = vec![0; width * height];
+    let mut next = 0;
+    for start in 0..labels.len() {
+        if labels[start] != 0 || !sailable(biome_map, start % width, start / width) {
+            continue;
+        }
+        next += 1;
+        labels[start] = next;
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            let (x, y) = (cell % width, cell / width);
+            for (nx, ny) in neighbours(width, height, x, y) {
+                let index = ny * width + nx;
+                if labels[index] == 0 && sailable(biome_map, nx, ny) {
+                    labels[index] = next;
+                    queue.push_back(index);
+                }
+            }
+        }
+    }
+    labels
+}
+
+/// The eight cells around one, within the map.
+fn neighbours(width: usize, height: usize, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+    (-1i64..=1)
+        .flat_map(|dy| (-1i64..=1).map(move |dx| (dx, dy)))
+        .filter(|&offset| offset != (0, 0))
+        .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+        .filter(move |&(nx, ny)| nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height)
+        .map(|(nx, ny)| (nx as usize, ny as usize))
+}
+
+/// Shortest course over open water between two sea cells, cell by cell.
+fn sail(biome_map: &BiomeMap, from: (usize, usize), to: (usize, usize)) -> Option<Vec<(i32, i32)>> {
+    let goal = (to.0 as i32, to.1 as i32);
+    astar(
+        &(from.0 as i32, from.1 as i32),
+        |&(x, y)| {
+            neighbours(biome_map.width, biome_map.height, x as usize, y as usize)
+                .filter(|&(nx, ny)| sailable(biome_map, nx, ny))
+                .map(move |(nx, ny)| {
+                    let diagonal = nx as i32 != x && ny as i32 != y;
+                    ((nx as i32, ny as i32), if diagonal { 14 } else { 10 })
+                })
+                .collect::<Vec<_>>()
+        },
+        |&(x, y)| {
+            // Octile distance, which never overestimates on this grid
+            let (dx, dy) = ((x - goal.0).abs(), (y - goal.1).abs());
+            10 * dx.max(dy) + 4 * dx.min(dy)
+        },
+        |&cell| cell == goal,
+    )
+    .map(|(path, _)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definition::{CityTier, Landmark};
+    use crate::faction::Faction;
+    use rb_core::TileType;
+
+    /// 64x32 sea with land down both sides, split by an ice shelf at
+    /// x = 30..34 that leaves a channel along the bottom when `channel`.
+    fn ice_map(channel: bool) -> BiomeMap {
+        let mut biome_map = BiomeMap::generate(42, 64, 32);
+        let ice_rows = if channel { 26 } else { 32 };
+        for y in 0..biome_map.height {
+            for x in 0..biome_map.width {
+                biome_map.biomes[y * biome_map.width + x] = if !(8..56).contains(&x) {
+                    TileType::Plains
+                } else if (30..34).contains(&x) && y < ice_rows {
+                    TileType::White
+                } else {
+                    TileType::Sea
+                };
+            }
+        }
+        biome_map
+    }
+
+    /// A port town on each coast.
+    fn world() -> WorldDefinition {
+        let mut world = WorldDefinition {
+            width: 64,
+            height: 32,
+            cities: vec![
+                City::new(1, "West".into(), Point2D::new(4.0, 10.0), CityTier::Town),
+                City::new(2, "East".into(), Point2D::new(60.0, 10.0), CityTier::Town),
+            ],
+            ..Default::default()
+        };
+        world.landmarks = vec![
+            Landmark::new(1, "Port West".into(), Point2D::new(7.0, 10.0), LandmarkKind::Port),
+            Landmark::new(2, "Port East".into(), Point2D::new(56.0, 10.0), LandmarkKind::Port),
+        ];
+        world
+    }
+
+    #[test]
+    fn ships_sail_around_the_ice() {
+        let biome_map = ice_map(true);
+        let routes = generate_sea_routes(&biome_map, &world());
+
+        assert_eq!(routes.len(), 1);
+        let route = &routes[0];
+        assert_eq!((route.connects, route.ports), ((1, 2), (1, 2)));
+        assert_eq!(route.waypoints.first(), Some(&Point2D::new(7.0, 10.0)));
+        assert_eq!(route.waypoints.last(), Some(&Point2D::new(56.0, 10.0)));
+        assert!(route.length() > 49.0);
+
+        // Through the channel, never over the ice
+        let course = sail(&biome_map, (8, 10), (55, 10)).unwrap();
+        assert!(course.iter().all(|&(x, y)| sailable(&biome_map, x as usize, y as usize)));
+        assert!(course.iter().any(|&(x, y)| x == 31 && y >= 26));
+    }
+
+    #[test]
+    fn frozen_seas_cut_ports_off() {
+        assert!(generate_sea_routes(&ice_map(false), &world()).is_empty());
+        assert!(water_bodies(&ice_map(false)).contains(&2));
+    }
+
+    #[test]
+    fn tide_walker_ports_sail_further() {
+        let biome_map = ice_map(true);
+        let mut world = world();
+        let mut realm = Faction::new(1, "Realm".into(), CultureType::StoneBorn);
+        realm.add_settlement(1);
+        realm.add_settlement(2);
+        world.factions.push(realm);
+        let reach = |world: &WorldDefinition| harbours(&biome_map, world).iter().map(|h| (h.lanes, h.range)).collect::<Vec<_>>();
+        assert_eq!(reach(&world), [(SEA_LANES, SEA_RANGE); 2]);
+
+        world.factions[0].culture = CultureType::TideWalker;
+        assert_eq!(reach(&world), [(TIDE_WALKER_LANES, TIDE_WALKER_RANGE); 2]);
+    }
+}
@@ -4,17 +4,53 @@
 //! and spacing constraints.
 
 use crate::culture::{Culture, CultureType};
-use crate::definition::{City, CityTier, Point2D};
-use rb_core::TileType;
-use rb_noise::BiomeMap;
+use crate::definition::{City, CityTier, Landmark, LandmarkKind, Point2D};
+use crate::faction::Faction;
+use crate::namegen::{NameData, NameGenerator, NameKind};
+use crate::roads::Road;
+use rb_core::{rng_for, ResourceType, TileType};
+use rb_noise::{BiomeMap, Symmetry};
 use rand::prelude::*;
-use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
 
 /// Minimum distance between settlements (in world units).
-const MIN_SETTLEMENT_DISTANCE: f64 = 40.0;
+pub(crate) const MIN_SETTLEMENT_DISTANCE: f64 = 40.0;
 
 /// Threshold for settlement placement.
-const SETTLEMENT_THRESHOLD: f64 = 0.3;
+pub(crate) const SETTLEMENT_THRESHOLD: f64 = 0.3;
+
+/// Radius (in pixels) a settlement draws its industries from.
+const INDUSTRY_RADIUS: usize = 6;
+
+/// Roads a settlement needs to count as a crossroads for trade.
+const CROSSROADS_ROADS: usize = 3;
+
+/// Distance (in world units) over which a landmark sways nearby sites.
+const LANDMARK_INFLUENCE_RADIUS: f64 = 30.0;
+
+/// Suitabilities closer than this are treated as equal and ranked by position.
+const SUITABILITY_EPSILON: f64 = 1e-9;
+
+/// Whether a site scoring `a` at row-major position `a_pos` ranks above one
+/// scoring `b` at `b_pos`. Near-ties go to the earlier position, so plateaus
+/// yield one maximum whatever the float noise.
+fn outranks(a: f64, a_pos: (usize, usize), b: f64, b_pos: (usize, usize)) -> bool {
+    if (a - b).abs() <= SUITABILITY_EPSILON {
+        (a_pos.1, a_pos.0) < (b_pos.1, b_pos.0)
+    } else {
+        a > b
+    }
+}
+
+/// Order candidates best first, breaking ties by position (row-major).
+fn sort_candidates(candidates: &mut [SettlementCandidate]) {
+    candidates.sort_by(|a, b| {
+        b.suitability
+            .total_cmp(&a.suitability)
+            .then(a.position.y.total_cmp(&b.position.y))
+            .then(a.position.x.total_cmp(&b.position.x))
+    });
+}
 
 /// A candidate site for settlement placement.
 #[derive(Debug, Clone)]
@@ -52,14 +88,23 @@ pub fn find_best_culture(
     temperature: f64,
     continentalness: f64,
 ) -> (CultureType, f64) {
+    best_culture_index(cultures, biome, temperature, continentalness)
+        .map_or((CultureType::TwilightDweller, 0.0), |(idx, score)| (cultures[idx].culture_type, score))
+}
+
+/// Index of the culture that suits a location best, and its score. Authored
+/// cultures can share an archetype, so they are told apart by position.
+fn best_culture_index(
+    cultures: &[Culture],
+    biome: TileType,
+    temperature: f64,
+    continentalness: f64,
+) -> Option<(usize, f64)> {
     cultures
         .iter()
-        .map(|c| {
-            let score = calculate_culture_suitability(c, biome, temperature, continentalness);
-            (c.culture_type, score)
-        })
-        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-        .unwrap_or((CultureType::TwilightDweller, 0.0))
+        .map(|c| calculate_culture_suitability(c, biome, temperature, continentalness))
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
 }
 
 /// Calculate local resource score by examining surrounding tiles.
@@ -100,7 +145,7 @@ fn local_resource_score(biome_map: &BiomeMap, x: usize, y: usize, radius: usize)
     diversity_score * 0.4 + good_ratio * 0.6
 }
 
-/// Calculate water access score (proximity to coast).
+/// Calculate water access score (proximity to coast or lake shore).
 fn water_access_score(biome_map: &BiomeMap, x: usize, y: usize, search_radius: usize) -> f64 {
     let x_start = x.saturating_sub(search_radius);
     let x_end = (x + search_radius).min(biome_map.width - 1);
@@ -110,7 +155,7 @@ fn water_access_score(biome_map: &BiomeMap, x: usize, y: usize, search_radius: u
     for ny in y_start..=y_end {
         for nx in x_start..=x_end {
             if let Some(biome) = biome_map.get_biome(nx, ny) {
-                if matches!(biome, TileType::Sea | TileType::Beach) {
+                if biome.is_ocean() || matches!(biome, TileType::Beach | TileType::Lake) {
                     // Found water - closer is better
                     let dx = (nx as f64 - x as f64).abs();
                     let dy = (ny as f64 - y as f64).abs();
@@ -124,6 +169,27 @@ fn water_access_score(biome_map: &BiomeMap, x: usize, y: usize, search_radius: u
     0.0 // No water found
 }
 
+/// Calculate river access score (proximity to a river bank).
+fn river_access_score(biome_map: &BiomeMap, x: usize, y: usize, search_radius: usize) -> f64 {
+    let x_start = x.saturating_sub(search_radius);
+    let x_end = (x + search_radius).min(biome_map.width - 1);
+    let y_start = y.saturating_sub(search_radius);
+    let y_end = (y + search_radius).min(biome_map.height - 1);
+
+    let mut best: f64 = 0.0;
+    for ny in y_start..=y_end {
+        for nx in x_start..=x_end {
+            if biome_map.river_flow(nx, ny) > 0.0 {
+                let dx = nx as f64 - x as f64;
+                let dy = ny as f64 - y as f64;
+                let dist = (dx * dx + dy * dy).sqrt();
+                best = best.max(1.0 - dist / search_radius as f64);
+            }
+        }
+    }
+    best
+}
+
 /// Calculate defensibility score (nearby mountains/plateaus).
 fn defensibility_score(biome_map: &BiomeMap, x: usize, y: usize, radius: usize) -> f64 {
     let mut defensive_tiles = 0;
@@ -159,9 +225,46 @@ fn defensibility_score(biome_map: &BiomeMap, x: usize, y: usize, radius: usize)
     }
 }
 
+/// How strongly a landmark draws settlers, from -1 (keeps them away) to 1.
+///
+/// Temples and monuments have always gathered towns around them; ruins hint
+/// at whatever emptied the place, volcanoes bury whoever settles too close,
+/// and caves are neither here nor there.
+fn landmark_pull(kind: LandmarkKind) -> f64 {
+    match kind {
+        LandmarkKind::Temple => 1.0,
+        LandmarkKind::Monument => 0.8,
+        LandmarkKind::Port => 0.6,
+        LandmarkKind::Bridge => 0.5,
+        LandmarkKind::Mine => 0.4,
+        LandmarkKind::Tower => 0.2,
+        LandmarkKind::Ruin => -0.3,
+        LandmarkKind::Volcano => -0.5,
+        LandmarkKind::Cave | LandmarkKind::Other => 0.0,
+    }
+}
+
+/// Calculate landmark score: the pull of every landmark in range, fading
+/// linearly to nothing at [`LANDMARK_INFLUENCE_RADIUS`], clamped to [-1, 1].
+fn landmark_score(landmarks: &[Landmark], x: usize, y: usize) -> f64 {
+    let score: f64 = landmarks
+        .iter()
+        .map(|landmark| {
+            let dist = (landmark.position.x - x as f64).hypot(landmark.position.y - y as f64);
+            let falloff = (1.0 - dist / LANDMARK_INFLUENCE_RADIUS).max(0.0);
+            landmark_pull(landmark.kind) * falloff
+        })
+        .sum();
+    score.clamp(-1.0, 1.0)
+}
+
 /// Calculate full site suitability combining all factors.
+///
+/// Authored `landmarks` nudge the result up or down by at most 0.1 on top of
+/// the terrain and culture factors.
 pub fn calculate_site_suitability(
     biome_map: &BiomeMap,
+    landmarks: &[Landmark],
     x: usize,
     y: usize,
     culture: &Culture,
@@ -171,7 +274,7 @@ pub fn calculate_site_suitability(
     };
 
     // Can't place settlements in water
-    if matches!(biome, TileType::Sea | TileType::White) {
+    if biome.is_ocean() || matches!(biome, TileType::White | TileType::Lake) {
         return 0.0;
     }
 
@@ -184,8 +287,9 @@ pub fn calculate_site_suitability(
     // Local resources (20%)
     let resource_score = local_resource_score(biome_map, x, y, 5);
 
-    // Water access (10%)
-    let water_score = water_access_score(biome_map, x, y, 15);
+    // Water access (10%) - a coast or a river bank
+    let water_score =
+        water_access_score(biome_map, x, y, 15).max(river_access_score(biome_map, x, y, 15));
 
     // Defensibility (5%)
     let defense_score = defensibility_score(biome_map, x, y, 8);
@@ -200,11 +304,89 @@ pub fn calculate_site_suitability(
         _ => 0.3,
     };
 
+    // Nearby landmarks (±10%)
+    let landmark_score = landmark_score(landmarks, x, y);
+
     0.40 * culture_score
         + 0.25 * flat_land_score
         + 0.20 * resource_score
         + 0.10 * water_score
         + 0.05 * defense_score
+        + 0.10 * landmark_score
+}
+
+/// Industries a settlement at `(x, y)` lives off, for [`City::industries`].
+///
+/// Mining needs ore or stone nearby, fishing a stretch of coast or lake, farming
+/// fertile or open flat land, hunting game or northern forest. Cultures lean
+/// on what they know: StoneBorn mine any mountainside, TideWalkers fish from
+/// the first sight of sea, and FrostKin farm only proper soil. Trade depends
+/// on the road network and is added later by [`tag_crossroads`].
+pub fn derive_industries(
+    biome_map: &BiomeMap,
+    x: usize,
+    y: usize,
+    culture: CultureType,
+) -> Vec<String> {
+    let (mut ore, mut mountains, mut water, mut fish) = (0, 0, 0, 0);
+    let (mut fertile, mut flat, mut game, mut total) = (0, 0, 0, 0);
+
+    let x_end = (x + INDUSTRY_RADIUS).min(biome_map.width - 1);
+    let y_end = (y + INDUSTRY_RADIUS).min(biome_map.height - 1);
+    for ny in y.saturating_sub(INDUSTRY_RADIUS)..=y_end {
+        for nx in x.saturating_sub(INDUSTRY_RADIUS)..=x_end {
+            let Some(biome) = biome_map.get_biome(nx, ny) else {
+                continue;
+            };
+            total += 1;
+            match biome {
+                b if b.is_ocean() || b == TileType::Lake => water += 1,
+                TileType::Mountain | TileType::Plateau => mountains += 1,
+                TileType::Plains | TileType::Savanna | TileType::Steppe => flat += 1,
+                TileType::Taiga | TileType::Tundra => game += 1,
+                _ => {}
+            }
+            for &(resource, _) in biome_map.resources.get_all(nx, ny) {
+                match resource {
+                    ResourceType::Fish => fish += 1,
+                    ResourceType::FertileSoil => fertile += 1,
+                    ResourceType::WildGame => game += 1,
+                    ResourceType::Timber => {}
+                    _ => ore += 1,
+                }
+            }
+        }
+    }
+
+    let mining = ore > 0 || (culture == CultureType::StoneBorn && mountains > 0);
+    let fishing = fish > 0 || water >= 3 || (culture == CultureType::TideWalker && water > 0);
+    let farming = fertile > 0 || (culture != CultureType::FrostKin && flat * 2 >= total);
+    let hunting = game > 0;
+
+    [
+        (mining, "Mining"),
+        (fishing, "Fishing"),
+        (farming, "Farming"),
+        (hunting, "Hunting"),
+    ]
+    .into_iter()
+    .filter(|&(found, _)| found)
+    .map(|(_, industry)| industry.to_string())
+    .collect()
+}
+
+/// Add "Trade" to every settlement where [`CROSSROADS_ROADS`] or more roads
+/// meet.
+pub fn tag_crossroads(cities: &mut [City], roads: &[Road]) {
+    for city in cities {
+        let meeting = roads
+            .iter()
+            .filter(|r| r.connects.0 == city.id || r.connects.1 == city.id)
+            .count();
+        if meeting >= CROSSROADS_ROADS && !city.industries.iter().any(|i| i == "Trade") {
+            city.industries.push("Trade".to_string());
+        }
+    }
 }
 
 /// Check if a position respects minimum spacing from existing settlements.
@@ -220,87 +402,143 @@ fn respects_spacing(settlements: &[City], pos: Point2D, min_distance: f64) -> bo
     true
 }
 
-/// Find local maxima in suitability across the map.
-fn find_local_maxima(
-    biome_map: &BiomeMap,
-    cultures: &[Culture],
+/// Site suitability sampled every `step` pixels.
+///
+/// Each sample holds the best culture for the site and the suitability of the
+/// site for that culture; `None` marks water, which scores zero for everyone.
+struct SuitabilityGrid {
+    cols: usize,
+    rows: usize,
     step: usize,
-) -> Vec<SettlementCandidate> {
-    let mut candidates = Vec::new();
+    cells: Vec<Option<(usize, f64)>>,
+}
 
-    // Sample at regular intervals
-    for y in (0..biome_map.height).step_by(step) {
-        for x in (0..biome_map.width).step_by(step) {
-            let Some(biome) = biome_map.get_biome(x, y) else {
-                continue;
-            };
+impl SuitabilityGrid {
+    /// Evaluate every sample once, in parallel.
+    fn new(
+        biome_map: &BiomeMap,
+        cultures: &[Culture],
+        landmarks: &[Landmark],
+        step: usize,
+    ) -> Self {
+        let cols = biome_map.width.div_ceil(step);
+        let rows = biome_map.height.div_ceil(step);
+
+        let cells = (0..cols * rows)
+            .into_par_iter()
+            .map(|i| {
+                let (x, y) = ((i % cols) * step, (i / cols) * step);
+                let biome = biome_map.get_biome(x, y)?;
+                if biome.is_ocean() || matches!(biome, TileType::White | TileType::Lake) {
+                    return None;
+                }
 
-            // Skip water
-            if matches!(biome, TileType::Sea | TileType::White) {
-                continue;
-            }
+                let temperature = biome_map.get_temperature(x, y).unwrap_or(20.0);
+                let continentalness = biome_map.get_continentalness(x, y).unwrap_or(0.1);
+                let (culture_idx, _) = best_culture_index(cultures, biome, temperature, continentalness)?;
+
+                let culture = &cultures[culture_idx];
+                let suitability = calculate_site_suitability(biome_map, landmarks, x, y, culture);
+                Some((culture_idx, suitability))
+            })
+            .collect();
+
+        Self { cols, rows, step, cells }
+    }
+
+    /// Suitability of sample `(col, row)` for the given culture.
+    ///
+    /// Reuses the stored value when the culture matches and only recomputes
+    /// along culture borders.
+    fn suitability_for(
+        &self,
+        biome_map: &BiomeMap,
+        cultures: &[Culture],
+        landmarks: &[Landmark],
+        col: usize,
+        row: usize,
+        culture_idx: usize,
+    ) -> f64 {
+        match self.cells[row * self.cols + col] {
+            None => 0.0,
+            Some((idx, suitability)) if idx == culture_idx => suitability,
+            Some(_) => calculate_site_suitability(
+                biome_map,
+                landmarks,
+                col * self.step,
+                row * self.step,
+                &cultures[culture_idx],
+            ),
+        }
+    }
+
+    /// Whether no neighbouring sample outranks `(col, row)` for its own culture.
+    fn is_local_maximum(
+        &self,
+        biome_map: &BiomeMap,
+        cultures: &[Culture],
+        landmarks: &[Landmark],
+        col: usize,
+        row: usize,
+    ) -> bool {
+        let Some((culture_idx, suitability)) = self.cells[row * self.cols + col] else {
+            return false;
+        };
 
-            let temperature = biome_map.get_temperature(x, y).unwrap_or(20.0);
-            let continentalness = biome_map.get_continentalness(x, y).unwrap_or(0.1);
-
-            // Find best culture for this location
-            let (best_culture, _) = find_best_culture(cultures, biome, temperature, continentalness);
-            let culture = cultures
-                .iter()
-                .find(|c| c.culture_type == best_culture)
-                .unwrap();
-
-            let suitability = calculate_site_suitability(biome_map, x, y, culture);
-
-            if suitability > SETTLEMENT_THRESHOLD {
-                // Check if this is a local maximum
-                let is_local_max = is_local_maximum(biome_map, x, y, culture, suitability, step);
-
-                if is_local_max {
-                    candidates.push(SettlementCandidate {
-                        position: Point2D::new(x as f64, y as f64),
-                        suitability,
-                        culture_type: best_culture,
-                        biome,
-                        temperature,
-                        continentalness,
-                    });
+        for nrow in row.saturating_sub(1)..=(row + 1).min(self.rows - 1) {
+            for ncol in col.saturating_sub(1)..=(col + 1).min(self.cols - 1) {
+                if (ncol, nrow) == (col, row) {
+                    continue;
+                }
+                let neighbor =
+                    self.suitability_for(biome_map, cultures, landmarks, ncol, nrow, culture_idx);
+                if outranks(neighbor, (ncol, nrow), suitability, (col, row)) {
+                    return false;
                 }
             }
         }
-    }
 
-    candidates
+        true
+    }
 }
 
-/// Check if a position is a local maximum in suitability.
-fn is_local_maximum(
+/// Find local maxima in suitability across the map.
+///
+/// Suitability is evaluated once per sample on a `step`-pixel grid, and a
+/// sample is a maximum if none of its eight grid neighbours outranks it.
+fn find_local_maxima(
     biome_map: &BiomeMap,
-    x: usize,
-    y: usize,
-    culture: &Culture,
-    current_suitability: f64,
-    radius: usize,
-) -> bool {
-    let x_start = x.saturating_sub(radius);
-    let x_end = (x + radius).min(biome_map.width - 1);
-    let y_start = y.saturating_sub(radius);
-    let y_end = (y + radius).min(biome_map.height - 1);
+    cultures: &[Culture],
+    landmarks: &[Landmark],
+    step: usize,
+) -> Vec<SettlementCandidate> {
+    let grid = SuitabilityGrid::new(biome_map, cultures, landmarks, step);
+    let mut candidates = Vec::new();
 
-    for ny in y_start..=y_end {
-        for nx in x_start..=x_end {
-            if nx == x && ny == y {
+    for row in 0..grid.rows {
+        for col in 0..grid.cols {
+            let Some((culture_idx, suitability)) = grid.cells[row * grid.cols + col] else {
+                continue;
+            };
+            if suitability <= SETTLEMENT_THRESHOLD
+                || !grid.is_local_maximum(biome_map, cultures, landmarks, col, row)
+            {
                 continue;
             }
 
-            let neighbor_suitability = calculate_site_suitability(biome_map, nx, ny, culture);
-            if neighbor_suitability > current_suitability {
-                return false;
-            }
+            let (x, y) = (col * step, row * step);
+            candidates.push(SettlementCandidate {
+                position: Point2D::new(x as f64, y as f64),
+                suitability,
+                culture_type: cultures[culture_idx].culture_type,
+                biome: biome_map.get_biome(x, y).unwrap(),
+                temperature: biome_map.get_temperature(x, y).unwrap_or(20.0),
+                continentalness: biome_map.get_continentalness(x, y).unwrap_or(0.1),
+            });
         }
     }
 
-    true
+    candidates
 }
 
 /// Determine city tier based on suitability and strategic value.
@@ -328,56 +566,159 @@ fn determine_tier(
     }
 }
 
-/// Generate a procedural name based on culture and biome.
-fn generate_name(culture: CultureType, biome: TileType, tier: CityTier, rng: &mut impl Rng) -> String {
-    let prefixes = match culture {
-        CultureType::TwilightDweller => &["New ", "Old ", "Great ", ""][..],
-        CultureType::FrostKin => &["North", "Ice", "Frost", "Winter"][..],
-        CultureType::SunForged => &["Sun", "Gold", "Bright", "Fire"][..],
-        CultureType::TideWalker => &["Port ", "Sea", "Harbor ", ""][..],
-        CultureType::StoneBorn => &["High", "Stone", "Iron", "Mount "][..],
-    };
+/// Suffixes reserved for capitals, so a capital never reads like a village.
+pub const CAPITAL_SUFFIXES: [&str; 4] = [" City", " Capital", " Prime", " Throne"];
+
+/// Naming flavour drawn from a faction's identity.
+#[derive(Debug, Clone, Copy)]
+pub struct FactionNameTheme {
+    /// Prefixes for the faction's capital.
+    pub capital_prefixes: &'static [&'static str],
+    /// Suffixes for the faction's capital.
+    pub capital_suffixes: &'static [&'static str],
+    /// Suffixes for the faction's towns. Villages keep their plain names.
+    pub town_suffixes: &'static [&'static str],
+}
 
-    let roots = match biome {
-        TileType::Plains => &["field", "dale", "meadow", "green"][..],
-        TileType::Forest => &["wood", "grove", "glen", "shade"][..],
-        TileType::Mountain => &["peak", "crag", "tor", "hold"][..],
-        TileType::Beach => &["haven", "cove", "bay", "shore"][..],
-        TileType::Desert => &["oasis", "dune", "sand", "mirage"][..],
-        TileType::Snow => &["frost", "ice", "white", "cold"][..],
-        _ => &["town", "stead", "burg", "haven"][..],
-    };
+impl FactionNameTheme {
+    /// Theme for a faction, keyed on the polity word in its name (League,
+    /// Holds, ...) and falling back to its culture.
+    pub fn for_faction(faction: &Faction) -> Self {
+        let polity = faction.name.split_whitespace().last().unwrap_or("");
+        match polity {
+            "League" => Self::LEAGUE,
+            "Hold" | "Holds" => Self::HOLDS,
+            "Tribe" | "Tribes" => Self::TRIBES,
+            "Kingdom" | "Kingdoms" => Self::KINGDOMS,
+            "Confederacy" => Self::CONFEDERACY,
+            _ => match faction.culture {
+                CultureType::TideWalker => Self::LEAGUE,
+                CultureType::FrostKin => Self::HOLDS,
+                CultureType::SunForged => Self::TRIBES,
+                CultureType::StoneBorn => Self::KINGDOMS,
+                CultureType::TwilightDweller => Self::CONFEDERACY,
+            },
+        }
+    }
 
-    let suffixes = match tier {
-        CityTier::Capital => &[" City", " Capital", "", " Prime"][..],
-        CityTier::Town => &["ton", "ville", "burg", ""][..],
-        CityTier::Village => &["", " Village", " Hamlet", ""][..],
+    const LEAGUE: Self = Self {
+        capital_prefixes: &["Free ", "Charter ", "Grand "],
+        capital_suffixes: &[" Exchange", " Guildhall", " Harbor Seat"],
+        town_suffixes: &[" Wharf", " Market"],
+    };
+    const HOLDS: Self = Self {
+        capital_prefixes: &["High ", "Elder ", "Jarl's "],
+        capital_suffixes: &[" Hold", " Keep", " Hall"],
+        town_suffixes: &[" Stead", " Watch"],
+    };
+    const TRIBES: Self = Self {
+        capital_prefixes: &["Great ", "Chief's ", "Sun "],
+        capital_suffixes: &[" Moot", " Sunseat", " Gathering"],
+        town_suffixes: &[" Camp", " Well"],
+    };
+    const KINGDOMS: Self = Self {
+        capital_prefixes: &["King's ", "Royal ", "Crown "],
+        capital_suffixes: &[" Citadel", " Throne", " Royal Seat"],
+        town_suffixes: &[" Gate", " Delve"],
     };
+    const CONFEDERACY: Self = Self {
+        capital_prefixes: &["Concord ", "Grand ", "Twin "],
+        capital_suffixes: &[" Assembly", " Council Seat", " Concord"],
+        town_suffixes: &[" Crossing", " Ward"],
+    };
+}
 
-    let prefix = prefixes[rng.gen_range(0..prefixes.len())];
-    let root = roots[rng.gen_range(0..roots.len())];
-    let suffix = suffixes[rng.gen_range(0..suffixes.len())];
+/// Restyle settlement names after factions are known.
+///
+/// Each faction's capital gets a themed prefix and suffix in place of its
+/// generic capital suffix; its towns get a themed suffix. Villages are left
+/// alone, so a faction's seat always reads grander than its hamlets.
+pub fn apply_faction_names(cities: &mut [City], factions: &[Faction], seed: u32) {
+    let mut rng = rng_for(seed, "faction_names");
+
+    for faction in factions {
+        let theme = FactionNameTheme::for_faction(faction);
+        for city in cities
+            .iter_mut()
+            .filter(|c| faction.settlement_ids.contains(&c.id))
+        {
+            let is_capital = faction.capital_id == Some(city.id);
+            if is_capital {
+                let base = CAPITAL_SUFFIXES
+                    .iter()
+                    .find_map(|s| city.name.strip_suffix(s))
+                    .unwrap_or(&city.name);
+                let prefix = theme.capital_prefixes[rng.gen_range(0..theme.capital_prefixes.len())];
+                let suffix = theme.capital_suffixes[rng.gen_range(0..theme.capital_suffixes.len())];
+                city.name = format!("{}{}{}", prefix, base.trim(), suffix);
+            } else if city.tier == CityTier::Town {
+                let suffix = theme.town_suffixes[rng.gen_range(0..theme.town_suffixes.len())];
+                city.name.push_str(suffix);
+            }
+        }
+    }
+}
 
-    format!("{}{}{}", prefix, root, suffix)
+/// Generate a settlement name in its culture's style. Capitals take one of
+/// the [`CAPITAL_SUFFIXES`].
+fn generate_name(names: &mut NameGenerator, culture: CultureType, tier: CityTier, rng: &mut impl Rng) -> String {
+    let name = names.name(culture, NameKind::Settlement, rng);
+    match tier {
+        CityTier::Capital => format!("{}{}", name, CAPITAL_SUFFIXES[rng.gen_range(0..CAPITAL_SUFFIXES.len())]),
+        CityTier::Town | CityTier::Village => name,
+    }
 }
 
 /// Place settlements across the map.
+///
+/// Each culture's first site becomes its capital. Later sites are kept with
+/// probability equal to the culture's `settlement_tendency`, so nomadic
+/// cultures end up sparser than settled ones.
 pub fn place_settlements(
     biome_map: &BiomeMap,
     cultures: &[Culture],
     seed: u32,
     max_settlements: usize,
 ) -> PlacementResult {
-    let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
+    place_settlements_with_symmetry(
+        biome_map,
+        cultures,
+        &[],
+        &mut NameGenerator::new(&NameData::default()),
+        seed,
+        max_settlements,
+        Symmetry::None,
+    )
+}
+
+/// Place settlements so the layout matches `symmetry`.
+///
+/// Sites are chosen in the source half only and each one is paired with a
+/// twin of the same tier at its mirror position. Sites too close to their own
+/// mirror image (near the axis) are skipped so every settlement has a twin.
+/// Existing `landmarks` sway where sites land; see
+/// [`calculate_site_suitability`]. Names come from `names`, so they don't
+/// repeat any it has given out.
+pub fn place_settlements_with_symmetry(
+    biome_map: &BiomeMap,
+    cultures: &[Culture],
+    landmarks: &[Landmark],
+    names: &mut NameGenerator,
+    seed: u32,
+    max_settlements: usize,
+    symmetry: Symmetry,
+) -> PlacementResult {
+    let (width, height) = (biome_map.width, biome_map.height);
+    let mut rng = rng_for(seed, "settlement_names");
     let mut settlements = Vec::new();
     let mut next_id = 1u32;
 
     // Find candidate locations (sample every 8 pixels for performance)
-    let mut candidates = find_local_maxima(biome_map, cultures, 8);
+    let mut candidates = find_local_maxima(biome_map, cultures, landmarks, 8);
     let candidates_evaluated = candidates.len();
 
     // Sort by suitability (best first)
-    candidates.sort_by(|a, b| b.suitability.partial_cmp(&a.suitability).unwrap());
+    sort_candidates(&mut candidates);
 
     // Track which cultures have capitals
     let mut has_capital: std::collections::HashSet<CultureType> = std::collections::HashSet::new();
@@ -399,17 +740,51 @@ pub fn place_settlements(
             continue;
         }
 
+        let twin = if symmetry == Symmetry::None {
+            None
+        } else {
+            let pos = candidate.position;
+            if !symmetry.is_source(pos.x as usize, pos.y as usize, width, height) {
+                continue;
+            }
+            let (tx, ty) = symmetry.mirror_point(pos.x, pos.y, width, height);
+            let twin = Point2D::new(tx, ty);
+            if settlements.len() + 2 > max_settlements
+                || !respects_spacing(&settlements, twin, min_dist)
+                || (twin.x - pos.x).hypot(twin.y - pos.y) < min_dist
+            {
+                continue;
+            }
+            Some(twin)
+        };
+
         // Determine if this should be a capital
         let is_capital = !has_capital.contains(&candidate.culture_type);
+
+        // Less settled cultures pass over more of their sites
+        if !is_capital && rng.gen::<f64>() >= culture.traits.settlement_tendency {
+            continue;
+        }
         if is_capital {
             has_capital.insert(candidate.culture_type);
         }
 
         let tier = determine_tier(candidate.suitability, is_capital, &candidate);
-        let name = generate_name(candidate.culture_type, candidate.biome, tier, &mut rng);
-
-        settlements.push(City::new(next_id, name, candidate.position, tier));
-        next_id += 1;
+        let name = generate_name(names, candidate.culture_type, tier, &mut rng);
+
+        let mut place = |position: Point2D, name: String| {
+            let mut city = City::new(next_id, name, position, tier);
+            let (x, y) = (position.x as usize, position.y as usize);
+            city.industries = derive_industries(biome_map, x, y, candidate.culture_type);
+            settlements.push(city);
+            next_id += 1;
+        };
+        place(candidate.position, name);
+
+        if let Some(twin) = twin {
+            let name = generate_name(names, candidate.culture_type, tier, &mut rng);
+            place(twin, name);
+        }
     }
 
     PlacementResult {
@@ -432,6 +807,99 @@ mod tests {
         assert!(score < 0.3);
     }
 
+    #[test]
+    fn cultures_sharing_an_archetype_are_told_apart() {
+        let mut plains_folk = Culture::twilight_dweller();
+        plains_folk.name = "Plains Folk".into();
+        plains_folk.biome_preferences.plains = 1.0;
+        let mut hill_folk = plains_folk.clone();
+        hill_folk.name = "Hill Folk".into();
+        hill_folk.biome_preferences.plains = -1.0;
+        let cultures = [hill_folk, plains_folk];
+
+        let (idx, _) = best_culture_index(&cultures, TileType::Plains, 20.0, 0.1).unwrap();
+        assert_eq!(cultures[idx].name, "Plains Folk");
+        assert!(best_culture_index(&[], TileType::Plains, 20.0, 0.1).is_none());
+    }
+
+    #[test]
+    fn river_banks_raise_suitability() {
+        let mut biome_map = BiomeMap::generate(42, 64, 64);
+        biome_map.biomes.fill(TileType::Plains);
+        biome_map.continentalness.fill(0.1);
+        biome_map.temperature.fill(20.0);
+        biome_map.rivers.fill(0.0);
+        biome_map.resources.clear();
+        let culture = Culture::twilight_dweller();
+
+        let dry = calculate_site_suitability(&biome_map, &[], 20, 32, &culture);
+        for y in 0..64 {
+            biome_map.rivers[y * 64 + 21] = 0.5;
+        }
+        let bank = calculate_site_suitability(&biome_map, &[], 20, 32, &culture);
+
+        assert!(bank > dry, "River bank {} not above dry {}", bank, dry);
+    }
+
+    #[test]
+    fn temples_attract_settlement() {
+        let mut biome_map = BiomeMap::generate(42, 64, 64);
+        biome_map.biomes.fill(TileType::Plains);
+        biome_map.continentalness.fill(0.1);
+        biome_map.temperature.fill(20.0);
+        biome_map.rivers.fill(0.0);
+        biome_map.resources.clear();
+        let culture = Culture::twilight_dweller();
+        let landmark = |kind| Landmark::new(1, "Shrine".into(), Point2D::new(36.0, 32.0), kind);
+
+        let bare = calculate_site_suitability(&biome_map, &[], 32, 32, &culture);
+        let temple = [landmark(LandmarkKind::Temple)];
+        let near_temple = calculate_site_suitability(&biome_map, &temple, 32, 32, &culture);
+        let ruin = [landmark(LandmarkKind::Ruin)];
+        let near_ruin = calculate_site_suitability(&biome_map, &ruin, 32, 32, &culture);
+
+        assert!(near_temple > bare, "Temple site {} not above bare {}", near_temple, bare);
+        assert!(near_ruin <= bare);
+        // Out of range, the temple makes no difference
+        let far = calculate_site_suitability(&biome_map, &temple, 32, 0, &culture);
+        assert_eq!(far, calculate_site_suitability(&biome_map, &[], 32, 0, &culture));
+    }
+
+    #[test]
+    fn industries_follow_terrain_and_culture() {
+        let mut biome_map = BiomeMap::generate(42, 64, 64);
+        biome_map.biomes.fill(TileType::Forest);
+        biome_map.resources.clear();
+        // Sea to the east, a mountain range in the north-west
+        for y in 0..64 {
+            for x in 0..64 {
+                if x >= 48 {
+                    biome_map.biomes[y * 64 + x] = TileType::Sea;
+                } else if x < 16 && y < 16 {
+                    biome_map.biomes[y * 64 + x] = TileType::Mountain;
+                }
+            }
+        }
+
+        let coastal = derive_industries(&biome_map, 46, 40, CultureType::TideWalker);
+        assert!(coastal.contains(&"Fishing".to_string()), "{:?}", coastal);
+        let mountain = derive_industries(&biome_map, 12, 12, CultureType::StoneBorn);
+        assert!(mountain.contains(&"Mining".to_string()), "{:?}", mountain);
+        // Without ore, only the StoneBorn see a mine in bare rock
+        let lowland = derive_industries(&biome_map, 12, 12, CultureType::TwilightDweller);
+        assert!(!lowland.contains(&"Mining".to_string()));
+
+        let mut cities: Vec<City> = (1..=4)
+            .map(|id| City::new(id, "Hub".into(), Point2D::new(0.0, 0.0), CityTier::Town))
+            .collect();
+        let roads: Vec<Road> = (2..=4)
+            .map(|to| Road::new(to, (1, to), crate::roads::RoadType::Provincial))
+            .collect();
+        tag_crossroads(&mut cities, &roads);
+        assert_eq!(cities[0].industries, vec!["Trade".to_string()]);
+        assert!(cities[1].industries.is_empty());
+    }
+
     #[test]
     fn spacing_check_works() {
         let cities = vec![
@@ -458,4 +926,232 @@ mod tests {
             assert_eq!(a.position.y, b.position.y);
         }
     }
+
+    #[test]
+    fn settlement_tendency_thins_placement() {
+        let biome_map = BiomeMap::generate(42, 512, 256);
+        let with_tendency = |tendency: f64| {
+            let mut culture = Culture::all_defaults().remove(0);
+            culture.traits.settlement_tendency = tendency;
+            place_settlements(&biome_map, &[culture], 42, 200).settlements.len()
+        };
+
+        let nomadic = with_tendency(0.3);
+        let settled = with_tendency(0.9);
+        assert!(nomadic < settled, "nomadic {} vs settled {}", nomadic, settled);
+    }
+
+    #[test]
+    fn symmetric_placement_pairs_twins() {
+        let config = rb_noise::GenerationConfig {
+            symmetry: Symmetry::Horizontal,
+            ..Default::default()
+        };
+        let biome_map = BiomeMap::generate_with_config(42, 256, 128, &config);
+        let result = place_settlements_with_symmetry(
+            &biome_map,
+            &Culture::all_defaults(),
+            &[],
+            &mut NameGenerator::new(&NameData::default()),
+            42,
+            20,
+            Symmetry::Horizontal,
+        );
+
+        let cities = &result.settlements;
+        assert!(!cities.is_empty());
+        assert_eq!(cities.len() % 2, 0);
+        for pair in cities.chunks(2) {
+            let (a, b) = (pair[0].position, pair[1].position);
+            assert_eq!(Symmetry::Horizontal.mirror_point(a.x, a.y, 256, 128), (b.x, b.y));
+            assert_eq!(pair[0].tier, pair[1].tier);
+        }
+    }
+
+    /// Reference implementation: compares each sample against every pixel
+    /// within `step`, evaluating suitability from scratch each time.
+    fn find_local_maxima_exhaustive(
+        biome_map: &BiomeMap,
+        cultures: &[Culture],
+        step: usize,
+    ) -> Vec<SettlementCandidate> {
+        let mut candidates = Vec::new();
+
+        // Sample at regular intervals
+        for y in (0..biome_map.height).step_by(step) {
+            for x in (0..biome_map.width).step_by(step) {
+                let Some(biome) = biome_map.get_biome(x, y) else {
+                    continue;
+                };
+
+                // Skip water
+                if biome.is_ocean() || biome == TileType::White {
+                    continue;
+                }
+
+                let temperature = biome_map.get_temperature(x, y).unwrap_or(20.0);
+                let continentalness = biome_map.get_continentalness(x, y).unwrap_or(0.1);
+
+                // Find best culture for this location
+                let (best_culture, _) = find_best_culture(cultures, biome, temperature, continentalness);
+                let culture = cultures
+                    .iter()
+                    .find(|c| c.culture_type == best_culture)
+                    .unwrap();
+
+                let suitability = calculate_site_suitability(biome_map, &[], x, y, culture);
+
+                if suitability > SETTLEMENT_THRESHOLD {
+                    // Check if this is a local maximum
+                    let is_local_max = is_local_maximum(biome_map, x, y, culture, suitability, step);
+
+                    if is_local_max {
+                        candidates.push(SettlementCandidate {
+                            position: Point2D::new(x as f64, y as f64),
+                            suitability,
+                            culture_type: best_culture,
+                            biome,
+                            temperature,
+                            continentalness,
+                        });
+                    }
+                }
+            }
+        }
+
+        candidates
+    }
+
+    /// Check if a position is a local maximum in suitability.
+    fn is_local_maximum(
+        biome_map: &BiomeMap,
+        x: usize,
+        y: usize,
+        culture: &Culture,
+        current_suitability: f64,
+        radius: usize,
+    ) -> bool {
+        let x_start = x.saturating_sub(radius);
+        let x_end = (x + radius).min(biome_map.width - 1);
+        let y_start = y.saturating_sub(radius);
+        let y_end = (y + radius).min(biome_map.height - 1);
+
+        for ny in y_start..=y_end {
+            for nx in x_start..=x_end {
+                if nx == x && ny == y {
+                    continue;
+                }
+
+                let neighbor_suitability = calculate_site_suitability(biome_map, &[], nx, ny, culture);
+                if outranks(neighbor_suitability, (nx, ny), current_suitability, (x, y)) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    #[test]
+    fn plateau_yields_single_maximum() {
+        let biome_map = BiomeMap::generate(42, 16, 16);
+        let cultures = Culture::all_defaults();
+
+        // 3×3 plateau centred on (3, 2) with float noise below the tie epsilon,
+        // falling away on all sides
+        let (cols, rows) = (6usize, 6);
+        let cells = (0..cols * rows)
+            .map(|i| {
+                let (col, row) = (i % cols, i / cols);
+                let ring = col.abs_diff(3).max(row.abs_diff(2));
+                let suitability = if ring <= 1 {
+                    0.8 + (i % 3) as f64 * 1e-12
+                } else {
+                    0.5 - 0.01 * ring as f64
+                };
+                Some((0, suitability))
+            })
+            .collect();
+        let grid = SuitabilityGrid { cols, rows, step: 1, cells };
+
+        let maxima: Vec<_> = (0..cols * rows)
+            .map(|i| (i % cols, i / cols))
+            .filter(|&(col, row)| grid.is_local_maximum(&biome_map, &cultures, &[], col, row))
+            .collect();
+        assert_eq!(maxima, vec![(2, 1)]);
+    }
+
+    #[test]
+    fn equal_candidates_sort_by_position() {
+        let candidate = |x: f64, y: f64| SettlementCandidate {
+            position: Point2D::new(x, y),
+            suitability: 0.7,
+            culture_type: CultureType::TwilightDweller,
+            biome: TileType::Plains,
+            temperature: 20.0,
+            continentalness: 0.1,
+        };
+        let mut candidates = vec![candidate(8.0, 8.0), candidate(16.0, 0.0), candidate(0.0, 8.0)];
+        sort_candidates(&mut candidates);
+
+        let order: Vec<_> = candidates.iter().map(|c| (c.position.x, c.position.y)).collect();
+        assert_eq!(order, vec![(16.0, 0.0), (0.0, 8.0), (8.0, 8.0)]);
+    }
+
+    #[test]
+    fn grid_maxima_match_exhaustive_search() {
+        // At step 1 the exhaustive window is the 3×3 neighbourhood, same as the grid
+        let biome_map = BiomeMap::generate(42, 64, 32);
+        let cultures = Culture::all_defaults();
+
+        let key = |c: &SettlementCandidate| {
+            (c.position.x as usize, c.position.y as usize, c.culture_type)
+        };
+        let grid: Vec<_> = find_local_maxima(&biome_map, &cultures, &[], 1)
+            .iter()
+            .map(key)
+            .collect();
+        let exhaustive: Vec<_> = find_local_maxima_exhaustive(&biome_map, &cultures, 1)
+            .iter()
+            .map(key)
+            .collect();
+
+        assert!(!grid.is_empty());
+        assert_eq!(grid, exhaustive);
+    }
+
+    #[test]
+    fn capital_names_are_grander_than_villages() {
+        let mut rng = rng_for(7, "test");
+        let mut names = NameGenerator::new(&NameData::default());
+        let culture = CultureType::TideWalker;
+        for _ in 0..32 {
+            let capital = generate_name(&mut names, culture, CityTier::Capital, &mut rng);
+            let village = generate_name(&mut names, culture, CityTier::Village, &mut rng);
+            assert!(CAPITAL_SUFFIXES.iter().any(|s| capital.ends_with(s)), "{}", capital);
+            assert!(!CAPITAL_SUFFIXES.iter().any(|s| village.ends_with(s)), "{}", village);
+        }
+    }
+
+    #[test]
+    fn faction_capital_takes_faction_theme() {
+        let pos = Point2D::new(0.0, 0.0);
+        let mut cities = vec![
+            City::new(1, "Seahaven City".into(), pos, CityTier::Capital),
+            City::new(2, "Seacove".into(), pos, CityTier::Village),
+        ];
+        let mut league = Faction::new(1, "Coastal League".into(), CultureType::TideWalker);
+        league.add_settlement(1);
+        league.add_settlement(2);
+        league.set_capital(1);
+
+        apply_faction_names(&mut cities, &[league], 42);
+
+        let capital = &cities[0].name;
+        let theme = FactionNameTheme::LEAGUE;
+        assert!(theme.capital_suffixes.iter().any(|s| capital.ends_with(s)), "{}", capital);
+        assert!(capital.contains("Seahaven"));
+        assert!(!capital.contains("City"));
+        assert_eq!(cities[1].name, "Seacove");
+    }
 }
@@ -3,43 +3,94 @@
 //! Territories are generated via flood-fill expansion from settlements,
 //! with natural boundaries at mountains, coasts, and other terrain features.
 
-use rb_core::TileType;
+use rb_core::{BiomeRegistry, TileType};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap};
 
-/// Influence decay rate for different terrain types.
-/// Lower values mean terrain acts as a stronger boundary.
-pub fn terrain_influence_decay(biome: TileType) -> f64 {
-    match biome {
-        // Complete barriers
-        TileType::Sea | TileType::OceanTrench | TileType::White | TileType::Glacier => 0.0,
-
-        // Strong barriers
-        TileType::Mountain => 0.3,
-        TileType::Volcanic => 0.2, // Very dangerous
-
-        // Moderate barriers
-        TileType::River => 0.5, // Natural boundary but crossable
-        TileType::Plateau => 0.5,
-        TileType::Badlands => 0.5,
-        TileType::Jungle => 0.55,
-        TileType::Marsh => 0.55,
-
-        // Weak barriers
-        TileType::Snow | TileType::Tundra => 0.6,
-        TileType::Taiga => 0.7,
-        TileType::Desert | TileType::Sahara => 0.7,
-        TileType::Forest => 0.8,
-        TileType::Steppe => 0.85,
-        TileType::Savanna => 0.85,
-
-        // Easy expansion
-        TileType::Beach => 0.9,
-        TileType::Plains => 0.95,
+/// Heap entry ordered by influence (strongest first).
+struct Frontier {
+    influence: f64,
+    idx: usize,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.influence
+            .total_cmp(&other.influence)
+            .then_with(|| other.idx.cmp(&self.idx))
+    }
+}
+
+/// Influence of a single faction over every cell.
+///
+/// Influence starts at each seed `(x, y, strength)` and is multiplied by the
+/// registry's influence decay for every cell it enters, keeping the strongest path. Cells
+/// that would fall to `threshold` or below stay at zero, as do impassable
+/// cells and those `blocked` says the faction can't enter. Factions don't
+/// interact, so fields can be computed in parallel and combined with
+/// [`TerritoryMap::from_influence_fields`].
+pub fn influence_field(
+    registry: &BiomeRegistry,
+    biomes: &[TileType],
+    width: usize,
+    height: usize,
+    seeds: &[(usize, usize, f64)],
+    threshold: f64,
+    blocked: impl Fn(usize) -> bool,
+) -> Vec<f64> {
+    let mut field = vec![0.0; width * height];
+    let mut heap = BinaryHeap::new();
+
+    for &(x, y, strength) in seeds {
+        if x < width && y < height {
+            let idx = y * width + x;
+            if strength > field[idx] {
+                field[idx] = strength;
+                heap.push(Frontier { influence: strength, idx });
+            }
+        }
+    }
+
+    while let Some(Frontier { influence, idx }) = heap.pop() {
+        if influence < field[idx] {
+            continue; // Stale entry
+        }
+        let (x, y) = (idx % width, idx / width);
+        let neighbors = [
+            (x > 0).then(|| idx - 1),
+            (x + 1 < width).then(|| idx + 1),
+            (y > 0).then(|| idx - width),
+            (y + 1 < height).then(|| idx + width),
+        ];
+        for nidx in neighbors.into_iter().flatten() {
+            let next = influence * registry.influence_decay(biomes[nidx]);
+            if next > threshold && next > field[nidx] && !blocked(nidx) {
+                field[nidx] = next;
+                heap.push(Frontier { influence: next, idx: nidx });
+            }
+        }
     }
+
+    field
 }
 
 /// Map of faction territory ownership.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TerritoryMap {
     pub width: usize,
     pub height: usize,
@@ -61,6 +112,21 @@ impl TerritoryMap {
         }
     }
 
+    /// Combine per-faction influence fields, giving each cell to the faction
+    /// with the strongest influence there. Ties go to the earlier faction.
+    pub fn from_influence_fields(width: usize, height: usize, fields: &[(u32, Vec<f64>)]) -> Self {
+        let mut map = Self::new(width, height);
+        for (faction_id, field) in fields {
+            for (idx, &influence) in field.iter().enumerate() {
+                if influence > map.influence[idx] {
+                    map.ownership[idx] = *faction_id;
+                    map.influence[idx] = influence;
+                }
+            }
+        }
+        map
+    }
+
     /// Get the index for a position.
     fn idx(&self, x: usize, y: usize) -> usize {
         y * self.width + x
@@ -165,10 +231,140 @@ impl TerritoryMap {
         counts
     }
 
+    /// Binary mask of the cells owned by a faction, row-major.
+    pub fn faction_mask(&self, faction_id: u32) -> Vec<bool> {
+        self.ownership.iter().map(|&id| id == faction_id).collect()
+    }
+
     /// Get total claimed area (non-zero ownership).
     pub fn total_claimed_area(&self) -> usize {
         self.ownership.iter().filter(|&&id| id != 0).count()
     }
+
+    /// Re-derive ownership of `cells` after their biomes were edited.
+    ///
+    /// Each cell takes the strongest influence its neighbours carry across the
+    /// new terrain, or is released if that falls below `threshold`. Settlement
+    /// cells (full influence) keep their owner unless painted into a barrier.
+    /// This is a local fix-up, not a full re-flood: influence does not spread
+    /// past the edited cells.
+    pub fn refresh_cells(
+        &mut self,
+        registry: &BiomeRegistry,
+        biomes: &[TileType],
+        cells: impl IntoIterator<Item = usize>,
+        threshold: f64,
+    ) {
+        for idx in cells {
+            let decay = registry.influence_decay(biomes[idx]);
+            if decay > 0.0 && self.influence[idx] >= 1.0 {
+                continue;
+            }
+
+            let (x, y) = (idx % self.width, idx / self.width);
+            let best = self
+                .neighbors(x, y)
+                .into_iter()
+                .map(|(nx, ny)| {
+                    let n = self.idx(nx, ny);
+                    (self.ownership[n], self.influence[n] * decay)
+                })
+                .filter(|&(owner, _)| owner != 0)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            match best {
+                Some((owner, influence)) if influence >= threshold => {
+                    self.ownership[idx] = owner;
+                    self.influence[idx] = influence;
+                }
+                _ => {
+                    self.ownership[idx] = 0;
+                    self.influence[idx] = 0.0;
+                }
+            }
+        }
+    }
+}
+
+/// Influence a locked cell gives its owner when territories are generated,
+/// as strong as a village: painted land holds, and only reaches a little way
+/// past its painted edge.
+pub const LOCKED_INFLUENCE: f64 = 0.5;
+
+/// Hand-painted ownership that territory generation keeps.
+///
+/// A locked cell always ends up with its owner. It seeds that faction's
+/// flood-fill and stops every other faction's, so a painted border is a hard
+/// edge. Cells locked to faction 0 are kept unclaimed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TerritoryLocks {
+    /// Owner of each locked cell by `(x, y)`.
+    cells: BTreeMap<(u32, u32), u32>,
+}
+
+impl TerritoryLocks {
+    /// Whether no cell is locked.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Number of locked cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Every locked cell `(x, y)` and its owner.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), u32)> + '_ {
+        self.cells.iter().map(|(&(x, y), &owner)| ((x as usize, y as usize), owner))
+    }
+
+    /// Owner `(x, y)` is locked to, if it is locked.
+    pub fn get(&self, x: usize, y: usize) -> Option<u32> {
+        self.cells.get(&(x as u32, y as u32)).copied()
+    }
+
+    /// Lock `(x, y)` to `faction_id`, or unlock it with `None`.
+    pub fn set(&mut self, x: usize, y: usize, faction_id: Option<u32>) {
+        let key = (x as u32, y as u32);
+        match faction_id {
+            Some(id) => self.cells.insert(key, id),
+            None => self.cells.remove(&key),
+        };
+    }
+
+    /// Owner of every cell of a `width` × `height` map, `None` where unlocked.
+    pub fn to_grid(&self, width: usize, height: usize) -> Vec<Option<u32>> {
+        let mut grid = vec![None; width * height];
+        for ((x, y), owner) in self.cells() {
+            if x < width && y < height {
+                grid[y * width + x] = Some(owner);
+            }
+        }
+        grid
+    }
+
+    /// Flood-fill seeds `(x, y, strength)` of the cells locked to `faction_id`.
+    pub fn seeds(&self, faction_id: u32) -> Vec<(usize, usize, f64)> {
+        self.cells()
+            .filter(|&(_, owner)| owner == faction_id)
+            .map(|((x, y), _)| (x, y, LOCKED_INFLUENCE))
+            .collect()
+    }
+
+    /// Give every locked cell of `territory` its owner. Cells already held by
+    /// that owner keep their influence if it is stronger.
+    pub fn apply(&self, territory: &mut TerritoryMap) {
+        for ((x, y), owner) in self.cells() {
+            if owner == 0 {
+                territory.set(x, y, 0, 0.0);
+            } else if territory.get_owner(x, y) != owner {
+                territory.set(x, y, owner, LOCKED_INFLUENCE);
+            } else {
+                let influence = territory.get_influence(x, y).max(LOCKED_INFLUENCE);
+                territory.set(x, y, owner, influence);
+            }
+        }
+    }
 }
 
 /// Types of natural boundaries.
@@ -234,13 +430,49 @@ mod tests {
         assert_eq!(neighbors.len(), 2);
     }
 
+    #[test]
+    fn faction_mask_matches_counts() {
+        let mut map = TerritoryMap::new(8, 4);
+        for x in 0..5 {
+            map.set(x, 1, 1, 1.0);
+        }
+        map.set(6, 2, 2, 0.5);
+        map.set(7, 3, 2, 0.5);
+
+        let counts = map.count_by_faction();
+        for faction_id in [1, 2] {
+            let mask = map.faction_mask(faction_id);
+            assert_eq!(mask.len(), 32);
+            assert_eq!(mask.iter().filter(|&&owned| owned).count(), counts[&faction_id]);
+        }
+    }
+
     #[test]
     fn sea_blocks_expansion() {
-        assert_eq!(terrain_influence_decay(TileType::Sea), 0.0);
+        assert_eq!(BiomeRegistry::default().influence_decay(TileType::Sea), 0.0);
     }
 
     #[test]
     fn plains_allow_expansion() {
-        assert!(terrain_influence_decay(TileType::Plains) > 0.9);
+        assert!(BiomeRegistry::default().influence_decay(TileType::Plains) > 0.9);
+    }
+
+    #[test]
+    fn refresh_releases_cells_painted_to_sea() {
+        let mut map = TerritoryMap::new(5, 1);
+        for x in 0..5 {
+            map.set(x, 0, 1, 1.0 - x as f64 * 0.1);
+        }
+        let mut biomes = vec![TileType::Plains; 5];
+        biomes[2] = TileType::Sea;
+        biomes[3] = TileType::Forest;
+
+        map.refresh_cells(&BiomeRegistry::default(), &biomes, [2, 3], 0.1);
+
+        assert!(!map.is_claimed(2, 0));
+        // Forest cell re-derives from the eastern neighbour: 0.6 * 0.8
+        assert_eq!(map.get_owner(3, 0), 1);
+        assert!((map.get_influence(3, 0) - 0.48).abs() < 1e-9);
     }
+
 }
@@ -0,0 +1,187 @@
+//! Goods moving between settlements over the road network.
+//!
+//! Each good flows from the settlements with a surplus of it to those short
+//! of it by a gravity model: the pull between a supplier and a buyer grows
+//! with the surplus on one side and the shortfall on the other, and falls
+//! away with the length of road between them. Goods travel the shortest way
+//! by road, so settlements with no road between them don't trade.
+
+use crate::definition::WorldDefinition;
+use crate::roads::{RoadGraph, TradeGood};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Road length over which the pull between two settlements falls to a
+/// quarter.
+const FLOW_DISTANCE: f64 = 128.0;
+
+/// Flows of a good smaller than this, in loads a year, are dropped.
+const MIN_FLOW: f32 = 0.1;
+
+/// Goods carried from one settlement to another each year.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeFlow {
+    /// Settlement the goods come from.
+    pub from: u32,
+    /// Settlement they go to.
+    pub to: u32,
+    /// Loads a year of each good, most first.
+    pub goods: Vec<(TradeGood, f32)>,
+    /// Roads travelled, in order from `from`.
+    pub road_ids: Vec<u32>,
+}
+
+impl TradeFlow {
+    /// Loads a year of every good together.
+    pub fn volume(&self) -> f32 {
+        self.goods.iter().map(|(_, amount)| amount).sum()
+    }
+}
+
+/// Flows of every good between the world's settlements, from their economies'
+/// surpluses and shortfalls. Each supplier's surplus is shared among the
+/// buyers it can reach by its pull on each, and no buyer takes more than it
+/// is short, so nothing is shipped that isn't made or wanted.
+pub fn simulate_trade_flows(world_def: &WorldDefinition) -> Vec<TradeFlow> {
+    let graph = RoadGraph::new(&world_def.roads);
+    let mut paths: HashMap<u32, BTreeMap<u32, (Vec<u32>, f64)>> = HashMap::new();
+    let mut shipped: BTreeMap<(u32, u32), Vec<(TradeGood, f32)>> = BTreeMap::new();
+
+    for &good in TradeGood::all() {
+        let surplus = |sign: f32| -> Vec<(u32, f64)> {
+            world_def
+                .cities
+                .iter()
+                .map(|c| (c.id, (sign * c.economy.surplus(good)) as f64))
+                .filter(|&(_, amount)| amount > 0.0)
+                .collect()
+        };
+        let (suppliers, buyers) = (surplus(1.0), surplus(-1.0));
+        if suppliers.is_empty() || buyers.is_empty() {
+            continue;
+        }
+
+        // Pull of every supplier on every buyer it can reach
+        let mut pull = vec![vec![0.0; buyers.len()]; suppliers.len()];
+        for (i, &(from, supply)) in suppliers.iter().enumerate() {
+            let reach = paths.entry(from).or_insert_with(|| graph.paths_from(from));
+            for (j, &(to, demand)) in buyers.iter().enumerate() {
+                if let Some((_, length)) = reach.get(&to) {
+                    pull[i][j] = supply * demand / (1.0 + length / FLOW_DISTANCE).powi(2);
+                }
+            }
+        }
+        let row_totals: Vec<f64> = pull.iter().map(|row| row.iter().sum()).collect();
+        let column_totals: Vec<f64> = (0..buyers.len()).map(|j| pull.iter().map(|row| row[j]).sum()).collect();
+
+        for (i, &(from, supply)) in suppliers.iter().enumerate() {
+            for (j, &(to, demand)) in buyers.iter().enumerate() {
+                if pull[i][j] <= 0.0 {
+                    continue;
+                }
+                let offered = supply * pull[i][j] / row_totals[i];
+                let wanted = demand * pull[i][j] / column_totals[j];
+                let amount = offered.min(wanted) as f32;
+                if amount >= MIN_FLOW {
+                    shipped.entry((from, to)).or_default().push((good, (amount * 10.0).round() / 10.0));
+                }
+            }
+        }
+    }
+
+    shipped
+        .into_iter()
+        .map(|((from, to), mut goods)| {
+            goods.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.name().cmp(b.0.name())));
+            let path = &paths[&from][&to].0;
+            TradeFlow {
+                from,
+                to,
+                goods,
+                road_ids: graph.path_roads(path),
+            }
+        })
+        .collect()
+}
+
+/// Loads a year carried along each road, by road ID, summed over every flow
+/// that travels it.
+pub fn road_volumes(flows: &[TradeFlow]) -> HashMap<u32, f32> {
+    let mut volumes = HashMap::new();
+    for flow in flows {
+        let volume = flow.volume();
+        for &road in &flow.road_ids {
+            *volumes.entry(road).or_insert(0.0) += volume;
+        }
+    }
+    volumes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definition::{City, CityTier, Point2D};
+    use crate::economy::Economy;
+    use crate::roads::{Road, RoadType};
+
+    fn city(id: u32, x: f64, production: Vec<(TradeGood, f32)>, consumption: Vec<(TradeGood, f32)>) -> City {
+        let mut city = City::new(id, format!("City {}", id), Point2D::new(x, 0.0), CityTier::Town);
+        city.economy = Economy {
+            prosperity: 0.5,
+            production,
+            consumption,
+        };
+        city
+    }
+
+    fn road(id: u32, connects: (u32, u32), from: f64, to: f64) -> Road {
+        let mut road = Road::new(id, connects, RoadType::Provincial);
+        road.waypoints = vec![Point2D::new(from, 0.0), Point2D::new(to, 0.0)];
+        road
+    }
+
+    /// A farm town feeding two hungry towns along a road, near and far, and
+    /// a third no road reaches.
+    fn world() -> WorldDefinition {
+        WorldDefinition {
+            cities: vec![
+                city(1, 0.0, vec![(TradeGood::Food, 30.0)], vec![(TradeGood::Food, 10.0)]),
+                city(2, 40.0, vec![], vec![(TradeGood::Food, 12.0)]),
+                city(3, 400.0, vec![], vec![(TradeGood::Food, 12.0)]),
+                city(4, 800.0, vec![], vec![(TradeGood::Food, 12.0)]),
+            ],
+            roads: vec![road(1, (1, 2), 0.0, 40.0), road(2, (2, 3), 40.0, 400.0)],
+            ..Default::default()
+        }
+    }
+
+    fn flow(flows: &[TradeFlow], from: u32, to: u32) -> Option<&TradeFlow> {
+        flows.iter().find(|f| f.from == from && f.to == to)
+    }
+
+    #[test]
+    fn goods_favour_near_buyers_and_follow_the_roads() {
+        let world = world();
+        let flows = simulate_trade_flows(&world);
+
+        let near = flow(&flows, 1, 2).unwrap();
+        let far = flow(&flows, 1, 3).unwrap();
+        assert!(near.volume() > far.volume());
+        assert_eq!(far.road_ids, [1, 2]);
+        assert!(flow(&flows, 1, 4).is_none());
+
+        // Nothing shipped beyond the surplus or the shortfall
+        assert!(near.volume() + far.volume() <= 20.0 + 1e-3);
+        assert!(near.volume() <= 12.0 + 1e-3);
+    }
+
+    #[test]
+    fn busy_roads_carry_every_flow_over_them() {
+        let flows = simulate_trade_flows(&world());
+        let volumes = road_volumes(&flows);
+        let total: f32 = flows.iter().map(TradeFlow::volume).sum();
+
+        assert!((volumes[&1] - total).abs() < 1e-3);
+        assert!(volumes[&2] < volumes[&1]);
+    }
+}
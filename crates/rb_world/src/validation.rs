@@ -0,0 +1,280 @@
+//! Consistency checks for world definitions.
+//!
+//! Saves are plain RON and get edited by hand, so a loaded world can hold
+//! positions off the map, repeated ids, or references to entities that no
+//! longer exist. [`WorldDefinition::validate`] reports these and
+//! [`WorldDefinition::repair`] fixes them in place.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::definition::{Point2D, WorldDefinition};
+
+/// Kind of entity an issue refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    Region,
+    City,
+    Landmark,
+    Faction,
+    Road,
+    TradeRoute,
+}
+
+impl EntityKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Region => "region",
+            Self::City => "city",
+            Self::Landmark => "landmark",
+            Self::Faction => "faction",
+            Self::Road => "road",
+            Self::TradeRoute => "trade route",
+        }
+    }
+}
+
+/// One inconsistency found in a world definition.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// A position (or any vertex or waypoint) lies outside the map.
+    OutOfBounds { kind: EntityKind, id: u32 },
+    /// Another entity of the same kind already uses this id.
+    DuplicateId { kind: EntityKind, id: u32 },
+    /// Entity `id` refers to a `target` that doesn't exist.
+    DanglingReference {
+        kind: EntityKind,
+        id: u32,
+        target: EntityKind,
+        target_id: u32,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { kind, id } => {
+                write!(f, "{} {} lies outside the map", kind.name(), id)
+            }
+            Self::DuplicateId { kind, id } => {
+                write!(f, "{} id {} is used more than once", kind.name(), id)
+            }
+            Self::DanglingReference { kind, id, target, target_id } => write!(
+                f,
+                "{} {} refers to missing {} {}",
+                kind.name(),
+                id,
+                target.name(),
+                target_id
+            ),
+        }
+    }
+}
+
+/// Ids in `ids` that repeat an earlier one, in order.
+fn duplicates(ids: impl Iterator<Item = u32>) -> Vec<u32> {
+    let mut seen = HashSet::new();
+    ids.filter(|&id| !seen.insert(id)).collect()
+}
+
+/// Give every repeated id a fresh one above the largest in use. The first
+/// holder of an id keeps it, so existing references resolve to that one.
+fn renumber_duplicates<'a>(ids: impl Iterator<Item = &'a mut u32>) {
+    let mut ids: Vec<&mut u32> = ids.collect();
+    let mut next = ids.iter().map(|id| **id).max().map_or(0, |max| max + 1);
+    let mut seen = HashSet::new();
+    for id in ids.iter_mut() {
+        if !seen.insert(**id) {
+            **id = next;
+            next += 1;
+        }
+    }
+}
+
+impl WorldDefinition {
+    /// Whether any of `points` lies off the map.
+    fn any_outside<'a>(&self, mut points: impl Iterator<Item = &'a Point2D>) -> bool {
+        let (width, height) = (self.width as f64, self.height as f64);
+        points.any(|p| !(0.0..=width).contains(&p.x) || !(0.0..=height).contains(&p.y))
+    }
+
+    /// Every inconsistency in the definition. Empty for a consistent world.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        use EntityKind::*;
+        let mut issues = Vec::new();
+
+        // Positions
+        let outside = [
+            self.regions
+                .iter()
+                .filter(|r| self.any_outside(r.bounds.vertices.iter()))
+                .map(|r| (Region, r.id))
+                .collect::<Vec<_>>(),
+            self.cities
+                .iter()
+                .filter(|c| self.any_outside(std::iter::once(&c.position)))
+                .map(|c| (City, c.id))
+                .collect(),
+            self.landmarks
+                .iter()
+                .filter(|l| self.any_outside(std::iter::once(&l.position)))
+                .map(|l| (Landmark, l.id))
+                .collect(),
+            self.roads
+                .iter()
+                .filter(|r| self.any_outside(r.waypoints.iter()))
+                .map(|r| (Road, r.id))
+                .collect(),
+        ];
+        for (kind, id) in outside.into_iter().flatten() {
+            issues.push(ValidationIssue::OutOfBounds { kind, id });
+        }
+
+        // Ids
+        let id_lists: [(EntityKind, Vec<u32>); 6] = [
+            (Region, self.regions.iter().map(|r| r.id).collect()),
+            (City, self.cities.iter().map(|c| c.id).collect()),
+            (Landmark, self.landmarks.iter().map(|l| l.id).collect()),
+            (Faction, self.factions.iter().map(|f| f.id).collect()),
+            (Road, self.roads.iter().map(|r| r.id).collect()),
+            (TradeRoute, self.trade_routes.iter().map(|t| t.id).collect()),
+        ];
+        for (kind, ids) in &id_lists {
+            for id in duplicates(ids.iter().copied()) {
+                issues.push(ValidationIssue::DuplicateId { kind: *kind, id });
+            }
+        }
+
+        // References
+        let cities: HashSet<u32> = self.cities.iter().map(|c| c.id).collect();
+        let factions: HashSet<u32> = self.factions.iter().map(|f| f.id).collect();
+        let roads: HashSet<u32> = self.roads.iter().map(|r| r.id).collect();
+        let mut dangling = |(kind, id), target, known: &HashSet<u32>, refs: &[u32]| {
+            for &target_id in refs.iter().filter(|r| !known.contains(r)) {
+                issues.push(ValidationIssue::DanglingReference { kind, id, target, target_id });
+            }
+        };
+        for faction in &self.factions {
+            let source = (Faction, faction.id);
+            let capital: Vec<u32> = faction.capital_id.into_iter().collect();
+            let relations: Vec<u32> = faction.relations.keys().copied().collect();
+            dangling(source, City, &cities, &capital);
+            dangling(source, City, &cities, &faction.settlement_ids);
+            dangling(source, Faction, &factions, &relations);
+        }
+        for road in &self.roads {
+            let (a, b) = road.connects;
+            dangling((Road, road.id), City, &cities, &[a, b]);
+        }
+        for route in &self.trade_routes {
+            let source = (TradeRoute, route.id);
+            dangling(source, Road, &roads, &route.road_ids);
+            dangling(source, Faction, &factions, &route.faction_ids);
+            dangling(source, City, &cities, &route.settlement_ids);
+        }
+
+        issues
+    }
+
+    /// Fix everything [`validate`](Self::validate) reports and return what
+    /// was found.
+    ///
+    /// Positions are clamped onto the map, repeated ids are renumbered above
+    /// the largest id of their kind, and references to missing entities are
+    /// dropped. A road whose endpoints are gone is removed along with it.
+    pub fn repair(&mut self) -> Vec<ValidationIssue> {
+        let issues = self.validate();
+        if issues.is_empty() {
+            return issues;
+        }
+
+        let (width, height) = (self.width as f64, self.height as f64);
+        let mut points: Vec<&mut Point2D> = Vec::new();
+        points.extend(self.regions.iter_mut().flat_map(|r| r.bounds.vertices.iter_mut()));
+        points.extend(self.cities.iter_mut().map(|c| &mut c.position));
+        points.extend(self.landmarks.iter_mut().map(|l| &mut l.position));
+        points.extend(self.roads.iter_mut().flat_map(|r| r.waypoints.iter_mut()));
+        for point in points {
+            // NaN survives clamp, so send it to the origin instead
+            point.x = if point.x.is_nan() { 0.0 } else { point.x.clamp(0.0, width) };
+            point.y = if point.y.is_nan() { 0.0 } else { point.y.clamp(0.0, height) };
+        }
+
+        renumber_duplicates(self.regions.iter_mut().map(|r| &mut r.id));
+        renumber_duplicates(self.cities.iter_mut().map(|c| &mut c.id));
+        renumber_duplicates(self.landmarks.iter_mut().map(|l| &mut l.id));
+        renumber_duplicates(self.factions.iter_mut().map(|f| &mut f.id));
+        renumber_duplicates(self.roads.iter_mut().map(|r| &mut r.id));
+        renumber_duplicates(self.trade_routes.iter_mut().map(|t| &mut t.id));
+
+        let cities: HashSet<u32> = self.cities.iter().map(|c| c.id).collect();
+        let factions: HashSet<u32> = self.factions.iter().map(|f| f.id).collect();
+        for faction in &mut self.factions {
+            faction.capital_id = faction.capital_id.filter(|id| cities.contains(id));
+            faction.settlement_ids.retain(|id| cities.contains(id));
+            faction.relations.retain(|id, _| factions.contains(id));
+        }
+
+        self.roads.retain(|r| cities.contains(&r.connects.0) && cities.contains(&r.connects.1));
+        let roads: HashSet<u32> = self.roads.iter().map(|r| r.id).collect();
+        for route in &mut self.trade_routes {
+            route.road_ids.retain(|id| roads.contains(id));
+            route.faction_ids.retain(|id| factions.contains(id));
+            route.settlement_ids.retain(|id| cities.contains(id));
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::culture::CultureType;
+    use crate::definition::{City, CityTier};
+    use crate::faction::Faction;
+    use crate::roads::{Road, RoadType};
+
+    #[test]
+    fn broken_world_repairs_to_consistent_state() {
+        let mut world = WorldDefinition::default();
+        let city = |id, x, y| {
+            City::new(id, format!("City {}", id), Point2D::new(x, y), CityTier::Town)
+        };
+        world.cities = vec![city(1, 10.0, 10.0), city(2, -50.0, 9000.0), city(2, 30.0, 30.0)];
+        let mut faction = Faction::new(1, "Ghosts".into(), CultureType::TwilightDweller);
+        faction.capital_id = Some(7);
+        faction.settlement_ids = vec![1, 7];
+        faction.relations.insert(9, -0.5);
+        world.factions = vec![faction];
+        world.roads = vec![
+            Road::new(1, (1, 2), RoadType::Trail),
+            Road::new(2, (1, 7), RoadType::Trail),
+        ];
+
+        // Round-trip through RON as a hand-edited save would
+        let ron = ron::to_string(&world).unwrap();
+        let mut world: WorldDefinition = ron::from_str(&ron).unwrap();
+
+        let issues = world.validate();
+        assert!(issues.contains(&ValidationIssue::OutOfBounds { kind: EntityKind::City, id: 2 }));
+        assert!(issues.contains(&ValidationIssue::DuplicateId { kind: EntityKind::City, id: 2 }));
+        assert!(issues.contains(&ValidationIssue::DanglingReference {
+            kind: EntityKind::Road,
+            id: 2,
+            target: EntityKind::City,
+            target_id: 7,
+        }));
+
+        assert_eq!(world.repair(), issues);
+        assert!(world.validate().is_empty(), "still broken: {:?}", world.validate());
+
+        assert_eq!(world.cities[1].position, Point2D::new(0.0, world.height as f64));
+        let ids: Vec<u32> = world.cities.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        let faction = &world.factions[0];
+        assert_eq!((faction.capital_id, faction.settlement_ids.as_slice()), (None, &[1][..]));
+        assert!(faction.relations.is_empty());
+        assert_eq!(world.roads.len(), 1);
+    }
+}
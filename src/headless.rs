@@ -0,0 +1,338 @@
+//! Subcommands that run without a window or GPU, for CI, batch experiments
+//! and documentation:
+//!
+//! - `randlebrot generate` writes a whole world, its artifact and previews.
+//! - `randlebrot preview` renders one layer of a saved or seeded world.
+//! - `randlebrot diff` reports what changed between two saved worlds.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Args;
+use rb_core::BiomeRegistry;
+use rb_artifacts::save_layers;
+use rb_noise::{BiomeMap, GenerationTimings, NoiseLayer};
+use rb_persistence::{
+    diff_worlds, export_layer_previews, layer_slug, load_biomes_or_default, load_names_or_default, load_world,
+    save_world, write_rgba_png, WorldFormat,
+};
+use rb_world::{religion_image, CivilizationGenerator, WorldDefinition};
+
+use crate::{civilization_config, generation_config};
+
+/// Options of `randlebrot generate`.
+#[derive(Args, Debug)]
+pub struct GenerateArgs {
+    /// World seed.
+    #[arg(long, default_value_t = 42)]
+    pub seed: u32,
+    /// Macro map size, as WIDTHxHEIGHT.
+    #[arg(long, default_value = "1024x512", value_parser = parse_size)]
+    pub size: (usize, usize),
+    /// Directory to write the world to. Its name tags the artifact.
+    #[arg(long)]
+    pub out: PathBuf,
+    /// Skip the PNG previews of each layer.
+    #[arg(long)]
+    pub no_previews: bool,
+}
+
+/// Options of `randlebrot preview`.
+#[derive(Args, Debug)]
+pub struct PreviewArgs {
+    /// Layer to render: a noise or resource layer such as `humidity` or
+    /// `iron_deposits`, `resources` for the resource overlay, `territory`
+    /// for faction borders, or `religion` for each place's prevailing faith.
+    #[arg(long, value_parser = parse_layer)]
+    pub layer: PreviewLayer,
+    /// PNG file to write.
+    #[arg(long)]
+    pub out: PathBuf,
+    /// Saved world to render. Without it, a world is generated from `--seed`.
+    #[arg(long, conflicts_with_all = ["seed", "size"])]
+    pub world: Option<PathBuf>,
+    /// World seed.
+    #[arg(long, default_value_t = 42)]
+    pub seed: u32,
+    /// Macro map size, as WIDTHxHEIGHT.
+    #[arg(long, default_value = "1024x512", value_parser = parse_size)]
+    pub size: (usize, usize),
+}
+
+/// Options of `randlebrot diff`.
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Older world file.
+    pub before: PathBuf,
+    /// Newer world file.
+    pub after: PathBuf,
+}
+
+/// What `randlebrot preview` can render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewLayer {
+    Noise(NoiseLayer),
+    /// Most abundant resource per cell, transparent where there is none.
+    Resources,
+    /// Faction territory, transparent where unclaimed.
+    Territory,
+    /// Prevailing faith around each settlement.
+    Religion,
+}
+
+impl PreviewLayer {
+    /// Name as given to `--layer`.
+    fn name(self) -> String {
+        match self {
+            Self::Noise(layer) => layer_slug(layer),
+            Self::Resources => "resources".to_string(),
+            Self::Territory => "territory".to_string(),
+            Self::Religion => "religion".to_string(),
+        }
+    }
+}
+
+/// Parse a layer name, ignoring case and accepting `-` or spaces for `_`.
+fn parse_layer(name: &str) -> Result<PreviewLayer, String> {
+    let slug = name.to_lowercase().replace(['-', ' '], "_");
+    match slug.as_str() {
+        "resources" => return Ok(PreviewLayer::Resources),
+        "territory" => return Ok(PreviewLayer::Territory),
+        "religion" => return Ok(PreviewLayer::Religion),
+        _ => {}
+    }
+    if let Some(&layer) = NoiseLayer::all().iter().find(|&&layer| layer_slug(layer) == slug) {
+        return Ok(PreviewLayer::Noise(layer));
+    }
+    let known: Vec<String> = NoiseLayer::all().iter().map(|&layer| layer_slug(layer)).collect();
+    Err(format!("unknown layer {:?}; expected one of {}, resources, territory, religion", name, known.join(", ")))
+}
+
+/// Parse a `WIDTHxHEIGHT` map size.
+fn parse_size(size: &str) -> Result<(usize, usize), String> {
+    let parsed = size
+        .split_once('x')
+        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+    match parsed {
+        Some((w, h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => Err(format!("expected WIDTHxHEIGHT, e.g. 2048x1024, not {:?}", size)),
+    }
+}
+
+/// Generate every macro layer and the civilization on top, then write them
+/// to `args.out`: an artifact (`manifest.ron` and its blobs), the world as
+/// `world.ron`, and unless disabled, a `previews/` PNG per layer.
+pub fn generate(args: &GenerateArgs) -> ExitCode {
+    match write_world(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn write_world(args: &GenerateArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let tag = args
+        .out
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("--out must name a directory")?;
+    let root = args.out.parent().unwrap_or(Path::new(""));
+    let (width, height) = args.size;
+
+    let biomes = load_biomes_or_default();
+    let mut world = WorldDefinition {
+        name: tag.to_string(),
+        seed: args.seed,
+        width,
+        height,
+        ..Default::default()
+    };
+
+    println!("Generating {}x{} world with seed {}...", width, height, args.seed);
+    let mut timings = GenerationTimings::new();
+    let map = timings.time("Macro noise", || generate_map(&world, &biomes));
+    let civilization = timings.time("Civilization", || {
+        CivilizationGenerator::new(args.seed, civilization_config(&biomes, &load_names_or_default())).generate(&map, &mut world)
+    });
+    println!(
+        "  {} settlements, {} factions, {} roads, {} landmarks, {} historical events, {} rulers, {} religions, {} sea routes, {} trade flows",
+        civilization.settlements_placed,
+        civilization.factions_created,
+        civilization.roads_built,
+        civilization.landmarks_placed,
+        civilization.historical_events,
+        civilization.rulers,
+        civilization.religions,
+        civilization.sea_routes,
+        civilization.trade_flows
+    );
+
+    let manifest = save_layers(root, tag, &map, &world, Some(&civilization))?;
+    save_world(&args.out.join("world.ron"), &world, WorldFormat::Ron)?;
+    if !args.no_previews {
+        let previews = args.out.join("previews");
+        timings.time("Previews", || -> Result<(), Box<dyn std::error::Error>> {
+            export_layer_previews(&previews, &map, NoiseLayer::all())?;
+            if let Some(image) = territory_image(&world) {
+                write_rgba_png(&previews.join("territory.png"), &image, width, height)?;
+            }
+            if !world.religions.is_empty() {
+                write_rgba_png(&previews.join("religion.png"), &religion_image(&world), width, height)?;
+            }
+            Ok(())
+        })?;
+    }
+
+    for phase in timings.phases() {
+        println!("  {}: {:.0} ms", phase.name, phase.duration_ms);
+    }
+    println!("Wrote {} layers to {}", manifest.layers.len(), args.out.display());
+    Ok(())
+}
+
+/// Render `args.layer` of a saved or seeded world to `args.out`.
+pub fn preview(args: &PreviewArgs) -> ExitCode {
+    match write_preview(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn write_preview(args: &PreviewArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let biomes = load_biomes_or_default();
+    let mut world = match &args.world {
+        Some(path) => load_world(path)?,
+        None => WorldDefinition {
+            seed: args.seed,
+            width: args.size.0,
+            height: args.size.1,
+            ..Default::default()
+        },
+    };
+    let map = generate_map(&world, &biomes);
+
+    let image = match args.layer {
+        PreviewLayer::Noise(layer) => map.to_layer_image(layer),
+        PreviewLayer::Resources => map.resources.to_overlay_image(),
+        PreviewLayer::Territory => {
+            let generator = CivilizationGenerator::new(world.seed, civilization_config(&biomes, &load_names_or_default()));
+            if world.factions.is_empty() {
+                // A bare seed has no factions yet; settle it first
+                generator.generate(&map, &mut world);
+            } else {
+                world.territory_cache = Some(generator.generate_territories(&map, &world.cities, &world.factions, &world.territory_locks));
+            }
+            territory_image(&world).ok_or("world has no territory")?
+        }
+        PreviewLayer::Religion => {
+            let generator = CivilizationGenerator::new(world.seed, civilization_config(&biomes, &load_names_or_default()));
+            if world.religions.is_empty() {
+                generator.generate(&map, &mut world);
+            } else {
+                // Faith is only drawn over claimed land
+                world.territory_cache = Some(generator.generate_territories(&map, &world.cities, &world.factions, &world.territory_locks));
+            }
+            religion_image(&world)
+        }
+    };
+
+    if let Some(dir) = args.out.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    write_rgba_png(&args.out, &image, map.width, map.height)?;
+    println!("Wrote {} of {} (seed {}) to {}", args.layer.name(), world.name, world.seed, args.out.display());
+    Ok(())
+}
+
+/// Print what changed from `args.before` to `args.after`.
+pub fn diff(args: &DiffArgs) -> ExitCode {
+    let load = |path: &Path| {
+        load_world(path).map_err(|e| eprintln!("error: could not load {}: {}", path.display(), e))
+    };
+    let (Ok(before), Ok(after)) = (load(&args.before), load(&args.after)) else {
+        return ExitCode::FAILURE;
+    };
+    print!("{}", diff_worlds(&before, &after));
+    ExitCode::SUCCESS
+}
+
+/// Macro map of `world`, generated on the CPU.
+fn generate_map(world: &WorldDefinition, biomes: &BiomeRegistry) -> BiomeMap {
+    BiomeMap::generate_with_config(world.seed, world.width, world.height, &generation_config(world, biomes))
+}
+
+/// Territory of `world` in its factions' colours, if it has been generated.
+fn territory_image(world: &WorldDefinition) -> Option<Vec<u8>> {
+    let territory = world.territory_cache.as_ref()?;
+    let colors: Vec<_> = world.factions.iter().map(|f| (f.id, f.color)).collect();
+    Some(territory.to_image(&colors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_artifacts::load_layers;
+
+    #[test]
+    fn generate_writes_artifact_world_and_previews() {
+        assert_eq!(parse_size("2048x1024"), Ok((2048, 1024)));
+        assert!(parse_size("2048").is_err());
+        assert!(parse_size("0x512").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let args = GenerateArgs {
+            seed: 7,
+            size: (96, 48),
+            out: dir.path().join("batch-7"),
+            no_previews: false,
+        };
+        assert_eq!(generate(&args), ExitCode::SUCCESS);
+
+        let artifact = load_layers(dir.path(), "batch-7").unwrap();
+        assert_eq!((artifact.manifest.seed, artifact.manifest.width), (7, 96));
+        assert!(artifact.manifest.civilization.is_some());
+        assert!(args.out.join("world.ron").exists());
+        assert!(args.out.join("previews/aggregate.png").exists());
+    }
+
+    #[test]
+    fn preview_renders_noise_and_territory_layers() {
+        assert_eq!(parse_layer("Peaks-Valleys"), Ok(PreviewLayer::Noise(NoiseLayer::PeaksValleys)));
+        assert_eq!(parse_layer("territory"), Ok(PreviewLayer::Territory));
+        assert_eq!(parse_layer("Religion"), Ok(PreviewLayer::Religion));
+        assert!(parse_layer("moisture").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut args = PreviewArgs {
+            layer: PreviewLayer::Noise(NoiseLayer::Humidity),
+            out: dir.path().join("humid.png"),
+            world: None,
+            seed: 7,
+            size: (64, 32),
+        };
+        assert_eq!(preview(&args), ExitCode::SUCCESS);
+
+        let world = WorldDefinition {
+            seed: 7,
+            width: 64,
+            height: 32,
+            ..Default::default()
+        };
+        let map = generate_map(&world, &load_biomes_or_default());
+        let decoder = png::Decoder::new(std::io::BufReader::new(std::fs::File::open(&args.out).unwrap()));
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!(&buf[..info.buffer_size()], &map.to_layer_image(NoiseLayer::Humidity)[..]);
+
+        args.layer = PreviewLayer::Territory;
+        args.out = dir.path().join("maps/territory.png");
+        assert_eq!(preview(&args), ExitCode::SUCCESS);
+        assert!(args.out.exists());
+    }
+}
@@ -4,14 +4,38 @@ use bevy_egui::{egui, EguiContexts};
 use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use clap::{Parser, Subcommand};
 use rayon::prelude::*;
-use rb_core::{AppMode, ModeTransitionEvent, handle_mode_shortcuts};
-use rb_editor::{CurrentLayer, GeneratorUiState, RegenerationRequest};
-use rb_noise::{BiomeMap, LayerId, LayerProgress, NoiseBackend};
-use rb_world::{CivilizationConfig, CivilizationGenerator, CivilizationResult, WorldDefinition};
+use rb_core::{
+    handle_mode_shortcuts, map_to_world, world_to_map, AppMode, BiomeRegistry, ModeTransitionEvent,
+};
+use rb_editor::{
+    BiomeBrushState, CurrentLayer, EditorSelection, EditorTool, GeneratorUiState, HeightmapResolution,
+    OverlayLayer, Place, RegenerationRequest, RoadEditState, SculptTerrain, SeedExplorerState, TerrainBrushState,
+    UndoStack, WorldTime,
+};
+use rb_noise::{
+    next_layer_where, BiomeMap, BiomeOverride, BiomeStroke, BiomeThresholds, DirtyRegion, GenerationConfig,
+    GenerationInputs, GenerationTimings, LayerId, LayerProgress, NoiseBackend, NoiseLayer,
+    LayerValue, NormalizationContext, TerrainEdits,
+};
+use rb_artifacts::{artifact_tag, save_layers, ARTIFACTS_DIR};
+use rb_persistence::{
+    export_heightmap, export_heightmap_tiles, heightmap_filename, heightmap_tiles_dirname, EXPORTS_DIR,
+};
+use rb_world::{
+    religion_image, Adherence, CivilizationConfig, CivilizationGenerator, CivilizationResult, NameData, Point2D,
+    Religion, Road, TerritoryMap, WorldDefinition, WorldIdGenerator, MESO_RESOLUTIONS,
+};
 use std::collections::HashMap;
+use std::process::ExitCode;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+mod headless;
+
+use headless::{DiffArgs, GenerateArgs, PreviewArgs};
 
 const MAP_WIDTH: usize = 1024;
 const MAP_HEIGHT: usize = 512;
@@ -19,8 +43,40 @@ const CHUNK_SIZE_I: usize = 64;
 const CHUNKS_X: usize = MAP_WIDTH / CHUNK_SIZE_I;   // 16
 const CHUNKS_Y: usize = MAP_HEIGHT / CHUNK_SIZE_I;  // 8
 const TOTAL_CHUNKS: usize = CHUNKS_X * CHUNKS_Y;    // 128
+/// Minimum influence for a faction to hold a cell.
+const TERRITORY_THRESHOLD: f64 = 0.1;
+
+/// World editor, or with a subcommand, a headless tool.
+#[derive(Parser)]
+#[command(name = "randlebrot", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
 
-fn main() {
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a world without opening a window.
+    Generate(GenerateArgs),
+    /// Render one layer of a saved or seeded world to a PNG.
+    Preview(PreviewArgs),
+    /// Report what changed between two saved worlds.
+    Diff(DiffArgs),
+}
+
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Some(Command::Generate(args)) => headless::generate(&args),
+        Some(Command::Preview(args)) => headless::preview(&args),
+        Some(Command::Diff(args)) => headless::diff(&args),
+        None => {
+            run_editor();
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn run_editor() {
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
@@ -37,11 +93,14 @@ fn main() {
         .init_resource::<CurrentLayer>()
         .init_resource::<GeneratorParams>()
         .init_resource::<CursorWorldPos>()
+        .init_resource::<PanBounds>()
         .init_resource::<ViewLevel>()
+        .init_resource::<LodBlend>()
         .init_resource::<LoadedMesoTiles>()
         .init_resource::<VisibleChunkRange>()
         .init_resource::<MesoTileCache>()
         .init_resource::<GenerationTask>()
+        .init_resource::<SeedThumbnailTask>()
         // Plugins
         .add_plugins((
             rb_core::RbCorePlugin,
@@ -66,17 +125,38 @@ fn main() {
         // Ready phase - main game systems
         .add_systems(Update, (
             handle_mode_shortcuts,
+            cycle_layer_shortcut
+                .before(handle_layer_change)
+                .run_if(in_state(AppMode::WorldGenerator)),
             handle_layer_change.run_if(in_state(AppMode::WorldGenerator)),
+            (paint_biome_brush, sculpt_terrain, route_requested_road, refresh_territory_overlay, refresh_religion_overlay).run_if(in_state(AppMode::WorldMapEditor)),
             regenerate_world.run_if(in_state(AppMode::WorldGenerator)),
+            apply_world_time.after(regenerate_world),
+            apply_biome_thresholds.after(apply_world_time),
+            poll_meso_regeneration,
             camera_zoom,
             camera_pan,
+            camera_fit_shortcuts,
+            restore_view_state.before(handle_layer_change),
+            clamp_camera_to_map
+                .after(camera_zoom)
+                .after(camera_pan)
+                .after(camera_fit_shortcuts)
+                .after(restore_view_state),
             calculate_visible_chunks,
             handle_view_level_transition,
             manage_meso_tiles,
+            fade_meso_tiles,
             update_cursor_world_pos,
             update_chunk_highlight,
             log_mode_transition,
         ).run_if(in_state(AppPhase::Ready)))
+        .add_systems(Update, (export_heightmaps, save_artifact)
+            .run_if(in_state(AppMode::WorldGenerator))
+            .run_if(in_state(AppPhase::Ready)))
+        .add_systems(Update, (explore_seeds, poll_seed_thumbnails)
+            .run_if(in_state(AppMode::SeedExplorer))
+            .run_if(in_state(AppPhase::Ready)))
         .run();
 }
 
@@ -107,8 +187,14 @@ struct WorldMapTextures {
     biome_map: Arc<BiomeMap>,
     /// Current layer texture handle
     current_handle: Handle<Image>,
-    /// Territory overlay from civilization generation
-    territory_overlay: Option<Vec<u8>>,
+    /// Territory overlay image from civilization generation
+    territory_handle: Handle<Image>,
+    /// Prevailing faith overlay image
+    religion_handle: Handle<Image>,
+    /// Resource deposit overlay image
+    resources_handle: Handle<Image>,
+    /// What the map and meso tiles were generated from
+    inputs: GenerationInputs,
 }
 
 /// Marker component for the world map sprite.
@@ -119,6 +205,18 @@ struct WorldMapSprite;
 #[derive(Component)]
 struct ChunkHighlight;
 
+/// Whether the camera is kept over the map. R toggles free roam.
+#[derive(Resource)]
+struct PanBounds {
+    enabled: bool,
+}
+
+impl Default for PanBounds {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
 /// Resource tracking cursor position in world space.
 #[derive(Resource, Default)]
 struct CursorWorldPos(Vec2);
@@ -131,6 +229,11 @@ enum ViewLevel {
     Meso,
 }
 
+/// Continuous detail between the macro map (0.0) and meso tiles (1.0).
+/// Drives the crossfade so the view doesn't snap at the zoom threshold.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Debug)]
+struct LodBlend(f32);
+
 /// Marker component for individual meso tile sprites.
 #[derive(Component)]
 #[allow(dead_code)]
@@ -163,6 +266,8 @@ struct MesoTileCache {
     maps: HashMap<(i32, i32), Arc<BiomeMap>>,
     /// Pre-rendered texture handles for current layer view
     textures: HashMap<(i32, i32), Handle<Image>>,
+    /// Tiles being regenerated after the seed or resolution changed
+    pending: Option<Task<MesoTileBatch>>,
 }
 
 /// Application phase - config, generating, or ready.
@@ -174,11 +279,15 @@ enum AppPhase {
     Ready,       // Map ready, can interact
 }
 
+/// Output of the background meso generation task, keyed by chunk coordinate,
+/// with each tile's generation time in milliseconds.
+type MesoTileBatch = Vec<((i32, i32), Arc<BiomeMap>, f64)>;
+
 /// Background generation task and progress tracking.
 #[derive(Resource, Default)]
 struct GenerationTask {
     /// The async task generating full BiomeMap tiles
-    task: Option<Task<Vec<((i32, i32), Arc<BiomeMap>)>>>,
+    task: Option<Task<MesoTileBatch>>,
     /// Per-layer progress tracking (7 progress bars)
     layer_progress: Option<Arc<LayerProgress>>,
     /// Tile completion counter
@@ -189,16 +298,46 @@ struct GenerationTask {
     civ_result: Option<CivilizationResult>,
     /// Territory overlay image data
     territory_image: Option<Vec<u8>>,
+    /// Religion overlay image data
+    religion_image: Option<Vec<u8>>,
+    /// Phase timings so far, completed when the meso task finishes
+    timings: Option<GenerationTimings>,
+    /// When the meso task was spawned
+    meso_started: Option<Instant>,
+}
+
+/// RGBA thumbnail of each seed on a Seed Explorer page.
+type ThumbnailBatch = Vec<(u32, Vec<u8>)>;
+
+/// Background generation of a page of Seed Explorer thumbnails.
+#[derive(Resource, Default)]
+struct SeedThumbnailTask {
+    task: Option<Task<ThumbnailBatch>>,
+    /// Size of each thumbnail in pixels
+    size: (usize, usize),
 }
 
+/// Target width of Seed Explorer thumbnails in pixels.
+const THUMBNAIL_WIDTH: usize = 256;
+
 /// Size of macro chunks in pixels (for highlighting grid).
 const CHUNK_SIZE: f32 = 64.0;
 
+/// Zoom limits for the map camera (projection scale).
+const MIN_CAMERA_SCALE: f32 = 0.05;
+const MAX_CAMERA_SCALE: f32 = 10.0;
+/// Fraction of the viewport left empty on each side when fitting to content.
+const FIT_MARGIN: f32 = 0.05;
+/// Smallest area fit-to-selection zooms to, so a lone city isn't blown up.
+const MIN_FIT_EXTENT: f32 = 128.0;
+/// World units of map kept on screen along each axis when pan clamping is on.
+const MIN_VISIBLE_MAP: f32 = 64.0;
+
 /// Zoom threshold for switching to meso view.
 const MESO_ZOOM_THRESHOLD: f32 = 0.5;
 
-/// Size of meso map in pixels (per tile).
-const MESO_MAP_SIZE: usize = 512;
+/// Zoom range above the threshold over which meso tiles fade in.
+const MESO_FADE_BAND: f32 = 0.25;
 
 fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
@@ -208,6 +347,7 @@ fn setup_camera(mut commands: Commands) {
 fn config_ui(
     mut contexts: EguiContexts,
     mut params: ResMut<GeneratorParams>,
+    mut world_def: ResMut<WorldDefinition>,
     mut next_phase: ResMut<NextState<AppPhase>>,
     mut commands: Commands,
 ) {
@@ -221,7 +361,7 @@ fn config_ui(
         .collapsible(false)
         .resizable(false)
         .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-        .fixed_size([300.0, 150.0])
+        .fixed_size([300.0, 180.0])
         .show(ctx, |ui| {
             ui.vertical_centered(|ui| {
                 ui.heading("Randlebrot");
@@ -232,6 +372,21 @@ fn config_ui(
                     ui.add(egui::DragValue::new(&mut params.seed));
                 });
 
+                ui.horizontal(|ui| {
+                    ui.label("Meso detail:");
+                    egui::ComboBox::from_id_salt("meso_resolution")
+                        .selected_text(format!("{} px", world_def.meso_resolution))
+                        .show_ui(ui, |ui| {
+                            for resolution in MESO_RESOLUTIONS {
+                                ui.selectable_value(
+                                    &mut world_def.meso_resolution,
+                                    resolution,
+                                    format!("{} px", resolution),
+                                );
+                            }
+                        });
+                });
+
                 ui.add_space(20.0);
 
                 if ui.button("Generate World").clicked() {
@@ -248,38 +403,47 @@ fn start_generation(
     mut task_res: ResMut<GenerationTask>,
     mut world_def: ResMut<WorldDefinition>,
     ui_state: Res<GeneratorUiState>,
+    biomes: Res<BiomeRegistry>,
+    names: Res<NameData>,
 ) {
     commands.remove_resource::<GenerationStarted>();
 
     let seed = world_def.seed;
     let width = world_def.width;
     let height = world_def.height;
+    let meso_resolution = world_def.meso_resolution;
     let backend = ui_state.backend();
 
     // First generate macro map synchronously (fast)
     let backend_name = if backend == NoiseBackend::Gpu { "GPU" } else { "CPU" };
     println!("Generating macro map {}x{} ({})...", width, height, backend_name);
-    let biome_map = Arc::new(BiomeMap::generate_with_backend(seed, width, height, backend));
+    let mut timings = GenerationTimings::new();
+    let config = generation_config(&world_def, &biomes);
+    let biome_map = Arc::new(timings.time("Macro noise", || {
+        BiomeMap::generate_with_backend(seed, width, height, backend, &config)
+    }));
     task_res.biome_map = Some(biome_map.clone());
     println!("  Resources: {} cells with deposits", biome_map.resources.cells_with_resources());
 
     // Generate civilization
     println!("Generating civilization...");
-    let civ_config = CivilizationConfig {
-        max_settlements: 40,
-        generate_roads: true,
-        generate_trade_routes: true,
-        generate_territories: true,
-        territory_threshold: 0.1,
-    };
-    let civ_generator = CivilizationGenerator::new(seed, civ_config);
-    let civ_result = civ_generator.generate(&biome_map, &mut world_def);
+    let civ_generator = CivilizationGenerator::new(seed, civilization_config(&biomes, &names));
+    let civ_result = timings.time("Civilization", || civ_generator.generate(&biome_map, &mut world_def));
     println!(
-        "Civilization: {} settlements, {} factions, {} roads",
+        "Civilization: {} settlements, {} factions, {} roads, {} landmarks, {} historical events, {} rulers, {} religions, {} sea routes, {} trade flows",
         civ_result.settlements_placed,
         civ_result.factions_created,
-        civ_result.roads_built
+        civ_result.roads_built,
+        civ_result.landmarks_placed,
+        civ_result.historical_events,
+        civ_result.rulers,
+        civ_result.religions,
+        civ_result.sea_routes,
+        civ_result.trade_flows
     );
+    if civ_result.disconnected_settlements > 0 {
+        println!("  {} settlements unreachable by road", civ_result.disconnected_settlements);
+    }
 
     // Generate territory overlay image
     let territory_image = if let Some(ref territory) = world_def.territory_cache {
@@ -291,52 +455,91 @@ fn start_generation(
         None
     };
     task_res.territory_image = territory_image;
+    task_res.religion_image = Some(religion_image(&world_def));
     task_res.civ_result = Some(civ_result);
 
     // Per-layer progress tracking for all meso tiles
-    let total_pixels_per_tile = MESO_MAP_SIZE * MESO_MAP_SIZE;
+    let total_pixels_per_tile = meso_resolution * meso_resolution;
     let total_pixels = total_pixels_per_tile * TOTAL_CHUNKS;
     let layer_progress = Arc::new(LayerProgress::new(total_pixels));
-    let layer_progress_clone = layer_progress.clone();
 
     // Tile completion counter
     let tile_progress = Arc::new(AtomicUsize::new(0));
-    let tile_progress_clone = tile_progress.clone();
 
-    // Spawn async task for meso tiles with full 7-layer generation
     println!("Generating {} meso tiles with 7-layer parallel generation ({})...", TOTAL_CHUNKS, backend_name);
-    let task = AsyncComputeTaskPool::get().spawn(async move {
-        (0..TOTAL_CHUNKS).into_par_iter().map(|chunk_idx| {
-            let cx = (chunk_idx % CHUNKS_X) as i32;
-            let cy = (chunk_idx / CHUNKS_X) as i32;
+    task_res.meso_started = Some(Instant::now());
+    let normalization = Arc::new(NormalizationContext::from_macro(&biome_map, seed));
+    let overrides = config.biome_overrides.clone().into();
+    let task = spawn_meso_tiles(
+        all_chunks(),
+        seed,
+        height,
+        meso_resolution,
+        backend,
+        normalization,
+        overrides,
+        layer_progress.clone(),
+        tile_progress.clone(),
+    );
+
+    task_res.task = Some(task);
+    task_res.timings = Some(timings);
+    task_res.layer_progress = Some(layer_progress);
+    task_res.tile_progress = Some(tile_progress);
+}
+
+/// Every meso tile's chunk coordinate.
+fn all_chunks() -> Vec<(i32, i32)> {
+    (0..TOTAL_CHUNKS)
+        .map(|idx| ((idx % CHUNKS_X) as i32, (idx / CHUNKS_X) as i32))
+        .collect()
+}
+
+/// Spawn a background task generating the meso tiles of `chunks` with all
+/// layers, all laid over the same macro baseline so they meet without seams.
+#[allow(clippy::too_many_arguments)]
+fn spawn_meso_tiles(
+    chunks: Vec<(i32, i32)>,
+    seed: u32,
+    height: usize,
+    meso_resolution: usize,
+    backend: NoiseBackend,
+    normalization: Arc<NormalizationContext>,
+    overrides: Arc<[BiomeOverride]>,
+    layer_progress: Arc<LayerProgress>,
+    tile_progress: Arc<AtomicUsize>,
+) -> Task<MesoTileBatch> {
+    AsyncComputeTaskPool::get().spawn(async move {
+        chunks.into_par_iter().map(|(cx, cy)| {
+            let tile_start = Instant::now();
 
             let world_x = cx as f64 * CHUNK_SIZE as f64;
             let world_y = cy as f64 * CHUNK_SIZE as f64;
 
             // Generate full BiomeMap with all 7 layers + derived
-            let meso_map = BiomeMap::generate_meso_full_with_backend(
+            let mut meso_map = BiomeMap::generate_meso_full_with_backend(
                 seed,
                 world_x,
                 world_y,
                 CHUNK_SIZE as f64,
-                MESO_MAP_SIZE,
+                meso_resolution,
                 height as f64,
                 1, // detail_level = meso
-                &layer_progress_clone,
+                &layer_progress,
                 backend,
+                Some(normalization.clone()),
             );
+            meso_map.apply_biome_overrides(&overrides, world_x, world_y, CHUNK_SIZE as f64);
 
-            tile_progress_clone.fetch_add(1, Ordering::Relaxed);
-            ((cx, cy), Arc::new(meso_map))
+            tile_progress.fetch_add(1, Ordering::Relaxed);
+            let tile_ms = tile_start.elapsed().as_secs_f64() * 1000.0;
+            ((cx, cy), Arc::new(meso_map), tile_ms)
         }).collect()
-    });
-
-    task_res.task = Some(task);
-    task_res.layer_progress = Some(layer_progress);
-    task_res.tile_progress = Some(tile_progress);
+    })
 }
 
 /// Poll generation task and transition when complete.
+#[allow(clippy::too_many_arguments)]
 fn poll_generation(
     mut commands: Commands,
     mut task_res: ResMut<GenerationTask>,
@@ -345,21 +548,21 @@ fn poll_generation(
     mut next_phase: ResMut<NextState<AppPhase>>,
     world_def: Res<WorldDefinition>,
     current_layer: Res<CurrentLayer>,
+    biomes: Res<BiomeRegistry>,
 ) {
     let Some(ref mut task) = task_res.task else { return };
 
     if let Some(result) = block_on(poll_once(task)) {
+        let mut timings = task_res.timings.take().unwrap_or_default();
+        if let Some(start) = task_res.meso_started.take() {
+            timings.record("Meso tiles", start, start.elapsed());
+        }
+
         // Meso tiles complete - store BiomeMap and create textures
-        for ((cx, cy), meso_map) in result {
-            // Generate texture for current layer view
-            let image_data = meso_map.to_layer_image(current_layer.0);
-            let meso_image = create_image(MESO_MAP_SIZE, MESO_MAP_SIZE, image_data);
-            let handle = images.add(meso_image);
-
-            // Store both the full BiomeMap and the texture
-            cache.maps.insert((cx, cy), meso_map);
-            cache.textures.insert((cx, cy), handle);
+        for &(_, _, tile_ms) in &result {
+            timings.record_tile(tile_ms);
         }
+        store_meso_tiles(&mut cache, result, current_layer.0, &mut images);
 
         // Create macro map textures and sprites
         if let Some(biome_map) = task_res.biome_map.take() {
@@ -368,13 +571,23 @@ fn poll_generation(
             let biome_image = create_image(world_def.width, world_def.height, biome_data);
             let biome_handle = images.add(biome_image);
 
-            // Store territory overlay for Political layer
-            let territory_overlay = task_res.territory_image.take();
+            // Map-wide overlays, shown from the map editor's overlay panel
+            let territory_data = task_res.territory_image.take()
+                .unwrap_or_else(|| vec![0; world_def.width * world_def.height * 4]);
+            let territory_handle = images.add(create_image(world_def.width, world_def.height, territory_data));
+            let religion_data = task_res.religion_image.take()
+                .unwrap_or_else(|| vec![0; world_def.width * world_def.height * 4]);
+            let religion_handle = images.add(create_image(world_def.width, world_def.height, religion_data));
+            let resources_data = biome_map.resources.to_overlay_image();
+            let resources_handle = images.add(create_image(world_def.width, world_def.height, resources_data));
 
             commands.insert_resource(WorldMapTextures {
                 biome_map,
                 current_handle: biome_handle.clone(),
-                territory_overlay,
+                territory_handle: territory_handle.clone(),
+                religion_handle: religion_handle.clone(),
+                resources_handle: resources_handle.clone(),
+                inputs: generation_inputs(&world_def, &biomes),
             });
 
             commands.spawn((
@@ -382,6 +595,19 @@ fn poll_generation(
                 WorldMapSprite,
             ));
 
+            for (handle, layer, z) in [
+                (territory_handle, OverlayLayer::Territory, 0.3),
+                (religion_handle, OverlayLayer::Religion, 0.32),
+                (resources_handle, OverlayLayer::Resources, 0.35),
+            ] {
+                commands.spawn((
+                    Sprite { image: handle, ..default() },
+                    Transform::from_xyz(0.0, 0.0, z),
+                    Visibility::Hidden,
+                    layer,
+                ));
+            }
+
             commands.spawn((
                 Sprite {
                     color: Color::srgba(1.0, 1.0, 0.8, 0.3),
@@ -400,6 +626,26 @@ fn poll_generation(
         task_res.civ_result = None;
         next_phase.set(AppPhase::Ready);
         println!("World ready! {} meso tiles cached ({} BiomeMaps).", cache.textures.len(), cache.maps.len());
+        println!("{}", timings);
+        commands.insert_resource(timings);
+    }
+}
+
+/// Cache generated meso tiles, with textures for the current layer.
+fn store_meso_tiles(
+    cache: &mut MesoTileCache,
+    tiles: MesoTileBatch,
+    layer: NoiseLayer,
+    images: &mut Assets<Image>,
+) {
+    for (coord, meso_map, _) in tiles {
+        // Generate texture for current layer view
+        let image_data = meso_map.to_layer_image(layer);
+        let meso_image = create_image(meso_map.width, meso_map.height, image_data);
+
+        // Store both the full BiomeMap and the texture
+        cache.textures.insert(coord, images.add(meso_image));
+        cache.maps.insert(coord, meso_map);
     }
 }
 
@@ -462,6 +708,9 @@ fn generation_progress_ui(
                         .min_col_width(100.0)
                         .show(ui, |ui| {
                             for layer_id in LayerId::all() {
+                                if layer_progress.is_skipped(*layer_id) {
+                                    continue;
+                                }
                                 let fraction = layer_progress.fraction(*layer_id);
 
                                 ui.label(layer_id.name());
@@ -478,6 +727,65 @@ fn generation_progress_ui(
         });
 }
 
+/// Snapshot of what generation reads from the world definition.
+fn generation_inputs(world_def: &WorldDefinition, biomes: &BiomeRegistry) -> GenerationInputs {
+    GenerationInputs {
+        seed: world_def.seed,
+        width: world_def.width,
+        height: world_def.height,
+        meso_resolution: world_def.meso_resolution,
+        config: generation_config(world_def, biomes),
+    }
+}
+
+/// Build macro generation options from the world definition.
+fn generation_config(world_def: &WorldDefinition, biomes: &BiomeRegistry) -> GenerationConfig {
+    GenerationConfig {
+        border_ocean: world_def.border_ocean,
+        symmetry: world_def.symmetry,
+        biome_thresholds: world_def.biome_thresholds,
+        hydraulic_iterations: if world_def.noise_params.hydraulic_erosion {
+            world_def.noise_params.erosion_iterations
+        } else {
+            0
+        },
+        warp_amplitude: world_def.noise_params.warp_amplitude,
+        warp_frequency: world_def.noise_params.warp_frequency,
+        bases: world_def.noise_params.bases,
+        layers: world_def.noise_params.layers,
+        cave_depth: Some(world_def.noise_params.cave_depth),
+        volcanoes: world_def.noise_params.volcanoes,
+        sketch: world_def.sketch.clone(),
+        sketch_weight: world_def.sketch_weight,
+        biomes: biomes.clone(),
+        biome_overrides: world_def.biome_overrides(),
+        terrain_edits: Arc::new(world_def.terrain_edits.clone()),
+        ..default()
+    }
+}
+
+/// Civilization options for a freshly generated world.
+fn civilization_config(biomes: &BiomeRegistry, names: &NameData) -> CivilizationConfig {
+    CivilizationConfig {
+        max_settlements: 40,
+        generate_roads: true,
+        generate_trade_routes: true,
+        generate_territories: true,
+        territory_threshold: TERRITORY_THRESHOLD,
+        generate_landmarks: true,
+        max_landmarks: 24,
+        max_ferry_span: 32.0,
+        faction_names: true,
+        parallel_territories: true,
+        biomes: biomes.clone(),
+        names: names.clone(),
+        history_years: 100,
+        generate_religions: true,
+        generate_sea_routes: true,
+        simulate_economy: true,
+    }
+}
+
 fn create_image(width: usize, height: usize, data: Vec<u8>) -> Image {
     let mut image = Image::new(
         Extent3d {
@@ -501,7 +809,123 @@ fn create_image(width: usize, height: usize, data: Vec<u8>) -> Image {
     image
 }
 
+/// Write the heightmaps requested from the export dialog into [`EXPORTS_DIR`].
+fn export_heightmaps(
+    mut ui_state: ResMut<GeneratorUiState>,
+    textures: Option<Res<WorldMapTextures>>,
+    meso_cache: Option<Res<MesoTileCache>>,
+    world_def: Res<WorldDefinition>,
+) {
+    let Some((format, resolution)) = ui_state.export_requested.take() else {
+        return;
+    };
+
+    let exports = std::path::Path::new(EXPORTS_DIR);
+    let result = match resolution {
+        HeightmapResolution::Macro => {
+            let Some(textures) = textures else {
+                return;
+            };
+            let path = exports.join(heightmap_filename(&world_def.name, format));
+            export_heightmap(&textures.biome_map, format, &path).map(|()| path.display().to_string())
+        }
+        HeightmapResolution::Meso => {
+            let Some(cache) = meso_cache.filter(|cache| !cache.maps.is_empty()) else {
+                ui_state.status_message = Some(("No meso tiles loaded: zoom in first".to_string(), 5.0));
+                return;
+            };
+            let dir = exports.join(heightmap_tiles_dirname(&world_def.name));
+            let mut tiles: Vec<_> = cache.maps.iter().map(|(&tile, map)| (tile, map.as_ref())).collect();
+            tiles.sort_by_key(|&(tile, _)| tile);
+            export_heightmap_tiles(&dir, tiles, format)
+                .map(|paths| format!("{} tiles to {}", paths.len(), dir.display()))
+        }
+    };
+
+    match result {
+        Ok(written) => {
+            ui_state.status_message = Some((format!("Exported {}", written), 3.0));
+            println!("Exported heightmap: {}", written);
+        }
+        Err(e) => {
+            ui_state.status_message = Some((format!("Export failed: {}", e), 5.0));
+            eprintln!("Failed to export heightmap: {}", e);
+        }
+    }
+}
+
+/// Store the macro map and world definition as an artifact when the UI asks,
+/// tagged by world name and seed.
+fn save_artifact(
+    mut ui_state: ResMut<GeneratorUiState>,
+    textures: Option<Res<WorldMapTextures>>,
+    world_def: Res<WorldDefinition>,
+) {
+    if !std::mem::take(&mut ui_state.artifact_requested) {
+        return;
+    }
+    let Some(textures) = textures else {
+        return;
+    };
+
+    let tag = artifact_tag(&world_def.name, world_def.seed);
+    let root = std::path::Path::new(ARTIFACTS_DIR);
+    match save_layers(root, &tag, &textures.biome_map, &world_def, None) {
+        Ok(manifest) => {
+            let message = format!("Saved {} layers to {}", manifest.layers.len(), root.join(&tag).display());
+            println!("{}", message);
+            ui_state.status_message = Some((message, 3.0));
+        }
+        Err(e) => {
+            ui_state.status_message = Some((format!("Artifact save failed: {}", e), 5.0));
+            eprintln!("Failed to save artifact {}: {}", tag, e);
+        }
+    }
+}
+
+/// Start generating thumbnails of the Seed Explorer's page when it asks,
+/// with the current world's size and settings. A page asked for while one
+/// is generating replaces it.
+fn explore_seeds(
+    mut explorer: ResMut<SeedExplorerState>,
+    mut thumbnails: ResMut<SeedThumbnailTask>,
+    world_def: Res<WorldDefinition>,
+    biomes: Res<BiomeRegistry>,
+) {
+    if !std::mem::take(&mut explorer.explore_requested) {
+        return;
+    }
+
+    let (width, height) = (world_def.width, world_def.height);
+    let factor = (width / THUMBNAIL_WIDTH).max(1);
+    let config = generation_config(&world_def, &biomes);
+    let seeds = explorer.seeds();
+    thumbnails.size = (width.div_ceil(factor), height.div_ceil(factor));
+    thumbnails.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+        seeds
+            .into_par_iter()
+            .map(|seed| (seed, BiomeMap::thumbnail(seed, width, height, factor, &config)))
+            .collect()
+    }));
+    explorer.generating = true;
+}
+
+/// Hand finished thumbnails to the Seed Explorer.
+fn poll_seed_thumbnails(
+    mut contexts: EguiContexts,
+    mut explorer: ResMut<SeedExplorerState>,
+    mut thumbnails: ResMut<SeedThumbnailTask>,
+) {
+    let Some(ref mut task) = thumbnails.task else { return };
+    let Some(images) = block_on(poll_once(task)) else { return };
+
+    thumbnails.task = None;
+    let (width, height) = thumbnails.size;
+    explorer.set_thumbnails(contexts.ctx_mut(), width, height, images);
+}
+
 /// System to handle layer changes from the UI and sync CurrentLayer with GeneratorUiState.
+#[allow(clippy::too_many_arguments)]
 fn handle_layer_change(
     mut ui_state: ResMut<GeneratorUiState>,
     mut current_layer: ResMut<CurrentLayer>,
@@ -541,7 +965,7 @@ fn handle_layer_change(
         let new_textures: Vec<_> = cache.maps.iter()
             .map(|(coord, biome_map)| {
                 let image_data = biome_map.to_layer_image(new_layer);
-                let new_image = create_image(MESO_MAP_SIZE, MESO_MAP_SIZE, image_data);
+                let new_image = create_image(biome_map.width, biome_map.height, image_data);
                 let new_handle = images.add(new_image);
                 (*coord, new_handle)
             })
@@ -560,6 +984,318 @@ fn handle_layer_change(
     }
 }
 
+/// Space steps to the next layer with data in the current view; Shift+Space
+/// steps through every layer, empty or not.
+fn cycle_layer_shortcut(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    current_layer: Res<CurrentLayer>,
+    view_level: Res<ViewLevel>,
+    textures: Option<Res<WorldMapTextures>>,
+    meso_cache: Option<Res<MesoTileCache>>,
+    mut ui_state: ResMut<GeneratorUiState>,
+    mut contexts: EguiContexts,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) || contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    let show_all = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let next = next_layer_where(current_layer.0, |layer| {
+        if show_all {
+            return true;
+        }
+        match *view_level {
+            ViewLevel::Macro => textures.as_ref().is_some_and(|t| t.biome_map.has_layer_data(layer)),
+            ViewLevel::Meso => meso_cache
+                .as_ref()
+                .is_some_and(|c| c.maps.values().any(|m| m.has_layer_data(layer))),
+        }
+    });
+    if next != current_layer.0 {
+        ui_state.layer_changed = Some(next);
+    }
+}
+
+/// Paint biomes onto the macro map while the brush tool is active.
+///
+/// Each dab redraws only the cells it covers. Resources and territory for the
+/// stroke are re-derived once the mouse is released, and the stroke is handed
+/// to the brush history for undo.
+#[allow(clippy::too_many_arguments)]
+fn paint_biome_brush(
+    mouse: Res<ButtonInput<MouseButton>>,
+    cursor_pos: Res<CursorWorldPos>,
+    tool: Res<EditorTool>,
+    mut brush: ResMut<BiomeBrushState>,
+    mut textures: ResMut<WorldMapTextures>,
+    mut images: ResMut<Assets<Image>>,
+    mut world_def: ResMut<WorldDefinition>,
+    current_layer: Res<CurrentLayer>,
+    biomes: Res<BiomeRegistry>,
+    mut active: Local<Option<BiomeStroke>>,
+    mut contexts: EguiContexts,
+) {
+    let textures = textures.as_mut();
+    let Some(biome_map) = Arc::get_mut(&mut textures.biome_map) else {
+        // Still shared with a generation task; try again next frame
+        return;
+    };
+
+    if std::mem::take(&mut brush.undo_requested) {
+        if let Some(stroke) = brush.history.pop() {
+            biome_map.revert_stroke(&stroke);
+            finish_biome_edit(biome_map, &stroke, &mut world_def, &biomes);
+            redraw_stroke(biome_map, &stroke, current_layer.0, &textures.current_handle, &mut images);
+            redraw_map_overlays(&world_def, textures, &mut images);
+        }
+        return;
+    }
+
+    let painting = *tool == EditorTool::PaintBiome && mouse.pressed(MouseButton::Left);
+    if !painting {
+        if let Some(stroke) = active.take() {
+            finish_biome_edit(biome_map, &stroke, &mut world_def, &biomes);
+            redraw_stroke(biome_map, &stroke, current_layer.0, &textures.current_handle, &mut images);
+            redraw_map_overlays(&world_def, textures, &mut images);
+            brush.push_stroke(stroke);
+        }
+        return;
+    }
+    if active.is_none() && contexts.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+
+    let (width, height) = (world_def.width as f32, world_def.height as f32);
+    let Some((x, y)) = biome_map.world_to_cell(cursor_pos.0, width, height) else {
+        return;
+    };
+
+    let dab = biome_map.paint_biome(x, y, brush.radius, brush.biome);
+    redraw_stroke(biome_map, &dab, current_layer.0, &textures.current_handle, &mut images);
+    active.get_or_insert_with(BiomeStroke::default).merge(dab);
+}
+
+/// Re-derive data that depends on the biomes a stroke touched.
+///
+/// Road and influence costs are looked up from the biome when needed, so only
+/// cached results have to be refreshed: resources and territory ownership.
+fn finish_biome_edit(
+    biome_map: &mut BiomeMap,
+    stroke: &BiomeStroke,
+    world_def: &mut WorldDefinition,
+    biomes: &BiomeRegistry,
+) {
+    let Some((x0, y0, x1, y1)) = stroke.bounds() else { return };
+    biome_map.recompute_resources_rect(x0, y0, x1, y1, world_def.seed, biomes);
+
+    // Locked cells keep their painted owner whatever the terrain
+    let locks = &world_def.territory_locks;
+    if let Some(ref mut territory) = world_def.territory_cache {
+        let width = biome_map.width;
+        let cells = stroke.indices().filter(|&idx| locks.get(idx % width, idx / width).is_none());
+        territory.refresh_cells(biomes, &biome_map.biomes, cells, TERRITORY_THRESHOLD);
+    }
+}
+
+/// Rebuild the territory, religion and resource overlay images from current
+/// data.
+fn redraw_map_overlays(world_def: &WorldDefinition, textures: &WorldMapTextures, images: &mut Assets<Image>) {
+    if let Some(ref territory) = world_def.territory_cache {
+        let faction_colors: Vec<_> = world_def.factions.iter()
+            .map(|f| (f.id, f.color))
+            .collect();
+        write_image(images, &textures.territory_handle, territory.to_image(&faction_colors));
+    }
+    write_image(images, &textures.religion_handle, religion_image(world_def));
+    write_image(images, &textures.resources_handle, textures.biome_map.resources.to_overlay_image());
+}
+
+/// Replace the pixels of an existing image of the same size.
+fn write_image(images: &mut Assets<Image>, handle: &Handle<Image>, data: Vec<u8>) {
+    if let Some(image) = images.get_mut(handle) {
+        image.data = data;
+    }
+}
+
+/// Redraw the part of the macro texture covered by a stroke.
+fn redraw_stroke(
+    biome_map: &BiomeMap,
+    stroke: &BiomeStroke,
+    layer: NoiseLayer,
+    handle: &Handle<Image>,
+    images: &mut Assets<Image>,
+) {
+    let Some(bounds) = stroke.bounds() else { return };
+    if let Some(image) = images.get_mut(handle) {
+        biome_map.patch_layer_image(layer, &mut image.data, bounds);
+    }
+}
+
+/// A terrain brush stroke being painted.
+struct TerrainStroke {
+    /// The world's terrain edits with the stroke so far on top.
+    edits: TerrainEdits,
+    /// Continentalness where the stroke started, for Flatten.
+    target: f64,
+}
+
+/// Sculpt the macro map's continentalness with the terrain brush.
+///
+/// Dabs change the map in place and redraw only the cells they cover, so the
+/// stroke shows on the continentalness layer as it is painted. Once the mouse
+/// is released the stroke is committed to the world's terrain edits through
+/// the undo stack, which queues a regeneration of the map and of the meso
+/// tiles under the stroke.
+#[allow(clippy::too_many_arguments)]
+fn sculpt_terrain(
+    mouse: Res<ButtonInput<MouseButton>>,
+    cursor_pos: Res<CursorWorldPos>,
+    tool: Res<EditorTool>,
+    brush: Res<TerrainBrushState>,
+    mut textures: ResMut<WorldMapTextures>,
+    mut images: ResMut<Assets<Image>>,
+    mut world_def: ResMut<WorldDefinition>,
+    mut undo: ResMut<UndoStack>,
+    current_layer: Res<CurrentLayer>,
+    mut active: Local<Option<TerrainStroke>>,
+    mut contexts: EguiContexts,
+) {
+    let sculpting = *tool == EditorTool::SculptTerrain && mouse.pressed(MouseButton::Left);
+    if !sculpting {
+        if let Some(stroke) = active.take() {
+            if let Some(command) = SculptTerrain::new(&world_def.terrain_edits, &stroke.edits) {
+                undo.execute(&mut world_def, command);
+            }
+        }
+        return;
+    }
+    if active.is_none() && contexts.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+
+    let textures = textures.as_mut();
+    let Some(biome_map) = Arc::get_mut(&mut textures.biome_map) else {
+        // Still shared with a generation task; try again next frame
+        return;
+    };
+    let (width, height) = (world_def.width as f32, world_def.height as f32);
+    let Some((x, y)) = biome_map.world_to_cell(cursor_pos.0, width, height) else {
+        return;
+    };
+
+    let stroke = active.get_or_insert_with(|| TerrainStroke {
+        edits: world_def.terrain_edits.clone(),
+        target: biome_map.continentalness[y * biome_map.width + x].to_f64(),
+    });
+    let (map_width, map_height) = (biome_map.width, biome_map.height);
+    let centre = (x as f64, y as f64);
+    let Some(bounds) =
+        stroke.edits.dab(&mut biome_map.continentalness, map_width, map_height, centre, &brush.brush(), stroke.target)
+    else {
+        return;
+    };
+    if let Some(image) = images.get_mut(&textures.current_handle) {
+        biome_map.patch_layer_image(current_layer.0, &mut image.data, bounds);
+    }
+}
+
+/// Route the road the map editor asked for over the macro map, which the
+/// editor doesn't have, and place it through the undo stack.
+#[allow(clippy::too_many_arguments)]
+fn route_requested_road(
+    mut road_edit: ResMut<RoadEditState>,
+    textures: Res<WorldMapTextures>,
+    mut world_def: ResMut<WorldDefinition>,
+    mut id_gen: ResMut<WorldIdGenerator>,
+    mut selection: ResMut<EditorSelection>,
+    mut undo: ResMut<UndoStack>,
+    biomes: Res<BiomeRegistry>,
+    names: Res<NameData>,
+) {
+    let Some((from_id, to_id)) = road_edit.connect_requested.take() else { return };
+    let city = |id| world_def.cities.iter().find(|c| c.id == id);
+    let (Some(from), Some(to)) = (city(from_id), city(to_id)) else { return };
+
+    let generator = CivilizationGenerator::new(world_def.seed, civilization_config(&biomes, &names));
+    let Some((waypoints, road_type)) = generator.route(&textures.biome_map, from, to) else {
+        road_edit.status = Some(format!("No route from {} to {}", from.name, to.name));
+        return;
+    };
+    let road = Road {
+        id: id_gen.next_road_id(),
+        waypoints,
+        road_type,
+        connects: (from_id, to_id),
+    };
+    println!("Routed {} road from {} to {} ({:.0} long)", road_type.name(), from.name, to.name, road.length());
+    road_edit.status = None;
+    selection.road_id = Some(road.id);
+    undo.execute(&mut world_def, Place(road));
+}
+
+/// What the territory overlay was last drawn from.
+#[derive(Default)]
+struct DrawnTerritory {
+    colors: Vec<(u32, [u8; 4])>,
+    territory: Option<TerritoryMap>,
+}
+
+/// Redraw the territory overlay when faction colours or ownership are
+/// edited, so the Factions panel and the territory brush show their changes
+/// on the map straight away, undo and redo included.
+fn refresh_territory_overlay(
+    world_def: Res<WorldDefinition>,
+    textures: Res<WorldMapTextures>,
+    mut images: ResMut<Assets<Image>>,
+    mut drawn: Local<DrawnTerritory>,
+) {
+    if !world_def.is_changed() {
+        return;
+    }
+    let colors: Vec<_> = world_def.factions.iter().map(|f| (f.id, f.color)).collect();
+    if drawn.colors == colors && drawn.territory == world_def.territory_cache {
+        return;
+    }
+    if let Some(ref territory) = world_def.territory_cache {
+        write_image(&mut images, &textures.territory_handle, territory.to_image(&colors));
+    }
+    *drawn = DrawnTerritory { colors, territory: world_def.territory_cache.clone() };
+}
+
+/// What the religion overlay was last drawn from.
+#[derive(Default)]
+struct DrawnReligion {
+    believers: Vec<(Point2D, Vec<Adherence>)>,
+    religions: Vec<Religion>,
+    territory: Option<TerritoryMap>,
+}
+
+/// Redraw the religion overlay when settlements move or are removed, a
+/// faith is recoloured, or the claimed land it is drawn over changes.
+fn refresh_religion_overlay(
+    world_def: Res<WorldDefinition>,
+    textures: Res<WorldMapTextures>,
+    mut images: ResMut<Assets<Image>>,
+    mut drawn: Local<DrawnReligion>,
+) {
+    if !world_def.is_changed() {
+        return;
+    }
+    let believers: Vec<_> = world_def.cities.iter().map(|c| (c.position, c.faiths.clone())).collect();
+    let unchanged = drawn.believers == believers
+        && drawn.religions == world_def.religions
+        && drawn.territory == world_def.territory_cache;
+    if unchanged {
+        return;
+    }
+    write_image(&mut images, &textures.religion_handle, religion_image(&world_def));
+    *drawn = DrawnReligion {
+        believers,
+        religions: world_def.religions.clone(),
+        territory: world_def.territory_cache.clone(),
+    };
+}
+
 fn log_mode_transition(
     mut events: EventReader<ModeTransitionEvent>,
 ) {
@@ -568,7 +1304,16 @@ fn log_mode_transition(
     }
 }
 
+/// Rebuild whatever the generation parameters changed.
+///
+/// The macro map is rebuilt on any option change. Meso tiles are only
+/// regenerated, in the background, when the seed, world size, tile
+/// resolution or continent sketch change; they are kept across other tweaks,
+/// on the baseline of the macro map they were first laid over. Terrain brush
+/// edits regenerate just the tiles they reach.
+#[allow(clippy::too_many_arguments)]
 fn regenerate_world(
+    mut commands: Commands,
     mut regen_request: ResMut<RegenerationRequest>,
     world_def: Res<WorldDefinition>,
     mut images: ResMut<Assets<Image>>,
@@ -576,41 +1321,164 @@ fn regenerate_world(
     mut query: Query<&mut Sprite, With<WorldMapSprite>>,
     current_layer: Res<CurrentLayer>,
     mut meso_cache: ResMut<MesoTileCache>,
+    mut loaded_tiles: ResMut<LoadedMesoTiles>,
     ui_state: Res<GeneratorUiState>,
+    world_time: Res<WorldTime>,
+    biomes: Res<BiomeRegistry>,
 ) {
     if !regen_request.pending {
         return;
     }
     regen_request.pending = false;
 
+    let inputs = generation_inputs(&world_def, &biomes);
+    let dirty = DirtyRegion::between(&textures.inputs, &inputs);
+    if dirty.is_clean() {
+        return;
+    }
+    textures.inputs = inputs;
+
     let backend = ui_state.backend();
     let backend_name = if backend == NoiseBackend::Gpu { "GPU" } else { "CPU" };
-    println!("Regenerating world map with seed {} ({})...", world_def.seed, backend_name);
 
-    // Clear meso tile cache - old tiles are stale after seed/param changes
-    meso_cache.maps.clear();
-    meso_cache.textures.clear();
+    // Tiles follow the macro map, so it goes first
+    if dirty.macro_map {
+        println!("Regenerating world map with seed {} ({})...", world_def.seed, backend_name);
 
-    // Generate new biome map with all layers
-    let biome_map = Arc::new(BiomeMap::generate_with_backend(world_def.seed, world_def.width, world_def.height, backend));
-    println!("  Resources: {} cells with deposits", biome_map.resources.cells_with_resources());
+        // Generate new biome map with all layers, at the season being shown
+        let config = GenerationConfig {
+            season: world_time.season,
+            ..generation_config(&world_def, &biomes)
+        };
+        let biome_map = Arc::new(BiomeMap::generate_with_backend(world_def.seed, world_def.width, world_def.height, backend, &config));
+        println!("  Resources: {} cells with deposits", biome_map.resources.cells_with_resources());
+
+        // Generate image for current layer
+        let image_data = biome_map.to_layer_image(current_layer.0);
+        let new_image = create_image(world_def.width, world_def.height, image_data);
+        let new_handle = images.add(new_image);
 
-    // Generate image for current layer
-    let image_data = biome_map.to_layer_image(current_layer.0);
-    let new_image = create_image(world_def.width, world_def.height, image_data);
-    let new_handle = images.add(new_image);
+        // Update textures resource
+        textures.biome_map = biome_map;
+        textures.current_handle = new_handle.clone();
+        // Clear territory and religion overlays (would need to regenerate
+        // civilization)
+        let blank = vec![0; world_def.width * world_def.height * 4];
+        write_image(&mut images, &textures.territory_handle, blank.clone());
+        write_image(&mut images, &textures.religion_handle, blank);
+        write_image(&mut images, &textures.resources_handle, textures.biome_map.resources.to_overlay_image());
+
+        // Update sprite
+        for mut sprite in &mut query {
+            sprite.image = new_handle.clone();
+        }
+
+        println!("World regenerated.");
+    }
+
+    if dirty.meso_tiles || !dirty.meso_chunks.is_empty() {
+        // Drop stale tiles and their sprites; manage_meso_tiles respawns
+        // sprites as the new tiles arrive
+        let stale = if dirty.meso_tiles { all_chunks() } else { dirty.meso_chunks };
+        for coord in &stale {
+            meso_cache.maps.remove(coord);
+            meso_cache.textures.remove(coord);
+            if let Some(entity) = loaded_tiles.tiles.remove(coord) {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        // Also redo whatever a replaced task had yet to deliver
+        let missing: Vec<_> = all_chunks().into_iter().filter(|coord| !meso_cache.maps.contains_key(coord)).collect();
+        println!("Regenerating {} meso tiles ({})...", missing.len(), backend_name);
+        let total_pixels = world_def.meso_resolution * world_def.meso_resolution * missing.len();
+        let normalization = NormalizationContext::from_macro(&textures.biome_map, world_def.seed);
+        meso_cache.pending = Some(spawn_meso_tiles(
+            missing,
+            world_def.seed,
+            world_def.height,
+            world_def.meso_resolution,
+            backend,
+            Arc::new(normalization),
+            textures.inputs.config.biome_overrides.clone().into(),
+            Arc::new(LayerProgress::new(total_pixels)),
+            Arc::new(AtomicUsize::new(0)),
+        ));
+    }
+}
+
+/// Re-tint the macro map's climate for the season in [`WorldTime`].
+///
+/// Only temperature, humidity and biomes are resampled; meso tiles keep the
+/// mean season.
+fn apply_world_time(
+    world_time: Res<WorldTime>,
+    world_def: Res<WorldDefinition>,
+    mut textures: ResMut<WorldMapTextures>,
+    current_layer: Res<CurrentLayer>,
+    mut images: ResMut<Assets<Image>>,
+    biomes: Res<BiomeRegistry>,
+    mut shown: Local<f64>,
+) {
+    if world_time.season == *shown {
+        return;
+    }
+    let textures = textures.as_mut();
+    let Some(biome_map) = Arc::get_mut(&mut textures.biome_map) else {
+        // Still shared with a generation task; try again next frame
+        return;
+    };
 
-    // Update textures resource
-    textures.biome_map = biome_map;
-    textures.current_handle = new_handle.clone();
-    textures.territory_overlay = None; // Clear territory overlay (would need to regenerate civilization)
+    let config = GenerationConfig {
+        season: world_time.season,
+        ..generation_config(&world_def, &biomes)
+    };
+    biome_map.apply_season(world_def.seed, &config);
+    write_image(&mut images, &textures.current_handle, biome_map.to_layer_image(current_layer.0));
+    *shown = world_time.season;
+}
 
-    // Update sprite
-    for mut sprite in &mut query {
-        sprite.image = new_handle.clone();
+/// Reclassify the macro map's biomes as the thresholds are edited, without
+/// regenerating any noise.
+fn apply_biome_thresholds(
+    world_def: Res<WorldDefinition>,
+    world_time: Res<WorldTime>,
+    mut textures: ResMut<WorldMapTextures>,
+    current_layer: Res<CurrentLayer>,
+    mut images: ResMut<Assets<Image>>,
+    biomes: Res<BiomeRegistry>,
+    mut shown: Local<BiomeThresholds>,
+) {
+    if world_def.biome_thresholds == *shown {
+        return;
     }
+    let textures = textures.as_mut();
+    let Some(biome_map) = Arc::get_mut(&mut textures.biome_map) else {
+        return;
+    };
+
+    let config = GenerationConfig {
+        season: world_time.season,
+        ..generation_config(&world_def, &biomes)
+    };
+    biome_map.reclassify_biomes(world_def.seed, &config);
+    write_image(&mut images, &textures.current_handle, biome_map.to_layer_image(current_layer.0));
+    *shown = world_def.biome_thresholds;
+}
 
-    println!("World regenerated.");
+/// Cache meso tiles regenerated by [`regenerate_world`] once they are ready.
+fn poll_meso_regeneration(
+    mut cache: ResMut<MesoTileCache>,
+    mut images: ResMut<Assets<Image>>,
+    current_layer: Res<CurrentLayer>,
+) {
+    let Some(ref mut task) = cache.pending else { return };
+    let Some(tiles) = block_on(poll_once(task)) else { return };
+
+    cache.pending = None;
+    let count = tiles.len();
+    store_meso_tiles(&mut cache, tiles, current_layer.0, &mut images);
+    println!("{} meso tiles regenerated.", count);
 }
 
 fn camera_zoom(
@@ -633,10 +1501,144 @@ fn camera_zoom(
     for mut projection in &mut query {
         // Zoom in (scroll up) decreases scale, zoom out (scroll down) increases scale
         let zoom_factor = 1.0 - scroll_delta;
-        projection.scale = (projection.scale * zoom_factor).clamp(0.05, 10.0);
+        projection.scale = (projection.scale * zoom_factor).clamp(MIN_CAMERA_SCALE, MAX_CAMERA_SCALE);
+    }
+}
+
+/// Projection scale at which a `content`-sized box fills `viewport`, leaving
+/// [`FIT_MARGIN`] on each side.
+fn fit_scale(content: Vec2, viewport: Vec2) -> f32 {
+    let usable = viewport * (1.0 - 2.0 * FIT_MARGIN);
+    (content / usable).max_element().clamp(MIN_CAMERA_SCALE, MAX_CAMERA_SCALE)
+}
+
+/// World-space bounds of the editor selection, grown to at least
+/// [`MIN_FIT_EXTENT`] on each axis.
+fn selection_bounds(world_def: &WorldDefinition, selection: &EditorSelection) -> Option<Rect> {
+    let map_points = if let Some(id) = selection.city_id {
+        world_def.cities.iter().filter(|c| c.id == id).map(|c| c.position).collect()
+    } else if let Some(id) = selection.landmark_id {
+        world_def.landmarks.iter().filter(|l| l.id == id).map(|l| l.position).collect()
+    } else if let Some(id) = selection.region_id {
+        world_def.regions.iter()
+            .filter(|r| r.id == id)
+            .flat_map(|r| r.bounds.vertices.clone())
+            .collect()
+    } else if let Some(id) = selection.road_id {
+        world_def.roads.iter()
+            .filter(|r| r.id == id)
+            .flat_map(|r| r.waypoints.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let half = Vec2::new(world_def.width as f32, world_def.height as f32) / 2.0;
+    let points: Vec<Vec2> = map_points.iter()
+        .map(|p| Vec2::new(p.x as f32 - half.x, half.y - p.y as f32)) // Flip Y
+        .collect();
+
+    let first = *points.first()?;
+    let bounds = points.iter().fold(Rect::from_center_size(first, Vec2::ZERO), |r, &p| {
+        r.union_point(p)
+    });
+    let size = bounds.size().max(Vec2::splat(MIN_FIT_EXTENT));
+    Some(Rect::from_center_size(bounds.center(), size))
+}
+
+/// Home fits the whole map in the window; F fits the editor selection.
+fn camera_fit_shortcuts(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    world_def: Res<WorldDefinition>,
+    selection: Res<EditorSelection>,
+    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+    mut contexts: EguiContexts,
+) {
+    // Don't steal keys from text fields
+    if contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    let target = if keyboard.just_pressed(KeyCode::Home) {
+        let size = Vec2::new(world_def.width as f32, world_def.height as f32);
+        Rect::from_center_size(Vec2::ZERO, size)
+    } else if keyboard.just_pressed(KeyCode::KeyF) {
+        let Some(bounds) = selection_bounds(&world_def, &selection) else { return };
+        bounds
+    } else {
+        return;
+    };
+
+    let Ok(window) = windows.get_single() else { return };
+    for (mut transform, mut projection) in &mut query {
+        projection.scale = fit_scale(target.size(), window.size());
+        transform.translation.x = target.center().x;
+        transform.translation.y = target.center().y;
+    }
+}
+
+/// Put the camera and layer back where the loaded world was saved. Worlds
+/// without a saved view keep whatever the camera is showing.
+fn restore_view_state(
+    mut regen_request: ResMut<RegenerationRequest>,
+    world_def: Res<WorldDefinition>,
+    mut ui_state: ResMut<GeneratorUiState>,
+    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    if !std::mem::take(&mut regen_request.restore_view) {
+        return;
+    }
+    let Some(view) = world_def.view else { return };
+
+    for (mut transform, mut projection) in &mut query {
+        transform.translation.x = view.camera.x as f32;
+        transform.translation.y = view.camera.y as f32;
+        projection.scale = view.zoom.clamp(MIN_CAMERA_SCALE, MAX_CAMERA_SCALE);
+    }
+    ui_state.layer_changed = Some(view.layer);
+}
+
+/// Clamp a camera centre so at least `min_visible` world units of the map
+/// stay in view along each axis (or all of it, if it's smaller than that).
+fn clamp_camera_translation(center: Vec2, map_half: Vec2, view_half: Vec2, min_visible: f32) -> Vec2 {
+    let keep = Vec2::splat(min_visible).min(map_half * 2.0).min(view_half * 2.0);
+    let limit = map_half + view_half - keep;
+    center.clamp(-limit, limit)
+}
+
+/// Keep part of the map on screen after panning or zooming, unless free
+/// roam is on.
+fn clamp_camera_to_map(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut bounds: ResMut<PanBounds>,
+    windows: Query<&Window>,
+    world_def: Res<WorldDefinition>,
+    mut query: Query<(&mut Transform, &OrthographicProjection), With<Camera2d>>,
+    mut contexts: EguiContexts,
+) {
+    if keyboard.just_pressed(KeyCode::KeyR) && !contexts.ctx_mut().wants_keyboard_input() {
+        bounds.enabled = !bounds.enabled;
+        println!("Free roam {}", if bounds.enabled { "off" } else { "on" });
+    }
+    if !bounds.enabled {
+        return;
+    }
+
+    let Ok(window) = windows.get_single() else { return };
+    let map_half = Vec2::new(world_def.width as f32, world_def.height as f32) / 2.0;
+    for (mut transform, projection) in &mut query {
+        let view_half = window.size() * projection.scale / 2.0;
+        let center = transform.translation.truncate();
+        let clamped = clamp_camera_translation(center, map_half, view_half, MIN_VISIBLE_MAP);
+        if clamped != center {
+            transform.translation.x = clamped.x;
+            transform.translation.y = clamped.y;
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn camera_pan(
     keyboard: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
@@ -644,6 +1646,9 @@ fn camera_pan(
     mut query: Query<(&mut Transform, &OrthographicProjection), With<Camera2d>>,
     time: Res<Time>,
     mut contexts: EguiContexts,
+    current_mode: Res<State<AppMode>>,
+    editor_tool: Res<EditorTool>,
+    road_edit: Res<RoadEditState>,
 ) {
     let mut pan_delta = Vec2::ZERO;
 
@@ -664,8 +1669,13 @@ fn camera_pan(
 
     // Left click drag panning (when not over UI)
     // Invert Y axis for natural "grab and drag" feel
+    // Left drag paints instead while the biome or terrain brush is active,
+    // and moves a road's waypoint once one is grabbed
     let over_ui = contexts.ctx_mut().is_pointer_over_area();
-    if mouse.pressed(MouseButton::Left) && !over_ui {
+    let painting = *current_mode.get() == AppMode::WorldMapEditor
+        && (matches!(*editor_tool, EditorTool::PaintBiome | EditorTool::SculptTerrain | EditorTool::PaintTerritory)
+            || road_edit.dragging());
+    if mouse.pressed(MouseButton::Left) && !over_ui && !painting {
         for event in motion_events.read() {
             pan_delta.x -= event.delta.x;
             pan_delta.y += event.delta.y; // Inverted Y
@@ -722,12 +1732,8 @@ fn update_chunk_highlight(
     };
     highlight_sprite.custom_size = Some(Vec2::splat(chunk_size));
 
-    // Convert world position to map coordinates
-    let half_width = world_def.width as f32 / 2.0;
-    let half_height = world_def.height as f32 / 2.0;
-
-    let map_x = cursor_pos.0.x + half_width;
-    let map_y = half_height - cursor_pos.0.y; // Flip Y
+    let map_size = Vec2::new(world_def.width as f32, world_def.height as f32);
+    let Vec2 { x: map_x, y: map_y } = world_to_map(cursor_pos.0, map_size);
 
     // Check if cursor is within map bounds
     if map_x < 0.0 || map_x >= world_def.width as f32 || map_y < 0.0 || map_y >= world_def.height as f32 {
@@ -741,11 +1747,9 @@ fn update_chunk_highlight(
     let chunk_y = (map_y / chunk_size).floor() * chunk_size;
 
     // Convert back to world coordinates (center of chunk)
-    let world_x = chunk_x + chunk_size / 2.0 - half_width;
-    let world_y = half_height - chunk_y - chunk_size / 2.0;
-
-    highlight_transform.translation.x = world_x;
-    highlight_transform.translation.y = world_y;
+    let center = map_to_world(Vec2::new(chunk_x, chunk_y) + chunk_size / 2.0, map_size);
+    highlight_transform.translation.x = center.x;
+    highlight_transform.translation.y = center.y;
 }
 
 /// Calculate which chunks are visible in the camera viewport.
@@ -793,9 +1797,12 @@ fn calculate_visible_chunks(
 fn handle_view_level_transition(
     camera_query: Query<&OrthographicProjection, With<Camera2d>>,
     mut view_level: ResMut<ViewLevel>,
+    mut lod_blend: ResMut<LodBlend>,
 ) {
     let Ok(projection) = camera_query.get_single() else { return };
 
+    lod_blend.set_if_neq(LodBlend(meso_blend(projection.scale)));
+
     let target_level = if projection.scale <= MESO_ZOOM_THRESHOLD {
         ViewLevel::Meso
     } else {
@@ -808,11 +1815,31 @@ fn handle_view_level_transition(
     }
 }
 
+/// Meso tile opacity for a camera scale: fully opaque at the threshold,
+/// smoothstepped to transparent over `MESO_FADE_BAND` above it.
+fn meso_blend(scale: f32) -> f32 {
+    let t = ((MESO_ZOOM_THRESHOLD + MESO_FADE_BAND - scale) / MESO_FADE_BAND).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Crossfade meso tiles over the macro map as the zoom changes.
+fn fade_meso_tiles(
+    lod_blend: Res<LodBlend>,
+    mut tiles: Query<&mut Sprite, (With<MesoTile>, Without<WorldMapSprite>)>,
+) {
+    if !lod_blend.is_changed() {
+        return;
+    }
+    for mut sprite in tiles.iter_mut() {
+        sprite.color = Color::srgba(1.0, 1.0, 1.0, lod_blend.0);
+    }
+}
+
 /// Manage meso tile sprites - spawn/despawn based on viewport.
 /// Uses pre-cached textures for instant display.
 fn manage_meso_tiles(
     mut commands: Commands,
-    view_level: Res<ViewLevel>,
+    lod_blend: Res<LodBlend>,
     visible_range: Res<VisibleChunkRange>,
     mut loaded_tiles: ResMut<LoadedMesoTiles>,
     cache: Res<MesoTileCache>,
@@ -822,8 +1849,8 @@ fn manage_meso_tiles(
     let half_map_width = world_def.width as f32 / 2.0;
     let half_map_height = world_def.height as f32 / 2.0;
 
-    if *view_level != ViewLevel::Meso {
-        // Despawn all meso tile sprites when at macro level
+    if lod_blend.0 <= 0.0 {
+        // Despawn all meso tile sprites once fully faded out
         for (entity, _) in &tiles_query {
             commands.entity(entity).despawn();
         }
@@ -874,6 +1901,7 @@ fn manage_meso_tiles(
         let entity = commands.spawn((
             Sprite {
                 image: handle.clone(),
+                color: Color::srgba(1.0, 1.0, 1.0, lod_blend.0),
                 custom_size: Some(Vec2::splat(CHUNK_SIZE)),
                 ..default()
             },
@@ -884,3 +1912,37 @@ fn manage_meso_tiles(
         loaded_tiles.tiles.insert(coord, entity);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_scale_fills_the_tighter_axis() {
+        let map = Vec2::new(1024.0, 512.0);
+        let margin = 1.0 - 2.0 * FIT_MARGIN;
+
+        // Same aspect: both axes fit exactly
+        assert!((fit_scale(map, map) - 1.0 / margin).abs() < 1e-5);
+        // Wide window: height is the limit
+        assert!((fit_scale(map, Vec2::new(2048.0, 256.0)) - 2.0 / margin).abs() < 1e-5);
+        // Tall window: width is the limit
+        assert!((fit_scale(map, Vec2::new(512.0, 1024.0)) - 2.0 / margin).abs() < 1e-5);
+        // Tiny content stays within the zoom limits
+        assert_eq!(fit_scale(Vec2::splat(1.0), map), MIN_CAMERA_SCALE);
+    }
+
+    #[test]
+    fn pan_beyond_range_is_clamped() {
+        let map_half = Vec2::new(512.0, 256.0);
+        let view_half = Vec2::new(400.0, 300.0);
+
+        // Far off to the east and south: the view keeps 64 units of map edge
+        let clamped = clamp_camera_translation(Vec2::new(5000.0, -5000.0), map_half, view_half, 64.0);
+        assert_eq!(clamped, Vec2::new(512.0 + 400.0 - 64.0, -(256.0 + 300.0 - 64.0)));
+
+        // Inside the range nothing moves
+        let inside = Vec2::new(100.0, -50.0);
+        assert_eq!(clamp_camera_translation(inside, map_half, view_half, 64.0), inside);
+    }
+}